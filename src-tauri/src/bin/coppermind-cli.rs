@@ -0,0 +1,96 @@
+// coppermind-cli
+// Headless access to the same Postgres database as the app, for scripts and SSH sessions.
+//
+//   coppermind-cli log "30m reading" [--goal <goal-id>] [--category <category>]
+//   coppermind-cli scrape <cf|lc|ac|gh>
+
+use std::process::ExitCode;
+
+use app_lib::coppermind_core::{self as core, Config, Platform};
+
+const USAGE: &str = "Usage:
+  coppermind-cli log \"<duration> <title>\" [--goal <goal-id>] [--category <category>]
+  coppermind-cli scrape <cf|lc|ac|gh>";
+
+enum Command {
+    Log { entry: String, goal_id: Option<String>, category: Option<String> },
+    Scrape(Platform),
+}
+
+fn parse_args(args: &[String]) -> Result<Command, String> {
+    match args.first().map(String::as_str) {
+        Some("log") => {
+            let mut entry = None;
+            let mut goal_id = None;
+            let mut category = None;
+            let mut rest = args[1..].iter();
+            while let Some(arg) = rest.next() {
+                match arg.as_str() {
+                    "--goal" | "-g" => goal_id = Some(rest.next().ok_or("--goal needs a value")?.clone()),
+                    "--category" | "-c" => category = Some(rest.next().ok_or("--category needs a value")?.clone()),
+                    flag if flag.starts_with('-') => return Err(format!("Unknown flag '{}'", flag)),
+                    text if entry.is_none() => entry = Some(text.to_string()),
+                    extra => return Err(format!("Unexpected argument '{}' (quote the entry)", extra)),
+                }
+            }
+            let entry = entry.ok_or("log needs an entry, e.g. \"30m reading\"")?;
+            Ok(Command::Log { entry, goal_id, category })
+        }
+        Some("scrape") => {
+            let platform = args.get(1).ok_or("scrape needs a platform: cf, lc, ac or gh")?;
+            Platform::parse(platform).map(Command::Scrape).map_err(|e| e.to_string())
+        }
+        Some(other) => Err(format!("Unknown command '{}'", other)),
+        None => Err("Missing command".to_string()),
+    }
+}
+
+async fn run(command: Command) -> Result<serde_json::Value, String> {
+    let config = Config::from_env()?;
+    let pool = core::connect(&config).await.map_err(|e| e.to_string())?;
+
+    let output = match command {
+        Command::Log { entry, goal_id, category } => {
+            let parsed = core::parse_quick_log(&entry).map_err(|e| e.to_string())?;
+            let activity = core::log_activity(&pool, &config, parsed, category, goal_id)
+                .await
+                .map_err(|e| e.to_string())?;
+            serde_json::to_value(activity)
+        }
+        Command::Scrape(platform) => {
+            let result = core::scrape(&pool, &config, platform).await.map_err(|e| e.to_string())?;
+            serde_json::to_value(result)
+        }
+    };
+    pool.close().await;
+    output.map_err(|e| e.to_string())
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let _ = dotenvy::dotenv();
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if matches!(args.first().map(String::as_str), Some("-h" | "--help" | "help")) {
+        println!("{}", USAGE);
+        return ExitCode::SUCCESS;
+    }
+
+    let command = match parse_args(&args) {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("error: {}\n\n{}", e, USAGE);
+            return ExitCode::from(2);
+        }
+    };
+
+    match run(command).await {
+        Ok(output) => {
+            println!("{}", serde_json::to_string_pretty(&output).unwrap_or_default());
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -1,4 +1,6 @@
 use crate::pos::error::{PosError, PosResult};
+use crate::pos::retry::{with_backoff, BackoffPolicy};
+use crate::pos::scrapers::{build_http_client, OPEN_LIBRARY_HOST};
 use crate::pos::utils::gen_id;
 use crate::{PosDb};
 use chrono::{DateTime, Utc};
@@ -134,10 +136,18 @@ pub async fn get_all_books(db: State<'_, PosDb>) -> PosResult<Vec<BookRow>> {
 
 async fn fetch_from_open_library(isbn: &str) -> PosResult<BookMetadata> {
     let url = format!("https://openlibrary.org/isbn/{}.json", isbn);
-    let response: serde_json::Value = reqwest::get(&url)
-        .await?
-        .json()
-        .await?;
+    let client = build_http_client();
+    let response: serde_json::Value = with_backoff(OPEN_LIBRARY_HOST, BackoffPolicy::default(), || async {
+        let resp = client.get(&url).send().await?;
+        // An unknown ISBN is an answer, not a failure worth retrying
+        if resp.status() == reqwest::StatusCode::NOT_FOUND {
+            return Err(PosError::NotFound(format!("No Open Library entry for ISBN {}", isbn)));
+        }
+        if !resp.status().is_success() {
+            return Err(PosError::External(format!("HTTP error: {}", resp.status())));
+        }
+        Ok(resp.json::<serde_json::Value>().await?)
+    }).await?;
     
     let title = response["title"]
         .as_str()
@@ -310,7 +320,7 @@ async fn get_reading_history(pool: &PgPool, book_id: &str) -> PosResult<BookRead
         r#"
         SELECT id, date, start_time, end_time, pages_read
         FROM pos_activities
-        WHERE book_id = $1
+        WHERE book_id = $1 AND deleted_at IS NULL
         ORDER BY start_time DESC
         "#
     )
@@ -128,7 +128,7 @@ pub async fn get_monthly_briefing(
         sqlx::query_as::<_, ActRow>(
             r#"SELECT date, start_time, end_time, category, is_productive, is_shadow,
                       goal_ids, milestone_id, book_id, pages_read
-               FROM pos_activities WHERE date >= $1 AND date <= $2 ORDER BY date, start_time"#
+               FROM pos_activities WHERE date >= $1 AND date <= $2 AND deleted_at IS NULL ORDER BY date, start_time"#
         ).bind(&month_start).bind(&month_end).fetch_all(pool),
 
         sqlx::query_as::<_, GoalRow>(
@@ -138,7 +138,8 @@ pub async fn get_monthly_briefing(
 
         sqlx::query_as::<_, SubRow>(
             r#"SELECT submitted_time, platform, verdict, difficulty
-               FROM pos_submissions WHERE submitted_time >= $1 AND submitted_time <= $2"#
+               FROM pos_submissions
+               WHERE submitted_time >= $1 AND submitted_time <= $2 AND excluded_at IS NULL"#
         ).bind(ts_start).bind(ts_end).fetch_all(pool),
 
         sqlx::query_as::<_, KbRow>(
@@ -148,7 +149,7 @@ pub async fn get_monthly_briefing(
 
         sqlx::query_as::<_, MilestoneRow>(
             r#"SELECT id, target_metric, unit, daily_amount, target_value, current_value, period_start, period_end
-               FROM goal_periods WHERE period_start <= $1 AND period_end >= $2"#
+               FROM goal_periods WHERE period_start <= $1 AND period_end >= $2 AND deleted_at IS NULL"#
         ).bind(ts_end).bind(ts_start).fetch_all(pool),
 
         sqlx::query_as::<_, RetroRow>(
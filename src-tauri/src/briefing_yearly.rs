@@ -108,7 +108,7 @@ pub async fn get_yearly_briefing(
     let (act_rows, goal_rows, sub_rows, kb_rows, retro_rows) = tokio::try_join!(
         sqlx::query_as::<_, ActYearRow>(
             r#"SELECT date, start_time, end_time, category, is_productive, is_shadow, book_id, pages_read
-               FROM pos_activities WHERE date >= $1 AND date <= $2 ORDER BY date"#
+               FROM pos_activities WHERE date >= $1 AND date <= $2 AND deleted_at IS NULL ORDER BY date"#
         ).bind(&year_start).bind(&year_end).fetch_all(pool),
 
         sqlx::query_as::<_, GoalYearRow>(
@@ -118,7 +118,7 @@ pub async fn get_yearly_briefing(
 
         sqlx::query_as::<_, SubYearRow>(
             r#"SELECT submitted_time, rating FROM pos_submissions
-               WHERE EXTRACT(YEAR FROM submitted_time) = $1 ORDER BY submitted_time"#
+               WHERE EXTRACT(YEAR FROM submitted_time) = $1 AND excluded_at IS NULL ORDER BY submitted_time"#
         ).bind(year).fetch_all(pool),
 
         sqlx::query_as::<_, KbYearRow>(
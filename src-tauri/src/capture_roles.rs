@@ -0,0 +1,244 @@
+// Capture Roles
+// Maps keyboard double-tap triggers to named roles ("question", "quote", "todo"…) and
+// decides where each capture lands. Note-bound roles are emitted to the frontend as before;
+// every other destination is persisted here by the listener.
+
+use std::sync::{Arc, RwLock};
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use tauri::State;
+
+use crate::PosDb;
+use crate::pos::error::{PosError, PosResult, db_context};
+use crate::pos::utils::gen_id;
+
+/// Double-tap triggers the listener understands
+pub const CAPTURE_TRIGGERS: &[&str] = &[
+    "double_left_shift",
+    "double_right_shift",
+    "double_left_ctrl",
+    "double_right_ctrl",
+    "double_left_alt",
+    "double_right_alt",
+];
+
+// ─── Types ──────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum CaptureDestination {
+    /// Appended to the active note by the frontend (original question/answer flow)
+    Note,
+    /// Knowledge item tagged with `item_type`
+    #[serde(rename_all = "camelCase")]
+    KnowledgeItem { item_type: String },
+    /// Unified goal due today
+    Goal,
+    /// Knowledge item linked to today's journal
+    Journal,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CaptureRole {
+    pub role: String,
+    pub trigger: String,
+    pub destination: CaptureDestination,
+}
+
+/// Roles shared between the keyboard listener thread and commands
+pub struct CaptureRolesState(pub Arc<RwLock<Vec<CaptureRole>>>);
+
+#[derive(sqlx::FromRow)]
+struct CaptureRoleRow {
+    role: String,
+    trigger: String,
+    destination: sqlx::types::Json<CaptureDestination>,
+}
+
+pub fn default_capture_roles() -> Vec<CaptureRole> {
+    vec![
+        CaptureRole {
+            role: "question".to_string(),
+            trigger: "double_left_shift".to_string(),
+            destination: CaptureDestination::Note,
+        },
+        CaptureRole {
+            role: "answer".to_string(),
+            trigger: "double_right_shift".to_string(),
+            destination: CaptureDestination::Note,
+        },
+    ]
+}
+
+// ─── Helpers ────────────────────────────────────────────────────────
+
+fn validate_roles(roles: &[CaptureRole]) -> PosResult<()> {
+    let mut seen_roles = std::collections::HashSet::new();
+    let mut seen_triggers = std::collections::HashSet::new();
+
+    for r in roles {
+        if r.role.trim().is_empty() {
+            return Err(PosError::InvalidInput("Role name cannot be empty".into()));
+        }
+        if !CAPTURE_TRIGGERS.contains(&r.trigger.as_str()) {
+            return Err(PosError::InvalidInput(format!(
+                "Unknown trigger '{}'. Expected one of: {}", r.trigger, CAPTURE_TRIGGERS.join(", ")
+            )));
+        }
+        if !seen_roles.insert(r.role.as_str()) {
+            return Err(PosError::InvalidInput(format!("Duplicate role: {}", r.role)));
+        }
+        if !seen_triggers.insert(r.trigger.as_str()) {
+            return Err(PosError::InvalidInput(format!("Trigger '{}' is assigned twice", r.trigger)));
+        }
+        if let CaptureDestination::KnowledgeItem { item_type } = &r.destination {
+            if item_type.trim().is_empty() {
+                return Err(PosError::InvalidInput(format!("Role '{}' needs an item type", r.role)));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Load persisted roles into shared state at startup. Empty table keeps the defaults.
+pub async fn load_capture_roles(pool: &PgPool, state: &CaptureRolesState) -> PosResult<()> {
+    let rows = sqlx::query_as::<_, CaptureRoleRow>(
+        "SELECT role, trigger, destination FROM capture_roles ORDER BY position"
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| db_context("load capture_roles", e))?;
+
+    if rows.is_empty() {
+        return Ok(());
+    }
+
+    let roles: Vec<CaptureRole> = rows.into_iter()
+        .map(|r| CaptureRole { role: r.role, trigger: r.trigger, destination: r.destination.0 })
+        .collect();
+    log::info!("[CAPTURE] Loaded {} capture roles", roles.len());
+    *state.0.write().unwrap() = roles;
+    Ok(())
+}
+
+/// Persist a capture for a non-note destination. Returns the id of the created row.
+pub async fn route_capture(pool: &PgPool, role: &CaptureRole, content: &str) -> PosResult<String> {
+    let id = gen_id();
+    let now = Utc::now();
+    let today = crate::pos::timezone::today_string();
+    let mut metadata = serde_json::json!({ "captureRole": role.role });
+    // Code captures become snippets (extra tags + language metadata)
+    let snippet = crate::snippets::snippet_fields(content);
+    let snippet_tags = snippet.as_ref().map(|(tags, _)| tags.clone()).unwrap_or_default();
+    if let Some((_, fields)) = &snippet {
+        if let (Some(meta), Some(extra)) = (metadata.as_object_mut(), fields.as_object()) {
+            meta.extend(extra.clone());
+        }
+    }
+    let metadata = crate::knowledge_problems::with_problem_ids(content, Some(metadata)).unwrap_or_default();
+
+    match &role.destination {
+        CaptureDestination::Note => {
+            return Err(PosError::InvalidInput("Note captures are handled by the frontend".into()));
+        }
+        CaptureDestination::KnowledgeItem { item_type } => {
+            sqlx::query(
+                r#"INSERT INTO knowledge_items (id, source, content, metadata, status, tags, created_at, updated_at)
+                   VALUES ($1, 'Manual', $2, $3, 'Inbox', $4, $5, $5)"#
+            )
+            .bind(&id).bind(content).bind(&metadata)
+            .bind([vec![item_type.clone(), role.role.clone()], snippet_tags].concat())
+            .bind(now)
+            .execute(pool)
+            .await
+            .map_err(|e| db_context("capture → knowledge item", e))?;
+        }
+        CaptureDestination::Journal => {
+            sqlx::query(
+                r#"INSERT INTO knowledge_items (id, source, content, metadata, status, tags, linked_journal_date, created_at, updated_at)
+                   VALUES ($1, 'Journal', $2, $3, 'Inbox', $4, $5, $6, $6)"#
+            )
+            .bind(&id).bind(content).bind(&metadata)
+            .bind([vec![role.role.clone()], snippet_tags].concat())
+            .bind(&today).bind(now)
+            .execute(pool)
+            .await
+            .map_err(|e| db_context("capture → journal", e))?;
+        }
+        CaptureDestination::Goal => {
+            sqlx::query(
+                r#"INSERT INTO unified_goals (id, text, completed, verified, date, priority, urgent, labels, created_at, updated_at, is_debt)
+                   VALUES ($1, $2, FALSE, FALSE, $3, 'medium', FALSE, $4, $5, $5, FALSE)"#
+            )
+            .bind(&id).bind(content).bind(&today)
+            .bind(sqlx::types::Json(vec![role.role.clone()]))
+            .bind(now)
+            .execute(pool)
+            .await
+            .map_err(|e| db_context("capture → goal", e))?;
+            crate::dashboard::mark_snapshot_stale(pool).await;
+        }
+    }
+
+    log::info!("[CAPTURE] Routed {} capture to {:?} ({})", role.role, role.destination, id);
+    Ok(id)
+}
+
+// ─── Commands ───────────────────────────────────────────────────────
+
+#[tauri::command]
+pub async fn get_capture_roles(roles: State<'_, CaptureRolesState>) -> PosResult<Vec<CaptureRole>> {
+    Ok(roles.0.read().unwrap().clone())
+}
+
+/// Replace the full role set. Takes effect for the running listener immediately.
+#[tauri::command]
+pub async fn set_capture_roles(
+    db: State<'_, PosDb>,
+    state: State<'_, CaptureRolesState>,
+    roles: Vec<CaptureRole>,
+) -> PosResult<Vec<CaptureRole>> {
+    save_capture_roles(&db.0, &state, roles).await
+}
+
+/// Validate, persist and activate `roles` (shared with settings import)
+pub(crate) async fn save_capture_roles(
+    pool: &PgPool,
+    state: &CaptureRolesState,
+    roles: Vec<CaptureRole>,
+) -> PosResult<Vec<CaptureRole>> {
+    let roles: Vec<CaptureRole> = roles.into_iter()
+        .map(|r| CaptureRole { role: r.role.trim().to_lowercase(), ..r })
+        .collect();
+    validate_roles(&roles)?;
+
+    let mut tx = pool.begin().await.map_err(|e| db_context("TX begin", e))?;
+
+    sqlx::query("DELETE FROM capture_roles")
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| db_context("clear capture_roles", e))?;
+
+    for (position, r) in roles.iter().enumerate() {
+        sqlx::query(
+            r#"INSERT INTO capture_roles (role, trigger, destination, position, updated_at)
+               VALUES ($1, $2, $3, $4, NOW())"#
+        )
+        .bind(&r.role)
+        .bind(&r.trigger)
+        .bind(sqlx::types::Json(&r.destination))
+        .bind(position as i32)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| db_context("insert capture_role", e))?;
+    }
+
+    tx.commit().await.map_err(|e| db_context("TX commit", e))?;
+
+    *state.0.write().unwrap() = roles.clone();
+    log::info!("[CAPTURE] Saved {} capture roles", roles.len());
+    Ok(roles)
+}
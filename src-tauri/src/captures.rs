@@ -0,0 +1,216 @@
+// Captures
+// Note-bound captures (the double-shift question/answer flow) are only emitted as
+// `capture-content` events, so the frontend stores them here to survive restarts. A
+// question can be paired with its answer, and either a single capture or a Q&A pair can be
+// promoted to a knowledge item; pairs become flashcards and show up in get_due_reviews.
+
+use chrono::Utc;
+use serde::Serialize;
+use serde_json::json;
+use sqlx::PgPool;
+use tauri::State;
+
+use crate::PosDb;
+use crate::knowledge_base::KnowledgeItemRow;
+use crate::pos::error::{PosError, PosResult, db_context};
+use crate::pos::utils::gen_id;
+
+const CAPTURE_COLS: &str = "id, role, content, answer_id, knowledge_item_id, created_at";
+const DEFAULT_LIMIT: i64 = 100;
+
+// ─── Types ──────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct CaptureRow {
+    pub id: String,
+    pub role: String,
+    pub content: String,
+    /// Set on a question once it has been paired with its answer
+    pub answer_id: Option<String>,
+    /// Set once promoted; both captures of a pair point at the same item
+    pub knowledge_item_id: Option<String>,
+    pub created_at: chrono::DateTime<Utc>,
+}
+
+// ─── Helpers ────────────────────────────────────────────────────────
+
+async fn fetch_capture(pool: &PgPool, id: &str) -> PosResult<CaptureRow> {
+    sqlx::query_as::<_, CaptureRow>(&format!("SELECT {} FROM captures WHERE id = $1", CAPTURE_COLS))
+        .bind(id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| db_context("fetch capture", e))?
+        .ok_or_else(|| PosError::NotFound(format!("Capture not found: {}", id)))
+}
+
+/// (question, answer) for a capture that is part of a pair
+async fn find_pair(pool: &PgPool, capture: &CaptureRow) -> PosResult<Option<(CaptureRow, CaptureRow)>> {
+    if let Some(answer_id) = &capture.answer_id {
+        return Ok(Some((capture.clone(), fetch_capture(pool, answer_id).await?)));
+    }
+    let question = sqlx::query_as::<_, CaptureRow>(&format!(
+        "SELECT {} FROM captures WHERE answer_id = $1", CAPTURE_COLS
+    ))
+    .bind(&capture.id)
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| db_context("fetch capture question", e))?;
+    Ok(question.map(|q| (q, capture.clone())))
+}
+
+// ─── Commands ───────────────────────────────────────────────────────
+
+/// Store a capture received via `capture-content`
+#[tauri::command]
+pub async fn save_capture(
+    db: State<'_, PosDb>,
+    role: String,
+    content: String,
+) -> PosResult<CaptureRow> {
+    let role = role.trim().to_lowercase();
+    if role.is_empty() {
+        return Err(PosError::InvalidInput("Capture role cannot be empty".into()));
+    }
+    if content.trim().is_empty() {
+        return Err(PosError::InvalidInput("Capture content cannot be empty".into()));
+    }
+
+    let row = sqlx::query_as::<_, CaptureRow>(&format!(
+        "INSERT INTO captures (id, role, content, created_at) VALUES ($1, $2, $3, NOW()) RETURNING {}",
+        CAPTURE_COLS
+    ))
+    .bind(gen_id())
+    .bind(&role)
+    .bind(&content)
+    .fetch_one(&db.0)
+    .await
+    .map_err(|e| db_context("save_capture", e))?;
+
+    log::info!("[CAPTURE] Saved {} capture {}", role, row.id);
+    Ok(row)
+}
+
+/// Recent captures, newest first
+#[tauri::command]
+pub async fn get_captures(
+    db: State<'_, PosDb>,
+    limit: Option<i64>,
+    unconverted_only: Option<bool>,
+) -> PosResult<Vec<CaptureRow>> {
+    let filter = if unconverted_only.unwrap_or(false) { "WHERE knowledge_item_id IS NULL" } else { "" };
+    sqlx::query_as::<_, CaptureRow>(&format!(
+        "SELECT {} FROM captures {} ORDER BY created_at DESC LIMIT $1",
+        CAPTURE_COLS, filter
+    ))
+    .bind(limit.unwrap_or(DEFAULT_LIMIT))
+    .fetch_all(&db.0)
+    .await
+    .map_err(|e| db_context("get_captures", e))
+}
+
+/// Link a question capture to its answer. Each capture belongs to at most one pair.
+#[tauri::command]
+pub async fn pair_captures(
+    db: State<'_, PosDb>,
+    question_id: String,
+    answer_id: String,
+) -> PosResult<CaptureRow> {
+    let pool = &db.0;
+    if question_id == answer_id {
+        return Err(PosError::InvalidInput("A capture cannot answer itself".into()));
+    }
+    let question = fetch_capture(pool, &question_id).await?;
+    let answer = fetch_capture(pool, &answer_id).await?;
+    for capture in [&question, &answer] {
+        if find_pair(pool, capture).await?.is_some() {
+            return Err(PosError::InvalidInput(format!("Capture {} is already paired", capture.id)));
+        }
+    }
+
+    let row = sqlx::query_as::<_, CaptureRow>(&format!(
+        "UPDATE captures SET answer_id = $2 WHERE id = $1 RETURNING {}", CAPTURE_COLS
+    ))
+    .bind(&question.id)
+    .bind(&answer.id)
+    .fetch_one(pool)
+    .await
+    .map_err(|e| db_context("pair_captures", e))?;
+
+    log::info!("[CAPTURE] Paired question {} with answer {}", question.id, answer.id);
+    Ok(row)
+}
+
+/// Promote a capture to a knowledge item. A paired capture converts the whole pair into
+/// one flashcard (question on the front, answer on the back). Converting again returns
+/// the existing item.
+#[tauri::command]
+pub async fn convert_capture_to_knowledge_item(
+    db: State<'_, PosDb>,
+    id: String,
+) -> PosResult<KnowledgeItemRow> {
+    let pool = &db.0;
+    let capture = fetch_capture(pool, &id).await?;
+    let item_cols = "id, tags, source, content, metadata, status, next_review_date, \
+                     linked_note_id, linked_journal_date, created_at, updated_at";
+
+    if let Some(item_id) = &capture.knowledge_item_id {
+        let existing = sqlx::query_as::<_, KnowledgeItemRow>(&format!(
+            "SELECT {} FROM knowledge_items WHERE id = $1", item_cols
+        ))
+        .bind(item_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| db_context("fetch converted capture", e))?;
+        if let Some(item) = existing {
+            return Ok(item);
+        }
+    }
+
+    let (content, tags, metadata, capture_ids) = match find_pair(pool, &capture).await? {
+        Some((question, answer)) => (
+            format!("Q: {}\n\nA: {}", question.content.trim(), answer.content.trim()),
+            vec!["flashcard".to_string()],
+            json!({
+                "captureRole": "flashcard",
+                "question": question.content,
+                "answer": answer.content,
+            }),
+            vec![question.id, answer.id],
+        ),
+        None => (
+            capture.content.clone(),
+            vec![capture.role.clone()],
+            json!({ "captureRole": capture.role }),
+            vec![capture.id.clone()],
+        ),
+    };
+    let metadata = crate::knowledge_problems::with_problem_ids(&content, Some(metadata))
+        .map(sqlx::types::Json);
+
+    let mut tx = pool.begin().await.map_err(|e| db_context("TX begin", e))?;
+    let item = sqlx::query_as::<_, KnowledgeItemRow>(&format!(
+        r#"INSERT INTO knowledge_items (id, tags, source, content, metadata, status, created_at, updated_at)
+           VALUES ($1, $2, 'Manual', $3, $4, 'Inbox', NOW(), NOW())
+           RETURNING {}"#,
+        item_cols
+    ))
+    .bind(gen_id())
+    .bind(&tags)
+    .bind(&content)
+    .bind(metadata)
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|e| db_context("capture → knowledge item", e))?;
+
+    sqlx::query("UPDATE captures SET knowledge_item_id = $1 WHERE id = ANY($2)")
+        .bind(&item.id)
+        .bind(&capture_ids)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| db_context("mark capture converted", e))?;
+    tx.commit().await.map_err(|e| db_context("TX commit", e))?;
+
+    log::info!("[CAPTURE] Converted {} capture(s) to knowledge item {}", capture_ids.len(), item.id);
+    Ok(item)
+}
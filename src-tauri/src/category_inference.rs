@@ -0,0 +1,405 @@
+// Category Inference
+// Guesses `category` and `is_productive` from an activity title for quick-add and CSV
+// imports. Signals, strongest first: past activities with the exact same title, built-in
+// keyword rules, and per-word category frequencies from the last year of history.
+// Guesses below REVIEW_THRESHOLD land in activity_category_reviews until confirmed.
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::PgPool;
+use tauri::State;
+
+use crate::{PosConfig, PosDb};
+use crate::pos::activities::{insert_activity, ActivityRow, CreateActivityRequest};
+use crate::pos::error::{PosError, PosResult, db_context};
+use crate::streaks::{self, StreakKind};
+
+/// Guesses under this confidence are queued for review
+pub const REVIEW_THRESHOLD: f64 = 0.6;
+const FALLBACK_CATEGORY: &str = "misc";
+const HISTORY_DAYS: i32 = 365;
+/// Vote of one keyword rule hit; a history word votes at most 1.0
+const KEYWORD_WEIGHT: f64 = 2.0;
+/// Words seen fewer times than this in history don't vote
+const MIN_WORD_SEEN: i64 = 2;
+/// Added to the vote total so a single weak signal never looks certain
+const SMOOTHING: f64 = 1.0;
+
+/// (title word, category, productive)
+const KEYWORD_RULES: &[(&str, &str, bool)] = &[
+    ("leetcode", "leetcode", true), ("lc", "leetcode", true),
+    ("codeforces", "codeforces", true), ("cf", "codeforces", true), ("contest", "codeforces", true),
+    ("c++", "cpp", true), ("cpp", "cpp", true), ("stl", "cpp", true),
+    ("code", "development", true), ("coding", "development", true), ("debug", "development", true),
+    ("refactor", "development", true), ("bug", "development", true), ("deploy", "development", true),
+    ("course", "learning", true), ("tutorial", "learning", true), ("learn", "learning", true),
+    ("study", "learning", true), ("notes", "learning", true),
+    ("book", "book", true), ("reading", "book", true), ("read", "book", true),
+    ("lecture", "college", true), ("class", "college", true), ("college", "college", true),
+    ("lab", "college", true), ("assignment", "college", true), ("exam", "college", true),
+    ("meeting", "discussion", true), ("call", "discussion", true), ("discussion", "discussion", true),
+    ("gym", "exercise", true), ("workout", "exercise", true), ("run", "exercise", true),
+    ("running", "exercise", true), ("yoga", "exercise", true),
+    ("walk", "walking", true), ("walking", "walking", true),
+    ("ncc", "ncc", true), ("parade", "ncc", true),
+    ("sleep", "sleep", false), ("nap", "sleep", false),
+    ("breakfast", "food", false), ("lunch", "food", false), ("dinner", "food", false),
+    ("snack", "food", false), ("cooking", "food", false),
+    ("family", "family", false), ("mom", "family", false), ("dad", "family", false),
+    ("youtube", "entertainment", false), ("netflix", "entertainment", false),
+    ("movie", "entertainment", false), ("gaming", "entertainment", false), ("anime", "entertainment", false),
+    ("reddit", "doom_scroll", false), ("instagram", "doom_scroll", false), ("reels", "doom_scroll", false),
+    ("twitter", "doom_scroll", false), ("scrolling", "doom_scroll", false),
+    ("browsing", "surfing", false), ("surfing", "surfing", false),
+    ("commute", "commute", false), ("bus", "commute", false), ("train", "commute", false),
+    ("travel", "commute", false),
+    ("bath", "bath", false), ("shower", "bath", false),
+    ("freshup", "freshup", false), ("break", "break", false), ("tea", "break", false),
+    ("coffee", "break", false),
+];
+
+// ─── Types ──────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CategoryInference {
+    pub category: String,
+    pub is_productive: bool,
+    /// 0.0 – 1.0
+    pub confidence: f64,
+    pub reason: String,
+}
+
+impl CategoryInference {
+    pub fn needs_review(&self) -> bool {
+        self.confidence < REVIEW_THRESHOLD
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QuickAddResult {
+    pub activity: ActivityRow,
+    pub inference: CategoryInference,
+    pub needs_review: bool,
+}
+
+#[derive(Debug, Serialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct CategoryReview {
+    pub activity_id: String,
+    pub title: String,
+    pub date: String,
+    pub start_time: DateTime<Utc>,
+    pub end_time: DateTime<Utc>,
+    pub suggested_category: String,
+    pub suggested_productive: bool,
+    pub confidence: f64,
+    /// quick_add | csv_import
+    pub source: String,
+    pub created_at: DateTime<Utc>,
+}
+
+// ─── Classifier ─────────────────────────────────────────────────────
+
+fn normalize(title: &str) -> String {
+    title.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase()
+}
+
+fn words(normalized: &str) -> impl Iterator<Item = &str> {
+    normalized
+        .split(|c: char| !(c.is_alphanumeric() || c == '+' || c == '#'))
+        .filter(|w| !w.is_empty())
+}
+
+/// Counts from past activities. Built once per request; cheap for a personal history.
+#[derive(Debug, Default)]
+pub struct Classifier {
+    /// Exact normalized title → category → count
+    titles: HashMap<String, HashMap<String, i64>>,
+    /// Title word → category → count
+    words: HashMap<String, HashMap<String, i64>>,
+    /// Category → (count, productive count)
+    categories: HashMap<String, (i64, i64)>,
+}
+
+impl Classifier {
+    /// From (title, category, count, productive count) rows
+    fn from_history(rows: &[(String, String, i64, i64)]) -> Self {
+        let mut c = Self::default();
+        for (title, category, count, productive) in rows {
+            let title = normalize(title);
+            *c.titles.entry(title.clone()).or_default().entry(category.clone()).or_default() += count;
+            for word in words(&title) {
+                *c.words.entry(word.to_string()).or_default().entry(category.clone()).or_default() += count;
+            }
+            let totals = c.categories.entry(category.clone()).or_default();
+            totals.0 += count;
+            totals.1 += productive;
+        }
+        c
+    }
+
+    /// Priors from the last HISTORY_DAYS of real activities, skipping unconfirmed guesses
+    pub async fn load(pool: &PgPool) -> PosResult<Self> {
+        let rows: Vec<(String, String, i64, i64)> = sqlx::query_as(
+            r#"SELECT title, category, COUNT(*), COUNT(*) FILTER (WHERE is_productive)
+               FROM pos_activities a
+               WHERE is_shadow = FALSE AND deleted_at IS NULL
+                 AND start_time > NOW() - make_interval(days => $1)
+                 AND NOT EXISTS (SELECT 1 FROM activity_category_reviews r
+                                 WHERE r.activity_id = a.id AND r.resolved_at IS NULL)
+               GROUP BY title, category"#
+        )
+        .bind(HISTORY_DAYS)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| db_context("load category priors", e))?;
+        Ok(Self::from_history(&rows))
+    }
+
+    fn productive(&self, category: &str) -> bool {
+        match self.categories.get(category) {
+            Some(&(n, p)) if n > 0 => p * 2 >= n,
+            _ => KEYWORD_RULES.iter()
+                .find(|(_, c, _)| *c == category)
+                .map_or(true, |(_, _, productive)| *productive),
+        }
+    }
+
+    fn inference(&self, category: &str, confidence: f64, reason: String) -> CategoryInference {
+        CategoryInference {
+            category: category.to_string(),
+            is_productive: self.productive(category),
+            confidence: (confidence * 100.0).round() / 100.0,
+            reason,
+        }
+    }
+
+    pub fn classify(&self, title: &str) -> CategoryInference {
+        let title = normalize(title);
+
+        if let Some((category, n, total)) = self.titles.get(&title).and_then(|cats| {
+            let total: i64 = cats.values().sum();
+            cats.iter().max_by(|a, b| a.1.cmp(b.1).then(b.0.cmp(a.0))).map(|(c, n)| (c, *n, total))
+        }) {
+            return self.inference(
+                category,
+                n as f64 / (total as f64 + 0.5),
+                format!("{} of {} past activities with this title", n, total),
+            );
+        }
+
+        let mut scores: HashMap<&str, f64> = HashMap::new();
+        let mut signals = Vec::new();
+        for word in words(&title) {
+            for (_, category, _) in KEYWORD_RULES.iter().filter(|(kw, _, _)| *kw == word) {
+                *scores.entry(category).or_default() += KEYWORD_WEIGHT;
+                signals.push(format!("keyword '{}'", word));
+            }
+            if let Some(cats) = self.words.get(word) {
+                let total: i64 = cats.values().sum();
+                if total >= MIN_WORD_SEEN {
+                    for (category, n) in cats {
+                        *scores.entry(category.as_str()).or_default() += *n as f64 / total as f64;
+                    }
+                    signals.push(format!("'{}' in {} past titles", word, total));
+                }
+            }
+        }
+
+        let sum: f64 = scores.values().sum();
+        match scores.iter().max_by(|a, b| a.1.total_cmp(b.1).then(b.0.cmp(a.0))) {
+            Some((category, best)) => self.inference(category, best / (sum + SMOOTHING), signals.join(", ")),
+            None => self.inference(FALLBACK_CATEGORY, 0.0, "no matching keywords or history".into()),
+        }
+    }
+}
+
+// ─── Review queue ───────────────────────────────────────────────────
+
+/// Queue a low-confidence guess for review (no-op when confident enough)
+pub async fn queue_for_review<'e, E>(
+    executor: E,
+    activity_id: &str,
+    inference: &CategoryInference,
+    source: &str,
+) -> PosResult<()>
+where
+    E: sqlx::Executor<'e, Database = sqlx::Postgres>,
+{
+    if !inference.needs_review() {
+        return Ok(());
+    }
+    sqlx::query(
+        r#"INSERT INTO activity_category_reviews
+           (activity_id, suggested_category, suggested_productive, confidence, source)
+           VALUES ($1, $2, $3, $4, $5)
+           ON CONFLICT (activity_id) DO NOTHING"#
+    )
+    .bind(activity_id)
+    .bind(&inference.category)
+    .bind(inference.is_productive)
+    .bind(inference.confidence)
+    .bind(source)
+    .execute(executor)
+    .await
+    .map_err(|e| db_context("queue category review", e))?;
+    Ok(())
+}
+
+// ─── Commands ───────────────────────────────────────────────────────
+
+/// Preview the guess for a title without saving anything
+#[tauri::command]
+pub async fn infer_activity_category(db: State<'_, PosDb>, title: String) -> PosResult<CategoryInference> {
+    Ok(Classifier::load(&db.0).await?.classify(&title))
+}
+
+/// Log an activity from just a title and times; category and productivity are inferred
+#[tauri::command]
+pub async fn quick_add_activity(
+    db: State<'_, PosDb>,
+    config: State<'_, PosConfig>,
+    title: String,
+    start_time: String,
+    end_time: String,
+) -> PosResult<QuickAddResult> {
+    let pool = &db.0;
+    let title = title.trim().to_string();
+    if title.is_empty() {
+        return Err(PosError::InvalidInput("Title is required".into()));
+    }
+
+    let inference = Classifier::load(pool).await?.classify(&title);
+    let req = CreateActivityRequest {
+        start_time,
+        end_time,
+        category: inference.category.clone(),
+        title,
+        description: String::new(),
+        is_productive: Some(inference.is_productive),
+        goal_ids: None,
+        milestone_id: None,
+        book_id: None,
+        pages_read: None,
+        updates: None,
+        date: None,
+        food_items: None,
+    };
+    let activity = insert_activity(pool, config.0.split_activities_at_midnight, req).await?;
+    queue_for_review(pool, &activity.id, &inference, "quick_add").await?;
+
+    log::info!("[CATEGORY] Quick-add '{}' → {} ({:.2})", activity.title, inference.category, inference.confidence);
+    Ok(QuickAddResult { needs_review: inference.needs_review(), activity, inference })
+}
+
+/// Unresolved low-confidence guesses, oldest first
+#[tauri::command]
+pub async fn get_category_review_queue(db: State<'_, PosDb>) -> PosResult<Vec<CategoryReview>> {
+    sqlx::query_as::<_, CategoryReview>(
+        r#"SELECT r.activity_id, a.title, a.date, a.start_time, a.end_time,
+                  r.suggested_category, r.suggested_productive, r.confidence, r.source, r.created_at
+           FROM activity_category_reviews r
+           JOIN pos_activities a ON a.id = r.activity_id
+           WHERE r.resolved_at IS NULL AND a.deleted_at IS NULL
+           ORDER BY r.created_at"#
+    )
+    .fetch_all(&db.0)
+    .await
+    .map_err(|e| db_context("get_category_review_queue", e))
+}
+
+/// Confirm a guess, or correct it with `category` / `is_productive`
+#[tauri::command]
+pub async fn resolve_category_review(
+    db: State<'_, PosDb>,
+    activity_id: String,
+    category: Option<String>,
+    is_productive: Option<bool>,
+) -> PosResult<ActivityRow> {
+    let pool = &db.0;
+    let category = category.map(|c| c.trim().to_string()).filter(|c| !c.is_empty());
+
+    let mut tx = pool.begin().await.map_err(|e| db_context("TX begin", e))?;
+    let resolved = sqlx::query(
+        "UPDATE activity_category_reviews SET resolved_at = NOW() WHERE activity_id = $1 AND resolved_at IS NULL"
+    )
+    .bind(&activity_id)
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| db_context("resolve category review", e))?;
+    if resolved.rows_affected() == 0 {
+        return Err(PosError::NotFound(format!("No pending review for activity {}", activity_id)));
+    }
+
+    let changed = category.is_some() || is_productive.is_some();
+    if changed {
+        sqlx::query(
+            r#"UPDATE pos_activities
+               SET category = COALESCE($2, category),
+                   is_productive = COALESCE($3, is_productive),
+                   updated_at = NOW()
+               WHERE id = $1"#
+        )
+        .bind(&activity_id)
+        .bind(&category)
+        .bind(is_productive)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| db_context("apply category correction", e))?;
+    }
+    tx.commit().await.map_err(|e| db_context("TX commit", e))?;
+
+    if changed {
+        crate::dashboard::mark_snapshot_stale(pool).await;
+        if is_productive.is_some() {
+            streaks::invalidate(pool, StreakKind::Activity).await;
+        }
+    }
+    crate::pos::activities::fetch_activity(pool, &activity_id).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn history() -> Classifier {
+        let row = |t: &str, c: &str, n: i64, p: i64| (t.to_string(), c.to_string(), n, p);
+        Classifier::from_history(&[
+            row("Graph theory lecture", "college", 4, 4),
+            row("Standup", "discussion", 3, 3),
+            row("Standup", "development", 1, 1),
+            row("Rust side project", "development", 6, 6),
+            row("Evening walk", "walking", 2, 0),
+        ])
+    }
+
+    #[test]
+    fn test_exact_title_wins() {
+        let guess = history().classify("  standup ");
+        assert_eq!(guess.category, "discussion");
+        assert!(guess.confidence > 0.6 && guess.confidence < 0.8);
+    }
+
+    #[test]
+    fn test_keywords_and_word_priors() {
+        let c = history();
+        let guess = c.classify("Codeforces Div 2");
+        assert_eq!((guess.category.as_str(), guess.is_productive), ("codeforces", true));
+        assert!(!guess.needs_review());
+
+        // History says this user's walks aren't productive, overriding the rule default
+        let walk = c.classify("Walk to the market");
+        assert_eq!((walk.category.as_str(), walk.is_productive), ("walking", false));
+
+        let rust = c.classify("rust bindings");
+        assert_eq!(rust.category, "development");
+        assert!(rust.needs_review());
+
+        let unknown = c.classify("zzz qqq");
+        assert_eq!((unknown.category.as_str(), unknown.confidence), ("misc", 0.0));
+    }
+}
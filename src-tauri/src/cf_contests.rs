@@ -0,0 +1,218 @@
+// Codeforces Contest Calendar
+// Upcoming rounds from contest.list, stored in cf_contests and refreshed when older than
+// REFRESH_HOURS. register_contest_goal turns a contest into a unified goal due on the
+// contest's local start date (goals are date-only; the exact start time goes in the
+// description) and remembers the goal so registering twice returns the same one.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use tauri::State;
+
+use crate::PosDb;
+use crate::pos::error::{PosError, PosResult, db_context};
+use crate::pos::retry::{with_backoff, BackoffPolicy};
+use crate::pos::scrapers::{build_http_client, CODEFORCES_HOST};
+use crate::pos::timezone;
+use crate::unified_goals::{insert_unified_goal_tx, CreateGoalRequest, UnifiedGoalRow, UNIFIED_GOAL_COLS};
+
+const REFRESH_HOURS: i64 = 6;
+const CONTEST_LABEL: &str = "contest";
+
+// ─── Types ──────────────────────────────────────────────────────────
+
+#[derive(Debug, Deserialize)]
+struct ContestListResponse {
+    status: String,
+    result: Option<Vec<ApiContest>>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ApiContest {
+    id: i64,
+    name: String,
+    #[serde(rename = "type")]
+    kind: String,
+    phase: String,
+    duration_seconds: i64,
+    start_time_seconds: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct CfContest {
+    pub id: i64,
+    pub name: String,
+    /// CF, IOI or ICPC scoring
+    pub kind: String,
+    /// BEFORE, CODING, PENDING_SYSTEM_TEST, SYSTEM_TEST or FINISHED
+    pub phase: String,
+    pub start_time: DateTime<Utc>,
+    pub duration_seconds: i64,
+    /// Goal created by register_contest_goal
+    pub goal_id: Option<String>,
+    pub fetched_at: DateTime<Utc>,
+}
+
+const CONTEST_COLS: &str = "id, name, kind, phase, start_time, duration_seconds, goal_id, fetched_at";
+
+// ─── Fetching ───────────────────────────────────────────────────────
+
+/// Upsert every contest that hasn't finished; returns how many were stored
+async fn refresh_contests(pool: &PgPool) -> PosResult<usize> {
+    let client = build_http_client();
+    let data: ContestListResponse = with_backoff(CODEFORCES_HOST, BackoffPolicy::default(), || async {
+        let resp = client.get("https://codeforces.com/api/contest.list?gym=false").send().await?;
+        if !resp.status().is_success() {
+            return Err(PosError::External(format!("HTTP error: {}", resp.status())));
+        }
+        Ok(resp.json::<ContestListResponse>().await?)
+    }).await?;
+
+    if data.status != "OK" {
+        return Err(PosError::External("Codeforces API returned non-OK status".into()));
+    }
+
+    let mut stored = 0;
+    for c in data.result.unwrap_or_default().into_iter().filter(|c| c.phase != "FINISHED") {
+        let Some(start_time) = c.start_time_seconds.and_then(|s| DateTime::from_timestamp(s, 0)) else {
+            continue;
+        };
+        sqlx::query(
+            r#"INSERT INTO cf_contests (id, name, kind, phase, start_time, duration_seconds, fetched_at)
+               VALUES ($1, $2, $3, $4, $5, $6, NOW())
+               ON CONFLICT (id) DO UPDATE SET
+                   name = EXCLUDED.name, kind = EXCLUDED.kind, phase = EXCLUDED.phase,
+                   start_time = EXCLUDED.start_time, duration_seconds = EXCLUDED.duration_seconds,
+                   fetched_at = NOW()"#
+        )
+        .bind(c.id)
+        .bind(&c.name)
+        .bind(&c.kind)
+        .bind(&c.phase)
+        .bind(start_time)
+        .bind(c.duration_seconds)
+        .execute(pool)
+        .await
+        .map_err(|e| db_context("store contest", e))?;
+        stored += 1;
+    }
+    log::info!("[CF CONTESTS] Refreshed {} upcoming/running contests", stored);
+    Ok(stored)
+}
+
+async fn fetch_contest(pool: &PgPool, contest_id: i64) -> PosResult<Option<CfContest>> {
+    sqlx::query_as::<_, CfContest>(&format!("SELECT {} FROM cf_contests WHERE id = $1", CONTEST_COLS))
+        .bind(contest_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| db_context("fetch contest", e))
+}
+
+// ─── Commands ───────────────────────────────────────────────────────
+
+/// Contests that haven't ended, soonest first. Refetched when the stored list is older
+/// than REFRESH_HOURS or `force_refresh` is set; a failed refetch falls back to stored rows.
+#[tauri::command]
+pub async fn get_upcoming_contests(
+    db: State<'_, PosDb>,
+    force_refresh: Option<bool>,
+) -> PosResult<Vec<CfContest>> {
+    let pool = &db.0;
+    let last_fetch: Option<DateTime<Utc>> = sqlx::query_scalar("SELECT MAX(fetched_at) FROM cf_contests")
+        .fetch_one(pool)
+        .await
+        .map_err(|e| db_context("contest freshness", e))?;
+    let stale = last_fetch.map_or(true, |t| Utc::now() - t > chrono::Duration::hours(REFRESH_HOURS));
+
+    if force_refresh.unwrap_or(false) || stale {
+        if let Err(e) = refresh_contests(pool).await {
+            if last_fetch.is_none() {
+                return Err(e);
+            }
+            log::warn!("[CF CONTESTS] Refresh failed, serving stored contests: {}", e);
+        }
+    }
+
+    sqlx::query_as::<_, CfContest>(&format!(
+        r#"SELECT {} FROM cf_contests
+           WHERE start_time + make_interval(secs => duration_seconds::double precision) > NOW()
+           ORDER BY start_time"#,
+        CONTEST_COLS
+    ))
+    .fetch_all(pool)
+    .await
+    .map_err(|e| db_context("get_upcoming_contests", e))
+}
+
+/// Create (or return the existing) goal for an upcoming contest
+#[tauri::command]
+pub async fn register_contest_goal(
+    db: State<'_, PosDb>,
+    contest_id: i64,
+) -> PosResult<UnifiedGoalRow> {
+    let pool = &db.0;
+    if fetch_contest(pool, contest_id).await?.is_none() {
+        refresh_contests(pool).await?;
+    }
+
+    // The row lock makes concurrent registrations wait, then return the first one's goal
+    let mut tx = pool.begin().await.map_err(|e| db_context("TX begin", e))?;
+    let contest = sqlx::query_as::<_, CfContest>(&format!(
+        "SELECT {} FROM cf_contests WHERE id = $1 FOR UPDATE", CONTEST_COLS
+    ))
+    .bind(contest_id)
+    .fetch_optional(&mut *tx)
+    .await
+    .map_err(|e| db_context("lock contest", e))?
+    .ok_or_else(|| PosError::NotFound(format!("Contest {} is not upcoming", contest_id)))?;
+
+    if let Some(goal_id) = &contest.goal_id {
+        let existing = sqlx::query_as::<_, UnifiedGoalRow>(&format!(
+            "SELECT {} FROM unified_goals WHERE id = $1 AND deleted_at IS NULL", UNIFIED_GOAL_COLS
+        ))
+        .bind(goal_id)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| db_context("fetch contest goal", e))?;
+        if let Some(goal) = existing {
+            return Ok(goal);
+        }
+    }
+    if contest.phase == "FINISHED" {
+        return Err(PosError::InvalidInput(format!("{} has already finished", contest.name)));
+    }
+
+    let start_local = timezone::local_datetime(contest.start_time);
+    let goal = insert_unified_goal_tx(&mut tx, CreateGoalRequest {
+        text: format!("Codeforces: {}", contest.name),
+        description: Some(format!(
+            "Starts {} ({}h{:02}m) — https://codeforces.com/contests/{}",
+            start_local.format("%a %d %b %H:%M"),
+            contest.duration_seconds / 3600,
+            contest.duration_seconds % 3600 / 60,
+            contest.id
+        )),
+        date: Some(timezone::local_date_string(contest.start_time)),
+        recurring_pattern: None,
+        priority: Some("high".into()),
+        urgent: None,
+        metrics: None,
+        problem_id: None,
+        labels: Some(vec![CONTEST_LABEL.to_string()]),
+        parent_goal_id: None,
+    }).await?;
+
+    sqlx::query("UPDATE cf_contests SET goal_id = $2 WHERE id = $1")
+        .bind(contest.id)
+        .bind(&goal.id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| db_context("link contest goal", e))?;
+    tx.commit().await.map_err(|e| db_context("TX commit", e))?;
+    crate::dashboard::mark_snapshot_stale(pool).await;
+
+    log::info!("[CF CONTESTS] Registered goal {} for contest {}", goal.id, contest.id);
+    Ok(goal)
+}
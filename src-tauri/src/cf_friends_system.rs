@@ -1,10 +1,16 @@
 use crate::PosDb;
 use crate::pos::utils::gen_id;
 use crate::pos::error::{PosError, PosResult};
+use crate::pos::retry::{with_backoff, BackoffPolicy};
+use crate::pos::scrapers::{build_http_client, queue, CODEFORCES_HOST};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use tauri::State;
 
+// Cursor-based incremental sync lives in its own file to keep this one under 600 lines
+mod sync;
+pub use sync::*;
+
 // ============================================================================
 // Types
 // ============================================================================
@@ -53,6 +59,16 @@ pub struct AddFriendRequest {
     pub display_name: Option<String>,
 }
 
+/// Return type for sync_friend_submissions
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FriendsSyncSummary {
+    pub friends_synced: i32,
+    pub imported: i32,
+    /// Handles whose sync failed, with the error
+    pub failed: Vec<String>,
+}
+
 /// Return type for generate_friends_ladder
 #[derive(Debug, Serialize, sqlx::FromRow)]
 #[serde(rename_all = "camelCase")]
@@ -107,45 +123,40 @@ struct CFUser {
     max_rank: Option<String>,
 }
 
-async fn fetch_cf_submissions(handle: &str) -> PosResult<Vec<CFSubmission>> {
-    let url = format!("https://codeforces.com/api/user.status?handle={}", handle);
-
-    let response = reqwest::get(&url)
-        .await
-        .map_err(|e| PosError::External(format!("CF API request failed: {}", e)))?;
-
-    let api_response: CFApiResponse<Vec<CFSubmission>> = response
-        .json()
-        .await
-        .map_err(|e| PosError::External(format!("CF API parse failed: {}", e)))?;
-
-    if api_response.status != "OK" {
-        return Err(PosError::External("CF API returned non-OK status".to_string()));
-    }
-
-    Ok(api_response.result.unwrap_or_default())
-}
+/// CF asks for at most one API call every 2 seconds
+const CF_API_DELAY: std::time::Duration = std::time::Duration::from_millis(2100);
 
 async fn verify_cf_handle(handle: &str) -> PosResult<CFUser> {
-    let url = format!("https://codeforces.com/api/user.info?handles={}", handle);
+    fetch_cf_users(&[handle.to_string()]).await?
+        .into_iter()
+        .next()
+        .ok_or_else(|| PosError::External("User not found in CF response".to_string()))
+}
 
-    let response = reqwest::get(&url)
-        .await
-        .map_err(|e| PosError::External(format!("CF API request failed: {}", e)))?;
+/// One user.info call for several handles
+async fn fetch_cf_users(handles: &[String]) -> PosResult<Vec<CFUser>> {
+    let url = format!("https://codeforces.com/api/user.info?handles={}", handles.join(";"));
 
-    let api_response: CFApiResponse<Vec<CFUser>> = response
-        .json()
-        .await
-        .map_err(|e| PosError::External(format!("CF API parse failed: {}", e)))?;
+    let client = build_http_client();
+    let api_response: CFApiResponse<Vec<CFUser>> = with_backoff(CODEFORCES_HOST, BackoffPolicy::default(), || async {
+        let response = client.get(&url)
+            .send()
+            .await
+            .map_err(|e| PosError::External(format!("CF API request failed: {}", e)))?;
+        if !response.status().is_success() {
+            return Err(PosError::External(format!("CF API HTTP error: {}", response.status())));
+        }
+        response
+            .json()
+            .await
+            .map_err(|e| PosError::External(format!("CF API parse failed: {}", e)))
+    }).await?;
 
     if api_response.status != "OK" {
         return Err(PosError::External("CF API returned non-OK status or user not found".to_string()));
     }
 
-    // The API returns a list, we asked for one handle
-    api_response.result
-        .and_then(|users| users.into_iter().next())
-        .ok_or_else(|| PosError::External("User not found in CF response".to_string()))
+    Ok(api_response.result.unwrap_or_default())
 }
 
 // ============================================================================
@@ -233,81 +244,45 @@ pub async fn sync_cf_friend_submissions(
     .await
     .map_err(|e| PosError::Database(format!("Friend not found: {}", e)))?;
 
-    // Fetch user info to update rating
-    let user_info = verify_cf_handle(&friend.cf_handle).await?;
+    queue::run(pool, QUEUE_PLATFORM, async {
+        // Fetch user info to update rating
+        let user_info = verify_cf_handle(&friend.cf_handle).await?;
+        sync_friend(pool, &friend, user_info).await
+    }).await
+}
+
+/// Full sync of one friend: every accepted submission plus their latest rating
+async fn sync_friend(pool: &sqlx::PgPool, friend: &CFFriendRow, user_info: CFUser) -> PosResult<i32> {
     log::info!("[CF FRIEND] Fetched user info for {}: rating={:?}, max_rating={:?}", 
                friend.cf_handle, user_info.rating, user_info.max_rating);
+    let (_, imported) = sync_one(pool, friend, &user_info, FriendSyncDepth::Full).await?;
+    Ok(imported)
+}
 
-    // Fetch submissions from CF API
-    let submissions = fetch_cf_submissions(&friend.cf_handle).await?;
-    let total_count = submissions.len() as i64;
-    log::info!("[CF FRIEND] Fetched {} submissions for {} from CF API", total_count, friend.cf_handle);
-
-    // Filter for AC (Accepted) submissions only
-    let ac_subs: Vec<CFSubmission> = submissions
-        .into_iter()
-        .filter(|s| s.verdict.as_deref() == Some("OK"))
-        .collect();
-
-    let mut imported_count: i32 = 0;
-
-    for sub in ac_subs {
-        if let Some(contest_id) = sub.problem.contest_id {
-            let problem_url = format!(
-                "https://codeforces.com/problemset/problem/{}/{}",
-                contest_id,
-                sub.problem.index
-            );
-            let problem_id = format!("cf_{}_{}", contest_id, sub.problem.index);
-            let submission_time = DateTime::from_timestamp(sub.creation_time_seconds, 0)
-                .unwrap_or_else(Utc::now);
-
-            let result = sqlx::query(
-                r#"
-                INSERT INTO cf_friend_submissions
-                (id, friend_id, problem_id, problem_name, problem_url,
-                 contest_id, problem_index, difficulty, verdict, submission_time, created_at)
-                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, 'OK', $9, $10)
-                ON CONFLICT (friend_id, problem_id) DO NOTHING
-                "#,
-            )
-            .bind(gen_id())
-            .bind(&friend.id)
-            .bind(&problem_id)
-            .bind(&sub.problem.name)
-            .bind(&problem_url)
-            .bind(contest_id)
-            .bind(&sub.problem.index)
-            .bind(sub.problem.rating)
-            .bind(submission_time)
-            .bind(Utc::now())
-            .execute(pool)
-            .await
-            .map_err(|e| PosError::Database(format!("Failed to insert submission: {}", e)))?;
-
-            if result.rows_affected() > 0 {
-                imported_count += 1;
-            }
-        }
-    }
+/// Full sync of every friend; see `sync_cf_friends` for incremental syncs with progress events
+#[tauri::command]
+pub async fn sync_friend_submissions(
+    db: State<'_, PosDb>,
+) -> PosResult<FriendsSyncSummary> {
+    queue::run(&db.0, QUEUE_PLATFORM, sync_all_friends(&db.0, FriendSyncDepth::Full, |_| {})).await
+}
 
-    // Update last_synced, total_submissions, current_rating, and max_rating
-    sqlx::query(
-        "UPDATE cf_friends SET last_synced = $1, total_submissions = $2, current_rating = $3, max_rating = $4 WHERE id = $5"
-    )
-        .bind(Utc::now())
-        .bind(total_count)
-        .bind(user_info.rating)
-        .bind(user_info.max_rating)
-        .bind(&friend.id)
-        .execute(pool)
+/// Remove a friend and (via FK cascade) their synced submissions
+#[tauri::command]
+pub async fn remove_cf_friend(
+    db: State<'_, PosDb>,
+    id: String,
+) -> PosResult<()> {
+    let deleted = sqlx::query("DELETE FROM cf_friends WHERE id = $1")
+        .bind(&id)
+        .execute(&db.0)
         .await
-        .map_err(|e| PosError::Database(format!("Failed to update sync data: {}", e)))?;
-
-    log::info!("[CF FRIEND] Sync complete for {}. Imported {} new AC submissions. Rating: {:?} -> {:?}", 
-               friend.cf_handle, imported_count, friend.current_rating, user_info.rating);
+        .map_err(|e| PosError::Database(format!("Failed to delete friend: {}", e)))?;
 
-    Ok(imported_count)
+    if deleted.rows_affected() == 0 {
+        return Err(PosError::NotFound(format!("CF friend {}", id)));
+    }
+    Ok(())
 }
 
 #[tauri::command]
@@ -371,3 +346,158 @@ pub async fn generate_friends_ladder(
 
     Ok(problems)
 }
+
+const PEER_LADDER_DEFAULT_BAND: i32 = 200;
+const PEER_LADDER_SIZE: i64 = 50;
+
+/// Build (or refresh) a `FriendsGenerated` ladder of problems most solved by friends
+/// rated within ±`rating_band` of my Codeforces rating that I have not solved yet.
+/// Refreshing keeps the ladder id, so existing progress rows stay attached.
+#[tauri::command]
+pub async fn generate_peer_ladder(
+    db: State<'_, PosDb>,
+    rating_band: Option<i32>,
+) -> PosResult<crate::cf_ladder_system::CFLadderRow> {
+    let pool = &db.0;
+    let band = rating_band.unwrap_or(PEER_LADDER_DEFAULT_BAND).clamp(50, 1000);
+
+    let my_rating: i32 = sqlx::query_scalar::<_, Option<serde_json::Value>>(
+        "SELECT data FROM platform_cache WHERE platform = 'codeforces' AND resource = 'user_stats'"
+    )
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| PosError::Database(format!("Failed to load user stats: {}", e)))?
+    .flatten()
+    .and_then(|data| data.get("rating").and_then(|r| r.as_i64()))
+    .map(|r| r as i32)
+    .ok_or_else(|| PosError::InvalidInput("No Codeforces rating yet — run the Codeforces sync first".into()))?;
+
+    let (lo, hi) = (my_rating - band, my_rating + band);
+
+    // Friend ids are "cf_{contest}_{index}", ladder ids "{contest}{index}", my submissions "cf-{contest}{index}"
+    let problems: Vec<(String, String, String, Option<i32>, i64)> = sqlx::query_as(
+        r#"
+        SELECT s.contest_id::text || s.problem_index        AS problem_id,
+               MAX(s.problem_name)                           AS problem_name,
+               MAX(s.problem_url)                            AS problem_url,
+               MAX(s.difficulty)                             AS difficulty,
+               COUNT(DISTINCT s.friend_id)::bigint           AS peer_count
+        FROM cf_friend_submissions s
+        JOIN cf_friends f ON f.id = s.friend_id
+        WHERE f.current_rating BETWEEN $1 AND $2
+          AND s.contest_id IS NOT NULL
+          AND s.problem_name <> ''
+          AND NOT EXISTS (
+              SELECT 1 FROM pos_submissions p
+              WHERE p.platform = 'codeforces'
+                AND p.verdict = 'OK'
+                AND p.excluded_at IS NULL
+                AND p.problem_id = 'cf-' || s.contest_id::text || s.problem_index
+          )
+        GROUP BY s.contest_id, s.problem_index
+        ORDER BY peer_count DESC, MAX(s.difficulty) ASC NULLS LAST
+        LIMIT $3
+        "#,
+    )
+    .bind(lo)
+    .bind(hi)
+    .bind(PEER_LADDER_SIZE)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| PosError::Database(format!("Failed to rank peer problems: {}", e)))?;
+
+    if problems.is_empty() {
+        return Err(PosError::NotFound(format!(
+            "No unsolved problems from friends rated {}-{}. Add or sync friends near your rating.", lo, hi
+        )));
+    }
+
+    let name = format!("Peer Ladder (±{})", band);
+    let description = format!(
+        "Most solved by friends rated {}-{} that you haven't solved. Regenerate to refresh.", lo, hi
+    );
+
+    let mut tx = pool.begin().await
+        .map_err(|e| PosError::Database(format!("Failed to begin transaction: {}", e)))?;
+
+    let existing: Option<String> = sqlx::query_scalar(
+        "SELECT id FROM cf_ladders WHERE name = $1 AND source = 'FriendsGenerated'"
+    )
+    .bind(&name)
+    .fetch_optional(&mut *tx)
+    .await
+    .map_err(|e| PosError::Database(format!("Failed to look up peer ladder: {}", e)))?;
+
+    let ladder_id = match existing {
+        Some(id) => {
+            sqlx::query("DELETE FROM cf_ladder_problems WHERE ladder_id = $1")
+                .bind(&id)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| PosError::Database(format!("Failed to clear peer ladder: {}", e)))?;
+            sqlx::query(
+                "UPDATE cf_ladders SET description = $1, rating_min = $2, rating_max = $3, problem_count = $4 WHERE id = $5"
+            )
+            .bind(&description)
+            .bind(lo)
+            .bind(hi)
+            .bind(problems.len() as i32)
+            .bind(&id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| PosError::Database(format!("Failed to update peer ladder: {}", e)))?;
+            id
+        }
+        None => {
+            let id = gen_id();
+            sqlx::query(
+                "INSERT INTO cf_ladders (id, name, description, rating_min, rating_max, difficulty, source, problem_count, created_at)
+                 VALUES ($1, $2, $3, $4, $5, NULL, 'FriendsGenerated', $6, $7)"
+            )
+            .bind(&id)
+            .bind(&name)
+            .bind(&description)
+            .bind(lo)
+            .bind(hi)
+            .bind(problems.len() as i32)
+            .bind(Utc::now())
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| PosError::Database(format!("Failed to create peer ladder: {}", e)))?;
+            id
+        }
+    };
+
+    for (position, (problem_id, problem_name, problem_url, difficulty, _)) in problems.iter().enumerate() {
+        sqlx::query(
+            "INSERT INTO cf_ladder_problems
+             (id, ladder_id, problem_id, problem_name, problem_url, position, difficulty, online_judge, created_at)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, 'Codeforces', NOW())"
+        )
+        .bind(gen_id())
+        .bind(&ladder_id)
+        .bind(problem_id)
+        .bind(problem_name)
+        .bind(problem_url)
+        .bind(position as i32 + 1)
+        .bind(difficulty)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| PosError::Database(format!("Failed to insert peer ladder problem: {}", e)))?;
+    }
+
+    tx.commit().await
+        .map_err(|e| PosError::Database(format!("Failed to commit peer ladder: {}", e)))?;
+
+    let ladder: crate::cf_ladder_system::CFLadderRow = sqlx::query_as(
+        "SELECT id, name, description, rating_min, rating_max, difficulty, source, problem_count, created_at FROM cf_ladders WHERE id = $1"
+    )
+    .bind(&ladder_id)
+    .fetch_one(pool)
+    .await
+    .map_err(|e| PosError::Database(format!("Failed to fetch peer ladder: {}", e)))?;
+
+    log::info!("[CF FRIEND] Peer ladder {} refreshed with {} problems (rating {}-{})",
+               ladder.id, ladder.problem_count, lo, hi);
+    Ok(ladder)
+}
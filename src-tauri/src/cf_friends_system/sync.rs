@@ -0,0 +1,327 @@
+// Friend submission sync depth
+// user.status lists submissions newest first. Each friend keeps a cursor (creation time of the
+// newest submission seen); an incremental sync pages back `depth` submissions at a time until
+// it reaches the cursor, so a quiet friend costs one small request. A full pass re-reads the
+// whole history and also picks up verdicts that were still pending when the cursor moved past.
+// Every sync runs through the scrape queue under its own cooldown, so it never overlaps another
+// scraper's API calls.
+
+use chrono::{DateTime, Duration, Local, NaiveTime, Utc};
+use serde::Serialize;
+use sqlx::PgPool;
+use tauri::{AppHandle, Emitter, State};
+
+use crate::{PosConfig, PosDb};
+use crate::pos::error::{PosError, PosResult, db_context};
+use crate::pos::problem_url::canonical_problem_url;
+use crate::pos::retry::{with_backoff, BackoffPolicy};
+use crate::pos::scrapers::{build_http_client, queue, CODEFORCES_HOST};
+use crate::pos::utils::gen_id;
+use super::{fetch_cf_users, CFApiResponse, CFFriendRow, CFSubmission, CFUser, FriendsSyncSummary, CF_API_DELAY};
+
+const PROGRESS_EVENT: &str = "cf-friends-sync-progress";
+/// Scrape queue platform key (cooldown separate from the user's own Codeforces scrape)
+pub(super) const QUEUE_PLATFORM: &str = "cf-friends";
+/// Local hour the nightly full reconciliation runs at
+const NIGHTLY_SYNC_HOUR: u32 = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FriendSyncDepth {
+    /// Page back this many submissions at a time until the cursor
+    Incremental(u32),
+    /// Whole history, ignoring the cursor
+    Full,
+}
+
+/// `None` / "incremental" → configured page size, "full", or an explicit page size
+fn parse_depth(depth: Option<&str>, default_page: u32) -> PosResult<FriendSyncDepth> {
+    match depth.map(|d| d.trim().to_lowercase()).as_deref() {
+        None | Some("") | Some("incremental") => Ok(FriendSyncDepth::Incremental(default_page)),
+        Some("full") => Ok(FriendSyncDepth::Full),
+        Some(n) => match n.parse::<u32>() {
+            Ok(n) if (1..=10_000).contains(&n) => Ok(FriendSyncDepth::Incremental(n)),
+            _ => Err(PosError::InvalidInput(format!(
+                "Invalid depth '{}'. Use incremental, full or a page size between 1 and 10000", n
+            ))),
+        },
+    }
+}
+
+/// Emitted once per friend as `cf-friends-sync-progress`
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FriendSyncProgress {
+    pub handle: String,
+    /// 1-based position in this run
+    pub index: usize,
+    pub total: usize,
+    pub fetched: usize,
+    pub imported: i32,
+    pub error: Option<String>,
+}
+
+// ─── Fetching ───────────────────────────────────────────────────────
+
+async fn fetch_page(handle: &str, from: u32, count: Option<u32>) -> PosResult<Vec<CFSubmission>> {
+    let url = match count {
+        Some(count) => format!("https://codeforces.com/api/user.status?handle={}&from={}&count={}", handle, from, count),
+        None => format!("https://codeforces.com/api/user.status?handle={}", handle),
+    };
+    let client = build_http_client();
+    let api_response: CFApiResponse<Vec<CFSubmission>> = with_backoff(CODEFORCES_HOST, BackoffPolicy::default(), || async {
+        let resp = client.get(&url)
+            .send()
+            .await
+            .map_err(|e| PosError::External(format!("CF API request failed: {}", e)))?;
+        if !resp.status().is_success() {
+            return Err(PosError::External(format!("CF API HTTP error: {}", resp.status())));
+        }
+        resp.json()
+            .await
+            .map_err(|e| PosError::External(format!("CF API parse failed: {}", e)))
+    }).await?;
+
+    if api_response.status != "OK" {
+        return Err(PosError::External("CF API returned non-OK status".to_string()));
+    }
+    queue::page_fetched();
+    Ok(api_response.result.unwrap_or_default())
+}
+
+/// Submissions newer than `cursor`, or everything for a full pass / first sync
+async fn fetch_since(handle: &str, cursor: Option<i64>, depth: FriendSyncDepth) -> PosResult<Vec<CFSubmission>> {
+    let (page_size, cursor) = match (depth, cursor) {
+        (FriendSyncDepth::Incremental(n), Some(c)) => (n, c),
+        _ => return fetch_page(handle, 1, None).await,
+    };
+    let mut newer = Vec::new();
+    let mut from = 1;
+    loop {
+        let page = fetch_page(handle, from, Some(page_size)).await?;
+        let len = page.len();
+        let reached = page.iter().any(|s| s.creation_time_seconds <= cursor);
+        newer.extend(page.into_iter().filter(|s| s.creation_time_seconds > cursor));
+        if reached || len < page_size as usize {
+            return Ok(newer);
+        }
+        from += page_size;
+        tokio::time::sleep(CF_API_DELAY).await;
+    }
+}
+
+// ─── Import ─────────────────────────────────────────────────────────
+
+/// Insert accepted submissions; returns how many were new
+async fn import_accepted(pool: &PgPool, friend_id: &str, submissions: &[CFSubmission]) -> PosResult<i32> {
+    let mut imported_count: i32 = 0;
+    for sub in submissions.iter().filter(|s| s.verdict.as_deref() == Some("OK")) {
+        let Some(contest_id) = sub.problem.contest_id else { continue };
+        let problem_url = canonical_problem_url(&format!(
+            "https://codeforces.com/problemset/problem/{}/{}",
+            contest_id,
+            sub.problem.index
+        ));
+        let problem_id = format!("cf_{}_{}", contest_id, sub.problem.index);
+        let submission_time = DateTime::from_timestamp(sub.creation_time_seconds, 0)
+            .unwrap_or_else(Utc::now);
+
+        let result = sqlx::query(
+            r#"
+            INSERT INTO cf_friend_submissions
+            (id, friend_id, problem_id, problem_name, problem_url,
+             contest_id, problem_index, difficulty, verdict, submission_time, created_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, 'OK', $9, $10)
+            ON CONFLICT (friend_id, problem_id) DO NOTHING
+            "#,
+        )
+        .bind(gen_id())
+        .bind(friend_id)
+        .bind(&problem_id)
+        .bind(&sub.problem.name)
+        .bind(&problem_url)
+        .bind(contest_id)
+        .bind(&sub.problem.index)
+        .bind(sub.problem.rating)
+        .bind(submission_time)
+        .bind(Utc::now())
+        .execute(pool)
+        .await
+        .map_err(|e| PosError::Database(format!("Failed to insert submission: {}", e)))?;
+
+        if result.rows_affected() > 0 {
+            imported_count += 1;
+        }
+    }
+    Ok(imported_count)
+}
+
+/// Sync one friend at `depth`, store their rating and advance the cursor.
+/// Returns (submissions fetched, new AC submissions).
+pub(super) async fn sync_one(
+    pool: &PgPool,
+    friend: &CFFriendRow,
+    user_info: &CFUser,
+    depth: FriendSyncDepth,
+) -> PosResult<(usize, i32)> {
+    let cursor: Option<i64> = sqlx::query_scalar("SELECT sync_cursor FROM cf_friends WHERE id = $1")
+        .bind(&friend.id)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| db_context("load friend sync cursor", e))?;
+
+    let submissions = fetch_since(&friend.cf_handle, cursor, depth).await?;
+    let full = depth == FriendSyncDepth::Full || cursor.is_none();
+    let imported = import_accepted(pool, &friend.id, &submissions).await?;
+    let newest = submissions.iter().map(|s| s.creation_time_seconds).max();
+
+    sqlx::query(
+        r#"UPDATE cf_friends
+           SET last_synced = NOW(),
+               sync_cursor = GREATEST(sync_cursor, $2),
+               last_full_sync = CASE WHEN $3 THEN NOW() ELSE last_full_sync END,
+               total_submissions = CASE WHEN $3 THEN $4 ELSE COALESCE(total_submissions, 0) + $4 END,
+               current_rating = $5, max_rating = $6, max_rank = $7
+           WHERE id = $1"#
+    )
+    .bind(&friend.id)
+    .bind(newest)
+    .bind(full)
+    .bind(submissions.len() as i64)
+    .bind(user_info.rating)
+    .bind(user_info.max_rating)
+    .bind(&user_info.max_rank)
+    .execute(pool)
+    .await
+    .map_err(|e| PosError::Database(format!("Failed to update sync data: {}", e)))?;
+
+    log::info!("[CF FRIEND] {} sync for {}: fetched {}, imported {} new AC. Rating: {:?} -> {:?}",
+               if full { "Full" } else { "Incremental" }, friend.cf_handle, submissions.len(), imported,
+               friend.current_rating, user_info.rating);
+    Ok((submissions.len(), imported))
+}
+
+/// Sync every friend: one batched user.info call for ratings, then user.status per friend.
+/// A failing handle is reported in the summary without stopping the others. Callers run
+/// this through `queue::run` with QUEUE_PLATFORM.
+pub async fn sync_all_friends(
+    pool: &PgPool,
+    depth: FriendSyncDepth,
+    on_progress: impl Fn(&FriendSyncProgress),
+) -> PosResult<FriendsSyncSummary> {
+    let friends: Vec<CFFriendRow> = sqlx::query_as(
+        "SELECT id, cf_handle, display_name, current_rating, max_rating, last_synced, created_at FROM cf_friends ORDER BY last_synced ASC NULLS FIRST"
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| PosError::Database(format!("Failed to get friends: {}", e)))?;
+
+    let mut summary = FriendsSyncSummary { friends_synced: 0, imported: 0, failed: Vec::new() };
+    if friends.is_empty() {
+        return Ok(summary);
+    }
+
+    queue::items_total(friends.len());
+    let handles: Vec<String> = friends.iter().map(|f| f.cf_handle.clone()).collect();
+    let mut users: std::collections::HashMap<String, CFUser> = fetch_cf_users(&handles).await?
+        .into_iter()
+        .map(|u| (u.handle.to_lowercase(), u))
+        .collect();
+
+    for (i, friend) in friends.iter().enumerate() {
+        let mut progress = FriendSyncProgress {
+            handle: friend.cf_handle.clone(),
+            index: i + 1,
+            total: friends.len(),
+            fetched: 0,
+            imported: 0,
+            error: None,
+        };
+        let result = match users.remove(&friend.cf_handle.to_lowercase()) {
+            Some(user_info) => {
+                tokio::time::sleep(CF_API_DELAY).await;
+                sync_one(pool, friend, &user_info, depth).await
+            }
+            None => Err(PosError::NotFound("not returned by user.info".into())),
+        };
+        match result {
+            Ok((fetched, n)) => {
+                summary.friends_synced += 1;
+                summary.imported += n;
+                progress.fetched = fetched;
+                progress.imported = n;
+            }
+            Err(e) => {
+                log::warn!("[CF FRIEND] Sync failed for {}: {}", friend.cf_handle, e);
+                summary.failed.push(format!("{}: {}", friend.cf_handle, e));
+                progress.error = Some(e.to_string());
+            }
+        }
+        queue::item_processed();
+        on_progress(&progress);
+    }
+
+    log::info!("[CF FRIEND] Synced {}/{} friends ({:?}), {} new AC submissions",
+               summary.friends_synced, friends.len(), depth, summary.imported);
+    Ok(summary)
+}
+
+// ─── Nightly reconciliation ─────────────────────────────────────────
+
+/// Time from `now` until the next NIGHTLY_SYNC_HOUR:00 local
+fn until_next_nightly(now: chrono::DateTime<Local>) -> std::time::Duration {
+    let at = NaiveTime::from_hms_opt(NIGHTLY_SYNC_HOUR, 0, 0).unwrap_or(NaiveTime::MIN);
+    let mut next = now.date_naive().and_time(at);
+    if next <= now.naive_local() {
+        next += Duration::days(1);
+    }
+    (next - now.naive_local()).to_std().unwrap_or_default()
+}
+
+/// Full friend sync every night (no-op unless CF_FRIEND_NIGHTLY_FULL_SYNC is set)
+pub fn start_nightly_full_sync(pool: PgPool, app: AppHandle, enabled: bool) {
+    if !enabled {
+        return;
+    }
+    tauri::async_runtime::spawn(async move {
+        loop {
+            tokio::time::sleep(until_next_nightly(Local::now())).await;
+            log::info!("[CF FRIEND] Nightly full reconciliation starting");
+            let emit = |p: &FriendSyncProgress| { let _ = app.emit(PROGRESS_EVENT, p); };
+            let sync = sync_all_friends(&pool, FriendSyncDepth::Full, emit);
+            if let Err(e) = queue::run(&pool, QUEUE_PLATFORM, sync).await {
+                log::warn!("[CF FRIEND] Nightly full sync failed: {}", e);
+            }
+        }
+    });
+}
+
+// ─── Commands ───────────────────────────────────────────────────────
+
+/// Sync all friends. `depth`: "incremental" (default, pages of CF_FRIEND_SYNC_DEPTH back to
+/// each friend's cursor), a page size, or "full". Emits `cf-friends-sync-progress` per friend.
+#[tauri::command]
+pub async fn sync_cf_friends(
+    app: AppHandle,
+    db: State<'_, PosDb>,
+    config: State<'_, PosConfig>,
+    depth: Option<String>,
+) -> PosResult<FriendsSyncSummary> {
+    let depth = parse_depth(depth.as_deref(), config.0.cf_friend_sync_depth)?;
+    let sync = sync_all_friends(&db.0, depth, |p| { let _ = app.emit(PROGRESS_EVENT, p); });
+    queue::run(&db.0, QUEUE_PLATFORM, sync).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_depth() {
+        assert_eq!(parse_depth(None, 100).unwrap(), FriendSyncDepth::Incremental(100));
+        assert_eq!(parse_depth(Some("Incremental"), 100).unwrap(), FriendSyncDepth::Incremental(100));
+        assert_eq!(parse_depth(Some(" full "), 100).unwrap(), FriendSyncDepth::Full);
+        assert_eq!(parse_depth(Some("250"), 100).unwrap(), FriendSyncDepth::Incremental(250));
+        assert!(parse_depth(Some("0"), 100).is_err());
+        assert!(parse_depth(Some("deep"), 100).is_err());
+    }
+}
@@ -6,6 +6,7 @@ use tauri::State;
 
 use crate::PosDb;
 use crate::pos::error::{PosError, PosResult, db_context};
+use crate::pos::problem_url::canonical_problem_url;
 use crate::pos::utils::gen_id;
 use super::cf_ladder_types::*;
 
@@ -128,13 +129,13 @@ pub async fn bulk_add_problems(
     let mut current_position = max_position.unwrap_or(0);
     
     for url in &req.urls {
-        let url = url.trim();
-        if url.is_empty() {
+        if url.trim().is_empty() {
             continue;
         }
+        let url = canonical_problem_url(url);
         
         // Parse URL
-        let (judge, problem_id, name) = match parse_problem_url(url) {
+        let (judge, problem_id, name) = match parse_problem_url(&url) {
             Ok(parsed) => parsed,
             Err(e) => {
                 errors.push(format!("{}: {}", url, e));
@@ -172,7 +173,7 @@ pub async fn bulk_add_problems(
         .bind(&ladder_id)
         .bind(&problem_id)
         .bind(&name)
-        .bind(url)
+        .bind(&url)
         .bind(current_position)
         .bind(&judge)
         .bind(now)
@@ -186,7 +187,7 @@ pub async fn bulk_add_problems(
         // Handle GoalForToday action
         if matches!(req.action, BulkAction::GoalForToday) {
             let goal_id = gen_id();
-            let today = Utc::now().format("%Y-%m-%d").to_string();
+            let today = crate::pos::timezone::today_string();
             let due_date = Utc::now();
             
             // Create unified goal
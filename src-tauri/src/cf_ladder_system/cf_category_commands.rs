@@ -54,10 +54,7 @@ pub async fn get_category_stats(
         FROM cf_category_problems p
         WHERE p.category_id = $1
         AND EXISTS (
-            SELECT 1 FROM pos_submissions s 
-            WHERE s.problem_id = ('cf-' || p.problem_id) 
-            AND s.platform = 'codeforces' 
-            AND s.verdict = 'OK'
+            SELECT 1 FROM solved_problems s WHERE s.problem_id = ('cf-' || p.problem_id)
         )
         "#
     )
@@ -77,6 +74,9 @@ pub async fn get_category_stats(
             SELECT 1 FROM pos_submissions s 
             WHERE s.problem_id = ('cf-' || p.problem_id) 
             AND s.platform = 'codeforces'
+            AND s.excluded_at IS NULL
+            UNION ALL
+            SELECT 1 FROM known_solved k WHERE k.problem_id = ('cf-' || p.problem_id)
         )
         "#
     )
@@ -114,51 +114,67 @@ pub async fn import_category_from_html(
     
     let category_id = gen_id();
     let now = Utc::now();
+    // One transaction: a failed import leaves no half-filled category behind
+    let mut tx = db.0.begin().await.map_err(|e| db_context("begin category import", e))?;
     
-    sqlx::query::<sqlx::Postgres>(
+    let actual_cat_id: String = sqlx::query_scalar::<sqlx::Postgres, String>(
         "INSERT INTO cf_categories (id, name, description, problem_count, created_at)
-         VALUES ($1, $2, $3, $4, $5)
-         ON CONFLICT (name) DO UPDATE SET problem_count = $4
-         RETURNING id, name, description, problem_count, created_at"
+         VALUES ($1, $2, $3, 0, $4)
+         ON CONFLICT (name) DO UPDATE SET name = EXCLUDED.name
+         RETURNING id"
     )
     .bind(&category_id)
     .bind(&name)
     .bind::<Option<String>>(None)
-    .bind(parsed.problems.len() as i32)
     .bind(now)
-    .fetch_one(&db.0)
+    .fetch_one(&mut *tx)
     .await
     .map_err(|e| db_context("insert cf_category", e))?;
-    
-    let actual_cat_id: String = sqlx::query_scalar("SELECT id FROM cf_categories WHERE name = $1")
-        .bind(&name)
-        .fetch_one(&db.0)
-        .await
-        .map_err(|e| db_context("fetch cat id", e))?;
 
+    let mut row_ids = Vec::with_capacity(parsed.problems.len());
+    let mut problem_ids = Vec::with_capacity(parsed.problems.len());
+    let mut names = Vec::with_capacity(parsed.problems.len());
+    let mut urls = Vec::with_capacity(parsed.problems.len());
+    let mut positions = Vec::with_capacity(parsed.problems.len());
+    let mut difficulties = Vec::with_capacity(parsed.problems.len());
+    let mut judges = Vec::with_capacity(parsed.problems.len());
+    let mut years = Vec::with_capacity(parsed.problems.len());
+    let mut contests = Vec::with_capacity(parsed.problems.len());
     for problem in parsed.problems {
-        let problem_row_id = gen_id();
-        sqlx::query::<sqlx::Postgres>(
-            "INSERT INTO cf_category_problems 
-             (id, category_id, problem_id, problem_name, problem_url, position, difficulty, online_judge, year, contest, created_at)
-             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
-             ON CONFLICT (category_id, problem_id) DO NOTHING"
-        )
-        .bind(&problem_row_id)
+        row_ids.push(gen_id());
+        problem_ids.push(problem.problem_id);
+        names.push(problem.name);
+        urls.push(problem.url);
+        positions.push(problem.position);
+        difficulties.push(problem.difficulty);
+        judges.push(problem.judge);
+        years.push(problem.year);
+        contests.push(problem.contest);
+    }
+
+    sqlx::query(
+        r#"INSERT INTO cf_category_problems
+           (id, category_id, problem_id, problem_name, problem_url, position, difficulty, online_judge, year, contest, created_at)
+           SELECT id, $1, problem_id, name, url, position, difficulty, judge, year, contest, $2
+           FROM UNNEST($3::text[], $4::text[], $5::text[], $6::text[], $7::int[], $8::int[], $9::text[], $10::text[], $11::text[])
+                AS t(id, problem_id, name, url, position, difficulty, judge, year, contest)
+           ON CONFLICT (category_id, problem_id) DO NOTHING"#
+    )
+    .bind(&actual_cat_id)
+    .bind(now)
+    .bind(&row_ids).bind(&problem_ids).bind(&names).bind(&urls)
+    .bind(&positions).bind(&difficulties).bind(&judges).bind(&years).bind(&contests)
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| db_context("insert cf_category_problems", e))?;
+
+    sqlx::query("UPDATE cf_categories SET problem_count = (SELECT COUNT(*) FROM cf_category_problems WHERE category_id = $1) WHERE id = $1")
         .bind(&actual_cat_id)
-        .bind(&problem.problem_id)
-        .bind(&problem.name)
-        .bind(&problem.url)
-        .bind(problem.position)
-        .bind(problem.difficulty)
-        .bind(&problem.judge)
-        .bind(&problem.year)
-        .bind(&problem.contest)
-        .bind(now)
-        .execute(&db.0)
+        .execute(&mut *tx)
         .await
-        .map_err(|e| db_context("insert cf_category_problem", e))?;
-    }
+        .map_err(|e| db_context("update category count", e))?;
+
+    tx.commit().await.map_err(|e| db_context("commit category import", e))?;
     
     let category = sqlx::query_as::<sqlx::Postgres, CFCategoryRow>(
         "SELECT id, name, description, problem_count, created_at FROM cf_categories WHERE id = $1"
@@ -207,13 +223,17 @@ pub async fn get_category_problems(
             p.online_judge,
             p.created_at,
             array_remove(array_agg(DISTINCT COALESCE(f.display_name, f.cf_handle)), NULL) as solved_by_friends,
-            (
-                SELECT s.verdict 
-                FROM pos_submissions s 
-                WHERE s.problem_id = ('cf-' || p.problem_id) 
-                AND s.platform = 'codeforces'
-                ORDER BY s.submitted_time DESC
-                LIMIT 1
+            COALESCE(
+                (SELECT 'OK' FROM known_solved k WHERE k.problem_id = ('cf-' || p.problem_id)),
+                (
+                    SELECT s.verdict 
+                    FROM pos_submissions s 
+                    WHERE s.problem_id = ('cf-' || p.problem_id) 
+                    AND s.platform = 'codeforces'
+                    AND s.excluded_at IS NULL
+                    ORDER BY s.submitted_time DESC
+                    LIMIT 1
+                )
             ) as status
         FROM cf_category_problems p
         LEFT JOIN cf_friend_submissions fs ON p.problem_url = fs.problem_url
@@ -222,8 +242,9 @@ pub async fn get_category_problems(
         GROUP BY p.id
         ORDER BY 
             CASE 
-                WHEN (SELECT s.verdict FROM pos_submissions s WHERE s.problem_id = ('cf-' || p.problem_id) AND s.platform = 'codeforces' ORDER BY s.submitted_time DESC LIMIT 1) = 'OK' THEN 1
-                WHEN (SELECT s.verdict FROM pos_submissions s WHERE s.problem_id = ('cf-' || p.problem_id) AND s.platform = 'codeforces' ORDER BY s.submitted_time DESC LIMIT 1) IS NOT NULL THEN 2
+                WHEN EXISTS (SELECT 1 FROM known_solved k WHERE k.problem_id = ('cf-' || p.problem_id)) THEN 1
+                WHEN (SELECT s.verdict FROM pos_submissions s WHERE s.problem_id = ('cf-' || p.problem_id) AND s.platform = 'codeforces' AND s.excluded_at IS NULL ORDER BY s.submitted_time DESC LIMIT 1) = 'OK' THEN 1
+                WHEN (SELECT s.verdict FROM pos_submissions s WHERE s.problem_id = ('cf-' || p.problem_id) AND s.platform = 'codeforces' AND s.excluded_at IS NULL ORDER BY s.submitted_time DESC LIMIT 1) IS NOT NULL THEN 2
                 ELSE 3
             END,
             p.position
@@ -0,0 +1,248 @@
+// Custom Ladders
+// Builds a ladder from a plain list of problem URLs (curated lists from blogs, not A2OJ
+// dumps). Each URL is resolved to its ladder-style problem_id, name and difficulty:
+// Codeforces from the problemset cache (refreshed once on a miss), AtCoder from AtCoder
+// Problems' resources, LeetCode from its GraphQL API.
+
+use std::collections::{HashMap, HashSet};
+
+use serde::Deserialize;
+use sqlx::PgPool;
+use tauri::State;
+
+use crate::PosDb;
+use crate::pos::error::{PosError, PosResult, db_context};
+use crate::pos::problem_url::{canonical_problem_url, problem_id_from_url};
+use crate::pos::retry::{with_backoff, BackoffPolicy};
+use crate::pos::scrapers::{atcoder, build_http_client, LEETCODE_HOST};
+use crate::pos::utils::gen_id;
+use super::cf_ladder_types::*;
+
+const MAX_URLS: usize = 500;
+/// Rough Codeforces-equivalent ratings so mixed ladders still sort by difficulty
+const LEETCODE_RATINGS: [(&str, i32); 3] = [("Easy", 1200), ("Medium", 1600), ("Hard", 2100)];
+
+// ─── Resolution ─────────────────────────────────────────────────────
+
+struct ResolvedProblem {
+    judge: &'static str,
+    problem_id: String,
+    name: String,
+    url: String,
+    difficulty: Option<i32>,
+}
+
+#[derive(Deserialize)]
+struct AtCoderProblem {
+    id: String,
+    #[serde(default)]
+    problem_index: String,
+    #[serde(default)]
+    name: String,
+}
+
+#[derive(Deserialize)]
+struct AtCoderProblemModel {
+    difficulty: Option<f64>,
+}
+
+/// Codeforces (name, rating) for bare ids, refreshing the problemset once if any are missing
+async fn codeforces_lookup(pool: &PgPool, ids: &[String]) -> PosResult<HashMap<String, (String, Option<i32>)>> {
+    let query = || async {
+        sqlx::query_as::<_, (String, String, Option<i32>)>(
+            "SELECT problem_id, name, rating FROM problemset_cache WHERE judge = 'codeforces' AND problem_id = ANY($1)"
+        )
+        .bind(ids)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| db_context("custom ladder problemset lookup", e))
+    };
+    let mut rows = query().await?;
+    if rows.len() < ids.len() {
+        match crate::problemset::refresh_problemset_cache(pool).await {
+            Ok(_) => rows = query().await?,
+            Err(e) => log::warn!("[CF] Problemset refresh for custom ladder failed: {}", e),
+        }
+    }
+    Ok(rows.into_iter().map(|(id, name, rating)| (id, (name, rating))).collect())
+}
+
+/// AtCoder task id → ("A. Title", difficulty)
+async fn atcoder_lookup(client: &reqwest::Client) -> PosResult<HashMap<String, (String, Option<i32>)>> {
+    let problems: Vec<AtCoderProblem> =
+        atcoder::fetch_json(client, &format!("{}/resources/problems.json", atcoder::API_BASE)).await?;
+    // Difficulty is optional metadata; a failure here only leaves it empty
+    let models: HashMap<String, AtCoderProblemModel> =
+        atcoder::fetch_json(client, &format!("{}/resources/problem-models.json", atcoder::API_BASE))
+            .await
+            .unwrap_or_else(|e| {
+                log::warn!("[CF] AtCoder difficulty models unavailable: {}", e);
+                HashMap::new()
+            });
+    Ok(problems.into_iter()
+        .map(|p| {
+            let name = if p.name.is_empty() { p.id.clone() } else { format!("{}. {}", p.problem_index, p.name) };
+            // Same scale as AtCoder ratings; negative for trivial tasks
+            let difficulty = models.get(&p.id)
+                .and_then(|m| m.difficulty)
+                .map(|d| d.max(0.0).round() as i32);
+            (p.id, (name, difficulty))
+        })
+        .collect())
+}
+
+/// LeetCode (title, difficulty label) for one slug; None when the problem doesn't exist
+async fn leetcode_lookup(client: &reqwest::Client, slug: &str) -> PosResult<Option<(String, String)>> {
+    let body = serde_json::json!({
+        "query": "query questionTitle($titleSlug: String!) { question(titleSlug: $titleSlug) { title difficulty } }",
+        "variables": { "titleSlug": slug }
+    });
+    let data: serde_json::Value = with_backoff(LEETCODE_HOST, BackoffPolicy::default(), || async {
+        Ok(client
+            .post("https://leetcode.com/graphql")
+            .header("Content-Type", "application/json")
+            .header("Referer", "https://leetcode.com")
+            .json(&body)
+            .send()
+            .await?
+            .json::<serde_json::Value>()
+            .await?)
+    }).await?;
+    let question = &data["data"]["question"];
+    Ok(question["title"].as_str().map(|title| {
+        (title.to_string(), question["difficulty"].as_str().unwrap_or("").to_string())
+    }))
+}
+
+// ─── Command ────────────────────────────────────────────────────────
+
+/// Create a Custom ladder from problem URLs in the given order. URLs that can't be
+/// resolved (unsupported site, unknown problem) or repeat an earlier one are skipped and
+/// reported; the ladder is only created if at least one problem resolves.
+#[tauri::command]
+pub async fn create_custom_ladder(
+    db: State<'_, PosDb>,
+    req: CreateCustomLadderRequest,
+) -> PosResult<CreateCustomLadderResponse> {
+    let pool = &db.0;
+    let name = req.name.trim().to_string();
+    if name.is_empty() {
+        return Err(PosError::InvalidInput("Ladder name cannot be empty".into()));
+    }
+    if req.urls.len() > MAX_URLS {
+        return Err(PosError::InvalidInput(format!("At most {} problems per ladder", MAX_URLS)));
+    }
+    let name_taken: bool = sqlx::query_scalar(
+        "SELECT EXISTS(SELECT 1 FROM cf_ladders WHERE name = $1 AND source = 'Custom')"
+    )
+    .bind(&name)
+    .fetch_one(pool)
+    .await
+    .map_err(|e| db_context("check custom ladder name", e))?;
+    if name_taken {
+        return Err(PosError::InvalidInput(format!("A custom ladder named '{}' already exists", name)));
+    }
+
+    // Submission-style ids tell the judge; keep input order and drop repeats
+    let mut errors = Vec::new();
+    let mut seen = HashSet::new();
+    let mut parsed: Vec<(String, String)> = Vec::new();
+    for raw in req.urls.iter().map(|u| u.trim()).filter(|u| !u.is_empty()) {
+        let url = canonical_problem_url(raw);
+        match problem_id_from_url(&url) {
+            Some(id) if !seen.insert(id.clone()) => errors.push(format!("{}: Duplicate problem", raw)),
+            Some(id) => parsed.push((url, id)),
+            None => errors.push(format!("{}: Not a Codeforces, AtCoder or LeetCode problem URL", raw)),
+        }
+    }
+
+    let client = build_http_client();
+    let cf_ids: Vec<String> = parsed.iter()
+        .filter_map(|(_, id)| id.strip_prefix("cf-").map(String::from))
+        .collect();
+    let codeforces = if cf_ids.is_empty() { HashMap::new() } else { codeforces_lookup(pool, &cf_ids).await? };
+    let atcoder_problems = if parsed.iter().any(|(_, id)| id.starts_with("atcoder-")) {
+        atcoder_lookup(&client).await?
+    } else {
+        HashMap::new()
+    };
+
+    let mut resolved = Vec::with_capacity(parsed.len());
+    for (url, id) in parsed {
+        let problem = if let Some(bare) = id.strip_prefix("cf-") {
+            codeforces.get(bare).map(|(name, rating)| ResolvedProblem {
+                judge: "Codeforces", problem_id: bare.to_string(), name: name.clone(), url: url.clone(), difficulty: *rating,
+            })
+        } else if let Some(task) = id.strip_prefix("atcoder-") {
+            atcoder_problems.get(task).map(|(name, difficulty)| ResolvedProblem {
+                judge: "AtCoder", problem_id: task.to_string(), name: name.clone(), url: url.clone(), difficulty: *difficulty,
+            })
+        } else if let Some(slug) = id.strip_prefix("leetcode-") {
+            leetcode_lookup(&client, slug).await?.map(|(title, level)| ResolvedProblem {
+                judge: "LeetCode",
+                problem_id: slug.to_string(),
+                name: title,
+                url: url.clone(),
+                difficulty: LEETCODE_RATINGS.iter().find(|(l, _)| *l == level).map(|(_, r)| *r),
+            })
+        } else {
+            None
+        };
+        match problem {
+            Some(p) => resolved.push(p),
+            None => errors.push(format!("{}: Problem not found", url)),
+        }
+    }
+    if resolved.is_empty() {
+        return Err(PosError::InvalidInput(format!(
+            "None of the URLs resolved to a problem: {}", errors.join("; ")
+        )));
+    }
+
+    let difficulties: Vec<i32> = resolved.iter().filter_map(|p| p.difficulty).collect();
+    let ladder_id = gen_id();
+    let mut tx = pool.begin().await.map_err(|e| db_context("TX begin", e))?;
+
+    let ladder = sqlx::query_as::<_, CFLadderRow>(
+        r#"INSERT INTO cf_ladders (id, name, description, rating_min, rating_max, difficulty, source, problem_count, created_at)
+           VALUES ($1, $2, $3, $4, $5, NULL, 'Custom', $6, NOW())
+           RETURNING id, name, description, rating_min, rating_max, difficulty, source, problem_count, created_at"#
+    )
+    .bind(&ladder_id)
+    .bind(&name)
+    .bind(&req.description)
+    .bind(difficulties.iter().min().copied())
+    .bind(difficulties.iter().max().copied())
+    .bind(resolved.len() as i32)
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|e| db_context("insert custom ladder", e))?;
+
+    for (position, p) in resolved.iter().enumerate() {
+        sqlx::query(
+            r#"INSERT INTO cf_ladder_problems
+               (id, ladder_id, problem_id, problem_name, problem_url, position, difficulty, online_judge, created_at)
+               VALUES ($1, $2, $3, $4, $5, $6, $7, $8, NOW())"#
+        )
+        .bind(gen_id())
+        .bind(&ladder_id)
+        .bind(&p.problem_id)
+        .bind(&p.name)
+        .bind(&p.url)
+        .bind(position as i32 + 1)
+        .bind(p.difficulty)
+        .bind(p.judge)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| db_context("insert custom ladder problem", e))?;
+    }
+
+    tx.commit().await.map_err(|e| db_context("TX commit", e))?;
+
+    log::info!("[CF] Created custom ladder '{}' with {} problems ({} skipped)", name, resolved.len(), errors.len());
+    Ok(CreateCustomLadderResponse {
+        ladder,
+        added_count: resolved.len() as i32,
+        errors,
+    })
+}
@@ -0,0 +1,210 @@
+// CF Custom Recommendation Strategies
+// User-registered SQL run by get_daily_recommendations(strategy = "custom:<name>").
+// Queries are validated on save and always executed in a read-only, time-limited transaction.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::PgPool;
+use tauri::State;
+
+use crate::PosDb;
+use crate::pos::error::{PosError, PosResult, db_context};
+use super::DailyRecommendation;
+
+pub const CUSTOM_STRATEGY_PREFIX: &str = "custom:";
+const STATEMENT_TIMEOUT_MS: u32 = 2000;
+const MAX_SQL_LEN: usize = 4000;
+
+/// Keywords that can write, change session state or escape the sandbox.
+/// The read-only transaction is the real guard; this just rejects obvious mistakes early.
+const FORBIDDEN_KEYWORDS: &[&str] = &[
+    "insert", "update", "delete", "merge", "upsert", "drop", "alter", "create", "truncate",
+    "grant", "revoke", "copy", "call", "do", "execute", "prepare", "deallocate", "lock",
+    "vacuum", "analyze", "cluster", "reindex", "comment", "set", "reset", "listen", "notify",
+    "unlisten", "refresh", "into", "begin", "commit", "rollback", "savepoint", "dblink",
+    "set_config", "nextval", "setval",
+];
+
+// ─── Types ──────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct CustomStrategyRow {
+    pub name: String,
+    pub sql_text: String,
+    pub description: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+// ─── Validation ─────────────────────────────────────────────────────
+
+fn validate_name(name: &str) -> PosResult<()> {
+    let ok = !name.is_empty()
+        && name.len() <= 64
+        && name.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_' || c == '-');
+    if !ok {
+        return Err(PosError::InvalidInput(
+            "Strategy name must be 1-64 chars of a-z, 0-9, '_' or '-'".into(),
+        ));
+    }
+    Ok(())
+}
+
+/// Static checks: a single SELECT/WITH statement, no comments, bound only on `$1` (count).
+fn validate_sql(sql: &str) -> PosResult<()> {
+    let lower = sql.trim().to_lowercase();
+    if lower.is_empty() || lower.len() > MAX_SQL_LEN {
+        return Err(PosError::InvalidInput(format!("SQL must be 1-{} characters", MAX_SQL_LEN)));
+    }
+    if !(lower.starts_with("select") || lower.starts_with("with")) {
+        return Err(PosError::InvalidInput("SQL must start with SELECT or WITH".into()));
+    }
+    if lower.contains(';') || lower.contains("--") || lower.contains("/*") {
+        return Err(PosError::InvalidInput("SQL must be a single statement without comments".into()));
+    }
+    if !lower.contains("$1") {
+        return Err(PosError::InvalidInput("SQL must use $1 as the result count (e.g. LIMIT $1)".into()));
+    }
+    if lower.contains("$2") {
+        return Err(PosError::InvalidInput("Only $1 (count) may be bound".into()));
+    }
+
+    for word in lower.split(|c: char| !(c.is_ascii_alphanumeric() || c == '_')) {
+        if FORBIDDEN_KEYWORDS.contains(&word) || word.starts_with("pg_") || word.starts_with("lo_") {
+            return Err(PosError::InvalidInput(format!("Keyword '{}' is not allowed", word)));
+        }
+    }
+    Ok(())
+}
+
+// ─── Execution ──────────────────────────────────────────────────────
+
+/// Run stored SQL in a READ ONLY transaction with a statement timeout. The query must return
+/// (problem_id, problem_name, problem_url, online_judge, difficulty) in that order.
+async fn execute_sandboxed(
+    pool: &PgPool,
+    sql: &str,
+    count: i32,
+) -> PosResult<Vec<(String, String, String, String, Option<i32>)>> {
+    let mut tx = pool.begin().await.map_err(|e| db_context("custom strategy TX begin", e))?;
+
+    sqlx::query("SET TRANSACTION READ ONLY")
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| db_context("custom strategy read-only", e))?;
+    sqlx::query(&format!("SET LOCAL statement_timeout = {}", STATEMENT_TIMEOUT_MS))
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| db_context("custom strategy timeout", e))?;
+
+    let rows = sqlx::query_as::<_, (String, String, String, String, Option<i32>)>(sql)
+        .bind(count)
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(|e| PosError::InvalidInput(format!("Custom strategy query failed: {}", e)))?;
+
+    // Nothing to keep — always roll back
+    tx.rollback().await.map_err(|e| db_context("custom strategy TX rollback", e))?;
+    Ok(rows)
+}
+
+/// Resolve a "custom:<name>" strategy and produce recommendations from its stored SQL
+pub(crate) async fn run_custom_strategy(
+    pool: &PgPool,
+    strategy: &str,
+    count: i32,
+) -> PosResult<Vec<DailyRecommendation>> {
+    let name = strategy.trim_start_matches(CUSTOM_STRATEGY_PREFIX);
+
+    let sql: String = sqlx::query_scalar("SELECT sql_text FROM cf_custom_strategies WHERE name = $1")
+        .bind(name)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| db_context("fetch custom strategy", e))?
+        .ok_or_else(|| PosError::NotFound(format!("Custom strategy not found: {}", name)))?;
+
+    // Re-check in case the row was edited outside the app
+    validate_sql(&sql)?;
+
+    let rows = execute_sandboxed(pool, &sql, count).await?;
+    Ok(rows
+        .into_iter()
+        .take(count.max(0) as usize)
+        .map(|(problem_id, problem_name, problem_url, online_judge, difficulty)| DailyRecommendation {
+            problem_id, problem_name, problem_url, online_judge, difficulty,
+            reason: format!("Custom rule: {}", name),
+            strategy: strategy.to_string(),
+        })
+        .collect())
+}
+
+// ─── Commands ───────────────────────────────────────────────────────
+
+/// Create or replace a custom strategy. The SQL is validated and dry-run (count = 1)
+/// so shape/syntax errors surface here rather than on the dashboard.
+#[tauri::command]
+pub async fn register_custom_strategy(
+    db: State<'_, PosDb>,
+    name: String,
+    sql: String,
+    description: Option<String>,
+) -> PosResult<CustomStrategyRow> {
+    upsert_custom_strategy(&db.0, name, sql, description).await
+}
+
+/// Validate, dry-run and store a strategy (shared with settings import)
+pub(crate) async fn upsert_custom_strategy(
+    pool: &PgPool,
+    name: String,
+    sql: String,
+    description: Option<String>,
+) -> PosResult<CustomStrategyRow> {
+    let name = name.trim().to_lowercase();
+    let sql = sql.trim().to_string();
+
+    validate_name(&name)?;
+    validate_sql(&sql)?;
+    execute_sandboxed(pool, &sql, 1).await?;
+
+    let row = sqlx::query_as::<_, CustomStrategyRow>(
+        r#"INSERT INTO cf_custom_strategies (name, sql_text, description, created_at, updated_at)
+           VALUES ($1, $2, $3, NOW(), NOW())
+           ON CONFLICT (name) DO UPDATE
+           SET sql_text = EXCLUDED.sql_text, description = EXCLUDED.description, updated_at = NOW()
+           RETURNING name, sql_text, description, created_at, updated_at"#
+    )
+    .bind(&name)
+    .bind(&sql)
+    .bind(description)
+    .fetch_one(pool)
+    .await
+    .map_err(|e| db_context("upsert cf_custom_strategies", e))?;
+
+    log::info!("[CF CUSTOM] Registered strategy {}{}", CUSTOM_STRATEGY_PREFIX, name);
+    Ok(row)
+}
+
+#[tauri::command]
+pub async fn get_custom_strategies(db: State<'_, PosDb>) -> PosResult<Vec<CustomStrategyRow>> {
+    sqlx::query_as::<_, CustomStrategyRow>(
+        "SELECT name, sql_text, description, created_at, updated_at FROM cf_custom_strategies ORDER BY name"
+    )
+    .fetch_all(&db.0)
+    .await
+    .map_err(|e| db_context("get_custom_strategies", e))
+}
+
+#[tauri::command]
+pub async fn delete_custom_strategy(db: State<'_, PosDb>, name: String) -> PosResult<()> {
+    let result = sqlx::query("DELETE FROM cf_custom_strategies WHERE name = $1")
+        .bind(&name)
+        .execute(&db.0)
+        .await
+        .map_err(|e| db_context("delete_custom_strategy", e))?;
+
+    if result.rows_affected() == 0 {
+        return Err(PosError::NotFound(format!("Custom strategy not found: {}", name)));
+    }
+    Ok(())
+}
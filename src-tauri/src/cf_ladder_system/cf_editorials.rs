@@ -0,0 +1,125 @@
+// CF Editorial Links
+// Looks up tutorial/editorial blog entries for ladder problems and caches them per problem.
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use scraper::{Html, Selector};
+use serde::Serialize;
+use tauri::State;
+
+use crate::PosDb;
+use crate::pos::error::{PosError, PosResult, db_context};
+use crate::pos::retry::{with_backoff, BackoffPolicy};
+use crate::pos::scrapers::{build_http_client, CODEFORCES_HOST};
+
+// ─── Types ──────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct ProblemEditorialRow {
+    pub problem_id: String,
+    pub contest_id: i32,
+    pub editorial_url: Option<String>,
+    pub fetched_at: DateTime<Utc>,
+}
+
+// ─── Helpers ────────────────────────────────────────────────────────
+
+/// "1520A" / "1354C1" → 1520 / 1354. Non-CF ids (SPOJ, UVA…) return None.
+fn contest_id_of(problem_id: &str) -> Option<i32> {
+    let digits: String = problem_id.chars().take_while(|c| c.is_ascii_digit()).collect();
+    if digits.is_empty() || digits.len() == problem_id.len() {
+        return None;
+    }
+    digits.parse().ok()
+}
+
+/// The CF REST API has no editorial endpoint, so read the contest page's
+/// "Contest materials" sidebar and pick the first Tutorial/Editorial blog link.
+async fn fetch_contest_editorial(client: &reqwest::Client, contest_id: i32) -> PosResult<Option<String>> {
+    let url = format!("https://codeforces.com/contest/{}", contest_id);
+    let html = with_backoff(CODEFORCES_HOST, BackoffPolicy::default(), || async {
+        let resp = client.get(&url).send().await?;
+        if resp.status() == reqwest::StatusCode::NOT_FOUND {
+            return Err(PosError::NotFound(format!("Codeforces contest {}", contest_id)));
+        }
+        if !resp.status().is_success() {
+            return Err(PosError::External(format!("HTTP error: {}", resp.status())));
+        }
+        Ok(resp.text().await?)
+    }).await?;
+
+    let document = Html::parse_document(&html);
+    let link_sel = Selector::parse(".sidebox a[href*='/blog/entry/']")
+        .map_err(|_| PosError::InvalidInput("Invalid selector".into()))?;
+
+    let editorial = document.select(&link_sel).find_map(|a| {
+        let text = a.text().collect::<String>().to_lowercase();
+        let title = a.value().attr("title").unwrap_or("").to_lowercase();
+        let is_editorial = ["tutorial", "editorial", "разбор"]
+            .iter()
+            .any(|k| text.contains(k) || title.contains(k));
+        if !is_editorial {
+            return None;
+        }
+        a.value().attr("href").map(|href| {
+            if href.starts_with("http") { href.to_string() } else { format!("https://codeforces.com{}", href) }
+        })
+    });
+
+    Ok(editorial)
+}
+
+// ─── Commands ───────────────────────────────────────────────────────
+
+/// Fetch and store editorial links for the given ladder problem ids (e.g. "1520A").
+/// One request per distinct contest; throttled or failing pages are retried with backoff.
+#[tauri::command]
+pub async fn fetch_problem_editorial_links(
+    db: State<'_, PosDb>,
+    problem_ids: Vec<String>,
+) -> PosResult<Vec<ProblemEditorialRow>> {
+    let pool = &db.0;
+    let client = build_http_client();
+
+    let mut by_contest: HashMap<i32, Vec<String>> = HashMap::new();
+    for pid in problem_ids {
+        match contest_id_of(&pid) {
+            Some(cid) => by_contest.entry(cid).or_default().push(pid),
+            None => log::warn!("[CF EDITORIAL] Skipping non-Codeforces problem id {}", pid),
+        }
+    }
+
+    let mut rows = Vec::new();
+    for (contest_id, pids) in by_contest {
+        let editorial_url = match fetch_contest_editorial(&client, contest_id).await {
+            Ok(url) => url,
+            Err(e) => {
+                log::warn!("[CF EDITORIAL] Contest {} lookup failed: {}", contest_id, e);
+                continue;
+            }
+        };
+
+        for pid in pids {
+            let row = sqlx::query_as::<_, ProblemEditorialRow>(
+                r#"INSERT INTO cf_problem_editorials (problem_id, contest_id, editorial_url, fetched_at)
+                   VALUES ($1, $2, $3, NOW())
+                   ON CONFLICT (problem_id) DO UPDATE
+                   SET editorial_url = EXCLUDED.editorial_url, fetched_at = EXCLUDED.fetched_at
+                   RETURNING problem_id, contest_id, editorial_url, fetched_at"#
+            )
+            .bind(&pid)
+            .bind(contest_id)
+            .bind(&editorial_url)
+            .fetch_one(pool)
+            .await
+            .map_err(|e| db_context("upsert cf_problem_editorials", e))?;
+            rows.push(row);
+        }
+    }
+
+    log::info!("[CF EDITORIAL] Stored {} editorial lookups ({} with links)",
+        rows.len(), rows.iter().filter(|r| r.editorial_url.is_some()).count());
+    Ok(rows)
+}
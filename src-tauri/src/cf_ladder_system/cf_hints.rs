@@ -0,0 +1,189 @@
+// CF Problem Hint Ladder
+// Progressive hint reveals per problem, tracking how many were consumed before the first AC.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use tauri::State;
+
+use crate::PosDb;
+use crate::pos::error::{PosError, PosResult, db_context};
+use crate::pos::utils::gen_id;
+
+// ─── Types ──────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct ProblemHintRow {
+    pub id: String,
+    pub problem_id: String,
+    pub position: i32,
+    pub hint_text: String,
+    pub revealed_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RevealHintResponse {
+    pub hint: Option<ProblemHintRow>, // None when every hint is already revealed
+    pub revealed_count: i32,
+    pub total_hints: i32,
+}
+
+#[derive(Debug, Serialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct HintUsageRow {
+    pub problem_id: String,
+    pub total_hints: i64,
+    pub revealed_count: i64,
+    pub hints_before_solve: i64,
+    pub solved_at: Option<DateTime<Utc>>,
+}
+
+const HINT_COLS: &str = "id, problem_id, position, hint_text, revealed_at, created_at";
+
+// ─── Commands ───────────────────────────────────────────────────────
+
+/// Append hints to a problem's hint ladder (positions continue after existing hints)
+#[tauri::command]
+pub async fn add_problem_hints(
+    db: State<'_, PosDb>,
+    problem_id: String,
+    hints: Vec<String>,
+) -> PosResult<Vec<ProblemHintRow>> {
+    let pool = &db.0;
+    let hints: Vec<String> = hints.into_iter()
+        .map(|h| h.trim().to_string())
+        .filter(|h| !h.is_empty())
+        .collect();
+    if hints.is_empty() {
+        return Err(PosError::InvalidInput("At least one non-empty hint is required".into()));
+    }
+
+    let mut tx = pool.begin().await.map_err(|e| db_context("TX begin", e))?;
+
+    let max_position: i32 = sqlx::query_scalar(
+        "SELECT COALESCE(MAX(position), 0) FROM problem_hints WHERE problem_id = $1"
+    )
+    .bind(&problem_id)
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|e| db_context("max hint position", e))?;
+
+    let mut rows = Vec::with_capacity(hints.len());
+    for (i, text) in hints.iter().enumerate() {
+        let row = sqlx::query_as::<_, ProblemHintRow>(&format!(
+            "INSERT INTO problem_hints (id, problem_id, position, hint_text, created_at)
+             VALUES ($1, $2, $3, $4, NOW())
+             RETURNING {HINT_COLS}"
+        ))
+        .bind(gen_id())
+        .bind(&problem_id)
+        .bind(max_position + i as i32 + 1)
+        .bind(text)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| db_context("insert problem_hint", e))?;
+        rows.push(row);
+    }
+
+    tx.commit().await.map_err(|e| db_context("TX commit", e))?;
+
+    log::info!("[CF HINTS] Added {} hints to {}", rows.len(), problem_id);
+    Ok(rows)
+}
+
+/// Reveal the next unrevealed hint for a problem and record when it was consumed
+#[tauri::command]
+pub async fn reveal_next_hint(
+    db: State<'_, PosDb>,
+    problem_id: String,
+) -> PosResult<RevealHintResponse> {
+    let pool = &db.0;
+
+    let hint = sqlx::query_as::<_, ProblemHintRow>(&format!(
+        "UPDATE problem_hints SET revealed_at = NOW()
+         WHERE id = (
+             SELECT id FROM problem_hints
+             WHERE problem_id = $1 AND revealed_at IS NULL
+             ORDER BY position ASC LIMIT 1
+         )
+         RETURNING {HINT_COLS}"
+    ))
+    .bind(&problem_id)
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| db_context("reveal_next_hint", e))?;
+
+    let (revealed_count, total_hints): (i64, i64) = sqlx::query_as(
+        "SELECT COUNT(revealed_at), COUNT(*) FROM problem_hints WHERE problem_id = $1"
+    )
+    .bind(&problem_id)
+    .fetch_one(pool)
+    .await
+    .map_err(|e| db_context("count hints", e))?;
+
+    if total_hints == 0 {
+        return Err(PosError::NotFound(format!("No hints for problem: {}", problem_id)));
+    }
+
+    Ok(RevealHintResponse {
+        hint,
+        revealed_count: revealed_count as i32,
+        total_hints: total_hints as i32,
+    })
+}
+
+/// Get revealed hints for a problem (unrevealed hint text is never returned)
+#[tauri::command]
+pub async fn get_revealed_hints(
+    db: State<'_, PosDb>,
+    problem_id: String,
+) -> PosResult<Vec<ProblemHintRow>> {
+    sqlx::query_as::<_, ProblemHintRow>(&format!(
+        "SELECT {HINT_COLS} FROM problem_hints
+         WHERE problem_id = $1 AND revealed_at IS NOT NULL
+         ORDER BY position ASC"
+    ))
+    .bind(&problem_id)
+    .fetch_all(&db.0)
+    .await
+    .map_err(|e| db_context("get_revealed_hints", e))
+}
+
+/// Hint consumption per problem, including how many were revealed before the first AC.
+/// Matches both raw ladder ids ("1520A") and submission ids ("cf-1520A").
+#[tauri::command]
+pub async fn get_hint_usage(
+    db: State<'_, PosDb>,
+    problem_ids: Option<Vec<String>>,
+) -> PosResult<Vec<HintUsageRow>> {
+    sqlx::query_as::<_, HintUsageRow>(
+        r#"WITH solves AS (
+               SELECT h.problem_id, MIN(s.submitted_time) AS solved_at
+               FROM (SELECT DISTINCT problem_id FROM problem_hints) h
+               JOIN pos_submissions s
+                 ON (s.problem_id = h.problem_id OR s.problem_id = 'cf-' || h.problem_id)
+                AND s.verdict IN ('OK', 'Accepted')
+                AND s.excluded_at IS NULL
+               GROUP BY h.problem_id
+           )
+           SELECT h.problem_id,
+                  COUNT(*)::bigint AS total_hints,
+                  COUNT(h.revealed_at)::bigint AS revealed_count,
+                  COUNT(*) FILTER (
+                      WHERE h.revealed_at IS NOT NULL
+                        AND (sv.solved_at IS NULL OR h.revealed_at <= sv.solved_at)
+                  )::bigint AS hints_before_solve,
+                  sv.solved_at
+           FROM problem_hints h
+           LEFT JOIN solves sv ON sv.problem_id = h.problem_id
+           WHERE $1::text[] IS NULL OR h.problem_id = ANY($1)
+           GROUP BY h.problem_id, sv.solved_at
+           ORDER BY h.problem_id"#
+    )
+    .bind(problem_ids)
+    .fetch_all(&db.0)
+    .await
+    .map_err(|e| db_context("get_hint_usage", e))
+}
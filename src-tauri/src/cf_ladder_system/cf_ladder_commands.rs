@@ -5,6 +5,7 @@ use chrono::Utc;
 use tauri::State;
 
 use crate::PosDb;
+use crate::pos::confirm::{consume_confirmation, request_confirmation, Confirmable, ImpactItem, ImpactReport};
 use crate::pos::error::{PosError, PosResult, db_context};
 use crate::pos::utils::gen_id;
 use super::cf_ladder_types::*;
@@ -20,29 +21,30 @@ pub async fn import_ladder_from_html(
     let parsed = parse_ladder_html(&req.html_content)?;
     
     let now = Utc::now();
+    // One transaction: a failed import leaves no half-filled ladder behind
+    let mut tx = db.0.begin().await.map_err(|e| db_context("begin ladder import", e))?;
 
     // Check if ladder already exists
     let existing_ladder = sqlx::query_scalar::<sqlx::Postgres, String>(
-        "SELECT id FROM cf_ladders WHERE name = $1 AND source = $2"
+        "SELECT id FROM cf_ladders WHERE name = $1 AND source = $2 FOR UPDATE"
     )
     .bind(&parsed.title)
     .bind(&req.source)
-    .fetch_optional(&db.0)
+    .fetch_optional(&mut *tx)
     .await
     .map_err(|e| db_context("check existing ladder", e))?;
 
     let ladder_id = if let Some(id) = existing_ladder {
         // Update existing ladder with ALL metadata
         sqlx::query(
-            "UPDATE cf_ladders SET description = $1, rating_min = $2, rating_max = $3, difficulty = $4, problem_count = $5 WHERE id = $6"
+            "UPDATE cf_ladders SET description = $1, rating_min = $2, rating_max = $3, difficulty = $4 WHERE id = $5"
         )
         .bind(&parsed.description)
         .bind(parsed.rating_min)
         .bind(parsed.rating_max)
         .bind(parsed.ladder_difficulty)
-        .bind(parsed.problems.len() as i32)
         .bind(&id)
-        .execute(&db.0)
+        .execute(&mut *tx)
         .await
         .map_err(|e| db_context("update cf_ladder", e))?;
         id
@@ -51,7 +53,7 @@ pub async fn import_ladder_from_html(
         let new_id = gen_id();
         sqlx::query(
             "INSERT INTO cf_ladders (id, name, description, rating_min, rating_max, difficulty, source, problem_count, created_at)
-             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)"
+             VALUES ($1, $2, $3, $4, $5, $6, $7, 0, $8)"
         )
         .bind(&new_id)
         .bind(&parsed.title)
@@ -60,47 +62,54 @@ pub async fn import_ladder_from_html(
         .bind(parsed.rating_max)
         .bind(parsed.ladder_difficulty)
         .bind(&req.source)
-        .bind(parsed.problems.len() as i32)
         .bind(now)
-        .execute(&db.0)
+        .execute(&mut *tx)
         .await
         .map_err(|e| db_context("insert cf_ladder", e))?;
         new_id
     };
     
-    // Insert problems (preventing duplicates via manual check)
+    // Insert all problems in one statement; unique (ladder_id, problem_id) makes re-imports no-ops
+    let mut row_ids = Vec::with_capacity(parsed.problems.len());
+    let mut problem_ids = Vec::with_capacity(parsed.problems.len());
+    let mut names = Vec::with_capacity(parsed.problems.len());
+    let mut urls = Vec::with_capacity(parsed.problems.len());
+    let mut positions = Vec::with_capacity(parsed.problems.len());
+    let mut difficulties = Vec::with_capacity(parsed.problems.len());
+    let mut judges = Vec::with_capacity(parsed.problems.len());
     for problem in parsed.problems {
-        let problem_row_id = gen_id();
-        
-        let exists: bool = sqlx::query_scalar(
-            "SELECT EXISTS(SELECT 1 FROM cf_ladder_problems WHERE ladder_id = $1 AND problem_id = $2)"
-        )
+        row_ids.push(gen_id());
+        problem_ids.push(problem.problem_id);
+        names.push(problem.name);
+        urls.push(problem.url);
+        positions.push(problem.position);
+        difficulties.push(problem.difficulty);
+        judges.push(problem.judge);
+    }
+
+    sqlx::query(
+        r#"INSERT INTO cf_ladder_problems
+           (id, ladder_id, problem_id, problem_name, problem_url, position, difficulty, online_judge, created_at)
+           SELECT id, $1, problem_id, name, url, position, difficulty, judge, $2
+           FROM UNNEST($3::text[], $4::text[], $5::text[], $6::text[], $7::int[], $8::int[], $9::text[])
+                AS t(id, problem_id, name, url, position, difficulty, judge)
+           ON CONFLICT (ladder_id, problem_id) DO NOTHING"#
+    )
+    .bind(&ladder_id)
+    .bind(now)
+    .bind(&row_ids).bind(&problem_ids).bind(&names).bind(&urls)
+    .bind(&positions).bind(&difficulties).bind(&judges)
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| db_context("insert cf_ladder_problems", e))?;
+
+    sqlx::query("UPDATE cf_ladders SET problem_count = (SELECT COUNT(*) FROM cf_ladder_problems WHERE ladder_id = $1) WHERE id = $1")
         .bind(&ladder_id)
-        .bind(&problem.problem_id)
-        .fetch_one(&db.0)
+        .execute(&mut *tx)
         .await
-        .unwrap_or(false);
-
-        if !exists {
-             sqlx::query::<sqlx::Postgres>(
-                "INSERT INTO cf_ladder_problems 
-                 (id, ladder_id, problem_id, problem_name, problem_url, position, difficulty, online_judge, created_at)
-                 VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)"
-            )
-            .bind(&problem_row_id)
-            .bind(&ladder_id)
-            .bind(&problem.problem_id)
-            .bind(&problem.name)
-            .bind(&problem.url)
-            .bind(problem.position)
-            .bind(problem.difficulty)
-            .bind(&problem.judge)
-            .bind(now)
-            .execute(&db.0)
-            .await
-            .map_err(|e| db_context("insert cf_ladder_problem", e))?;
-        }
-    }
+        .map_err(|e| db_context("update ladder count", e))?;
+
+    tx.commit().await.map_err(|e| db_context("commit ladder import", e))?;
     
     let ladder = sqlx::query_as::<sqlx::Postgres, CFLadderRow>(
         "SELECT id, name, description, rating_min, rating_max, difficulty, source, problem_count, created_at FROM cf_ladders WHERE id = $1"
@@ -152,6 +161,58 @@ pub async fn get_ladder_by_id(
     Ok(ladder)
 }
 
+// ─── Delete Ladder ──────────────────────────────────────────────────
+
+/// Delete a ladder with its problems and progress (FK cascade). Two-step: the first call
+/// returns a confirmation token and impact report; repeat with the token to execute.
+#[tauri::command]
+pub async fn delete_ladder(
+    ladder_id: String,
+    confirmation_token: Option<String>,
+    db: State<'_, PosDb>,
+) -> PosResult<Confirmable<CFLadderRow>> {
+    let ladder = sqlx::query_as::<sqlx::Postgres, CFLadderRow>(
+        "SELECT id, name, description, rating_min, rating_max, difficulty, source, problem_count, created_at FROM cf_ladders WHERE id = $1"
+    )
+    .bind(&ladder_id)
+    .fetch_optional(&db.0)
+    .await
+    .map_err(|e| db_context("fetch cf_ladder for delete", e))?
+    .ok_or_else(|| PosError::NotFound(format!("Ladder not found: {}", ladder_id)))?;
+
+    let params = serde_json::json!({ "ladderId": ladder_id });
+    let Some(token) = confirmation_token else {
+        let (problems, progress): (i64, i64) = sqlx::query_as(
+            r#"SELECT (SELECT COUNT(*) FROM cf_ladder_problems WHERE ladder_id = $1),
+                      (SELECT COUNT(*) FROM cf_ladder_progress WHERE ladder_id = $1)"#
+        )
+        .bind(&ladder_id)
+        .fetch_one(&db.0)
+        .await
+        .map_err(|e| db_context("count ladder impact", e))?;
+
+        let impact = ImpactReport::new(
+            format!("Delete ladder '{}'", ladder.name),
+            vec![
+                ImpactItem { target: "cf_ladders".into(), rows: 1 },
+                ImpactItem { target: "cf_ladder_problems".into(), rows: problems },
+                ImpactItem { target: "cf_ladder_progress".into(), rows: progress },
+            ],
+        );
+        return request_confirmation(&db.0, "delete_ladder", params, impact).await;
+    };
+    consume_confirmation(&db.0, &token, "delete_ladder", &params).await?;
+
+    sqlx::query("DELETE FROM cf_ladders WHERE id = $1")
+        .bind(&ladder_id)
+        .execute(&db.0)
+        .await
+        .map_err(|e| db_context("delete cf_ladder", e))?;
+
+    log::info!("[CF LADDER] Deleted ladder {} ({})", ladder.id, ladder.name);
+    Ok(Confirmable::Executed { result: ladder })
+}
+
 // ─── Get Ladder Problems ────────────────────────────────────────────
 
 #[tauri::command]
@@ -174,23 +235,31 @@ pub async fn get_ladder_problems(
             p.online_judge,
             p.created_at,
             array_remove(array_agg(DISTINCT COALESCE(f.display_name, f.cf_handle)), NULL) as solved_by_friends,
-            (
-                SELECT s.verdict 
-                FROM pos_submissions s 
-                WHERE s.problem_id = ('cf-' || p.problem_id) 
-                AND s.platform = 'codeforces'
-                ORDER BY s.submitted_time DESC
-                LIMIT 1
-            ) as status
+            COALESCE(
+                (SELECT 'OK' FROM known_solved k WHERE k.problem_id = ('cf-' || p.problem_id)),
+                (
+                    SELECT s.verdict 
+                    FROM pos_submissions s 
+                    WHERE s.problem_id = ('cf-' || p.problem_id) 
+                    AND s.platform = 'codeforces'
+                    AND s.excluded_at IS NULL
+                    ORDER BY s.submitted_time DESC
+                    LIMIT 1
+                )
+            ) as status,
+            COALESCE(MAX(n.editorial_url), MAX(ed.editorial_url)) as editorial_url
         FROM cf_ladder_problems p
         LEFT JOIN cf_friend_submissions fs ON p.problem_url = fs.problem_url
         LEFT JOIN cf_friends f ON fs.friend_id = f.id
+        LEFT JOIN cf_problem_editorials ed ON ed.problem_id = p.problem_id
+        LEFT JOIN cf_problem_notes n ON n.problem_id = p.problem_id
         WHERE p.ladder_id = $1
         GROUP BY p.id
         ORDER BY 
             CASE 
-                WHEN (SELECT s.verdict FROM pos_submissions s WHERE s.problem_id = ('cf-' || p.problem_id) AND s.platform = 'codeforces' ORDER BY s.submitted_time DESC LIMIT 1) = 'OK' THEN 1
-                WHEN (SELECT s.verdict FROM pos_submissions s WHERE s.problem_id = ('cf-' || p.problem_id) AND s.platform = 'codeforces' ORDER BY s.submitted_time DESC LIMIT 1) IS NOT NULL THEN 2
+                WHEN EXISTS (SELECT 1 FROM known_solved k WHERE k.problem_id = ('cf-' || p.problem_id)) THEN 1
+                WHEN (SELECT s.verdict FROM pos_submissions s WHERE s.problem_id = ('cf-' || p.problem_id) AND s.platform = 'codeforces' AND s.excluded_at IS NULL ORDER BY s.submitted_time DESC LIMIT 1) = 'OK' THEN 1
+                WHEN (SELECT s.verdict FROM pos_submissions s WHERE s.problem_id = ('cf-' || p.problem_id) AND s.platform = 'codeforces' AND s.excluded_at IS NULL ORDER BY s.submitted_time DESC LIMIT 1) IS NOT NULL THEN 2
                 ELSE 3
             END,
             p.position
@@ -259,75 +328,85 @@ pub async fn track_ladder_progress(
 
 // ─── Get Ladder Stats ───────────────────────────────────────────────
 
+/// Per-ladder totals in one pass: submissions are pre-aggregated per problem,
+/// then counted with FILTER clauses instead of one EXISTS scan per metric.
+const LADDER_STATS_SQL: &str = r#"
+    SELECT
+        l.id AS ladder_id,
+        COUNT(p.id)::bigint AS total,
+        COUNT(DISTINCT p.problem_id) FILTER (WHERE s.solved)::bigint AS solved,
+        COUNT(DISTINCT p.problem_id) FILTER (WHERE s.problem_id IS NOT NULL)::bigint AS attempted
+    FROM cf_ladders l
+    LEFT JOIN cf_ladder_problems p ON p.ladder_id = l.id
+    LEFT JOIN (
+        SELECT problem_id, BOOL_OR(solved) AS solved
+        FROM (
+            SELECT problem_id, verdict = 'OK' AS solved FROM pos_submissions WHERE platform = 'codeforces' AND excluded_at IS NULL
+            UNION ALL
+            SELECT problem_id, TRUE FROM known_solved WHERE platform = 'codeforces'
+        ) x
+        GROUP BY problem_id
+    ) s ON s.problem_id = ('cf-' || p.problem_id)
+    WHERE $1::text IS NULL OR l.id = $1
+    GROUP BY l.id
+"#;
+
+#[derive(sqlx::FromRow)]
+struct LadderStatsRow {
+    ladder_id: String,
+    total: i64,
+    solved: i64,
+    attempted: i64,
+}
+
+impl From<LadderStatsRow> for LadderStats {
+    fn from(r: LadderStatsRow) -> Self {
+        let unsolved = (r.total - r.attempted).max(0);
+        let percentage = if r.total > 0 { (r.solved as f64 / r.total as f64) * 100.0 } else { 0.0 };
+        LadderStats {
+            total_problems: r.total as i32,
+            solved: r.solved as i32,
+            attempted: r.attempted as i32,
+            unsolved: unsolved as i32,
+            progress_percentage: percentage,
+        }
+    }
+}
+
 #[tauri::command]
 pub async fn get_ladder_stats(
     ladder_id: String,
     db: State<'_, PosDb>,
 ) -> PosResult<LadderStats> {
-    log::info!("[CF STATS] Getting stats for ladder: {}", ladder_id);
-    
-    let total: i64 = sqlx::query_scalar::<sqlx::Postgres, i64>(
-        "SELECT COUNT(*) FROM cf_ladder_problems WHERE ladder_id = $1"
-    )
-    .bind(&ladder_id)
-    .fetch_one(&db.0)
-    .await
-    .map_err(|e| db_context("count cf_ladder_problems", e))?;
-
-    log::info!("[CF STATS] Total problems in ladder: {}", total);
-
-    let solved: i64 = sqlx::query_scalar::<sqlx::Postgres, i64>(
-        r#"
-        SELECT COUNT(DISTINCT p.problem_id)
-        FROM cf_ladder_problems p
-        WHERE p.ladder_id = $1
-        AND EXISTS (
-            SELECT 1 FROM pos_submissions s 
-            WHERE s.problem_id = ('cf-' || p.problem_id) 
-            AND s.platform = 'codeforces' 
-            AND s.verdict = 'OK'
-        )
-        "#
-    )
-    .bind(&ladder_id)
-    .fetch_one(&db.0)
-    .await
-    .map_err(|e| db_context("count solved", e))?;
-
-    log::info!("[CF STATS] Solved problems: {}", solved);
-
-    let attempted: i64 = sqlx::query_scalar::<sqlx::Postgres, i64>(
-        r#"
-        SELECT COUNT(DISTINCT p.problem_id)
-        FROM cf_ladder_problems p
-        WHERE p.ladder_id = $1
-        AND EXISTS (
-            SELECT 1 FROM pos_submissions s 
-            WHERE s.problem_id = ('cf-' || p.problem_id) 
-            AND s.platform = 'codeforces'
-        )
-        "#
-    )
-    .bind(&ladder_id)
-    .fetch_one(&db.0)
-    .await
-    .map_err(|e| db_context("count attempted", e))?;
+    let row = sqlx::query_as::<sqlx::Postgres, LadderStatsRow>(LADDER_STATS_SQL)
+        .bind(Some(&ladder_id))
+        .fetch_optional(&db.0)
+        .await
+        .map_err(|e| db_context("get_ladder_stats", e))?
+        // Unknown ids keep the old behaviour: zeroed stats, not an error
+        .unwrap_or_else(|| LadderStatsRow { ladder_id: ladder_id.clone(), total: 0, solved: 0, attempted: 0 });
 
-    log::info!("[CF STATS] Attempted problems (any submission): {}", attempted);
+    let stats = LadderStats::from(row);
+    log::info!("[CF STATS] Ladder {} - Total: {}, Solved: {}, Attempted: {}, Unsolved: {}, Percentage: {:.2}%",
+        ladder_id, stats.total_problems, stats.solved, stats.attempted, stats.unsolved, stats.progress_percentage);
 
-    let unsolved = (total - attempted).max(0);
-    let percentage = if total > 0 { (solved as f64 / total as f64) * 100.0 } else { 0.0 };
+    Ok(stats)
+}
 
-    log::info!("[CF STATS] Final stats - Total: {}, Solved: {}, Attempted: {}, Unsolved: {}, Percentage: {:.2}%", 
-        total, solved, attempted, unsolved, percentage);
+/// Stats for every ladder in a single query (ladder list page)
+#[tauri::command]
+pub async fn get_all_ladder_stats(
+    db: State<'_, PosDb>,
+) -> PosResult<Vec<LadderStatsEntry>> {
+    let rows = sqlx::query_as::<sqlx::Postgres, LadderStatsRow>(LADDER_STATS_SQL)
+        .bind(None::<String>)
+        .fetch_all(&db.0)
+        .await
+        .map_err(|e| db_context("get_all_ladder_stats", e))?;
 
-    Ok(LadderStats {
-        total_problems: total as i32,
-        solved: solved as i32,
-        attempted: attempted as i32,
-        unsolved: unsolved as i32,
-        progress_percentage: percentage,
-    })
+    Ok(rows.into_iter()
+        .map(|r| LadderStatsEntry { ladder_id: r.ladder_id.clone(), stats: r.into() })
+        .collect())
 }
 
 // ─── Sync Ladder Progress ───────────────────────────────────────────
@@ -336,13 +415,17 @@ pub async fn get_ladder_stats(
 pub async fn sync_ladder_progress_from_submissions(
     db: State<'_, PosDb>,
 ) -> PosResult<String> {
+    sync_ladder_progress(&db.0).await
+}
+
+/// Mark ladder problems solved from accepted Codeforces submissions
+pub async fn sync_ladder_progress(pool: &sqlx::PgPool) -> PosResult<String> {
     let now = Utc::now();
-    let pool = &db.0;
 
     log::info!("[CF SYNC] Starting ladder progress sync...");
 
     let cf_submissions_count: i64 = sqlx::query_scalar(
-        "SELECT COUNT(*) FROM pos_submissions WHERE platform = 'codeforces' AND verdict = 'OK'"
+        "SELECT COUNT(*) FROM pos_submissions WHERE platform = 'codeforces' AND verdict = 'OK' AND excluded_at IS NULL"
     )
     .fetch_one(pool)
     .await
@@ -364,7 +447,7 @@ pub async fn sync_ladder_progress_from_submissions(
         SELECT COUNT(*)
         FROM pos_submissions s
         JOIN cf_ladder_problems lp ON s.problem_id = ('cf-' || lp.problem_id)
-        WHERE s.platform = 'codeforces' AND s.verdict = 'OK'
+        WHERE s.platform = 'codeforces' AND s.verdict = 'OK' AND s.excluded_at IS NULL
         "#
     )
     .fetch_one(pool)
@@ -388,6 +471,7 @@ pub async fn sync_ladder_progress_from_submissions(
         LEFT JOIN cf_ladder_progress pr ON pr.ladder_id = lp.ladder_id AND pr.problem_id = lp.problem_id
         WHERE s.platform = 'codeforces' 
           AND s.verdict = 'OK'
+          AND s.excluded_at IS NULL
           AND pr.id IS NULL
         "#
     )
@@ -413,7 +497,7 @@ pub async fn sync_ladder_progress_from_submissions(
         SELECT COUNT(*)
         FROM pos_submissions s
         JOIN cf_category_problems cp ON s.problem_id = ('cf-' || cp.problem_id)
-        WHERE s.platform = 'codeforces' AND s.verdict = 'OK'
+        WHERE s.platform = 'codeforces' AND s.verdict = 'OK' AND s.excluded_at IS NULL
         "#
     )
     .fetch_one(pool)
@@ -436,6 +520,7 @@ pub async fn sync_ladder_progress_from_submissions(
         LEFT JOIN cf_category_progress pr ON pr.category_id = cp.category_id AND pr.problem_id = cp.problem_id
         WHERE s.platform = 'codeforces' 
           AND s.verdict = 'OK'
+          AND s.excluded_at IS NULL
           AND pr.id IS NULL
         "#
     )
@@ -505,7 +590,7 @@ pub async fn update_ladder_problem(
     
     q = q.bind(&problem_id).bind(&ladder_id);
     
-    q.execute(&db.0)
+    q.execute(pool)
         .await
         .map_err(|e| db_context("update ladder problem", e))?;
     
@@ -5,6 +5,7 @@ use scraper::{Html, Selector, ElementRef};
 use regex::Regex;
 
 use crate::pos::error::{PosError, PosResult};
+use crate::pos::problem_url::canonical_problem_url;
 use super::cf_ladder_types::{ParsedLadder, ParsedProblem, ParsedCategory, ParsedCategoryProblem};
 
 // ─── Helper Functions ───────────────────────────────────────────────
@@ -41,7 +42,7 @@ fn extract_rating_range(title: &str, description: Option<&str>) -> (Option<i32>,
     (None, None)
 }
 
-fn extract_problem_id(url: &str, judge: &str) -> Option<String> {
+pub(crate) fn extract_problem_id(url: &str, judge: &str) -> Option<String> {
     match judge {
         "Codeforces" => {
             // http://codeforces.com/problemset/problem/472/D -> 472D
@@ -148,7 +149,7 @@ pub fn parse_ladder_html(html: &str) -> PosResult<ParsedLadder> {
             // Column 2: Problem name + URL
             if let Some(link) = cells[1].select(&link_sel).next() {
                 let name = link.text().collect::<String>().trim().to_string();
-                let url = link.value().attr("href").unwrap_or("").to_string();
+                let url = canonical_problem_url(link.value().attr("href").unwrap_or(""));
                 
                 // Column 3: Online Judge
                 let judge = if cells.len() > 2 {
@@ -233,7 +234,7 @@ pub fn parse_category_html(html: &str) -> PosResult<ParsedCategory> {
             // Col 1: Problem name + URL
             if let Some(link) = cells[1].select(&link_sel).next() {
                 let name = link.text().collect::<String>().trim().to_string();
-                let url = link.value().attr("href").unwrap_or("").to_string();
+                let url = canonical_problem_url(link.value().attr("href").unwrap_or(""));
                 
                 // Col 2: Online Judge
                 let judge = cells[2].text().collect::<String>().trim().to_string();
@@ -0,0 +1,146 @@
+// CF Ladder Search
+// Server-side search over ladders with their progress stats joined in, so the ladder
+// list can filter by name, rating range, source and completion without loading all
+// ladders and their stats into the frontend.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+use crate::PosDb;
+use crate::pos::error::{PosError, PosResult, db_context};
+use super::{CFLadderRow, LadderStats};
+
+const SORT_KEYS: &[&str] = &["recency", "progress", "name", "created"];
+
+// ─── Types ──────────────────────────────────────────────────────────
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LadderSearchFilters {
+    /// Ladders whose rating range overlaps [rating_min, rating_max]
+    pub rating_min: Option<i32>,
+    pub rating_max: Option<i32>,
+    /// "A2OJ" | "Custom" | "FriendsGenerated"
+    pub source: Option<String>,
+    /// Completion percentage bounds (0-100, inclusive)
+    pub min_progress: Option<f64>,
+    pub max_progress: Option<f64>,
+    /// "recency" (default: last submission on a ladder problem) | "progress" | "name" | "created"
+    pub sort: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LadderSearchResult {
+    #[serde(flatten)]
+    pub ladder: CFLadderRow,
+    pub stats: LadderStats,
+    /// Latest submission on any of the ladder's problems
+    pub last_activity_at: Option<DateTime<Utc>>,
+}
+
+#[derive(sqlx::FromRow)]
+struct LadderSearchRow {
+    #[sqlx(flatten)]
+    ladder: CFLadderRow,
+    total: i64,
+    solved: i64,
+    attempted: i64,
+    last_activity_at: Option<DateTime<Utc>>,
+}
+
+// ─── Commands ───────────────────────────────────────────────────────
+
+/// Ladders matching `query` (case-insensitive, name or description) and `filters`.
+/// Progress counts follow `get_ladder_stats`.
+#[tauri::command]
+pub async fn search_ladders(
+    db: State<'_, PosDb>,
+    query: Option<String>,
+    filters: Option<LadderSearchFilters>,
+) -> PosResult<Vec<LadderSearchResult>> {
+    let filters = filters.unwrap_or_default();
+    let sort = filters.sort.as_deref().unwrap_or("recency").to_lowercase();
+    if !SORT_KEYS.contains(&sort.as_str()) {
+        return Err(PosError::InvalidInput(format!(
+            "Unknown sort '{}'. Expected one of: {}", sort, SORT_KEYS.join(", ")
+        )));
+    }
+    for bound in [filters.min_progress, filters.max_progress].into_iter().flatten() {
+        if !(0.0..=100.0).contains(&bound) {
+            return Err(PosError::InvalidInput("Progress bounds must be between 0 and 100".into()));
+        }
+    }
+    let pattern = query.map(|q| q.trim().to_string()).filter(|q| !q.is_empty())
+        .map(|q| format!("%{}%", q.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_")));
+
+    let rows = sqlx::query_as::<_, LadderSearchRow>(
+        r#"WITH subs AS (
+               SELECT problem_id, BOOL_OR(solved) AS solved, MAX(submitted_time) AS last_at
+               FROM (
+                   SELECT problem_id, verdict = 'OK' AS solved, submitted_time
+                   FROM pos_submissions WHERE platform = 'codeforces' AND excluded_at IS NULL
+                   UNION ALL
+                   SELECT problem_id, TRUE, NULL FROM known_solved WHERE platform = 'codeforces'
+               ) x
+               GROUP BY problem_id
+           ),
+           stats AS (
+               SELECT l.id AS ladder_id,
+                      COUNT(p.id)::bigint AS total,
+                      COUNT(DISTINCT p.problem_id) FILTER (WHERE s.solved)::bigint AS solved,
+                      COUNT(DISTINCT p.problem_id) FILTER (WHERE s.problem_id IS NOT NULL)::bigint AS attempted,
+                      MAX(s.last_at) AS last_activity_at
+               FROM cf_ladders l
+               LEFT JOIN cf_ladder_problems p ON p.ladder_id = l.id
+               LEFT JOIN subs s ON s.problem_id = ('cf-' || p.problem_id)
+               GROUP BY l.id
+           )
+           SELECT l.id, l.name, l.description, l.rating_min, l.rating_max, l.difficulty,
+                  l.source, l.problem_count, l.created_at,
+                  st.total, st.solved, st.attempted, st.last_activity_at
+           FROM cf_ladders l
+           JOIN stats st ON st.ladder_id = l.id
+           WHERE ($1::text IS NULL OR l.name ILIKE $1 OR l.description ILIKE $1)
+             AND ($2::int IS NULL OR COALESCE(l.rating_max, l.rating_min, 0) >= $2)
+             AND ($3::int IS NULL OR COALESCE(l.rating_min, l.rating_max, 0) <= $3)
+             AND ($4::text IS NULL OR l.source = $4)
+             AND ($5::float8 IS NULL OR
+                  (CASE WHEN st.total > 0 THEN st.solved * 100.0 / st.total ELSE 0 END) >= $5)
+             AND ($6::float8 IS NULL OR
+                  (CASE WHEN st.total > 0 THEN st.solved * 100.0 / st.total ELSE 0 END) <= $6)
+           ORDER BY
+               CASE WHEN $7 = 'progress'
+                    THEN (CASE WHEN st.total > 0 THEN st.solved * 100.0 / st.total ELSE 0 END) END DESC,
+               CASE WHEN $7 = 'recency' THEN COALESCE(st.last_activity_at, l.created_at) END DESC,
+               CASE WHEN $7 = 'created' THEN l.created_at END DESC,
+               LOWER(l.name) ASC"#
+    )
+    .bind(&pattern)
+    .bind(filters.rating_min)
+    .bind(filters.rating_max)
+    .bind(&filters.source)
+    .bind(filters.min_progress)
+    .bind(filters.max_progress)
+    .bind(&sort)
+    .fetch_all(&db.0)
+    .await
+    .map_err(|e| db_context("search_ladders", e))?;
+
+    Ok(rows.into_iter().map(|r| {
+        let unsolved = (r.total - r.attempted).max(0);
+        let progress_percentage = if r.total > 0 { (r.solved as f64 / r.total as f64) * 100.0 } else { 0.0 };
+        LadderSearchResult {
+            ladder: r.ladder,
+            stats: LadderStats {
+                total_problems: r.total as i32,
+                solved: r.solved as i32,
+                attempted: r.attempted as i32,
+                unsolved: unsolved as i32,
+                progress_percentage,
+            },
+            last_activity_at: r.last_activity_at,
+        }
+    }).collect())
+}
@@ -36,6 +36,8 @@ pub struct CFLadderProblemRow {
     pub solved_by_friends: Option<Vec<String>>,
     #[sqlx(default)]
     pub status: Option<String>,
+    #[sqlx(default)]
+    pub editorial_url: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, sqlx::FromRow)]
@@ -95,6 +97,14 @@ pub struct LadderStats {
     pub progress_percentage: f64,
 }
 
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LadderStatsEntry {
+    pub ladder_id: String,
+    #[serde(flatten)]
+    pub stats: LadderStats,
+}
+
 #[derive(Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct DailyRecommendation {
@@ -170,3 +180,75 @@ pub struct BulkAddProblemsResponse {
     pub skipped_count: i32,
     pub errors: Vec<String>,
 }
+
+// ─── Custom Ladder Types ────────────────────────────────────────────
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateCustomLadderRequest {
+    pub name: String,
+    pub description: Option<String>,
+    /// Codeforces, AtCoder or LeetCode problem URLs, in ladder order
+    pub urls: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateCustomLadderResponse {
+    pub ladder: CFLadderRow,
+    pub added_count: i32,
+    /// URLs that couldn't be resolved or were duplicates, with the reason
+    pub errors: Vec<String>,
+}
+
+// ─── Timed Session Types ────────────────────────────────────────────
+
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct TimedSessionRow {
+    pub id: String,
+    pub ladder_id: String,
+    pub planned_minutes: i32,
+    pub created_at: DateTime<Utc>,
+    /// Set once every problem in the plan has an outcome
+    pub ended_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct TimedSessionItemRow {
+    pub id: String,
+    pub session_id: String,
+    pub problem_id: String,
+    pub problem_name: String,
+    pub problem_url: String,
+    pub online_judge: String,
+    pub difficulty: Option<i32>,
+    pub position: i32,
+    pub budget_minutes: i32,
+    /// solved | skipped | gave_up; None while pending
+    pub outcome: Option<String>,
+    pub actual_minutes: Option<i32>,
+    pub recorded_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TimedSessionSummary {
+    pub budgeted_minutes: i32,
+    pub actual_minutes: i32,
+    pub solved: i32,
+    pub skipped: i32,
+    pub gave_up: i32,
+    pub pending: i32,
+    /// Problems that took longer than their budget
+    pub over_budget: i32,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TimedSession {
+    pub session: TimedSessionRow,
+    pub items: Vec<TimedSessionItemRow>,
+    pub summary: TimedSessionSummary,
+}
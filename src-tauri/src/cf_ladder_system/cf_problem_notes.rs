@@ -0,0 +1,111 @@
+// CF Problem Notes
+// Personal markdown notes (approach, mistakes) per ladder/category problem id, plus a
+// manually linked editorial that takes precedence over the one found by
+// fetch_problem_editorial_links.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use tauri::State;
+
+use crate::PosDb;
+use crate::pos::error::{PosError, PosResult, db_context};
+
+// ─── Types ──────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct ProblemNoteRow {
+    pub problem_id: String,
+    pub markdown: String,
+    /// Manually linked editorial, else the fetched one
+    pub editorial_url: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+const NOTE_SELECT: &str = r#"
+    SELECT n.problem_id, n.markdown, COALESCE(n.editorial_url, e.editorial_url) AS editorial_url,
+           n.created_at, n.updated_at
+    FROM cf_problem_notes n
+    LEFT JOIN cf_problem_editorials e ON e.problem_id = n.problem_id
+    WHERE n.problem_id = $1"#;
+
+// ─── Helpers ────────────────────────────────────────────────────────
+
+fn check_problem_id(problem_id: &str) -> PosResult<String> {
+    let id = problem_id.trim();
+    if id.is_empty() {
+        return Err(PosError::InvalidInput("Problem id is required".into()));
+    }
+    Ok(id.to_string())
+}
+
+pub(crate) async fn fetch_note(pool: &sqlx::PgPool, problem_id: &str) -> PosResult<Option<ProblemNoteRow>> {
+    sqlx::query_as::<_, ProblemNoteRow>(NOTE_SELECT)
+        .bind(problem_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| db_context("fetch cf_problem_note", e))
+}
+
+// ─── Commands ───────────────────────────────────────────────────────
+
+/// Create or replace the note for a problem (e.g. "1520A")
+#[tauri::command]
+pub async fn save_problem_note(
+    db: State<'_, PosDb>,
+    problem_id: String,
+    markdown: String,
+) -> PosResult<ProblemNoteRow> {
+    let problem_id = check_problem_id(&problem_id)?;
+    sqlx::query(
+        r#"INSERT INTO cf_problem_notes (problem_id, markdown, created_at, updated_at)
+           VALUES ($1, $2, NOW(), NOW())
+           ON CONFLICT (problem_id) DO UPDATE SET markdown = EXCLUDED.markdown, updated_at = NOW()"#
+    )
+    .bind(&problem_id)
+    .bind(&markdown)
+    .execute(&db.0)
+    .await
+    .map_err(|e| db_context("save cf_problem_note", e))?;
+
+    fetch_note(&db.0, &problem_id).await?
+        .ok_or_else(|| PosError::NotFound(format!("Note not found: {}", problem_id)))
+}
+
+/// The note for a problem, or None if nothing was saved yet
+#[tauri::command]
+pub async fn get_problem_note(
+    db: State<'_, PosDb>,
+    problem_id: String,
+) -> PosResult<Option<ProblemNoteRow>> {
+    fetch_note(&db.0, &check_problem_id(&problem_id)?).await
+}
+
+/// Attach an editorial URL to a problem's note (creating an empty note if needed)
+#[tauri::command]
+pub async fn link_editorial(
+    db: State<'_, PosDb>,
+    problem_id: String,
+    url: String,
+) -> PosResult<ProblemNoteRow> {
+    let problem_id = check_problem_id(&problem_id)?;
+    let url = url.trim();
+    if !(url.starts_with("https://") || url.starts_with("http://")) {
+        return Err(PosError::InvalidInput(format!("Editorial link must be an http(s) URL: {}", url)));
+    }
+
+    sqlx::query(
+        r#"INSERT INTO cf_problem_notes (problem_id, editorial_url, created_at, updated_at)
+           VALUES ($1, $2, NOW(), NOW())
+           ON CONFLICT (problem_id) DO UPDATE SET editorial_url = EXCLUDED.editorial_url, updated_at = NOW()"#
+    )
+    .bind(&problem_id)
+    .bind(url)
+    .execute(&db.0)
+    .await
+    .map_err(|e| db_context("link editorial", e))?;
+
+    fetch_note(&db.0, &problem_id).await?
+        .ok_or_else(|| PosError::NotFound(format!("Note not found: {}", problem_id)))
+}
@@ -0,0 +1,328 @@
+// Timed Ladder Sessions
+// "Give me 90 minutes of this ladder": picks the next unsolved problems (ladder order)
+// whose estimated solve time fits the window and stores them as an ordered plan with a
+// time budget each. Estimates come from my own submissions: the median time from first
+// submission to first accept per 100-point rating bucket, plus reading time. Outcomes
+// recorded against the plan feed ladder progress.
+
+use std::collections::HashMap;
+
+use chrono::Utc;
+use sqlx::PgPool;
+use tauri::State;
+
+use crate::PosDb;
+use crate::pos::error::{PosError, PosResult, db_context};
+use crate::pos::utils::gen_id;
+use super::cf_ladder_types::*;
+
+const MIN_SESSION_MINUTES: i32 = 15;
+const MAX_SESSION_MINUTES: i32 = 8 * 60;
+/// First submission → accept only measures coding/debugging; reading comes on top
+const READ_MINUTES: f64 = 10.0;
+/// Longer gaps are a later sitting, not one solve
+const MAX_SOLVE_GAP_MINUTES: i32 = 180;
+/// Buckets with fewer solves fall back to the rating formula
+const MIN_BUCKET_SAMPLES: i64 = 3;
+const MIN_ESTIMATE: i32 = 10;
+const MAX_ESTIMATE: i32 = 120;
+const OUTCOMES: [&str; 3] = ["solved", "skipped", "gave_up"];
+
+const ITEM_COLS: &str = "id, session_id, problem_id, problem_name, problem_url, online_judge, difficulty, \
+    position, budget_minutes, outcome, actual_minutes, recorded_at";
+
+// ─── Estimation ─────────────────────────────────────────────────────
+
+/// Median solve minutes and sample count per rating bucket (rating rounded down to 100)
+async fn solve_time_buckets(pool: &PgPool) -> PosResult<HashMap<i32, (f64, i64)>> {
+    let rows = sqlx::query_as::<_, (i32, f64, i64)>(
+        r#"WITH firsts AS (
+               SELECT problem_id, MAX(rating) AS rating, MIN(submitted_time) AS first_sub,
+                      MIN(submitted_time) FILTER (WHERE verdict IN ('OK', 'Accepted', 'AC')) AS first_ac
+               FROM pos_submissions
+               WHERE excluded_at IS NULL
+               GROUP BY problem_id
+           )
+           SELECT (rating / 100) * 100 AS bucket,
+                  percentile_cont(0.5) WITHIN GROUP (ORDER BY EXTRACT(EPOCH FROM first_ac - first_sub) / 60)::float8,
+                  COUNT(*)
+           FROM firsts
+           WHERE rating IS NOT NULL AND first_ac IS NOT NULL
+             AND first_ac - first_sub <= make_interval(mins => $1)
+           GROUP BY 1"#
+    )
+    .bind(MAX_SOLVE_GAP_MINUTES)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| db_context("solve time buckets", e))?;
+    Ok(rows.into_iter().map(|(bucket, median, n)| (bucket, (median, n))).collect())
+}
+
+/// Estimated minutes for a problem of `difficulty`: my median for its bucket when there
+/// is enough history, otherwise 20 min at 800 plus 5 per 100 rating
+fn estimate_minutes(difficulty: Option<i32>, buckets: &HashMap<i32, (f64, i64)>) -> i32 {
+    let rating = difficulty.unwrap_or(1200).max(0);
+    let estimate = match buckets.get(&(rating / 100 * 100)) {
+        Some(&(median, n)) if n >= MIN_BUCKET_SAMPLES => median + READ_MINUTES,
+        _ => 20.0 + (rating - 800).max(0) as f64 / 100.0 * 5.0,
+    };
+    (estimate.round() as i32).clamp(MIN_ESTIMATE, MAX_ESTIMATE)
+}
+
+/// Greedy fill in ladder order: take every problem whose estimate still fits, skipping
+/// (not stopping at) ones that don't
+fn fit_plan(estimates: &[i32], minutes: i32) -> Vec<usize> {
+    let mut left = minutes;
+    let mut picked = Vec::new();
+    for (i, &est) in estimates.iter().enumerate() {
+        if est <= left {
+            left -= est;
+            picked.push(i);
+        }
+    }
+    picked
+}
+
+fn summarize(items: &[TimedSessionItemRow]) -> TimedSessionSummary {
+    let count = |o: &str| items.iter().filter(|i| i.outcome.as_deref() == Some(o)).count() as i32;
+    TimedSessionSummary {
+        budgeted_minutes: items.iter().map(|i| i.budget_minutes).sum(),
+        actual_minutes: items.iter().filter_map(|i| i.actual_minutes).sum(),
+        solved: count("solved"),
+        skipped: count("skipped"),
+        gave_up: count("gave_up"),
+        pending: items.iter().filter(|i| i.outcome.is_none()).count() as i32,
+        over_budget: items.iter()
+            .filter(|i| i.actual_minutes.is_some_and(|a| a > i.budget_minutes))
+            .count() as i32,
+    }
+}
+
+async fn load_session(pool: &PgPool, session_id: &str) -> PosResult<TimedSession> {
+    let session = sqlx::query_as::<_, TimedSessionRow>(
+        "SELECT id, ladder_id, planned_minutes, created_at, ended_at FROM ladder_sessions WHERE id = $1"
+    )
+    .bind(session_id)
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| db_context("get ladder session", e))?
+    .ok_or_else(|| PosError::NotFound(format!("Timed session {} not found", session_id)))?;
+    let items = sqlx::query_as::<_, TimedSessionItemRow>(&format!(
+        "SELECT {} FROM ladder_session_items WHERE session_id = $1 ORDER BY position", ITEM_COLS
+    ))
+    .bind(session_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| db_context("get ladder session items", e))?;
+    let summary = summarize(&items);
+    Ok(TimedSession { session, items, summary })
+}
+
+// ─── Commands ───────────────────────────────────────────────────────
+
+/// Plan a `minutes`-long session from the next unsolved problems of `ladder_id`
+#[tauri::command]
+pub async fn build_timed_session(
+    db: State<'_, PosDb>,
+    ladder_id: String,
+    minutes: i32,
+) -> PosResult<TimedSession> {
+    let pool = &db.0;
+    if !(MIN_SESSION_MINUTES..=MAX_SESSION_MINUTES).contains(&minutes) {
+        return Err(PosError::InvalidInput(format!(
+            "Session length must be between {} and {} minutes", MIN_SESSION_MINUTES, MAX_SESSION_MINUTES
+        )));
+    }
+
+    // Unsolved = no ladder progress, no accepted submission and not marked known-solved
+    let (candidates, buckets) = tokio::try_join!(
+        async {
+            sqlx::query_as::<_, CFLadderProblemRow>(
+                r#"SELECT lp.id, lp.ladder_id, lp.problem_id, lp.problem_name, lp.problem_url, lp.position,
+                          lp.difficulty, lp.online_judge, lp.created_at
+                   FROM cf_ladder_problems lp
+                   CROSS JOIN LATERAL (SELECT CASE lp.online_judge
+                       WHEN 'AtCoder' THEN 'atcoder-' WHEN 'LeetCode' THEN 'leetcode-' ELSE 'cf-'
+                   END || lp.problem_id AS sub_id) k
+                   WHERE lp.ladder_id = $1
+                     AND NOT EXISTS (SELECT 1 FROM cf_ladder_progress pr
+                                     WHERE pr.ladder_id = lp.ladder_id AND pr.problem_id = lp.problem_id
+                                       AND pr.solved_at IS NOT NULL)
+                     AND NOT EXISTS (SELECT 1 FROM pos_submissions s
+                                     WHERE s.problem_id = k.sub_id AND s.excluded_at IS NULL
+                                       AND s.verdict IN ('OK', 'Accepted', 'AC'))
+                     AND NOT EXISTS (SELECT 1 FROM known_solved ks WHERE ks.problem_id = k.sub_id)
+                   ORDER BY lp.position"#
+            )
+            .bind(&ladder_id)
+            .fetch_all(pool)
+            .await
+            .map_err(|e| db_context("timed session candidates", e))
+        },
+        solve_time_buckets(pool),
+    )?;
+    if candidates.is_empty() {
+        return Err(PosError::InvalidInput("No unsolved problems left in this ladder".into()));
+    }
+
+    let estimates: Vec<i32> = candidates.iter().map(|p| estimate_minutes(p.difficulty, &buckets)).collect();
+    let picked = fit_plan(&estimates, minutes);
+    if picked.is_empty() {
+        return Err(PosError::InvalidInput(format!(
+            "No unsolved problem fits in {} minutes (shortest estimate is {})",
+            minutes, estimates.iter().min().copied().unwrap_or_default()
+        )));
+    }
+
+    let session_id = gen_id();
+    let mut tx = pool.begin().await.map_err(|e| db_context("TX begin", e))?;
+    sqlx::query(
+        "INSERT INTO ladder_sessions (id, ladder_id, planned_minutes, created_at) VALUES ($1, $2, $3, $4)"
+    )
+    .bind(&session_id)
+    .bind(&ladder_id)
+    .bind(minutes)
+    .bind(Utc::now())
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| db_context("insert ladder session", e))?;
+
+    for (position, &i) in picked.iter().enumerate() {
+        let p = &candidates[i];
+        sqlx::query(
+            r#"INSERT INTO ladder_session_items
+               (id, session_id, problem_id, problem_name, problem_url, online_judge, difficulty, position, budget_minutes)
+               VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)"#
+        )
+        .bind(gen_id())
+        .bind(&session_id)
+        .bind(&p.problem_id)
+        .bind(&p.problem_name)
+        .bind(&p.problem_url)
+        .bind(&p.online_judge)
+        .bind(p.difficulty)
+        .bind(position as i32 + 1)
+        .bind(estimates[i])
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| db_context("insert ladder session item", e))?;
+    }
+    tx.commit().await.map_err(|e| db_context("TX commit", e))?;
+
+    let session = load_session(pool, &session_id).await?;
+    log::info!(
+        "[CF] Timed session for ladder {}: {} problems, {}/{} min budgeted",
+        ladder_id, session.items.len(), session.summary.budgeted_minutes, minutes
+    );
+    Ok(session)
+}
+
+/// Record how a planned problem went. A solve also marks ladder progress; giving up
+/// counts as an attempt. The session ends once every problem has an outcome. An outcome is
+/// recorded once; a second call for the same item is rejected.
+#[tauri::command]
+pub async fn record_timed_session_outcome(
+    db: State<'_, PosDb>,
+    item_id: String,
+    outcome: String,
+    actual_minutes: Option<i32>,
+) -> PosResult<TimedSession> {
+    let pool = &db.0;
+    if !OUTCOMES.contains(&outcome.as_str()) {
+        return Err(PosError::InvalidInput(format!("Unknown outcome '{}' (expected {})", outcome, OUTCOMES.join(", "))));
+    }
+    if actual_minutes.is_some_and(|m| m < 0) {
+        return Err(PosError::InvalidInput("actual_minutes cannot be negative".into()));
+    }
+
+    let now = Utc::now();
+    let mut tx = pool.begin().await.map_err(|e| db_context("TX begin", e))?;
+    let recorded = sqlx::query_as::<_, (String, String, String)>(
+        r#"UPDATE ladder_session_items i SET outcome = $2, actual_minutes = $3, recorded_at = $4
+           FROM ladder_sessions s
+           WHERE i.id = $1 AND s.id = i.session_id AND i.recorded_at IS NULL
+           RETURNING i.session_id, s.ladder_id, i.problem_id"#
+    )
+    .bind(&item_id)
+    .bind(&outcome)
+    .bind(actual_minutes)
+    .bind(now)
+    .fetch_optional(&mut *tx)
+    .await
+    .map_err(|e| db_context("record session outcome", e))?;
+    let Some((session_id, ladder_id, problem_id)) = recorded else {
+        // Re-recording would bump ladder attempts a second time
+        let exists: bool = sqlx::query_scalar("SELECT EXISTS (SELECT 1 FROM ladder_session_items WHERE id = $1)")
+            .bind(&item_id)
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(|e| db_context("check session item", e))?;
+        return Err(match exists {
+            true => PosError::InvalidInput(format!("Outcome for session item {} is already recorded", item_id)),
+            false => PosError::NotFound(format!("Session item {} not found", item_id)),
+        });
+    };
+
+    if outcome != "skipped" {
+        let solved_at = (outcome == "solved").then_some(now);
+        sqlx::query(
+            r#"INSERT INTO cf_ladder_progress (id, ladder_id, problem_id, solved_at, attempts, created_at)
+               VALUES ($1, $2, $3, $4, 1, $5)
+               ON CONFLICT (ladder_id, problem_id)
+               DO UPDATE SET solved_at = COALESCE(cf_ladder_progress.solved_at, EXCLUDED.solved_at),
+                             attempts = cf_ladder_progress.attempts + 1"#
+        )
+        .bind(gen_id())
+        .bind(&ladder_id)
+        .bind(&problem_id)
+        .bind(solved_at)
+        .bind(now)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| db_context("session ladder progress", e))?;
+    }
+
+    sqlx::query(
+        r#"UPDATE ladder_sessions SET ended_at = $2
+           WHERE id = $1 AND ended_at IS NULL
+             AND NOT EXISTS (SELECT 1 FROM ladder_session_items WHERE session_id = $1 AND outcome IS NULL)"#
+    )
+    .bind(&session_id)
+    .bind(now)
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| db_context("end ladder session", e))?;
+    tx.commit().await.map_err(|e| db_context("TX commit", e))?;
+
+    load_session(pool, &session_id).await
+}
+
+/// A timed session's plan with outcomes and the plan-vs-actual summary
+#[tauri::command]
+pub async fn get_timed_session(
+    db: State<'_, PosDb>,
+    session_id: String,
+) -> PosResult<TimedSession> {
+    load_session(&db.0, &session_id).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_estimate_uses_history_then_formula() {
+        let buckets = HashMap::from([(1400, (22.4, 5)), (1600, (40.0, 2))]);
+        assert_eq!(estimate_minutes(Some(1450), &buckets), 32);
+        // Too few samples: formula
+        assert_eq!(estimate_minutes(Some(1600), &buckets), 60);
+        assert_eq!(estimate_minutes(Some(3500), &buckets), MAX_ESTIMATE);
+        assert_eq!(estimate_minutes(None, &buckets), 40);
+    }
+
+    #[test]
+    fn test_fit_plan_skips_oversized() {
+        assert_eq!(fit_plan(&[30, 70, 40, 20], 90), vec![0, 2, 3]);
+        assert!(fit_plan(&[100], 90).is_empty());
+    }
+}
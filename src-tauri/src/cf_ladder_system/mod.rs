@@ -8,6 +8,7 @@ pub use cf_ladder_types::*;
 // Re-export parser
 mod cf_ladder_parser;
 pub use cf_ladder_parser::{parse_ladder_html, parse_category_html};
+pub(crate) use cf_ladder_parser::extract_problem_id;
 
 // Re-export ladder commands
 mod cf_ladder_commands;
@@ -20,3 +21,31 @@ pub use cf_category_commands::*;
 // Re-export bulk operations
 mod cf_bulk_operations;
 pub use cf_bulk_operations::*;
+
+// Re-export custom ladders built from URL lists
+mod cf_custom_ladders;
+pub use cf_custom_ladders::*;
+
+// Re-export timed ladder sessions
+mod cf_timed_sessions;
+pub use cf_timed_sessions::*;
+
+// Re-export editorial lookups
+mod cf_editorials;
+pub use cf_editorials::*;
+
+// Re-export problem notes
+mod cf_problem_notes;
+pub use cf_problem_notes::*;
+
+// Re-export hint ladder
+mod cf_hints;
+pub use cf_hints::*;
+
+// Re-export custom recommendation strategies
+mod cf_custom_strategies;
+pub use cf_custom_strategies::*;
+
+// Re-export ladder search
+mod cf_ladder_search;
+pub use cf_ladder_search::*;
@@ -0,0 +1,236 @@
+// Daily Recommendation History
+// get_daily_recommendations recomputes on every call. generate_daily_recommendations freezes
+// one set of picks per date (cf_daily_recommendations + one cf_recommendation_items row per
+// problem) so completion can be tracked over time.
+
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::Serialize;
+use sqlx::PgPool;
+use tauri::State;
+
+use crate::PosDb;
+use crate::cf_recommendations::compute_recommendations;
+use crate::pos::error::{PosError, PosResult, db_context};
+use crate::pos::utils::gen_id;
+
+const DEFAULT_COUNT: i32 = 5;
+const MAX_COUNT: i32 = 50;
+const DEFAULT_HISTORY_DAYS: i64 = 30;
+const MAX_HISTORY_DAYS: i64 = 365;
+
+// ─── Types ──────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct RecommendationItem {
+    pub date: NaiveDate,
+    pub problem_id: String,
+    pub position: i32,
+    pub problem_name: String,
+    pub problem_url: String,
+    pub online_judge: String,
+    pub difficulty: Option<i32>,
+    pub reason: String,
+    pub strategy: String,
+    pub done_at: Option<DateTime<Utc>>,
+    /// Solved on the judge (submissions or known_solved), whether or not marked done
+    pub solved: bool,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecommendationDay {
+    pub date: NaiveDate,
+    pub generated_at: DateTime<Utc>,
+    pub total: usize,
+    pub done: usize,
+    pub items: Vec<RecommendationItem>,
+}
+
+const ITEM_COLS: &str = r#"i.date, i.problem_id, i.position, i.problem_name, i.problem_url, i.online_judge,
+    i.difficulty, i.reason, i.strategy, i.done_at,
+    EXISTS (SELECT 1 FROM solved_problems s WHERE s.problem_id IN (i.problem_id, 'cf-' || i.problem_id)) AS solved"#;
+
+// ─── Helpers ────────────────────────────────────────────────────────
+
+fn parse_date(date: Option<&str>) -> PosResult<NaiveDate> {
+    match date {
+        Some(d) => NaiveDate::parse_from_str(d, "%Y-%m-%d")
+            .map_err(|e| PosError::InvalidInput(format!("Invalid date: {}", e))),
+        None => Ok(crate::pos::timezone::today()),
+    }
+}
+
+async fn load_days(pool: &PgPool, from: NaiveDate, to: NaiveDate) -> PosResult<Vec<RecommendationDay>> {
+    let (headers, items) = tokio::try_join!(
+        sqlx::query_as::<_, (NaiveDate, DateTime<Utc>)>(
+            "SELECT date, created_at FROM cf_daily_recommendations WHERE date BETWEEN $1 AND $2 ORDER BY date DESC"
+        )
+        .bind(from)
+        .bind(to)
+        .fetch_all(pool),
+        sqlx::query_as::<_, RecommendationItem>(&format!(
+            "SELECT {} FROM cf_recommendation_items i WHERE i.date BETWEEN $1 AND $2 ORDER BY i.date DESC, i.position",
+            ITEM_COLS
+        ))
+        .bind(from)
+        .bind(to)
+        .fetch_all(pool),
+    )
+    .map_err(|e| db_context("load recommendation history", e))?;
+
+    Ok(headers.into_iter().map(|(date, generated_at)| {
+        let items: Vec<RecommendationItem> = items.iter().filter(|i| i.date == date).cloned().collect();
+        RecommendationDay {
+            date,
+            generated_at,
+            total: items.len(),
+            done: items.iter().filter(|i| i.done_at.is_some()).count(),
+            items,
+        }
+    }).collect())
+}
+
+// ─── Commands ───────────────────────────────────────────────────────
+
+/// Persist the picks for `date` (default today). Idempotent per date: once a day has
+/// picks, later calls return them unchanged whatever the strategy.
+#[tauri::command]
+pub async fn generate_daily_recommendations(
+    db: State<'_, PosDb>,
+    strategy: String,
+    count: Option<i32>,
+    category_id: Option<String>,
+    date: Option<String>,
+) -> PosResult<RecommendationDay> {
+    let pool = &db.0;
+    let date = parse_date(date.as_deref())?;
+
+    if let Some(day) = load_days(pool, date, date).await?.pop() {
+        return Ok(day);
+    }
+
+    let n = count.unwrap_or(DEFAULT_COUNT).clamp(1, MAX_COUNT);
+    let recs = compute_recommendations(pool, &strategy, n, category_id).await?;
+    if recs.is_empty() {
+        return Err(PosError::NotFound(format!("No unsolved problems available for strategy '{}'", strategy)));
+    }
+
+    let problem_ids: Vec<&str> = recs.iter().map(|r| r.problem_id.as_str()).collect();
+    let mut sources: Vec<&str> = recs.iter().map(|r| r.strategy.as_str()).collect();
+    sources.sort_unstable();
+    sources.dedup();
+
+    let mut tx = pool.begin().await.map_err(|e| db_context("TX begin", e))?;
+    let inserted = sqlx::query(
+        r#"INSERT INTO cf_daily_recommendations (id, date, problem_ids, sources, created_at)
+           VALUES ($1, $2, $3, $4, NOW())
+           ON CONFLICT (date) DO NOTHING"#
+    )
+    .bind(gen_id())
+    .bind(date)
+    .bind(&problem_ids)
+    .bind(&sources)
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| db_context("persist daily recommendations", e))?
+    .rows_affected();
+
+    // A concurrent call won the race; its picks stand
+    if inserted > 0 {
+        let mut seen: Vec<&str> = Vec::new();
+        for (position, rec) in recs.iter().enumerate() {
+            if seen.contains(&rec.problem_id.as_str()) {
+                continue;
+            }
+            seen.push(&rec.problem_id);
+            sqlx::query(
+                r#"INSERT INTO cf_recommendation_items
+                   (date, problem_id, position, problem_name, problem_url, online_judge, difficulty, reason, strategy)
+                   VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)"#
+            )
+            .bind(date)
+            .bind(&rec.problem_id)
+            .bind(position as i32)
+            .bind(&rec.problem_name)
+            .bind(&rec.problem_url)
+            .bind(&rec.online_judge)
+            .bind(rec.difficulty)
+            .bind(&rec.reason)
+            .bind(&rec.strategy)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| db_context("persist recommendation item", e))?;
+        }
+    }
+    tx.commit().await.map_err(|e| db_context("TX commit", e))?;
+
+    if inserted > 0 {
+        log::info!("[CF RECOMMENDATIONS] Persisted {} picks for {} ({})", recs.len(), date, strategy);
+        crate::dashboard::mark_snapshot_stale(pool).await;
+    }
+
+    load_days(pool, date, date).await?.pop()
+        .ok_or_else(|| PosError::NotFound(format!("No recommendations for {}", date)))
+}
+
+/// Persisted days, newest first, between `from` and `to` (default: the last 30 days)
+#[tauri::command]
+pub async fn get_recommendation_history(
+    db: State<'_, PosDb>,
+    from: Option<String>,
+    to: Option<String>,
+) -> PosResult<Vec<RecommendationDay>> {
+    let to = parse_date(to.as_deref())?;
+    let from = match from {
+        Some(f) => parse_date(Some(&f))?,
+        None => to - chrono::Duration::days(DEFAULT_HISTORY_DAYS - 1),
+    };
+    if from > to {
+        return Err(PosError::InvalidInput("from must not be after to".into()));
+    }
+    if (to - from).num_days() >= MAX_HISTORY_DAYS {
+        return Err(PosError::InvalidInput(format!("History range is limited to {} days", MAX_HISTORY_DAYS)));
+    }
+    load_days(&db.0, from, to).await
+}
+
+/// Mark a recommended problem done (or not done with `done = false`). Without `date`,
+/// the most recent recommendation of that problem is updated.
+#[tauri::command]
+pub async fn mark_recommendation_done(
+    db: State<'_, PosDb>,
+    problem_id: String,
+    date: Option<String>,
+    done: Option<bool>,
+) -> PosResult<RecommendationItem> {
+    let date = date.as_deref().map(|d| parse_date(Some(d))).transpose()?;
+    let done = done.unwrap_or(true);
+
+    let item = sqlx::query_as::<_, RecommendationItem>(&format!(
+        r#"WITH target AS (
+               SELECT date, problem_id FROM cf_recommendation_items
+               WHERE problem_id = $1 AND ($2::date IS NULL OR date = $2)
+               ORDER BY date DESC LIMIT 1
+           ), updated AS (
+               UPDATE cf_recommendation_items r
+               SET done_at = CASE WHEN $3 THEN COALESCE(r.done_at, NOW()) ELSE NULL END
+               FROM target t
+               WHERE r.date = t.date AND r.problem_id = t.problem_id
+               RETURNING r.*
+           )
+           SELECT {} FROM updated i"#,
+        ITEM_COLS
+    ))
+    .bind(&problem_id)
+    .bind(date)
+    .bind(done)
+    .fetch_optional(&db.0)
+    .await
+    .map_err(|e| db_context("mark_recommendation_done", e))?
+    .ok_or_else(|| PosError::NotFound(format!("Problem {} was not recommended", problem_id)))?;
+
+    log::info!("[CF RECOMMENDATIONS] {} for {} marked {}", item.problem_id, item.date,
+        if done { "done" } else { "not done" });
+    Ok(item)
+}
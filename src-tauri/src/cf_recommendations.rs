@@ -9,7 +9,7 @@ use crate::pos::error::{PosError, PosResult, db_context};
 use crate::pos::utils::gen_id;
 use crate::cf_ladder_system::{
     CFCategoryRow, CFLadderProblemRow, DailyRecommendation,
-    ImportCategoryRequest, parse_ladder_html,
+    ImportCategoryRequest, parse_ladder_html, run_custom_strategy, CUSTOM_STRATEGY_PREFIX,
 };
 
 // ─── Categories ──────────────────────────────────────────────────────
@@ -25,10 +25,19 @@ pub async fn get_daily_recommendations(
     count: Option<i32>,
     category_id: Option<String>,
 ) -> PosResult<Vec<DailyRecommendation>> {
-    let n = count.unwrap_or(5);
+    compute_recommendations(&db.0, &strategy, count.unwrap_or(5), category_id).await
+}
+
+/// Fresh picks for `strategy`; nothing is persisted
+pub(crate) async fn compute_recommendations(
+    pool: &sqlx::PgPool,
+    strategy: &str,
+    n: i32,
+    category_id: Option<String>,
+) -> PosResult<Vec<DailyRecommendation>> {
     let mut recs: Vec<DailyRecommendation> = Vec::new();
 
-    match strategy.as_str() {
+    match strategy {
         "ladder" => {
             let rows = sqlx::query_as::<sqlx::Postgres, CFLadderProblemRow>(
                 r#"
@@ -38,12 +47,13 @@ pub async fn get_daily_recommendations(
                 LEFT JOIN cf_ladder_progress pr
                   ON pr.ladder_id = p.ladder_id AND pr.problem_id = p.problem_id
                 WHERE pr.id IS NULL
+                  AND NOT EXISTS (SELECT 1 FROM solved_problems sp WHERE sp.problem_id = ('cf-' || p.problem_id))
                 ORDER BY p.position
                 LIMIT $1
                 "#,
             )
             .bind(n)
-            .fetch_all(&db.0)
+            .fetch_all(pool)
             .await
             .map_err(|e| db_context("get ladder recommendations", e))?;
 
@@ -71,12 +81,16 @@ pub async fn get_daily_recommendations(
                 LEFT JOIN cf_ladder_progress pr ON pr.problem_id = s.problem_id
                 WHERE pr.id IS NULL
                   AND s.problem_name <> ''
+                  AND NOT EXISTS (
+                      SELECT 1 FROM solved_problems sp
+                      WHERE sp.problem_id = ('cf-' || s.contest_id::text || s.problem_index)
+                  )
                 ORDER BY s.problem_id, s.submission_time DESC
                 LIMIT $1
                 "#,
             )
             .bind(n)
-            .fetch_all(&db.0)
+            .fetch_all(pool)
             .await
             .map_err(|e| db_context("get friends recommendations", e))?;
 
@@ -96,9 +110,9 @@ pub async fn get_daily_recommendations(
         "category" => {
             // Get user rating
             let user_rating: Option<i32> = sqlx::query_scalar::<sqlx::Postgres, Option<serde_json::Value>>(
-                "SELECT data FROM pos_user_stats WHERE platform = 'codeforces'"
+                "SELECT data FROM platform_cache WHERE platform = 'codeforces' AND resource = 'user_stats'"
             )
-            .fetch_optional(&db.0)
+            .fetch_optional(pool)
             .await
             .map_err(|e| db_context("get user stats", e))?
             .flatten()
@@ -148,9 +162,7 @@ pub async fn get_daily_recommendations(
                                FROM cf_category_problems p
                                WHERE p.category_id = $1 AND p.difficulty = $2
                                AND NOT EXISTS (
-                                   SELECT 1 FROM pos_submissions s
-                                   WHERE s.problem_id = ('cf-' || p.problem_id)
-                                   AND s.platform = 'codeforces' AND s.verdict = 'OK'
+                                   SELECT 1 FROM solved_problems s WHERE s.problem_id = ('cf-' || p.problem_id)
                                )
                                ORDER BY p.position LIMIT $3"#
                         )
@@ -165,9 +177,7 @@ pub async fn get_daily_recommendations(
                                FROM cf_category_problems p
                                WHERE p.difficulty = $1
                                AND NOT EXISTS (
-                                   SELECT 1 FROM pos_submissions s
-                                   WHERE s.problem_id = ('cf-' || p.problem_id)
-                                   AND s.platform = 'codeforces' AND s.verdict = 'OK'
+                                   SELECT 1 FROM solved_problems s WHERE s.problem_id = ('cf-' || p.problem_id)
                                )
                                GROUP BY p.problem_id, p.problem_name, p.problem_url, p.online_judge, p.difficulty
                                ORDER BY RANDOM() LIMIT $2"#
@@ -192,11 +202,11 @@ pub async fn get_daily_recommendations(
                     "SELECT name FROM cf_categories WHERE id = $1"
                 )
                 .bind(&cat_id)
-                .fetch_one(&db.0)
+                .fetch_one(pool)
                 .await
                 .unwrap_or_else(|_| "Unknown".to_string());
 
-                let (problems, actual_level) = fetch_with_fallback(&db.0, Some(&cat_id), base_level, n)
+                let (problems, actual_level) = fetch_with_fallback(pool, Some(&cat_id), base_level, n)
                     .await
                     .map_err(|e| db_context("get category recommendations with fallback", e))?;
 
@@ -220,7 +230,7 @@ pub async fn get_daily_recommendations(
             } else {
                 log::info!("[CF RECOMMENDATIONS] Category strategy (random): base_level={}", base_level);
 
-                let (problems, actual_level) = fetch_with_fallback(&db.0, None, base_level, n)
+                let (problems, actual_level) = fetch_with_fallback(pool, None, base_level, n)
                     .await
                     .map_err(|e| db_context("get category recommendations with fallback", e))?;
 
@@ -249,9 +259,9 @@ pub async fn get_daily_recommendations(
         "rating" => {
             // 1. Get user's Codeforces rating
             let user_rating: Option<i32> = sqlx::query_scalar::<sqlx::Postgres, Option<serde_json::Value>>(
-                "SELECT data FROM pos_user_stats WHERE platform = 'codeforces'"
+                "SELECT data FROM platform_cache WHERE platform = 'codeforces' AND resource = 'user_stats'"
             )
-            .fetch_optional(&db.0)
+            .fetch_optional(pool)
             .await
             .map_err(|e| db_context("get user stats", e))?
             .flatten()
@@ -280,7 +290,7 @@ pub async fn get_daily_recommendations(
             )
             .bind(max_r)
             .bind(min_r)
-            .fetch_all(&db.0)
+            .fetch_all(pool)
             .await
             .map_err(|e| db_context("find matching ladders", e))?;
 
@@ -300,10 +310,7 @@ pub async fn get_daily_recommendations(
                     FROM cf_ladder_problems p
                     WHERE p.ladder_id = $1 
                     AND NOT EXISTS (
-                        SELECT 1 FROM pos_submissions s 
-                        WHERE s.problem_id = ('cf-' || p.problem_id) 
-                        AND s.platform = 'codeforces'
-                        AND s.verdict = 'OK'
+                        SELECT 1 FROM solved_problems s WHERE s.problem_id = ('cf-' || p.problem_id)
                     )
                     ORDER BY p.position
                     LIMIT $2
@@ -311,7 +318,7 @@ pub async fn get_daily_recommendations(
                 )
                 .bind(ladder_id)
                 .bind(problems_per_ladder)
-                .fetch_all(&db.0)
+                .fetch_all(pool)
                 .await
                 .map_err(|e| db_context("get ladder problems", e))?;
 
@@ -356,10 +363,7 @@ pub async fn get_daily_recommendations(
                     WHERE p.difficulty >= $1 
                     AND p.difficulty <= $2
                     AND NOT EXISTS (
-                        SELECT 1 FROM pos_submissions s 
-                        WHERE s.problem_id = ('cf-' || p.problem_id) 
-                        AND s.platform = 'codeforces'
-                        AND s.verdict = 'OK'
+                        SELECT 1 FROM solved_problems s WHERE s.problem_id = ('cf-' || p.problem_id)
                     )
                     GROUP BY p.problem_id, p.problem_name, p.problem_url, p.online_judge, p.difficulty
                     ORDER BY p.difficulty, RANDOM()
@@ -369,7 +373,7 @@ pub async fn get_daily_recommendations(
                 .bind(min_diff)
                 .bind(max_diff)
                 .bind(needed)
-                .fetch_all(&db.0)
+                .fetch_all(pool)
                 .await
                 .map_err(|e| db_context("get category fallback", e))?;
                 
@@ -395,6 +399,10 @@ pub async fn get_daily_recommendations(
         }
 
         // "hybrid" and fallback — round-robin: ladder + friends + category
+        s if s.starts_with(CUSTOM_STRATEGY_PREFIX) => {
+            recs = run_custom_strategy(pool, s, n).await?;
+        }
+
         _ => {
             let per = (n / 3).max(1);
 
@@ -403,10 +411,12 @@ pub async fn get_daily_recommendations(
                           p.position, p.difficulty, p.online_judge, p.created_at
                    FROM cf_ladder_problems p
                    LEFT JOIN cf_ladder_progress pr ON pr.ladder_id = p.ladder_id AND pr.problem_id = p.problem_id
-                   WHERE pr.id IS NULL ORDER BY p.position LIMIT $1"#,
+                   WHERE pr.id IS NULL
+                     AND NOT EXISTS (SELECT 1 FROM solved_problems sp WHERE sp.problem_id = ('cf-' || p.problem_id))
+                   ORDER BY p.position LIMIT $1"#,
             )
             .bind(per)
-            .fetch_all(&db.0)
+            .fetch_all(pool)
             .await
             .map_err(|e| db_context("hybrid: ladder", e))?;
 
@@ -426,10 +436,14 @@ pub async fn get_daily_recommendations(
                    FROM cf_friend_submissions s
                    LEFT JOIN cf_ladder_progress pr ON pr.problem_id = s.problem_id
                    WHERE pr.id IS NULL AND s.problem_name <> ''
+                     AND NOT EXISTS (
+                         SELECT 1 FROM solved_problems sp
+                         WHERE sp.problem_id = ('cf-' || s.contest_id::text || s.problem_index)
+                     )
                    ORDER BY s.problem_id, s.submission_time DESC LIMIT $1"#,
             )
             .bind(per)
-            .fetch_all(&db.0)
+            .fetch_all(pool)
             .await
             .map_err(|e| db_context("hybrid: friends", e))?;
 
@@ -447,13 +461,14 @@ pub async fn get_daily_recommendations(
                 r#"SELECT p.problem_id, p.problem_name, p.problem_url, p.online_judge, p.difficulty
                    FROM cf_category_problems p
                    LEFT JOIN cf_category_progress cp ON cp.category_id = p.category_id AND cp.problem_id = p.problem_id
-                   WHERE cp.id IS NULL 
+                   WHERE cp.id IS NULL
+                     AND NOT EXISTS (SELECT 1 FROM solved_problems sp WHERE sp.problem_id = ('cf-' || p.problem_id))
                    GROUP BY p.problem_id, p.problem_name, p.problem_url, p.online_judge, p.difficulty
                    ORDER BY MIN(p.position) 
                    LIMIT $1"#,
             )
             .bind(per)
-            .fetch_all(&db.0)
+            .fetch_all(pool)
             .await
             .map_err(|e| db_context("hybrid: category", e))?;
 
@@ -0,0 +1,415 @@
+// Cohort
+// A small study group tracked side by side. Each member is a public Codeforces or LeetCode
+// handle; a background loop records their solved count once per sync into cohort_progress
+// (one row per member per day). The leaderboard ranks problems solved within a period and
+// streaks of consecutive days on which a member's solved count grew.
+
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
+
+use chrono::{DateTime, Datelike, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use tauri::State;
+
+use crate::PosDb;
+use crate::pos::error::{PosError, PosResult, db_context};
+use crate::pos::retry::{with_backoff, BackoffPolicy};
+use crate::pos::scrapers::{build_http_client, CODEFORCES_HOST, LEETCODE_HOST};
+use crate::pos::timezone;
+use crate::pos::utils::gen_id;
+
+const PLATFORMS: [&str; 2] = ["codeforces", "leetcode"];
+const COHORT_SYNC_HOURS: u64 = 6;
+/// Spacing between members so Codeforces' 1 request / 2s limit holds
+const MEMBER_SYNC_DELAY: Duration = Duration::from_millis(2100);
+
+// ─── Types ──────────────────────────────────────────────────────────
+
+#[derive(Debug, Serialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct CohortMember {
+    pub id: String,
+    pub platform: String,
+    pub handle: String,
+    pub display_name: String,
+    pub last_synced: Option<DateTime<Utc>>,
+    pub last_error: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AddCohortMemberRequest {
+    pub platform: String,
+    pub handle: String,
+    pub display_name: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CohortSyncSummary {
+    pub members_synced: i32,
+    /// Handles whose sync failed, with the error
+    pub failed: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CohortLeaderboardEntry {
+    /// 1-based; members tied on solved and streak share a rank
+    pub rank: usize,
+    pub member_id: String,
+    pub platform: String,
+    pub handle: String,
+    pub display_name: String,
+    /// Growth of the solved count since the period started
+    pub solved_in_period: i32,
+    pub solved_total: Option<i32>,
+    pub current_streak: i32,
+    pub longest_streak: i32,
+    pub last_synced: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CohortLeaderboard {
+    pub period: String,
+    /// None for "all"
+    pub since: Option<NaiveDate>,
+    pub entries: Vec<CohortLeaderboardEntry>,
+}
+
+#[derive(sqlx::FromRow)]
+struct LeaderboardRow {
+    id: String,
+    platform: String,
+    handle: String,
+    display_name: String,
+    last_synced: Option<DateTime<Utc>>,
+    solved_total: Option<i32>,
+    solved_in_period: Option<i32>,
+}
+
+// ─── Helpers ────────────────────────────────────────────────────────
+
+/// First day of `period` (week | month | year | all) relative to `today`
+fn period_start(period: &str, today: NaiveDate) -> PosResult<Option<NaiveDate>> {
+    match period {
+        "week" => Ok(Some(today - chrono::Duration::days(today.weekday().num_days_from_monday() as i64))),
+        "month" => Ok(today.with_day(1)),
+        "year" => Ok(NaiveDate::from_ymd_opt(today.year(), 1, 1)),
+        "all" => Ok(None),
+        other => Err(PosError::InvalidInput(format!(
+            "Unknown period '{}'. Use week, month, year or all", other
+        ))),
+    }
+}
+
+/// Distinct accepted problems for a Codeforces handle
+async fn fetch_codeforces_solved(client: &reqwest::Client, handle: &str) -> PosResult<i32> {
+    let url = format!("https://codeforces.com/api/user.status?handle={}", handle);
+    let body: serde_json::Value = with_backoff(CODEFORCES_HOST, BackoffPolicy::default(), || async {
+        let resp = client.get(&url).send().await?;
+        if resp.status().as_u16() == 400 {
+            return Err(PosError::NotFound(format!("Codeforces handle '{}' not found", handle)));
+        }
+        if !resp.status().is_success() {
+            return Err(PosError::External(format!("HTTP error: {}", resp.status())));
+        }
+        Ok(resp.json::<serde_json::Value>().await?)
+    }).await?;
+
+    if body["status"] != "OK" {
+        return Err(PosError::External(format!("Codeforces API error for '{}': {}", handle, body["comment"])));
+    }
+    let solved: HashSet<String> = body["result"].as_array().into_iter().flatten()
+        .filter(|s| s["verdict"] == "OK")
+        .map(|s| format!("{}{}", s["problem"]["contestId"], s["problem"]["index"].as_str().unwrap_or("")))
+        .collect();
+    Ok(solved.len() as i32)
+}
+
+/// LeetCode's own accepted-problem count for a username
+async fn fetch_leetcode_solved(client: &reqwest::Client, username: &str) -> PosResult<i32> {
+    let body = serde_json::json!({
+        "query": "query($username: String!) { matchedUser(username: $username) { submitStats { acSubmissionNum { difficulty count } } } }",
+        "variables": { "username": username },
+    });
+    let data: serde_json::Value = with_backoff(LEETCODE_HOST, BackoffPolicy::default(), || async {
+        let resp = client.post("https://leetcode.com/graphql")
+            .header("Content-Type", "application/json")
+            .header("Referer", "https://leetcode.com")
+            .json(&body)
+            .send()
+            .await?;
+        if !resp.status().is_success() {
+            return Err(PosError::External(format!("HTTP error: {}", resp.status())));
+        }
+        Ok(resp.json::<serde_json::Value>().await?)
+    }).await?;
+
+    let user = &data["data"]["matchedUser"];
+    if user.is_null() {
+        return Err(PosError::NotFound(format!("LeetCode user '{}' not found", username)));
+    }
+    user["submitStats"]["acSubmissionNum"].as_array().into_iter().flatten()
+        .find(|c| c["difficulty"] == "All")
+        .and_then(|c| c["count"].as_i64())
+        .map(|n| n as i32)
+        .ok_or_else(|| PosError::External(format!("Unexpected LeetCode response for '{}'", username)))
+}
+
+async fn fetch_solved(client: &reqwest::Client, platform: &str, handle: &str) -> PosResult<i32> {
+    match platform {
+        "codeforces" => fetch_codeforces_solved(client, handle).await,
+        "leetcode" => fetch_leetcode_solved(client, handle).await,
+        other => Err(PosError::InvalidInput(format!("Unsupported cohort platform '{}'", other))),
+    }
+}
+
+async fn record_progress(pool: &PgPool, member_id: &str, solved: i32) -> PosResult<()> {
+    let mut tx = pool.begin().await.map_err(|e| db_context("TX begin", e))?;
+    sqlx::query(
+        r#"INSERT INTO cohort_progress (member_id, date, solved_total, synced_at)
+           VALUES ($1, $2, $3, NOW())
+           ON CONFLICT (member_id, date) DO UPDATE SET solved_total = EXCLUDED.solved_total, synced_at = NOW()"#
+    )
+    .bind(member_id)
+    .bind(timezone::today())
+    .bind(solved)
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| db_context("record cohort progress", e))?;
+    sqlx::query("UPDATE cohort_members SET last_synced = NOW(), last_error = NULL WHERE id = $1")
+        .bind(member_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| db_context("mark cohort member synced", e))?;
+    tx.commit().await.map_err(|e| db_context("TX commit", e))
+}
+
+/// Snapshot every member's solved count; failures are recorded per member
+pub async fn sync_all(pool: &PgPool) -> PosResult<CohortSyncSummary> {
+    let members: Vec<(String, String, String)> = sqlx::query_as(
+        "SELECT id, platform, handle FROM cohort_members ORDER BY created_at"
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| db_context("list cohort members", e))?;
+
+    let client = build_http_client();
+    let mut summary = CohortSyncSummary { members_synced: 0, failed: Vec::new() };
+    for (i, (id, platform, handle)) in members.iter().enumerate() {
+        if i > 0 {
+            tokio::time::sleep(MEMBER_SYNC_DELAY).await;
+        }
+        let result = match fetch_solved(&client, platform, handle).await {
+            Ok(solved) => record_progress(pool, id, solved).await,
+            Err(e) => Err(e),
+        };
+        match result {
+            Ok(()) => summary.members_synced += 1,
+            Err(e) => {
+                log::warn!("[COHORT] Sync failed for {} ({}): {}", handle, platform, e);
+                sqlx::query("UPDATE cohort_members SET last_error = $2 WHERE id = $1")
+                    .bind(id)
+                    .bind(e.to_string())
+                    .execute(pool)
+                    .await
+                    .map_err(|e| db_context("record cohort sync error", e))?;
+                summary.failed.push(format!("{} ({}): {}", handle, platform, e));
+            }
+        }
+    }
+    log::info!("[COHORT] Synced {} members, {} failed", summary.members_synced, summary.failed.len());
+    Ok(summary)
+}
+
+/// Sync the cohort now and every COHORT_SYNC_HOURS after
+pub fn start(pool: PgPool) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            if let Err(e) = sync_all(&pool).await {
+                log::warn!("[COHORT] Background sync failed: {}", e);
+            }
+            tokio::time::sleep(Duration::from_secs(COHORT_SYNC_HOURS * 3600)).await;
+        }
+    });
+}
+
+// ─── Commands ───────────────────────────────────────────────────────
+
+/// Add a member after confirming the handle exists; records its first snapshot
+#[tauri::command]
+pub async fn add_cohort_member(
+    db: State<'_, PosDb>,
+    req: AddCohortMemberRequest,
+) -> PosResult<CohortMember> {
+    let pool = &db.0;
+    let platform = req.platform.trim().to_lowercase();
+    let handle = req.handle.trim().to_string();
+    if !PLATFORMS.contains(&platform.as_str()) {
+        return Err(PosError::InvalidInput(format!(
+            "Unsupported cohort platform '{}'. Use {}", platform, PLATFORMS.join(" or ")
+        )));
+    }
+    if handle.is_empty() {
+        return Err(PosError::InvalidInput("Handle is required".into()));
+    }
+
+    let solved = fetch_solved(&build_http_client(), &platform, &handle).await?;
+    let display_name = req.display_name
+        .map(|n| n.trim().to_string())
+        .filter(|n| !n.is_empty())
+        .unwrap_or_else(|| handle.clone());
+
+    let member = sqlx::query_as::<_, CohortMember>(
+        r#"INSERT INTO cohort_members (id, platform, handle, display_name)
+           VALUES ($1, $2, $3, $4)
+           RETURNING id, platform, handle, display_name, last_synced, last_error, created_at"#
+    )
+    .bind(gen_id())
+    .bind(&platform)
+    .bind(&handle)
+    .bind(&display_name)
+    .fetch_one(pool)
+    .await
+    .map_err(|e| match &e {
+        sqlx::Error::Database(d) if d.is_unique_violation() => {
+            PosError::InvalidInput(format!("{} is already in the cohort ({})", handle, platform))
+        }
+        _ => db_context("add_cohort_member", e),
+    })?;
+
+    record_progress(pool, &member.id, solved).await?;
+    log::info!("[COHORT] Added {} ({}) with {} solved", handle, platform, solved);
+    Ok(CohortMember { last_synced: Some(Utc::now()), ..member })
+}
+
+#[tauri::command]
+pub async fn remove_cohort_member(db: State<'_, PosDb>, id: String) -> PosResult<()> {
+    let res = sqlx::query("DELETE FROM cohort_members WHERE id = $1")
+        .bind(&id)
+        .execute(&db.0)
+        .await
+        .map_err(|e| db_context("remove_cohort_member", e))?;
+    if res.rows_affected() == 0 {
+        return Err(PosError::NotFound(format!("Cohort member {} not found", id)));
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_cohort_members(db: State<'_, PosDb>) -> PosResult<Vec<CohortMember>> {
+    sqlx::query_as::<_, CohortMember>(
+        r#"SELECT id, platform, handle, display_name, last_synced, last_error, created_at
+           FROM cohort_members ORDER BY display_name"#
+    )
+    .fetch_all(&db.0)
+    .await
+    .map_err(|e| db_context("get_cohort_members", e))
+}
+
+/// Sync every member now instead of waiting for the background loop
+#[tauri::command]
+pub async fn sync_cohort(db: State<'_, PosDb>) -> PosResult<CohortSyncSummary> {
+    sync_all(&db.0).await
+}
+
+/// Rank members by problems solved in `period` (week | month | year | all, default week),
+/// then by current streak
+#[tauri::command]
+pub async fn get_cohort_leaderboard(
+    db: State<'_, PosDb>,
+    period: Option<String>,
+) -> PosResult<CohortLeaderboard> {
+    let pool = &db.0;
+    let period = period.unwrap_or_else(|| "week".into()).to_lowercase();
+    let today = timezone::today();
+    let since = period_start(&period, today)?;
+
+    let (rows, growth_days) = tokio::try_join!(
+        sqlx::query_as::<_, LeaderboardRow>(
+            r#"SELECT m.id, m.platform, m.handle, m.display_name, m.last_synced,
+                      latest.solved_total,
+                      CASE WHEN $1::date IS NULL THEN latest.solved_total
+                           ELSE latest.solved_total - COALESCE(base.solved_total, first_in.solved_total)
+                      END AS solved_in_period
+               FROM cohort_members m
+               LEFT JOIN LATERAL (SELECT solved_total FROM cohort_progress
+                                  WHERE member_id = m.id ORDER BY date DESC LIMIT 1) latest ON TRUE
+               LEFT JOIN LATERAL (SELECT solved_total FROM cohort_progress
+                                  WHERE member_id = m.id AND date < $1 ORDER BY date DESC LIMIT 1) base ON TRUE
+               LEFT JOIN LATERAL (SELECT solved_total FROM cohort_progress
+                                  WHERE member_id = m.id AND date >= $1 ORDER BY date LIMIT 1) first_in ON TRUE"#
+        )
+        .bind(since)
+        .fetch_all(pool),
+        // Days on which the solved count grew over the previous snapshot
+        sqlx::query_as::<_, (String, NaiveDate)>(
+            r#"SELECT member_id, date FROM (
+                   SELECT member_id, date,
+                          solved_total - LAG(solved_total) OVER (PARTITION BY member_id ORDER BY date) AS delta
+                   FROM cohort_progress
+               ) t
+               WHERE delta > 0
+               ORDER BY member_id, date"#
+        )
+        .fetch_all(pool),
+    )
+    .map_err(|e| db_context("get_cohort_leaderboard", e))?;
+
+    let mut days_by_member: HashMap<String, Vec<NaiveDate>> = HashMap::new();
+    for (member_id, day) in growth_days {
+        days_by_member.entry(member_id).or_default().push(day);
+    }
+
+    let mut entries: Vec<CohortLeaderboardEntry> = rows.into_iter().map(|r| {
+        let (current_streak, longest_streak) = days_by_member.get(&r.id)
+            .map(|days| crate::streaks::streak_lengths(days, today))
+            .unwrap_or((0, 0));
+        CohortLeaderboardEntry {
+            rank: 0,
+            member_id: r.id,
+            platform: r.platform,
+            handle: r.handle,
+            display_name: r.display_name,
+            solved_in_period: r.solved_in_period.unwrap_or(0).max(0),
+            solved_total: r.solved_total,
+            current_streak,
+            longest_streak,
+            last_synced: r.last_synced,
+        }
+    }).collect();
+
+    entries.sort_by(|a, b| b.solved_in_period.cmp(&a.solved_in_period)
+        .then(b.current_streak.cmp(&a.current_streak))
+        .then_with(|| a.display_name.to_lowercase().cmp(&b.display_name.to_lowercase())));
+    for i in 0..entries.len() {
+        entries[i].rank = match i.checked_sub(1).map(|p| &entries[p]) {
+            Some(prev) if prev.solved_in_period == entries[i].solved_in_period
+                && prev.current_streak == entries[i].current_streak => prev.rank,
+            _ => i + 1,
+        };
+    }
+
+    Ok(CohortLeaderboard { period, since, entries })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_period_start() {
+        let d = |s: &str| NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap();
+        // 2024-05-16 is a Thursday
+        assert_eq!(period_start("week", d("2024-05-16")).unwrap(), Some(d("2024-05-13")));
+        assert_eq!(period_start("week", d("2024-05-13")).unwrap(), Some(d("2024-05-13")));
+        assert_eq!(period_start("month", d("2024-05-16")).unwrap(), Some(d("2024-05-01")));
+        assert_eq!(period_start("year", d("2024-05-16")).unwrap(), Some(d("2024-01-01")));
+        assert_eq!(period_start("all", d("2024-05-16")).unwrap(), None);
+        assert!(period_start("fortnight", d("2024-05-16")).is_err());
+    }
+}
@@ -0,0 +1,246 @@
+// Context-Switch Report
+// How fragmented each day's activity timeline is: category switches, focus blocks
+// (consecutive activities in one category, broken by a switch or a gap longer than
+// BLOCK_GAP_MINUTES) and their average length, set against that day's goal completion.
+// Days with more switches than the median are "fragmented"; the report compares their
+// completion rate with the focused days and gives the switches/completion correlation.
+
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::Serialize;
+use tauri::State;
+
+use crate::PosDb;
+use crate::pos::error::{PosError, PosResult, db_context};
+
+/// A pause longer than this starts a new block even in the same category
+const BLOCK_GAP_MINUTES: i64 = 15;
+
+// ─── Types ──────────────────────────────────────────────────────────
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DaySwitchStats {
+    pub date: String, // YYYY-MM-DD
+    pub switches: i64,
+    pub blocks: i64,
+    pub avg_block_minutes: f64,
+    pub logged_minutes: i64,
+    pub goals_due: i64,
+    pub goals_completed: i64,
+    /// None when no goals were due
+    pub completion_rate: Option<f64>,
+    pub fragmented: bool,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContextSwitchReport {
+    pub days: Vec<DaySwitchStats>,
+    pub avg_switches_per_day: f64,
+    pub avg_block_minutes: f64,
+    /// Days above this many switches count as fragmented
+    pub median_switches: f64,
+    pub fragmented_completion_rate: Option<f64>,
+    pub focused_completion_rate: Option<f64>,
+    /// Pearson r between daily switches and completion rate; None with fewer than 3 days
+    pub switch_completion_correlation: Option<f64>,
+}
+
+#[derive(sqlx::FromRow)]
+struct TimelineRow {
+    date: String,
+    start_time: DateTime<Utc>,
+    end_time: DateTime<Utc>,
+    category: String,
+}
+
+// ─── Helpers ────────────────────────────────────────────────────────
+
+/// (switches, block lengths in minutes) for one day's activities, ordered by start
+fn day_blocks(activities: &[&TimelineRow]) -> (i64, Vec<i64>) {
+    let mut switches = 0;
+    let mut blocks: Vec<i64> = Vec::new();
+    let mut prev: Option<&TimelineRow> = None;
+    for a in activities {
+        let minutes = (a.end_time - a.start_time).num_minutes().max(0);
+        match prev {
+            Some(p) if p.category == a.category
+                && (a.start_time - p.end_time).num_minutes() <= BLOCK_GAP_MINUTES =>
+            {
+                if let Some(last) = blocks.last_mut() {
+                    *last += minutes;
+                }
+            }
+            Some(p) => {
+                if p.category != a.category {
+                    switches += 1;
+                }
+                blocks.push(minutes);
+            }
+            None => blocks.push(minutes),
+        }
+        prev = Some(a);
+    }
+    (switches, blocks)
+}
+
+fn median(values: &mut [i64]) -> f64 {
+    if values.is_empty() {
+        return 0.0;
+    }
+    values.sort_unstable();
+    let mid = values.len() / 2;
+    if values.len() % 2 == 0 {
+        (values[mid - 1] + values[mid]) as f64 / 2.0
+    } else {
+        values[mid] as f64
+    }
+}
+
+fn pearson(pairs: &[(f64, f64)]) -> Option<f64> {
+    if pairs.len() < 3 {
+        return None;
+    }
+    let n = pairs.len() as f64;
+    let (mx, my) = (
+        pairs.iter().map(|p| p.0).sum::<f64>() / n,
+        pairs.iter().map(|p| p.1).sum::<f64>() / n,
+    );
+    let cov: f64 = pairs.iter().map(|(x, y)| (x - mx) * (y - my)).sum();
+    let vx: f64 = pairs.iter().map(|(x, _)| (x - mx).powi(2)).sum();
+    let vy: f64 = pairs.iter().map(|(_, y)| (y - my).powi(2)).sum();
+    if vx == 0.0 || vy == 0.0 {
+        return None;
+    }
+    Some(cov / (vx.sqrt() * vy.sqrt()))
+}
+
+/// Completed / due over the given days, None when nothing was due
+fn completion_rate<'a>(days: impl Iterator<Item = &'a DaySwitchStats>) -> Option<f64> {
+    let (due, done) = days.fold((0, 0), |(d, c), s| (d + s.goals_due, c + s.goals_completed));
+    (due > 0).then(|| done as f64 / due as f64)
+}
+
+// ─── Commands ───────────────────────────────────────────────────────
+
+/// Per-day fragmentation between `start_date` and `end_date` (inclusive, YYYY-MM-DD).
+/// Only days with logged (non-shadow) activity are included.
+#[tauri::command]
+pub async fn get_context_switch_report(
+    db: State<'_, PosDb>,
+    start_date: String,
+    end_date: String,
+) -> PosResult<ContextSwitchReport> {
+    let pool = &db.0;
+    for d in [&start_date, &end_date] {
+        NaiveDate::parse_from_str(d, "%Y-%m-%d")
+            .map_err(|e| PosError::InvalidInput(format!("Invalid date '{}': {}", d, e)))?;
+    }
+    if start_date > end_date {
+        return Err(PosError::InvalidInput("start_date must be on or before end_date".into()));
+    }
+
+    let (timeline, goal_rows) = tokio::try_join!(
+        sqlx::query_as::<_, TimelineRow>(
+            r#"SELECT date, start_time, end_time, category FROM pos_activities
+               WHERE is_shadow = FALSE AND deleted_at IS NULL AND date BETWEEN $1 AND $2
+               ORDER BY date, start_time"#
+        )
+        .bind(&start_date)
+        .bind(&end_date)
+        .fetch_all(pool),
+        sqlx::query_as::<_, (String, i64, i64)>(
+            r#"SELECT date, COUNT(*), COUNT(*) FILTER (WHERE completed)
+               FROM unified_goals
+               WHERE deleted_at IS NULL AND archived_at IS NULL
+                 AND NOT (recurring_pattern IS NOT NULL AND recurring_template_id IS NULL)
+                 AND date BETWEEN $1 AND $2
+               GROUP BY date"#
+        )
+        .bind(&start_date)
+        .bind(&end_date)
+        .fetch_all(pool),
+    )
+    .map_err(|e| db_context("get_context_switch_report", e))?;
+
+    let goals: BTreeMap<String, (i64, i64)> = goal_rows.into_iter().map(|(d, due, done)| (d, (due, done))).collect();
+    let mut by_day: BTreeMap<&str, Vec<&TimelineRow>> = BTreeMap::new();
+    for row in &timeline {
+        by_day.entry(row.date.as_str()).or_default().push(row);
+    }
+
+    let mut all_blocks: Vec<i64> = Vec::new();
+    let mut days: Vec<DaySwitchStats> = by_day.into_iter().map(|(date, activities)| {
+        let (switches, blocks) = day_blocks(&activities);
+        let logged_minutes: i64 = blocks.iter().sum();
+        let (goals_due, goals_completed) = goals.get(date).copied().unwrap_or((0, 0));
+        let stats = DaySwitchStats {
+            date: date.to_string(),
+            switches,
+            blocks: blocks.len() as i64,
+            avg_block_minutes: if blocks.is_empty() { 0.0 } else { logged_minutes as f64 / blocks.len() as f64 },
+            logged_minutes,
+            goals_due,
+            goals_completed,
+            completion_rate: (goals_due > 0).then(|| goals_completed as f64 / goals_due as f64),
+            fragmented: false,
+        };
+        all_blocks.extend(blocks);
+        stats
+    }).collect();
+
+    let median_switches = median(&mut days.iter().map(|d| d.switches).collect::<Vec<_>>());
+    for d in &mut days {
+        d.fragmented = d.switches as f64 > median_switches;
+    }
+
+    let pairs: Vec<(f64, f64)> = days.iter()
+        .filter_map(|d| d.completion_rate.map(|r| (d.switches as f64, r)))
+        .collect();
+    let day_count = days.len().max(1) as f64;
+
+    Ok(ContextSwitchReport {
+        avg_switches_per_day: days.iter().map(|d| d.switches).sum::<i64>() as f64 / day_count,
+        avg_block_minutes: if all_blocks.is_empty() {
+            0.0
+        } else {
+            all_blocks.iter().sum::<i64>() as f64 / all_blocks.len() as f64
+        },
+        median_switches,
+        fragmented_completion_rate: completion_rate(days.iter().filter(|d| d.fragmented)),
+        focused_completion_rate: completion_rate(days.iter().filter(|d| !d.fragmented)),
+        switch_completion_correlation: pearson(&pairs),
+        days,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(start: i64, end: i64, category: &str) -> TimelineRow {
+        let at = |m: i64| DateTime::from_timestamp(m * 60, 0).unwrap();
+        TimelineRow { date: "2024-01-01".into(), start_time: at(start), end_time: at(end), category: category.into() }
+    }
+
+    #[test]
+    fn test_day_blocks_merges_same_category() {
+        let rows = [row(0, 30, "dev"), row(35, 60, "dev"), row(60, 90, "reading"), row(120, 150, "reading"), row(150, 160, "dev")];
+        let refs: Vec<&TimelineRow> = rows.iter().collect();
+        let (switches, blocks) = day_blocks(&refs);
+        assert_eq!(switches, 2);
+        // The 30-minute pause splits the reading block
+        assert_eq!(blocks, vec![55, 30, 30, 10]);
+    }
+
+    #[test]
+    fn test_pearson_and_median() {
+        assert_eq!(median(&mut [3, 1, 2]), 2.0);
+        assert_eq!(median(&mut [4, 1, 2, 3]), 2.5);
+        let r = pearson(&[(1.0, 0.9), (2.0, 0.7), (3.0, 0.5), (4.0, 0.3)]).unwrap();
+        assert!((r + 1.0).abs() < 1e-9);
+        assert_eq!(pearson(&[(1.0, 1.0), (2.0, 1.0)]), None);
+    }
+}
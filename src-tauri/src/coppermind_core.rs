@@ -0,0 +1,170 @@
+// Coppermind Core
+// Tauri-free entry points over the same logic the app commands run, so the CLI
+// (src/bin/coppermind-cli.rs) and scripts can write data without the GUI.
+// Uses the same .env / POS_DATABASE_URL configuration as the app.
+
+use std::time::Duration;
+
+use chrono::Utc;
+use sqlx::postgres::PgPoolOptions;
+use sqlx::PgPool;
+
+pub use crate::pos::activities::{ActivityRow, CreateActivityRequest};
+pub use crate::pos::config::PosConfig as Config;
+pub use crate::pos::error::{PosError, PosResult};
+pub use crate::pos::scrapers::ScraperResponse;
+
+/// Activity categories known to the grid (mirrors ACTIVITY_COLORS in the frontend)
+const KNOWN_CATEGORIES: &[&str] = &[
+    "learning", "leetcode", "codeforces", "cpp", "sleep", "book", "development", "exercise",
+    "college", "food", "family", "entertainment", "commute", "misc", "ncc", "surfing", "bath",
+    "walking", "break", "doom_scroll", "discussion", "freshup",
+];
+const MAX_LOG_MINUTES: i64 = 16 * 60;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Platform {
+    Codeforces,
+    LeetCode,
+    AtCoder,
+    GitHub,
+}
+
+impl Platform {
+    pub fn parse(value: &str) -> PosResult<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "cf" | "codeforces" => Ok(Self::Codeforces),
+            "lc" | "leetcode" => Ok(Self::LeetCode),
+            "ac" | "atcoder" => Ok(Self::AtCoder),
+            "gh" | "github" => Ok(Self::GitHub),
+            other => Err(PosError::InvalidInput(format!(
+                "Unknown platform '{}'. Expected cf, lc, ac or gh", other
+            ))),
+        }
+    }
+}
+
+/// A parsed "30m reading" style entry
+#[derive(Debug, Clone, PartialEq)]
+pub struct QuickLog {
+    pub minutes: i64,
+    pub title: String,
+    /// First title word when it names a known category
+    pub category: Option<String>,
+}
+
+/// Parse `<duration> <title>`. Duration forms: `30m`, `2h`, `1h30m`, `1.5h`, `45`
+/// (bare number = minutes).
+pub fn parse_quick_log(input: &str) -> PosResult<QuickLog> {
+    let input = input.trim();
+    let (duration, title) = input.split_once(char::is_whitespace)
+        .map(|(d, t)| (d, t.trim()))
+        .unwrap_or((input, ""));
+    if title.is_empty() {
+        return Err(PosError::InvalidInput("Expected \"<duration> <title>\", e.g. \"30m reading\"".into()));
+    }
+
+    let d = duration.to_lowercase();
+    let minutes = if let Ok(m) = d.parse::<i64>() {
+        m
+    } else if let Some((h, rest)) = d.split_once('h') {
+        let hours: f64 = h.parse().map_err(|_| bad_duration(duration))?;
+        let extra: i64 = match rest.strip_suffix('m') {
+            Some(m) => m.parse().map_err(|_| bad_duration(duration))?,
+            None if rest.is_empty() => 0,
+            None => return Err(bad_duration(duration)),
+        };
+        (hours * 60.0).round() as i64 + extra
+    } else if let Some(m) = d.strip_suffix("min").or_else(|| d.strip_suffix('m')) {
+        m.parse().map_err(|_| bad_duration(duration))?
+    } else {
+        return Err(bad_duration(duration));
+    };
+
+    if !(1..=MAX_LOG_MINUTES).contains(&minutes) {
+        return Err(PosError::InvalidInput(format!("Duration must be between 1 and {} minutes", MAX_LOG_MINUTES)));
+    }
+
+    let first_word = title.split_whitespace().next().unwrap_or("").to_lowercase();
+    let category = KNOWN_CATEGORIES.contains(&first_word.as_str()).then_some(first_word);
+    Ok(QuickLog { minutes, title: title.to_string(), category })
+}
+
+fn bad_duration(value: &str) -> PosError {
+    PosError::InvalidInput(format!("Invalid duration '{}'. Use e.g. 30m, 2h, 1h30m", value))
+}
+
+/// Open a pool with the app's connection settings. Tables are created by the app.
+pub async fn connect(config: &Config) -> PosResult<PgPool> {
+    crate::pos::timezone::init(config.timezone);
+    PgPoolOptions::new()
+        .max_connections(config.db_max_connections)
+        .acquire_timeout(Duration::from_secs(config.db_connection_timeout_secs))
+        .connect(&config.database_url)
+        .await
+        .map_err(|e| PosError::Database(format!("Failed to connect: {}", e)))
+}
+
+/// Log an activity that ends now, optionally linked to a goal
+pub async fn log_activity(
+    pool: &PgPool,
+    config: &Config,
+    entry: QuickLog,
+    category: Option<String>,
+    goal_id: Option<String>,
+) -> PosResult<ActivityRow> {
+    let end = Utc::now();
+    let start = end - chrono::Duration::minutes(entry.minutes);
+    let req = CreateActivityRequest {
+        start_time: start.to_rfc3339(),
+        end_time: end.to_rfc3339(),
+        category: category.or(entry.category).unwrap_or_else(|| "misc".to_string()),
+        title: entry.title,
+        description: String::new(),
+        is_productive: None,
+        goal_ids: goal_id.map(|g| vec![g]),
+        milestone_id: None,
+        book_id: None,
+        pages_read: None,
+        updates: None,
+        date: Some(crate::pos::timezone::local_date_string(start)),
+        food_items: None,
+    };
+    crate::pos::activities::insert_activity(pool, config.split_activities_at_midnight, req).await
+}
+
+/// Scrape one platform through the shared scrape queue (serialized, cooldown-enforced)
+pub async fn scrape(pool: &PgPool, config: &Config, platform: Platform) -> PosResult<ScraperResponse> {
+    use crate::pos::scrapers::{atcoder, codeforces, github, leetcode, queue};
+    match platform {
+        Platform::Codeforces => queue::run(pool, "codeforces", codeforces::run_codeforces_scrape(pool, config)).await,
+        Platform::LeetCode => queue::run(pool, "leetcode", leetcode::run_leetcode_scrape(pool, config)).await,
+        Platform::AtCoder => queue::run(pool, "atcoder", atcoder::run_atcoder_scrape(pool, config)).await,
+        Platform::GitHub => queue::run(pool, "github", github::fetcher::run_github_scrape(pool, config)).await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_quick_log_durations() {
+        assert_eq!(parse_quick_log("30m reading").unwrap().minutes, 30);
+        assert_eq!(parse_quick_log("2h deep work").unwrap().minutes, 120);
+        assert_eq!(parse_quick_log("1h30m cf upsolve").unwrap().minutes, 90);
+        assert_eq!(parse_quick_log("1.5h book").unwrap().minutes, 90);
+        assert_eq!(parse_quick_log("45 walk").unwrap().minutes, 45);
+        assert!(parse_quick_log("30m").is_err());
+        assert!(parse_quick_log("soon reading").is_err());
+        assert!(parse_quick_log("0m nothing").is_err());
+    }
+
+    #[test]
+    fn test_parse_quick_log_category() {
+        let log = parse_quick_log("25m Exercise pushups").unwrap();
+        assert_eq!(log.title, "Exercise pushups");
+        assert_eq!(log.category.as_deref(), Some("exercise"));
+        assert_eq!(parse_quick_log("30m reading").unwrap().category, None);
+    }
+}
@@ -0,0 +1,402 @@
+// Cross-Platform Topic Gaps
+// Maps Codeforces and LeetCode tags onto shared topics (`tag_taxonomy`), compares solved
+// counts per topic, and suggests unsolved problems on the platform that's lagging.
+
+use std::collections::{HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+use crate::PosDb;
+use crate::pos::error::{PosError, PosResult, db_context};
+use crate::pos::retry::{with_backoff, BackoffPolicy};
+use crate::pos::scrapers::{build_http_client, CODEFORCES_HOST, LEETCODE_HOST};
+
+/// A topic is "strong" with at least this many distinct solves…
+const STRONG_MIN_SOLVED: i64 = 5;
+/// …and the other platform is a gap below this fraction of it
+const WEAK_RATIO: f64 = 0.2;
+const MAX_GAPS_WITH_SUGGESTIONS: usize = 5;
+const SUGGESTIONS_PER_GAP: usize = 3;
+
+/// (platform, platform tag, shared topic). Seeded on first use; editable via set_tag_mapping.
+const DEFAULT_TAXONOMY: &[(&str, &str, &str)] = &[
+    ("codeforces", "dp", "dynamic programming"),
+    ("leetcode", "Dynamic Programming", "dynamic programming"),
+    ("codeforces", "greedy", "greedy"),
+    ("leetcode", "Greedy", "greedy"),
+    ("codeforces", "graphs", "graphs"),
+    ("leetcode", "Graph", "graphs"),
+    ("codeforces", "dfs and similar", "graph traversal"),
+    ("leetcode", "Depth-First Search", "graph traversal"),
+    ("leetcode", "Breadth-First Search", "graph traversal"),
+    ("codeforces", "shortest paths", "shortest paths"),
+    ("leetcode", "Shortest Path", "shortest paths"),
+    ("codeforces", "dsu", "union find"),
+    ("leetcode", "Union Find", "union find"),
+    ("codeforces", "trees", "trees"),
+    ("leetcode", "Tree", "trees"),
+    ("leetcode", "Binary Tree", "trees"),
+    ("codeforces", "binary search", "binary search"),
+    ("leetcode", "Binary Search", "binary search"),
+    ("codeforces", "two pointers", "two pointers"),
+    ("leetcode", "Two Pointers", "two pointers"),
+    ("leetcode", "Sliding Window", "two pointers"),
+    ("codeforces", "sortings", "sorting"),
+    ("leetcode", "Sorting", "sorting"),
+    ("codeforces", "strings", "strings"),
+    ("leetcode", "String", "strings"),
+    ("codeforces", "hashing", "hashing"),
+    ("leetcode", "Hash Table", "hashing"),
+    ("codeforces", "math", "math"),
+    ("leetcode", "Math", "math"),
+    ("codeforces", "number theory", "number theory"),
+    ("leetcode", "Number Theory", "number theory"),
+    ("codeforces", "combinatorics", "combinatorics"),
+    ("leetcode", "Combinatorics", "combinatorics"),
+    ("codeforces", "bitmasks", "bit manipulation"),
+    ("leetcode", "Bit Manipulation", "bit manipulation"),
+    ("codeforces", "geometry", "geometry"),
+    ("leetcode", "Geometry", "geometry"),
+    ("codeforces", "data structures", "data structures"),
+    ("leetcode", "Segment Tree", "data structures"),
+    ("leetcode", "Heap (Priority Queue)", "data structures"),
+    ("leetcode", "Monotonic Stack", "data structures"),
+    ("codeforces", "brute force", "brute force"),
+    ("leetcode", "Backtracking", "brute force"),
+    ("leetcode", "Enumeration", "brute force"),
+    ("codeforces", "implementation", "implementation"),
+    ("leetcode", "Simulation", "implementation"),
+];
+
+// ─── Types ──────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct TagMapping {
+    pub platform: String,
+    pub platform_tag: String,
+    pub topic: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GapSuggestion {
+    pub platform: String,
+    pub problem_id: String,
+    pub title: String,
+    pub url: String,
+    pub difficulty: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TopicGap {
+    pub topic: String,
+    pub strong_platform: String,
+    pub weak_platform: String,
+    pub strong_solved: i64,
+    pub weak_solved: i64,
+    pub suggestions: Vec<GapSuggestion>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CfProblemsetResponse {
+    status: String,
+    result: Option<CfProblemsetResult>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CfProblemsetResult {
+    problems: Vec<CfProblem>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CfProblem {
+    contest_id: Option<i64>,
+    index: String,
+    name: String,
+    rating: Option<i32>,
+}
+
+// ─── Helpers ────────────────────────────────────────────────────────
+
+async fn ensure_default_taxonomy(pool: &sqlx::PgPool) -> PosResult<()> {
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM tag_taxonomy")
+        .fetch_one(pool)
+        .await
+        .map_err(|e| db_context("count tag_taxonomy", e))?;
+    if count > 0 {
+        return Ok(());
+    }
+
+    let (platforms, tags, topics): (Vec<&str>, Vec<&str>, Vec<&str>) = DEFAULT_TAXONOMY.iter()
+        .fold((vec![], vec![], vec![]), |(mut p, mut t, mut o), (a, b, c)| {
+            p.push(*a); t.push(*b); o.push(*c);
+            (p, t, o)
+        });
+
+    sqlx::query(
+        r#"INSERT INTO tag_taxonomy (platform, platform_tag, topic)
+           SELECT * FROM UNNEST($1::text[], $2::text[], $3::text[])
+           ON CONFLICT DO NOTHING"#
+    )
+    .bind(&platforms)
+    .bind(&tags)
+    .bind(&topics)
+    .execute(pool)
+    .await
+    .map_err(|e| db_context("seed tag_taxonomy", e))?;
+
+    log::info!("[GAPS] Seeded {} default tag mappings", DEFAULT_TAXONOMY.len());
+    Ok(())
+}
+
+/// Unsolved Codeforces problems for the given CF tags, nearest to my rating first
+async fn suggest_codeforces(
+    client: &reqwest::Client,
+    cf_tags: &[String],
+    solved: &HashSet<String>,
+    my_rating: Option<i32>,
+) -> PosResult<Vec<GapSuggestion>> {
+    // CF "tags" param is an AND filter, so query each tag and merge
+    let mut candidates: Vec<CfProblem> = Vec::new();
+    for tag in cf_tags {
+        let resp: CfProblemsetResponse = with_backoff(CODEFORCES_HOST, BackoffPolicy::default(), || async {
+            let resp = client
+                .get("https://codeforces.com/api/problemset.problems")
+                .query(&[("tags", tag.as_str())])
+                .send().await?;
+            if !resp.status().is_success() {
+                return Err(PosError::External(format!("HTTP error: {}", resp.status())));
+            }
+            Ok(resp.json::<CfProblemsetResponse>().await?)
+        }).await?;
+        if resp.status != "OK" {
+            return Err(PosError::External("Codeforces problemset API returned non-OK status".into()));
+        }
+        candidates.extend(resp.result.map(|r| r.problems).unwrap_or_default());
+    }
+
+    let target = my_rating.unwrap_or(1200) + 100;
+    let mut seen = HashSet::new();
+    let mut picks: Vec<(i32, GapSuggestion)> = candidates.into_iter()
+        .filter_map(|p| {
+            let contest_id = p.contest_id?;
+            let problem_id = format!("cf-{}{}", contest_id, p.index);
+            if solved.contains(&problem_id) || !seen.insert(problem_id.clone()) {
+                return None;
+            }
+            let rating = p.rating?;
+            Some(((rating - target).abs(), GapSuggestion {
+                platform: "codeforces".into(),
+                url: format!("https://codeforces.com/problemset/problem/{}/{}", contest_id, p.index),
+                problem_id,
+                title: p.name,
+                difficulty: Some(rating.to_string()),
+            }))
+        })
+        .collect();
+
+    picks.sort_by_key(|(distance, _)| *distance);
+    Ok(picks.into_iter().take(SUGGESTIONS_PER_GAP).map(|(_, s)| s).collect())
+}
+
+/// "Heap (Priority Queue)" → "heap-priority-queue"
+fn leetcode_tag_slug(tag: &str) -> String {
+    tag.to_lowercase()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect::<String>()
+        .split('-')
+        .filter(|s| !s.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+/// Unsolved LeetCode Medium problems for the given tags (problemset GraphQL)
+async fn suggest_leetcode(
+    client: &reqwest::Client,
+    lc_tags: &[String],
+    solved: &HashSet<String>,
+) -> PosResult<Vec<GapSuggestion>> {
+    let query = r#"
+        query problemsetQuestionList($filters: QuestionListFilterInput, $limit: Int) {
+            problemsetQuestionList: questionList(categorySlug: "", limit: $limit, skip: 0, filters: $filters) {
+                data { title titleSlug difficulty isPaidOnly }
+            }
+        }
+    "#;
+    let slugs: Vec<String> = lc_tags.iter().map(|t| leetcode_tag_slug(t)).collect();
+    let body = serde_json::json!({
+        "query": query,
+        "variables": { "limit": 50, "filters": { "tags": slugs, "difficulty": "MEDIUM" } }
+    });
+
+    let resp: serde_json::Value = with_backoff(LEETCODE_HOST, BackoffPolicy::default(), || async {
+        let resp = client
+            .post("https://leetcode.com/graphql")
+            .header("Content-Type", "application/json")
+            .header("Referer", "https://leetcode.com")
+            .json(&body)
+            .send().await?;
+        if !resp.status().is_success() {
+            return Err(PosError::External(format!("HTTP error: {}", resp.status())));
+        }
+        Ok(resp.json::<serde_json::Value>().await?)
+    }).await?;
+
+    let questions = resp.pointer("/data/problemsetQuestionList/data")
+        .and_then(|d| d.as_array())
+        .cloned()
+        .unwrap_or_default();
+
+    Ok(questions.iter()
+        .filter(|q| !q.get("isPaidOnly").and_then(|v| v.as_bool()).unwrap_or(false))
+        .filter_map(|q| {
+            let slug = q.get("titleSlug")?.as_str()?;
+            let problem_id = format!("leetcode-{}", slug);
+            if solved.contains(&problem_id) {
+                return None;
+            }
+            Some(GapSuggestion {
+                platform: "leetcode".into(),
+                problem_id,
+                title: q.get("title")?.as_str()?.to_string(),
+                url: format!("https://leetcode.com/problems/{}/", slug),
+                difficulty: q.get("difficulty").and_then(|d| d.as_str()).map(String::from),
+            })
+        })
+        .take(SUGGESTIONS_PER_GAP)
+        .collect())
+}
+
+// ─── Commands ───────────────────────────────────────────────────────
+
+#[tauri::command]
+pub async fn get_tag_taxonomy(db: State<'_, PosDb>) -> PosResult<Vec<TagMapping>> {
+    ensure_default_taxonomy(&db.0).await?;
+    sqlx::query_as::<_, TagMapping>(
+        "SELECT platform, platform_tag, topic FROM tag_taxonomy ORDER BY topic, platform, platform_tag"
+    )
+    .fetch_all(&db.0)
+    .await
+    .map_err(|e| db_context("get_tag_taxonomy", e))
+}
+
+/// Add or remap one platform tag to a shared topic
+#[tauri::command]
+pub async fn set_tag_mapping(db: State<'_, PosDb>, mapping: TagMapping) -> PosResult<TagMapping> {
+    if !matches!(mapping.platform.as_str(), "codeforces" | "leetcode") {
+        return Err(PosError::InvalidInput("platform must be codeforces or leetcode".into()));
+    }
+    if mapping.platform_tag.trim().is_empty() || mapping.topic.trim().is_empty() {
+        return Err(PosError::InvalidInput("platform_tag and topic are required".into()));
+    }
+    ensure_default_taxonomy(&db.0).await?;
+
+    sqlx::query_as::<_, TagMapping>(
+        r#"INSERT INTO tag_taxonomy (platform, platform_tag, topic) VALUES ($1, $2, $3)
+           ON CONFLICT (platform, platform_tag) DO UPDATE SET topic = EXCLUDED.topic
+           RETURNING platform, platform_tag, topic"#
+    )
+    .bind(&mapping.platform)
+    .bind(mapping.platform_tag.trim())
+    .bind(mapping.topic.trim().to_lowercase())
+    .fetch_one(&db.0)
+    .await
+    .map_err(|e| db_context("set_tag_mapping", e))
+}
+
+/// Topics strong on one platform (≥ STRONG_MIN_SOLVED distinct solves) but nearly absent
+/// on the other, largest gap first. The top gaps include unsolved problem suggestions;
+/// if a platform API is unreachable the gap is still reported without suggestions.
+#[tauri::command]
+pub async fn get_cross_platform_gaps(db: State<'_, PosDb>) -> PosResult<Vec<TopicGap>> {
+    let pool = &db.0;
+    ensure_default_taxonomy(pool).await?;
+
+    let (counts, mappings, solved_ids, user_stats) = tokio::try_join!(
+        sqlx::query_as::<_, (String, String, i64)>(
+            r#"SELECT t.topic, s.platform, COUNT(DISTINCT s.problem_id)::bigint
+               FROM pos_submissions s
+               CROSS JOIN LATERAL UNNEST(s.tags) AS tag
+               JOIN tag_taxonomy t ON t.platform = s.platform AND LOWER(t.platform_tag) = LOWER(tag)
+               WHERE s.verdict IN ('OK', 'Accepted') AND s.excluded_at IS NULL
+               GROUP BY t.topic, s.platform"#
+        ).fetch_all(pool),
+
+        sqlx::query_as::<_, TagMapping>("SELECT platform, platform_tag, topic FROM tag_taxonomy")
+            .fetch_all(pool),
+
+        sqlx::query_scalar::<_, String>(
+            "SELECT DISTINCT problem_id FROM solved_problems"
+        ).fetch_all(pool),
+
+        sqlx::query_scalar::<_, Option<serde_json::Value>>(
+            "SELECT data FROM platform_cache WHERE platform = 'codeforces' AND resource = 'user_stats'"
+        ).fetch_optional(pool),
+    ).map_err(|e| db_context("get_cross_platform_gaps", e))?;
+
+    let my_rating = user_stats.flatten()
+        .and_then(|d| d.get("rating").and_then(|r| r.as_i64()))
+        .map(|r| r as i32);
+    let solved: HashSet<String> = solved_ids.into_iter().collect();
+
+    // topic → (codeforces, leetcode)
+    let mut by_topic: HashMap<String, (i64, i64)> = HashMap::new();
+    for m in &mappings {
+        by_topic.entry(m.topic.clone()).or_default();
+    }
+    for (topic, platform, n) in counts {
+        let entry = by_topic.entry(topic).or_default();
+        match platform.as_str() {
+            "codeforces" => entry.0 += n,
+            "leetcode" => entry.1 += n,
+            _ => {}
+        }
+    }
+
+    let mut gaps: Vec<TopicGap> = by_topic.into_iter().filter_map(|(topic, (cf, lc))| {
+        let (strong_platform, weak_platform, strong, weak) = if cf >= lc {
+            ("codeforces", "leetcode", cf, lc)
+        } else {
+            ("leetcode", "codeforces", lc, cf)
+        };
+        let is_gap = strong >= STRONG_MIN_SOLVED && (weak as f64) < strong as f64 * WEAK_RATIO;
+        is_gap.then(|| TopicGap {
+            topic,
+            strong_platform: strong_platform.into(),
+            weak_platform: weak_platform.into(),
+            strong_solved: strong,
+            weak_solved: weak,
+            suggestions: Vec::new(),
+        })
+    }).collect();
+
+    gaps.sort_by(|a, b| (b.strong_solved - b.weak_solved).cmp(&(a.strong_solved - a.weak_solved)));
+
+    let client = build_http_client();
+    for gap in gaps.iter_mut().take(MAX_GAPS_WITH_SUGGESTIONS) {
+        let tags: Vec<String> = mappings.iter()
+            .filter(|m| m.topic == gap.topic && m.platform == gap.weak_platform)
+            .map(|m| m.platform_tag.clone())
+            .collect();
+        if tags.is_empty() {
+            continue;
+        }
+        let result = if gap.weak_platform == "codeforces" {
+            suggest_codeforces(&client, &tags, &solved, my_rating).await
+        } else {
+            suggest_leetcode(&client, &tags, &solved).await
+        };
+        match result {
+            Ok(s) => gap.suggestions = s,
+            Err(e) => log::warn!("[GAPS] No suggestions for '{}' on {}: {}", gap.topic, gap.weak_platform, e),
+        }
+    }
+
+    Ok(gaps)
+}
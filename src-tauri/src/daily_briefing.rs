@@ -75,7 +75,7 @@ pub async fn get_daily_briefing(
 
     // 3. Query active milestones (period_start <= date AND period_end >= date)
     let milestone_rows = sqlx::query_as::<_, MilestoneRow>(
-        "SELECT id, target_metric, target_value, daily_amount, period_type, period_start, period_end, current_value, problem_id, unit, created_at, updated_at FROM goal_periods WHERE period_start <= $1 AND period_end >= $1 ORDER BY period_start ASC"
+        "SELECT id, target_metric, target_value, daily_amount, period_type, period_start, period_end, current_value, problem_id, unit, created_at, updated_at FROM goal_periods WHERE period_start <= $1 AND period_end >= $1 AND deleted_at IS NULL ORDER BY period_start ASC"
     )
     .bind(date_parsed)
     .fetch_all(pool)
@@ -0,0 +1,296 @@
+// Dashboard Snapshot
+// Pre-computed single-payload dashboard, cached in `dashboard_snapshots` per local date.
+// Rebuilt when older than SNAPSHOT_TTL_MINUTES or after a write/scrape marked it stale.
+
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use serde::Serialize;
+use sqlx::PgPool;
+use tauri::{Manager, State};
+
+use crate::PosDb;
+use crate::pos::error::{PosError, PosResult, db_context};
+use crate::pos::timezone;
+use crate::unified_goals::{UnifiedGoalRow, UNIFIED_GOAL_COLS};
+
+const SNAPSHOT_TTL_MINUTES: i64 = 10;
+
+// ─── Response types ─────────────────────────────────────────────────
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DashboardStats {
+    pub logged_minutes: i64,
+    pub productive_minutes: i64,
+    pub goals_total: i64,
+    pub goals_completed: i64,
+    pub submissions_today: i64,
+    pub accepted_today: i64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DashboardSnapshot {
+    pub date: String,
+    pub goals: Vec<UnifiedGoalRow>,
+    pub stats: DashboardStats,
+    pub activity_streak_days: i32,
+    pub debt_count: i64,
+    pub recommended_problem_ids: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DashboardSnapshotResponse {
+    pub date: String,
+    pub refreshed_at: DateTime<Utc>,
+    pub from_cache: bool,
+    pub snapshot: serde_json::Value,
+}
+
+#[derive(Debug, Serialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct WidgetGoal {
+    pub id: String,
+    pub text: String,
+    pub priority: String,
+    pub urgent: bool,
+    pub is_debt: bool,
+}
+
+#[derive(Debug, Serialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct WidgetLiveActivity {
+    pub id: String,
+    pub title: String,
+    pub category: String,
+    pub start_time: DateTime<Utc>,
+    pub end_time: DateTime<Utc>,
+}
+
+/// Compact payload for the desktop widget. `available` is false when the database
+/// isn't connected (yet) in this process; every other field is then empty.
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WidgetSummary {
+    pub available: bool,
+    pub date: String,
+    pub top_goals: Vec<WidgetGoal>,
+    pub next_recommendation: Option<String>,
+    pub streak_days: i32,
+    pub live_activity: Option<WidgetLiveActivity>,
+}
+
+#[derive(sqlx::FromRow)]
+struct CachedSnapshotRow {
+    data: serde_json::Value,
+    refreshed_at: DateTime<Utc>,
+    is_stale: bool,
+}
+
+// ─── Helpers ────────────────────────────────────────────────────────
+
+/// Mark every cached snapshot stale. Called after writes/scrapes; never fails the caller.
+pub async fn mark_snapshot_stale(pool: &PgPool) {
+    if let Err(e) = sqlx::query("UPDATE dashboard_snapshots SET is_stale = TRUE WHERE is_stale = FALSE")
+        .execute(pool)
+        .await
+    {
+        log::warn!("[DASHBOARD] Failed to invalidate snapshot: {}", e);
+    }
+}
+
+/// Consecutive days (ending today, or yesterday if today is still empty) with a non-shadow activity
+fn compute_streak(today: NaiveDate, active_dates: &[String]) -> i32 {
+    let set: std::collections::HashSet<&str> = active_dates.iter().map(|d| d.as_str()).collect();
+    let mut day = today;
+    if !set.contains(day.format("%Y-%m-%d").to_string().as_str()) {
+        day -= Duration::days(1);
+    }
+    let mut streak = 0;
+    while set.contains(day.format("%Y-%m-%d").to_string().as_str()) {
+        streak += 1;
+        day -= Duration::days(1);
+    }
+    streak
+}
+
+async fn build_snapshot(pool: &PgPool, date: &str, today: NaiveDate) -> PosResult<DashboardSnapshot> {
+    let streak_floor = (today - Duration::days(366)).format("%Y-%m-%d").to_string();
+    // Submissions are bucketed by the local day, like activities and goals
+    let (day_from, day_until) = timezone::day_start(today)
+        .and_then(|from| Some((from, timezone::next_midnight(from)?)))
+        .ok_or_else(|| PosError::InvalidInput(format!("No local day bounds for {}", date)))?;
+
+    let (goals, activity_totals, submission_totals, debt_count, active_dates, recs) = tokio::try_join!(
+        sqlx::query_as::<_, UnifiedGoalRow>(&format!(
+            "SELECT {} FROM unified_goals WHERE date = $1 AND archived_at IS NULL ORDER BY completed ASC, priority DESC, created_at ASC",
+            UNIFIED_GOAL_COLS
+        )).bind(date).fetch_all(pool),
+
+        sqlx::query_as::<_, (i64, i64)>(
+            r#"SELECT COALESCE(SUM(EXTRACT(EPOCH FROM (end_time - start_time)) / 60), 0)::bigint,
+                      COALESCE(SUM(EXTRACT(EPOCH FROM (end_time - start_time)) / 60) FILTER (WHERE is_productive), 0)::bigint
+               FROM pos_activities WHERE date = $1 AND is_shadow = FALSE AND deleted_at IS NULL"#
+        ).bind(date).fetch_one(pool),
+
+        sqlx::query_as::<_, (i64, i64)>(
+            r#"SELECT COUNT(*), COUNT(*) FILTER (WHERE verdict IN ('OK', 'Accepted'))
+               FROM pos_submissions WHERE submitted_time >= $1 AND submitted_time < $2 AND excluded_at IS NULL"#
+        ).bind(day_from).bind(day_until).fetch_one(pool),
+
+        sqlx::query_scalar::<_, i64>(
+            "SELECT COUNT(*) FROM unified_goals WHERE is_debt = TRUE AND completed = FALSE AND archived_at IS NULL"
+        ).fetch_one(pool),
+
+        sqlx::query_scalar::<_, String>(
+            "SELECT DISTINCT date FROM pos_activities WHERE is_shadow = FALSE AND deleted_at IS NULL AND date >= $1 AND date <= $2"
+        ).bind(&streak_floor).bind(date).fetch_all(pool),
+
+        sqlx::query_scalar::<_, Vec<String>>(
+            "SELECT problem_ids FROM cf_daily_recommendations WHERE date = $1::date"
+        ).bind(date).fetch_optional(pool),
+    ).map_err(|e| db_context("build dashboard snapshot", e))?;
+
+    let goals_completed = goals.iter().filter(|g| g.completed).count() as i64;
+
+    Ok(DashboardSnapshot {
+        date: date.to_string(),
+        stats: DashboardStats {
+            logged_minutes: activity_totals.0,
+            productive_minutes: activity_totals.1,
+            goals_total: goals.len() as i64,
+            goals_completed,
+            submissions_today: submission_totals.0,
+            accepted_today: submission_totals.1,
+        },
+        goals,
+        activity_streak_days: compute_streak(today, &active_dates),
+        debt_count,
+        recommended_problem_ids: recs.unwrap_or_default(),
+    })
+}
+
+// ─── Commands ───────────────────────────────────────────────────────
+
+/// Single-payload dashboard: today's goals, stats, streak, debt count, recommendations.
+/// Served from `dashboard_snapshots` unless stale, expired, or `force_refresh` is set.
+#[tauri::command]
+pub async fn get_dashboard_snapshot(
+    db: State<'_, PosDb>,
+    local_date: Option<String>,   // YYYY-MM-DD, defaults to today (local)
+    force_refresh: Option<bool>,
+) -> PosResult<DashboardSnapshotResponse> {
+    let pool = &db.0;
+    let date = local_date.unwrap_or_else(timezone::today_string);
+    let today = NaiveDate::parse_from_str(&date, "%Y-%m-%d")
+        .map_err(|e| PosError::InvalidInput(format!("Invalid date: {}", e)))?;
+
+    if !force_refresh.unwrap_or(false) {
+        let cached = sqlx::query_as::<_, CachedSnapshotRow>(
+            "SELECT data, refreshed_at, is_stale FROM dashboard_snapshots WHERE snapshot_date = $1"
+        )
+        .bind(&date)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| db_context("fetch dashboard snapshot", e))?;
+
+        if let Some(row) = cached {
+            let fresh = Utc::now() - row.refreshed_at < Duration::minutes(SNAPSHOT_TTL_MINUTES);
+            if fresh && !row.is_stale {
+                return Ok(DashboardSnapshotResponse {
+                    date,
+                    refreshed_at: row.refreshed_at,
+                    from_cache: true,
+                    snapshot: row.data,
+                });
+            }
+        }
+    }
+
+    let snapshot = build_snapshot(pool, &date, today).await?;
+    let data = serde_json::to_value(&snapshot)
+        .map_err(|e| PosError::InvalidInput(format!("Failed to serialize snapshot: {}", e)))?;
+    let now = Utc::now();
+
+    sqlx::query(
+        r#"INSERT INTO dashboard_snapshots (snapshot_date, data, refreshed_at, is_stale)
+           VALUES ($1, $2, $3, FALSE)
+           ON CONFLICT (snapshot_date) DO UPDATE
+           SET data = EXCLUDED.data, refreshed_at = EXCLUDED.refreshed_at, is_stale = FALSE"#
+    )
+    .bind(&date)
+    .bind(&data)
+    .bind(now)
+    .execute(pool)
+    .await
+    .map_err(|e| db_context("store dashboard snapshot", e))?;
+
+    log::info!("[DASHBOARD] Rebuilt snapshot for {}", date);
+    Ok(DashboardSnapshotResponse { date, refreshed_at: now, from_cache: false, snapshot: data })
+}
+
+/// Lightweight "today" data for the widget process: top 3 open goals, next unsolved
+/// recommendation, activity streak and the activity covering the current moment.
+/// Reads only the DB pool, via `try_state`, so it never panics in widget mode where
+/// config or the pool may be missing.
+#[tauri::command]
+pub async fn get_widget_summary(
+    app: tauri::AppHandle,
+    local_date: Option<String>,   // YYYY-MM-DD, defaults to today (local)
+) -> PosResult<WidgetSummary> {
+    let date = local_date.unwrap_or_else(timezone::today_string);
+    let today = NaiveDate::parse_from_str(&date, "%Y-%m-%d")
+        .map_err(|e| PosError::InvalidInput(format!("Invalid date: {}", e)))?;
+
+    let Some(db) = app.try_state::<PosDb>() else {
+        return Ok(WidgetSummary { date, ..Default::default() });
+    };
+    let pool = &db.0;
+    let streak_floor = (today - Duration::days(366)).format("%Y-%m-%d").to_string();
+
+    let (top_goals, next_recommendation, active_dates, live_activity) = tokio::try_join!(
+        sqlx::query_as::<_, WidgetGoal>(
+            r#"SELECT id, text, priority, urgent, is_debt
+               FROM unified_goals
+               WHERE date = $1 AND completed = FALSE AND archived_at IS NULL
+               ORDER BY urgent DESC,
+                        CASE priority WHEN 'high' THEN 0 WHEN 'medium' THEN 1 ELSE 2 END,
+                        created_at ASC
+               LIMIT 3"#
+        ).bind(&date).fetch_all(pool),
+
+        sqlx::query_scalar::<_, String>(
+            r#"SELECT r.pid
+               FROM cf_daily_recommendations d
+               CROSS JOIN LATERAL UNNEST(d.problem_ids) WITH ORDINALITY AS r(pid, ord)
+               WHERE d.date = $1::date
+                 AND NOT EXISTS (
+                     SELECT 1 FROM solved_problems s
+                     WHERE s.problem_id IN (r.pid, 'cf-' || r.pid)
+                 )
+               ORDER BY r.ord
+               LIMIT 1"#
+        ).bind(&date).fetch_optional(pool),
+
+        sqlx::query_scalar::<_, String>(
+            "SELECT DISTINCT date FROM pos_activities WHERE is_shadow = FALSE AND deleted_at IS NULL AND date >= $1 AND date <= $2"
+        ).bind(&streak_floor).bind(&date).fetch_all(pool),
+
+        sqlx::query_as::<_, WidgetLiveActivity>(
+            r#"SELECT id, title, category, start_time, end_time
+               FROM pos_activities
+               WHERE is_shadow = FALSE AND deleted_at IS NULL AND start_time <= NOW() AND end_time > NOW()
+               ORDER BY start_time DESC
+               LIMIT 1"#
+        ).fetch_optional(pool),
+    ).map_err(|e| db_context("get_widget_summary", e))?;
+
+    Ok(WidgetSummary {
+        available: true,
+        date,
+        top_goals,
+        next_recommendation,
+        streak_days: compute_streak(today, &active_dates),
+        live_activity,
+    })
+}
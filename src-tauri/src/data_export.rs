@@ -0,0 +1,122 @@
+// Data Export
+// Full backup of the POS database as one portable JSON file: every table in the schema,
+// each row as a JSON object keyed by column name. Tables are discovered at export time,
+// so new tables are included without touching this module.
+
+use std::path::PathBuf;
+
+use chrono::Utc;
+use serde::Serialize;
+use serde_json::json;
+use tauri::{AppHandle, Manager, State};
+use tokio::io::AsyncWriteExt;
+
+use crate::PosDb;
+use crate::pos::error::{PosError, PosResult, db_context};
+
+/// Bumped when the archive layout changes
+const EXPORT_FORMAT_VERSION: u32 = 1;
+
+// ─── Types ──────────────────────────────────────────────────────────
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportedTable {
+    pub name: String,
+    pub rows: i64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportSummary {
+    pub path: String,
+    pub bytes: u64,
+    pub tables: Vec<ExportedTable>,
+    pub total_rows: i64,
+}
+
+// ─── Helpers ────────────────────────────────────────────────────────
+
+fn default_export_path(app: &AppHandle) -> PosResult<PathBuf> {
+    let dir = app.path().app_data_dir()
+        .map_err(|e| PosError::External(format!("No app data dir: {}", e)))?
+        .join("exports");
+    std::fs::create_dir_all(&dir)
+        .map_err(|e| PosError::External(format!("Failed to create exports dir: {}", e)))?;
+    Ok(dir.join(format!("coppermind-export-{}.json", Utc::now().format("%Y%m%d-%H%M%S"))))
+}
+
+fn io_err(e: std::io::Error) -> PosError {
+    PosError::External(format!("Export write failed: {}", e))
+}
+
+// ─── Commands ───────────────────────────────────────────────────────
+
+/// Dump every table into `path` (default: app data `exports/` dir). Written table by
+/// table inside one read-only repeatable-read transaction, so the archive is a
+/// consistent snapshot without holding all rows in memory at once.
+///
+/// Layout: `{ formatVersion, exportedAt, tables: { <name>: [ {column: value}… ] } }`
+#[tauri::command]
+pub async fn export_all_data(
+    app: AppHandle,
+    db: State<'_, PosDb>,
+    path: Option<String>,
+) -> PosResult<ExportSummary> {
+    let path = match path.map(|p| p.trim().to_string()).filter(|p| !p.is_empty()) {
+        Some(p) => PathBuf::from(p),
+        None => default_export_path(&app)?,
+    };
+
+    let mut tx = db.0.begin().await.map_err(|e| db_context("TX begin", e))?;
+    sqlx::query("SET TRANSACTION ISOLATION LEVEL REPEATABLE READ READ ONLY")
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| db_context("export snapshot", e))?;
+
+    let table_names: Vec<String> = sqlx::query_scalar(
+        r#"SELECT table_name::text FROM information_schema.tables
+           WHERE table_schema = current_schema() AND table_type = 'BASE TABLE'
+           ORDER BY table_name"#
+    )
+    .fetch_all(&mut *tx)
+    .await
+    .map_err(|e| db_context("list tables", e))?;
+
+    let mut file = tokio::fs::File::create(&path).await.map_err(io_err)?;
+    let header = format!(
+        "{{\"formatVersion\":{},\"exportedAt\":{},\"tables\":{{",
+        EXPORT_FORMAT_VERSION, json!(Utc::now())
+    );
+    file.write_all(header.as_bytes()).await.map_err(io_err)?;
+
+    let mut tables = Vec::with_capacity(table_names.len());
+    for (i, name) in table_names.iter().enumerate() {
+        let (rows_json, rows): (String, i64) = sqlx::query_as(&format!(
+            r#"SELECT COALESCE(jsonb_agg(to_jsonb(t)), '[]'::jsonb)::text, COUNT(*) FROM "{}" t"#,
+            name.replace('"', "\"\"")
+        ))
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| db_context(&format!("export {}", name), e))?;
+
+        let sep = if i == 0 { "" } else { "," };
+        file.write_all(format!("{}{}:", sep, json!(name)).as_bytes()).await.map_err(io_err)?;
+        file.write_all(rows_json.as_bytes()).await.map_err(io_err)?;
+        tables.push(ExportedTable { name: name.clone(), rows });
+    }
+    file.write_all(b"}}").await.map_err(io_err)?;
+    file.flush().await.map_err(io_err)?;
+    tx.commit().await.map_err(|e| db_context("TX commit", e))?;
+
+    let bytes = tokio::fs::metadata(&path).await.map_err(io_err)?.len();
+    let total_rows = tables.iter().map(|t| t.rows).sum();
+    log::info!("[EXPORT] {} tables, {} rows → {} ({} bytes)", tables.len(), total_rows, path.display(), bytes);
+
+    Ok(ExportSummary {
+        path: path.to_string_lossy().to_string(),
+        bytes,
+        tables,
+        total_rows,
+    })
+}
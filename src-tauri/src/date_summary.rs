@@ -186,7 +186,7 @@ pub async fn get_yearly_graph_data(
     let act_rows = sqlx::query_as::<_, ActivityRow>(
         r#"SELECT id, date, title, category, start_time, end_time, is_productive
            FROM pos_activities
-           WHERE date >= $1 AND date <= $2
+           WHERE date >= $1 AND date <= $2 AND deleted_at IS NULL
            ORDER BY date ASC, start_time ASC"#,
     )
     .bind(&year_start).bind(&year_end)
@@ -220,7 +220,7 @@ pub async fn get_yearly_graph_data(
         r#"SELECT id, submitted_time::date::text AS date_str,
                   platform, problem_title, verdict, submitted_time, difficulty
            FROM pos_submissions
-           WHERE EXTRACT(YEAR FROM submitted_time) = $1
+           WHERE EXTRACT(YEAR FROM submitted_time) = $1 AND excluded_at IS NULL
            ORDER BY submitted_time ASC"#,
     )
     .bind(year).fetch_all(pool).await
@@ -0,0 +1,386 @@
+// Day Capacity
+// How much of a day is already spoken for. Capacity is the day's working window from
+// capacity_profile (per weekday; DEFAULT_WORK_START–DEFAULT_WORK_END when unset) minus the
+// fixed routine blocks inside it. Each open goal costs its remaining time-metric target,
+// else the label effort estimate goal ordering uses. When the goals don't fit, the
+// lowest-priority non-urgent ones are suggested for deferral to the next days with room.
+// create_unified_goal runs the check and emits `day-overcommitted` for a full day.
+
+use std::collections::HashMap;
+
+use chrono::{Datelike, Duration, NaiveDate, NaiveTime};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use tauri::{AppHandle, Emitter, State};
+
+use crate::PosDb;
+use crate::goal_ordering::{estimate_minutes, EFFORT_HISTORY_DAYS, LABEL_EFFORT_SQL};
+use crate::pos::error::{PosError, PosResult, db_context};
+use crate::pos::units::lookup_unit;
+use crate::unified_goals::UnifiedGoalMetric;
+
+const DEFAULT_WORK_START: &str = "09:00";
+const DEFAULT_WORK_END: &str = "18:00";
+/// Days after the checked one searched for room for deferred goals
+const DEFERRAL_LOOKAHEAD_DAYS: i64 = 7;
+const OVERCOMMITTED_EVENT: &str = "day-overcommitted";
+
+// ─── Types ──────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RoutineBlock {
+    pub label: String,
+    pub start: String, // HH:MM
+    pub end: String,   // HH:MM
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CapacityProfileDay {
+    /// 0 = Monday … 6 = Sunday
+    pub weekday: i16,
+    pub work_start: String, // HH:MM
+    pub work_end: String,   // HH:MM
+    pub routine_blocks: Vec<RoutineBlock>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GoalLoad {
+    pub id: String,
+    pub text: String,
+    pub priority: String,
+    pub urgent: bool,
+    pub is_debt: bool,
+    pub estimated_minutes: i64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Deferral {
+    pub goal_id: String,
+    pub text: String,
+    pub estimated_minutes: i64,
+    /// First following day with room, None when the lookahead is full too
+    pub to_date: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DayCapacity {
+    pub date: String,
+    pub work_minutes: i64,
+    pub routine_minutes: i64,
+    /// Working minutes left for goals once routines are taken out
+    pub available_minutes: i64,
+    pub goal_minutes: i64,
+    pub overcommitted_minutes: i64,
+    pub goals: Vec<GoalLoad>,
+    pub suggested_deferrals: Vec<Deferral>,
+    /// Set when the goals don't fit
+    pub warning: Option<String>,
+}
+
+#[derive(sqlx::FromRow)]
+struct OpenGoalRow {
+    id: String,
+    text: String,
+    date: String,
+    priority: Option<String>,
+    urgent: Option<bool>,
+    is_debt: Option<bool>,
+    metrics: Option<sqlx::types::Json<Vec<UnifiedGoalMetric>>>,
+    labels: Option<sqlx::types::Json<Vec<String>>>,
+}
+
+// ─── Helpers ────────────────────────────────────────────────────────
+
+fn parse_hm(s: &str) -> PosResult<NaiveTime> {
+    NaiveTime::parse_from_str(s.trim(), "%H:%M")
+        .map_err(|_| PosError::InvalidInput(format!("Invalid time '{}', expected HH:MM", s)))
+}
+
+fn default_day(weekday: i16) -> CapacityProfileDay {
+    CapacityProfileDay {
+        weekday,
+        work_start: DEFAULT_WORK_START.into(),
+        work_end: DEFAULT_WORK_END.into(),
+        routine_blocks: Vec::new(),
+    }
+}
+
+/// (working minutes, routine minutes inside the window); overlapping routines count once
+fn window_minutes(start: NaiveTime, end: NaiveTime, routines: &[(NaiveTime, NaiveTime)]) -> (i64, i64) {
+    let work = (end - start).num_minutes().max(0);
+    let mut clipped: Vec<(NaiveTime, NaiveTime)> = routines.iter()
+        .map(|&(s, e)| (s.max(start), e.min(end)))
+        .filter(|(s, e)| s < e)
+        .collect();
+    clipped.sort();
+
+    let mut routine = 0;
+    let mut current: Option<(NaiveTime, NaiveTime)> = None;
+    for (s, e) in clipped {
+        current = match current {
+            Some((cs, ce)) if s <= ce => Some((cs, ce.max(e))),
+            Some((cs, ce)) => {
+                routine += (ce - cs).num_minutes();
+                Some((s, e))
+            }
+            None => Some((s, e)),
+        };
+    }
+    if let Some((cs, ce)) = current {
+        routine += (ce - cs).num_minutes();
+    }
+    (work, routine)
+}
+
+/// Minutes a day's profile leaves for goals: (work, routine, available)
+fn day_minutes(profile: &CapacityProfileDay) -> PosResult<(i64, i64, i64)> {
+    let routines = profile.routine_blocks.iter()
+        .map(|b| Ok((parse_hm(&b.start)?, parse_hm(&b.end)?)))
+        .collect::<PosResult<Vec<_>>>()?;
+    let (work, routine) = window_minutes(parse_hm(&profile.work_start)?, parse_hm(&profile.work_end)?, &routines);
+    Ok((work, routine, (work - routine).max(0)))
+}
+
+/// Remaining time-metric target in minutes, if the goal has one
+fn metric_minutes(metrics: &[UnifiedGoalMetric]) -> Option<i64> {
+    let timed: Vec<f64> = metrics.iter()
+        .filter_map(|m| {
+            let unit = lookup_unit(&m.unit).filter(|u| u.dimension == "time")?;
+            Some((m.target - m.current).max(0.0) * unit.factor)
+        })
+        .collect();
+    (!timed.is_empty()).then(|| timed.iter().sum::<f64>().round() as i64)
+}
+
+/// Lowest priority first, then the longest, never urgent goals; enough to cover `overflow`
+fn pick_deferrals(goals: &[GoalLoad], overflow: i64) -> Vec<&GoalLoad> {
+    let rank = |p: &str| match p {
+        "low" => 0,
+        "medium" => 1,
+        _ => 2,
+    };
+    let mut candidates: Vec<&GoalLoad> = goals.iter().filter(|g| !g.urgent).collect();
+    candidates.sort_by(|a, b| rank(&a.priority).cmp(&rank(&b.priority))
+        .then(b.estimated_minutes.cmp(&a.estimated_minutes)));
+
+    let mut covered = 0;
+    candidates.into_iter()
+        .take_while(|g| {
+            let needed = covered < overflow;
+            covered += g.estimated_minutes;
+            needed
+        })
+        .collect()
+}
+
+async fn load_profile(pool: &PgPool) -> PosResult<Vec<CapacityProfileDay>> {
+    let rows: Vec<(i16, NaiveTime, NaiveTime, sqlx::types::Json<Vec<RoutineBlock>>)> = sqlx::query_as(
+        "SELECT weekday, work_start, work_end, routine_blocks FROM capacity_profile"
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| db_context("load capacity_profile", e))?;
+
+    let mut days: Vec<CapacityProfileDay> = (0..7).map(default_day).collect();
+    for (weekday, start, end, sqlx::types::Json(routine_blocks)) in rows {
+        if let Some(day) = days.get_mut(weekday as usize) {
+            *day = CapacityProfileDay {
+                weekday,
+                work_start: start.format("%H:%M").to_string(),
+                work_end: end.format("%H:%M").to_string(),
+                routine_blocks,
+            };
+        }
+    }
+    Ok(days)
+}
+
+/// Capacity of `date`, with deferrals planned against the following days
+pub(crate) async fn day_capacity(pool: &PgPool, date: NaiveDate) -> PosResult<DayCapacity> {
+    let last = date + Duration::days(DEFERRAL_LOOKAHEAD_DAYS);
+    let profile = load_profile(pool).await?;
+    let (goal_rows, label_effort) = tokio::try_join!(
+        sqlx::query_as::<_, OpenGoalRow>(
+            r#"SELECT id, text, date, priority, urgent, is_debt, metrics, labels
+               FROM unified_goals
+               WHERE date BETWEEN $1 AND $2 AND completed = FALSE
+                 AND deleted_at IS NULL AND archived_at IS NULL
+                 AND NOT (recurring_pattern IS NOT NULL AND recurring_template_id IS NULL)
+               ORDER BY created_at"#
+        )
+        .bind(date.format("%Y-%m-%d").to_string())
+        .bind(last.format("%Y-%m-%d").to_string())
+        .fetch_all(pool),
+        sqlx::query_as::<_, (String, f64)>(LABEL_EFFORT_SQL).bind(EFFORT_HISTORY_DAYS).fetch_all(pool),
+    )
+    .map_err(|e| db_context("day_capacity", e))?;
+    let label_effort: HashMap<String, f64> = label_effort.into_iter().collect();
+
+    let mut loads: HashMap<String, Vec<GoalLoad>> = HashMap::new();
+    for g in goal_rows {
+        let labels = g.labels.map(|l| l.0).unwrap_or_default();
+        let estimated_minutes = g.metrics.as_ref()
+            .and_then(|m| metric_minutes(&m.0))
+            .unwrap_or_else(|| estimate_minutes(&labels, &label_effort));
+        loads.entry(g.date).or_default().push(GoalLoad {
+            id: g.id,
+            text: g.text,
+            priority: g.priority.unwrap_or_else(|| "medium".to_string()),
+            urgent: g.urgent.unwrap_or(false),
+            is_debt: g.is_debt.unwrap_or(false),
+            estimated_minutes,
+        });
+    }
+
+    let key = |d: NaiveDate| d.format("%Y-%m-%d").to_string();
+    let weekday = |d: NaiveDate| d.weekday().num_days_from_monday() as usize;
+    let (work_minutes, routine_minutes, available_minutes) = day_minutes(&profile[weekday(date)])?;
+    let goals = loads.remove(&key(date)).unwrap_or_default();
+    let goal_minutes: i64 = goals.iter().map(|g| g.estimated_minutes).sum();
+    let overcommitted_minutes = (goal_minutes - available_minutes).max(0);
+
+    let mut suggested_deferrals = Vec::new();
+    if overcommitted_minutes > 0 {
+        // Room left on each following day, filled as deferrals are placed
+        let mut room: Vec<(NaiveDate, i64)> = Vec::new();
+        for offset in 1..=DEFERRAL_LOOKAHEAD_DAYS {
+            let day = date + Duration::days(offset);
+            let used: i64 = loads.get(&key(day)).map_or(0, |l| l.iter().map(|g| g.estimated_minutes).sum());
+            room.push((day, day_minutes(&profile[weekday(day)])?.2 - used));
+        }
+        for g in pick_deferrals(&goals, overcommitted_minutes) {
+            let slot = room.iter_mut().find(|(_, left)| *left >= g.estimated_minutes);
+            let to_date = slot.map(|(day, left)| {
+                *left -= g.estimated_minutes;
+                key(*day)
+            });
+            suggested_deferrals.push(Deferral {
+                goal_id: g.id.clone(),
+                text: g.text.clone(),
+                estimated_minutes: g.estimated_minutes,
+                to_date,
+            });
+        }
+    }
+
+    let warning = (overcommitted_minutes > 0).then(|| format!(
+        "{} has ~{} min of goals but only {} min free after routines ({} min over)",
+        key(date), goal_minutes, available_minutes, overcommitted_minutes
+    ));
+
+    Ok(DayCapacity {
+        date: key(date),
+        work_minutes,
+        routine_minutes,
+        available_minutes,
+        goal_minutes,
+        overcommitted_minutes,
+        goals,
+        suggested_deferrals,
+        warning,
+    })
+}
+
+/// Background check after a goal is added; emits `day-overcommitted` when the day is full
+pub(crate) fn spawn_overcommit_check(app: AppHandle, pool: PgPool, date: String) {
+    tauri::async_runtime::spawn(async move {
+        let Ok(day) = NaiveDate::parse_from_str(&date, "%Y-%m-%d") else {
+            return;
+        };
+        match day_capacity(&pool, day).await {
+            Ok(capacity) if capacity.overcommitted_minutes > 0 => {
+                log::info!("[CAPACITY] {}", capacity.warning.as_deref().unwrap_or_default());
+                let _ = app.emit(OVERCOMMITTED_EVENT, &capacity);
+            }
+            Ok(_) => {}
+            Err(e) => log::warn!("[CAPACITY] Check for {} failed: {}", date, e),
+        }
+    });
+}
+
+// ─── Commands ───────────────────────────────────────────────────────
+
+/// Working window and routine blocks for all seven weekdays (defaults filled in)
+#[tauri::command]
+pub async fn get_capacity_profile(db: State<'_, PosDb>) -> PosResult<Vec<CapacityProfileDay>> {
+    load_profile(&db.0).await
+}
+
+#[tauri::command]
+pub async fn set_capacity_profile(
+    db: State<'_, PosDb>,
+    day: CapacityProfileDay,
+) -> PosResult<CapacityProfileDay> {
+    if !(0..7).contains(&day.weekday) {
+        return Err(PosError::InvalidInput(format!("weekday must be 0 (Monday) to 6 (Sunday), got {}", day.weekday)));
+    }
+    let (start, end) = (parse_hm(&day.work_start)?, parse_hm(&day.work_end)?);
+    if start >= end {
+        return Err(PosError::InvalidInput("work_start must be before work_end".into()));
+    }
+    for block in &day.routine_blocks {
+        if parse_hm(&block.start)? >= parse_hm(&block.end)? {
+            return Err(PosError::InvalidInput(format!("Routine '{}' must start before it ends", block.label)));
+        }
+    }
+
+    sqlx::query(
+        r#"INSERT INTO capacity_profile (weekday, work_start, work_end, routine_blocks, updated_at)
+           VALUES ($1, $2, $3, $4, NOW())
+           ON CONFLICT (weekday) DO UPDATE
+           SET work_start = EXCLUDED.work_start, work_end = EXCLUDED.work_end,
+               routine_blocks = EXCLUDED.routine_blocks, updated_at = NOW()"#
+    )
+    .bind(day.weekday)
+    .bind(start)
+    .bind(end)
+    .bind(sqlx::types::Json(&day.routine_blocks))
+    .execute(&db.0)
+    .await
+    .map_err(|e| db_context("set_capacity_profile", e))?;
+    Ok(day)
+}
+
+/// Goal load of `date` (YYYY-MM-DD) against its capacity, with deferral suggestions
+#[tauri::command]
+pub async fn check_day_capacity(db: State<'_, PosDb>, date: String) -> PosResult<DayCapacity> {
+    let day = NaiveDate::parse_from_str(&date, "%Y-%m-%d")
+        .map_err(|e| PosError::InvalidInput(format!("Invalid date '{}': {}", date, e)))?;
+    day_capacity(&db.0, day).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_window_minutes_merges_routines() {
+        let t = |s: &str| parse_hm(s).unwrap();
+        // Lunch and a call overlap; the gym runs past the end of the window
+        let routines = [(t("12:00"), t("13:00")), (t("12:30"), t("13:30")), (t("17:30"), t("19:00"))];
+        assert_eq!(window_minutes(t("09:00"), t("18:00"), &routines), (540, 120));
+        assert_eq!(window_minutes(t("09:00"), t("18:00"), &[]), (540, 0));
+    }
+
+    #[test]
+    fn test_pick_deferrals() {
+        let goal = |id: &str, priority: &str, urgent: bool, minutes: i64| GoalLoad {
+            id: id.into(),
+            text: id.into(),
+            priority: priority.into(),
+            urgent,
+            is_debt: false,
+            estimated_minutes: minutes,
+        };
+        let goals = [goal("a", "high", false, 60), goal("b", "low", false, 30), goal("c", "low", true, 90), goal("d", "medium", false, 45)];
+        let ids = |overflow| pick_deferrals(&goals, overflow).iter().map(|g| g.id.clone()).collect::<Vec<_>>();
+        assert_eq!(ids(20), vec!["b"]);
+        assert_eq!(ids(60), vec!["b", "d"]);
+        assert!(ids(0).is_empty());
+    }
+}
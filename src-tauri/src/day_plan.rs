@@ -0,0 +1,250 @@
+// Day Plan Locking
+// Commitment mode: locking a day snapshots its goal list. Goals created for or moved onto
+// that day afterwards carry the `unplanned` label, and the weekly report compares how planned
+// and unplanned work actually went.
+
+use std::collections::{HashMap, HashSet};
+
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use serde::Serialize;
+use tauri::State;
+
+use crate::PosDb;
+use crate::pos::error::{PosError, PosResult, db_context};
+
+pub const UNPLANNED_LABEL: &str = "unplanned";
+
+// ─── Types ──────────────────────────────────────────────────────────
+
+#[derive(Debug, Serialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct DayPlanLock {
+    pub date: String,
+    pub locked_at: DateTime<Utc>,
+    pub planned_goal_ids: sqlx::types::Json<Vec<String>>,
+}
+
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlanBucket {
+    pub goals: i64,
+    pub completed: i64,
+    /// Minutes of activities linked to these goals
+    pub minutes: i64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DayPlanStats {
+    pub date: String,
+    pub locked: bool,
+    pub planned: PlanBucket,
+    pub unplanned: PlanBucket,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WeekPlanAdherence {
+    pub week_start: String,
+    pub days: Vec<DayPlanStats>,
+    pub planned: PlanBucket,
+    pub unplanned: PlanBucket,
+    /// Completed planned goals / planned goals on locked days
+    pub planned_completion_rate: Option<f64>,
+    /// Share of goals on locked days that were added after the lock
+    pub unplanned_share: Option<f64>,
+}
+
+#[derive(sqlx::FromRow)]
+struct WeekGoalRow {
+    id: String,
+    date: Option<String>,
+    completed: bool,
+    labels: Option<sqlx::types::Json<Vec<String>>>,
+    linked_activity_ids: Option<sqlx::types::Json<Vec<String>>>,
+}
+
+// ─── Helpers ────────────────────────────────────────────────────────
+
+pub(crate) async fn is_day_locked<'e>(db: impl sqlx::PgExecutor<'e>, date: &str) -> PosResult<bool> {
+    sqlx::query_scalar("SELECT EXISTS (SELECT 1 FROM day_plan_locks WHERE date = $1)")
+        .bind(date)
+        .fetch_one(db)
+        .await
+        .map_err(|e| db_context("is_day_locked", e))
+}
+
+/// Label a goal that now sits on a locked day it wasn't planned for (moved there by an
+/// update or reschedule). Returns whether the label was added.
+pub(crate) async fn label_if_unplanned<'e>(db: impl sqlx::PgExecutor<'e>, goal_id: &str) -> PosResult<bool> {
+    let res = sqlx::query(
+        r#"UPDATE unified_goals g
+           SET labels = COALESCE(g.labels, '[]'::jsonb) || jsonb_build_array($2::text)
+           FROM day_plan_locks l
+           WHERE g.id = $1 AND l.date = g.date
+             AND NOT (l.planned_goal_ids ? g.id)
+             AND NOT (COALESCE(g.labels, '[]'::jsonb) ? $2)"#
+    )
+    .bind(goal_id)
+    .bind(UNPLANNED_LABEL)
+    .execute(db)
+    .await
+    .map_err(|e| db_context("label_if_unplanned", e))?;
+    Ok(res.rows_affected() > 0)
+}
+
+fn add(bucket: &mut PlanBucket, completed: bool, minutes: i64) {
+    bucket.goals += 1;
+    bucket.completed += completed as i64;
+    bucket.minutes += minutes;
+}
+
+fn merge(total: &mut PlanBucket, day: &PlanBucket) {
+    total.goals += day.goals;
+    total.completed += day.completed;
+    total.minutes += day.minutes;
+}
+
+// ─── Commands ───────────────────────────────────────────────────────
+
+/// Freeze the goal list for `date` (YYYY-MM-DD). Locking an already locked day
+/// returns the existing lock unchanged.
+#[tauri::command]
+pub async fn lock_day_plan(db: State<'_, PosDb>, date: String) -> PosResult<DayPlanLock> {
+    NaiveDate::parse_from_str(&date, "%Y-%m-%d")
+        .map_err(|e| PosError::InvalidInput(format!("Invalid date: {}", e)))?;
+
+    let lock = sqlx::query_as::<_, DayPlanLock>(
+        r#"INSERT INTO day_plan_locks (date, locked_at, planned_goal_ids)
+           SELECT $1, NOW(), COALESCE(jsonb_agg(id ORDER BY created_at), '[]'::jsonb)
+           FROM unified_goals
+           WHERE date = $1 AND archived_at IS NULL
+             AND NOT (recurring_pattern IS NOT NULL AND recurring_template_id IS NULL)
+           ON CONFLICT (date) DO UPDATE SET date = EXCLUDED.date
+           RETURNING date, locked_at, planned_goal_ids"#
+    )
+    .bind(&date)
+    .fetch_one(&db.0)
+    .await
+    .map_err(|e| db_context("lock_day_plan", e))?;
+
+    log::info!("[DAY_PLAN] {} locked with {} planned goals", date, lock.planned_goal_ids.0.len());
+    Ok(lock)
+}
+
+#[tauri::command]
+pub async fn unlock_day_plan(db: State<'_, PosDb>, date: String) -> PosResult<()> {
+    let res = sqlx::query("DELETE FROM day_plan_locks WHERE date = $1")
+        .bind(&date)
+        .execute(&db.0)
+        .await
+        .map_err(|e| db_context("unlock_day_plan", e))?;
+    if res.rows_affected() == 0 {
+        return Err(PosError::NotFound(format!("No plan lock for {}", date)));
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_day_plan_lock(db: State<'_, PosDb>, date: String) -> PosResult<Option<DayPlanLock>> {
+    sqlx::query_as::<_, DayPlanLock>(
+        "SELECT date, locked_at, planned_goal_ids FROM day_plan_locks WHERE date = $1"
+    )
+    .bind(&date)
+    .fetch_optional(&db.0)
+    .await
+    .map_err(|e| db_context("get_day_plan_lock", e))
+}
+
+/// Planned vs unplanned goals for the 7 days from `week_start`. Only locked days are
+/// split; goals on unlocked days don't count either way. Planned goals moved to another
+/// day still count on the day they were planned for.
+#[tauri::command]
+pub async fn get_plan_adherence(db: State<'_, PosDb>, week_start: String) -> PosResult<WeekPlanAdherence> {
+    let pool = &db.0;
+    let start = NaiveDate::parse_from_str(&week_start, "%Y-%m-%d")
+        .map_err(|e| PosError::InvalidInput(format!("Invalid week_start: {}", e)))?;
+    let dates: Vec<String> = (0..7).map(|i| (start + Duration::days(i)).format("%Y-%m-%d").to_string()).collect();
+
+    let locks = sqlx::query_as::<_, DayPlanLock>(
+        "SELECT date, locked_at, planned_goal_ids FROM day_plan_locks WHERE date = ANY($1)"
+    )
+    .bind(&dates)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| db_context("get_plan_adherence locks", e))?;
+
+    let planned_ids: Vec<String> = locks.iter().flat_map(|l| l.planned_goal_ids.0.iter().cloned()).collect();
+    let goals = sqlx::query_as::<_, WeekGoalRow>(
+        r#"SELECT id, date, completed, labels, linked_activity_ids FROM unified_goals
+           WHERE (date = ANY($1) AND labels ? $2 AND deleted_at IS NULL AND archived_at IS NULL)
+              OR id = ANY($3)"#
+    )
+    .bind(&dates)
+    .bind(UNPLANNED_LABEL)
+    .bind(&planned_ids)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| db_context("get_plan_adherence goals", e))?;
+
+    let activity_ids: Vec<String> = goals.iter()
+        .flat_map(|g| g.linked_activity_ids.as_ref().map(|a| a.0.clone()).unwrap_or_default())
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .collect();
+    let minutes: HashMap<String, i64> = sqlx::query_as::<_, (String, i64)>(
+        r#"SELECT id, (EXTRACT(EPOCH FROM (end_time - start_time)) / 60)::bigint
+           FROM pos_activities WHERE id = ANY($1)"#
+    )
+    .bind(&activity_ids)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| db_context("get_plan_adherence minutes", e))?
+    .into_iter()
+    .collect();
+
+    let goal_minutes = |g: &WeekGoalRow| -> i64 {
+        g.linked_activity_ids.as_ref()
+            .map(|ids| ids.0.iter().filter_map(|id| minutes.get(id)).sum())
+            .unwrap_or(0)
+    };
+    let by_id: HashMap<&str, &WeekGoalRow> = goals.iter().map(|g| (g.id.as_str(), g)).collect();
+    let locks: HashMap<&str, &DayPlanLock> = locks.iter().map(|l| (l.date.as_str(), l)).collect();
+
+    let mut days = Vec::with_capacity(7);
+    let mut planned = PlanBucket::default();
+    let mut unplanned = PlanBucket::default();
+    for date in &dates {
+        let mut day = DayPlanStats {
+            date: date.clone(),
+            locked: false,
+            planned: PlanBucket::default(),
+            unplanned: PlanBucket::default(),
+        };
+        if let Some(lock) = locks.get(date.as_str()) {
+            day.locked = true;
+            for g in lock.planned_goal_ids.0.iter().filter_map(|id| by_id.get(id.as_str())) {
+                add(&mut day.planned, g.completed, goal_minutes(g));
+            }
+            let flagged = goals.iter().filter(|g| {
+                g.date.as_deref() == Some(date.as_str()) && g.labels.as_ref().is_some_and(|l| l.0.iter().any(|x| x == UNPLANNED_LABEL))
+            });
+            for g in flagged {
+                add(&mut day.unplanned, g.completed, goal_minutes(g));
+            }
+        }
+        merge(&mut planned, &day.planned);
+        merge(&mut unplanned, &day.unplanned);
+        days.push(day);
+    }
+
+    let total = planned.goals + unplanned.goals;
+    Ok(WeekPlanAdherence {
+        week_start,
+        days,
+        planned_completion_rate: (planned.goals > 0).then(|| planned.completed as f64 / planned.goals as f64),
+        unplanned_share: (total > 0).then(|| unplanned.goals as f64 / total as f64),
+        planned,
+        unplanned,
+    })
+}
@@ -0,0 +1,304 @@
+// End-of-Day Summary
+// Composes one day into a structured summary: tracked time by category, goals completed
+// and missed, problems solved, knowledge captured. The rendered Markdown is stored as a
+// Journal-source knowledge item linked to the day (tagged `day-summary`); regenerating a
+// day rewrites that item instead of adding another. With `export`, the Markdown is also
+// written to JOURNAL_EXPORT_DIR/<date>.md for an external journal to pick up.
+
+use std::path::PathBuf;
+
+use chrono::{NaiveDate, Utc};
+use serde::Serialize;
+use tauri::State;
+
+use crate::PosDb;
+use crate::pos::error::{PosError, PosResult, db_context};
+use crate::pos::timezone;
+use crate::pos::utils::gen_id;
+
+const SUMMARY_TAG: &str = "day-summary";
+
+// ─── Types ──────────────────────────────────────────────────────────
+
+#[derive(Debug, Serialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct CategoryTime {
+    pub category: String,
+    pub activities: i64,
+    pub minutes: f64,
+    pub productive_minutes: f64,
+}
+
+#[derive(Debug, Serialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct SummaryGoal {
+    pub id: String,
+    pub text: String,
+    pub completed: bool,
+}
+
+#[derive(Debug, Serialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct SolvedProblem {
+    pub problem_id: String,
+    pub platform: String,
+    pub problem_title: String,
+    pub rating: Option<i32>,
+    pub difficulty: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CapturedItem {
+    pub id: String,
+    pub source: String,
+    /// metadata title, else the content's first line
+    pub label: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DaySummary {
+    pub date: String,
+    pub knowledge_item_id: String,
+    pub time_by_category: Vec<CategoryTime>,
+    pub goals_completed: Vec<SummaryGoal>,
+    pub goals_missed: Vec<SummaryGoal>,
+    pub problems_solved: Vec<SolvedProblem>,
+    pub knowledge_captured: Vec<CapturedItem>,
+    pub markdown: String,
+    /// Set when the Markdown was written to the journal export dir
+    pub exported_path: Option<String>,
+}
+
+#[derive(sqlx::FromRow)]
+struct CapturedRow {
+    id: String,
+    source: String,
+    title: Option<String>,
+    content: String,
+}
+
+// ─── Helpers ────────────────────────────────────────────────────────
+
+fn fmt_minutes(minutes: f64) -> String {
+    let m = minutes.round() as i64;
+    match (m / 60, m % 60) {
+        (0, m) => format!("{}m", m),
+        (h, 0) => format!("{}h", h),
+        (h, m) => format!("{}h {}m", h, m),
+    }
+}
+
+fn capture_label(title: Option<String>, content: &str) -> String {
+    let label = title.filter(|t| !t.trim().is_empty())
+        .unwrap_or_else(|| content.lines().find(|l| !l.trim().is_empty()).unwrap_or("").trim().to_string());
+    if label.chars().count() > 80 {
+        format!("{}…", label.chars().take(79).collect::<String>())
+    } else {
+        label
+    }
+}
+
+fn render_markdown(s: &DaySummary) -> String {
+    let mut md = format!("# Day summary — {}\n", s.date);
+
+    let total: f64 = s.time_by_category.iter().map(|c| c.minutes).sum();
+    let productive: f64 = s.time_by_category.iter().map(|c| c.productive_minutes).sum();
+    md += &format!("\n## Time ({} tracked, {} productive)\n", fmt_minutes(total), fmt_minutes(productive));
+    for c in &s.time_by_category {
+        md += &format!("- {} — {} ({})\n", c.category, fmt_minutes(c.minutes), c.activities);
+    }
+
+    let goals = s.goals_completed.len() + s.goals_missed.len();
+    md += &format!("\n## Goals ({}/{} completed)\n", s.goals_completed.len(), goals);
+    for g in &s.goals_completed {
+        md += &format!("- [x] {}\n", g.text);
+    }
+    for g in &s.goals_missed {
+        md += &format!("- [ ] {}\n", g.text);
+    }
+
+    md += &format!("\n## Problems solved ({})\n", s.problems_solved.len());
+    for p in &s.problems_solved {
+        let level = p.rating.map(|r| r.to_string()).or_else(|| p.difficulty.clone());
+        match level {
+            Some(level) => md += &format!("- {} ({}, {})\n", p.problem_title, p.platform, level),
+            None => md += &format!("- {} ({})\n", p.problem_title, p.platform),
+        }
+    }
+
+    md += &format!("\n## Knowledge captured ({})\n", s.knowledge_captured.len());
+    for k in &s.knowledge_captured {
+        md += &format!("- {}\n", k.label);
+    }
+    md
+}
+
+// ─── Command ────────────────────────────────────────────────────────
+
+/// Summarize `date` (YYYY-MM-DD, local) and store it as the day's journal summary item.
+/// With `export`, also write it to JOURNAL_EXPORT_DIR/<date>.md.
+#[tauri::command]
+pub async fn generate_day_summary(
+    db: State<'_, PosDb>,
+    config: State<'_, crate::PosConfig>,
+    date: String,
+    export: Option<bool>,
+) -> PosResult<DaySummary> {
+    let pool = &db.0;
+    let day = NaiveDate::parse_from_str(&date, "%Y-%m-%d")
+        .map_err(|e| PosError::InvalidInput(format!("Invalid date '{}': {}", date, e)))?;
+    let export_dir = match export.unwrap_or(false) {
+        true => Some(config.0.journal_export_dir.clone().ok_or_else(|| {
+            PosError::InvalidInput("JOURNAL_EXPORT_DIR not configured".into())
+        })?),
+        false => None,
+    };
+    let start = day.and_hms_opt(0, 0, 0)
+        .and_then(timezone::from_local)
+        .ok_or_else(|| PosError::InvalidInput(format!("No local midnight on {}", date)))?;
+    let end = timezone::next_midnight(start)
+        .ok_or_else(|| PosError::InvalidInput(format!("No local day after {}", date)))?;
+
+    let time_q = sqlx::query_as::<_, CategoryTime>(
+        r#"SELECT category, COUNT(*)::bigint AS activities,
+                  COALESCE(SUM(EXTRACT(EPOCH FROM (end_time - start_time)) / 60), 0)::float8 AS minutes,
+                  COALESCE(SUM(EXTRACT(EPOCH FROM (end_time - start_time)) / 60)
+                      FILTER (WHERE is_productive), 0)::float8 AS productive_minutes
+           FROM pos_activities
+           WHERE date = $1 AND deleted_at IS NULL AND is_shadow = FALSE
+           GROUP BY category
+           ORDER BY minutes DESC"#
+    )
+    .bind(&date)
+    .fetch_all(pool);
+
+    let goals_q = sqlx::query_as::<_, SummaryGoal>(
+        r#"SELECT id, text, completed FROM unified_goals
+           WHERE date = $1 AND deleted_at IS NULL AND archived_at IS NULL
+             AND NOT (recurring_pattern IS NOT NULL AND recurring_template_id IS NULL)
+           ORDER BY completed DESC, created_at"#
+    )
+    .bind(&date)
+    .fetch_all(pool);
+
+    let solved_q = sqlx::query_as::<_, SolvedProblem>(
+        r#"SELECT DISTINCT ON (problem_id) problem_id, platform, problem_title, rating, difficulty
+           FROM pos_submissions
+           WHERE verdict IN ('OK', 'Accepted', 'AC') AND excluded_at IS NULL
+             AND submitted_time >= $1 AND submitted_time < $2
+           ORDER BY problem_id, submitted_time"#
+    )
+    .bind(start)
+    .bind(end)
+    .fetch_all(pool);
+
+    let captured_q = sqlx::query_as::<_, CapturedRow>(
+        r#"SELECT id, source, metadata->>'title' AS title, content FROM knowledge_items
+           WHERE created_at >= $1 AND created_at < $2
+             AND NOT (COALESCE(tags, '{}') @> ARRAY[$3])
+           ORDER BY created_at"#
+    )
+    .bind(start)
+    .bind(end)
+    .bind(SUMMARY_TAG)
+    .fetch_all(pool);
+
+    let (time_by_category, goals, problems_solved, captured) = tokio::try_join!(time_q, goals_q, solved_q, captured_q)
+        .map_err(|e| db_context("generate_day_summary", e))?;
+    let (goals_completed, goals_missed): (Vec<_>, Vec<_>) = goals.into_iter().partition(|g| g.completed);
+
+    let existing_id: Option<String> = sqlx::query_scalar(
+        r#"SELECT id FROM knowledge_items
+           WHERE linked_journal_date = $1 AND source = 'Journal' AND COALESCE(tags, '{}') @> ARRAY[$2]
+           ORDER BY created_at LIMIT 1"#
+    )
+    .bind(&date)
+    .bind(SUMMARY_TAG)
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| db_context("find day summary item", e))?;
+
+    let mut summary = DaySummary {
+        knowledge_item_id: existing_id.clone().unwrap_or_else(gen_id),
+        date: date.clone(),
+        time_by_category,
+        goals_completed,
+        goals_missed,
+        problems_solved,
+        knowledge_captured: captured.into_iter()
+            .map(|r| CapturedItem { label: capture_label(r.title, &r.content), id: r.id, source: r.source })
+            .collect(),
+        markdown: String::new(),
+        exported_path: None,
+    };
+    summary.markdown = render_markdown(&summary);
+
+    let mut metadata = serde_json::to_value(&summary).unwrap_or_default();
+    if let Some(obj) = metadata.as_object_mut() {
+        for key in ["markdown", "exportedPath", "knowledgeItemId"] {
+            obj.remove(key);
+        }
+    }
+    let metadata = serde_json::json!({ "title": format!("Day summary — {}", date), "daySummary": metadata });
+    let now = Utc::now();
+    if existing_id.is_some() {
+        sqlx::query("UPDATE knowledge_items SET content = $2, metadata = $3, updated_at = $4 WHERE id = $1")
+            .bind(&summary.knowledge_item_id).bind(&summary.markdown).bind(&metadata).bind(now)
+            .execute(pool)
+            .await
+            .map_err(|e| db_context("update day summary item", e))?;
+    } else {
+        sqlx::query(
+            r#"INSERT INTO knowledge_items (id, source, content, metadata, status, tags, linked_journal_date, created_at, updated_at)
+               VALUES ($1, 'Journal', $2, $3, 'Completed', $4, $5, $6, $6)"#
+        )
+        .bind(&summary.knowledge_item_id).bind(&summary.markdown).bind(&metadata)
+        .bind(vec![SUMMARY_TAG.to_string()])
+        .bind(&date).bind(now)
+        .execute(pool)
+        .await
+        .map_err(|e| db_context("insert day summary item", e))?;
+    }
+
+    if let Some(dir) = export_dir {
+        let dir = PathBuf::from(dir);
+        let path = dir.join(format!("{}.md", date));
+        let write = async {
+            tokio::fs::create_dir_all(&dir).await?;
+            tokio::fs::write(&path, &summary.markdown).await
+        };
+        write.await.map_err(|e| PosError::External(format!("Failed to write {}: {}", path.display(), e)))?;
+        summary.exported_path = Some(path.to_string_lossy().to_string());
+    }
+
+    log::info!(
+        "[JOURNAL] Day summary for {}: {} categories, {}/{} goals, {} solved, {} captured{}",
+        date, summary.time_by_category.len(), summary.goals_completed.len(),
+        summary.goals_completed.len() + summary.goals_missed.len(),
+        summary.problems_solved.len(), summary.knowledge_captured.len(),
+        summary.exported_path.as_deref().map(|p| format!(" → {}", p)).unwrap_or_default()
+    );
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fmt_minutes() {
+        assert_eq!(fmt_minutes(0.0), "0m");
+        assert_eq!(fmt_minutes(45.4), "45m");
+        assert_eq!(fmt_minutes(120.0), "2h");
+        assert_eq!(fmt_minutes(95.0), "1h 35m");
+    }
+
+    #[test]
+    fn test_capture_label() {
+        assert_eq!(capture_label(Some("Segment trees".into()), "body"), "Segment trees");
+        assert_eq!(capture_label(None, "\n  first line \nsecond"), "first line");
+        assert_eq!(capture_label(Some(" ".into()), &"x".repeat(100)).chars().count(), 80);
+    }
+}
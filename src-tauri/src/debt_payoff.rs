@@ -0,0 +1,306 @@
+// Debt Payoff Planner
+// Snowball-style catch-up plan for accumulated debt goals: unresolved debt is ordered by
+// age or priority, costed in the chosen capacity unit (minutes or problems) and packed
+// into future days up to the daily capacity. Each scheduled debt gets a "Catch up:" goal
+// on its day, linked back through parent_goal_id. Debts that already have an open
+// catch-up goal are left alone, so re-planning doesn't double-book, and catch-up goals
+// are never planned themselves. Completing a catch-up goal completes its debt
+// (settle_catch_ups).
+
+use chrono::{Duration, NaiveDate};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use tauri::State;
+
+use crate::PosDb;
+use crate::pos::error::{PosError, PosResult, db_context};
+use crate::pos::units::lookup_unit;
+use crate::unified_goals::{insert_unified_goal_tx, CreateGoalRequest, UnifiedGoalMetric, UnifiedGoalRow, UNIFIED_GOAL_COLS};
+
+pub const PAYOFF_LABEL: &str = "debt-payoff";
+/// Cost of a debt goal with no time metric when planning in minutes
+const DEFAULT_GOAL_MINUTES: f64 = 30.0;
+const DEFAULT_MAX_DAYS: i64 = 60;
+
+// ─── Types ──────────────────────────────────────────────────────────
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlanDebtPayoffRequest {
+    /// "minutes" | "problems"
+    pub capacity_unit: String,
+    pub daily_capacity: f64,
+    /// "age" (oldest first, default) | "priority" (high first, then oldest)
+    pub order: Option<String>,
+    /// First day to schedule on (YYYY-MM-DD, default tomorrow)
+    pub start_date: Option<String>,
+    /// Plan horizon in days (default 60); debts that don't fit are returned unscheduled
+    pub max_days: Option<i64>,
+    /// Return the plan without creating goals
+    pub dry_run: Option<bool>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PayoffItem {
+    pub debt_goal_id: String,
+    pub text: String,
+    pub original_date: Option<String>,
+    pub cost: f64,
+    /// The created catch-up goal (None on a dry run)
+    pub catch_up_goal: Option<UnifiedGoalRow>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PayoffDay {
+    pub date: String,
+    pub load: f64,
+    pub items: Vec<PayoffItem>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DebtPayoffPlan {
+    pub capacity_unit: String,
+    pub daily_capacity: f64,
+    pub days: Vec<PayoffDay>,
+    /// Debt goal ids that didn't fit in the horizon
+    pub unscheduled: Vec<String>,
+    /// Debt goals skipped because they already have an open catch-up goal
+    pub already_planned: usize,
+    pub finish_date: Option<String>,
+}
+
+// ─── Helpers ────────────────────────────────────────────────────────
+
+fn priority_rank(priority: &str) -> u8 {
+    match priority {
+        "high" => 0,
+        "medium" => 1,
+        _ => 2,
+    }
+}
+
+/// What's left of a metric, converted to its dimension's base unit
+fn remaining_in(metrics: &[UnifiedGoalMetric], dimension: &str, canonical: Option<&str>) -> Option<f64> {
+    let mut found = false;
+    let total: f64 = metrics.iter()
+        .filter_map(|m| lookup_unit(&m.unit).map(|u| (m, u)))
+        .filter(|(_, u)| u.dimension == dimension && canonical.map_or(true, |c| u.canonical == c))
+        .map(|(m, u)| {
+            found = true;
+            (m.target - m.current).max(0.0) * u.factor
+        })
+        .sum();
+    found.then_some(total)
+}
+
+/// Cost of paying off a goal in the capacity unit
+fn goal_cost(goal: &UnifiedGoalRow, unit: &str) -> f64 {
+    let metrics = goal.metrics.as_ref().map(|m| m.0.as_slice()).unwrap_or_default();
+    match unit {
+        "minutes" => remaining_in(metrics, "time", None).unwrap_or(DEFAULT_GOAL_MINUTES),
+        _ => remaining_in(metrics, "count", Some("problems")).unwrap_or(1.0),
+    }
+}
+
+/// Pack costs into consecutive days (in order, no reordering). A cost above the daily
+/// capacity gets a day to itself. Returns the day index per item, None past `max_days`.
+fn pack(costs: &[f64], capacity: f64, max_days: i64) -> Vec<Option<i64>> {
+    let mut day = 0i64;
+    let mut load = 0.0;
+    costs.iter().map(|&cost| {
+        if load > 0.0 && load + cost > capacity {
+            day += 1;
+            load = 0.0;
+        }
+        load += cost;
+        (day < max_days).then_some(day)
+    }).collect()
+}
+
+/// Complete the debt behind each of `goal_ids` that is a completed catch-up goal.
+/// Called by every goal completion path. Never fails the caller.
+pub(crate) async fn settle_catch_ups(pool: &PgPool, goal_ids: &[String]) {
+    if goal_ids.is_empty() {
+        return;
+    }
+    let res = sqlx::query(
+        r#"UPDATE unified_goals d
+           SET completed = TRUE, completed_at = COALESCE(d.completed_at, c.completed_at, NOW()), updated_at = NOW()
+           FROM unified_goals c
+           WHERE c.id = ANY($1) AND c.completed = TRUE AND c.labels ? $2
+             AND d.id = c.parent_goal_id AND d.is_debt = TRUE AND d.completed = FALSE"#
+    )
+    .bind(goal_ids)
+    .bind(PAYOFF_LABEL)
+    .execute(pool)
+    .await;
+    match res {
+        Ok(r) if r.rows_affected() > 0 => {
+            log::info!("[DEBT] Settled {} debts through their catch-up goals", r.rows_affected());
+            crate::dashboard::mark_snapshot_stale(pool).await;
+        }
+        Ok(_) => {}
+        Err(e) => log::warn!("[DEBT] Failed to settle catch-up debts for {:?}: {}", goal_ids, e),
+    }
+}
+
+// ─── Command ────────────────────────────────────────────────────────
+
+#[tauri::command]
+pub async fn plan_debt_payoff(
+    db: State<'_, PosDb>,
+    req: PlanDebtPayoffRequest,
+) -> PosResult<DebtPayoffPlan> {
+    let pool = &db.0;
+    let unit = req.capacity_unit.trim().to_lowercase();
+    if unit != "minutes" && unit != "problems" {
+        return Err(PosError::InvalidInput("capacity_unit must be 'minutes' or 'problems'".into()));
+    }
+    if req.daily_capacity <= 0.0 {
+        return Err(PosError::InvalidInput("daily_capacity must be positive".into()));
+    }
+    let order = req.order.as_deref().unwrap_or("age");
+    if order != "age" && order != "priority" {
+        return Err(PosError::InvalidInput("order must be 'age' or 'priority'".into()));
+    }
+    let start = match &req.start_date {
+        Some(d) => NaiveDate::parse_from_str(d, "%Y-%m-%d")
+            .map_err(|e| PosError::InvalidInput(format!("Invalid start_date: {}", e)))?,
+        None => crate::pos::timezone::today() + Duration::days(1),
+    };
+    let max_days = req.max_days.unwrap_or(DEFAULT_MAX_DAYS).clamp(1, 366);
+
+    // Overdue catch-up goals are debt too, but their parent debt is what gets planned
+    let debts = sqlx::query_as::<_, UnifiedGoalRow>(&format!(
+        r#"SELECT {} FROM unified_goals
+           WHERE is_debt = TRUE AND completed = FALSE AND archived_at IS NULL AND deleted_at IS NULL
+             AND NOT (COALESCE(labels, '[]'::jsonb) ? $1)
+           ORDER BY COALESCE(original_date, date) ASC, created_at ASC"#,
+        UNIFIED_GOAL_COLS
+    ))
+    .bind(PAYOFF_LABEL)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| db_context("plan_debt_payoff debts", e))?;
+
+    let planned: Vec<String> = sqlx::query_scalar(
+        r#"SELECT DISTINCT parent_goal_id FROM unified_goals
+           WHERE parent_goal_id IS NOT NULL AND completed = FALSE AND deleted_at IS NULL
+             AND labels ? $1"#
+    )
+    .bind(PAYOFF_LABEL)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| db_context("plan_debt_payoff existing", e))?;
+
+    let total = debts.len();
+    let mut debts: Vec<UnifiedGoalRow> = debts.into_iter().filter(|g| !planned.contains(&g.id)).collect();
+    let already_planned = total - debts.len();
+    if order == "priority" {
+        // Stable: ties keep the oldest-first order from the query
+        debts.sort_by_key(|g| priority_rank(&g.priority));
+    }
+
+    let costs: Vec<f64> = debts.iter().map(|g| goal_cost(g, &unit)).collect();
+    let slots = pack(&costs, req.daily_capacity, max_days);
+    let dry_run = req.dry_run.unwrap_or(false);
+
+    // All catch-up goals land together or not at all
+    let mut tx = match dry_run {
+        true => None,
+        false => Some(pool.begin().await.map_err(|e| db_context("TX begin", e))?),
+    };
+    let mut days: Vec<PayoffDay> = Vec::new();
+    let mut unscheduled = Vec::new();
+    for ((goal, cost), slot) in debts.into_iter().zip(costs).zip(slots) {
+        let Some(offset) = slot else {
+            unscheduled.push(goal.id);
+            continue;
+        };
+        let date = (start + Duration::days(offset)).format("%Y-%m-%d").to_string();
+        let catch_up_goal = match tx.as_mut() {
+            None => None,
+            Some(tx) => {
+                let mut labels = goal.labels.as_ref().map(|l| l.0.clone()).unwrap_or_default();
+                labels.retain(|l| l != PAYOFF_LABEL);
+                labels.push(PAYOFF_LABEL.to_string());
+                Some(insert_unified_goal_tx(tx, CreateGoalRequest {
+                    text: format!("Catch up: {}", goal.text),
+                    description: Some(format!(
+                        "Debt from {}", goal.original_date.as_deref().or(goal.date.as_deref()).unwrap_or("earlier")
+                    )),
+                    date: Some(date.clone()),
+                    recurring_pattern: None,
+                    priority: Some(goal.priority.clone()),
+                    urgent: Some(goal.urgent),
+                    metrics: goal.metrics.as_ref().map(|m| m.0.clone()),
+                    problem_id: goal.problem_id.clone(),
+                    labels: Some(labels),
+                    parent_goal_id: Some(goal.id.clone()),
+                }).await?)
+            }
+        };
+        let item = PayoffItem {
+            debt_goal_id: goal.id,
+            text: goal.text,
+            original_date: goal.original_date,
+            cost,
+            catch_up_goal,
+        };
+        match days.last_mut() {
+            Some(day) if day.date == date => {
+                day.load += cost;
+                day.items.push(item);
+            }
+            _ => days.push(PayoffDay { date, load: cost, items: vec![item] }),
+        }
+    }
+
+    if let Some(tx) = tx {
+        tx.commit().await.map_err(|e| db_context("TX commit", e))?;
+        crate::dashboard::mark_snapshot_stale(pool).await;
+    }
+
+    log::info!(
+        "[DEBT] Payoff plan ({}, {} {}/day): {} debts over {} days, {} unscheduled, {} already planned{}",
+        order, req.daily_capacity, unit,
+        days.iter().map(|d| d.items.len()).sum::<usize>(), days.len(), unscheduled.len(), already_planned,
+        if dry_run { " (dry run)" } else { "" }
+    );
+    Ok(DebtPayoffPlan {
+        capacity_unit: unit,
+        daily_capacity: req.daily_capacity,
+        finish_date: days.last().map(|d| d.date.clone()),
+        days,
+        unscheduled,
+        already_planned,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pack_fills_days_in_order() {
+        // 60-minute days: 30+20 | 45 | 90 (alone, over capacity) | 10 past the horizon
+        assert_eq!(
+            pack(&[30.0, 20.0, 45.0, 90.0, 10.0], 60.0, 3),
+            vec![Some(0), Some(0), Some(1), Some(2), None]
+        );
+    }
+
+    #[test]
+    fn test_remaining_converts_units() {
+        let metric = |unit: &str, target: f64, current: f64| UnifiedGoalMetric {
+            id: "m".into(), label: "x".into(), target, current, unit: unit.into(),
+        };
+        let metrics = [metric("hours", 2.0, 0.5), metric("minutes", 30.0, 40.0), metric("problems", 5.0, 2.0)];
+        assert_eq!(remaining_in(&metrics, "time", None), Some(90.0));
+        assert_eq!(remaining_in(&metrics, "count", Some("problems")), Some(3.0));
+        assert_eq!(remaining_in(&metrics, "count", Some("pages")), None);
+    }
+}
@@ -0,0 +1,136 @@
+// Debt Resolution Stats
+// How quickly debt gets paid off, which labels resolve (or don't), and which recurring
+// goals keep falling into debt — a read on whether planning is overcommitted.
+
+use chrono::NaiveDate;
+use serde::Serialize;
+use tauri::State;
+
+use crate::PosDb;
+use crate::pos::error::{PosError, PosResult, db_context};
+
+const UNLABELED: &str = "(unlabeled)";
+
+// ─── Types ──────────────────────────────────────────────────────────
+
+#[derive(Debug, Serialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct LabelResolution {
+    pub label: String,
+    pub debt_count: i64,
+    pub resolved_count: i64,
+    pub resolution_rate: f64,
+    pub avg_days_to_resolve: Option<f64>,
+}
+
+#[derive(Debug, Serialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct RecurringDebtOffender {
+    pub template_id: String,
+    pub text: String,
+    pub instances: i64,
+    pub debt_instances: i64,
+    pub debt_rate: f64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DebtResolutionStats {
+    pub start_date: String,
+    pub end_date: String,
+    pub total_debt: i64,
+    pub resolved: i64,
+    pub outstanding: i64,
+    pub resolution_rate: f64,
+    pub avg_days_to_resolve: Option<f64>,
+    pub median_days_to_resolve: Option<f64>,
+    pub by_label: Vec<LabelResolution>,
+    /// Recurring templates with more than one instance that became debt
+    pub recidivism: Vec<RecurringDebtOffender>,
+}
+
+// ─── Commands ───────────────────────────────────────────────────────
+
+/// Debt outcomes for goals originally due between `start_date` and `end_date`
+/// (inclusive, YYYY-MM-DD). A debt goal counts as resolved once completed; days to
+/// resolve are measured from its original due date to `completed_at`.
+#[tauri::command]
+pub async fn get_debt_resolution_stats(
+    db: State<'_, PosDb>,
+    start_date: String,
+    end_date: String,
+) -> PosResult<DebtResolutionStats> {
+    let pool = &db.0;
+    for d in [&start_date, &end_date] {
+        NaiveDate::parse_from_str(d, "%Y-%m-%d")
+            .map_err(|e| PosError::InvalidInput(format!("Invalid date '{}': {}", d, e)))?;
+    }
+    if start_date > end_date {
+        return Err(PosError::InvalidInput("start_date must be on or before end_date".into()));
+    }
+
+    let (overall, by_label, recidivism) = tokio::try_join!(
+        sqlx::query_as::<_, (i64, i64, Option<f64>, Option<f64>)>(
+            r#"WITH debt AS (
+                   SELECT completed,
+                          CASE WHEN completed AND completed_at IS NOT NULL
+                               THEN GREATEST(completed_at::date - original_date::date, 0)
+                          END AS days
+                   FROM unified_goals
+                   WHERE original_date IS NOT NULL
+                     AND original_date BETWEEN $1 AND $2
+               )
+               SELECT COUNT(*)::bigint,
+                      COUNT(*) FILTER (WHERE completed)::bigint,
+                      AVG(days)::float8,
+                      (PERCENTILE_CONT(0.5) WITHIN GROUP (ORDER BY days))::float8
+               FROM debt"#
+        ).bind(&start_date).bind(&end_date).fetch_one(pool),
+
+        sqlx::query_as::<_, LabelResolution>(
+            r#"SELECT COALESCE(lbl, $3) AS label,
+                      COUNT(*)::bigint AS debt_count,
+                      COUNT(*) FILTER (WHERE g.completed)::bigint AS resolved_count,
+                      (COUNT(*) FILTER (WHERE g.completed))::float8 / COUNT(*) AS resolution_rate,
+                      AVG(GREATEST(g.completed_at::date - g.original_date::date, 0))
+                          FILTER (WHERE g.completed AND g.completed_at IS NOT NULL)::float8 AS avg_days_to_resolve
+               FROM unified_goals g
+               LEFT JOIN LATERAL jsonb_array_elements_text(
+                   CASE WHEN jsonb_typeof(g.labels) = 'array' THEN g.labels ELSE '[]'::jsonb END
+               ) lbl ON TRUE
+               WHERE g.original_date IS NOT NULL
+                 AND g.original_date BETWEEN $1 AND $2
+               GROUP BY 1
+               ORDER BY resolution_rate ASC, debt_count DESC"#
+        ).bind(&start_date).bind(&end_date).bind(UNLABELED).fetch_all(pool),
+
+        sqlx::query_as::<_, RecurringDebtOffender>(
+            r#"SELECT t.id AS template_id,
+                      t.text,
+                      COUNT(i.id)::bigint AS instances,
+                      COUNT(i.id) FILTER (WHERE i.original_date IS NOT NULL)::bigint AS debt_instances,
+                      (COUNT(i.id) FILTER (WHERE i.original_date IS NOT NULL))::float8 / COUNT(i.id) AS debt_rate
+               FROM unified_goals t
+               JOIN unified_goals i ON i.recurring_template_id = t.id
+               WHERE i.date BETWEEN $1 AND $2
+               GROUP BY t.id, t.text
+               HAVING COUNT(i.id) FILTER (WHERE i.original_date IS NOT NULL) > 1
+               ORDER BY debt_instances DESC, debt_rate DESC"#
+        ).bind(&start_date).bind(&end_date).fetch_all(pool),
+    ).map_err(|e| db_context("get_debt_resolution_stats", e))?;
+
+    let (total_debt, resolved, avg_days_to_resolve, median_days_to_resolve) = overall;
+
+    Ok(DebtResolutionStats {
+        start_date,
+        end_date,
+        total_debt,
+        resolved,
+        outstanding: total_debt - resolved,
+        resolution_rate: if total_debt > 0 { resolved as f64 / total_debt as f64 } else { 0.0 },
+        avg_days_to_resolve,
+        median_days_to_resolve,
+        by_label,
+        recidivism,
+    })
+}
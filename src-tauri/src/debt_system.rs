@@ -41,12 +41,13 @@ pub struct TransitionDebtRequest {
 // ─── Commands ───────────────────────────────────────────────────────
 
 /// Get goals completed on a specific local date (by completed_at UTC timestamp)
-/// timezone_offset_minutes: JS getTimezoneOffset() value (e.g. -330 for UTC+5:30)
+/// timezone_offset_minutes: JS getTimezoneOffset() value (e.g. -330 for UTC+5:30);
+/// defaults to the app's local zone when omitted
 #[tauri::command]
 pub async fn get_completed_goals_for_date(
     db: State<'_, PosDb>,
     local_date: String,           // YYYY-MM-DD in local timezone
-    timezone_offset_minutes: i32, // JS getTimezoneOffset(): negative for UTC+ zones
+    timezone_offset_minutes: Option<i32>, // JS getTimezoneOffset(): negative for UTC+ zones
 ) -> PosResult<Vec<UnifiedGoalRow>> {
     use chrono::{NaiveDate, NaiveTime, NaiveDateTime, Duration};
 
@@ -62,6 +63,7 @@ pub async fn get_completed_goals_for_date(
     // Convert local boundaries to UTC.
     // JS getTimezoneOffset() returns -330 for UTC+5:30 (sign is inverted vs standard offset).
     // UTC = local + JS_offset  →  00:00 + (-330min) = 18:30 prev day UTC  ✓
+    let timezone_offset_minutes = timezone_offset_minutes.unwrap_or_else(crate::pos::timezone::js_offset_minutes);
     let offset_duration = Duration::minutes(timezone_offset_minutes as i64);
     let day_start: DateTime<Utc> = DateTime::from_naive_utc_and_offset(
         midnight + offset_duration,
@@ -110,6 +112,7 @@ pub async fn get_accumulated_debt(
         &format!("SELECT {} FROM unified_goals \
            WHERE is_debt = true \
            AND completed = false \
+           AND archived_at IS NULL \
            AND original_date IS NOT NULL \
            AND original_date < $1 \
            ORDER BY original_date ASC, created_at ASC", UNIFIED_GOAL_COLS)
@@ -141,6 +144,7 @@ pub async fn get_debt_trail(
         &format!("SELECT {} FROM unified_goals \
            WHERE is_debt = true \
            AND completed = false \
+           AND archived_at IS NULL \
            AND original_date IS NOT NULL \
            AND original_date < $1 \
            ORDER BY original_date ASC", UNIFIED_GOAL_COLS)
@@ -260,7 +264,7 @@ pub async fn transition_monthly_debt(
         .map_err(|e| db_context("insert debt archive", e))?;
 
         // Mark as debt (keep in unified_goals for history)
-        sqlx::query("UPDATE unified_goals SET is_debt = true WHERE id = $1")
+        sqlx::query("UPDATE unified_goals SET is_debt = true, updated_at = NOW() WHERE id = $1")
             .bind(&goal.id)
             .execute(&mut *tx)
             .await
@@ -321,7 +325,7 @@ pub async fn reset_debt_for_month(
         .collect();
     
     let query = format!(
-        "UPDATE unified_goals SET is_debt = false WHERE id IN ({})",
+        "UPDATE unified_goals SET is_debt = false, updated_at = NOW() WHERE id IN ({})",
         placeholders.join(", ")
     );
 
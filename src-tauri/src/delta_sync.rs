@@ -0,0 +1,145 @@
+// Delta Sync
+// Lets the frontend reconcile its stores after sleep without refetching everything:
+// get_changes_since returns created / updated / deleted ids per entity since a watermark.
+// Created and updated come from created_at / updated_at; soft deletes from deleted_at;
+// hard deletes (knowledge items, purges) leave a row in sync_tombstones. Tombstones are
+// kept TOMBSTONE_RETENTION_DAYS, so an older watermark asks for a full refetch instead.
+
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+use sqlx::PgPool;
+use tauri::State;
+
+use crate::PosDb;
+use crate::pos::error::{PosResult, db_context};
+
+const TOMBSTONE_RETENTION_DAYS: i64 = 90;
+
+/// (entity name, table, whether the table soft-deletes via deleted_at)
+const SYNCED_TABLES: [(&str, &str, bool); 4] = [
+    ("goals", "unified_goals", true),
+    ("activities", "pos_activities", true),
+    ("knowledgeItems", "knowledge_items", false),
+    ("submissions", "pos_submissions", false),
+];
+
+// ─── Types ──────────────────────────────────────────────────────────
+
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EntityChanges {
+    pub created: Vec<String>,
+    pub updated: Vec<String>,
+    pub deleted: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangesSince {
+    pub since: DateTime<Utc>,
+    /// Pass this as `timestamp` next time
+    pub watermark: DateTime<Utc>,
+    /// `since` predates the retained tombstones; refetch everything instead
+    pub full_resync_required: bool,
+    pub goals: EntityChanges,
+    pub activities: EntityChanges,
+    pub knowledge_items: EntityChanges,
+    pub submissions: EntityChanges,
+}
+
+// ─── Tombstones ─────────────────────────────────────────────────────
+
+fn entity_for_table(table: &str) -> Option<&'static str> {
+    SYNCED_TABLES.iter().find(|(_, t, _)| *t == table).map(|(entity, _, _)| *entity)
+}
+
+/// Wrap a `DELETE FROM <table> ...` so the deleted ids of synced tables are tombstoned in
+/// the same statement; rows_affected still counts the deleted rows. Other tables pass through.
+pub(crate) fn with_tombstones(table: &str, delete_sql: &str) -> String {
+    match entity_for_table(table) {
+        Some(entity) => format!(
+            r#"WITH gone AS ({} RETURNING id)
+               INSERT INTO sync_tombstones (entity, id, deleted_at)
+               SELECT '{}', id, NOW() FROM gone
+               ON CONFLICT (entity, id) DO UPDATE SET deleted_at = NOW()"#,
+            delete_sql, entity
+        ),
+        None => delete_sql.to_string(),
+    }
+}
+
+/// Drop tombstones past retention (startup)
+pub async fn prune_tombstones(pool: &PgPool) -> Result<u64, sqlx::Error> {
+    Ok(sqlx::query("DELETE FROM sync_tombstones WHERE deleted_at < NOW() - make_interval(days => $1)")
+        .bind(TOMBSTONE_RETENTION_DAYS as i32)
+        .execute(pool)
+        .await?
+        .rows_affected())
+}
+
+// ─── Commands ───────────────────────────────────────────────────────
+
+/// Ids created, updated and deleted after `timestamp` for goals, activities, knowledge
+/// items and submissions. An id changed more than once is listed by its latest state.
+#[tauri::command]
+pub async fn get_changes_since(
+    db: State<'_, PosDb>,
+    timestamp: DateTime<Utc>,
+) -> PosResult<ChangesSince> {
+    let pool = &db.0;
+    // Taken before reading so concurrent writes show up again next time rather than never
+    let watermark: DateTime<Utc> = sqlx::query_scalar("SELECT NOW()")
+        .fetch_one(pool)
+        .await
+        .map_err(|e| db_context("sync watermark", e))?;
+
+    let mut changes: [EntityChanges; SYNCED_TABLES.len()] = Default::default();
+    for (i, (entity, table, soft_delete)) in SYNCED_TABLES.into_iter().enumerate() {
+        let deleted = if soft_delete { "deleted_at IS NOT NULL" } else { "FALSE" };
+        let deleted_since = if soft_delete { "OR deleted_at > $1" } else { "" };
+        let rows: Vec<(String, String)> = sqlx::query_as(&format!(
+            r#"SELECT id, CASE WHEN {deleted} THEN 'deleted'
+                               WHEN created_at > $1 THEN 'created'
+                               ELSE 'updated' END
+               FROM {table}
+               WHERE created_at > $1 OR updated_at > $1 {deleted_since}
+               ORDER BY id"#
+        ))
+        .bind(timestamp)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| db_context(&format!("changes for {}", entity), e))?;
+
+        for (id, kind) in rows {
+            match kind.as_str() {
+                "created" => changes[i].created.push(id),
+                "updated" => changes[i].updated.push(id),
+                _ => changes[i].deleted.push(id),
+            }
+        }
+    }
+
+    let tombstones: Vec<(String, String)> = sqlx::query_as(
+        "SELECT entity, id FROM sync_tombstones WHERE deleted_at > $1 ORDER BY id"
+    )
+    .bind(timestamp)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| db_context("changes tombstones", e))?;
+    for (entity, id) in tombstones {
+        if let Some(i) = SYNCED_TABLES.iter().position(|(e, _, _)| *e == entity) {
+            changes[i].deleted.push(id);
+        }
+    }
+
+    let [goals, activities, knowledge_items, submissions] = changes;
+    Ok(ChangesSince {
+        since: timestamp,
+        watermark,
+        full_resync_required: timestamp < Utc::now() - Duration::days(TOMBSTONE_RETENTION_DAYS),
+        goals,
+        activities,
+        knowledge_items,
+        submissions,
+    })
+}
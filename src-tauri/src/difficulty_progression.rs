@@ -0,0 +1,177 @@
+// Difficulty Progression
+// Month-by-month counts of newly solved problems per difficulty band: Codeforces by
+// 200-point rating band, LeetCode by Easy/Medium/Hard. Each problem counts once, in the
+// month of its first accepted submission (local time), so re-solves don't inflate a band.
+// The monthly average and max CF rating give the "am I climbing?" line.
+
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Datelike, Months, NaiveDate, Utc};
+use serde::Serialize;
+use tauri::State;
+
+use crate::PosDb;
+use crate::pos::error::{PosError, PosResult, db_context};
+use crate::pos::timezone;
+
+const DEFAULT_MONTHS: u32 = 12;
+const MAX_MONTHS: u32 = 120;
+const CF_BAND_FLOOR: i32 = 800;
+const CF_BAND_WIDTH: i32 = 200;
+const LC_BANDS: [&str; 3] = ["Easy", "Medium", "Hard"];
+
+// ─── Types ──────────────────────────────────────────────────────────
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BandSeries {
+    /// "800–1000", "unrated", "Easy"…
+    pub band: String,
+    /// One count per entry of `months`
+    pub counts: Vec<i64>,
+    pub total: i64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DifficultyProgression {
+    /// YYYY-MM, oldest first
+    pub months: Vec<String>,
+    pub codeforces: Vec<BandSeries>,
+    pub leetcode: Vec<BandSeries>,
+    /// Mean rating of the month's rated CF solves
+    pub cf_average_rating: Vec<Option<f64>>,
+    pub cf_max_rating: Vec<Option<i32>>,
+}
+
+#[derive(sqlx::FromRow)]
+struct FirstSolve {
+    platform: String,
+    solved_at: DateTime<Utc>,
+    rating: Option<i32>,
+    difficulty: Option<String>,
+}
+
+// ─── Helpers ────────────────────────────────────────────────────────
+
+/// Lower bound of the 200-point band (ratings under 800 join the first band)
+fn cf_band(rating: i32) -> i32 {
+    CF_BAND_FLOOR + (rating.max(CF_BAND_FLOOR) - CF_BAND_FLOOR) / CF_BAND_WIDTH * CF_BAND_WIDTH
+}
+
+fn lc_band(difficulty: Option<&str>) -> Option<&'static str> {
+    let d = difficulty?.trim();
+    LC_BANDS.iter().copied().find(|b| b.eq_ignore_ascii_case(d))
+}
+
+/// Months elapsed from `first` (a month start) to `date`
+fn month_offset(first: NaiveDate, date: NaiveDate) -> Option<usize> {
+    let months = (date.year() - first.year()) * 12 + date.month() as i32 - first.month() as i32;
+    usize::try_from(months).ok()
+}
+
+fn series<K: Ord>(bands: BTreeMap<K, Vec<i64>>, label: impl Fn(&K) -> String) -> Vec<BandSeries> {
+    bands.into_iter()
+        .map(|(k, counts)| BandSeries { band: label(&k), total: counts.iter().sum(), counts })
+        .collect()
+}
+
+// ─── Command ────────────────────────────────────────────────────────
+
+/// Newly solved problems per month and difficulty band over the last `months` months
+/// (including the current one, default 12)
+#[tauri::command]
+pub async fn get_difficulty_progression(
+    db: State<'_, PosDb>,
+    months: Option<u32>,
+) -> PosResult<DifficultyProgression> {
+    let count = months.unwrap_or(DEFAULT_MONTHS);
+    if !(1..=MAX_MONTHS).contains(&count) {
+        return Err(PosError::InvalidInput(format!("months must be between 1 and {}", MAX_MONTHS)));
+    }
+    let today = timezone::today();
+    let first = NaiveDate::from_ymd_opt(today.year(), today.month(), 1)
+        .and_then(|d| d.checked_sub_months(Months::new(count - 1)))
+        .ok_or_else(|| PosError::InvalidInput("months reaches before the calendar".into()))?;
+    let since = first.and_hms_opt(0, 0, 0)
+        .and_then(timezone::from_local)
+        .ok_or_else(|| PosError::InvalidInput(format!("No local midnight on {}", first)))?;
+
+    let solves = sqlx::query_as::<_, FirstSolve>(
+        r#"SELECT platform, solved_at, rating, difficulty FROM (
+               SELECT DISTINCT ON (problem_id) platform, submitted_time AS solved_at, rating, difficulty
+               FROM pos_submissions
+               WHERE verdict IN ('OK', 'Accepted', 'AC') AND excluded_at IS NULL
+                 AND platform IN ('codeforces', 'leetcode')
+               ORDER BY problem_id, submitted_time
+           ) f
+           WHERE solved_at >= $1"#
+    )
+    .bind(since)
+    .fetch_all(&db.0)
+    .await
+    .map_err(|e| db_context("get_difficulty_progression", e))?;
+
+    let n = count as usize;
+    let mut cf: BTreeMap<Option<i32>, Vec<i64>> = BTreeMap::new();
+    let mut lc: BTreeMap<usize, Vec<i64>> = BTreeMap::new();
+    let mut cf_ratings: Vec<Vec<i32>> = vec![Vec::new(); n];
+    for s in &solves {
+        let Some(i) = month_offset(first, timezone::local_date(s.solved_at)).filter(|i| *i < n) else {
+            continue;
+        };
+        if s.platform == "codeforces" {
+            // None (unrated) sorts first in the map; moved to the end below
+            cf.entry(s.rating.map(cf_band)).or_insert_with(|| vec![0; n])[i] += 1;
+            if let Some(r) = s.rating {
+                cf_ratings[i].push(r);
+            }
+        } else if let Some(band) = lc_band(s.difficulty.as_deref()) {
+            let rank = LC_BANDS.iter().position(|b| *b == band).unwrap_or(0);
+            lc.entry(rank).or_insert_with(|| vec![0; n])[i] += 1;
+        }
+    }
+
+    let mut codeforces = series(cf, |band| match band {
+        Some(lo) => format!("{}–{}", lo, lo + CF_BAND_WIDTH),
+        None => "unrated".to_string(),
+    });
+    if codeforces.first().is_some_and(|b| b.band == "unrated") {
+        codeforces.rotate_left(1);
+    }
+    Ok(DifficultyProgression {
+        months: (0..count)
+            .filter_map(|i| first.checked_add_months(Months::new(i)))
+            .map(|m| m.format("%Y-%m").to_string())
+            .collect(),
+        codeforces,
+        leetcode: series(lc, |rank| LC_BANDS[*rank].to_string()),
+        cf_average_rating: cf_ratings.iter()
+            .map(|r| (!r.is_empty()).then(|| r.iter().sum::<i32>() as f64 / r.len() as f64))
+            .collect(),
+        cf_max_rating: cf_ratings.iter().map(|r| r.iter().copied().max()).collect(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bands() {
+        assert_eq!(cf_band(800), 800);
+        assert_eq!(cf_band(999), 800);
+        assert_eq!(cf_band(1000), 1000);
+        assert_eq!(cf_band(1450), 1400);
+        assert_eq!(cf_band(500), 800);
+        assert_eq!(lc_band(Some("medium")), Some("Medium"));
+        assert_eq!(lc_band(Some("Insane")), None);
+    }
+
+    #[test]
+    fn test_month_offset() {
+        let first = NaiveDate::from_ymd_opt(2025, 11, 1).unwrap();
+        assert_eq!(month_offset(first, NaiveDate::from_ymd_opt(2026, 2, 14).unwrap()), Some(3));
+        assert_eq!(month_offset(first, NaiveDate::from_ymd_opt(2025, 10, 31).unwrap()), None);
+    }
+}
@@ -0,0 +1,118 @@
+// Drafts
+// Half-edited goals and activities are kept server-side per (entity, window) so they
+// survive a window closing or the app crashing, and can be picked up from any window.
+// `entity` is whatever key the editor uses ("goal:<id>", "activity:new", ...). Drafts
+// untouched for DRAFT_TTL_HOURS are dropped at startup and ignored before then.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_json::Value;
+use sqlx::PgPool;
+use tauri::State;
+
+use crate::PosDb;
+use crate::pos::error::{PosError, PosResult, db_context};
+
+const DRAFT_TTL_HOURS: i32 = 72;
+/// Payloads above this are almost certainly not a form's state
+const MAX_PAYLOAD_BYTES: usize = 256 * 1024;
+
+// ─── Types ──────────────────────────────────────────────────────────
+
+#[derive(Debug, Serialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct DraftRow {
+    pub entity: String,
+    pub window_label: String,
+    pub payload: Value,
+    pub updated_at: DateTime<Utc>,
+}
+
+// ─── Helpers ────────────────────────────────────────────────────────
+
+fn validate_entity(entity: &str) -> PosResult<&str> {
+    let entity = entity.trim();
+    if entity.is_empty() {
+        return Err(PosError::InvalidInput("Draft entity cannot be empty".into()));
+    }
+    Ok(entity)
+}
+
+/// Drop drafts past their TTL (startup)
+pub async fn prune_expired_drafts(pool: &PgPool) -> Result<u64, sqlx::Error> {
+    Ok(sqlx::query("DELETE FROM drafts WHERE updated_at < NOW() - make_interval(hours => $1)")
+        .bind(DRAFT_TTL_HOURS)
+        .execute(pool)
+        .await?
+        .rows_affected())
+}
+
+// ─── Commands ───────────────────────────────────────────────────────
+
+/// Store (or replace) this window's draft of `entity`
+#[tauri::command]
+pub async fn save_draft(
+    db: State<'_, PosDb>,
+    entity: String,
+    window_label: String,
+    payload: Value,
+) -> PosResult<DraftRow> {
+    let entity = validate_entity(&entity)?;
+    if payload.to_string().len() > MAX_PAYLOAD_BYTES {
+        return Err(PosError::InvalidInput(format!("Draft payload exceeds {} KB", MAX_PAYLOAD_BYTES / 1024)));
+    }
+    sqlx::query_as::<_, DraftRow>(
+        r#"INSERT INTO drafts (entity, window_label, payload, updated_at)
+           VALUES ($1, $2, $3, NOW())
+           ON CONFLICT (entity, window_label) DO UPDATE
+           SET payload = EXCLUDED.payload, updated_at = EXCLUDED.updated_at
+           RETURNING entity, window_label, payload, updated_at"#
+    )
+    .bind(entity)
+    .bind(window_label.trim())
+    .bind(&payload)
+    .fetch_one(&db.0)
+    .await
+    .map_err(|e| db_context("save_draft", e))
+}
+
+/// Draft of `entity` from `window_label`, or the most recently saved one from any window
+/// when no label is given. None when there is no live draft.
+#[tauri::command]
+pub async fn get_draft(
+    db: State<'_, PosDb>,
+    entity: String,
+    window_label: Option<String>,
+) -> PosResult<Option<DraftRow>> {
+    let entity = validate_entity(&entity)?;
+    sqlx::query_as::<_, DraftRow>(
+        r#"SELECT entity, window_label, payload, updated_at FROM drafts
+           WHERE entity = $1 AND ($2::text IS NULL OR window_label = $2)
+             AND updated_at >= NOW() - make_interval(hours => $3)
+           ORDER BY updated_at DESC
+           LIMIT 1"#
+    )
+    .bind(entity)
+    .bind(window_label.as_deref().map(str::trim))
+    .bind(DRAFT_TTL_HOURS)
+    .fetch_optional(&db.0)
+    .await
+    .map_err(|e| db_context("get_draft", e))
+}
+
+/// Discard drafts of `entity` (after a successful save): one window's, or all of them
+#[tauri::command]
+pub async fn discard_draft(
+    db: State<'_, PosDb>,
+    entity: String,
+    window_label: Option<String>,
+) -> PosResult<u64> {
+    let entity = validate_entity(&entity)?;
+    Ok(sqlx::query("DELETE FROM drafts WHERE entity = $1 AND ($2::text IS NULL OR window_label = $2)")
+        .bind(entity)
+        .bind(window_label.as_deref().map(str::trim))
+        .execute(&db.0)
+        .await
+        .map_err(|e| db_context("discard_draft", e))?
+        .rows_affected())
+}
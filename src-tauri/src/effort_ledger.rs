@@ -0,0 +1,391 @@
+// Effort Ledger
+// One currency across systems: productive activity time, first solves (weighted by
+// rating), knowledge reviews and GitHub commits each award points into effort_ledger at
+// the rate in effort_point_rules (DEFAULT_RULES until changed). Awards are booked once per
+// source row and keep the rate they were booked at. Edited activities are re-booked at
+// that rate; deleted (or no longer productive) ones are voided. A repo's commits count
+// from the first sync that sees it: its existing total becomes the baseline, unpaid.
+// Milestones with target_metric "effort_points" count the ledger's points in their period.
+
+use chrono::NaiveDate;
+use serde::Serialize;
+use sqlx::PgPool;
+use tauri::State;
+
+use crate::PosDb;
+use crate::pos::error::{PosError, PosResult, db_context};
+use crate::pos::utils::gen_id;
+
+pub const EFFORT_METRIC: &str = "effort_points";
+
+/// (source, points per unit, unit)
+const DEFAULT_RULES: [(&str, f64, &str); 4] = [
+    ("activity", 10.0, "hour of productive activity"),
+    ("solve", 10.0, "first accept at rating 1000 (scaled by rating / 1000)"),
+    ("review", 2.0, "knowledge review"),
+    ("commit", 3.0, "commit"),
+];
+/// Unrated problems (LeetCode, gym) are weighted like this
+const UNRATED_WEIGHT_RATING: f64 = 1000.0;
+
+// ─── Types ──────────────────────────────────────────────────────────
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EffortRule {
+    pub source: String,
+    pub points: f64,
+    pub unit: String,
+}
+
+#[derive(Debug, Serialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct EffortSourceTotal {
+    pub source: String,
+    pub units: f64,
+    pub points: f64,
+    pub entries: i64,
+}
+
+#[derive(Debug, Serialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct EffortDay {
+    pub day: NaiveDate,
+    pub points: f64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EffortBalance {
+    pub start_date: String,
+    pub end_date: String,
+    pub total: f64,
+    pub by_source: Vec<EffortSourceTotal>,
+    pub by_day: Vec<EffortDay>,
+}
+
+// ─── Helpers ────────────────────────────────────────────────────────
+
+fn validate_source(source: &str) -> PosResult<()> {
+    if DEFAULT_RULES.iter().any(|(s, _, _)| *s == source) {
+        Ok(())
+    } else {
+        let known: Vec<&str> = DEFAULT_RULES.iter().map(|(s, _, _)| *s).collect();
+        Err(PosError::InvalidInput(format!("Unknown effort source '{}'. Use {}", source, known.join(", "))))
+    }
+}
+
+fn parse_date(raw: &str, field: &str) -> PosResult<NaiveDate> {
+    NaiveDate::parse_from_str(raw, "%Y-%m-%d")
+        .map_err(|e| PosError::InvalidInput(format!("Invalid {}: {}", field, e)))
+}
+
+/// Points per unit for `source`, overridden values first
+async fn rate(pool: &PgPool, source: &str) -> PosResult<f64> {
+    let custom: Option<f64> = sqlx::query_scalar("SELECT points FROM effort_point_rules WHERE source = $1")
+        .bind(source)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| db_context("effort rule", e))?;
+    Ok(custom.unwrap_or_else(|| {
+        DEFAULT_RULES.iter().find(|(s, _, _)| *s == source).map_or(0.0, |(_, p, _)| *p)
+    }))
+}
+
+/// Points a solve is worth at `per_solve` for a problem of `rating`
+fn solve_points(per_solve: f64, rating: Option<i32>) -> f64 {
+    let rating = rating.filter(|r| *r > 0).map_or(UNRATED_WEIGHT_RATING, f64::from);
+    (per_solve * rating / 1000.0 * 100.0).round() / 100.0
+}
+
+/// Points booked between two local dates (inclusive)
+pub(crate) async fn points_between(pool: &PgPool, start: &str, end: &str) -> PosResult<f64> {
+    sqlx::query_scalar("SELECT COALESCE(SUM(points), 0)::float8 FROM effort_ledger WHERE day BETWEEN $1::date AND $2::date")
+        .bind(start)
+        .bind(end)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| db_context("effort points between", e))
+}
+
+/// Book one knowledge review (called from submit_review). Never fails the caller.
+pub(crate) async fn award_review(pool: &PgPool, item_id: &str, reviewed_at: chrono::DateTime<chrono::Utc>) {
+    let res: PosResult<()> = async {
+        let points = rate(pool, "review").await?;
+        sqlx::query(
+            r#"INSERT INTO effort_ledger (id, source, source_id, day, units, points, detail, created_at)
+               VALUES ($1, 'review', $2, $3, 1, $4, $5, NOW())
+               ON CONFLICT (source, source_id) DO NOTHING"#
+        )
+        .bind(gen_id())
+        .bind(format!("{}@{}", item_id, reviewed_at.timestamp()))
+        .bind(crate::pos::timezone::local_date(reviewed_at))
+        .bind(points)
+        .bind(item_id)
+        .execute(pool)
+        .await
+        .map_err(|e| db_context("award review", e))?;
+        Ok(())
+    }.await;
+    if let Err(e) = res {
+        log::warn!("[EFFORT] Failed to book review of {}: {}", item_id, e);
+    }
+}
+
+/// Book everything not yet in the ledger: new productive activities, first solves and
+/// commits beyond what each repo has been credited so far. Voids deleted activities and
+/// refreshes effort-point milestones when anything changed. Returns rows booked.
+pub async fn sync_effort_ledger(pool: &PgPool) -> PosResult<u64> {
+    let (activity_rate, solve_rate, commit_rate) =
+        tokio::try_join!(rate(pool, "activity"), rate(pool, "solve"), rate(pool, "commit"))?;
+    let mut tx = pool.begin().await.map_err(|e| db_context("TX begin", e))?;
+
+    let voided = sqlx::query(
+        r#"DELETE FROM effort_ledger l WHERE l.source = 'activity' AND NOT EXISTS (
+               SELECT 1 FROM pos_activities a
+               WHERE a.id = l.source_id AND a.deleted_at IS NULL AND a.is_productive AND NOT a.is_shadow
+                 AND a.end_time > a.start_time)"#
+    )
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| db_context("void deleted activities", e))?
+    .rows_affected();
+
+    // Shadow activities mirror submissions and commits, which are booked on their own.
+    // An edited activity is re-booked at the rate of its original award.
+    let activities = sqlx::query(
+        r#"INSERT INTO effort_ledger (id, source, source_id, day, units, points, detail, created_at)
+           SELECT gen_random_uuid()::text, 'activity', a.id, a.date::date, m.minutes / 60.0,
+                  ROUND((m.minutes / 60.0 * $1)::numeric, 2)::float8, a.title, NOW()
+           FROM pos_activities a
+           CROSS JOIN LATERAL (SELECT EXTRACT(EPOCH FROM (a.end_time - a.start_time)) / 60 AS minutes) m
+           WHERE a.is_productive AND NOT a.is_shadow AND a.deleted_at IS NULL AND m.minutes > 0
+           ON CONFLICT (source, source_id) DO UPDATE
+           SET day = EXCLUDED.day, units = EXCLUDED.units, detail = EXCLUDED.detail,
+               points = CASE WHEN effort_ledger.units > 0
+                             THEN ROUND((EXCLUDED.units * effort_ledger.points / effort_ledger.units)::numeric, 2)::float8
+                             ELSE EXCLUDED.points END
+           WHERE effort_ledger.day <> EXCLUDED.day OR effort_ledger.units <> EXCLUDED.units
+              OR effort_ledger.detail IS DISTINCT FROM EXCLUDED.detail"#
+    )
+    .bind(activity_rate)
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| db_context("book activities", e))?
+    .rows_affected();
+
+    let solves: Vec<(String, chrono::DateTime<chrono::Utc>, Option<i32>, String)> = sqlx::query_as(
+        r#"SELECT DISTINCT ON (s.problem_id) s.problem_id, s.submitted_time, s.rating, s.problem_title
+           FROM pos_submissions s
+           WHERE s.verdict IN ('OK', 'Accepted', 'AC') AND s.excluded_at IS NULL
+             AND NOT EXISTS (SELECT 1 FROM effort_ledger l WHERE l.source = 'solve' AND l.source_id = s.problem_id)
+           ORDER BY s.problem_id, s.submitted_time"#
+    )
+    .fetch_all(&mut *tx)
+    .await
+    .map_err(|e| db_context("unbooked solves", e))?;
+    for (problem_id, solved_at, rating, title) in &solves {
+        sqlx::query(
+            r#"INSERT INTO effort_ledger (id, source, source_id, day, units, points, detail, created_at)
+               VALUES ($1, 'solve', $2, $3, 1, $4, $5, NOW())
+               ON CONFLICT (source, source_id) DO NOTHING"#
+        )
+        .bind(gen_id())
+        .bind(problem_id)
+        .bind(crate::pos::timezone::local_date(*solved_at))
+        .bind(solve_points(solve_rate, *rating))
+        .bind(title)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| db_context("book solve", e))?;
+    }
+
+    // Backfilled history books on its own day; anything beyond it books today
+    let backfilled = sqlx::query(
+        r#"INSERT INTO effort_ledger (id, source, source_id, day, units, points, detail, created_at)
+           SELECT gen_random_uuid()::text, 'commit', b.repo || '@' || b.day, b.day, b.commit_count,
+                  b.commit_count * $1, b.repo, NOW()
+           FROM github_backfill_days b
+           ON CONFLICT (source, source_id) DO NOTHING"#
+    )
+    .bind(commit_rate)
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| db_context("book backfilled commits", e))?
+    .rows_affected();
+    // A repo seen for the first time starts at its current total, so its lifetime
+    // commits don't land on today as one spike
+    sqlx::query(
+        r#"INSERT INTO effort_commit_baselines (repo, commits, updated_at)
+           SELECT full_name, total_commits, NOW() FROM github_repositories
+           ON CONFLICT (repo) DO NOTHING"#
+    )
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| db_context("seed commit baselines", e))?;
+    let today = crate::pos::timezone::today();
+    let commits = sqlx::query(
+        r#"INSERT INTO effort_ledger (id, source, source_id, day, units, points, detail, created_at)
+           SELECT gen_random_uuid()::text, 'commit', g.full_name || '@' || $2::date, $2::date,
+                  g.total_commits - b.commits, (g.total_commits - b.commits) * $1, g.full_name, NOW()
+           FROM github_repositories g
+           JOIN effort_commit_baselines b ON b.repo = g.full_name
+           WHERE g.total_commits > b.commits
+           ON CONFLICT (source, source_id) DO UPDATE
+           SET units = effort_ledger.units + EXCLUDED.units, points = effort_ledger.points + EXCLUDED.points"#
+    )
+    .bind(commit_rate)
+    .bind(today)
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| db_context("book commits", e))?
+    .rows_affected();
+    // Follow the total down too (history rewrites), so later commits still count
+    sqlx::query(
+        r#"UPDATE effort_commit_baselines b SET commits = g.total_commits, updated_at = NOW()
+           FROM github_repositories g
+           WHERE g.full_name = b.repo AND g.total_commits <> b.commits"#
+    )
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| db_context("advance commit baselines", e))?;
+
+    tx.commit().await.map_err(|e| db_context("TX commit", e))?;
+
+    let booked = activities + solves.len() as u64 + backfilled + commits;
+    if booked + voided > 0 {
+        log::info!(
+            "[EFFORT] Booked {} activities, {} solves, {} commit rows; voided {}",
+            activities, solves.len(), backfilled + commits, voided
+        );
+        refresh_effort_milestones(pool).await;
+    }
+    Ok(booked)
+}
+
+/// Recompute milestones that target effort points
+async fn refresh_effort_milestones(pool: &PgPool) {
+    let ids: Vec<String> = match sqlx::query_scalar(
+        "SELECT id FROM goal_periods WHERE target_metric = $1 AND deleted_at IS NULL AND period_end >= NOW()"
+    )
+    .bind(EFFORT_METRIC)
+    .fetch_all(pool)
+    .await
+    {
+        Ok(ids) => ids,
+        Err(e) => {
+            log::warn!("[EFFORT] Failed to list effort milestones: {}", e);
+            return;
+        }
+    };
+    for id in ids {
+        if let Err(e) = crate::milestones::recompute_milestone(pool, &id).await {
+            log::warn!("[EFFORT] Failed to refresh milestone {}: {}", id, e);
+        }
+    }
+}
+
+// ─── Commands ───────────────────────────────────────────────────────
+
+/// Points earned between `start_date` and `end_date` (inclusive, YYYY-MM-DD), by source
+/// and by day. Books anything new first.
+#[tauri::command]
+pub async fn get_effort_balance(
+    db: State<'_, PosDb>,
+    start_date: String,
+    end_date: String,
+) -> PosResult<EffortBalance> {
+    let pool = &db.0;
+    let (start, end) = (parse_date(&start_date, "start_date")?, parse_date(&end_date, "end_date")?);
+    if start > end {
+        return Err(PosError::InvalidInput("start_date must not be after end_date".into()));
+    }
+    sync_effort_ledger(pool).await?;
+
+    let (by_source, by_day) = tokio::try_join!(
+        async {
+            sqlx::query_as::<_, EffortSourceTotal>(
+                r#"SELECT source, SUM(units)::float8 AS units, SUM(points)::float8 AS points, COUNT(*) AS entries
+                   FROM effort_ledger WHERE day BETWEEN $1 AND $2
+                   GROUP BY source ORDER BY points DESC"#
+            )
+            .bind(start)
+            .bind(end)
+            .fetch_all(pool)
+            .await
+            .map_err(|e| db_context("effort by source", e))
+        },
+        async {
+            sqlx::query_as::<_, EffortDay>(
+                r#"SELECT day, SUM(points)::float8 AS points FROM effort_ledger
+                   WHERE day BETWEEN $1 AND $2 GROUP BY day ORDER BY day"#
+            )
+            .bind(start)
+            .bind(end)
+            .fetch_all(pool)
+            .await
+            .map_err(|e| db_context("effort by day", e))
+        },
+    )?;
+
+    Ok(EffortBalance {
+        start_date,
+        end_date,
+        total: by_source.iter().map(|s| s.points).sum(),
+        by_source,
+        by_day,
+    })
+}
+
+/// Current points per unit for every source
+#[tauri::command]
+pub async fn get_effort_rules(db: State<'_, PosDb>) -> PosResult<Vec<EffortRule>> {
+    let custom: Vec<(String, f64)> = sqlx::query_as("SELECT source, points FROM effort_point_rules")
+        .fetch_all(&db.0)
+        .await
+        .map_err(|e| db_context("get_effort_rules", e))?;
+    Ok(DEFAULT_RULES.iter().map(|(source, default, unit)| EffortRule {
+        source: source.to_string(),
+        points: custom.iter().find(|(s, _)| s == source).map_or(*default, |(_, p)| *p),
+        unit: unit.to_string(),
+    }).collect())
+}
+
+/// Change a source's rate. Applies to awards booked from now on.
+#[tauri::command]
+pub async fn set_effort_rule(
+    db: State<'_, PosDb>,
+    source: String,
+    points: f64,
+) -> PosResult<EffortRule> {
+    validate_source(&source)?;
+    if !points.is_finite() || points < 0.0 {
+        return Err(PosError::InvalidInput("points must be a non-negative number".into()));
+    }
+    sqlx::query(
+        r#"INSERT INTO effort_point_rules (source, points, updated_at) VALUES ($1, $2, NOW())
+           ON CONFLICT (source) DO UPDATE SET points = EXCLUDED.points, updated_at = NOW()"#
+    )
+    .bind(&source)
+    .bind(points)
+    .execute(&db.0)
+    .await
+    .map_err(|e| db_context("set_effort_rule", e))?;
+
+    let unit = DEFAULT_RULES.iter().find(|(s, _, _)| *s == source).map_or("", |(_, _, u)| *u);
+    log::info!("[EFFORT] {} now earns {} points per {}", source, points, unit);
+    Ok(EffortRule { source, points, unit: unit.to_string() })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_solve_points_scale_with_rating() {
+        assert_eq!(solve_points(10.0, Some(1600)), 16.0);
+        assert_eq!(solve_points(10.0, None), 10.0);
+        assert_eq!(solve_points(10.0, Some(0)), 10.0);
+        assert_eq!(solve_points(3.0, Some(1433)), 4.3);
+    }
+}
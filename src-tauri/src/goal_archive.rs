@@ -0,0 +1,112 @@
+// Goal Archive
+// Archived goals are hidden from the active list, debt trail and dashboard but stay
+// browsable (paginated) and can be restored. Partial indexes keep both paths cheap.
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+use crate::PosDb;
+use crate::pos::error::{PosError, PosResult, db_context};
+use crate::unified_goals::{UnifiedGoalRow, UNIFIED_GOAL_COLS};
+
+const DEFAULT_PAGE_SIZE: i64 = 50;
+const MAX_PAGE_SIZE: i64 = 200;
+
+// ─── Types ──────────────────────────────────────────────────────────
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchivedGoalFilters {
+    pub search: Option<String>,
+    pub completed: Option<bool>,
+    pub label: Option<String>,
+    pub date_from: Option<String>, // YYYY-MM-DD (goal date)
+    pub date_to: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchivedGoalsPage {
+    pub goals: Vec<UnifiedGoalRow>,
+    pub total: i64,
+    pub page: i64,
+    pub page_size: i64,
+}
+
+// ─── Helpers ────────────────────────────────────────────────────────
+
+async fn set_archived(db: &PosDb, id: &str, archived: bool) -> PosResult<UnifiedGoalRow> {
+    let row = sqlx::query_as::<_, UnifiedGoalRow>(&format!(
+        "UPDATE unified_goals
+         SET archived_at = CASE WHEN $1 THEN COALESCE(archived_at, $2) ELSE NULL END,
+             updated_at = $2
+         WHERE id = $3 AND deleted_at IS NULL
+         RETURNING {}",
+        UNIFIED_GOAL_COLS
+    ))
+    .bind(archived)
+    .bind(Utc::now())
+    .bind(id)
+    .fetch_optional(&db.0)
+    .await
+    .map_err(|e| db_context("set goal archived", e))?
+    .ok_or_else(|| PosError::NotFound(format!("Goal not found: {}", id)))?;
+
+    crate::dashboard::mark_snapshot_stale(&db.0).await;
+    Ok(row)
+}
+
+// ─── Commands ───────────────────────────────────────────────────────
+
+#[tauri::command]
+pub async fn archive_goal(db: State<'_, PosDb>, id: String) -> PosResult<UnifiedGoalRow> {
+    let row = set_archived(&db, &id, true).await?;
+    log::info!("[UnifiedGoals] Archived goal {}", id);
+    Ok(row)
+}
+
+#[tauri::command]
+pub async fn unarchive_goal(db: State<'_, PosDb>, id: String) -> PosResult<UnifiedGoalRow> {
+    let row = set_archived(&db, &id, false).await?;
+    log::info!("[UnifiedGoals] Restored goal {}", id);
+    Ok(row)
+}
+
+/// Archived goals, most recently archived first. `page` is 1-based.
+#[tauri::command]
+pub async fn get_archived_goals(
+    db: State<'_, PosDb>,
+    filters: Option<ArchivedGoalFilters>,
+    page: Option<i64>,
+    page_size: Option<i64>,
+) -> PosResult<ArchivedGoalsPage> {
+    let pool = &db.0;
+    let f = filters.unwrap_or_default();
+    let page = page.unwrap_or(1).max(1);
+    let page_size = page_size.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_PAGE_SIZE);
+    let search = f.search.filter(|s| !s.trim().is_empty()).map(|s| format!("%{}%", s.trim()));
+
+    const WHERE: &str = "archived_at IS NOT NULL AND deleted_at IS NULL
+           AND ($1::text IS NULL OR text ILIKE $1 OR description ILIKE $1)
+           AND ($2::boolean IS NULL OR completed = $2)
+           AND ($3::text IS NULL OR labels ? $3)
+           AND ($4::text IS NULL OR date >= $4)
+           AND ($5::text IS NULL OR date <= $5)";
+
+    let (goals, total) = tokio::try_join!(
+        sqlx::query_as::<_, UnifiedGoalRow>(&format!(
+            "SELECT {} FROM unified_goals WHERE {} ORDER BY archived_at DESC, id LIMIT $6 OFFSET $7",
+            UNIFIED_GOAL_COLS, WHERE
+        ))
+        .bind(&search).bind(f.completed).bind(&f.label).bind(&f.date_from).bind(&f.date_to)
+        .bind(page_size).bind((page - 1) * page_size)
+        .fetch_all(pool),
+
+        sqlx::query_scalar::<_, i64>(&format!("SELECT COUNT(*) FROM unified_goals WHERE {}", WHERE))
+        .bind(&search).bind(f.completed).bind(&f.label).bind(&f.date_from).bind(&f.date_to)
+        .fetch_one(pool),
+    ).map_err(|e| db_context("get_archived_goals", e))?;
+
+    Ok(ArchivedGoalsPage { goals, total, page, page_size })
+}
@@ -0,0 +1,177 @@
+// Goal Dependencies
+// "goal_id depends on depends_on_id": the dependency blocks completion until it is done.
+// Completion paths (update_unified_goal, link_activity_to_unified_goal, set_goal_completed)
+// call `ensure_unblocked`, which an explicit override flag skips. Cycles are rejected on insert.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::PgPool;
+use tauri::State;
+
+use crate::PosDb;
+use crate::pos::error::{PosError, PosResult, db_context};
+
+// ─── Types ──────────────────────────────────────────────────────────
+
+#[derive(Debug, Serialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct GoalDependency {
+    pub goal_id: String,
+    pub depends_on_id: String,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct GoalGraphNode {
+    pub id: String,
+    pub text: String,
+    pub date: Option<String>,
+    pub completed: bool,
+    /// Has at least one incomplete dependency
+    pub blocked: bool,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GoalGraph {
+    /// Goals that appear in at least one dependency
+    pub nodes: Vec<GoalGraphNode>,
+    pub edges: Vec<GoalDependency>,
+}
+
+// ─── Enforcement ────────────────────────────────────────────────────
+
+/// Fail with InvalidInput while `goal_id` has incomplete, non-deleted dependencies,
+/// unless `override_dependencies` is set
+pub async fn ensure_unblocked(pool: &PgPool, goal_id: &str, override_dependencies: bool) -> PosResult<()> {
+    if override_dependencies {
+        return Ok(());
+    }
+    let blockers: Vec<String> = sqlx::query_scalar(
+        r#"SELECT g.text FROM goal_dependencies d
+           JOIN unified_goals g ON g.id = d.depends_on_id
+           WHERE d.goal_id = $1 AND g.completed = FALSE AND g.deleted_at IS NULL
+           ORDER BY g.text"#
+    )
+    .bind(goal_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| db_context("check goal dependencies", e))?;
+
+    if blockers.is_empty() {
+        Ok(())
+    } else {
+        Err(PosError::InvalidInput(format!(
+            "Goal is blocked by {} incomplete dependencies: {}. Complete them first or pass overrideDependencies",
+            blockers.len(), blockers.join(", ")
+        )))
+    }
+}
+
+// ─── Commands ───────────────────────────────────────────────────────
+
+/// Make `goal_id` depend on `depends_on_id`. Rejects self-links and cycles.
+#[tauri::command]
+pub async fn add_goal_dependency(
+    db: State<'_, PosDb>,
+    goal_id: String,
+    depends_on_id: String,
+) -> PosResult<GoalDependency> {
+    let pool = &db.0;
+    if goal_id == depends_on_id {
+        return Err(PosError::InvalidInput("A goal cannot depend on itself".into()));
+    }
+
+    let found: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM unified_goals WHERE id IN ($1, $2) AND deleted_at IS NULL"
+    )
+    .bind(&goal_id)
+    .bind(&depends_on_id)
+    .fetch_one(pool)
+    .await
+    .map_err(|e| db_context("check dependency goals", e))?;
+    if found < 2 {
+        return Err(PosError::NotFound(format!("Goal not found: {} or {}", goal_id, depends_on_id)));
+    }
+
+    // A path depends_on_id → … → goal_id would close a loop
+    let creates_cycle: bool = sqlx::query_scalar(
+        r#"WITH RECURSIVE reach(id) AS (
+               SELECT depends_on_id FROM goal_dependencies WHERE goal_id = $1
+               UNION
+               SELECT d.depends_on_id FROM goal_dependencies d JOIN reach r ON d.goal_id = r.id
+           )
+           SELECT EXISTS (SELECT 1 FROM reach WHERE id = $2)"#
+    )
+    .bind(&depends_on_id)
+    .bind(&goal_id)
+    .fetch_one(pool)
+    .await
+    .map_err(|e| db_context("check dependency cycle", e))?;
+    if creates_cycle {
+        return Err(PosError::InvalidInput("Dependency would create a cycle".into()));
+    }
+
+    let dep = sqlx::query_as::<_, GoalDependency>(
+        r#"INSERT INTO goal_dependencies (goal_id, depends_on_id)
+           VALUES ($1, $2)
+           ON CONFLICT (goal_id, depends_on_id) DO UPDATE SET goal_id = EXCLUDED.goal_id
+           RETURNING goal_id, depends_on_id, created_at"#
+    )
+    .bind(&goal_id)
+    .bind(&depends_on_id)
+    .fetch_one(pool)
+    .await
+    .map_err(|e| db_context("add_goal_dependency", e))?;
+
+    log::info!("[UnifiedGoals] {} now depends on {}", goal_id, depends_on_id);
+    Ok(dep)
+}
+
+#[tauri::command]
+pub async fn remove_goal_dependency(
+    db: State<'_, PosDb>,
+    goal_id: String,
+    depends_on_id: String,
+) -> PosResult<()> {
+    let res = sqlx::query("DELETE FROM goal_dependencies WHERE goal_id = $1 AND depends_on_id = $2")
+        .bind(&goal_id)
+        .bind(&depends_on_id)
+        .execute(&db.0)
+        .await
+        .map_err(|e| db_context("remove_goal_dependency", e))?;
+    if res.rows_affected() == 0 {
+        return Err(PosError::NotFound(format!("No dependency {} → {}", goal_id, depends_on_id)));
+    }
+    Ok(())
+}
+
+/// Every dependency between non-deleted goals, with the goals involved
+#[tauri::command]
+pub async fn get_goal_graph(db: State<'_, PosDb>) -> PosResult<GoalGraph> {
+    let pool = &db.0;
+    let (edges, nodes) = tokio::try_join!(
+        sqlx::query_as::<_, GoalDependency>(
+            r#"SELECT d.goal_id, d.depends_on_id, d.created_at FROM goal_dependencies d
+               JOIN unified_goals a ON a.id = d.goal_id AND a.deleted_at IS NULL
+               JOIN unified_goals b ON b.id = d.depends_on_id AND b.deleted_at IS NULL
+               ORDER BY d.created_at"#
+        )
+        .fetch_all(pool),
+        sqlx::query_as::<_, GoalGraphNode>(
+            r#"SELECT g.id, g.text, g.date, g.completed,
+                      EXISTS (SELECT 1 FROM goal_dependencies d
+                              JOIN unified_goals b ON b.id = d.depends_on_id
+                              WHERE d.goal_id = g.id AND b.completed = FALSE AND b.deleted_at IS NULL) AS blocked
+               FROM unified_goals g
+               WHERE g.deleted_at IS NULL
+                 AND EXISTS (SELECT 1 FROM goal_dependencies d WHERE g.id IN (d.goal_id, d.depends_on_id))
+               ORDER BY g.date NULLS LAST, g.text"#
+        )
+        .fetch_all(pool),
+    )
+    .map_err(|e| db_context("get_goal_graph", e))?;
+
+    Ok(GoalGraph { nodes, edges })
+}
@@ -0,0 +1,170 @@
+// Batched Metric Updates
+// Applies many metric increments in one transaction so multi-metric logging forms
+// don't pay a round trip per field. Either every update lands or none do.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+use crate::PosDb;
+use crate::pos::error::{PosError, PosResult, db_context};
+use crate::pos::idempotency::idempotent;
+use crate::unified_goals::UnifiedGoalMetric;
+
+const MAX_BATCH: usize = 100;
+
+// ─── Types ──────────────────────────────────────────────────────────
+
+/// One increment. With `goal_id` it targets a metric inside `unified_goals.metrics`;
+/// without it, `metric_id` is a `pos_goal_metrics` row (integer counters only).
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MetricUpdate {
+    pub goal_id: Option<String>,
+    pub metric_id: String,
+    pub delta: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MetricValue {
+    pub goal_id: Option<String>,
+    pub metric_id: String,
+    pub current: f64,
+    pub target: f64,
+    pub reached: bool,
+}
+
+// ─── Helpers ────────────────────────────────────────────────────────
+
+fn validate(updates: &[MetricUpdate]) -> PosResult<()> {
+    if updates.is_empty() {
+        return Err(PosError::InvalidInput("No metric updates given".into()));
+    }
+    if updates.len() > MAX_BATCH {
+        return Err(PosError::InvalidInput(format!("At most {} metric updates per batch", MAX_BATCH)));
+    }
+    for u in updates {
+        if u.metric_id.trim().is_empty() {
+            return Err(PosError::InvalidInput("metric_id is required".into()));
+        }
+        if !u.delta.is_finite() {
+            return Err(PosError::InvalidInput(format!("Invalid delta for metric {}", u.metric_id)));
+        }
+        if u.goal_id.is_none() && u.delta.fract() != 0.0 {
+            return Err(PosError::InvalidInput(format!(
+                "Activity metric {} only accepts whole-number increments", u.metric_id
+            )));
+        }
+    }
+    Ok(())
+}
+
+// ─── Commands ───────────────────────────────────────────────────────
+
+/// Validate and apply all increments atomically, returning each metric's new value in
+/// request order. Fails (and rolls back) if any metric is unknown or would go negative.
+/// A replayed `idempotency_key` returns the first result without incrementing again.
+#[tauri::command]
+pub async fn batch_update_metrics(
+    db: State<'_, PosDb>,
+    updates: Vec<MetricUpdate>,
+    idempotency_key: Option<String>,
+) -> PosResult<Vec<MetricValue>> {
+    validate(&updates)?;
+    idempotent(&db.0, "batch_update_metrics", idempotency_key, apply_updates(&db.0, updates)).await
+}
+
+async fn apply_updates(pool: &sqlx::PgPool, updates: Vec<MetricUpdate>) -> PosResult<Vec<MetricValue>> {
+    let mut tx = pool.begin().await.map_err(|e| db_context("begin tx", e))?;
+
+    // Unified goal metrics live in a JSONB array: lock each goal once, apply all of its
+    // deltas in memory, then write the array back.
+    let mut goal_metrics: HashMap<String, Vec<UnifiedGoalMetric>> = HashMap::new();
+    for goal_id in updates.iter().filter_map(|u| u.goal_id.as_ref()) {
+        if goal_metrics.contains_key(goal_id) {
+            continue;
+        }
+        let metrics: Option<sqlx::types::Json<Vec<UnifiedGoalMetric>>> = sqlx::query_scalar(
+            "SELECT metrics FROM unified_goals WHERE id = $1 FOR UPDATE"
+        )
+        .bind(goal_id)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| db_context("lock unified_goal metrics", e))?
+        .ok_or_else(|| PosError::NotFound(format!("Goal {}", goal_id)))?;
+
+        goal_metrics.insert(goal_id.clone(), metrics.map(|m| m.0).unwrap_or_default());
+    }
+
+    let mut results = Vec::with_capacity(updates.len());
+    for u in &updates {
+        match &u.goal_id {
+            Some(goal_id) => {
+                let metric = goal_metrics.get_mut(goal_id)
+                    .and_then(|ms| ms.iter_mut().find(|m| m.id == u.metric_id))
+                    .ok_or_else(|| PosError::NotFound(format!("Metric {} on goal {}", u.metric_id, goal_id)))?;
+                let next = metric.current + u.delta;
+                if next < 0.0 {
+                    return Err(PosError::InvalidInput(format!(
+                        "Metric '{}' would drop below zero ({})", metric.label, next
+                    )));
+                }
+                metric.current = next;
+                results.push(MetricValue {
+                    goal_id: Some(goal_id.clone()),
+                    metric_id: u.metric_id.clone(),
+                    current: next,
+                    target: metric.target,
+                    reached: next >= metric.target,
+                });
+            }
+            None => {
+                let (current, target): (i32, i32) = sqlx::query_as(
+                    r#"UPDATE pos_goal_metrics SET current_value = current_value + $1
+                       WHERE id = $2
+                       RETURNING current_value, target_value"#
+                )
+                .bind(u.delta as i32)
+                .bind(&u.metric_id)
+                .fetch_optional(&mut *tx)
+                .await
+                .map_err(|e| db_context("update goal_metric", e))?
+                .ok_or_else(|| PosError::NotFound(format!("Metric {}", u.metric_id)))?;
+
+                if current < 0 {
+                    return Err(PosError::InvalidInput(format!(
+                        "Metric {} would drop below zero ({})", u.metric_id, current
+                    )));
+                }
+                results.push(MetricValue {
+                    goal_id: None,
+                    metric_id: u.metric_id.clone(),
+                    current: current as f64,
+                    target: target as f64,
+                    reached: current >= target,
+                });
+            }
+        }
+    }
+
+    let touched_goals: Vec<String> = goal_metrics.keys().cloned().collect();
+    for (goal_id, metrics) in goal_metrics {
+        sqlx::query("UPDATE unified_goals SET metrics = $1, updated_at = NOW() WHERE id = $2")
+            .bind(sqlx::types::Json(metrics))
+            .bind(&goal_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| db_context("write unified_goal metrics", e))?;
+    }
+
+    tx.commit().await.map_err(|e| db_context("commit tx", e))?;
+
+    for goal_id in &touched_goals {
+        if let Err(e) = crate::milestones::rollup_for_goal(pool, goal_id, &[]).await {
+            log::warn!("[METRICS] Milestone rollup after {} failed: {}", goal_id, e);
+        }
+    }
+    Ok(results)
+}
@@ -0,0 +1,94 @@
+// Goal Month Summary
+// Per-day goal counts for the calendar month grid in one grouped query, instead of
+// fetching a month of goals and counting client-side.
+// Goal dates are local YYYY-MM-DD already; completed_at is UTC and is shifted by the
+// caller's timezone offset (JS getTimezoneOffset(), e.g. -330 for UTC+5:30), defaulting to
+// the app's local zone.
+
+use chrono::{Duration, NaiveDate, Utc};
+use serde::Serialize;
+use tauri::State;
+
+use crate::PosDb;
+use crate::pos::error::{PosError, PosResult, db_context};
+
+#[derive(Debug, Serialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct GoalDayCounts {
+    /// Local date YYYY-MM-DD
+    pub date: String,
+    /// Goals dated this day
+    pub due: i64,
+    /// Goals completed this day (local), whatever their date
+    pub completed: i64,
+    /// Goals dated this day that are now overdue
+    pub debt: i64,
+    /// Recurring instances dated this day
+    pub recurring: i64,
+}
+
+/// First and last day of a YYYY-MM month
+fn month_bounds(month: &str) -> PosResult<(NaiveDate, NaiveDate)> {
+    let first = NaiveDate::parse_from_str(&format!("{}-01", month.trim()), "%Y-%m-%d")
+        .map_err(|_| PosError::InvalidInput(format!("Invalid month '{}', expected YYYY-MM", month)))?;
+    let next = first.checked_add_months(chrono::Months::new(1))
+        .ok_or_else(|| PosError::InvalidInput(format!("Month out of range: {}", month)))?;
+    Ok((first, next - Duration::days(1)))
+}
+
+/// One row per day of `month` (YYYY-MM), including days without goals
+#[tauri::command]
+pub async fn get_month_goal_summary(
+    db: State<'_, PosDb>,
+    month: String,
+    timezone_offset_minutes: Option<i32>,
+) -> PosResult<Vec<GoalDayCounts>> {
+    let (first, last) = month_bounds(&month)?;
+    let timezone_offset_minutes = timezone_offset_minutes.unwrap_or_else(crate::pos::timezone::js_offset_minutes);
+    let today_local = (Utc::now() - Duration::minutes(timezone_offset_minutes as i64)).date_naive();
+
+    sqlx::query_as::<_, GoalDayCounts>(
+        r#"WITH g AS (
+               SELECT date, COALESCE(completed, FALSE) AS completed, COALESCE(is_debt, FALSE) AS is_debt,
+                      recurring_template_id,
+                      ((completed_at AT TIME ZONE 'UTC') - make_interval(mins => $3))::date AS completed_day
+               FROM unified_goals
+               WHERE deleted_at IS NULL AND archived_at IS NULL
+                 AND NOT (recurring_pattern IS NOT NULL AND recurring_template_id IS NULL)
+                 AND (date BETWEEN $1::text AND $2::text
+                      OR completed_at BETWEEN ($1::date - 1)::timestamptz AND ($2::date + 2)::timestamptz)
+           )
+           SELECT to_char(d, 'YYYY-MM-DD') AS date,
+                  COUNT(*) FILTER (WHERE g.date = to_char(d, 'YYYY-MM-DD')) AS due,
+                  COUNT(*) FILTER (WHERE g.completed AND g.completed_day = d::date) AS completed,
+                  COUNT(*) FILTER (WHERE g.date = to_char(d, 'YYYY-MM-DD') AND NOT g.completed
+                                     AND (g.is_debt OR g.date < $4)) AS debt,
+                  COUNT(*) FILTER (WHERE g.date = to_char(d, 'YYYY-MM-DD')
+                                     AND g.recurring_template_id IS NOT NULL) AS recurring
+           FROM generate_series($1::date, $2::date, INTERVAL '1 day') d
+           LEFT JOIN g ON g.date = to_char(d, 'YYYY-MM-DD') OR g.completed_day = d::date
+           GROUP BY d
+           ORDER BY d"#
+    )
+    .bind(first.format("%Y-%m-%d").to_string())
+    .bind(last.format("%Y-%m-%d").to_string())
+    .bind(timezone_offset_minutes)
+    .bind(today_local.format("%Y-%m-%d").to_string())
+    .fetch_all(&db.0)
+    .await
+    .map_err(|e| db_context("get_month_goal_summary", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_month_bounds() {
+        let d = |s: &str| NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap();
+        assert_eq!(month_bounds("2024-02").unwrap(), (d("2024-02-01"), d("2024-02-29")));
+        assert_eq!(month_bounds("2023-12").unwrap(), (d("2023-12-01"), d("2023-12-31")));
+        assert!(month_bounds("2024-13").is_err());
+        assert!(month_bounds("Feb").is_err());
+    }
+}
@@ -0,0 +1,278 @@
+// Today's Goal Ordering
+// Ranks today's open goals into a "do this next" list. Each goal gets a score built
+// from urgency, priority, debt age, how far behind a related milestone is, and whether
+// its estimated effort still fits in what's left of the day. The breakdown is returned
+// so the UI can explain the order.
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, NaiveDate, Timelike, Utc};
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+use crate::PosDb;
+use crate::pos::error::{PosError, PosResult, db_context};
+
+const URGENT_POINTS: f64 = 30.0;
+const HIGH_PRIORITY_POINTS: f64 = 20.0;
+const MEDIUM_PRIORITY_POINTS: f64 = 10.0;
+const DEBT_POINTS_PER_DAY: f64 = 3.0;
+const MAX_DEBT_POINTS: f64 = 25.0;
+const MAX_PACE_POINTS: f64 = 25.0;
+const FITS_POINTS: f64 = 10.0;
+const OVERFLOW_PENALTY: f64 = -15.0;
+/// Estimate for goals with no effort history under any of their labels
+const DEFAULT_EFFORT_MINUTES: i64 = 30;
+pub(crate) const EFFORT_HISTORY_DAYS: i32 = 60;
+/// Day is assumed to wind down at this hour when no capacity is given
+const DAY_END_HOUR: u32 = 23;
+
+// ─── Types ──────────────────────────────────────────────────────────
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GoalOrderContext {
+    /// Minutes I still have today (default: until 23:00 local)
+    pub available_minutes: Option<i64>,
+    /// "low" favours short goals, "high" doesn't penalise long ones
+    pub energy: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GoalScoreBreakdown {
+    pub urgency: f64,
+    pub priority: f64,
+    pub debt_age: f64,
+    pub milestone_pace: f64,
+    pub capacity_fit: f64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RankedGoal {
+    pub id: String,
+    pub text: String,
+    pub priority: String,
+    pub urgent: bool,
+    pub is_debt: bool,
+    pub labels: Vec<String>,
+    pub estimated_minutes: i64,
+    pub score: f64,
+    pub breakdown: GoalScoreBreakdown,
+    /// Why the goal ranks where it does, strongest reason first
+    pub reasons: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OrderedGoals {
+    pub date: String,
+    pub available_minutes: i64,
+    /// Sum of estimates for every open goal today
+    pub planned_minutes: i64,
+    pub goals: Vec<RankedGoal>,
+}
+
+#[derive(sqlx::FromRow)]
+struct TodayGoalRow {
+    id: String,
+    text: String,
+    priority: Option<String>,
+    urgent: Option<bool>,
+    is_debt: Option<bool>,
+    original_date: Option<String>,
+    problem_id: Option<String>,
+    labels: Option<sqlx::types::Json<Vec<String>>>,
+    created_at: DateTime<Utc>,
+}
+
+#[derive(sqlx::FromRow)]
+struct ActiveMilestoneRow {
+    target_metric: String,
+    target_value: i32,
+    current_value: Option<i32>,
+    problem_id: Option<String>,
+    label: Option<String>,
+    period_start: DateTime<Utc>,
+    period_end: DateTime<Utc>,
+}
+
+// ─── Helpers ────────────────────────────────────────────────────────
+
+/// Share of the target a milestone should have reached by now but hasn't (0..1)
+fn milestone_shortfall(m: &ActiveMilestoneRow, now: DateTime<Utc>) -> f64 {
+    let span = (m.period_end - m.period_start).num_seconds().max(1) as f64;
+    let elapsed = ((now - m.period_start).num_seconds() as f64 / span).clamp(0.0, 1.0);
+    let expected = m.target_value as f64 * elapsed;
+    let behind = expected - m.current_value.unwrap_or(0) as f64;
+    if m.target_value <= 0 {
+        return 0.0;
+    }
+    (behind / m.target_value as f64).clamp(0.0, 1.0)
+}
+
+/// Same rule the milestone rollup uses
+fn goal_matches_milestone(goal: &TodayGoalRow, labels: &[String], m: &ActiveMilestoneRow) -> bool {
+    crate::milestones::goal_follows_milestone(
+        &goal.text, goal.problem_id.as_deref(), labels,
+        &m.target_metric, m.problem_id.as_deref(), m.label.as_deref(),
+    )
+}
+
+/// Average minutes logged against completed goals per label ($1 = days of history)
+pub(crate) const LABEL_EFFORT_SQL: &str = r#"
+    SELECT lbl, AVG(m.minutes)::float8
+    FROM unified_goals g
+    CROSS JOIN LATERAL (
+        SELECT SUM(EXTRACT(EPOCH FROM (a.end_time - a.start_time)) / 60)::float8 AS minutes
+        FROM pos_activities a
+        WHERE a.id IN (SELECT jsonb_array_elements_text(
+            CASE WHEN jsonb_typeof(g.linked_activity_ids) = 'array' THEN g.linked_activity_ids ELSE '[]'::jsonb END))
+    ) m
+    CROSS JOIN LATERAL jsonb_array_elements_text(
+        CASE WHEN jsonb_typeof(g.labels) = 'array' THEN g.labels ELSE '[]'::jsonb END
+    ) lbl
+    WHERE g.completed = TRUE AND m.minutes > 0
+      AND g.completed_at >= NOW() - make_interval(days => $1)
+    GROUP BY lbl"#;
+
+/// Mean effort of the goal's labels with history, else DEFAULT_EFFORT_MINUTES
+pub(crate) fn estimate_minutes(labels: &[String], label_effort: &HashMap<String, f64>) -> i64 {
+    let known: Vec<f64> = labels.iter().filter_map(|l| label_effort.get(l).copied()).collect();
+    if known.is_empty() {
+        DEFAULT_EFFORT_MINUTES
+    } else {
+        (known.iter().sum::<f64>() / known.len() as f64).round().max(5.0) as i64
+    }
+}
+
+fn round1(v: f64) -> f64 {
+    (v * 10.0).round() / 10.0
+}
+
+// ─── Commands ───────────────────────────────────────────────────────
+
+/// Today's open goals, highest score first. Effort is estimated from the average
+/// minutes logged against completed goals sharing a label over the last 60 days.
+#[tauri::command]
+pub async fn get_today_ordered_goals(
+    db: State<'_, PosDb>,
+    context: Option<GoalOrderContext>,
+) -> PosResult<OrderedGoals> {
+    let pool = &db.0;
+    let context = context.unwrap_or_default();
+    let now = crate::pos::timezone::local_datetime(Utc::now());
+    let today = now.date();
+    let date = today.format("%Y-%m-%d").to_string();
+
+    let available_minutes = match context.available_minutes {
+        Some(m) if m < 0 => return Err(PosError::InvalidInput("available_minutes cannot be negative".into())),
+        Some(m) => m,
+        None => (DAY_END_HOUR as i64 * 60 - (now.hour() * 60 + now.minute()) as i64).max(0),
+    };
+    let energy = context.energy.as_deref().map(str::to_lowercase);
+    if let Some(e) = energy.as_deref() {
+        if !matches!(e, "low" | "normal" | "high") {
+            return Err(PosError::InvalidInput(format!("Unknown energy level '{}'", e)));
+        }
+    }
+
+    let (goals, milestones, label_effort) = tokio::try_join!(
+        sqlx::query_as::<_, TodayGoalRow>(
+            r#"SELECT id, text, priority, urgent, is_debt, original_date, problem_id, labels, created_at
+               FROM unified_goals
+               WHERE date = $1 AND completed = FALSE AND archived_at IS NULL
+                 AND NOT (recurring_pattern IS NOT NULL AND recurring_template_id IS NULL)"#
+        ).bind(&date).fetch_all(pool),
+
+        sqlx::query_as::<_, ActiveMilestoneRow>(
+            r#"SELECT target_metric, target_value, current_value, problem_id, label, period_start, period_end
+               FROM goal_periods WHERE period_start <= NOW() AND period_end >= NOW() AND deleted_at IS NULL"#
+        ).fetch_all(pool),
+
+        sqlx::query_as::<_, (String, f64)>(LABEL_EFFORT_SQL).bind(EFFORT_HISTORY_DAYS).fetch_all(pool),
+    ).map_err(|e| db_context("get_today_ordered_goals", e))?;
+
+    let label_effort: HashMap<String, f64> = label_effort.into_iter().collect();
+    let now_utc = Utc::now();
+
+    let mut ranked: Vec<(DateTime<Utc>, RankedGoal)> = goals.into_iter().map(|g| {
+        let labels = g.labels.as_ref().map(|l| l.0.clone()).unwrap_or_default();
+        let priority = g.priority.clone().unwrap_or_else(|| "medium".to_string());
+        let urgent = g.urgent.unwrap_or(false);
+        let is_debt = g.is_debt.unwrap_or(false);
+        let estimated_minutes = estimate_minutes(&labels, &label_effort);
+
+        let urgency = if urgent { URGENT_POINTS } else { 0.0 };
+        let priority_points = match priority.as_str() {
+            "high" => HIGH_PRIORITY_POINTS,
+            "medium" => MEDIUM_PRIORITY_POINTS,
+            _ => 0.0,
+        };
+        let debt_days = g.original_date.as_deref()
+            .and_then(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d").ok())
+            .map(|d| (today - d).num_days().max(0))
+            .filter(|_| is_debt)
+            .unwrap_or(0);
+        let debt_age = (debt_days as f64 * DEBT_POINTS_PER_DAY).min(MAX_DEBT_POINTS);
+        let shortfall = milestones.iter()
+            .filter(|m| goal_matches_milestone(&g, &labels, m))
+            .map(|m| milestone_shortfall(m, now_utc))
+            .fold(0.0, f64::max);
+        let milestone_pace = shortfall * MAX_PACE_POINTS;
+        let capacity_fit = if estimated_minutes > available_minutes {
+            if energy.as_deref() == Some("high") { OVERFLOW_PENALTY / 2.0 } else { OVERFLOW_PENALTY }
+        } else if energy.as_deref() == Some("low") {
+            // Shorter goals get more of the bonus when energy is low
+            FITS_POINTS * (1.0 - (estimated_minutes as f64 / 120.0).min(1.0)) + FITS_POINTS / 2.0
+        } else {
+            FITS_POINTS
+        };
+
+        let mut reasons: Vec<(f64, String)> = vec![
+            (urgency, "Marked urgent".to_string()),
+            (priority_points, format!("{} priority", priority)),
+            (debt_age, format!("Debt carried {} days", debt_days)),
+            (milestone_pace, format!("Milestone {:.0}% behind pace", shortfall * 100.0)),
+        ];
+        reasons.push((capacity_fit.abs(), if capacity_fit < 0.0 {
+            format!("~{} min won't fit the {} min left", estimated_minutes, available_minutes)
+        } else {
+            format!("~{} min fits the time left", estimated_minutes)
+        }));
+        reasons.retain(|(points, _)| *points > 0.0);
+        reasons.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+        let breakdown = GoalScoreBreakdown {
+            urgency,
+            priority: priority_points,
+            debt_age: round1(debt_age),
+            milestone_pace: round1(milestone_pace),
+            capacity_fit: round1(capacity_fit),
+        };
+        let score = round1(urgency + priority_points + debt_age + milestone_pace + capacity_fit);
+        (g.created_at, RankedGoal {
+            id: g.id,
+            text: g.text,
+            priority,
+            urgent,
+            is_debt,
+            labels,
+            estimated_minutes,
+            score,
+            breakdown,
+            reasons: reasons.into_iter().map(|(_, r)| r).collect(),
+        })
+    }).collect();
+
+    ranked.sort_by(|a, b| b.1.score.total_cmp(&a.1.score).then(a.0.cmp(&b.0)));
+    let goals: Vec<RankedGoal> = ranked.into_iter().map(|(_, g)| g).collect();
+
+    Ok(OrderedGoals {
+        date,
+        available_minutes,
+        planned_minutes: goals.iter().map(|g| g.estimated_minutes).sum(),
+        goals,
+    })
+}
@@ -0,0 +1,279 @@
+// Activity → Goal Suggestions
+// Ranks the day's open goals for a new activity so the frontend can pre-select one.
+// Score = text similarity + category keyword rules + how this category was linked before.
+// Also suggests a due time for new goals from when similar goals actually got done.
+
+use std::collections::HashSet;
+
+use chrono::{DateTime, Duration, Timelike, Utc};
+use serde::Serialize;
+use tauri::State;
+
+use crate::PosDb;
+use crate::pos::error::{PosError, PosResult, db_context};
+use crate::unified_goals::{UnifiedGoalRow, UNIFIED_GOAL_COLS};
+
+const TEXT_WEIGHT: f64 = 0.5;
+const CATEGORY_WEIGHT: f64 = 0.3;
+const HISTORY_WEIGHT: f64 = 0.2;
+const HISTORY_DAYS: i32 = 90;
+const MAX_SUGGESTIONS: usize = 5;
+const DUE_TIME_HISTORY_DAYS: i32 = 180;
+/// Minimum text similarity for a past goal to count as "similar"
+const DUE_TIME_MIN_SIMILARITY: f64 = 0.3;
+const DUE_TIME_MIN_SAMPLES: usize = 3;
+
+/// Activity category → words that suggest a goal belongs to it
+const CATEGORY_KEYWORDS: &[(&str, &[&str])] = &[
+    ("codeforces", &["codeforces", "cf", "problem", "problems", "ladder", "contest", "upsolve", "rating"]),
+    ("leetcode", &["leetcode", "lc", "problem", "problems", "daily", "contest"]),
+    ("development", &["ship", "build", "code", "feature", "bug", "fix", "project", "refactor", "deploy"]),
+    ("reading", &["read", "reading", "book", "pages", "chapter", "article"]),
+    ("exercise", &["workout", "exercise", "run", "gym", "walk", "stretch"]),
+    ("study", &["study", "review", "notes", "learn", "course", "lecture"]),
+];
+
+// ─── Types ──────────────────────────────────────────────────────────
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GoalSuggestion {
+    pub goal_id: String,
+    pub text: String,
+    pub score: f64,
+    pub reasons: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DueTimeSuggestion {
+    /// HH:MM (local), rounded to 15 minutes; None when history is too thin
+    pub suggested_time: Option<String>,
+    /// HH:MM by which three quarters of similar goals were done
+    pub latest_typical: Option<String>,
+    pub sample_size: usize,
+    pub basis: Vec<String>,
+}
+
+#[derive(sqlx::FromRow)]
+struct CompletedGoalRow {
+    text: String,
+    labels: Option<sqlx::types::Json<Vec<String>>>,
+    completed_at: DateTime<Utc>,
+}
+
+#[derive(sqlx::FromRow)]
+struct PriorLinkRow {
+    hour: i32,
+    text: String,
+    recurring_template_id: Option<String>,
+}
+
+// ─── Helpers ────────────────────────────────────────────────────────
+
+fn tokens(text: &str) -> HashSet<String> {
+    text.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| w.len() > 1)
+        .map(|w| w.to_string())
+        .collect()
+}
+
+/// Dice coefficient of the two token sets (0..1)
+fn similarity(a: &HashSet<String>, b: &HashSet<String>) -> f64 {
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+    let shared = a.intersection(b).count() as f64;
+    2.0 * shared / (a.len() + b.len()) as f64
+}
+
+fn category_matches(category: &str, goal: &UnifiedGoalRow, goal_tokens: &HashSet<String>) -> bool {
+    let labels_match = goal.labels.as_ref()
+        .is_some_and(|l| l.0.iter().any(|label| label.eq_ignore_ascii_case(category)));
+    let problem_match = goal.problem_id.as_deref()
+        .is_some_and(|p| p.starts_with(&format!("{}-", category)) || (category == "codeforces" && p.starts_with("cf-")));
+    let keyword_match = CATEGORY_KEYWORDS.iter()
+        .find(|(c, _)| *c == category)
+        .is_some_and(|(_, words)| words.iter().any(|w| goal_tokens.contains(*w)));
+
+    labels_match || problem_match || keyword_match || goal_tokens.contains(category)
+}
+
+/// Weighted percentile of (minute-of-day, weight) samples
+fn weighted_percentile(samples: &mut [(i64, f64)], p: f64) -> i64 {
+    samples.sort_by_key(|(m, _)| *m);
+    let total: f64 = samples.iter().map(|(_, w)| w).sum();
+    let mut acc = 0.0;
+    for (minute, w) in samples.iter() {
+        acc += w;
+        if acc >= total * p {
+            return *minute;
+        }
+    }
+    samples.last().map(|(m, _)| *m).unwrap_or(0)
+}
+
+fn format_minute(minute: i64, round_to: i64) -> String {
+    let m = ((minute + round_to / 2) / round_to * round_to).min(23 * 60 + 59);
+    format!("{:02}:{:02}", m / 60, m % 60)
+}
+
+// ─── Commands ───────────────────────────────────────────────────────
+
+/// Rank the open goals due on the activity's date for a new activity.
+/// `time` is the activity start (RFC3339); its date selects the goals and its hour
+/// weights goals that were previously linked to this category around the same time.
+#[tauri::command]
+pub async fn suggest_goals_for_activity(
+    db: State<'_, PosDb>,
+    title: String,
+    category: String,
+    time: String,
+) -> PosResult<Vec<GoalSuggestion>> {
+    let pool = &db.0;
+    let start = DateTime::parse_from_rfc3339(&time)
+        .map_err(|e| PosError::InvalidInput(format!("Invalid time: {}", e)))?
+        .with_timezone(&Utc);
+    let date = crate::pos::timezone::local_date_string(start);
+    let category = category.trim().to_lowercase();
+
+    let (goals, prior_links) = tokio::try_join!(
+        sqlx::query_as::<_, UnifiedGoalRow>(&format!(
+            r#"SELECT {} FROM unified_goals
+               WHERE date = $1 AND completed = FALSE AND archived_at IS NULL AND deleted_at IS NULL
+                 AND NOT (recurring_pattern IS NOT NULL AND recurring_template_id IS NULL)"#,
+            UNIFIED_GOAL_COLS
+        )).bind(&date).fetch_all(pool),
+
+        sqlx::query_as::<_, PriorLinkRow>(
+            r#"SELECT EXTRACT(HOUR FROM a.start_time)::int AS hour, g.text, g.recurring_template_id
+               FROM pos_activities a
+               JOIN unified_goals g ON g.id = ANY(a.goal_ids)
+               WHERE a.is_shadow = FALSE AND a.deleted_at IS NULL
+                 AND a.category = $1
+                 AND a.start_time >= NOW() - make_interval(days => $2)"#
+        ).bind(&category).bind(HISTORY_DAYS).fetch_all(pool),
+    ).map_err(|e| db_context("suggest_goals_for_activity", e))?;
+
+    let title_tokens = tokens(&title);
+    let hour = start.hour() as i32;
+
+    let mut suggestions: Vec<GoalSuggestion> = goals.into_iter().filter_map(|goal| {
+        let goal_tokens = tokens(&format!(
+            "{} {}",
+            goal.text,
+            goal.labels.as_ref().map(|l| l.0.join(" ")).unwrap_or_default()
+        ));
+        let mut reasons = Vec::new();
+        let mut score = 0.0;
+
+        let text_score = similarity(&title_tokens, &goal_tokens);
+        if text_score > 0.0 {
+            score += TEXT_WEIGHT * text_score;
+            reasons.push(format!("Title overlaps goal text ({:.0}%)", text_score * 100.0));
+        }
+
+        if category_matches(&category, &goal, &goal_tokens) {
+            score += CATEGORY_WEIGHT;
+            reasons.push(format!("Goal fits the '{}' category", category));
+        }
+
+        // Same recurring template or identical text counts as "the same goal" historically
+        let same_goal = |l: &&PriorLinkRow| {
+            l.text.eq_ignore_ascii_case(&goal.text)
+                || (goal.recurring_template_id.is_some() && l.recurring_template_id == goal.recurring_template_id)
+        };
+        let links = prior_links.iter().filter(same_goal).count();
+        if links > 0 {
+            let near_hour = prior_links.iter().filter(same_goal)
+                .filter(|l| (l.hour - hour).abs() <= 2)
+                .count();
+            let history = (links.min(5) as f64 / 5.0) * 0.7 + (near_hour.min(3) as f64 / 3.0) * 0.3;
+            score += HISTORY_WEIGHT * history;
+            reasons.push(format!("Linked from '{}' {} time(s) before", category, links));
+        }
+
+        (score > 0.0).then(|| GoalSuggestion {
+            goal_id: goal.id,
+            text: goal.text,
+            score: (score * 1000.0).round() / 1000.0,
+            reasons,
+        })
+    }).collect();
+
+    suggestions.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
+    suggestions.truncate(MAX_SUGGESTIONS);
+    Ok(suggestions)
+}
+
+/// Suggest a due time for a new goal from when past goals with the same label or
+/// similar text were completed (last 180 days). Label matches count fully; text
+/// matches are weighted by similarity. `timezone_offset_minutes` is JS
+/// getTimezoneOffset() (negative for UTC+ zones); defaults to the app's local zone.
+#[tauri::command]
+pub async fn suggest_due_time(
+    db: State<'_, PosDb>,
+    label: Option<String>,
+    text: String,
+    timezone_offset_minutes: Option<i32>,
+) -> PosResult<DueTimeSuggestion> {
+    let label = label.map(|l| l.trim().to_lowercase()).filter(|l| !l.is_empty());
+    if label.is_none() && text.trim().is_empty() {
+        return Err(PosError::InvalidInput("Provide a label or goal text".into()));
+    }
+
+    let rows = sqlx::query_as::<_, CompletedGoalRow>(
+        r#"SELECT text, labels, completed_at FROM unified_goals
+           WHERE completed = TRUE AND completed_at IS NOT NULL
+             AND completed_at >= NOW() - make_interval(days => $1)"#
+    )
+    .bind(DUE_TIME_HISTORY_DAYS)
+    .fetch_all(&db.0)
+    .await
+    .map_err(|e| db_context("suggest_due_time", e))?;
+
+    let offset = timezone_offset_minutes.unwrap_or_else(crate::pos::timezone::js_offset_minutes);
+    let text_tokens = tokens(&text);
+    let (mut label_hits, mut text_hits) = (0usize, 0usize);
+    let mut samples: Vec<(i64, f64)> = rows.iter().filter_map(|g| {
+        let label_match = label.as_ref().is_some_and(|l| {
+            g.labels.as_ref().is_some_and(|ls| ls.0.iter().any(|x| x.eq_ignore_ascii_case(l)))
+        });
+        let sim = if text_tokens.is_empty() { 0.0 } else { similarity(&text_tokens, &tokens(&g.text)) };
+        let weight = if label_match {
+            label_hits += 1;
+            1.0
+        } else if sim >= DUE_TIME_MIN_SIMILARITY {
+            text_hits += 1;
+            sim
+        } else {
+            return None;
+        };
+
+        let local = (g.completed_at - Duration::minutes(offset as i64)).time();
+        Some(((local.hour() * 60 + local.minute()) as i64, weight))
+    }).collect();
+
+    let mut basis = Vec::new();
+    if label_hits > 0 {
+        basis.push(format!("{} completed goal(s) labelled '{}'", label_hits, label.as_deref().unwrap_or_default()));
+    }
+    if text_hits > 0 {
+        basis.push(format!("{} completed goal(s) with similar text", text_hits));
+    }
+
+    let sample_size = samples.len();
+    if sample_size < DUE_TIME_MIN_SAMPLES {
+        return Ok(DueTimeSuggestion { suggested_time: None, latest_typical: None, sample_size, basis });
+    }
+
+    let median = weighted_percentile(&mut samples, 0.5);
+    let p75 = weighted_percentile(&mut samples, 0.75);
+    Ok(DueTimeSuggestion {
+        suggested_time: Some(format_minute(median, 15)),
+        latest_typical: Some(format_minute(p75, 15)),
+        sample_size,
+        basis,
+    })
+}
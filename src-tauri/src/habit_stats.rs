@@ -0,0 +1,169 @@
+// Habit Stats
+// Habit-tracker view of a recurring template: how often its instances get done, the
+// current and longest unbroken chains, when in the day they usually get done, and
+// whether the last four weeks are better or worse than the four before.
+
+use chrono::{DateTime, Duration, NaiveDate, Timelike, Utc};
+use serde::Serialize;
+use tauri::State;
+
+use crate::PosDb;
+use crate::pos::error::{PosError, PosResult, db_context};
+use crate::pos::timezone;
+
+/// Size of each window compared by the trend
+const TREND_WINDOW_DAYS: i64 = 28;
+/// Rate change (in percentage points) below which the trend is "steady"
+const TREND_THRESHOLD: f64 = 0.10;
+
+// ─── Types ──────────────────────────────────────────────────────────
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HabitTrend {
+    /// "improving" | "declining" | "steady" | "insufficient_data"
+    pub direction: String,
+    pub recent_rate: Option<f64>,
+    pub previous_rate: Option<f64>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HabitStats {
+    pub template_id: String,
+    pub text: String,
+    pub recurring_pattern: String,
+    /// Instances due up to today (today's counts only once completed)
+    pub instances: i64,
+    pub completed: i64,
+    pub completion_rate: Option<f64>,
+    pub current_chain: i64,
+    pub longest_chain: i64,
+    /// Median local completion time (HH:MM)
+    pub typical_completion_time: Option<String>,
+    /// "morning" | "afternoon" | "evening" | "night"
+    pub typical_time_of_day: Option<String>,
+    pub trend: HabitTrend,
+}
+
+#[derive(sqlx::FromRow)]
+struct InstanceRow {
+    due_date: String,
+    completed: bool,
+    completed_at: Option<DateTime<Utc>>,
+}
+
+// ─── Helpers ────────────────────────────────────────────────────────
+
+fn rate(done: i64, total: i64) -> Option<f64> {
+    (total > 0).then(|| done as f64 / total as f64)
+}
+
+fn time_of_day(minute_of_day: u32) -> &'static str {
+    match minute_of_day / 60 {
+        5..=11 => "morning",
+        12..=16 => "afternoon",
+        17..=21 => "evening",
+        _ => "night",
+    }
+}
+
+/// Completion rate of instances due in `[from, to)`
+fn window_rate(instances: &[(NaiveDate, bool)], from: NaiveDate, to: NaiveDate) -> Option<f64> {
+    let window: Vec<bool> = instances.iter()
+        .filter(|(d, _)| *d >= from && *d < to)
+        .map(|(_, done)| *done)
+        .collect();
+    rate(window.iter().filter(|d| **d).count() as i64, window.len() as i64)
+}
+
+// ─── Commands ───────────────────────────────────────────────────────
+
+/// Stats for the instances of recurring template `template_id`. Instances are dated by
+/// the day they were originally due, so ones moved into debt still count where they
+/// belonged. A chain is a run of consecutive completed instances; today's instance
+/// doesn't break the current chain until the day is over.
+#[tauri::command]
+pub async fn get_habit_stats(db: State<'_, PosDb>, template_id: String) -> PosResult<HabitStats> {
+    let pool = &db.0;
+
+    let (text, recurring_pattern): (String, String) = sqlx::query_as(
+        r#"SELECT text, recurring_pattern FROM unified_goals
+           WHERE id = $1 AND recurring_pattern IS NOT NULL AND recurring_template_id IS NULL"#
+    )
+    .bind(&template_id)
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| db_context("get_habit_stats template", e))?
+    .ok_or_else(|| PosError::NotFound(format!("Recurring template {}", template_id)))?;
+
+    let today = timezone::today();
+    let rows = sqlx::query_as::<_, InstanceRow>(
+        r#"SELECT COALESCE(original_date, date) AS due_date, COALESCE(completed, FALSE) AS completed, completed_at
+           FROM unified_goals
+           WHERE recurring_template_id = $1 AND archived_at IS NULL
+             AND COALESCE(original_date, date) <= $2
+           ORDER BY COALESCE(original_date, date) ASC"#
+    )
+    .bind(&template_id)
+    .bind(today.format("%Y-%m-%d").to_string())
+    .fetch_all(pool)
+    .await
+    .map_err(|e| db_context("get_habit_stats instances", e))?;
+
+    // Today's open instance is still in play, so it counts neither way
+    let instances: Vec<(NaiveDate, bool)> = rows.iter()
+        .filter_map(|r| {
+            let date = NaiveDate::parse_from_str(&r.due_date, "%Y-%m-%d").ok()?;
+            (date < today || r.completed).then_some((date, r.completed))
+        })
+        .collect();
+
+    let completed = instances.iter().filter(|(_, done)| *done).count() as i64;
+    let mut longest_chain = 0;
+    let mut chain = 0;
+    for (_, done) in &instances {
+        chain = if *done { chain + 1 } else { 0 };
+        longest_chain = longest_chain.max(chain);
+    }
+    let current_chain = chain;
+
+    let mut minutes: Vec<u32> = rows.iter()
+        .filter_map(|r| r.completed_at.filter(|_| r.completed))
+        .map(|t| {
+            let local = timezone::local_datetime(t);
+            local.hour() * 60 + local.minute()
+        })
+        .collect();
+    minutes.sort_unstable();
+    let median = minutes.get(minutes.len() / 2).copied();
+
+    let recent_start = today - Duration::days(TREND_WINDOW_DAYS);
+    let previous_start = recent_start - Duration::days(TREND_WINDOW_DAYS);
+    let recent_rate = window_rate(&instances, recent_start, today + Duration::days(1));
+    let previous_rate = window_rate(&instances, previous_start, recent_start);
+    let direction = match (recent_rate, previous_rate) {
+        (Some(r), Some(p)) if r - p >= TREND_THRESHOLD => "improving",
+        (Some(r), Some(p)) if p - r >= TREND_THRESHOLD => "declining",
+        (Some(_), Some(_)) => "steady",
+        _ => "insufficient_data",
+    };
+
+    Ok(HabitStats {
+        template_id,
+        text,
+        recurring_pattern,
+        instances: instances.len() as i64,
+        completed,
+        completion_rate: rate(completed, instances.len() as i64),
+        current_chain,
+        longest_chain,
+        typical_completion_time: median.map(|m| format!("{:02}:{:02}", m / 60, m % 60)),
+        typical_time_of_day: median.map(|m| time_of_day(m).to_string()),
+        trend: HabitTrend {
+            direction: direction.to_string(),
+            recent_rate,
+            previous_rate,
+        },
+    })
+}
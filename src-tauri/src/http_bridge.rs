@@ -0,0 +1,216 @@
+// HTTP Bridge
+// Optional local HTTP server for external integrations (browser extension, Obsidian
+// plugin) that can't use Tauri IPC. Off unless HTTP_BRIDGE_PORT is set, and bound to
+// 127.0.0.1 only. Every request needs a bearer token from pos::api_tokens: reads need
+// any scope, the capture webhook needs write-knowledge. Handlers call the same commands
+// the frontend does, so responses have the same shape (errors are the serialized PosError).
+
+use std::net::SocketAddr;
+
+use axum::extract::{Query, State};
+use axum::http::{header, HeaderMap, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::PosDb;
+use crate::capture_roles::{self, CaptureDestination, CaptureRolesState};
+use crate::pos::activities::ActivityResponse;
+use crate::pos::api_tokens::{authorize, ApiScope};
+use crate::pos::error::{PosError, PosResult, db_context};
+use crate::unified_goals::{UnifiedGoalRow, UNIFIED_GOAL_COLS};
+
+// ─── Types ──────────────────────────────────────────────────────────
+
+#[derive(Debug, Deserialize)]
+struct DateQuery {
+    /// YYYY-MM-DD, defaults to today
+    date: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CaptureBody {
+    /// Capture role; unknown and note-bound roles land in the captures table
+    role: Option<String>,
+    content: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CaptureAccepted {
+    id: String,
+    role: String,
+    /// "captures" or the role's routed destination
+    destination: serde_json::Value,
+}
+
+/// PosError as an HTTP response with a matching status
+struct ApiError(PosError);
+
+impl From<PosError> for ApiError {
+    fn from(err: PosError) -> Self {
+        ApiError(err)
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let status = match &self.0 {
+            PosError::Unauthorized(_) => StatusCode::UNAUTHORIZED,
+            PosError::RateLimited(_) => StatusCode::TOO_MANY_REQUESTS,
+            PosError::NotFound(_) => StatusCode::NOT_FOUND,
+            PosError::InvalidInput(_) => StatusCode::BAD_REQUEST,
+            PosError::Conflict(_) => StatusCode::CONFLICT,
+            PosError::External(_) => StatusCode::BAD_GATEWAY,
+            PosError::Database(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        (status, Json(self.0)).into_response()
+    }
+}
+
+type ApiResult<T> = Result<Json<T>, ApiError>;
+
+// ─── Helpers ────────────────────────────────────────────────────────
+
+fn bearer(headers: &HeaderMap) -> Option<&str> {
+    headers.get(header::AUTHORIZATION)?.to_str().ok()?.strip_prefix("Bearer ")
+}
+
+/// Authorize the request's token for `scope` (audited by api_tokens)
+async fn guard(app: &AppHandle, headers: &HeaderMap, scope: ApiScope, method: &str, path: &str) -> PosResult<()> {
+    let db = app.try_state::<PosDb>()
+        .ok_or_else(|| PosError::External("Database not connected".into()))?;
+    authorize(&db.0, bearer(headers), scope, method, path).await.map(|_| ())
+}
+
+fn query_date(q: &DateQuery) -> PosResult<String> {
+    match &q.date {
+        Some(d) => NaiveDate::parse_from_str(d, "%Y-%m-%d")
+            .map(|d| d.format("%Y-%m-%d").to_string())
+            .map_err(|e| PosError::InvalidInput(format!("Invalid date: {}", e))),
+        None => Ok(crate::pos::timezone::today_string()),
+    }
+}
+
+// ─── Handlers ───────────────────────────────────────────────────────
+
+async fn activities(
+    State(app): State<AppHandle>,
+    headers: HeaderMap,
+    Query(q): Query<DateQuery>,
+) -> ApiResult<ActivityResponse> {
+    guard(&app, &headers, ApiScope::ReadOnly, "GET", "/api/activities").await?;
+    let date = query_date(&q)?;
+    Ok(Json(crate::pos::activities::get_activities(app.state(), date).await?))
+}
+
+/// Goals due on the day (templates excluded). A plain read: unlike get_unified_goals it
+/// doesn't mark debt or generate recurring instances.
+async fn goals(
+    State(app): State<AppHandle>,
+    headers: HeaderMap,
+    Query(q): Query<DateQuery>,
+) -> ApiResult<Vec<UnifiedGoalRow>> {
+    guard(&app, &headers, ApiScope::ReadOnly, "GET", "/api/goals").await?;
+    let date = query_date(&q)?;
+    let rows = sqlx::query_as::<_, UnifiedGoalRow>(&format!(
+        r#"SELECT {} FROM unified_goals
+           WHERE date = $1 AND deleted_at IS NULL
+             AND NOT (recurring_pattern IS NOT NULL AND recurring_template_id IS NULL)
+           ORDER BY completed, urgent DESC, created_at"#,
+        UNIFIED_GOAL_COLS
+    ))
+    .bind(&date)
+    .fetch_all(&app.state::<PosDb>().0)
+    .await
+    .map_err(|e| db_context("http bridge goals", e))?;
+    Ok(Json(rows))
+}
+
+async fn stats(
+    State(app): State<AppHandle>,
+    headers: HeaderMap,
+    Query(q): Query<DateQuery>,
+) -> ApiResult<crate::dashboard::DashboardSnapshotResponse> {
+    guard(&app, &headers, ApiScope::ReadOnly, "GET", "/api/stats").await?;
+    let date = query_date(&q)?;
+    Ok(Json(crate::dashboard::get_dashboard_snapshot(app.state(), Some(date), None).await?))
+}
+
+/// Webhook for pushed captures. Roles with a routed destination (knowledge item, journal,
+/// goal) are persisted like keyboard captures; anything else is stored in `captures`.
+async fn capture(
+    State(app): State<AppHandle>,
+    headers: HeaderMap,
+    Json(body): Json<CaptureBody>,
+) -> ApiResult<CaptureAccepted> {
+    guard(&app, &headers, ApiScope::WriteKnowledge, "POST", "/api/captures").await?;
+    let role_name = body.role.as_deref().map(|r| r.trim().to_lowercase())
+        .filter(|r| !r.is_empty())
+        .unwrap_or_else(|| "note".to_string());
+    let role = app.try_state::<CaptureRolesState>().and_then(|roles| {
+        roles.0.read().unwrap().iter()
+            .find(|r| r.role == role_name && r.destination != CaptureDestination::Note)
+            .cloned()
+    });
+
+    let accepted = match role {
+        Some(role) => {
+            if body.content.trim().is_empty() {
+                return Err(PosError::InvalidInput("Capture content cannot be empty".into()).into());
+            }
+            let id = capture_roles::route_capture(&app.state::<PosDb>().0, &role, &body.content).await?;
+            CaptureAccepted {
+                id,
+                role: role.role,
+                destination: serde_json::to_value(&role.destination).unwrap_or_default(),
+            }
+        }
+        None => {
+            let row = crate::captures::save_capture(app.state(), role_name, body.content).await?;
+            CaptureAccepted { id: row.id, role: row.role, destination: "captures".into() }
+        }
+    };
+    let _ = app.emit("capture-routed", serde_json::json!({
+        "role": accepted.role,
+        "destination": accepted.destination,
+        "id": accepted.id,
+        "source": "http"
+    }));
+    Ok(Json(accepted))
+}
+
+// ─── Server ─────────────────────────────────────────────────────────
+
+/// Serve the bridge on 127.0.0.1:`port` (no-op when the port isn't configured).
+/// Called once the pool is managed, so handlers can rely on PosDb.
+pub fn start(app: AppHandle, port: Option<u16>) {
+    let Some(port) = port else {
+        log::info!("[HTTP] Bridge disabled (HTTP_BRIDGE_PORT not set)");
+        return;
+    };
+    let router = Router::new()
+        .route("/api/activities", get(activities))
+        .route("/api/goals", get(goals))
+        .route("/api/stats", get(stats))
+        .route("/api/captures", post(capture))
+        .with_state(app);
+
+    tauri::async_runtime::spawn(async move {
+        let addr = SocketAddr::from(([127, 0, 0, 1], port));
+        let listener = match tokio::net::TcpListener::bind(addr).await {
+            Ok(l) => l,
+            Err(e) => {
+                log::error!("[HTTP] Failed to bind bridge on {}: {}", addr, e);
+                return;
+            }
+        };
+        log::info!("[HTTP] Bridge listening on http://{}", addr);
+        if let Err(e) = axum::serve(listener, router).await {
+            log::error!("[HTTP] Bridge stopped: {}", e);
+        }
+    });
+}
@@ -0,0 +1,279 @@
+// Integrity Check
+// Scans for rows the app can't reach or trust: metrics and progress rows whose parent is
+// gone (tables created before their FKs existed keep them), recurring instances whose
+// template was purged or deleted, cached problem_count columns that drifted from the
+// problem tables, and caches that outlived their use. run_integrity_check only reports;
+// repair_integrity applies the fixes for the chosen findings in one transaction.
+// Each repair re-applies its detection predicate, so findings that went stale between
+// check and repair can't touch rows that are fine now.
+
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use tauri::State;
+
+use crate::PosDb;
+use crate::pos::error::{PosError, PosResult, db_context};
+
+/// Ids returned per finding so the UI can show what would change
+const SAMPLE_SIZE: i64 = 5;
+
+// ─── Types ──────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FindingKind {
+    OrphanActivityMetrics,
+    OrphanLadderProgress,
+    OrphanCategoryProgress,
+    GoalsMissingTemplate,
+    GoalsDeletedTemplate,
+    LadderProblemCount,
+    CategoryProblemCount,
+    StaleDashboardSnapshots,
+    StaleStreaks,
+}
+
+impl FindingKind {
+    const ALL: [FindingKind; 9] = [
+        FindingKind::OrphanActivityMetrics,
+        FindingKind::OrphanLadderProgress,
+        FindingKind::OrphanCategoryProgress,
+        FindingKind::GoalsMissingTemplate,
+        FindingKind::GoalsDeletedTemplate,
+        FindingKind::LadderProblemCount,
+        FindingKind::CategoryProblemCount,
+        FindingKind::StaleDashboardSnapshots,
+        FindingKind::StaleStreaks,
+    ];
+
+    fn description(self) -> &'static str {
+        match self {
+            FindingKind::OrphanActivityMetrics => "Activity metrics whose activity no longer exists",
+            FindingKind::OrphanLadderProgress => "Ladder progress rows whose ladder no longer exists",
+            FindingKind::OrphanCategoryProgress => "Category progress rows whose category no longer exists",
+            FindingKind::GoalsMissingTemplate => "Recurring goal instances whose template was purged",
+            FindingKind::GoalsDeletedTemplate => "Open upcoming instances of a deleted recurring template",
+            FindingKind::LadderProblemCount => "Ladders whose problem_count differs from their problems",
+            FindingKind::CategoryProblemCount => "Categories whose problem_count differs from their problems",
+            FindingKind::StaleDashboardSnapshots => "Dashboard snapshots for past days",
+            FindingKind::StaleStreaks => "Streak caches not recomputed in over a week",
+        }
+    }
+
+    fn repair_description(self) -> &'static str {
+        match self {
+            FindingKind::OrphanActivityMetrics
+            | FindingKind::OrphanLadderProgress
+            | FindingKind::OrphanCategoryProgress
+            | FindingKind::StaleDashboardSnapshots => "Delete the rows",
+            FindingKind::GoalsMissingTemplate => "Detach them into one-off goals",
+            FindingKind::GoalsDeletedTemplate => "Delete them (restorable from trash)",
+            FindingKind::LadderProblemCount | FindingKind::CategoryProblemCount => "Recount the problems",
+            FindingKind::StaleStreaks => "Recompute on next read",
+        }
+    }
+
+    /// Ids of affected rows
+    fn detect_sql(self) -> &'static str {
+        match self {
+            FindingKind::OrphanActivityMetrics => r#"
+                SELECT m.id FROM pos_activity_metrics m
+                WHERE NOT EXISTS (SELECT 1 FROM pos_activities a WHERE a.id = m.activity_id)"#,
+            FindingKind::OrphanLadderProgress => r#"
+                SELECT p.id FROM cf_ladder_progress p
+                WHERE NOT EXISTS (SELECT 1 FROM cf_ladders l WHERE l.id = p.ladder_id)"#,
+            FindingKind::OrphanCategoryProgress => r#"
+                SELECT p.id FROM cf_category_progress p
+                WHERE NOT EXISTS (SELECT 1 FROM cf_categories c WHERE c.id = p.category_id)"#,
+            FindingKind::GoalsMissingTemplate => r#"
+                SELECT g.id FROM unified_goals g
+                WHERE g.recurring_template_id IS NOT NULL
+                  AND NOT EXISTS (SELECT 1 FROM unified_goals t WHERE t.id = g.recurring_template_id)"#,
+            FindingKind::GoalsDeletedTemplate => r#"
+                SELECT g.id FROM unified_goals g
+                JOIN unified_goals t ON t.id = g.recurring_template_id
+                WHERE t.deleted_at IS NOT NULL AND g.deleted_at IS NULL
+                  AND COALESCE(g.completed, FALSE) = FALSE
+                  AND g.date >= to_char(CURRENT_DATE, 'YYYY-MM-DD')"#,
+            FindingKind::LadderProblemCount => r#"
+                SELECT l.id FROM cf_ladders l
+                WHERE COALESCE(l.problem_count, 0) <>
+                      (SELECT COUNT(*) FROM cf_ladder_problems p WHERE p.ladder_id = l.id)"#,
+            FindingKind::CategoryProblemCount => r#"
+                SELECT c.id FROM cf_categories c
+                WHERE COALESCE(c.problem_count, 0) <>
+                      (SELECT COUNT(*) FROM cf_category_problems p WHERE p.category_id = c.id)"#,
+            // Snapshots are keyed by local date; yesterday's may still be today somewhere
+            FindingKind::StaleDashboardSnapshots => r#"
+                SELECT snapshot_date FROM dashboard_snapshots
+                WHERE snapshot_date < to_char(CURRENT_DATE - 1, 'YYYY-MM-DD')"#,
+            FindingKind::StaleStreaks => r#"
+                SELECT kind FROM pos_streaks
+                WHERE dirty = FALSE AND updated_at < NOW() - INTERVAL '7 days'"#,
+        }
+    }
+
+    fn repair_sql(self) -> &'static str {
+        match self {
+            FindingKind::OrphanActivityMetrics => r#"
+                DELETE FROM pos_activity_metrics m
+                WHERE NOT EXISTS (SELECT 1 FROM pos_activities a WHERE a.id = m.activity_id)"#,
+            FindingKind::OrphanLadderProgress => r#"
+                DELETE FROM cf_ladder_progress p
+                WHERE NOT EXISTS (SELECT 1 FROM cf_ladders l WHERE l.id = p.ladder_id)"#,
+            FindingKind::OrphanCategoryProgress => r#"
+                DELETE FROM cf_category_progress p
+                WHERE NOT EXISTS (SELECT 1 FROM cf_categories c WHERE c.id = p.category_id)"#,
+            // Clearing the pattern too, or the detached instance would turn into a template
+            FindingKind::GoalsMissingTemplate => r#"
+                UPDATE unified_goals g
+                SET recurring_template_id = NULL, recurring_pattern = NULL, updated_at = NOW()
+                WHERE g.recurring_template_id IS NOT NULL
+                  AND NOT EXISTS (SELECT 1 FROM unified_goals t WHERE t.id = g.recurring_template_id)"#,
+            FindingKind::GoalsDeletedTemplate => r#"
+                UPDATE unified_goals g
+                SET deleted_at = NOW(), archived_at = COALESCE(g.archived_at, NOW()), updated_at = NOW()
+                FROM unified_goals t
+                WHERE t.id = g.recurring_template_id
+                  AND t.deleted_at IS NOT NULL AND g.deleted_at IS NULL
+                  AND COALESCE(g.completed, FALSE) = FALSE
+                  AND g.date >= to_char(CURRENT_DATE, 'YYYY-MM-DD')"#,
+            FindingKind::LadderProblemCount => r#"
+                UPDATE cf_ladders l
+                SET problem_count = (SELECT COUNT(*) FROM cf_ladder_problems p WHERE p.ladder_id = l.id)
+                WHERE COALESCE(l.problem_count, 0) <>
+                      (SELECT COUNT(*) FROM cf_ladder_problems p WHERE p.ladder_id = l.id)"#,
+            FindingKind::CategoryProblemCount => r#"
+                UPDATE cf_categories c
+                SET problem_count = (SELECT COUNT(*) FROM cf_category_problems p WHERE p.category_id = c.id)
+                WHERE COALESCE(c.problem_count, 0) <>
+                      (SELECT COUNT(*) FROM cf_category_problems p WHERE p.category_id = c.id)"#,
+            FindingKind::StaleDashboardSnapshots => r#"
+                DELETE FROM dashboard_snapshots
+                WHERE snapshot_date < to_char(CURRENT_DATE - 1, 'YYYY-MM-DD')"#,
+            FindingKind::StaleStreaks => r#"
+                UPDATE pos_streaks SET dirty = TRUE
+                WHERE dirty = FALSE AND updated_at < NOW() - INTERVAL '7 days'"#,
+        }
+    }
+
+    fn touches_goals(self) -> bool {
+        matches!(self, FindingKind::GoalsMissingTemplate | FindingKind::GoalsDeletedTemplate)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IntegrityFinding {
+    pub kind: FindingKind,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub repair: String,
+    #[serde(default)]
+    pub count: i64,
+    /// Up to SAMPLE_SIZE affected ids
+    #[serde(default)]
+    pub sample_ids: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RepairedFinding {
+    pub kind: FindingKind,
+    pub rows_affected: u64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RepairReport {
+    pub repaired: Vec<RepairedFinding>,
+    pub total_rows_affected: u64,
+}
+
+// ─── Check ──────────────────────────────────────────────────────────
+
+/// Every check with at least one affected row
+pub async fn check(pool: &PgPool) -> PosResult<Vec<IntegrityFinding>> {
+    let mut findings = Vec::new();
+    for kind in FindingKind::ALL {
+        let rows: Vec<(String, i64)> = sqlx::query_as(&format!(
+            "SELECT id::text, COUNT(*) OVER () FROM ({}) AS s(id) ORDER BY id LIMIT $1",
+            kind.detect_sql()
+        ))
+        .bind(SAMPLE_SIZE)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| db_context(&format!("integrity check {:?}", kind), e))?;
+
+        if let Some(&(_, count)) = rows.first() {
+            findings.push(IntegrityFinding {
+                kind,
+                description: kind.description().to_string(),
+                repair: kind.repair_description().to_string(),
+                count,
+                sample_ids: rows.into_iter().map(|(id, _)| id).collect(),
+            });
+        }
+    }
+    Ok(findings)
+}
+
+/// Log what a startup check found; repairs are left to the user
+pub async fn log_startup_check(pool: &PgPool) {
+    match check(pool).await {
+        Ok(findings) if findings.is_empty() => log::info!("[INTEGRITY] No issues found"),
+        Ok(findings) => {
+            for f in &findings {
+                log::warn!("[INTEGRITY] {}: {} ({} rows)", f.description, f.sample_ids.join(", "), f.count);
+            }
+        }
+        Err(e) => log::warn!("[INTEGRITY] Startup check failed: {}", e),
+    }
+}
+
+// ─── Commands ───────────────────────────────────────────────────────
+
+#[tauri::command]
+pub async fn run_integrity_check(db: State<'_, PosDb>) -> PosResult<Vec<IntegrityFinding>> {
+    check(&db.0).await
+}
+
+/// Apply the repair for each finding's kind, all or nothing
+#[tauri::command]
+pub async fn repair_integrity(
+    db: State<'_, PosDb>,
+    findings: Vec<IntegrityFinding>,
+) -> PosResult<RepairReport> {
+    if findings.is_empty() {
+        return Err(PosError::InvalidInput("No findings to repair".into()));
+    }
+    let mut kinds: Vec<FindingKind> = Vec::new();
+    for f in findings {
+        if !kinds.contains(&f.kind) {
+            kinds.push(f.kind);
+        }
+    }
+
+    let pool = &db.0;
+    let mut tx = pool.begin().await.map_err(|e| db_context("begin repair", e))?;
+    let mut repaired = Vec::with_capacity(kinds.len());
+    for kind in &kinds {
+        let rows_affected = sqlx::query(kind.repair_sql())
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| db_context(&format!("repair {:?}", kind), e))?
+            .rows_affected();
+        repaired.push(RepairedFinding { kind: *kind, rows_affected });
+    }
+    tx.commit().await.map_err(|e| db_context("commit repair", e))?;
+
+    if kinds.iter().any(|k| k.touches_goals()) {
+        crate::dashboard::mark_snapshot_stale(pool).await;
+        crate::streaks::invalidate(pool, crate::streaks::StreakKind::AllGoals).await;
+    }
+
+    let total_rows_affected = repaired.iter().map(|r| r.rows_affected).sum();
+    log::info!("[INTEGRITY] Repaired {} rows across {} checks", total_rows_affected, repaired.len());
+    Ok(RepairReport { repaired, total_rows_affected })
+}
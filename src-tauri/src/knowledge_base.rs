@@ -9,7 +9,7 @@ use crate::pos::utils::gen_id;
 
 // ─── Row types ──────────────────────────────────────────────────────
 
-#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
 #[serde(rename_all = "camelCase")]
 pub struct KnowledgeItemRow {
     pub id: String,
@@ -125,7 +125,8 @@ pub async fn create_knowledge_item(
         .as_ref()
         .and_then(|s| s.parse::<DateTime<Utc>>().ok());
 
-    let metadata_json = req.metadata.as_ref().map(|m| sqlx::types::Json(m.clone()));
+    let metadata_json = crate::knowledge_problems::with_problem_ids(&req.content, req.metadata.clone())
+        .map(sqlx::types::Json);
 
     let row = sqlx::query_as::<_, KnowledgeItemRow>(
         r#"INSERT INTO knowledge_items (
@@ -241,6 +242,8 @@ pub async fn update_knowledge_item(
     let pool = &db.0;
     let now = Utc::now();
 
+    let content_or_metadata_changed = req.content.is_some() || req.metadata.is_some();
+
     // Build dynamic update query
     let mut updates: Vec<String> = Vec::new();
     let mut bind_index = 1;
@@ -309,10 +312,18 @@ pub async fn update_knowledge_item(
 
     q = q.bind(now).bind(&id);
 
-    let row = q.fetch_one(pool)
+    let mut row = q.fetch_one(pool)
         .await
         .map_err(|e| db_context("update_knowledge_item", e))?;
 
+    // Content or metadata may have changed which problems the item is about
+    if content_or_metadata_changed {
+        let ids = crate::knowledge_problems::link_item_problems(pool, &id).await?;
+        let mut meta = row.metadata.take().map(|m| m.0).filter(|m| m.is_object()).unwrap_or_else(|| json!({}));
+        meta["problemIds"] = json!(ids);
+        row.metadata = Some(sqlx::types::Json(meta));
+    }
+
     log::info!("[KB] Updated knowledge item {}", id);
     Ok(row)
 }
@@ -325,7 +336,8 @@ pub async fn delete_knowledge_item(
 ) -> PosResult<()> {
     let pool = &db.0;
 
-    sqlx::query("DELETE FROM knowledge_items WHERE id = $1")
+    let stmt = crate::delta_sync::with_tombstones("knowledge_items", "DELETE FROM knowledge_items WHERE id = $1");
+    sqlx::query(&stmt)
         .bind(&id)
         .execute(pool)
         .await
@@ -480,3 +492,167 @@ pub fn extract_urls(text: &str) -> Vec<String> {
     }
 }
 
+// ─── Spaced Repetition ──────────────────────────────────────────────
+// SM-2 scheduling. Per-item state lives in `metadata.review`; the computed due date is
+// written to `next_review_date` so the briefing and week planner pick reviews up.
+
+/// Tags/capture roles that make an item a flashcard before its first review
+const FLASHCARD_TAGS: &[&str] = &["flashcard", "question", "answer"];
+const MIN_EASE: f64 = 1.3;
+const DEFAULT_EASE: f64 = 2.5;
+const MAX_DUE_REVIEWS: i64 = 200;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReviewState {
+    pub ease: f64,
+    /// Days until the next review
+    pub interval: i64,
+    /// Consecutive successful reviews
+    pub repetitions: i64,
+    pub last_grade: Option<u8>,
+    pub last_reviewed_at: Option<DateTime<Utc>>,
+}
+
+impl Default for ReviewState {
+    fn default() -> Self {
+        Self { ease: DEFAULT_EASE, interval: 0, repetitions: 0, last_grade: None, last_reviewed_at: None }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReviewResult {
+    pub item: KnowledgeItemRow,
+    pub review: ReviewState,
+}
+
+/// SM-2 step for `grade` (0 = blackout … 5 = perfect). Grades below 3 restart the card.
+pub fn sm2_next(prev: &ReviewState, grade: u8) -> ReviewState {
+    let q = grade.min(5) as f64;
+    let ease = (prev.ease + 0.1 - (5.0 - q) * (0.08 + (5.0 - q) * 0.02)).max(MIN_EASE);
+    let (interval, repetitions) = if grade < 3 {
+        (1, 0)
+    } else {
+        let interval = match prev.repetitions {
+            0 => 1,
+            1 => 6,
+            _ => (prev.interval as f64 * ease).round() as i64,
+        };
+        (interval.max(1), prev.repetitions + 1)
+    };
+    ReviewState {
+        ease: (ease * 100.0).round() / 100.0,
+        interval,
+        repetitions,
+        last_grade: Some(grade),
+        last_reviewed_at: Some(Utc::now()),
+    }
+}
+
+/// Items due now, oldest due first: scheduled reviews plus flashcards never reviewed
+/// (tagged flashcard/question/answer or captured under those roles).
+#[tauri::command]
+pub async fn get_due_reviews(
+    db: State<'_, PosDb>,
+    limit: Option<i64>,
+) -> PosResult<Vec<KnowledgeItemRow>> {
+    let limit = limit.unwrap_or(50);
+    if !(1..=MAX_DUE_REVIEWS).contains(&limit) {
+        return Err(PosError::InvalidInput(format!("limit must be between 1 and {}", MAX_DUE_REVIEWS)));
+    }
+    let flashcard_tags: Vec<String> = FLASHCARD_TAGS.iter().map(|t| t.to_string()).collect();
+
+    sqlx::query_as::<_, KnowledgeItemRow>(
+        r#"SELECT id, tags, source, content, metadata, status, next_review_date,
+                  linked_note_id, linked_journal_date, created_at, updated_at
+           FROM knowledge_items
+           WHERE status <> 'Archived'
+             AND (next_review_date <= NOW()
+                  OR (next_review_date IS NULL
+                      AND NOT COALESCE(metadata ? 'review', FALSE)
+                      AND (tags && $1 OR metadata->>'captureRole' = ANY($1))))
+           ORDER BY COALESCE(next_review_date, created_at) ASC
+           LIMIT $2"#
+    )
+    .bind(&flashcard_tags)
+    .bind(limit)
+    .fetch_all(&db.0)
+    .await
+    .map_err(|e| db_context("get_due_reviews", e))
+}
+
+/// Grade a review (0-5) and schedule the next one
+#[tauri::command]
+pub async fn submit_review(
+    db: State<'_, PosDb>,
+    item_id: String,
+    grade: u8,
+) -> PosResult<ReviewResult> {
+    if grade > 5 {
+        return Err(PosError::InvalidInput("grade must be between 0 and 5".into()));
+    }
+    let pool = &db.0;
+
+    let metadata: Option<sqlx::types::Json<serde_json::Value>> = sqlx::query_scalar(
+        "SELECT metadata FROM knowledge_items WHERE id = $1"
+    )
+    .bind(&item_id)
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| db_context("submit_review fetch", e))?
+    .ok_or_else(|| PosError::NotFound(format!("Knowledge item {}", item_id)))?;
+
+    let prev: ReviewState = metadata
+        .and_then(|m| m.0.get("review").cloned())
+        .and_then(|r| serde_json::from_value(r).ok())
+        .unwrap_or_default();
+    let review = sm2_next(&prev, grade);
+    let next_review = Utc::now() + chrono::Duration::days(review.interval);
+
+    let item = sqlx::query_as::<_, KnowledgeItemRow>(
+        r#"UPDATE knowledge_items
+           SET metadata = COALESCE(metadata, '{}'::jsonb) || jsonb_build_object('review', $2::jsonb),
+               next_review_date = $3,
+               updated_at = NOW()
+           WHERE id = $1
+           RETURNING id, tags, source, content, metadata, status, next_review_date,
+                     linked_note_id, linked_journal_date, created_at, updated_at"#
+    )
+    .bind(&item_id)
+    .bind(sqlx::types::Json(&review))
+    .bind(next_review)
+    .fetch_one(pool)
+    .await
+    .map_err(|e| db_context("submit_review", e))?;
+
+    if let Some(reviewed_at) = review.last_reviewed_at {
+        crate::effort_ledger::award_review(pool, &item_id, reviewed_at).await;
+    }
+    log::info!("[KB REVIEW] {} graded {} → next in {} days (ease {})", item_id, grade, review.interval, review.ease);
+    Ok(ReviewResult { item, review })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sm2_intervals() {
+        let first = sm2_next(&ReviewState::default(), 4);
+        assert_eq!((first.interval, first.repetitions), (1, 1));
+        let second = sm2_next(&first, 4);
+        assert_eq!((second.interval, second.repetitions), (6, 2));
+        let third = sm2_next(&second, 5);
+        assert_eq!(third.interval, (6.0 * third.ease).round() as i64);
+        assert!(third.ease > second.ease);
+    }
+
+    #[test]
+    fn test_sm2_lapse_and_ease_floor() {
+        let mut state = ReviewState { ease: 1.4, interval: 30, repetitions: 5, ..Default::default() };
+        state = sm2_next(&state, 1);
+        assert_eq!((state.interval, state.repetitions), (1, 0));
+        assert_eq!(state.ease, MIN_EASE);
+    }
+}
@@ -4,8 +4,10 @@ use tauri::State;
 
 use crate::PosDb;
 use crate::pos::error::{PosError, PosResult, db_context};
+use crate::pos::problem_url::canonical_problem_url;
 use crate::pos::utils::gen_id;
 use crate::knowledge_base::{KnowledgeItemRow, KnowledgeLinkRow, CaptureLink};
+use crate::knowledge_problems::with_problem_ids;
 
 // ─── Quick Save & Backlinks ─────────────────────────────────────────
 
@@ -39,17 +41,28 @@ pub async fn quick_save_link(
     let row = sqlx::query_as::<_, KnowledgeItemRow>(
         r#"INSERT INTO knowledge_items
            (id, tags, source, content, metadata, status, next_review_date, linked_note_id, linked_journal_date, created_at, updated_at)
-           VALUES ($1, ARRAY['link']::TEXT[], 'Manual', $2, NULL, 'Inbox', NULL, NULL, NULL, $3, $3)
+           VALUES ($1, ARRAY['link']::TEXT[], 'Manual', $2, $4, 'Inbox', NULL, NULL, NULL, $3, $3)
            RETURNING id, tags, source, content, metadata, status, next_review_date, linked_note_id, linked_journal_date, created_at, updated_at"#
     )
     .bind(&id)
     .bind(&url)
     .bind(now)
+    .bind(with_problem_ids(&url, None).map(sqlx::types::Json))
     .fetch_one(pool)
     .await
     .map_err(|e| db_context("quick_save_link", e))?;
 
     log::info!("[KB] Quick saved link: {}", id);
+
+    // Title + reading time in the background; the item is usable without them
+    let bg_pool = pool.clone();
+    let bg_id = id.clone();
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = crate::reading_queue::enrich_link_item(&bg_pool, &bg_id).await {
+            log::warn!("[KB] Link enrichment failed for {}: {}", bg_id, e);
+        }
+    });
+
     Ok(row)
 }
 
@@ -165,7 +178,7 @@ pub async fn capture_daily_urls(
     // Build new link entries with source fingerprint
     let new_links: Vec<serde_json::Value> = urls.iter().map(|u| {
         json!({
-            "url": u.url,
+            "url": canonical_problem_url(&u.url),
             "url_type": u.url_type,
             "source_type": u.source_type,
             "source_id": u.source_id,
@@ -208,6 +221,7 @@ pub async fn capture_daily_urls(
     };
 
     let total_urls = metadata["urls"].as_array().map(|a| a.len()).unwrap_or(0);
+    let metadata = with_problem_ids("", Some(metadata)).unwrap_or_default();
 
     // Collect unique source types across all stored URLs for tagging
     let mut source_tags: Vec<String> = vec!["daily-capture".to_string(), date.clone()];
@@ -328,7 +342,7 @@ pub async fn backfill_activity_urls(db: State<'_, PosDb>) -> PosResult<BackfillR
             let combined = format!("{} {}", act.title, act.description);
             for m in url_re.find_iter(&combined) {
                 let raw = m.as_str();
-                let url = clean_url(raw);
+                let url = canonical_problem_url(&clean_url(raw));
                 if url.len() < 10 { continue; } // skip garbage
 
                 let key = (url.clone(), act.id.clone());
@@ -403,6 +417,7 @@ pub async fn backfill_activity_urls(db: State<'_, PosDb>) -> PosResult<BackfillR
 
         // Always upsert: either creating new or cleaning up existing duplicates/legacy fields
         let count = metadata["urls"].as_array().map(|a| a.len()).unwrap_or(0);
+        let metadata = with_problem_ids("", Some(metadata)).unwrap_or_default();
 
         let mut source_tags: Vec<String> = vec!["daily-capture".to_string(), date.clone()];
         if let Some(arr) = metadata["urls"].as_array() {
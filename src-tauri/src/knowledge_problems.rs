@@ -0,0 +1,159 @@
+// Knowledge ↔ Problem Linkage
+// Knowledge items whose content mentions a problem URL get the normalized problem IDs
+// stored in `metadata.problemIds`, so a problem view can pull up every note, capture
+// and editorial link written about it.
+
+use std::collections::BTreeSet;
+
+use serde::Serialize;
+use serde_json::{json, Value};
+use sqlx::PgPool;
+use tauri::State;
+
+use crate::PosDb;
+use crate::knowledge_base::{extract_urls, KnowledgeItemRow};
+use crate::pos::error::{PosError, PosResult, db_context};
+use crate::pos::problem_url::problem_id_from_url;
+
+/// Cheap pre-filter for rows that might contain a problem URL
+const PROBLEM_HOST_PATTERN: &str = "(codeforc|leetcode\\.com|atcoder\\.jp)";
+
+// ─── Types ──────────────────────────────────────────────────────────
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProblemKnowledge {
+    pub problem_id: String,
+    pub items: Vec<KnowledgeItemRow>,
+    /// Cached Codeforces editorial, if one has been looked up
+    pub editorial_url: Option<String>,
+}
+
+// ─── Helpers ────────────────────────────────────────────────────────
+
+/// Normalized, de-duplicated problem IDs mentioned in `content` or in the `urls` list
+/// that daily captures keep in metadata
+pub fn problem_ids_in(content: &str, metadata: Option<&Value>) -> Vec<String> {
+    let meta_urls = metadata
+        .and_then(|m| m.get("urls"))
+        .and_then(|u| u.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| entry.get("url").and_then(|u| u.as_str()).map(String::from));
+    extract_urls(content).into_iter()
+        .chain(meta_urls)
+        .filter_map(|u| problem_id_from_url(&u))
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect()
+}
+
+/// Add `problemIds` to a metadata object about to be written (no-op when there are none)
+pub(crate) fn with_problem_ids(content: &str, metadata: Option<Value>) -> Option<Value> {
+    let ids = problem_ids_in(content, metadata.as_ref());
+    if ids.is_empty() {
+        return metadata;
+    }
+    let mut meta = metadata.filter(|m| m.is_object()).unwrap_or_else(|| json!({}));
+    meta["problemIds"] = json!(ids);
+    Some(meta)
+}
+
+/// Bare ladder IDs ("1520A") are Codeforces problems
+pub(crate) fn normalize_problem_id(problem_id: &str) -> String {
+    let id = problem_id.trim();
+    if id.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("cf-{}", id.to_uppercase())
+    } else {
+        id.to_string()
+    }
+}
+
+/// Recompute `metadata.problemIds` for one item from its current content
+pub(crate) async fn link_item_problems(pool: &PgPool, id: &str) -> PosResult<Vec<String>> {
+    let (content, metadata): (String, Option<Value>) = sqlx::query_as(
+        "SELECT content, metadata FROM knowledge_items WHERE id = $1"
+    )
+    .bind(id)
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| db_context("link_item_problems fetch", e))?
+    .ok_or_else(|| PosError::NotFound(format!("Knowledge item {}", id)))?;
+
+    let ids = problem_ids_in(&content, metadata.as_ref());
+    sqlx::query(
+        "UPDATE knowledge_items SET metadata = COALESCE(metadata, '{}'::jsonb) || $2, updated_at = NOW() WHERE id = $1"
+    )
+    .bind(id)
+    .bind(json!({ "problemIds": ids }))
+    .execute(pool)
+    .await
+    .map_err(|e| db_context("link_item_problems store", e))?;
+    Ok(ids)
+}
+
+/// Fill `metadata.problemIds` for every item that mentions a problem host and hasn't been
+/// linked yet. Runs at startup and after bulk URL captures.
+pub async fn link_unlinked_knowledge_problems(pool: &PgPool) -> Result<(), sqlx::Error> {
+    let rows: Vec<(String, String, Option<Value>)> = sqlx::query_as(
+        r#"SELECT id, content, metadata FROM knowledge_items
+           WHERE (content ~* $1 OR metadata::text ~* $1)
+             AND NOT COALESCE(metadata ? 'problemIds', FALSE)"#
+    )
+    .bind(PROBLEM_HOST_PATTERN)
+    .fetch_all(pool)
+    .await?;
+    if rows.is_empty() {
+        return Ok(());
+    }
+
+    let (ids, patches): (Vec<String>, Vec<serde_json::Value>) = rows.into_iter()
+        .map(|(id, content, metadata)| (id, json!({ "problemIds": problem_ids_in(&content, metadata.as_ref()) })))
+        .unzip();
+    let updated = sqlx::query(
+        r#"UPDATE knowledge_items k SET metadata = COALESCE(k.metadata, '{}'::jsonb) || p.patch, updated_at = NOW()
+           FROM UNNEST($1::text[], $2::jsonb[]) AS p(id, patch)
+           WHERE k.id = p.id"#
+    )
+    .bind(&ids)
+    .bind(&patches)
+    .execute(pool)
+    .await?
+    .rows_affected();
+
+    log::info!("[KB PROBLEMS] Linked problem IDs on {} knowledge items", updated);
+    Ok(())
+}
+
+// ─── Commands ───────────────────────────────────────────────────────
+
+/// Notes, captures and links mentioning `problem_id` (normalized like `cf-1520A`,
+/// `leetcode-two-sum`; bare ladder IDs such as `1520A` are accepted), newest first.
+#[tauri::command]
+pub async fn get_knowledge_for_problem(
+    db: State<'_, PosDb>,
+    problem_id: String,
+) -> PosResult<ProblemKnowledge> {
+    let pool = &db.0;
+    let problem_id = normalize_problem_id(&problem_id);
+    if problem_id.is_empty() {
+        return Err(PosError::InvalidInput("problem_id is required".into()));
+    }
+    let editorial_key = problem_id.strip_prefix("cf-").unwrap_or("").to_string();
+
+    let (items, editorial_url) = tokio::try_join!(
+        sqlx::query_as::<_, KnowledgeItemRow>(
+            r#"SELECT id, tags, source, content, metadata, status, next_review_date,
+                      linked_note_id, linked_journal_date, created_at, updated_at
+               FROM knowledge_items
+               WHERE metadata->'problemIds' ? $1
+               ORDER BY created_at DESC"#
+        ).bind(&problem_id).fetch_all(pool),
+
+        sqlx::query_scalar::<_, Option<String>>(
+            "SELECT editorial_url FROM cf_problem_editorials WHERE problem_id = $1"
+        ).bind(&editorial_key).fetch_optional(pool),
+    ).map_err(|e| db_context("get_knowledge_for_problem", e))?;
+
+    Ok(ProblemKnowledge { problem_id, items, editorial_url: editorial_url.flatten() })
+}
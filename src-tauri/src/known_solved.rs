@@ -0,0 +1,180 @@
+// Known Solved Problems
+// Problems solved before coppermind was tracking anything. They have no submissions, so
+// they live in `known_solved`; the `solved_problems` view merges them with accepted
+// submissions for recommendation strategies and ladder stats.
+
+use serde::Serialize;
+use tauri::State;
+
+use crate::PosDb;
+use crate::pos::error::{PosError, PosResult, db_context};
+use crate::pos::problem_url::problem_id_from_url;
+
+const MAX_IMPORT: usize = 20_000;
+
+// ─── Types ──────────────────────────────────────────────────────────
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportSolvedResult {
+    pub platform: String,
+    pub imported: u64,
+    pub already_known: u64,
+    /// Lines that held no recognizable problem reference
+    pub invalid: Vec<String>,
+}
+
+// ─── Parsing ────────────────────────────────────────────────────────
+
+/// `pos_submissions.platform` value for a platform name or alias
+pub fn normalize_platform(platform: &str) -> PosResult<&'static str> {
+    match platform.trim().to_lowercase().as_str() {
+        "cf" | "codeforces" => Ok("codeforces"),
+        "lc" | "leetcode" => Ok("leetcode"),
+        "ac" | "atcoder" => Ok("atcoder"),
+        other => Err(PosError::InvalidInput(format!(
+            "Unknown platform '{}'. Expected codeforces, leetcode or atcoder", other
+        ))),
+    }
+}
+
+/// Submission-style id (`cf-1520A`, `leetcode-two-sum`, `atcoder-abc300_a`) for one cell:
+/// a problem URL, a prefixed id or a bare id.
+pub(crate) fn normalize_ref(platform: &str, cell: &str) -> Option<String> {
+    let cell = cell.trim().trim_matches('"');
+    if cell.contains("://") {
+        return problem_id_from_url(cell).filter(|id| id.starts_with(id_prefix(platform)));
+    }
+    match platform {
+        "codeforces" => {
+            let bare = cell.strip_prefix("cf-").unwrap_or(cell).replace(['/', ' ', '-'], "");
+            let digits = bare.chars().take_while(|c| c.is_ascii_digit()).count();
+            let index = &bare[digits..];
+            let valid_index = index.chars().next().is_some_and(|c| c.is_ascii_alphabetic())
+                && index.len() <= 3
+                && index.chars().all(|c| c.is_ascii_alphanumeric());
+            (digits > 0 && valid_index).then(|| format!("cf-{}{}", &bare[..digits], index.to_uppercase()))
+        }
+        "leetcode" => {
+            let slug = cell.strip_prefix("leetcode-").unwrap_or(cell).to_lowercase();
+            let valid = !slug.is_empty()
+                && !slug.chars().all(|c| c.is_ascii_digit())
+                && slug.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-');
+            valid.then(|| format!("leetcode-{}", slug))
+        }
+        _ => {
+            let task = cell.strip_prefix("atcoder-").unwrap_or(cell).to_lowercase();
+            let valid = task.contains('_')
+                && task.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_' || c == '-');
+            valid.then(|| format!("atcoder-{}", task))
+        }
+    }
+}
+
+fn id_prefix(platform: &str) -> &'static str {
+    match platform {
+        "codeforces" => "cf-",
+        "leetcode" => "leetcode-",
+        _ => "atcoder-",
+    }
+}
+
+/// Problem ids from a pasted list or CSV: one reference per line (the first cell, or any
+/// cell holding a problem URL), or a single comma/space separated line.
+/// Returns (ids, unparseable lines).
+pub fn parse_problem_refs(platform: &str, input: &str) -> (Vec<String>, Vec<String>) {
+    let lines: Vec<&str> = input.lines().map(str::trim).filter(|l| !l.is_empty()).collect();
+    let mut ids: Vec<String> = Vec::new();
+    let mut invalid = Vec::new();
+
+    let push = |id: String, ids: &mut Vec<String>| {
+        if !ids.contains(&id) {
+            ids.push(id);
+        }
+    };
+
+    if lines.len() == 1 {
+        for cell in lines[0].split([',', ';', '\t', ' ']).filter(|c| !c.trim().is_empty()) {
+            match normalize_ref(platform, cell) {
+                Some(id) => push(id, &mut ids),
+                None => invalid.push(cell.trim().to_string()),
+            }
+        }
+        return (ids, invalid);
+    }
+
+    for line in lines {
+        let mut cells = line.split([',', ';', '\t']);
+        let first = cells.next().and_then(|cell| normalize_ref(platform, cell));
+        let from_url = || cells.filter(|c| c.contains("://")).find_map(|cell| normalize_ref(platform, cell));
+        match first.or_else(from_url) {
+            Some(id) => push(id, &mut ids),
+            None => invalid.push(line.to_string()),
+        }
+    }
+    (ids, invalid)
+}
+
+// ─── Commands ───────────────────────────────────────────────────────
+
+/// Mark historical solves so recommendations and ladder stats treat them as solved
+#[tauri::command]
+pub async fn import_solved_problems(
+    db: State<'_, PosDb>,
+    platform: String,
+    ids_or_csv: String,
+) -> PosResult<ImportSolvedResult> {
+    let platform = normalize_platform(&platform)?;
+    let (ids, invalid) = parse_problem_refs(platform, &ids_or_csv);
+    if ids.is_empty() {
+        return Err(PosError::InvalidInput("No problem ids or URLs found in input".into()));
+    }
+    if ids.len() > MAX_IMPORT {
+        return Err(PosError::InvalidInput(format!("At most {} problems per import", MAX_IMPORT)));
+    }
+
+    let imported = sqlx::query(
+        r#"INSERT INTO known_solved (problem_id, platform, source)
+           SELECT pid, $2, 'import' FROM UNNEST($1::text[]) AS pid
+           ON CONFLICT (problem_id) DO NOTHING"#
+    )
+    .bind(&ids)
+    .bind(platform)
+    .execute(&db.0)
+    .await
+    .map_err(|e| db_context("import_solved_problems", e))?
+    .rows_affected();
+
+    log::info!("[KNOWN SOLVED] Imported {} {} problems ({} already known, {} invalid lines)",
+        imported, platform, ids.len() as u64 - imported, invalid.len());
+
+    Ok(ImportSolvedResult {
+        platform: platform.to_string(),
+        imported,
+        already_known: ids.len() as u64 - imported,
+        invalid,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_codeforces_refs() {
+        let (ids, invalid) = parse_problem_refs("codeforces", "1520A, cf-1700b 4/C https://codeforces.com/contest/1843/problem/D1 oops");
+        assert_eq!(ids, vec!["cf-1520A", "cf-1700B", "cf-4C", "cf-1843D1"]);
+        assert_eq!(invalid, vec!["oops"]);
+    }
+
+    #[test]
+    fn test_parse_csv_lines() {
+        let csv = "problem_id,solved_on\ntwo-sum,2021-03-01\nhttps://leetcode.com/problems/3sum/,2021-03-02\n42,2021-03-03\n";
+        let (ids, invalid) = parse_problem_refs("leetcode", csv);
+        assert_eq!(ids, vec!["leetcode-two-sum", "leetcode-3sum"]);
+        assert_eq!(invalid.len(), 2);
+
+        let (ids, _) = parse_problem_refs("atcoder", "abc300_a\natcoder-ARC150_B\n");
+        assert_eq!(ids, vec!["atcoder-abc300_a", "atcoder-arc150_b"]);
+    }
+}
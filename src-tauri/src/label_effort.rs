@@ -0,0 +1,152 @@
+// Label Effort Matrix
+// Minutes and completions per goal label per calendar week (Monday start), used by the
+// weekly review to spot areas that are getting starved of time.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use chrono::NaiveDate;
+use serde::Serialize;
+use tauri::State;
+
+use crate::PosDb;
+use crate::pos::error::{PosError, PosResult, db_context};
+use crate::pos::timezone;
+
+const UNLABELED: &str = "(unlabeled)";
+
+// ─── Types ──────────────────────────────────────────────────────────
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LabelWeekCell {
+    pub label: String,
+    pub week_start: String, // YYYY-MM-DD (Monday)
+    pub minutes: i64,
+    pub completed: i64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LabelTotal {
+    pub label: String,
+    pub minutes: i64,
+    pub completed: i64,
+    pub share: f64, // fraction of all labelled minutes in range
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LabelEffortMatrix {
+    pub weeks: Vec<String>,
+    pub labels: Vec<String>,
+    pub cells: Vec<LabelWeekCell>,
+    pub totals: Vec<LabelTotal>,
+    /// Labels getting under half an even share overall, or nothing in the latest week
+    pub starved_labels: Vec<String>,
+}
+
+// ─── Commands ───────────────────────────────────────────────────────
+
+/// Label × week effort between `start_date` and `end_date` (inclusive, YYYY-MM-DD).
+/// Activity minutes are split evenly across the goals they're linked to, then credited
+/// to each label of those goals. Completions count goals by `completed_at` week.
+#[tauri::command]
+pub async fn get_label_effort_matrix(
+    db: State<'_, PosDb>,
+    start_date: String,
+    end_date: String,
+) -> PosResult<LabelEffortMatrix> {
+    let pool = &db.0;
+    for d in [&start_date, &end_date] {
+        NaiveDate::parse_from_str(d, "%Y-%m-%d")
+            .map_err(|e| PosError::InvalidInput(format!("Invalid date '{}': {}", d, e)))?;
+    }
+    if start_date > end_date {
+        return Err(PosError::InvalidInput("start_date must be on or before end_date".into()));
+    }
+
+    // Completions bucket by local day, like the activities' stored date
+    let completed_day = timezone::sql_local_date("g.completed_at");
+    let (minute_rows, completion_rows, weeks) = tokio::try_join!(
+        sqlx::query_as::<_, (String, String, i64)>(
+            r#"SELECT date_trunc('week', a.date::date)::date::text AS week_start,
+                      COALESCE(lbl, $3) AS label,
+                      SUM(EXTRACT(EPOCH FROM (a.end_time - a.start_time)) / 60
+                          / cardinality(a.goal_ids))::bigint AS minutes
+               FROM pos_activities a
+               JOIN unified_goals g ON g.id = ANY(a.goal_ids)
+               LEFT JOIN LATERAL jsonb_array_elements_text(
+                   CASE WHEN jsonb_typeof(g.labels) = 'array' THEN g.labels ELSE '[]'::jsonb END
+               ) lbl ON TRUE
+               WHERE a.is_shadow = FALSE AND a.deleted_at IS NULL
+                 AND a.date::date BETWEEN $1::date AND $2::date
+               GROUP BY 1, 2"#
+        ).bind(&start_date).bind(&end_date).bind(UNLABELED).fetch_all(pool),
+
+        sqlx::query_as::<_, (String, String, i64)>(&format!(
+            r#"SELECT date_trunc('week', {completed_day})::date::text AS week_start,
+                      COALESCE(lbl, $3) AS label,
+                      COUNT(*)::bigint AS completed
+               FROM unified_goals g
+               LEFT JOIN LATERAL jsonb_array_elements_text(
+                   CASE WHEN jsonb_typeof(g.labels) = 'array' THEN g.labels ELSE '[]'::jsonb END
+               ) lbl ON TRUE
+               WHERE g.completed = TRUE
+                 AND {completed_day} BETWEEN $1::date AND $2::date
+               GROUP BY 1, 2"#
+        )).bind(&start_date).bind(&end_date).bind(UNLABELED).fetch_all(pool),
+
+        sqlx::query_scalar::<_, String>(
+            r#"SELECT w::date::text FROM generate_series(
+                   date_trunc('week', $1::date), date_trunc('week', $2::date), INTERVAL '1 week'
+               ) w"#
+        ).bind(&start_date).bind(&end_date).fetch_all(pool),
+    ).map_err(|e| db_context("get_label_effort_matrix", e))?;
+
+    // (label, week) → (minutes, completed)
+    let mut grid: BTreeMap<(String, String), (i64, i64)> = BTreeMap::new();
+    for (week, label, minutes) in minute_rows {
+        grid.entry((label, week)).or_default().0 += minutes;
+    }
+    for (week, label, completed) in completion_rows {
+        grid.entry((label, week)).or_default().1 += completed;
+    }
+
+    let labels: Vec<String> = grid.keys().map(|(l, _)| l.clone()).collect::<BTreeSet<_>>().into_iter().collect();
+
+    let mut cells = Vec::with_capacity(labels.len() * weeks.len());
+    for label in &labels {
+        for week in &weeks {
+            let (minutes, completed) = grid.get(&(label.clone(), week.clone())).copied().unwrap_or((0, 0));
+            cells.push(LabelWeekCell { label: label.clone(), week_start: week.clone(), minutes, completed });
+        }
+    }
+
+    let all_minutes: i64 = cells.iter().map(|c| c.minutes).sum();
+    let totals: Vec<LabelTotal> = labels.iter().map(|label| {
+        let (minutes, completed) = cells.iter()
+            .filter(|c| &c.label == label)
+            .fold((0, 0), |(m, c), cell| (m + cell.minutes, c + cell.completed));
+        LabelTotal {
+            label: label.clone(),
+            minutes,
+            completed,
+            share: if all_minutes > 0 { minutes as f64 / all_minutes as f64 } else { 0.0 },
+        }
+    }).collect();
+
+    let fair_share = if labels.is_empty() { 0.0 } else { 1.0 / labels.len() as f64 };
+    let latest_week = weeks.last();
+    let starved_labels = totals.iter()
+        .filter(|t| t.label != UNLABELED)
+        .filter(|t| {
+            let idle_latest = latest_week.is_some_and(|w| {
+                cells.iter().any(|c| &c.label == &t.label && &c.week_start == w && c.minutes == 0)
+            });
+            t.share < fair_share / 2.0 || idle_latest
+        })
+        .map(|t| t.label.clone())
+        .collect();
+
+    Ok(LabelEffortMatrix { weeks, labels, cells, totals, starved_labels })
+}
@@ -1,5 +1,6 @@
 use std::io::Read;
-use std::sync::{Arc, Mutex};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, RwLock};
 use std::time::{Duration, Instant};
 use std::thread;
 use tauri::{AppHandle, Emitter, Manager};
@@ -21,10 +22,66 @@ mod cf_recommendations;
 mod date_summary;
 mod books;
 mod daily_briefing;
+mod day_summary;
 mod briefing_aggregates;
 mod briefing_monthly;
 mod briefing_yearly;
 mod cross_references;
+mod topic_timeline;
+mod seed;
+mod dashboard;
+mod goal_suggestions;
+mod capture_roles;
+mod trends;
+mod year_heatmap;
+mod difficulty_progression;
+mod label_effort;
+mod goal_archive;
+mod cross_platform_gaps;
+mod goal_metrics;
+mod debt_stats;
+mod debt_payoff;
+mod effort_ledger;
+mod problemset;
+mod week_plan;
+mod palette;
+mod voice_memo;
+mod repo_goals;
+mod day_plan;
+mod reading_queue;
+mod habit_stats;
+mod snippets;
+mod data_export;
+mod goal_ordering;
+mod settings_transfer;
+mod knowledge_problems;
+mod session_log;
+mod scrape_scheduler;
+mod known_solved;
+mod offline_queue;
+mod cf_recommendation_history;
+mod streaks;
+mod cohort;
+mod goal_dependencies;
+mod category_inference;
+mod notifications;
+mod cf_contests;
+mod goal_month_summary;
+mod integrity;
+mod context_switch;
+mod saved_filters;
+mod day_capacity;
+mod delta_sync;
+mod problem_renormalize;
+mod captures;
+mod stuck_log;
+mod drafts;
+mod streak_freezes;
+mod recurring_reconcile;
+mod recurrence;
+mod http_bridge;
+mod problem_detail;
+pub mod coppermind_core;
 
 pub mod github {
     pub use crate::pos::github::*;
@@ -39,21 +96,35 @@ pub struct PosConfig(pub pos::config::PosConfig);
 /// Double-tap threshold in milliseconds
 const DOUBLE_TAP_MS: u64 = 300;
 
-/// State for tracking shift key double-taps
-struct ShiftState {
-    last_left_release: Option<Instant>,
-    last_right_release: Option<Instant>,
+/// Delay between PostgreSQL reconnect attempts after startup failed
+const POS_RECONNECT_SECS: u64 = 60;
+
+/// Last release time per double-tap trigger
+struct TapState {
+    last_release: HashMap<&'static str, Instant>,
 }
 
-impl ShiftState {
+impl TapState {
     fn new() -> Self {
         Self {
-            last_left_release: None,
-            last_right_release: None,
+            last_release: HashMap::new(),
         }
     }
 }
 
+/// Map a released key to its capture trigger name (see capture_roles::CAPTURE_TRIGGERS)
+fn trigger_for_key(key: Key) -> Option<&'static str> {
+    match key {
+        Key::ShiftLeft => Some("double_left_shift"),
+        Key::ShiftRight => Some("double_right_shift"),
+        Key::ControlLeft => Some("double_left_ctrl"),
+        Key::ControlRight => Some("double_right_ctrl"),
+        Key::Alt => Some("double_left_alt"),
+        Key::AltGr => Some("double_right_alt"),
+        _ => None,
+    }
+}
+
 /// Read the selection (Smart: Primary -> Clipboard fallback, prioritizing URLs)
 #[tauri::command]
 fn read_primary_selection() -> Result<String, String> {
@@ -153,9 +224,11 @@ fn read_x11_generic(primary: bool) -> Result<String, String> {
         .map_err(|e| e.to_string())
 }
 
-/// Start the keyboard listener for double-shift detection using grab (works on Wayland)
-fn start_keyboard_listener(app_handle: AppHandle) {
-    let state = Arc::new(Mutex::new(ShiftState::new()));
+/// Start the keyboard listener for double-tap detection using grab (works on Wayland).
+/// Triggers are resolved to roles through the shared capture role state on every tap,
+/// so `set_capture_roles` takes effect without a restart.
+fn start_keyboard_listener(app_handle: AppHandle, roles: Arc<RwLock<Vec<capture_roles::CaptureRole>>>) {
+    let state = Arc::new(Mutex::new(TapState::new()));
     let double_tap_threshold = Duration::from_millis(DOUBLE_TAP_MS);
     
     thread::spawn(move || {
@@ -163,57 +236,34 @@ fn start_keyboard_listener(app_handle: AppHandle) {
         let app = app_handle.clone();
         
         log::info!("Keyboard listener starting with grab (evdev)...");
-        log::info!("Double-tap LeftShift = Question, RightShift = Answer");
+        log::info!("Double-tap LeftShift = Question, RightShift = Answer (defaults)");
         
         // Use grab() instead of listen() for Wayland support via evdev
         // Returns Some(event) to pass through, None to consume
         let result = grab(move |event: Event| -> Option<Event> {
             if let EventType::KeyRelease(key) = event.event_type {
+                let Some(trigger) = trigger_for_key(key) else {
+                    return Some(event);
+                };
                 let now = Instant::now();
                 let mut state = state.lock().unwrap();
-                
-                match key {
-                    Key::ShiftLeft => {
-                        // Check for double-tap left shift -> Question
-                        if let Some(last) = state.last_left_release {
-                            if now.duration_since(last) < double_tap_threshold {
-                                // Double-tap detected!
-                                if let Ok(content) = read_primary_selection() {
-                                    if !content.is_empty() {
-                                        let _ = app.emit("capture-content", serde_json::json!({
-                                            "role": "question",
-                                            "content": content
-                                        }));
-                                        log::info!("Captured question: {} chars", content.len());
-                                    }
-                                }
-                                state.last_left_release = None;
-                                return Some(event); // Pass through the event
-                            }
-                        }
-                        state.last_left_release = Some(now);
-                    }
-                    Key::ShiftRight => {
-                        // Check for double-tap right shift -> Answer
-                        if let Some(last) = state.last_right_release {
-                            if now.duration_since(last) < double_tap_threshold {
-                                // Double-tap detected!
-                                if let Ok(content) = read_primary_selection() {
-                                    if !content.is_empty() {
-                                        let _ = app.emit("capture-content", serde_json::json!({
-                                            "role": "answer",
-                                            "content": content
-                                        }));
-                                        log::info!("Captured answer: {} chars", content.len());
-                                    }
-                                }
-                                state.last_right_release = None;
-                                return Some(event); // Pass through the event
-                            }
+
+                let is_double_tap = state.last_release.get(trigger)
+                    .is_some_and(|last| now.duration_since(*last) < double_tap_threshold);
+                if !is_double_tap {
+                    state.last_release.insert(trigger, now);
+                    return Some(event);
+                }
+                state.last_release.remove(trigger);
+
+                // Double-tap detected — unbound triggers are ignored
+                let role = roles.read().unwrap().iter().find(|r| r.trigger == trigger).cloned();
+                if let Some(role) = role {
+                    if let Ok(content) = read_primary_selection() {
+                        if !content.is_empty() {
+                            dispatch_capture(&app, role, content);
                         }
-                        state.last_right_release = Some(now);
                     }
-                    _ => {}
                 }
             }
             
@@ -227,12 +277,139 @@ fn start_keyboard_listener(app_handle: AppHandle) {
     });
 }
 
+/// Send a capture to its role's destination: notes go to the frontend,
+/// everything else is persisted directly and announced via `capture-routed`.
+fn dispatch_capture(app: &AppHandle, role: capture_roles::CaptureRole, content: String) {
+    if role.destination == capture_roles::CaptureDestination::Note {
+        let _ = app.emit("capture-content", serde_json::json!({
+            "role": role.role,
+            "content": content
+        }));
+        log::info!("Captured {}: {} chars", role.role, content.len());
+        return;
+    }
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let Some(db) = app.try_state::<PosDb>() else {
+            log::error!("[CAPTURE] Database not ready, dropping {} capture", role.role);
+            return;
+        };
+        match capture_roles::route_capture(&db.0, &role, &content).await {
+            Ok(id) => {
+                let _ = app.emit("capture-routed", serde_json::json!({
+                    "role": role.role,
+                    "destination": role.destination,
+                    "id": id
+                }));
+            }
+            Err(e) => log::error!("[CAPTURE] Failed to route {} capture: {}", role.role, e),
+        }
+    });
+}
+
+/// Called in the already-running instance when coppermind is launched again.
+/// Brings the main window forward and forwards any deep-link URLs from the new argv.
+fn focus_existing_instance(app: &AppHandle, argv: Vec<String>) {
+    log::info!("[INSTANCE] Second launch detected, focusing existing window");
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.unminimize();
+        let _ = window.show();
+        let _ = window.set_focus();
+    }
+
+    let links: Vec<String> = argv.into_iter().skip(1).filter(|a| a.contains("://")).collect();
+    if !links.is_empty() {
+        let _ = app.emit("deep-link", serde_json::json!({ "urls": links }));
+    }
+}
+
+/// Manage the pool, run migrations and start everything that needs PostgreSQL,
+/// then replay writes queued while it was unreachable
+async fn on_pos_connected(handle: AppHandle, pool: sqlx::PgPool) {
+    log::info!("[POS] Step 3b: PostgreSQL connected, managing PosDb state immediately");
+
+    // Store pool in managed state IMMEDIATELY to prevent "state not managed" panics
+    handle.manage(PosDb(pool.clone()));
+
+    log::info!("[POS] Step 3c: Initializing tables (Migrations)");
+
+    // Create POS tables with retry
+    let init_result = pos::retry::retry_db_operation(
+        || pos::db::init_pos_tables(&pool),
+        3,
+    ).await;
+
+    if let Err(e) = init_result {
+        log::error!("[POS] Failed to init tables after retries: {e}");
+        return;
+    }
+
+    log::info!("[POS] ✓ Tables initialized successfully");
+
+    if let Some(roles) = handle.try_state::<capture_roles::CaptureRolesState>() {
+        if let Err(e) = capture_roles::load_capture_roles(&pool, &roles).await {
+            log::warn!("[CAPTURE] Using default capture roles: {e}");
+        }
+    }
+
+    match pos::idempotency::purge_expired_keys(&pool).await {
+        Ok(n) if n > 0 => log::info!("[IDEMPOTENCY] Purged {n} expired keys"),
+        Ok(_) => {}
+        Err(e) => log::warn!("[IDEMPOTENCY] Failed to purge expired keys: {e}"),
+    }
+    match delta_sync::prune_tombstones(&pool).await {
+        Ok(n) if n > 0 => log::info!("[SYNC] Pruned {n} expired tombstones"),
+        Ok(_) => {}
+        Err(e) => log::warn!("[SYNC] Failed to prune tombstones: {e}"),
+    }
+    match drafts::prune_expired_drafts(&pool).await {
+        Ok(n) if n > 0 => log::info!("[DRAFTS] Pruned {n} expired drafts"),
+        Ok(_) => {}
+        Err(e) => log::warn!("[DRAFTS] Failed to prune drafts: {e}"),
+    }
+    streak_freezes::settle_freezes(&pool, Some(&handle)).await;
+    if let Err(e) = effort_ledger::sync_effort_ledger(&pool).await {
+        log::warn!("[EFFORT] Failed to sync effort ledger: {e}");
+    }
+
+    integrity::log_startup_check(&pool).await;
+
+    let split = handle.try_state::<PosConfig>().is_some_and(|c| c.0.split_activities_at_midnight);
+    if let Some(queue) = handle.try_state::<offline_queue::OfflineQueue>() {
+        if let Err(e) = offline_queue::replay(&pool, &queue.0, split).await {
+            log::warn!("[OFFLINE] Queue replay failed: {e}");
+        }
+    }
+
+    if let Some(config) = handle.try_state::<PosConfig>() {
+        scrape_scheduler::start(pool.clone(), config.0.clone());
+        cf_friends_system::start_nightly_full_sync(pool.clone(), handle.clone(), config.0.cf_friend_nightly_full_sync);
+        http_bridge::start(handle.clone(), config.0.http_bridge_port);
+    }
+    cohort::start(pool.clone());
+    // Reminders only from the main window's process
+    if std::env::var("WIDGET_MODE").is_err() {
+        notifications::start(handle.clone(), pool.clone());
+    }
+}
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     // Load .env from project root (coppermind/)
     let _ = dotenvy::dotenv();
 
-    tauri::Builder::default()
+    let mut builder = tauri::Builder::default();
+
+    // Must be the first plugin. The widget runs as its own process by design, so it is exempt;
+    // a second main instance would otherwise double the keyboard grab and every scheduler.
+    if std::env::var("WIDGET_MODE").is_err() {
+        builder = builder.plugin(tauri_plugin_single_instance::init(|app, argv, _cwd| {
+            focus_existing_instance(app, argv);
+        }));
+    }
+
+    builder
         .setup(|app| {
             if cfg!(debug_assertions) {
                 app.handle().plugin(
@@ -248,11 +425,22 @@ pub fn run() {
             }
             app.handle().plugin(tauri_plugin_clipboard_manager::init())?;
             app.handle().plugin(tauri_plugin_shell::init())?;
+            app.handle().plugin(tauri_plugin_notification::init())?;
             
             // rdev::grab is an exclusive evdev grab — only one process can hold it.
             // Widget process must not grab, or it breaks double-shift in the main app.
+            let capture_roles_state = Arc::new(RwLock::new(capture_roles::default_capture_roles()));
+            app.handle().manage(capture_roles::CaptureRolesState(capture_roles_state.clone()));
             if !is_widget {
-                start_keyboard_listener(app.handle().clone());
+                start_keyboard_listener(app.handle().clone(), capture_roles_state);
+            }
+
+            // ─── Offline write queue (available even without Postgres) ──
+            match tauri::async_runtime::block_on(offline_queue::open(app.handle())) {
+                Ok(queue) => {
+                    app.handle().manage(offline_queue::OfflineQueue(queue));
+                }
+                Err(e) => log::error!("[OFFLINE] Failed to open offline queue: {e}"),
             }
 
             // ─── POS: Load and validate configuration ─────────────────
@@ -274,6 +462,11 @@ pub fn run() {
             let max_connections = pos_config.db_max_connections;
             let timeout_secs = pos_config.db_connection_timeout_secs;
             
+            pos::timezone::init(pos_config.timezone);
+            log::info!("[POS Config] Local dates use timezone: {}", pos::timezone::zone_name());
+            pos::timezone::watch_system_zone(app.handle().clone());
+            pos::scrapers::queue::init(app.handle().clone());
+
             log::info!("[POS] Step 2: Managing PosConfig state");
             app.handle().manage(PosConfig(pos_config));
             log::info!("[POS] Step 2: PosConfig state managed successfully");
@@ -298,30 +491,26 @@ pub fn run() {
                 ).await;
 
                 match pool_result {
-                    Ok(pool) => {
-                        log::info!("[POS] Step 3b: PostgreSQL connected, managing PosDb state immediately");
-                        
-                        // Store pool in managed state IMMEDIATELY to prevent "state not managed" panics
-                        handle.manage(PosDb(pool.clone()));
-
-                        log::info!("[POS] Step 3c: Initializing tables (Migrations)");
-                        
-                        // Create POS tables with retry
-                        let init_result = pos::retry::retry_db_operation(
-                            || pos::db::init_pos_tables(&pool),
-                            3,
-                        ).await;
-
-                        if let Err(e) = init_result {
-                            log::error!("[POS] Failed to init tables after retries: {e}");
-                            return;
-                        }
-                        
-                        log::info!("[POS] ✓ Tables initialized successfully");
-                    }
+                    Ok(pool) => on_pos_connected(handle, pool).await,
                     Err(e) => {
                         log::error!("[POS] Failed to connect to PostgreSQL after retries: {e}");
-                        log::error!("[POS] POS features will be unavailable");
+                        log::error!("[POS] Writes will be queued offline; retrying every {POS_RECONNECT_SECS}s");
+                        loop {
+                            tokio::time::sleep(Duration::from_secs(POS_RECONNECT_SECS)).await;
+                            let reconnect = PgPoolOptions::new()
+                                .max_connections(max_connections)
+                                .acquire_timeout(Duration::from_secs(timeout_secs))
+                                .connect(&db_url)
+                                .await;
+                            match reconnect {
+                                Ok(pool) => {
+                                    log::info!("[POS] Reconnected to PostgreSQL");
+                                    on_pos_connected(handle, pool).await;
+                                    break;
+                                }
+                                Err(e) => log::warn!("[POS] Reconnect failed: {e}"),
+                            }
+                        }
                     }
                 }
             });
@@ -340,26 +529,94 @@ pub fn run() {
             pos::activities::create_activity,
             pos::activities::update_activity,
             pos::activities::patch_activity,
+            pos::activities::delete_activity,
+            pos::activities::restore_activity,
+            pos::activities::import_activities_csv,
+            pos::activities::find_overlapping_activities,
+            pos::shadow::reconcile_shadow_activities,
+            pos::timezone::rederive_activity_dates,
+            pos::timezone::get_timezone,
             pos::activities::get_activity_range,
             pos::activities::get_food_activities,
             pos::activities::get_project_activities,
+            pos::evidence::attach_evidence_to_activity,
+            pos::evidence::get_activity_evidence,
+            pos::evidence::delete_activity_evidence,
             pos::submissions::get_submissions,
+            pos::submissions::get_problem_statuses,
+            pos::submissions::exclude_submission,
+            pos::submissions::include_submission,
+            pos::submissions::edit_submission_metadata,
+            streaks::get_streaks,
+            cohort::add_cohort_member,
+            cohort::remove_cohort_member,
+            cohort::get_cohort_members,
+            cohort::sync_cohort,
+            cohort::get_cohort_leaderboard,
+            goal_dependencies::add_goal_dependency,
+            goal_dependencies::remove_goal_dependency,
+            goal_dependencies::get_goal_graph,
+            category_inference::infer_activity_category,
+            category_inference::quick_add_activity,
+            category_inference::get_category_review_queue,
+            category_inference::resolve_category_review,
+            notifications::get_notification_preferences,
+            notifications::set_notification_muted,
+            notifications::set_quiet_hours,
+            cf_contests::get_upcoming_contests,
+            cf_contests::register_contest_goal,
+            goal_month_summary::get_month_goal_summary,
+            integrity::run_integrity_check,
+            integrity::repair_integrity,
+            context_switch::get_context_switch_report,
+            saved_filters::save_filter,
+            saved_filters::get_saved_filters,
+            saved_filters::delete_saved_filter,
+            saved_filters::apply_saved_filter,
+            day_capacity::get_capacity_profile,
+            day_capacity::set_capacity_profile,
+            day_capacity::check_day_capacity,
+            delta_sync::get_changes_since,
+            problem_renormalize::renormalize_problem_ids,
             pos::scrapers::leetcode::scrape_leetcode,
             pos::scrapers::leetcode::get_leetcode_user_stats,
+            pos::scrapers::leetcode_contests::sync_leetcode_contests,
+            pos::scrapers::leetcode_contests::get_leetcode_contest_history,
             pos::scrapers::codeforces::scrape_codeforces,
+            pos::scrapers::atcoder::scrape_atcoder,
             pos::scrapers::codeforces::get_codeforces_user_stats,
             pos::scrapers::github::fetcher::scrape_github,
+            pos::scrapers::github::backfill::backfill_github_shadow_activities,
+            pos::scrapers::queue::get_scrape_queue_status,
+            pos::retry::get_sync_status,
             pos::github::get_github_repositories,
             pos::github::get_github_user_stats,
+            pos::github::get_github_language_stats,
             pos::github::fetch_github_repo_info,
             pos::config::get_pos_config,
+            pos::units::get_unit_registry,
+            pos::units::convert_unit_value,
+            pos::purge::purge_platform_data,
+            pos::purge::reset_table,
+            pos::purge::purge_deleted,
+            pos::api_tokens::create_api_token,
+            pos::api_tokens::revoke_api_token,
+            pos::api_tokens::list_api_tokens,
+            pos::api_tokens::get_api_audit_log,
             unified_goals::create_unified_goal,
             unified_goals::get_unified_goals,
             unified_goals::update_unified_goal,
             unified_goals::delete_unified_goal,
+            unified_goals::restore_unified_goal,
+            recurring_reconcile::reconcile_recurring_instances,
+            goal_metrics::batch_update_metrics,
+            goal_archive::archive_goal,
+            goal_archive::unarchive_goal,
+            goal_archive::get_archived_goals,
             // REMOVED: toggle_unified_goal_completion - goals only completable via activity linkage
             unified_goals::link_activity_to_unified_goal,
             daily_briefing::get_daily_briefing,
+            day_summary::generate_day_summary,
             briefing_monthly::get_monthly_briefing,
             briefing_yearly::get_yearly_briefing,
             knowledge_base::create_knowledge_item,
@@ -370,6 +627,8 @@ pub fn run() {
             knowledge_base::get_knowledge_links,
             knowledge_base::delete_knowledge_link,
             knowledge_base::check_knowledge_duplicates,
+            knowledge_base::get_due_reviews,
+            knowledge_base::submit_review,
             knowledge_base_commands::quick_save_link,
             knowledge_base_commands::get_backlinks,
             knowledge_base_commands::bulk_update_kb_status,
@@ -378,11 +637,14 @@ pub fn run() {
             knowledge_base_commands::backfill_activity_urls,
             milestones::create_milestone,
             milestones::get_milestones,
+            week_plan::generate_week_plan,
             milestones::update_milestone,
             milestones::run_balancer_engine,
             milestones::delete_milestone,
+            milestones::restore_milestone,
             milestones::increment_milestone_progress,
             milestones::set_milestone_progress_for_date,
+            milestones::recompute_milestone_progress,
             milestones::get_milestone_today_progress,
             milestones::get_milestone_with_daily_breakdown,
             milestones::get_milestone_progress_for_range,
@@ -392,6 +654,11 @@ pub fn run() {
             debt_system::get_debt_archive,
             debt_system::reset_debt_for_month,
             debt_system::get_completed_goals_for_date,
+            debt_stats::get_debt_resolution_stats,
+            debt_payoff::plan_debt_payoff,
+            effort_ledger::get_effort_balance,
+            effort_ledger::get_effort_rules,
+            effort_ledger::set_effort_rule,
             context_engine::get_context_for_goal,
             reflection::create_reflection,
             reflection::get_reflections,
@@ -399,21 +666,34 @@ pub fn run() {
             retrospectives::create_retrospective,
             retrospectives::get_retrospectives,
             retrospectives::get_retrospective_stats,
+            retrospectives::get_retrospective_prompts,
+            retrospectives::update_retrospective,
             retrospectives::delete_retrospective,
             // CF Ladder System
             cf_friends_system::add_cf_friend,
             cf_friends_system::get_cf_friends,
             cf_friends_system::sync_cf_friend_submissions,
             cf_friends_system::delete_cf_friend,
+            cf_friends_system::remove_cf_friend,
+            cf_friends_system::sync_friend_submissions,
+            cf_friends_system::sync_cf_friends,
             cf_friends_system::generate_friends_ladder,
+            cf_friends_system::generate_peer_ladder,
             cf_ladder_system::import_ladder_from_html,
             cf_ladder_system::get_ladders,
             cf_ladder_system::get_ladder_problems,
             cf_ladder_system::track_ladder_progress,
             cf_ladder_system::get_ladder_stats,
+            cf_ladder_system::get_all_ladder_stats,
+            cf_ladder_system::search_ladders,
             cf_ladder_system::get_ladder_by_id,
+            cf_ladder_system::delete_ladder,
             cf_ladder_system::update_ladder_problem,
             cf_ladder_system::bulk_add_problems,
+            cf_ladder_system::create_custom_ladder,
+            cf_ladder_system::build_timed_session,
+            cf_ladder_system::record_timed_session_outcome,
+            cf_ladder_system::get_timed_session,
             cf_ladder_system::get_categories,
             cf_ladder_system::get_category_by_id,
             cf_ladder_system::get_category_stats,
@@ -421,8 +701,74 @@ pub fn run() {
             cf_ladder_system::get_category_problems,
             cf_ladder_system::update_category_problem,
             cf_ladder_system::scan_and_import_public_data,
+            cf_ladder_system::fetch_problem_editorial_links,
+            cf_ladder_system::save_problem_note,
+            cf_ladder_system::get_problem_note,
+            cf_ladder_system::link_editorial,
+            cf_ladder_system::add_problem_hints,
+            cf_ladder_system::reveal_next_hint,
+            cf_ladder_system::get_revealed_hints,
+            cf_ladder_system::get_hint_usage,
+            cf_ladder_system::register_custom_strategy,
+            cf_ladder_system::get_custom_strategies,
+            cf_ladder_system::delete_custom_strategy,
             cf_recommendations::get_daily_recommendations,
+            cf_recommendation_history::generate_daily_recommendations,
+            cf_recommendation_history::get_recommendation_history,
+            cf_recommendation_history::mark_recommendation_done,
             date_summary::get_yearly_graph_data,
+            topic_timeline::get_topic_timeline,
+            seed::seed_demo_data,
+            dashboard::get_dashboard_snapshot,
+            dashboard::get_widget_summary,
+            goal_suggestions::suggest_goals_for_activity,
+            goal_suggestions::suggest_due_time,
+            capture_roles::get_capture_roles,
+            capture_roles::set_capture_roles,
+            captures::save_capture,
+            captures::get_captures,
+            captures::pair_captures,
+            captures::convert_capture_to_knowledge_item,
+            stuck_log::log_stuck,
+            drafts::save_draft,
+            drafts::get_draft,
+            drafts::discard_draft,
+            streak_freezes::get_streak_freeze_inventory,
+            streak_freezes::get_streak_freeze_history,
+            trends::get_trend_series,
+            year_heatmap::get_year_heatmap,
+            difficulty_progression::get_difficulty_progression,
+            label_effort::get_label_effort_matrix,
+            cross_platform_gaps::get_cross_platform_gaps,
+            cross_platform_gaps::get_tag_taxonomy,
+            cross_platform_gaps::set_tag_mapping,
+            problemset::refresh_problemset,
+            problemset::pick_random_problem,
+            palette::palette_search,
+            voice_memo::record_voice_memo,
+            repo_goals::suggest_repo_goals,
+            repo_goals::accept_repo_goal_suggestions,
+            day_plan::lock_day_plan,
+            day_plan::unlock_day_plan,
+            day_plan::get_day_plan_lock,
+            day_plan::get_plan_adherence,
+            reading_queue::enrich_knowledge_link,
+            reading_queue::get_reading_queue,
+            habit_stats::get_habit_stats,
+            snippets::get_snippets,
+            data_export::export_all_data,
+            goal_ordering::get_today_ordered_goals,
+            settings_transfer::export_settings,
+            settings_transfer::import_settings,
+            knowledge_problems::get_knowledge_for_problem,
+            problem_detail::get_problem_detail,
+            session_log::log_session,
+            scrape_scheduler::get_scrape_schedule,
+            known_solved::import_solved_problems,
+            offline_queue::record_activity,
+            offline_queue::set_goal_completed,
+            offline_queue::get_sync_queue_status,
+            offline_queue::replay_sync_queue,
             books::fetch_book_by_isbn,
             books::create_or_get_book,
             books::update_book,
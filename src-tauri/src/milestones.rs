@@ -1,9 +1,11 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use sqlx::{Postgres, QueryBuilder};
 use tauri::State;
 
 use crate::PosDb;
 use crate::pos::error::{PosError, PosResult, db_context};
+use crate::pos::units::normalize_unit;
 use crate::pos::utils::gen_id;
 
 // ─── Row types ──────────────────────────────────────────────────────
@@ -73,6 +75,10 @@ const MILESTONE_COLS: &str =
     "id, target_metric, target_value, daily_amount, period_type, period_start, period_end, \
      current_value, problem_id, unit, created_at, updated_at";
 
+// Goal metric rollup lives in its own file to keep this one under 600 lines
+mod rollup;
+pub(crate) use rollup::{goal_follows_milestone, milestones_for_goal, recompute_milestone, rollup_for_goal};
+
 // ─── Commands ───────────────────────────────────────────────────────
 
 #[tauri::command]
@@ -95,6 +101,10 @@ pub async fn create_milestone(
     if !["monthly", "weekly", "daily"].contains(&req.period_type.as_str()) {
         return Err(PosError::InvalidInput("period_type must be 'monthly', 'weekly', or 'daily'".into()));
     }
+    let unit = match req.unit.as_deref() {
+        Some(u) if !u.trim().is_empty() => Some(normalize_unit(u)?),
+        _ => None,
+    };
 
     let target_value = calculate_target_value(req.daily_amount, period_start, period_end);
 
@@ -109,12 +119,16 @@ pub async fn create_milestone(
     )
     .bind(&id).bind(&req.target_metric).bind(target_value).bind(req.daily_amount)
     .bind(&req.period_type).bind(period_start).bind(period_end)
-    .bind(&req.problem_id).bind(&req.unit).bind(now)
+    .bind(&req.problem_id).bind(&unit).bind(now)
     .fetch_one(pool).await
     .map_err(|e| db_context("create_milestone", e))?;
 
     log::info!("[MILESTONE] Created {} {} for {} (daily: {}, target: {})",
         req.period_type, id, req.target_metric, req.daily_amount, target_value);
+    // Effort-point milestones start from the points already earned in the period
+    if req.target_metric == crate::effort_ledger::EFFORT_METRIC {
+        return recompute_milestone(pool, &id).await;
+    }
     Ok(row)
 }
 
@@ -125,9 +139,9 @@ pub async fn get_milestones(
 ) -> PosResult<Vec<MilestoneRow>> {
     let pool = &db.0;
     let query = if active_only.unwrap_or(false) {
-        format!("SELECT {MILESTONE_COLS} FROM goal_periods WHERE period_end >= NOW() ORDER BY period_start DESC")
+        format!("SELECT {MILESTONE_COLS} FROM goal_periods WHERE deleted_at IS NULL AND period_end >= NOW() ORDER BY period_start DESC")
     } else {
-        format!("SELECT {MILESTONE_COLS} FROM goal_periods ORDER BY period_start DESC")
+        format!("SELECT {MILESTONE_COLS} FROM goal_periods WHERE deleted_at IS NULL ORDER BY period_start DESC")
     };
     sqlx::query_as::<_, MilestoneRow>(&query)
         .fetch_all(pool).await
@@ -143,24 +157,15 @@ pub async fn update_milestone(
     let pool = &db.0;
     let now = Utc::now();
 
-    let mut updates: Vec<String> = vec!["updated_at = $1".to_string()];
-    let bind_idx = 2;
-    if req.target_value.is_some() {
-        updates.push(format!("target_value = ${}", bind_idx));
+    let mut query = QueryBuilder::<Postgres>::new("UPDATE goal_periods SET updated_at = ");
+    query.push_bind(now);
+    if let Some(v) = req.target_value {
+        query.push(", target_value = ").push_bind(v);
     }
+    query.push(" WHERE id = ").push_bind(id.clone());
+    query.push(format!(" AND deleted_at IS NULL RETURNING {MILESTONE_COLS}"));
 
-    let query = format!(
-        "UPDATE goal_periods SET {} WHERE id = ${} RETURNING {MILESTONE_COLS}",
-        updates.join(", "),
-        bind_idx + 1
-    );
-
-    let mut q = sqlx::query_as::<_, MilestoneRow>(&query);
-    q = q.bind(now);
-    if let Some(v) = req.target_value { q = q.bind(v); }
-    q = q.bind(&id);
-
-    let row = q.fetch_one(pool).await
+    let row = query.build_query_as::<MilestoneRow>().fetch_one(pool).await
         .map_err(|e| db_context("update_milestone", e))?;
 
     log::info!("[MILESTONE] Updated {}", id);
@@ -178,7 +183,7 @@ pub async fn run_balancer_engine(
     let pool = &db.0;
 
     let milestone = sqlx::query_as::<_, MilestoneRow>(
-        &format!("SELECT {MILESTONE_COLS} FROM goal_periods WHERE id = $1")
+        &format!("SELECT {MILESTONE_COLS} FROM goal_periods WHERE id = $1 AND deleted_at IS NULL")
     )
     .bind(&milestone_id)
     .fetch_one(pool).await
@@ -203,7 +208,7 @@ pub async fn run_balancer_engine(
         });
     }
 
-    let offset_minutes = timezone_offset.unwrap_or(0);
+    let offset_minutes = timezone_offset.unwrap_or_else(crate::pos::timezone::js_offset_minutes);
     let now_local = Utc::now() + chrono::Duration::minutes(offset_minutes as i64);
     let today = now_local.date_naive();
     let end_date = (milestone.period_end + chrono::Duration::minutes(offset_minutes as i64)).date_naive();
@@ -232,21 +237,45 @@ pub async fn run_balancer_engine(
     })
 }
 
+/// Soft delete; daily progress is kept so restore_milestone brings it back intact.
 #[tauri::command]
 pub async fn delete_milestone(
     db: State<'_, PosDb>,
     id: String,
 ) -> PosResult<()> {
     let pool = &db.0;
-    sqlx::query("DELETE FROM goal_periods WHERE id = $1")
+    let deleted = sqlx::query("UPDATE goal_periods SET deleted_at = NOW() WHERE id = $1 AND deleted_at IS NULL")
         .bind(&id).execute(pool).await
-        .map_err(|e| db_context("delete_milestone", e))?;
+        .map_err(|e| db_context("delete_milestone", e))?
+        .rows_affected();
+    if deleted == 0 {
+        return Err(PosError::NotFound(format!("Milestone not found: {}", id)));
+    }
     log::info!("[MILESTONE] Deleted {}", id);
     Ok(())
 }
 
+#[tauri::command]
+pub async fn restore_milestone(
+    db: State<'_, PosDb>,
+    id: String,
+) -> PosResult<MilestoneRow> {
+    let pool = &db.0;
+    let row = sqlx::query_as::<_, MilestoneRow>(&format!(
+        "UPDATE goal_periods SET deleted_at = NULL, updated_at = NOW()
+         WHERE id = $1 AND deleted_at IS NOT NULL
+         RETURNING {MILESTONE_COLS}"
+    ))
+    .bind(&id)
+    .fetch_optional(pool).await
+    .map_err(|e| db_context("restore_milestone", e))?
+    .ok_or_else(|| PosError::NotFound(format!("Deleted milestone not found: {}", id)))?;
+    log::info!("[MILESTONE] Restored {}", id);
+    Ok(row)
+}
+
 /// Additive UPSERT into milestone_daily_progress for a specific date.
-/// Recomputes goal_periods.current_value = SUM(amount) after update.
+/// Recomputes goal_periods.current_value from its sources (see rollup) after update.
 #[tauri::command]
 pub async fn increment_milestone_progress(
     db: State<'_, PosDb>,
@@ -274,12 +303,11 @@ pub async fn increment_milestone_progress(
     let updated = sqlx::query_as::<_, MilestoneRow>(
         &format!(
             "UPDATE goal_periods
-             SET current_value = (
-                 SELECT COALESCE(SUM(amount), 0) FROM milestone_daily_progress WHERE milestone_id = $1
-             ),
+             SET current_value = {},
              updated_at = NOW()
              WHERE id = $1
-             RETURNING {MILESTONE_COLS}"
+             RETURNING {MILESTONE_COLS}",
+            rollup::current_value_sql("goal_rollup")
         )
     )
     .bind(&milestone_id)
@@ -292,7 +320,7 @@ pub async fn increment_milestone_progress(
 }
 
 /// Absolute SET for a specific date (edit path).
-/// Recomputes goal_periods.current_value = SUM(amount) after update.
+/// Recomputes goal_periods.current_value from its sources (see rollup) after update.
 #[tauri::command]
 pub async fn set_milestone_progress_for_date(
     db: State<'_, PosDb>,
@@ -320,12 +348,11 @@ pub async fn set_milestone_progress_for_date(
     let updated = sqlx::query_as::<_, MilestoneRow>(
         &format!(
             "UPDATE goal_periods
-             SET current_value = (
-                 SELECT COALESCE(SUM(amount), 0) FROM milestone_daily_progress WHERE milestone_id = $1
-             ),
+             SET current_value = {},
              updated_at = NOW()
              WHERE id = $1
-             RETURNING {MILESTONE_COLS}"
+             RETURNING {MILESTONE_COLS}",
+            rollup::current_value_sql("goal_rollup")
         )
     )
     .bind(&milestone_id)
@@ -337,6 +364,16 @@ pub async fn set_milestone_progress_for_date(
     Ok(updated)
 }
 
+/// Recompute a milestone's progress from its daily log and the metrics of the goals
+/// that follow it. Runs automatically whenever a child goal's metrics change.
+#[tauri::command]
+pub async fn recompute_milestone_progress(
+    db: State<'_, PosDb>,
+    milestone_id: String,
+) -> PosResult<MilestoneRow> {
+    recompute_milestone(&db.0, &milestone_id).await
+}
+
 /// Get today's progress for a milestone from milestone_daily_progress.
 /// Returns None when no row exists (distinguishable from an explicit 0).
 #[tauri::command]
@@ -374,7 +411,7 @@ pub async fn get_milestone_with_daily_breakdown(
     let pool = &db.0;
 
     let milestone = sqlx::query_as::<_, MilestoneRow>(
-        &format!("SELECT {MILESTONE_COLS} FROM goal_periods WHERE id = $1")
+        &format!("SELECT {MILESTONE_COLS} FROM goal_periods WHERE id = $1 AND deleted_at IS NULL")
     )
     .bind(&milestone_id)
     .fetch_one(pool).await
@@ -429,7 +466,7 @@ pub async fn get_milestone_progress_for_range(
                gp.daily_amount
            FROM milestone_daily_progress mdp
            JOIN goal_periods gp ON gp.id = mdp.milestone_id
-           WHERE mdp.date >= $1 AND mdp.date <= $2
+           WHERE mdp.date >= $1 AND mdp.date <= $2 AND gp.deleted_at IS NULL
            ORDER BY mdp.date ASC, gp.target_metric ASC"#,
     )
     .bind(&start_date)
@@ -440,3 +477,4 @@ pub async fn get_milestone_progress_for_range(
 
     Ok(rows)
 }
+
@@ -0,0 +1,233 @@
+// Milestone Goal Rollup
+// current_value = manual daily progress + metrics logged on linked activities + goal_rollup,
+// where goal_rollup sums the metric progress of the unified goals that follow the milestone
+// inside its period (or, for "effort_points" milestones, the effort ledger's points in the
+// period). Every writer recomputes from CURRENT_VALUE_SQL so no source overwrites another.
+
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+
+use crate::effort_ledger::{points_between, EFFORT_METRIC};
+use crate::pos::error::{PosError, PosResult, db_context};
+use crate::unified_goals::UnifiedGoalMetric;
+use super::{MilestoneRow, MILESTONE_COLS};
+
+// ─── Types ──────────────────────────────────────────────────────────
+
+#[derive(sqlx::FromRow)]
+struct RollupMilestone {
+    id: String,
+    target_metric: String,
+    problem_id: Option<String>,
+    label: Option<String>,
+    unit: Option<String>,
+    period_start: DateTime<Utc>,
+    period_end: DateTime<Utc>,
+}
+
+#[derive(sqlx::FromRow)]
+struct RollupGoal {
+    text: String,
+    date: Option<String>,
+    problem_id: Option<String>,
+    labels: Option<sqlx::types::Json<Vec<String>>>,
+    metrics: Option<sqlx::types::Json<Vec<UnifiedGoalMetric>>>,
+}
+
+const ROLLUP_MILESTONE_COLS: &str =
+    "id, target_metric, problem_id, label, unit, period_start, period_end";
+
+/// current_value of milestone $1 from its sources, plus `goal_rollup` (a column or bind).
+/// Activity metrics count per live activity, never below zero, as create_activity adds them.
+pub(crate) fn current_value_sql(goal_rollup: &str) -> String {
+    format!(
+        "(SELECT COALESCE(SUM(amount), 0) FROM milestone_daily_progress WHERE milestone_id = $1)
+         + (SELECT COALESCE(SUM(GREATEST(t.total, 0)), 0) FROM (
+               SELECT SUM(am.value) AS total
+               FROM pos_activities a JOIN pos_activity_metrics am ON am.activity_id = a.id
+               WHERE a.milestone_id = $1 AND a.deleted_at IS NULL
+               GROUP BY a.id
+           ) t)
+         + {goal_rollup}"
+    )
+}
+
+// ─── Helpers ────────────────────────────────────────────────────────
+
+/// A goal follows a milestone when it shares its problem, carries its label, or was
+/// generated from it (planned goals are titled "<metric>: …")
+pub(crate) fn goal_follows_milestone(
+    goal_text: &str,
+    goal_problem_id: Option<&str>,
+    goal_labels: &[String],
+    target_metric: &str,
+    problem_id: Option<&str>,
+    label: Option<&str>,
+) -> bool {
+    let same_problem = problem_id.is_some() && goal_problem_id == problem_id;
+    let same_label = label.is_some_and(|l| goal_labels.iter().any(|x| x.eq_ignore_ascii_case(l)));
+    let from_metric = goal_text.to_lowercase().starts_with(&format!("{}:", target_metric.to_lowercase()));
+    same_problem || same_label || from_metric
+}
+
+/// Progress a goal's metrics contribute: its only metric, or those named after the
+/// milestone metric or counted in the milestone's unit
+fn metric_contribution(metrics: &[UnifiedGoalMetric], target_metric: &str, unit: Option<&str>) -> f64 {
+    if let [only] = metrics {
+        return only.current;
+    }
+    metrics.iter()
+        .filter(|m| m.label.eq_ignore_ascii_case(target_metric) || unit.is_some_and(|u| m.unit == u))
+        .map(|m| m.current)
+        .sum()
+}
+
+fn period_dates(m: &RollupMilestone) -> (String, String) {
+    (
+        crate::pos::timezone::local_date_string(m.period_start),
+        crate::pos::timezone::local_date_string(m.period_end),
+    )
+}
+
+// ─── Rollup ─────────────────────────────────────────────────────────
+
+/// Summed metric progress of the goals following `m` between `start` and `end`
+async fn goal_rollup(pool: &PgPool, m: &RollupMilestone, start: &str, end: &str) -> PosResult<f64> {
+    let goals = sqlx::query_as::<_, RollupGoal>(
+        "SELECT text, date, problem_id, labels, metrics FROM unified_goals
+         WHERE deleted_at IS NULL AND date >= $1 AND date <= $2 AND jsonb_typeof(metrics) = 'array'"
+    )
+    .bind(start).bind(end)
+    .fetch_all(pool).await
+    .map_err(|e| db_context("fetch milestone child goals", e))?;
+
+    Ok(goals.iter()
+        .filter(|g| {
+            let labels = g.labels.as_ref().map(|l| l.0.as_slice()).unwrap_or_default();
+            goal_follows_milestone(
+                &g.text, g.problem_id.as_deref(), labels,
+                &m.target_metric, m.problem_id.as_deref(), m.label.as_deref(),
+            )
+        })
+        .filter_map(|g| g.metrics.as_ref())
+        .map(|metrics| metric_contribution(&metrics.0, &m.target_metric, m.unit.as_deref()))
+        .sum())
+}
+
+/// Recompute one milestone's goal rollup and current_value. Effort-point milestones
+/// take the effort ledger's points for the period instead of goal metrics.
+pub(crate) async fn recompute_milestone(pool: &PgPool, milestone_id: &str) -> PosResult<MilestoneRow> {
+    let m = sqlx::query_as::<_, RollupMilestone>(&format!(
+        "SELECT {ROLLUP_MILESTONE_COLS} FROM goal_periods WHERE id = $1 AND deleted_at IS NULL"
+    ))
+    .bind(milestone_id)
+    .fetch_optional(pool).await
+    .map_err(|e| db_context("fetch milestone for rollup", e))?
+    .ok_or_else(|| PosError::NotFound(format!("Milestone not found: {}", milestone_id)))?;
+
+    let (start, end) = period_dates(&m);
+    let rollup = if m.target_metric == EFFORT_METRIC {
+        points_between(pool, &start, &end).await?
+    } else {
+        goal_rollup(pool, &m, &start, &end).await?
+    };
+
+    let row = sqlx::query_as::<_, MilestoneRow>(&format!(
+        "UPDATE goal_periods
+         SET goal_rollup = $2,
+             current_value = {},
+             updated_at = NOW()
+         WHERE id = $1
+         RETURNING {MILESTONE_COLS}",
+        current_value_sql("$2")
+    ))
+    .bind(milestone_id)
+    .bind(rollup.round() as i32)
+    .fetch_one(pool).await
+    .map_err(|e| db_context("write milestone rollup", e))?;
+
+    log::info!("[MILESTONE] Rolled up {} from goals: {} (total now {})",
+        milestone_id, rollup.round(), row.current_value);
+    Ok(row)
+}
+
+/// Ids of the milestones `goal_id` follows inside their period. Taken before a goal is
+/// re-dated, relabelled or deleted so the milestones it leaves are recomputed too.
+pub(crate) async fn milestones_for_goal(pool: &PgPool, goal_id: &str) -> PosResult<Vec<String>> {
+    let Some(goal) = sqlx::query_as::<_, RollupGoal>(
+        "SELECT text, date, problem_id, labels, metrics FROM unified_goals WHERE id = $1"
+    )
+    .bind(goal_id)
+    .fetch_optional(pool).await
+    .map_err(|e| db_context("fetch goal for rollup", e))?
+    else {
+        return Ok(Vec::new());
+    };
+    let Some(date) = goal.date.as_deref() else {
+        return Ok(Vec::new());
+    };
+
+    let milestones = sqlx::query_as::<_, RollupMilestone>(&format!(
+        "SELECT {ROLLUP_MILESTONE_COLS} FROM goal_periods WHERE deleted_at IS NULL"
+    ))
+    .fetch_all(pool).await
+    .map_err(|e| db_context("fetch milestones for rollup", e))?;
+
+    let labels = goal.labels.as_ref().map(|l| l.0.as_slice()).unwrap_or_default();
+    Ok(milestones.into_iter()
+        .filter(|m| {
+            let (start, end) = period_dates(m);
+            goal_follows_milestone(
+                &goal.text, goal.problem_id.as_deref(), labels,
+                &m.target_metric, m.problem_id.as_deref(), m.label.as_deref(),
+            ) && start.as_str() <= date && date <= end.as_str()
+        })
+        .map(|m| m.id)
+        .collect())
+}
+
+/// Recompute every milestone `goal_id` follows now, plus those in `before` (from
+/// milestones_for_goal ahead of the change). Called after a goal's metrics, date,
+/// labels or deletion state change.
+pub(crate) async fn rollup_for_goal(pool: &PgPool, goal_id: &str, before: &[String]) -> PosResult<()> {
+    let mut ids = milestones_for_goal(pool, goal_id).await?;
+    ids.extend(before.iter().cloned());
+    ids.sort_unstable();
+    ids.dedup();
+    for id in ids {
+        // A milestone deleted since `before` was taken has nothing to recompute
+        if let Err(e) = recompute_milestone(pool, &id).await {
+            if !matches!(e, PosError::NotFound(_)) {
+                return Err(e);
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn metric(label: &str, unit: &str, current: f64) -> UnifiedGoalMetric {
+        UnifiedGoalMetric { id: label.to_string(), label: label.to_string(), target: 10.0, current, unit: unit.to_string() }
+    }
+
+    #[test]
+    fn test_goal_follows_milestone() {
+        let labels = vec!["DSA".to_string()];
+        assert!(goal_follows_milestone("Problems: 3", None, &[], "problems", None, None));
+        assert!(goal_follows_milestone("Grind", None, &labels, "problems", None, Some("dsa")));
+        assert!(goal_follows_milestone("Solve", Some("cf-1520A"), &[], "problems", Some("cf-1520A"), None));
+        assert!(!goal_follows_milestone("Read", None, &labels, "pages", None, Some("books")));
+    }
+
+    #[test]
+    fn test_metric_contribution() {
+        assert_eq!(metric_contribution(&[metric("anything", "count", 4.0)], "problems", None), 4.0);
+        let metrics = [metric("Problems", "count", 3.0), metric("Time", "minutes", 45.0), metric("Pages", "pages", 2.0)];
+        assert_eq!(metric_contribution(&metrics, "problems", None), 3.0);
+        assert_eq!(metric_contribution(&metrics, "reading", Some("minutes")), 45.0);
+        assert_eq!(metric_contribution(&[], "problems", None), 0.0);
+    }
+}
@@ -0,0 +1,300 @@
+// Notifications
+// Background checks for goals due within the hour, overdue goals (debt) and knowledge items
+// due for review. Each reminder is emitted as a `notification` event and shown as a native
+// desktop notification, at most once per key (logged in notification_log). Categories can
+// be muted and quiet hours set per category or for 'all' in notification_preferences.
+// Goals are date-only, so a goal counts as due at the end of its local day.
+
+use std::time::Duration;
+
+use chrono::{NaiveDateTime, NaiveTime, Utc};
+use serde::Serialize;
+use sqlx::PgPool;
+use tauri::{AppHandle, Emitter, State};
+use tauri_plugin_notification::NotificationExt;
+
+use crate::PosDb;
+use crate::pos::error::{PosError, PosResult, db_context};
+use crate::pos::timezone;
+
+const CHECK_INTERVAL_SECS: u64 = 300;
+const EVENT: &str = "notification";
+/// notification_log rows older than this are pruned
+const LOG_RETENTION_DAYS: i32 = 30;
+
+pub const CATEGORIES: [&str; 3] = ["goal_due", "debt_overdue", "kb_review"];
+/// Preference row that applies to every category
+const ALL: &str = "all";
+
+// ─── Types ──────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Reminder {
+    pub category: String,
+    /// Dedup key; one notification per key
+    pub key: String,
+    pub title: String,
+    pub body: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct NotificationPreference {
+    /// A category from CATEGORIES, or "all"
+    pub category: String,
+    pub muted: bool,
+    pub quiet_start: Option<NaiveTime>,
+    pub quiet_end: Option<NaiveTime>,
+}
+
+// ─── Helpers ────────────────────────────────────────────────────────
+
+/// True when `now` falls in [start, end), wrapping past midnight when start > end
+fn in_quiet_hours(now: NaiveTime, start: Option<NaiveTime>, end: Option<NaiveTime>) -> bool {
+    match (start, end) {
+        (Some(s), Some(e)) if s <= e => now >= s && now < e,
+        (Some(s), Some(e)) => now >= s || now < e,
+        _ => false,
+    }
+}
+
+fn parse_time(value: &str) -> PosResult<NaiveTime> {
+    NaiveTime::parse_from_str(value.trim(), "%H:%M")
+        .map_err(|_| PosError::InvalidInput(format!("Invalid time '{}', expected HH:MM", value)))
+}
+
+fn check_category(category: &str) -> PosResult<()> {
+    if category == ALL || CATEGORIES.contains(&category) {
+        Ok(())
+    } else {
+        Err(PosError::InvalidInput(format!(
+            "Unknown notification category '{}'. Use all, {}", category, CATEGORIES.join(", ")
+        )))
+    }
+}
+
+async fn load_preferences(pool: &PgPool) -> PosResult<Vec<NotificationPreference>> {
+    sqlx::query_as::<_, NotificationPreference>(
+        "SELECT category, muted, quiet_start, quiet_end FROM notification_preferences ORDER BY category"
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| db_context("load notification preferences", e))
+}
+
+/// Whether `category` may notify at `now` under `prefs` (the 'all' row applies too)
+fn allowed(prefs: &[NotificationPreference], category: &str, now: NaiveTime) -> bool {
+    prefs.iter()
+        .filter(|p| p.category == category || p.category == ALL)
+        .all(|p| !p.muted && !in_quiet_hours(now, p.quiet_start, p.quiet_end))
+}
+
+// ─── Checks ─────────────────────────────────────────────────────────
+
+async fn collect_reminders(pool: &PgPool, now: NaiveDateTime) -> PosResult<Vec<Reminder>> {
+    let today = now.date();
+    let today_str = today.format("%Y-%m-%d").to_string();
+    let end_of_day = (today + chrono::Duration::days(1)).and_time(NaiveTime::MIN);
+    let due_soon = end_of_day - now <= chrono::Duration::hours(1);
+
+    let (due_goals, overdue, kb_due) = tokio::try_join!(
+        sqlx::query_as::<_, (String, String)>(
+            r#"SELECT id, text FROM unified_goals
+               WHERE date = $1 AND $2 AND COALESCE(completed, FALSE) = FALSE
+                 AND deleted_at IS NULL AND archived_at IS NULL
+                 AND NOT (recurring_pattern IS NOT NULL AND recurring_template_id IS NULL)
+               ORDER BY text"#
+        )
+        .bind(&today_str)
+        .bind(due_soon)
+        .fetch_all(pool),
+        sqlx::query_scalar::<_, i64>(
+            r#"SELECT COUNT(*) FROM unified_goals
+               WHERE date < $1 AND COALESCE(completed, FALSE) = FALSE
+                 AND deleted_at IS NULL AND archived_at IS NULL
+                 AND NOT (recurring_pattern IS NOT NULL AND recurring_template_id IS NULL)"#
+        )
+        .bind(&today_str)
+        .fetch_one(pool),
+        sqlx::query_scalar::<_, i64>(
+            r#"SELECT COUNT(*) FROM knowledge_items
+               WHERE next_review_date <= NOW() AND status NOT IN ('Completed', 'Archived')"#
+        )
+        .fetch_one(pool),
+    )
+    .map_err(|e| db_context("collect reminders", e))?;
+
+    let mut reminders: Vec<Reminder> = due_goals.into_iter().map(|(id, text)| Reminder {
+        category: "goal_due".into(),
+        key: format!("goal_due:{}:{}", id, today_str),
+        title: "Goal due within the hour".into(),
+        body: text,
+    }).collect();
+    if overdue > 0 {
+        reminders.push(Reminder {
+            category: "debt_overdue".into(),
+            key: format!("debt_overdue:{}", today_str),
+            title: "Overdue goals".into(),
+            body: format!("{} goal{} past due", overdue, if overdue == 1 { " is" } else { "s are" }),
+        });
+    }
+    if kb_due > 0 {
+        reminders.push(Reminder {
+            category: "kb_review".into(),
+            key: format!("kb_review:{}", today_str),
+            title: "Knowledge review".into(),
+            body: format!("{} item{} due for review", kb_due, if kb_due == 1 { " is" } else { "s are" }),
+        });
+    }
+    Ok(reminders)
+}
+
+/// One pass: send every allowed reminder not sent before. Returns how many were sent.
+async fn run_checks(app: &AppHandle, pool: &PgPool) -> PosResult<usize> {
+    let now = timezone::local_datetime(Utc::now());
+    let prefs = load_preferences(pool).await?;
+    let mut sent = 0;
+
+    for reminder in collect_reminders(pool, now).await? {
+        // Held back, not dropped: it goes out once quiet hours end or the category is unmuted
+        if !allowed(&prefs, &reminder.category, now.time()) {
+            continue;
+        }
+        let fresh = sqlx::query(
+            "INSERT INTO notification_log (key, category, sent_at) VALUES ($1, $2, NOW()) ON CONFLICT (key) DO NOTHING"
+        )
+        .bind(&reminder.key)
+        .bind(&reminder.category)
+        .execute(pool)
+        .await
+        .map_err(|e| db_context("log notification", e))?
+        .rows_affected() > 0;
+        if !fresh {
+            continue;
+        }
+
+        let _ = app.emit(EVENT, &reminder);
+        if let Err(e) = app.notification().builder().title(&reminder.title).body(&reminder.body).show() {
+            log::warn!("[NOTIFY] Native notification failed: {}", e);
+        }
+        sent += 1;
+    }
+
+    sqlx::query("DELETE FROM notification_log WHERE sent_at < NOW() - make_interval(days => $1)")
+        .bind(LOG_RETENTION_DAYS)
+        .execute(pool)
+        .await
+        .map_err(|e| db_context("prune notification log", e))?;
+    Ok(sent)
+}
+
+/// Check every CHECK_INTERVAL_SECS for as long as the app runs
+pub fn start(app: AppHandle, pool: PgPool) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            match run_checks(&app, &pool).await {
+                Ok(0) => {}
+                Ok(n) => log::info!("[NOTIFY] Sent {} reminders", n),
+                Err(e) => log::warn!("[NOTIFY] Reminder check failed: {}", e),
+            }
+            tokio::time::sleep(Duration::from_secs(CHECK_INTERVAL_SECS)).await;
+        }
+    });
+}
+
+// ─── Commands ───────────────────────────────────────────────────────
+
+/// Stored preferences; categories without a row are unmuted with no quiet hours
+#[tauri::command]
+pub async fn get_notification_preferences(db: State<'_, PosDb>) -> PosResult<Vec<NotificationPreference>> {
+    let stored = load_preferences(&db.0).await?;
+    Ok(std::iter::once(ALL).chain(CATEGORIES).map(|category| {
+        stored.iter().find(|p| p.category == category).cloned().unwrap_or_else(|| NotificationPreference {
+            category: category.to_string(),
+            ..Default::default()
+        })
+    }).collect())
+}
+
+/// Mute or unmute a category ("all" mutes everything)
+#[tauri::command]
+pub async fn set_notification_muted(
+    db: State<'_, PosDb>,
+    category: String,
+    muted: bool,
+) -> PosResult<NotificationPreference> {
+    check_category(&category)?;
+    sqlx::query_as::<_, NotificationPreference>(
+        r#"INSERT INTO notification_preferences (category, muted, updated_at) VALUES ($1, $2, NOW())
+           ON CONFLICT (category) DO UPDATE SET muted = EXCLUDED.muted, updated_at = NOW()
+           RETURNING category, muted, quiet_start, quiet_end"#
+    )
+    .bind(&category)
+    .bind(muted)
+    .fetch_one(&db.0)
+    .await
+    .map_err(|e| db_context("set_notification_muted", e))
+}
+
+/// Set quiet hours (HH:MM, local; may wrap past midnight) for a category, default "all".
+/// Passing neither start nor end clears them.
+#[tauri::command]
+pub async fn set_quiet_hours(
+    db: State<'_, PosDb>,
+    start: Option<String>,
+    end: Option<String>,
+    category: Option<String>,
+) -> PosResult<NotificationPreference> {
+    let category = category.unwrap_or_else(|| ALL.to_string());
+    check_category(&category)?;
+    let (quiet_start, quiet_end) = match (start.as_deref(), end.as_deref()) {
+        (Some(s), Some(e)) => (Some(parse_time(s)?), Some(parse_time(e)?)),
+        (None, None) => (None, None),
+        _ => return Err(PosError::InvalidInput("Quiet hours need both start and end".into())),
+    };
+    if quiet_start.is_some() && quiet_start == quiet_end {
+        return Err(PosError::InvalidInput("Quiet hours start and end must differ".into()));
+    }
+
+    sqlx::query_as::<_, NotificationPreference>(
+        r#"INSERT INTO notification_preferences (category, quiet_start, quiet_end, updated_at) VALUES ($1, $2, $3, NOW())
+           ON CONFLICT (category) DO UPDATE SET quiet_start = EXCLUDED.quiet_start,
+               quiet_end = EXCLUDED.quiet_end, updated_at = NOW()
+           RETURNING category, muted, quiet_start, quiet_end"#
+    )
+    .bind(&category)
+    .bind(quiet_start)
+    .bind(quiet_end)
+    .fetch_one(&db.0)
+    .await
+    .map_err(|e| db_context("set_quiet_hours", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn t(s: &str) -> NaiveTime {
+        NaiveTime::parse_from_str(s, "%H:%M").unwrap()
+    }
+
+    #[test]
+    fn test_quiet_hours_wrap_midnight() {
+        let (s, e) = (Some(t("22:00")), Some(t("07:00")));
+        assert!(in_quiet_hours(t("23:30"), s, e));
+        assert!(in_quiet_hours(t("06:59"), s, e));
+        assert!(!in_quiet_hours(t("07:00"), s, e));
+        assert!(!in_quiet_hours(t("12:00"), s, e));
+        assert!(in_quiet_hours(t("13:00"), Some(t("12:00")), Some(t("14:00"))));
+        assert!(!in_quiet_hours(t("13:00"), None, None));
+    }
+
+    #[test]
+    fn test_allowed_respects_all_row() {
+        let pref = |c: &str, muted: bool| NotificationPreference { category: c.into(), muted, ..Default::default() };
+        assert!(allowed(&[pref("kb_review", true)], "goal_due", t("12:00")));
+        assert!(!allowed(&[pref("kb_review", true)], "kb_review", t("12:00")));
+        assert!(!allowed(&[pref("all", true)], "goal_due", t("12:00")));
+    }
+}
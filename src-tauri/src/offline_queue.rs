@@ -0,0 +1,324 @@
+// Offline Write Queue
+// When Postgres is unreachable, new activities and goal completion toggles go to a local
+// SQLite queue instead of failing. Once the pool connects the queue is replayed in order.
+// Queued activities carry an idempotency key, so a replay interrupted between the
+// Postgres commit and the queue delete never writes the same activity twice.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::sqlite::{SqliteConnectOptions, SqlitePool, SqlitePoolOptions};
+use sqlx::PgPool;
+use tauri::{AppHandle, Manager, State};
+
+use crate::{PosConfig, PosDb};
+use crate::pos::activities::{insert_activity, ActivityRow, CreateActivityRequest};
+use crate::pos::error::{PosError, PosResult, db_context};
+use crate::pos::idempotency::idempotent;
+use crate::pos::utils::gen_id;
+use crate::unified_goals::{UnifiedGoalRow, UNIFIED_GOAL_COLS};
+
+/// SQLite queue pool, managed for the whole session (independent of Postgres)
+pub struct OfflineQueue(pub SqlitePool);
+
+const QUEUE_FILE: &str = "offline_queue.db";
+/// Database errors after this many replays mark a write failed instead of blocking the queue
+const MAX_REPLAY_ATTEMPTS: i64 = 5;
+
+// ─── Types ──────────────────────────────────────────────────────────
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+enum QueuedWrite {
+    CreateActivity { req: CreateActivityRequest, idempotency_key: String },
+    SetGoalCompleted {
+        goal_id: String,
+        completed: bool,
+        #[serde(default)]
+        override_dependencies: bool,
+    },
+}
+
+impl QueuedWrite {
+    fn kind(&self) -> &'static str {
+        match self {
+            QueuedWrite::CreateActivity { .. } => "create_activity",
+            QueuedWrite::SetGoalCompleted { .. } => "set_goal_completed",
+        }
+    }
+}
+
+/// Result of a write that may have been queued. `result` is None while queued.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OfflineWrite<T> {
+    pub queued: bool,
+    pub queue_id: Option<i64>,
+    pub result: Option<T>,
+}
+
+#[derive(Debug, Serialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct QueueKindCount {
+    pub kind: String,
+    pub count: i64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncQueueStatus {
+    /// Postgres pool connected
+    pub online: bool,
+    pub pending: Vec<QueueKindCount>,
+    pub total_pending: i64,
+    /// Writes Postgres rejected on replay (kept for inspection, not retried)
+    pub failed: i64,
+    pub oldest_pending_at: Option<DateTime<Utc>>,
+    pub last_error: Option<String>,
+}
+
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplaySummary {
+    pub replayed: u64,
+    pub failed: u64,
+    pub remaining: i64,
+}
+
+// ─── Storage ────────────────────────────────────────────────────────
+
+/// Open (creating if needed) the queue database in the app data directory
+pub async fn open(app: &AppHandle) -> Result<SqlitePool, String> {
+    let dir = app.path().app_data_dir().map_err(|e| e.to_string())?;
+    std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+
+    let options = SqliteConnectOptions::new()
+        .filename(dir.join(QUEUE_FILE))
+        .create_if_missing(true);
+    let pool = SqlitePoolOptions::new()
+        .max_connections(2)
+        .connect_with(options)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    sqlx::query(
+        r#"CREATE TABLE IF NOT EXISTS pending_writes (
+               id          INTEGER PRIMARY KEY AUTOINCREMENT,
+               kind        TEXT NOT NULL,
+               payload     TEXT NOT NULL,
+               status      TEXT NOT NULL DEFAULT 'pending',
+               attempts    INTEGER NOT NULL DEFAULT 0,
+               last_error  TEXT,
+               created_at  TEXT NOT NULL
+           )"#
+    )
+    .execute(&pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    Ok(pool)
+}
+
+async fn enqueue(queue: &SqlitePool, write: &QueuedWrite) -> PosResult<i64> {
+    let payload = serde_json::to_string(write)
+        .map_err(|e| PosError::InvalidInput(format!("Cannot queue write: {}", e)))?;
+    let id = sqlx::query("INSERT INTO pending_writes (kind, payload, created_at) VALUES (?, ?, ?)")
+        .bind(write.kind())
+        .bind(payload)
+        .bind(Utc::now())
+        .execute(queue)
+        .await
+        .map_err(|e| db_context("enqueue offline write", e))?
+        .last_insert_rowid();
+    log::info!("[OFFLINE] Queued {} as #{}", write.kind(), id);
+    Ok(id)
+}
+
+fn split_midnight(app: &AppHandle) -> bool {
+    app.try_state::<PosConfig>().is_some_and(|c| c.0.split_activities_at_midnight)
+}
+
+// ─── Replay ─────────────────────────────────────────────────────────
+
+async fn set_goal_completed_pg(
+    pool: &PgPool,
+    goal_id: &str,
+    completed: bool,
+    override_dependencies: bool,
+    at: DateTime<Utc>,
+) -> PosResult<UnifiedGoalRow> {
+    if completed {
+        crate::goal_dependencies::ensure_unblocked(pool, goal_id, override_dependencies).await?;
+    }
+    let row = sqlx::query_as::<_, UnifiedGoalRow>(&format!(
+        r#"UPDATE unified_goals
+           SET completed = $1,
+               completed_at = CASE WHEN $1 THEN COALESCE(completed_at, $2) ELSE NULL END,
+               updated_at = $2
+           WHERE id = $3 AND deleted_at IS NULL
+           RETURNING {}"#, UNIFIED_GOAL_COLS
+    ))
+    .bind(completed)
+    .bind(at)
+    .bind(goal_id)
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| db_context("set_goal_completed", e))?
+    .ok_or_else(|| PosError::NotFound(format!("Goal not found: {}", goal_id)))?;
+
+    crate::dashboard::mark_snapshot_stale(pool).await;
+    if completed {
+        crate::debt_payoff::settle_catch_ups(pool, &[goal_id.to_string()]).await;
+    }
+    Ok(row)
+}
+
+async fn apply(pool: &PgPool, split: bool, write: QueuedWrite, queued_at: DateTime<Utc>) -> PosResult<()> {
+    match write {
+        QueuedWrite::CreateActivity { req, idempotency_key } => {
+            idempotent(pool, "create_activity", Some(idempotency_key), insert_activity(pool, split, req)).await?;
+        }
+        QueuedWrite::SetGoalCompleted { goal_id, completed, override_dependencies } => {
+            // The toggle happened when it was queued, not when it replays
+            set_goal_completed_pg(pool, &goal_id, completed, override_dependencies, queued_at).await?;
+        }
+    }
+    Ok(())
+}
+
+/// Replay pending writes oldest first. Rejected writes (bad input, missing goal) are marked
+/// failed and skipped; a database error stops the replay so the rest wait for the next one,
+/// until the same write has hit MAX_REPLAY_ATTEMPTS of them.
+pub async fn replay(pool: &PgPool, queue: &SqlitePool, split_midnight: bool) -> PosResult<ReplaySummary> {
+    let rows: Vec<(i64, String, i64, DateTime<Utc>)> = sqlx::query_as(
+        "SELECT id, payload, attempts, created_at FROM pending_writes WHERE status = 'pending' ORDER BY id"
+    )
+    .fetch_all(queue)
+    .await
+    .map_err(|e| db_context("load offline queue", e))?;
+
+    let mut summary = ReplaySummary::default();
+    for (id, payload, attempts, queued_at) in rows {
+        let outcome = match serde_json::from_str::<QueuedWrite>(&payload) {
+            Ok(write) => apply(pool, split_midnight, write, queued_at).await,
+            Err(e) => Err(PosError::InvalidInput(format!("Unreadable queued write: {}", e))),
+        };
+        match outcome {
+            Ok(()) => {
+                sqlx::query("DELETE FROM pending_writes WHERE id = ?")
+                    .bind(id)
+                    .execute(queue)
+                    .await
+                    .map_err(|e| db_context("dequeue offline write", e))?;
+                summary.replayed += 1;
+            }
+            Err(e) => {
+                let stop = matches!(e, PosError::Database(_)) && attempts + 1 < MAX_REPLAY_ATTEMPTS;
+                sqlx::query(
+                    "UPDATE pending_writes SET attempts = attempts + 1, last_error = ?, status = ? WHERE id = ?"
+                )
+                .bind(e.to_string())
+                .bind(if stop { "pending" } else { "failed" })
+                .bind(id)
+                .execute(queue)
+                .await
+                .map_err(|e| db_context("mark offline write", e))?;
+                if stop {
+                    log::warn!("[OFFLINE] Replay paused at #{}: {}", id, e);
+                    break;
+                }
+                log::warn!("[OFFLINE] Queued write #{} rejected: {}", id, e);
+                summary.failed += 1;
+            }
+        }
+    }
+
+    summary.remaining = sqlx::query_scalar("SELECT COUNT(*) FROM pending_writes WHERE status = 'pending'")
+        .fetch_one(queue)
+        .await
+        .map_err(|e| db_context("count offline queue", e))?;
+    log::info!("[OFFLINE] Replay: {} written, {} rejected, {} still pending",
+        summary.replayed, summary.failed, summary.remaining);
+    Ok(summary)
+}
+
+// ─── Commands ───────────────────────────────────────────────────────
+
+/// create_activity that falls back to the offline queue while Postgres is unavailable
+#[tauri::command]
+pub async fn record_activity(
+    app: AppHandle,
+    queue: State<'_, OfflineQueue>,
+    req: CreateActivityRequest,
+    idempotency_key: Option<String>,
+) -> PosResult<OfflineWrite<ActivityRow>> {
+    let split = split_midnight(&app);
+    if let Some(db) = app.try_state::<PosDb>() {
+        let row = idempotent(&db.0, "create_activity", idempotency_key, insert_activity(&db.0, split, req)).await?;
+        return Ok(OfflineWrite { queued: false, queue_id: None, result: Some(row) });
+    }
+    let write = QueuedWrite::CreateActivity { req, idempotency_key: idempotency_key.unwrap_or_else(gen_id) };
+    let id = enqueue(&queue.0, &write).await?;
+    Ok(OfflineWrite { queued: true, queue_id: Some(id), result: None })
+}
+
+/// Mark a goal completed or not, queued offline while Postgres is unavailable.
+/// Completing a goal with open dependencies needs `override_dependencies`.
+#[tauri::command]
+pub async fn set_goal_completed(
+    app: AppHandle,
+    queue: State<'_, OfflineQueue>,
+    goal_id: String,
+    completed: bool,
+    override_dependencies: Option<bool>,
+) -> PosResult<OfflineWrite<UnifiedGoalRow>> {
+    let override_dependencies = override_dependencies.unwrap_or(false);
+    if let Some(db) = app.try_state::<PosDb>() {
+        let row = set_goal_completed_pg(&db.0, &goal_id, completed, override_dependencies, Utc::now()).await?;
+        return Ok(OfflineWrite { queued: false, queue_id: None, result: Some(row) });
+    }
+    let write = QueuedWrite::SetGoalCompleted { goal_id, completed, override_dependencies };
+    let id = enqueue(&queue.0, &write).await?;
+    Ok(OfflineWrite { queued: true, queue_id: Some(id), result: None })
+}
+
+#[tauri::command]
+pub async fn get_sync_queue_status(
+    app: AppHandle,
+    queue: State<'_, OfflineQueue>,
+) -> PosResult<SyncQueueStatus> {
+    let pending = sqlx::query_as::<_, QueueKindCount>(
+        "SELECT kind, COUNT(*) AS count FROM pending_writes WHERE status = 'pending' GROUP BY kind ORDER BY kind"
+    )
+    .fetch_all(&queue.0)
+    .await
+    .map_err(|e| db_context("queue status counts", e))?;
+
+    let (failed, oldest_pending_at, last_error): (i64, Option<DateTime<Utc>>, Option<String>) = sqlx::query_as(
+        r#"SELECT (SELECT COUNT(*) FROM pending_writes WHERE status = 'failed'),
+                  (SELECT MIN(created_at) FROM pending_writes WHERE status = 'pending'),
+                  (SELECT last_error FROM pending_writes WHERE last_error IS NOT NULL ORDER BY id DESC LIMIT 1)"#
+    )
+    .fetch_one(&queue.0)
+    .await
+    .map_err(|e| db_context("queue status summary", e))?;
+
+    Ok(SyncQueueStatus {
+        online: app.try_state::<PosDb>().is_some(),
+        total_pending: pending.iter().map(|p| p.count).sum(),
+        pending,
+        failed,
+        oldest_pending_at,
+        last_error,
+    })
+}
+
+/// Replay now instead of waiting for the next reconnect
+#[tauri::command]
+pub async fn replay_sync_queue(
+    app: AppHandle,
+    queue: State<'_, OfflineQueue>,
+) -> PosResult<ReplaySummary> {
+    let db = app.try_state::<PosDb>()
+        .ok_or_else(|| PosError::External("Database is not connected".into()))?;
+    replay(&db.0, &queue.0, split_midnight(&app)).await
+}
@@ -0,0 +1,223 @@
+// Command Palette Search
+// Backend for the Ctrl+K palette: fuzzy-matches a query against built-in commands,
+// entities (goals, ladders, knowledge items) and recent activities, returning typed
+// actions the frontend can execute directly.
+
+use serde::Serialize;
+use tauri::State;
+
+use crate::PosDb;
+use crate::pos::error::{PosResult, db_context};
+
+const MAX_RESULTS: usize = 20;
+const MAX_RECENT: usize = 5;
+const RECENT_DAYS: i32 = 7;
+
+/// (title, extra keywords, action). Routes mirror App.tsx.
+const COMMANDS: &[(&str, &str, PaletteActionSpec)] = &[
+    ("Create goal", "new add todo", PaletteActionSpec::Navigate("/goals?new=1")),
+    ("Open goals", "unified", PaletteActionSpec::Navigate("/goals")),
+    ("Open milestones", "targets", PaletteActionSpec::Navigate("/milestones")),
+    ("Open knowledge base", "kb inbox", PaletteActionSpec::Navigate("/knowledge")),
+    ("Open briefing", "daily summary", PaletteActionSpec::Navigate("/briefing")),
+    ("Open retrospectives", "review weekly", PaletteActionSpec::Navigate("/retrospectives")),
+    ("Open journal", "diary", PaletteActionSpec::Navigate("/journal")),
+    ("Open calendar", "schedule", PaletteActionSpec::Navigate("/calendar")),
+    ("Open books", "reading", PaletteActionSpec::Navigate("/books")),
+    ("Open activity grid", "pos log", PaletteActionSpec::Navigate("/pos/grid")),
+    ("Open GitHub", "repos", PaletteActionSpec::Navigate("/pos/github")),
+    ("Open ladders", "cf codeforces a2oj", PaletteActionSpec::Navigate("/cf/ladders")),
+    ("Open categories", "cf codeforces", PaletteActionSpec::Navigate("/cf/categories")),
+    ("Open friends", "cf codeforces", PaletteActionSpec::Navigate("/cf/friends")),
+    ("Open daily problems", "cf recommendations", PaletteActionSpec::Navigate("/cf/daily")),
+    ("Open settings", "config preferences", PaletteActionSpec::Navigate("/settings")),
+    ("Start Codeforces scrape", "sync submissions cf", PaletteActionSpec::Invoke("scrape_codeforces")),
+    ("Start LeetCode scrape", "sync submissions lc", PaletteActionSpec::Invoke("scrape_leetcode")),
+    ("Start GitHub scrape", "sync commits", PaletteActionSpec::Invoke("scrape_github")),
+    ("Pick random problem", "roll surprise", PaletteActionSpec::Invoke("pick_random_problem")),
+];
+
+// ─── Types ──────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Copy)]
+enum PaletteActionSpec {
+    Navigate(&'static str),
+    Invoke(&'static str),
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum PaletteAction {
+    /// Route in the frontend router
+    Navigate { route: String },
+    /// Tauri command to invoke with no arguments
+    Invoke { command: String },
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PaletteItem {
+    pub kind: String, // command | goal | ladder | knowledge | recent
+    pub id: String,
+    pub title: String,
+    pub subtitle: Option<String>,
+    pub score: i64,
+    pub action: PaletteAction,
+}
+
+// ─── Fuzzy matching ─────────────────────────────────────────────────
+
+/// Subsequence match of `query` in `candidate` (case-insensitive). Rewards consecutive
+/// runs, word-start hits and an early first match; None if some query char is missing.
+fn fuzzy_score(query: &str, candidate: &str) -> Option<i64> {
+    let cand: Vec<char> = candidate.to_lowercase().chars().collect();
+    let mut score = 0i64;
+    let mut pos = 0usize;
+    let mut prev_match: Option<usize> = None;
+    let mut first_match = None;
+
+    for qc in query.to_lowercase().chars().filter(|c| !c.is_whitespace()) {
+        let idx = (pos..cand.len()).find(|&i| cand[i] == qc)?;
+        first_match.get_or_insert(idx);
+        score += 10;
+        if prev_match.is_some_and(|p| p + 1 == idx) {
+            score += 15;
+        }
+        if idx == 0 || !cand[idx - 1].is_alphanumeric() {
+            score += 20;
+        }
+        prev_match = Some(idx);
+        pos = idx + 1;
+    }
+
+    let first = first_match.unwrap_or(0) as i64;
+    // Shorter candidates and earlier matches rank higher
+    Some(score - first.min(20) - (cand.len() as i64 / 10))
+}
+
+fn first_line(s: &str, max: usize) -> String {
+    let line = s.lines().next().unwrap_or("").trim();
+    if line.chars().count() <= max {
+        line.to_string()
+    } else {
+        format!("{}…", line.chars().take(max).collect::<String>())
+    }
+}
+
+// ─── Commands ───────────────────────────────────────────────────────
+
+/// Fuzzy search across commands, open goals, ladders, knowledge items and recent
+/// activities. An empty query returns the commands plus recent activities.
+#[tauri::command]
+pub async fn palette_search(db: State<'_, PosDb>, query: String) -> PosResult<Vec<PaletteItem>> {
+    let pool = &db.0;
+    let query = query.trim();
+
+    let (goals, ladders, knowledge, recent) = tokio::try_join!(
+        sqlx::query_as::<_, (String, String, Option<String>)>(
+            r#"SELECT id, text, date FROM unified_goals
+               WHERE completed = FALSE AND archived_at IS NULL
+                 AND NOT (recurring_pattern IS NOT NULL AND recurring_template_id IS NULL)
+               ORDER BY date DESC NULLS LAST LIMIT 2000"#
+        ).fetch_all(pool),
+
+        sqlx::query_as::<_, (String, String, String)>(
+            "SELECT id, name, source FROM cf_ladders ORDER BY name"
+        ).fetch_all(pool),
+
+        sqlx::query_as::<_, (String, String, String)>(
+            r#"SELECT id, content, status FROM knowledge_items
+               WHERE status <> 'Archived'
+               ORDER BY updated_at DESC LIMIT 2000"#
+        ).fetch_all(pool),
+
+        sqlx::query_as::<_, (String, String, String)>(
+            r#"SELECT DISTINCT ON (title) id, title, date FROM pos_activities
+               WHERE is_shadow = FALSE AND deleted_at IS NULL
+                 AND start_time >= NOW() - make_interval(days => $1)
+               ORDER BY title, start_time DESC"#
+        ).bind(RECENT_DAYS).fetch_all(pool),
+    ).map_err(|e| db_context("palette_search", e))?;
+
+    let score = |text: &str| -> Option<i64> {
+        if query.is_empty() { Some(0) } else { fuzzy_score(query, text) }
+    };
+    let mut items = Vec::new();
+
+    for (i, (title, keywords, spec)) in COMMANDS.iter().enumerate() {
+        // Title matches beat keyword-only matches
+        let s = score(title).or_else(|| score(&format!("{} {}", title, keywords)).map(|s| s / 2));
+        if let Some(s) = s {
+            let action = match spec {
+                PaletteActionSpec::Navigate(route) => PaletteAction::Navigate { route: route.to_string() },
+                PaletteActionSpec::Invoke(cmd) => PaletteAction::Invoke { command: cmd.to_string() },
+            };
+            // Commands get a small boost so "open x" style queries surface them first
+            items.push(PaletteItem {
+                kind: "command".into(),
+                id: format!("cmd:{}", i),
+                title: title.to_string(),
+                subtitle: None,
+                score: s + 5,
+                action,
+            });
+        }
+    }
+
+    if !query.is_empty() {
+        for (id, text, date) in goals {
+            if let Some(s) = score(&text) {
+                items.push(PaletteItem {
+                    kind: "goal".into(),
+                    title: first_line(&text, 80),
+                    subtitle: date.map(|d| format!("Goal · due {}", d)),
+                    score: s,
+                    action: PaletteAction::Navigate { route: format!("/goals?focus={}", id) },
+                    id,
+                });
+            }
+        }
+        for (id, name, source) in ladders {
+            if let Some(s) = score(&name) {
+                items.push(PaletteItem {
+                    kind: "ladder".into(),
+                    title: format!("Open ladder {}", name),
+                    subtitle: Some(format!("Ladder · {}", source)),
+                    score: s,
+                    action: PaletteAction::Navigate { route: format!("/cf/ladders/{}", id) },
+                    id,
+                });
+            }
+        }
+        for (id, content, status) in knowledge {
+            if let Some(s) = score(&first_line(&content, 200)) {
+                items.push(PaletteItem {
+                    kind: "knowledge".into(),
+                    title: first_line(&content, 80),
+                    subtitle: Some(format!("Knowledge · {}", status)),
+                    score: s,
+                    action: PaletteAction::Navigate { route: format!("/knowledge?focus={}", id) },
+                    id,
+                });
+            }
+        }
+    }
+
+    let mut recent_items: Vec<PaletteItem> = recent.into_iter()
+        .filter_map(|(id, title, date)| score(&title).map(|s| PaletteItem {
+            kind: "recent".into(),
+            title,
+            subtitle: Some(format!("Activity · {}", date)),
+            // With no query, recent activity leads the list
+            score: if query.is_empty() { 10 } else { s },
+            action: PaletteAction::Navigate { route: format!("/pos/grid/{}", date) },
+            id,
+        }))
+        .collect();
+    recent_items.sort_by(|a, b| b.score.cmp(&a.score));
+    items.extend(recent_items.into_iter().take(MAX_RECENT));
+
+    items.sort_by(|a, b| b.score.cmp(&a.score).then_with(|| a.title.len().cmp(&b.title.len())));
+    items.truncate(MAX_RESULTS);
+    Ok(items)
+}
@@ -1,14 +1,23 @@
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
 use tauri::State;
 
 use crate::PosDb;
+use crate::pos::config::ActivityOverlapPolicy;
+use crate::streaks::{self, StreakKind};
 use super::error::{PosError, PosResult, db_context};
+use super::idempotency::idempotent;
 use super::utils::gen_id;
 
+// CSV import and overlap handling live in their own files to keep this one under 600 lines
+mod import;
+pub use import::*;
+mod overlap;
+pub use overlap::*;
+
 // ─── Row type ───────────────────────────────────────────────────────
 
-#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
 #[serde(rename_all = "camelCase")]
 pub struct ActivityRow {
     pub id: String,
@@ -26,11 +35,14 @@ pub struct ActivityRow {
     pub pages_read: Option<i32>,
     pub created_at: DateTime<Utc>,
     pub food_items: Option<Vec<String>>,
+    /// Row version for update_activity's expected_updated_at (None for queries that don't select it)
+    #[sqlx(default)]
+    pub updated_at: Option<DateTime<Utc>>,
 }
 
 // ─── Request/Response types ─────────────────────────────────────────
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct CreateActivityRequest {
     pub start_time: String,
@@ -48,7 +60,7 @@ pub struct CreateActivityRequest {
     pub food_items: Option<Vec<String>>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct MetricUpdate {
     pub metric_id: String,
@@ -76,7 +88,45 @@ pub struct DateRange {
 const SELECT_COLS: &str =
     "id, date, start_time, end_time, category, title, description,
      is_productive, is_shadow, goal_ids, milestone_id, book_id, pages_read, created_at,
-     food_items";
+     food_items, updated_at";
+
+// ─── Midnight splitting ──────────────────────────────────────────────
+
+/// Split [start, end) at each local midnight. Returns one (start, end) pair per
+/// calendar day touched; a same-day activity yields a single segment.
+fn split_at_midnight(start: DateTime<Utc>, end: DateTime<Utc>) -> Vec<(DateTime<Utc>, DateTime<Utc>)> {
+    let mut segments = Vec::new();
+    let mut cursor = start;
+    while cursor < end {
+        let next_midnight = super::timezone::next_midnight(cursor);
+        let seg_end = match next_midnight {
+            Some(m) if m < end => m,
+            _ => end,
+        };
+        segments.push((cursor, seg_end));
+        cursor = seg_end;
+    }
+    segments
+}
+
+/// Local calendar date (YYYY-MM-DD) of a UTC instant
+fn local_date(ts: DateTime<Utc>) -> String {
+    super::timezone::local_date_string(ts)
+}
+
+/// Parse and validate a request's start/end (RFC 3339, any offset)
+fn parse_bounds(req: &CreateActivityRequest) -> PosResult<(DateTime<Utc>, DateTime<Utc>)> {
+    let parse = |value: &str, field: &str| value.parse::<DateTime<chrono::FixedOffset>>()
+        .map(|d| d.with_timezone(&Utc))
+        .or_else(|_| value.parse::<DateTime<Utc>>())
+        .map_err(|e| PosError::InvalidInput(format!("Invalid {}: {}", field, e)));
+    let start = parse(&req.start_time, "start_time")?;
+    let end = parse(&req.end_time, "end_time")?;
+    if start >= end {
+        return Err(PosError::InvalidInput("end_time must be after start_time".into()));
+    }
+    Ok((start, end))
+}
 
 // ─── Commands ───────────────────────────────────────────────────────
 
@@ -89,7 +139,7 @@ pub async fn get_activities(
     let pool = &db.0;
 
     let sql = format!(
-        "SELECT {} FROM pos_activities WHERE date = $1 ORDER BY start_time ASC",
+        "SELECT {} FROM pos_activities WHERE date = $1 AND deleted_at IS NULL ORDER BY start_time ASC",
         SELECT_COLS
     );
     let rows = sqlx::query_as::<_, ActivityRow>(&sql)
@@ -113,27 +163,86 @@ pub async fn get_activities(
 }
 
 /// CREATE activity with optional metric updates + goal verification.
+/// A replayed `idempotency_key` returns the originally created activity.
 #[tauri::command]
 pub async fn create_activity(
     db: State<'_, PosDb>,
+    config: State<'_, crate::PosConfig>,
     req: CreateActivityRequest,
+    idempotency_key: Option<String>,
 ) -> PosResult<ActivityRow> {
-    let pool = &db.0;
+    let split = config.0.split_activities_at_midnight;
+    let policy = config.0.activity_overlap_policy;
+    idempotent(&db.0, "create_activity", idempotency_key, insert_activity_with_policy(&db.0, split, policy, req)).await
+}
 
-    let start: DateTime<Utc> = req.start_time.parse::<DateTime<chrono::FixedOffset>>()
-        .map(|d| d.with_timezone(&Utc))
-        .or_else(|_| req.start_time.parse::<DateTime<Utc>>())
-        .map_err(|e| PosError::InvalidInput(format!("Invalid start_time: {}", e)))?;
-    let end: DateTime<Utc> = req.end_time.parse::<DateTime<chrono::FixedOffset>>()
-        .map(|d| d.with_timezone(&Utc))
-        .or_else(|_| req.end_time.parse::<DateTime<Utc>>())
-        .map_err(|e| PosError::InvalidInput(format!("Invalid end_time: {}", e)))?;
+pub(crate) async fn insert_activity(
+    pool: &sqlx::PgPool,
+    split_midnight: bool,
+    req: CreateActivityRequest,
+) -> PosResult<ActivityRow> {
+    insert_activity_with_policy(pool, split_midnight, ActivityOverlapPolicy::Allow, req).await
+}
 
-    if start >= end {
-        return Err(PosError::InvalidInput("end_time must be after start_time".into()));
+/// insert_activity after resolving overlaps with real activities per `policy`
+pub(crate) async fn insert_activity_with_policy(
+    pool: &sqlx::PgPool,
+    split_midnight: bool,
+    policy: ActivityOverlapPolicy,
+    mut req: CreateActivityRequest,
+) -> PosResult<ActivityRow> {
+    let mut tx = pool.begin().await.map_err(|e| db_context("TX begin", e))?;
+    if policy != ActivityOverlapPolicy::Allow {
+        let (start, end) = parse_bounds(&req)?;
+        let (start, end) = resolve_overlaps(&mut tx, policy, start, end, None).await?;
+        req.start_time = start.to_rfc3339();
+        req.end_time = end.to_rfc3339();
     }
+    let (activity_id, segments) = insert_activity_tx(&mut tx, split_midnight, &req).await?;
+    tx.commit().await.map_err(|e| db_context("TX commit", e))?;
+
+    let activity = fetch_activity(pool, &activity_id).await?;
+    crate::dashboard::mark_snapshot_stale(pool).await;
+    note_activity_streak(pool, &activity, segments).await;
+    log::info!("[POS] Created activity {} (goals: {:?}, milestone: {:?}, segments: {})",
+        activity.id, req.goal_ids, req.milestone_id, segments);
+    Ok(activity)
+}
 
-    let date = req.date.unwrap_or_else(|| start.format("%Y-%m-%d").to_string());
+/// Extend the productive-activity streak; split activities span several days, so recompute
+pub(crate) async fn note_activity_streak(pool: &sqlx::PgPool, activity: &ActivityRow, segments: usize) {
+    if !activity.is_productive || activity.is_shadow {
+        return;
+    }
+    match NaiveDate::parse_from_str(&activity.date, "%Y-%m-%d") {
+        Ok(day) if segments == 1 => streaks::note_day(pool, StreakKind::Activity, day).await,
+        _ => streaks::invalidate(pool, StreakKind::Activity).await,
+    }
+}
+
+pub(crate) async fn fetch_activity(pool: &sqlx::PgPool, id: &str) -> PosResult<ActivityRow> {
+    let sql = format!("SELECT {} FROM pos_activities WHERE id = $1", SELECT_COLS);
+    sqlx::query_as::<_, ActivityRow>(&sql)
+        .bind(id)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| db_context("fetch created activity", e))
+}
+
+/// Activity insert + metric updates + goal verification inside the caller's transaction.
+/// Returns the id of the first segment and the number of segments written.
+pub(crate) async fn insert_activity_tx(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    split_midnight: bool,
+    req: &CreateActivityRequest,
+) -> PosResult<(String, usize)> {
+    let (start, end) = parse_bounds(req)?;
+
+    // A split activity's first segment always falls on its start's local day
+    let date = match req.date.clone() {
+        Some(d) if !split_midnight => d,
+        _ => local_date(start),
+    };
     let activity_id = gen_id();
     let is_productive = req.is_productive.unwrap_or(true);
 
@@ -141,30 +250,46 @@ pub async fn create_activity(
         return Err(PosError::InvalidInput("Cannot link to both goals and milestone".into()));
     }
 
-    let mut tx = pool.begin().await.map_err(|e| db_context("TX begin", e))?;
-
-    sqlx::query(
-        r#"INSERT INTO pos_activities
-           (id, date, start_time, end_time, category, title, description,
-            is_productive, is_shadow, goal_ids, milestone_id, book_id, pages_read, food_items)
-           VALUES ($1, $2, $3, $4, $5, $6, $7, $8, FALSE, $9, $10, $11, $12, $13)"#,
-    )
-    .bind(&activity_id)
-    .bind(&date)
-    .bind(start)
-    .bind(end)
-    .bind(&req.category)
-    .bind(&req.title)
-    .bind(&req.description)
-    .bind(is_productive)
-    .bind(&req.goal_ids)
-    .bind(&req.milestone_id)
-    .bind(&req.book_id)
-    .bind(req.pages_read)
-    .bind(&req.food_items)
-    .execute(&mut *tx)
-    .await
-    .map_err(|e| db_context("insert activity", e))?;
+    // With splitting on, the first segment keeps the id, date, metrics and pages;
+    // later segments are plain continuation rows dated to their own day, with
+    // split_from pointing at the first.
+    let segments = if split_midnight {
+        split_at_midnight(start, end)
+    } else {
+        vec![(start, end)]
+    };
+
+    for (i, (seg_start, seg_end)) in segments.iter().enumerate() {
+        let (seg_id, seg_date, pages, split_from) = if i == 0 {
+            (activity_id.clone(), date.clone(), req.pages_read, None)
+        } else {
+            (gen_id(), local_date(*seg_start), None, Some(&activity_id))
+        };
+
+        sqlx::query(
+            r#"INSERT INTO pos_activities
+               (id, date, start_time, end_time, category, title, description,
+                is_productive, is_shadow, goal_ids, milestone_id, book_id, pages_read, food_items, split_from)
+               VALUES ($1, $2, $3, $4, $5, $6, $7, $8, FALSE, $9, $10, $11, $12, $13, $14)"#,
+        )
+        .bind(&seg_id)
+        .bind(&seg_date)
+        .bind(seg_start)
+        .bind(seg_end)
+        .bind(&req.category)
+        .bind(&req.title)
+        .bind(&req.description)
+        .bind(is_productive)
+        .bind(&req.goal_ids)
+        .bind(&req.milestone_id)
+        .bind(&req.book_id)
+        .bind(pages)
+        .bind(&req.food_items)
+        .bind(split_from)
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| db_context("insert activity", e))?;
+    }
 
     if let Some(updates) = &req.updates {
         for u in updates {
@@ -174,18 +299,18 @@ pub async fn create_activity(
                 "INSERT INTO pos_activity_metrics (id, activity_id, goal_metric_id, value) VALUES ($1, $2, $3, $4)",
             )
             .bind(&am_id).bind(&activity_id).bind(&u.metric_id).bind(u.value)
-            .execute(&mut *tx).await.map_err(|e| db_context("insert activity_metric", e))?;
+            .execute(&mut **tx).await.map_err(|e| db_context("insert activity_metric", e))?;
 
             sqlx::query("UPDATE pos_goal_metrics SET current_value = current_value + $1 WHERE id = $2")
                 .bind(u.value).bind(&u.metric_id)
-                .execute(&mut *tx).await.map_err(|e| db_context("update goal_metric", e))?;
+                .execute(&mut **tx).await.map_err(|e| db_context("update goal_metric", e))?;
         }
     }
 
     if let Some(ref goal_ids) = req.goal_ids {
         for gid in goal_ids {
-            sqlx::query("UPDATE unified_goals SET verified = TRUE WHERE id = $1")
-                .bind(gid).execute(&mut *tx).await.map_err(|e| db_context("verify goal", e))?;
+            sqlx::query("UPDATE unified_goals SET verified = TRUE, updated_at = NOW() WHERE id = $1")
+                .bind(gid).execute(&mut **tx).await.map_err(|e| db_context("verify goal", e))?;
         }
     }
 
@@ -195,47 +320,26 @@ pub async fn create_activity(
             if total > 0 {
                 sqlx::query("UPDATE goal_periods SET current_value = current_value + $1 WHERE id = $2")
                     .bind(total).bind(milestone_id)
-                    .execute(&mut *tx).await.map_err(|e| db_context("increment milestone", e))?;
+                    .execute(&mut **tx).await.map_err(|e| db_context("increment milestone", e))?;
             }
         }
     }
 
-    tx.commit().await.map_err(|e| db_context("TX commit", e))?;
-
-    let sql = format!("SELECT {} FROM pos_activities WHERE id = $1", SELECT_COLS);
-    let activity = sqlx::query_as::<_, ActivityRow>(&sql)
-        .bind(&activity_id)
-        .fetch_one(pool)
-        .await
-        .map_err(|e| db_context("fetch created activity", e))?;
-
-    log::info!("[POS] Created activity {} (goals: {:?}, milestone: {:?})", activity.id, req.goal_ids, req.milestone_id);
-    Ok(activity)
+    Ok((activity_id, segments.len()))
 }
 
 /// UPDATE: Modify activity details and reconcile milestone current_value.
+/// With `expected_updated_at` a stale edit fails with a Conflict carrying the current row.
 #[tauri::command]
 pub async fn update_activity(
     db: State<'_, PosDb>,
+    config: State<'_, crate::PosConfig>,
     id: String,
     req: CreateActivityRequest,
+    expected_updated_at: Option<DateTime<Utc>>,
 ) -> PosResult<ActivityRow> {
     let pool = &db.0;
-
-    let start: DateTime<Utc> = req.start_time.parse::<DateTime<chrono::FixedOffset>>()
-        .map(|d| d.with_timezone(&Utc))
-        .or_else(|_| req.start_time.parse::<DateTime<Utc>>())
-        .map_err(|e| PosError::InvalidInput(format!("Invalid start_time: {}", e)))?;
-    let end: DateTime<Utc> = req.end_time.parse::<DateTime<chrono::FixedOffset>>()
-        .map(|d| d.with_timezone(&Utc))
-        .or_else(|_| req.end_time.parse::<DateTime<Utc>>())
-        .map_err(|e| PosError::InvalidInput(format!("Invalid end_time: {}", e)))?;
-
-    if start >= end {
-        return Err(PosError::InvalidInput("end_time must be after start_time".into()));
-    }
-
-    let date = req.date.unwrap_or_else(|| start.format("%Y-%m-%d").to_string());
+    let (start, end) = parse_bounds(&req)?;
     let is_productive = req.is_productive.unwrap_or(true);
 
     if req.goal_ids.is_some() && req.milestone_id.is_some() {
@@ -244,13 +348,34 @@ pub async fn update_activity(
 
     let mut tx = pool.begin().await.map_err(|e| db_context("TX begin", e))?;
 
-    let old: (Option<String>, Option<i32>) = sqlx::query_as(
+    let old: (Option<String>, Option<i32>, DateTime<Utc>, DateTime<Utc>, DateTime<Utc>) = sqlx::query_as(
         r#"SELECT a.milestone_id,
-                  (SELECT COALESCE(SUM(m.value), 0)::int FROM pos_activity_metrics m WHERE m.activity_id = a.id)
-           FROM pos_activities a WHERE a.id = $1"#,
+                  (SELECT COALESCE(SUM(m.value), 0)::int FROM pos_activity_metrics m WHERE m.activity_id = a.id),
+                  a.updated_at, a.start_time, a.end_time
+           FROM pos_activities a WHERE a.id = $1 AND a.deleted_at IS NULL
+           FOR UPDATE OF a"#,
     )
     .bind(&id).fetch_one(&mut *tx).await.map_err(|e| db_context("fetch old activity", e))?;
 
+    if expected_updated_at.is_some_and(|expected| expected != old.2) {
+        tx.rollback().await.map_err(|e| db_context("TX rollback", e))?;
+        let current = fetch_activity(pool, &id).await?;
+        log::info!("[POS] Rejected stale update of activity {} (expected {:?}, current {})",
+            id, expected_updated_at, old.2);
+        return Err(PosError::conflict("activity", &id, &current));
+    }
+
+    // Edits that keep the time span (title, links, metrics) don't re-check overlaps
+    let (start, end) = if (start, end) == (old.3, old.4) {
+        (start, end)
+    } else {
+        resolve_overlaps(&mut tx, config.0.activity_overlap_policy, start, end, Some(&id)).await?
+    };
+    let split_midnight = config.0.split_activities_at_midnight;
+    let date = match req.date {
+        Some(d) if !split_midnight => d,
+        _ => local_date(start),
+    };
     let old_milestone_id = old.0;
     let old_metric_sum = old.1.unwrap_or(0);
 
@@ -262,19 +387,48 @@ pub async fn update_activity(
         }
     }
 
+    let segments = if split_midnight {
+        split_at_midnight(start, end)
+    } else {
+        vec![(start, end)]
+    };
+    let (_, first_end) = segments[0];
+
     sqlx::query(
         r#"UPDATE pos_activities SET
            date = $1, start_time = $2, end_time = $3, category = $4,
            title = $5, description = $6, is_productive = $7, goal_ids = $8,
-           milestone_id = $9, book_id = $10, pages_read = $11, food_items = $12
+           milestone_id = $9, book_id = $10, pages_read = $11, food_items = $12,
+           updated_at = NOW()
            WHERE id = $13"#,
     )
-    .bind(&date).bind(start).bind(end).bind(&req.category)
+    .bind(&date).bind(start).bind(first_end).bind(&req.category)
     .bind(&req.title).bind(&req.description).bind(is_productive).bind(&req.goal_ids)
     .bind(&req.milestone_id).bind(&req.book_id).bind(&req.pages_read).bind(&req.food_items)
     .bind(&id)
     .execute(&mut *tx).await.map_err(|e| db_context("update activity", e))?;
 
+    // The edited row keeps the first day; earlier continuation rows are replaced by
+    // the segments of the new span
+    sqlx::query(&crate::delta_sync::with_tombstones(
+        "pos_activities", "DELETE FROM pos_activities WHERE split_from = $1"
+    ))
+    .bind(&id)
+    .execute(&mut *tx).await.map_err(|e| db_context("remove old split segments", e))?;
+    for (seg_start, seg_end) in segments.iter().skip(1) {
+        sqlx::query(
+            r#"INSERT INTO pos_activities
+               (id, date, start_time, end_time, category, title, description,
+                is_productive, is_shadow, goal_ids, milestone_id, book_id, pages_read, food_items, split_from)
+               VALUES ($1, $2, $3, $4, $5, $6, $7, $8, FALSE, $9, $10, $11, NULL, $12, $13)"#,
+        )
+        .bind(gen_id()).bind(local_date(*seg_start)).bind(seg_start).bind(seg_end)
+        .bind(&req.category).bind(&req.title).bind(&req.description).bind(is_productive)
+        .bind(&req.goal_ids).bind(&req.milestone_id).bind(&req.book_id).bind(&req.food_items)
+        .bind(&id)
+        .execute(&mut *tx).await.map_err(|e| db_context("insert split segment", e))?;
+    }
+
     if let Some(ref new_mid) = req.milestone_id {
         let new_total: i32 = req.updates.as_ref().map(|us| us.iter().map(|u| u.value).sum()).unwrap_or(0);
         if new_total > 0 {
@@ -290,6 +444,8 @@ pub async fn update_activity(
     let activity = sqlx::query_as::<_, ActivityRow>(&sql)
         .bind(&id).fetch_one(pool).await.map_err(|e| db_context("fetch updated activity", e))?;
 
+    crate::dashboard::mark_snapshot_stale(pool).await;
+    streaks::invalidate(pool, StreakKind::Activity).await;
     log::info!("[POS] Updated activity {} (old_milestone: {:?}, new_milestone: {:?})", id, old_milestone_id, req.milestone_id);
     Ok(activity)
 }
@@ -305,11 +461,11 @@ pub async fn patch_activity(
     let pool = &db.0;
     let mut tx = pool.begin().await.map_err(|e| db_context("TX begin", e))?;
 
-    sqlx::query("UPDATE pos_activities SET goal_ids = ARRAY[$1::TEXT] WHERE id = $2")
+    sqlx::query("UPDATE pos_activities SET goal_ids = ARRAY[$1::TEXT], updated_at = NOW() WHERE id = $2")
         .bind(&goal_id).bind(&id)
         .execute(&mut *tx).await.map_err(|e| db_context("patch activity", e))?;
 
-    sqlx::query("UPDATE unified_goals SET verified = TRUE WHERE id = $1")
+    sqlx::query("UPDATE unified_goals SET verified = TRUE, updated_at = NOW() WHERE id = $1")
         .bind(&goal_id)
         .execute(&mut *tx).await.map_err(|e| db_context("verify goal", e))?;
 
@@ -318,17 +474,91 @@ pub async fn patch_activity(
     let sql = format!("SELECT {} FROM pos_activities WHERE id = $1", SELECT_COLS);
     let activity = sqlx::query_as::<_, ActivityRow>(&sql)
         .bind(&id).fetch_one(pool).await.map_err(|e| db_context("fetch patched activity", e))?;
+    crate::dashboard::mark_snapshot_stale(pool).await;
 
     log::info!("[POS] Linked activity {} → goal {}", id, goal_id);
     Ok(activity)
 }
 
+/// Flip deleted_at and move the activity's metric total off (or back onto) its milestone.
+async fn set_activity_deleted(pool: &sqlx::PgPool, id: &str, deleted: bool) -> PosResult<()> {
+    let mut tx = pool.begin().await.map_err(|e| db_context("TX begin", e))?;
+    set_activity_deleted_tx(&mut tx, id, deleted).await?;
+    tx.commit().await.map_err(|e| db_context("TX commit", e))?;
+    crate::dashboard::mark_snapshot_stale(pool).await;
+    streaks::invalidate(pool, StreakKind::Activity).await;
+    Ok(())
+}
+
+pub(crate) async fn set_activity_deleted_tx(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    id: &str,
+    deleted: bool,
+) -> PosResult<()> {
+    set_row_deleted_tx(tx, id, deleted).await?;
+
+    // Continuation rows of a split activity go (and come back) with it
+    sqlx::query(
+        r#"UPDATE pos_activities SET deleted_at = CASE WHEN $1 THEN NOW() ELSE NULL END, updated_at = NOW()
+           WHERE split_from = $2 AND (deleted_at IS NULL) = $1"#
+    )
+    .bind(deleted).bind(id)
+    .execute(&mut **tx).await.map_err(|e| db_context("set split segments deleted", e))?;
+    Ok(())
+}
+
+/// Flip deleted_at on this one row only (its continuation rows are left alone)
+pub(crate) async fn set_row_deleted_tx(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    id: &str,
+    deleted: bool,
+) -> PosResult<()> {
+    let (milestone_id, metric_sum): (Option<String>, i32) = sqlx::query_as(
+        r#"UPDATE pos_activities a
+           SET deleted_at = CASE WHEN $1 THEN NOW() ELSE NULL END, updated_at = NOW()
+           WHERE a.id = $2 AND (a.deleted_at IS NULL) = $1
+           RETURNING a.milestone_id,
+                     (SELECT COALESCE(SUM(m.value), 0)::int FROM pos_activity_metrics m WHERE m.activity_id = a.id)"#,
+    )
+    .bind(deleted).bind(id)
+    .fetch_optional(&mut **tx).await.map_err(|e| db_context("set activity deleted", e))?
+    .ok_or_else(|| PosError::NotFound(format!(
+        "{} activity not found: {}", if deleted { "Active" } else { "Deleted" }, id
+    )))?;
+
+    if let Some(ref mid) = milestone_id {
+        if metric_sum > 0 {
+            let delta = if deleted { -metric_sum } else { metric_sum };
+            sqlx::query("UPDATE goal_periods SET current_value = GREATEST(0, current_value + $1) WHERE id = $2")
+                .bind(delta).bind(mid)
+                .execute(&mut **tx).await.map_err(|e| db_context("reconcile milestone", e))?;
+        }
+    }
+    Ok(())
+}
+
+/// DELETE (soft): hidden everywhere until restore_activity or purge_deleted.
+#[tauri::command]
+pub async fn delete_activity(db: State<'_, PosDb>, id: String) -> PosResult<()> {
+    set_activity_deleted(&db.0, &id, true).await?;
+    log::info!("[POS] Deleted activity {}", id);
+    Ok(())
+}
+
+/// Undo delete_activity, re-applying its metrics to the linked milestone.
+#[tauri::command]
+pub async fn restore_activity(db: State<'_, PosDb>, id: String) -> PosResult<ActivityRow> {
+    set_activity_deleted(&db.0, &id, false).await?;
+    log::info!("[POS] Restored activity {}", id);
+    fetch_activity(&db.0, &id).await
+}
+
 /// GET the min/max activity dates (for grid date range).
 #[tauri::command]
 pub async fn get_activity_range(db: State<'_, PosDb>) -> PosResult<DateRange> {
     let pool = &db.0;
     let row: (Option<String>, Option<String>) = sqlx::query_as(
-        "SELECT MIN(date), MAX(date) FROM pos_activities",
+        "SELECT MIN(date), MAX(date) FROM pos_activities WHERE deleted_at IS NULL",
     )
     .fetch_one(pool).await.map_err(|e| db_context("get activity range", e))?;
     Ok(DateRange { min_date: row.0, max_date: row.1 })
@@ -348,7 +578,7 @@ pub async fn get_activities_batch(
     }
 
     let sql = format!(
-        "SELECT {} FROM pos_activities WHERE date = ANY($1) ORDER BY date ASC, start_time ASC",
+        "SELECT {} FROM pos_activities WHERE date = ANY($1) AND deleted_at IS NULL ORDER BY date ASC, start_time ASC",
         SELECT_COLS
     );
     let rows = sqlx::query_as::<_, ActivityRow>(&sql)
@@ -381,7 +611,7 @@ pub async fn get_activities_batch(
 pub async fn get_food_activities(db: State<'_, PosDb>) -> PosResult<Vec<ActivityRow>> {
     let pool = &db.0;
     let sql = format!(
-        "SELECT {} FROM pos_activities WHERE category = 'food' ORDER BY date DESC, start_time DESC",
+        "SELECT {} FROM pos_activities WHERE category = 'food' AND deleted_at IS NULL ORDER BY date DESC, start_time DESC",
         SELECT_COLS
     );
     sqlx::query_as::<_, ActivityRow>(&sql)
@@ -393,7 +623,7 @@ pub async fn get_food_activities(db: State<'_, PosDb>) -> PosResult<Vec<Activity
 pub async fn get_project_activities(db: State<'_, PosDb>) -> PosResult<Vec<ActivityRow>> {
     let pool = &db.0;
     let sql = format!(
-        "SELECT {} FROM pos_activities WHERE category = 'development' ORDER BY date DESC, start_time DESC",
+        "SELECT {} FROM pos_activities WHERE category = 'development' AND deleted_at IS NULL ORDER BY date DESC, start_time DESC",
         SELECT_COLS
     );
     sqlx::query_as::<_, ActivityRow>(&sql)
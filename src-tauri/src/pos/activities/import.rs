@@ -0,0 +1,375 @@
+// Activity CSV import
+// Bulk-loads time-tracking exports (Toggl and similar) into pos_activities. Columns are
+// mapped by header name; Toggl's split "Start date" / "Start time" columns are detected
+// automatically. Rows are validated and checked for overlaps against each other and
+// against existing activities before a single all-or-nothing transaction. Rows without a
+// category are classified from their title; unsure guesses go to the review queue.
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, Utc};
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+use crate::{PosConfig, PosDb};
+use crate::category_inference::{self, CategoryInference, Classifier};
+use crate::streaks::{self, StreakKind};
+use super::super::error::{PosError, PosResult, db_context};
+use super::{insert_activity_tx, local_date, CreateActivityRequest};
+
+const MAX_IMPORT_ROWS: usize = 20_000;
+
+// ─── Types ──────────────────────────────────────────────────────────
+
+/// Header names for each field. Without a mapping, Toggl's export headers are used.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CsvColumnMapping {
+    /// Full start timestamp, or the start date when `start_time` is set
+    pub start: String,
+    pub start_time: Option<String>,
+    /// Full end timestamp, or the end date when `end_time` is set
+    pub end: String,
+    pub end_time: Option<String>,
+    pub description: Option<String>,
+    /// Source value (e.g. Toggl project) for the category; see `category_map`
+    pub category: Option<String>,
+    /// Source category value → POS category; unmapped values are lowercased
+    pub category_map: Option<HashMap<String, String>>,
+    /// Used when the category column is missing or empty; otherwise the title is classified
+    pub default_category: Option<String>,
+    /// Overrides both the default (true) and inferred productivity
+    pub is_productive: Option<bool>,
+}
+
+impl CsvColumnMapping {
+    fn toggl() -> Self {
+        Self {
+            start: "Start date".into(),
+            start_time: Some("Start time".into()),
+            end: "End date".into(),
+            end_time: Some("End time".into()),
+            description: Some("Description".into()),
+            category: Some("Project".into()),
+            category_map: None,
+            default_category: None,
+            is_productive: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportRowError {
+    /// 1-based line in the file (header is line 1)
+    pub line: usize,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportPreviewRow {
+    pub line: usize,
+    pub start_time: DateTime<Utc>,
+    pub end_time: DateTime<Utc>,
+    pub category: String,
+    pub title: String,
+    /// Set when the category was inferred from the title
+    pub inference: Option<CategoryInference>,
+    /// Line of another imported row, or id of an existing activity, this row overlaps
+    pub overlaps_with: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportReport {
+    pub dry_run: bool,
+    pub rows_parsed: usize,
+    pub rows_valid: usize,
+    pub rows_overlapping: usize,
+    /// Rows written (0 on a dry run)
+    pub rows_imported: usize,
+    pub total_minutes: i64,
+    pub errors: Vec<ImportRowError>,
+    pub rows: Vec<ImportPreviewRow>,
+}
+
+// ─── CSV parsing ────────────────────────────────────────────────────
+
+/// Minimal RFC 4180 reader: quoted fields, doubled quotes, embedded commas and newlines.
+/// Returns each record with the line it starts on; blank lines are skipped.
+fn parse_csv(content: &str) -> Vec<(usize, Vec<String>)> {
+    let mut records = Vec::new();
+    let mut record: Vec<String> = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut line = 1;
+    let mut record_line = 1;
+    let mut chars = content.trim_start_matches('\u{feff}').chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if in_quotes {
+            match c {
+                '"' if chars.peek() == Some(&'"') => {
+                    field.push('"');
+                    chars.next();
+                }
+                '"' => in_quotes = false,
+                '\n' => {
+                    line += 1;
+                    field.push(c);
+                }
+                _ => field.push(c),
+            }
+            continue;
+        }
+        match c {
+            '"' => in_quotes = true,
+            ',' => record.push(std::mem::take(&mut field)),
+            '\r' => {}
+            '\n' => {
+                record.push(std::mem::take(&mut field));
+                if record.iter().any(|f| !f.trim().is_empty()) {
+                    records.push((record_line, std::mem::take(&mut record)));
+                }
+                record.clear();
+                line += 1;
+                record_line = line;
+            }
+            _ => field.push(c),
+        }
+    }
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        if record.iter().any(|f| !f.trim().is_empty()) {
+            records.push((record_line, record));
+        }
+    }
+    records
+}
+
+/// Local wall-clock timestamp from one column or a date + time column pair
+fn parse_local_timestamp(date_or_full: &str, time: Option<&str>) -> Result<DateTime<Utc>, String> {
+    let value = match time {
+        Some(t) => format!("{} {}", date_or_full.trim(), t.trim()),
+        None => date_or_full.trim().to_string(),
+    };
+    if let Ok(dt) = DateTime::parse_from_rfc3339(&value) {
+        return Ok(dt.with_timezone(&Utc));
+    }
+    let naive = ["%Y-%m-%d %H:%M:%S", "%Y-%m-%d %H:%M", "%Y-%m-%dT%H:%M:%S", "%Y-%m-%dT%H:%M",
+                 "%m/%d/%Y %H:%M:%S", "%m/%d/%Y %H:%M"]
+        .iter()
+        .find_map(|f| NaiveDateTime::parse_from_str(&value, f).ok())
+        .or_else(|| {
+            // Date-only cells (no time column) start at local midnight
+            NaiveDate::parse_from_str(&value, "%Y-%m-%d").ok()
+                .map(|d| d.and_time(NaiveTime::MIN))
+        })
+        .ok_or_else(|| format!("Unrecognised timestamp '{}'", value))?;
+    crate::pos::timezone::from_local(naive)
+        .ok_or_else(|| format!("'{}' does not exist in the local timezone", value))
+}
+
+// ─── Validation ─────────────────────────────────────────────────────
+
+fn column(headers: &[String], name: &str) -> PosResult<usize> {
+    headers.iter()
+        .position(|h| h.trim().eq_ignore_ascii_case(name.trim()))
+        .ok_or_else(|| PosError::InvalidInput(format!("CSV has no '{}' column", name)))
+}
+
+/// Validated rows (without overlap info) and per-line errors
+fn build_rows(
+    content: &str,
+    mapping: &CsvColumnMapping,
+    classifier: &Classifier,
+) -> PosResult<(usize, Vec<ImportPreviewRow>, Vec<ImportRowError>)> {
+    let mut records = parse_csv(content).into_iter();
+    let (_, headers) = records.next()
+        .ok_or_else(|| PosError::InvalidInput("CSV is empty".into()))?;
+
+    let start_col = column(&headers, &mapping.start)?;
+    let start_time_col = mapping.start_time.as_deref().map(|c| column(&headers, c)).transpose()?;
+    let end_col = column(&headers, &mapping.end)?;
+    let end_time_col = mapping.end_time.as_deref().map(|c| column(&headers, c)).transpose()?;
+    let desc_col = mapping.description.as_deref().map(|c| column(&headers, c)).transpose()?;
+    let cat_col = mapping.category.as_deref().map(|c| column(&headers, c)).transpose()?;
+
+    let mut rows = Vec::new();
+    let mut errors = Vec::new();
+    let mut parsed = 0;
+    for (line, record) in records {
+        parsed += 1;
+        if parsed > MAX_IMPORT_ROWS {
+            return Err(PosError::InvalidInput(format!("CSV exceeds {} rows", MAX_IMPORT_ROWS)));
+        }
+        let cell = |i: usize| record.get(i).map(|s| s.trim()).unwrap_or("");
+
+        let start = parse_local_timestamp(cell(start_col), start_time_col.map(cell));
+        let end = parse_local_timestamp(cell(end_col), end_time_col.map(cell));
+        let (start, end) = match (start, end) {
+            (Ok(s), Ok(e)) if s < e => (s, e),
+            (Ok(_), Ok(_)) => {
+                errors.push(ImportRowError { line, message: "End must be after start".into() });
+                continue;
+            }
+            (Err(message), _) | (_, Err(message)) => {
+                errors.push(ImportRowError { line, message });
+                continue;
+            }
+        };
+
+        let source_category = cat_col.map(cell).unwrap_or("");
+        let description = desc_col.map(cell).unwrap_or("");
+        let (category, inference) = match (source_category, &mapping.default_category) {
+            ("", Some(default)) => (default.clone(), None),
+            ("", None) => {
+                let guess = classifier.classify(description);
+                (guess.category.clone(), Some(guess))
+            }
+            (source, _) => (
+                mapping.category_map.as_ref()
+                    .and_then(|m| m.get(source).cloned())
+                    .unwrap_or_else(|| source.to_lowercase()),
+                None,
+            ),
+        };
+        let title = if description.is_empty() { category.clone() } else { description.to_string() };
+
+        rows.push(ImportPreviewRow {
+            line, start_time: start, end_time: end, category, title, inference, overlaps_with: None,
+        });
+    }
+    Ok((parsed, rows, errors))
+}
+
+/// Flag rows overlapping an earlier imported row or an existing activity.
+/// `existing` holds (id, start, end) of stored activities.
+fn mark_overlaps(rows: &mut [ImportPreviewRow], existing: &[(String, DateTime<Utc>, DateTime<Utc>)]) {
+    rows.sort_by_key(|r| r.start_time);
+    let mut latest: Option<(usize, DateTime<Utc>)> = None;
+    for row in rows.iter_mut() {
+        row.overlaps_with = match latest {
+            Some((line, end)) if row.start_time < end => Some(format!("line {}", line)),
+            _ => existing.iter()
+                .find(|(_, s, e)| row.start_time < *e && *s < row.end_time)
+                .map(|(id, _, _)| id.clone()),
+        };
+        if latest.map_or(true, |(_, end)| row.end_time > end) {
+            latest = Some((row.line, row.end_time));
+        }
+    }
+}
+
+// ─── Command ────────────────────────────────────────────────────────
+
+/// Import activities from CSV `content`. With `dry_run` nothing is written and the report
+/// previews every row. A real import is refused while any row is invalid or overlapping
+/// unless `skip_conflicts` is set, in which case only clean rows are inserted.
+#[tauri::command]
+pub async fn import_activities_csv(
+    db: State<'_, PosDb>,
+    config: State<'_, PosConfig>,
+    content: String,
+    mapping: Option<CsvColumnMapping>,
+    dry_run: bool,
+    skip_conflicts: Option<bool>,
+) -> PosResult<ImportReport> {
+    let pool = &db.0;
+    let mapping = mapping.unwrap_or_else(CsvColumnMapping::toggl);
+    let classifier = Classifier::load(pool).await?;
+    let (rows_parsed, mut rows, errors) = build_rows(&content, &mapping, &classifier)?;
+
+    let existing: Vec<(String, DateTime<Utc>, DateTime<Utc>)> = match (
+        rows.iter().map(|r| r.start_time).min(),
+        rows.iter().map(|r| r.end_time).max(),
+    ) {
+        (Some(from), Some(to)) => sqlx::query_as(
+            r#"SELECT id, start_time, end_time FROM pos_activities
+               WHERE is_shadow = FALSE AND deleted_at IS NULL AND start_time < $2 AND end_time > $1
+               ORDER BY start_time"#
+        )
+        .bind(from)
+        .bind(to)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| db_context("load overlapping activities", e))?,
+        _ => Vec::new(),
+    };
+    mark_overlaps(&mut rows, &existing);
+
+    let rows_overlapping = rows.iter().filter(|r| r.overlaps_with.is_some()).count();
+    let mut report = ImportReport {
+        dry_run,
+        rows_parsed,
+        rows_valid: rows.len(),
+        rows_overlapping,
+        rows_imported: 0,
+        total_minutes: rows.iter().map(|r| (r.end_time - r.start_time).num_minutes()).sum(),
+        errors,
+        rows,
+    };
+    if dry_run {
+        return Ok(report);
+    }
+    if !skip_conflicts.unwrap_or(false) && (!report.errors.is_empty() || rows_overlapping > 0) {
+        return Err(PosError::InvalidInput(format!(
+            "{} invalid and {} overlapping rows; fix them or import with skipConflicts",
+            report.errors.len(), rows_overlapping
+        )));
+    }
+
+    let split = config.0.split_activities_at_midnight;
+    let mut tx = pool.begin().await.map_err(|e| db_context("TX begin", e))?;
+    for row in report.rows.iter().filter(|r| r.overlaps_with.is_none()) {
+        let req = CreateActivityRequest {
+            start_time: row.start_time.to_rfc3339(),
+            end_time: row.end_time.to_rfc3339(),
+            category: row.category.clone(),
+            title: row.title.clone(),
+            description: String::new(),
+            is_productive: Some(mapping.is_productive
+                .or(row.inference.as_ref().map(|i| i.is_productive))
+                .unwrap_or(true)),
+            goal_ids: None,
+            milestone_id: None,
+            book_id: None,
+            pages_read: None,
+            updates: None,
+            date: Some(local_date(row.start_time)),
+            food_items: None,
+        };
+        let (activity_id, _) = insert_activity_tx(&mut tx, split, &req).await
+            .map_err(|e| PosError::InvalidInput(format!("Line {}: {}", row.line, e)))?;
+        if let Some(inference) = &row.inference {
+            category_inference::queue_for_review(&mut *tx, &activity_id, inference, "csv_import").await?;
+        }
+        report.rows_imported += 1;
+    }
+    tx.commit().await.map_err(|e| db_context("TX commit", e))?;
+
+    if report.rows_imported > 0 {
+        crate::dashboard::mark_snapshot_stale(pool).await;
+        streaks::invalidate(pool, StreakKind::Activity).await;
+    }
+    log::info!("[POS] Imported {} of {} CSV activities ({} invalid, {} overlapping)",
+        report.rows_imported, rows_parsed, report.errors.len(), rows_overlapping);
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_csv_quoting() {
+        let csv = "\u{feff}Description,Project\r\n\"Write, test\",Work\n\n\"Say \"\"hi\"\"\nagain\",\nlast,x";
+        let recs = parse_csv(csv);
+        assert_eq!(recs.len(), 4);
+        assert_eq!(recs[0], (1, vec!["Description".to_string(), "Project".to_string()]));
+        assert_eq!(recs[1].1, vec!["Write, test", "Work"]);
+        assert_eq!(recs[2], (4, vec!["Say \"hi\"\nagain".to_string(), String::new()]));
+        assert_eq!(recs[3], (6, vec!["last".to_string(), "x".to_string()]));
+    }
+}
@@ -0,0 +1,220 @@
+// Activity overlap handling
+// Overlapping real activities count the same minutes twice in total_minutes.
+// create_activity and update_activity resolve overlaps per ACTIVITY_OVERLAP_POLICY:
+// reject the write, truncate the new block to its longest uncovered stretch, or split the
+// existing blocks around it. Shadow activities are ignored here (they have their own
+// collision policy). find_overlapping_activities audits what's already stored.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use tauri::State;
+
+use crate::PosDb;
+use crate::pos::config::ActivityOverlapPolicy;
+use super::super::error::{PosError, PosResult, db_context};
+use super::super::timezone;
+use super::super::utils::gen_id;
+use super::set_row_deleted_tx;
+
+type Span = (DateTime<Utc>, DateTime<Utc>);
+
+// ─── Types ──────────────────────────────────────────────────────────
+
+#[derive(Debug, Serialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct ActivityOverlap {
+    pub date: String,
+    /// The activity that starts first
+    pub first_id: String,
+    pub first_title: String,
+    pub first_start: DateTime<Utc>,
+    pub first_end: DateTime<Utc>,
+    pub second_id: String,
+    pub second_title: String,
+    pub second_start: DateTime<Utc>,
+    pub second_end: DateTime<Utc>,
+    pub overlap_minutes: f64,
+}
+
+#[derive(sqlx::FromRow)]
+struct Overlapping {
+    id: String,
+    title: String,
+    start_time: DateTime<Utc>,
+    end_time: DateTime<Utc>,
+}
+
+// ─── Helpers ────────────────────────────────────────────────────────
+
+/// Stretches of `span` not covered by any of `busy`, in order
+fn uncovered(span: Span, busy: &[Span]) -> Vec<Span> {
+    let mut busy: Vec<Span> = busy.iter().copied().filter(|(s, e)| *s < span.1 && *e > span.0).collect();
+    busy.sort();
+    let mut free = Vec::new();
+    let mut cursor = span.0;
+    for (s, e) in busy {
+        if s > cursor {
+            free.push((cursor, s));
+        }
+        cursor = cursor.max(e);
+    }
+    if cursor < span.1 {
+        free.push((cursor, span.1));
+    }
+    free
+}
+
+/// How an existing block is cut around a new one: `None` when it's fully covered, else the
+/// stretch the row keeps and the remainders after the new block, each with its own date
+fn split_around(existing: Span, new: Span, date_of: impl Fn(DateTime<Utc>) -> String) -> Option<(Span, Vec<(Span, String)>)> {
+    let mut pieces = uncovered(existing, &[new]).into_iter();
+    let keep = pieces.next()?;
+    Some((keep, pieces.map(|p| (p, date_of(p.0))).collect()))
+}
+
+/// Apply `policy` to the real activities overlapping `[start, end)` (other than
+/// `exclude_id`, the row being edited) inside the caller's transaction. Returns the bounds
+/// to store the new/edited activity with.
+pub(crate) async fn resolve_overlaps(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    policy: ActivityOverlapPolicy,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    exclude_id: Option<&str>,
+) -> PosResult<Span> {
+    if policy == ActivityOverlapPolicy::Allow {
+        return Ok((start, end));
+    }
+    let overlapping = sqlx::query_as::<_, Overlapping>(
+        r#"SELECT id, title, start_time, end_time FROM pos_activities
+           WHERE is_shadow = FALSE AND deleted_at IS NULL AND start_time < $2 AND end_time > $1
+             AND ($3::text IS NULL OR id <> $3)
+           ORDER BY start_time
+           FOR UPDATE"#
+    )
+    .bind(start)
+    .bind(end)
+    .bind(exclude_id)
+    .fetch_all(&mut **tx)
+    .await
+    .map_err(|e| db_context("load overlapping activities", e))?;
+    if overlapping.is_empty() {
+        return Ok((start, end));
+    }
+
+    match policy {
+        ActivityOverlapPolicy::Reject | ActivityOverlapPolicy::Allow => {
+            let names: Vec<String> = overlapping.iter().map(|a| format!("'{}' ({})", a.title, a.id)).collect();
+            Err(PosError::InvalidInput(format!("Activity overlaps {}", names.join(", "))))
+        }
+        ActivityOverlapPolicy::Truncate => {
+            let busy: Vec<Span> = overlapping.iter().map(|a| (a.start_time, a.end_time)).collect();
+            let longest = uncovered((start, end), &busy).into_iter()
+                .max_by_key(|(s, e)| (*e - *s, std::cmp::Reverse(*s)))
+                .ok_or_else(|| PosError::InvalidInput(format!(
+                    "Activity is fully covered by '{}' ({})", overlapping[0].title, overlapping[0].id
+                )))?;
+            log::info!("[POS] Truncated new activity {}–{} to {}–{} around {} overlapping",
+                start, end, longest.0, longest.1, overlapping.len());
+            Ok(longest)
+        }
+        ActivityOverlapPolicy::Split => {
+            for a in &overlapping {
+                // A fully covered row goes alone; its continuation rows on other days don't overlap
+                let Some((keep, rest)) = split_around((a.start_time, a.end_time), (start, end), timezone::local_date_string) else {
+                    set_row_deleted_tx(tx, &a.id, true).await?;
+                    continue;
+                };
+                // The part after the new block becomes a continuation row, like a midnight split
+                for ((s, e), date) in rest {
+                    sqlx::query(
+                        r#"INSERT INTO pos_activities
+                           (id, date, start_time, end_time, category, title, description,
+                            is_productive, is_shadow, goal_ids, milestone_id, book_id, pages_read, food_items, split_from)
+                           SELECT $1, $2, $3, $4, category, title, description,
+                                  is_productive, FALSE, goal_ids, milestone_id, book_id, NULL, food_items, id
+                           FROM pos_activities WHERE id = $5"#
+                    )
+                    .bind(gen_id()).bind(&date).bind(s).bind(e).bind(&a.id)
+                    .execute(&mut **tx).await.map_err(|e| db_context("insert split remainder", e))?;
+                }
+                sqlx::query("UPDATE pos_activities SET start_time = $1, end_time = $2, updated_at = NOW() WHERE id = $3")
+                    .bind(keep.0).bind(keep.1).bind(&a.id)
+                    .execute(&mut **tx).await.map_err(|e| db_context("trim overlapped activity", e))?;
+            }
+            log::info!("[POS] Split {} existing activities around new block {}–{}", overlapping.len(), start, end);
+            Ok((start, end))
+        }
+    }
+}
+
+// ─── Commands ───────────────────────────────────────────────────────
+
+/// Pairs of real activities whose time ranges overlap, optionally between two dates
+/// (YYYY-MM-DD, inclusive)
+#[tauri::command]
+pub async fn find_overlapping_activities(
+    db: State<'_, PosDb>,
+    start_date: Option<String>,
+    end_date: Option<String>,
+) -> PosResult<Vec<ActivityOverlap>> {
+    sqlx::query_as::<_, ActivityOverlap>(
+        r#"SELECT a.date,
+                  a.id AS first_id, a.title AS first_title, a.start_time AS first_start, a.end_time AS first_end,
+                  b.id AS second_id, b.title AS second_title, b.start_time AS second_start, b.end_time AS second_end,
+                  (EXTRACT(EPOCH FROM (LEAST(a.end_time, b.end_time) - GREATEST(a.start_time, b.start_time))) / 60)::float8
+                      AS overlap_minutes
+           FROM pos_activities a
+           JOIN pos_activities b
+             ON (a.start_time, a.id) < (b.start_time, b.id)
+            AND b.start_time < a.end_time AND a.start_time < b.end_time
+           WHERE a.is_shadow = FALSE AND a.deleted_at IS NULL
+             AND b.is_shadow = FALSE AND b.deleted_at IS NULL
+             AND ($1::text IS NULL OR a.date >= $1)
+             AND ($2::text IS NULL OR a.date <= $2)
+           ORDER BY a.start_time, b.start_time"#
+    )
+    .bind(&start_date)
+    .bind(&end_date)
+    .fetch_all(&db.0)
+    .await
+    .map_err(|e| db_context("find_overlapping_activities", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn t(h: u32, m: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 3, 2, h, m, 0).unwrap()
+    }
+
+    #[test]
+    fn test_uncovered_stretches() {
+        let busy = [(t(10, 30), t(11, 0)), (t(9, 0), t(9, 45)), (t(10, 45), t(11, 15))];
+        assert_eq!(uncovered((t(9, 30), t(12, 0)), &busy), vec![(t(9, 45), t(10, 30)), (t(11, 15), t(12, 0))]);
+        // Splitting an existing block around a new one inside it leaves both ends
+        assert_eq!(uncovered((t(9, 0), t(12, 0)), &[(t(10, 0), t(11, 0))]), vec![(t(9, 0), t(10, 0)), (t(11, 0), t(12, 0))]);
+        assert!(uncovered((t(10, 0), t(11, 0)), &[(t(9, 0), t(12, 0))]).is_empty());
+    }
+
+    fn utc_date(ts: DateTime<Utc>) -> String {
+        ts.format("%Y-%m-%d").to_string()
+    }
+
+    #[test]
+    fn test_split_fully_covered() {
+        assert_eq!(split_around((t(10, 0), t(11, 0)), (t(9, 0), t(12, 0)), utc_date), None);
+        assert_eq!(split_around((t(10, 0), t(11, 0)), (t(10, 0), t(11, 0)), utc_date), None);
+    }
+
+    #[test]
+    fn test_split_remainder_after_midnight() {
+        // 22:00–02:00 cut by 23:00–00:30: the remainder belongs to the next day
+        let next = |h, m| Utc.with_ymd_and_hms(2026, 3, 3, h, m, 0).unwrap();
+        let (keep, rest) = split_around((t(22, 0), next(2, 0)), (t(23, 0), next(0, 30)), utc_date).unwrap();
+        assert_eq!(keep, (t(22, 0), t(23, 0)));
+        assert_eq!(rest, vec![((next(0, 30), next(2, 0)), "2026-03-03".to_string())]);
+    }
+}
@@ -0,0 +1,291 @@
+// API Tokens for the local HTTP bridge.
+// Each token carries scopes and a per-minute rate limit. Only a SHA-256 hash is stored;
+// the plaintext is returned once by create_api_token. Every authorization attempt
+// (allowed or not) lands in api_request_log so external access can be audited.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::PgPool;
+use tauri::State;
+
+use crate::PosDb;
+use super::error::{PosError, PosResult, db_context};
+use super::utils::gen_id;
+
+const TOKEN_PREFIX: &str = "cm_";
+/// Characters of the plaintext kept for display ("cm_1a2b3c4d…")
+const DISPLAY_PREFIX_LEN: usize = 11;
+const DEFAULT_RATE_LIMIT_PER_MINUTE: i32 = 60;
+const MAX_RATE_LIMIT_PER_MINUTE: i32 = 10_000;
+const DEFAULT_AUDIT_LIMIT: i64 = 200;
+const MAX_AUDIT_LIMIT: i64 = 2_000;
+
+// ─── Scopes ─────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ApiScope {
+    ReadOnly,
+    WriteActivities,
+    WriteKnowledge,
+}
+
+impl ApiScope {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ApiScope::ReadOnly => "read-only",
+            ApiScope::WriteActivities => "write-activities",
+            ApiScope::WriteKnowledge => "write-knowledge",
+        }
+    }
+
+    pub fn parse(s: &str) -> PosResult<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "read-only" | "read" => Ok(ApiScope::ReadOnly),
+            "write-activities" => Ok(ApiScope::WriteActivities),
+            "write-knowledge" => Ok(ApiScope::WriteKnowledge),
+            other => Err(PosError::InvalidInput(format!(
+                "Unknown scope '{}'. Expected read-only, write-activities or write-knowledge", other
+            ))),
+        }
+    }
+}
+
+/// Whether a token's scopes cover `required`. Every scope can read; writes need their own scope.
+pub fn scopes_allow(granted: &[String], required: ApiScope) -> bool {
+    let mut granted = granted.iter().filter_map(|s| ApiScope::parse(s).ok());
+    match required {
+        ApiScope::ReadOnly => granted.next().is_some(),
+        _ => granted.any(|s| s == required),
+    }
+}
+
+// ─── Types ──────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiTokenRow {
+    pub id: String,
+    pub name: String,
+    pub token_prefix: String,
+    pub scopes: Vec<String>,
+    pub rate_limit_per_minute: i32,
+    pub created_at: DateTime<Utc>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub revoked_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreatedApiToken {
+    /// Plaintext token. Shown once; only its hash is stored.
+    pub token: String,
+    pub info: ApiTokenRow,
+}
+
+#[derive(Debug, Serialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiAuditEntry {
+    pub id: i64,
+    pub token_id: Option<String>,
+    pub method: String,
+    pub path: String,
+    pub scope: String,
+    /// allowed | unauthorized | forbidden | rate_limited
+    pub outcome: String,
+    pub created_at: DateTime<Utc>,
+}
+
+const TOKEN_COLS: &str = "id, name, token_prefix, scopes, rate_limit_per_minute, created_at, last_used_at, revoked_at";
+
+// ─── Authorization (used by the HTTP bridge) ────────────────────────
+
+async fn audit(pool: &PgPool, token_id: Option<&str>, method: &str, path: &str, scope: ApiScope, outcome: &str) {
+    let res = sqlx::query(
+        r#"INSERT INTO api_request_log (token_id, method, path, scope, outcome, created_at)
+           VALUES ($1, $2, $3, $4, $5, NOW())"#
+    )
+    .bind(token_id)
+    .bind(method)
+    .bind(path)
+    .bind(scope.as_str())
+    .bind(outcome)
+    .execute(pool)
+    .await;
+    if let Err(e) = res {
+        log::warn!("[API] Failed to write audit entry for {} {}: {}", method, path, e);
+    }
+}
+
+/// Check a bearer token for one request: known and not revoked, holds `required`, and
+/// under its per-minute limit. The attempt is audited whatever the outcome.
+pub async fn authorize(
+    pool: &PgPool,
+    raw_token: Option<&str>,
+    required: ApiScope,
+    method: &str,
+    path: &str,
+) -> PosResult<ApiTokenRow> {
+    let token = match raw_token.map(str::trim).filter(|t| t.starts_with(TOKEN_PREFIX)) {
+        Some(t) => sqlx::query_as::<_, ApiTokenRow>(&format!(
+            "SELECT {} FROM api_tokens WHERE token_hash = encode(sha256(convert_to($1, 'UTF8')), 'hex')",
+            TOKEN_COLS
+        ))
+        .bind(t)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| db_context("authorize lookup token", e))?,
+        None => None,
+    };
+
+    let Some(token) = token.filter(|t| t.revoked_at.is_none()) else {
+        audit(pool, None, method, path, required, "unauthorized").await;
+        return Err(PosError::Unauthorized("Missing, unknown or revoked API token".into()));
+    };
+
+    if !scopes_allow(&token.scopes, required) {
+        audit(pool, Some(&token.id), method, path, required, "forbidden").await;
+        return Err(PosError::Unauthorized(format!("Token lacks the {} scope", required.as_str())));
+    }
+
+    let recent: i64 = sqlx::query_scalar(
+        r#"SELECT COUNT(*) FROM api_request_log
+           WHERE token_id = $1 AND outcome = 'allowed' AND created_at > NOW() - INTERVAL '1 minute'"#
+    )
+    .bind(&token.id)
+    .fetch_one(pool)
+    .await
+    .map_err(|e| db_context("authorize rate limit", e))?;
+    if recent >= token.rate_limit_per_minute as i64 {
+        audit(pool, Some(&token.id), method, path, required, "rate_limited").await;
+        return Err(PosError::RateLimited(format!(
+            "Limit of {} requests per minute reached", token.rate_limit_per_minute
+        )));
+    }
+
+    audit(pool, Some(&token.id), method, path, required, "allowed").await;
+    sqlx::query("UPDATE api_tokens SET last_used_at = NOW() WHERE id = $1")
+        .bind(&token.id)
+        .execute(pool)
+        .await
+        .map_err(|e| db_context("authorize touch token", e))?;
+    Ok(token)
+}
+
+// ─── Commands ───────────────────────────────────────────────────────
+
+#[tauri::command]
+pub async fn create_api_token(
+    db: State<'_, PosDb>,
+    scopes: Vec<String>,
+    name: Option<String>,
+    rate_limit_per_minute: Option<i32>,
+) -> PosResult<CreatedApiToken> {
+    let pool = &db.0;
+    let mut parsed: Vec<&'static str> = Vec::new();
+    for s in &scopes {
+        let scope = ApiScope::parse(s)?.as_str();
+        if !parsed.contains(&scope) {
+            parsed.push(scope);
+        }
+    }
+    if parsed.is_empty() {
+        return Err(PosError::InvalidInput("At least one scope is required".into()));
+    }
+    let limit = rate_limit_per_minute.unwrap_or(DEFAULT_RATE_LIMIT_PER_MINUTE);
+    if !(1..=MAX_RATE_LIMIT_PER_MINUTE).contains(&limit) {
+        return Err(PosError::InvalidInput(format!(
+            "rate_limit_per_minute must be between 1 and {}", MAX_RATE_LIMIT_PER_MINUTE
+        )));
+    }
+
+    // Two v4 UUIDs (244 random bits) from Postgres; the app has no CSPRNG of its own
+    let secret: String = sqlx::query_scalar(
+        "SELECT replace(gen_random_uuid()::text || gen_random_uuid()::text, '-', '')"
+    )
+    .fetch_one(pool)
+    .await
+    .map_err(|e| db_context("generate api token", e))?;
+    let token = format!("{}{}", TOKEN_PREFIX, secret);
+    let name = name.map(|n| n.trim().to_string()).filter(|n| !n.is_empty())
+        .unwrap_or_else(|| format!("Token {}", Utc::now().format("%Y-%m-%d")));
+
+    let info = sqlx::query_as::<_, ApiTokenRow>(&format!(
+        r#"INSERT INTO api_tokens (id, name, token_hash, token_prefix, scopes, rate_limit_per_minute, created_at)
+           VALUES ($1, $2, encode(sha256(convert_to($3, 'UTF8')), 'hex'), $4, $5, $6, NOW())
+           RETURNING {}"#, TOKEN_COLS
+    ))
+    .bind(gen_id())
+    .bind(&name)
+    .bind(&token)
+    .bind(&token[..DISPLAY_PREFIX_LEN])
+    .bind(&parsed)
+    .bind(limit)
+    .fetch_one(pool)
+    .await
+    .map_err(|e| db_context("create_api_token", e))?;
+
+    log::info!("[API] Created token {} ({}) with scopes {:?}", info.id, info.name, info.scopes);
+    Ok(CreatedApiToken { token, info })
+}
+
+#[tauri::command]
+pub async fn revoke_api_token(db: State<'_, PosDb>, id: String) -> PosResult<ApiTokenRow> {
+    let row = sqlx::query_as::<_, ApiTokenRow>(&format!(
+        "UPDATE api_tokens SET revoked_at = COALESCE(revoked_at, NOW()) WHERE id = $1 RETURNING {}",
+        TOKEN_COLS
+    ))
+    .bind(&id)
+    .fetch_optional(&db.0)
+    .await
+    .map_err(|e| db_context("revoke_api_token", e))?
+    .ok_or_else(|| PosError::NotFound(format!("API token not found: {}", id)))?;
+
+    log::info!("[API] Revoked token {} ({})", row.id, row.name);
+    Ok(row)
+}
+
+#[tauri::command]
+pub async fn list_api_tokens(db: State<'_, PosDb>) -> PosResult<Vec<ApiTokenRow>> {
+    sqlx::query_as::<_, ApiTokenRow>(&format!(
+        "SELECT {} FROM api_tokens ORDER BY revoked_at IS NOT NULL, created_at DESC", TOKEN_COLS
+    ))
+    .fetch_all(&db.0)
+    .await
+    .map_err(|e| db_context("list_api_tokens", e))
+}
+
+/// Most recent external requests first, optionally for one token
+#[tauri::command]
+pub async fn get_api_audit_log(
+    db: State<'_, PosDb>,
+    token_id: Option<String>,
+    limit: Option<i64>,
+) -> PosResult<Vec<ApiAuditEntry>> {
+    let limit = limit.unwrap_or(DEFAULT_AUDIT_LIMIT).clamp(1, MAX_AUDIT_LIMIT);
+    sqlx::query_as::<_, ApiAuditEntry>(
+        r#"SELECT id, token_id, method, path, scope, outcome, created_at FROM api_request_log
+           WHERE ($1::text IS NULL OR token_id = $1)
+           ORDER BY created_at DESC, id DESC LIMIT $2"#
+    )
+    .bind(&token_id)
+    .bind(limit)
+    .fetch_all(&db.0)
+    .await
+    .map_err(|e| db_context("get_api_audit_log", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scopes_allow() {
+        let s = |v: &[&str]| v.iter().map(|x| x.to_string()).collect::<Vec<_>>();
+        assert!(scopes_allow(&s(&["read-only"]), ApiScope::ReadOnly));
+        assert!(!scopes_allow(&s(&["read-only"]), ApiScope::WriteActivities));
+        assert!(scopes_allow(&s(&["write-knowledge"]), ApiScope::ReadOnly));
+        assert!(scopes_allow(&s(&["write-activities"]), ApiScope::WriteActivities));
+        assert!(!scopes_allow(&s(&["write-activities"]), ApiScope::WriteKnowledge));
+        assert!(!scopes_allow(&s(&[]), ApiScope::ReadOnly));
+    }
+}
@@ -0,0 +1,173 @@
+// Platform Data Cache
+// TTL cache for data fetched from external platforms, keyed by (platform, resource).
+// Fresh entries are served directly. Stale entries are refreshed inline, or with
+// stale-while-revalidate served at once while a background task refreshes them.
+// A failed fetch falls back to the cached value however old it is.
+
+use std::collections::HashSet;
+use std::future::Future;
+use std::sync::{Mutex, OnceLock};
+
+use serde::{de::DeserializeOwned, Serialize};
+use sqlx::PgPool;
+
+use super::error::{PosError, PosResult, db_context};
+
+/// Resource name for per-platform profile stats (rating, rank, solved counts)
+pub const USER_STATS: &str = "user_stats";
+pub const DEFAULT_TTL_HOURS: i64 = 24;
+
+#[derive(Debug, Clone, Copy)]
+pub struct CachePolicy {
+    pub ttl_secs: i64,
+    /// Serve a stale entry immediately and refresh it in the background
+    pub stale_while_revalidate: bool,
+    /// Skip the cached entry and fetch (still falls back to it if the fetch fails)
+    pub force_refresh: bool,
+}
+
+impl CachePolicy {
+    pub fn hours(hours: i64) -> Self {
+        Self { ttl_secs: hours * 3600, stale_while_revalidate: false, force_refresh: false }
+    }
+
+    pub fn revalidate_in_background(mut self) -> Self {
+        self.stale_while_revalidate = true;
+        self
+    }
+
+    pub fn force(mut self, force_refresh: bool) -> Self {
+        self.force_refresh = force_refresh;
+        self
+    }
+}
+
+impl Default for CachePolicy {
+    fn default() -> Self {
+        Self::hours(DEFAULT_TTL_HOURS)
+    }
+}
+
+// ─── Storage ────────────────────────────────────────────────────────
+
+/// Cached value and its age in seconds
+async fn load(pool: &PgPool, platform: &str, resource: &str) -> PosResult<Option<(serde_json::Value, i64)>> {
+    sqlx::query_as::<_, (serde_json::Value, i64)>(
+        r#"SELECT data, EXTRACT(EPOCH FROM (NOW() - fetched_at))::bigint
+           FROM platform_cache WHERE platform = $1 AND resource = $2"#
+    )
+    .bind(platform)
+    .bind(resource)
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| db_context("load cache entry", e))
+}
+
+async fn store<T: Serialize>(pool: &PgPool, platform: &str, resource: &str, value: &T) -> PosResult<()> {
+    let data = serde_json::to_value(value)
+        .map_err(|e| PosError::InvalidInput(format!("Cache serialize error: {}", e)))?;
+    sqlx::query(
+        r#"INSERT INTO platform_cache (platform, resource, data, fetched_at)
+           VALUES ($1, $2, $3, NOW())
+           ON CONFLICT (platform, resource) DO UPDATE
+           SET data = EXCLUDED.data, fetched_at = EXCLUDED.fetched_at"#
+    )
+    .bind(platform)
+    .bind(resource)
+    .bind(data)
+    .execute(pool)
+    .await
+    .map_err(|e| db_context("store cache entry", e))?;
+    Ok(())
+}
+
+/// Drop every cached resource of a platform, e.g. after a full sync rewrote the source data
+pub async fn invalidate(pool: &PgPool, platform: &str) -> PosResult<()> {
+    sqlx::query("DELETE FROM platform_cache WHERE platform = $1")
+        .bind(platform)
+        .execute(pool)
+        .await
+        .map_err(|e| db_context("invalidate cache", e))?;
+    Ok(())
+}
+
+// ─── Fetch ──────────────────────────────────────────────────────────
+
+async fn refresh<T, F, Fut>(pool: &PgPool, platform: &str, resource: &str, fetch: F) -> PosResult<T>
+where
+    T: Serialize,
+    F: FnOnce() -> Fut,
+    Fut: Future<Output = PosResult<T>>,
+{
+    let value = fetch().await?;
+    store(pool, platform, resource, &value).await?;
+    log::info!("[CACHE] Refreshed {}/{}", platform, resource);
+    Ok(value)
+}
+
+/// Keys with a background revalidation running, so a burst of reads spawns only one
+fn in_flight() -> &'static Mutex<HashSet<String>> {
+    static IN_FLIGHT: OnceLock<Mutex<HashSet<String>>> = OnceLock::new();
+    IN_FLIGHT.get_or_init(|| Mutex::new(HashSet::new()))
+}
+
+fn spawn_revalidate<T, F, Fut>(pool: &PgPool, platform: &str, resource: &str, fetch: F)
+where
+    T: Serialize + Send + Sync + 'static,
+    F: FnOnce() -> Fut + Send + 'static,
+    Fut: Future<Output = PosResult<T>> + Send + 'static,
+{
+    let key = format!("{}/{}", platform, resource);
+    if !in_flight().lock().unwrap_or_else(|e| e.into_inner()).insert(key.clone()) {
+        return;
+    }
+    let (pool, platform, resource) = (pool.clone(), platform.to_string(), resource.to_string());
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = refresh(&pool, &platform, &resource, fetch).await {
+            log::warn!("[CACHE] Background refresh of {} failed: {}", key, e);
+        }
+        in_flight().lock().unwrap_or_else(|e| e.into_inner()).remove(&key);
+    });
+}
+
+/// Cached value for (platform, resource) under `policy`, calling `fetch` when it is
+/// missing, stale or a refresh is forced.
+pub async fn get_or_fetch<T, F, Fut>(
+    pool: &PgPool,
+    platform: &str,
+    resource: &str,
+    policy: CachePolicy,
+    fetch: F,
+) -> PosResult<T>
+where
+    T: Serialize + DeserializeOwned + Send + Sync + 'static,
+    F: FnOnce() -> Fut + Send + 'static,
+    Fut: Future<Output = PosResult<T>> + Send + 'static,
+{
+    // An entry that no longer deserializes (shape changed) counts as a miss
+    let cached = load(pool, platform, resource).await?
+        .and_then(|(data, age)| serde_json::from_value::<T>(data).ok().map(|v| (v, age)));
+
+    match cached {
+        Some((value, age)) if !policy.force_refresh && age < policy.ttl_secs => {
+            log::info!("[CACHE] Serving {}/{} (age: {} min)", platform, resource, age / 60);
+            Ok(value)
+        }
+        Some((value, age)) if !policy.force_refresh && policy.stale_while_revalidate => {
+            log::info!("[CACHE] Serving stale {}/{} (age: {} min), revalidating", platform, resource, age / 60);
+            spawn_revalidate(pool, platform, resource, fetch);
+            Ok(value)
+        }
+        cached => match refresh(pool, platform, resource, fetch).await {
+            Ok(value) => Ok(value),
+            Err(e) => match cached {
+                Some((value, age)) => {
+                    log::warn!("[CACHE] Refresh of {}/{} failed ({}), serving cache from {} min ago",
+                        platform, resource, e, age / 60);
+                    Ok(value)
+                }
+                None => Err(e),
+            },
+        },
+    }
+}
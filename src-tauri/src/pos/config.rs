@@ -1,5 +1,61 @@
 use std::env;
 
+/// What the shadow logger does when a submission's block overlaps a real activity
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ShadowCollisionPolicy {
+    /// Attach the problem to the overlapping real activity instead of logging a shadow block
+    Merge,
+    /// Drop the shadow block without attaching anything
+    Skip,
+    /// Start the shadow block where the real activity ends; merge if the submission falls inside one
+    Shrink,
+    /// Log the full shadow block regardless (pre-collision behaviour)
+    Keep,
+}
+
+impl ShadowCollisionPolicy {
+    pub fn parse(value: &str) -> Result<Self, String> {
+        match value.trim().to_lowercase().as_str() {
+            "merge" => Ok(Self::Merge),
+            "skip" => Ok(Self::Skip),
+            "shrink" => Ok(Self::Shrink),
+            "keep" => Ok(Self::Keep),
+            other => Err(format!(
+                "SHADOW_COLLISION_POLICY must be merge, skip, shrink or keep, got: {}", other
+            )),
+        }
+    }
+}
+
+/// What create_activity / update_activity do when the new block overlaps a real activity
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ActivityOverlapPolicy {
+    /// Refuse the write
+    Reject,
+    /// Shorten the new activity to its longest stretch not covered by existing ones
+    Truncate,
+    /// Keep the new activity whole and cut the existing ones around it
+    Split,
+    /// Store it as given (pre-validation behaviour)
+    Allow,
+}
+
+impl ActivityOverlapPolicy {
+    pub fn parse(value: &str) -> Result<Self, String> {
+        match value.trim().to_lowercase().as_str() {
+            "reject" => Ok(Self::Reject),
+            "truncate" => Ok(Self::Truncate),
+            "split" => Ok(Self::Split),
+            "allow" => Ok(Self::Allow),
+            other => Err(format!(
+                "ACTIVITY_OVERLAP_POLICY must be reject, truncate, split or allow, got: {}", other
+            )),
+        }
+    }
+}
+
 /// POS configuration loaded from environment variables
 #[derive(Debug, Clone)]
 pub struct PosConfig {
@@ -9,6 +65,8 @@ pub struct PosConfig {
     pub leetcode_username: Option<String>,
     /// Codeforces handle for scraping
     pub codeforces_handle: Option<String>,
+    /// AtCoder user ID for scraping
+    pub atcoder_username: Option<String>,
     /// GitHub username for scraping
     pub github_username: Option<String>,
     /// GitHub personal access token for API access
@@ -19,6 +77,34 @@ pub struct PosConfig {
     pub db_connection_timeout_secs: u64,
     /// Database max connections (default: 5)
     pub db_max_connections: u32,
+    /// Split activities that cross local midnight into one row per day (default: false)
+    pub split_activities_at_midnight: bool,
+    /// Overlap handling for shadow activities (default: shrink)
+    pub shadow_collision_policy: ShadowCollisionPolicy,
+    /// Minutes around a real activity that still count as colliding with a shadow block (default: 0)
+    pub shadow_merge_window_minutes: i64,
+    /// Overlap handling for manually created/edited activities (default: reject)
+    pub activity_overlap_policy: ActivityOverlapPolicy,
+    /// whisper.cpp model used to transcribe voice memos (optional)
+    pub whisper_model_path: Option<String>,
+    /// whisper.cpp CLI binary (default: whisper-cli)
+    pub whisper_bin: String,
+    /// Run background platform scrapes (default: true)
+    pub scrape_scheduler_enabled: bool,
+    /// Shortest adaptive scrape interval, used during contests (default: 5)
+    pub scrape_min_interval_minutes: i64,
+    /// Longest adaptive scrape interval, used when idle (default: 360)
+    pub scrape_max_interval_minutes: i64,
+    /// Submissions fetched per page by incremental CF friend syncs (default: 100)
+    pub cf_friend_sync_depth: u32,
+    /// Re-fetch every friend's full history once a night (default: false)
+    pub cf_friend_nightly_full_sync: bool,
+    /// IANA zone local dates are derived in (default: the OS zone)
+    pub timezone: Option<chrono_tz::Tz>,
+    /// Local HTTP bridge port for external integrations (default: off)
+    pub http_bridge_port: Option<u16>,
+    /// Directory generated day summaries are exported to as Markdown (default: off)
+    pub journal_export_dir: Option<String>,
 }
 
 impl PosConfig {
@@ -49,6 +135,12 @@ impl PosConfig {
             log::warn!("[POS Config] CODEFORCES_HANDLE not set - Codeforces scraper will be unavailable");
         }
 
+        // AtCoder user ID (optional)
+        let atcoder_username = env::var("ATCODER_USERNAME").ok();
+        if atcoder_username.is_none() {
+            log::warn!("[POS Config] ATCODER_USERNAME not set - AtCoder scraper will be unavailable");
+        }
+
         // GitHub username (optional)
         let github_username = env::var("GITHUB_USERNAME").ok();
         if github_username.is_none() {
@@ -108,15 +200,116 @@ impl PosConfig {
             ));
         }
 
+        // Midnight splitting (optional, default off)
+        let split_activities_at_midnight = env::var("SPLIT_ACTIVITIES_AT_MIDNIGHT")
+            .map(|v| matches!(v.trim().to_lowercase().as_str(), "1" | "true" | "yes"))
+            .unwrap_or(false);
+
+        // Shadow collision policy (optional, default shrink)
+        let shadow_collision_policy = match env::var("SHADOW_COLLISION_POLICY") {
+            Ok(v) => ShadowCollisionPolicy::parse(&v)?,
+            Err(_) => ShadowCollisionPolicy::Shrink,
+        };
+        let shadow_merge_window_minutes = env::var("SHADOW_MERGE_WINDOW_MINUTES")
+            .ok()
+            .and_then(|v| v.parse::<i64>().ok())
+            .unwrap_or(0);
+        if !(0..=120).contains(&shadow_merge_window_minutes) {
+            return Err(format!(
+                "SHADOW_MERGE_WINDOW_MINUTES must be between 0 and 120, got: {}", shadow_merge_window_minutes
+            ));
+        }
+
+        // Activity overlap policy (optional, default reject)
+        let activity_overlap_policy = match env::var("ACTIVITY_OVERLAP_POLICY") {
+            Ok(v) => ActivityOverlapPolicy::parse(&v)?,
+            Err(_) => ActivityOverlapPolicy::Reject,
+        };
+
+        // Local transcription (optional): voice memos are saved untranscribed without a model
+        let whisper_model_path = env::var("WHISPER_MODEL_PATH").ok().filter(|v| !v.trim().is_empty());
+        let whisper_bin = env::var("WHISPER_CPP_BIN").unwrap_or_else(|_| "whisper-cli".to_string());
+
+        // Background scrape scheduler (optional, default on with 5 min – 6 h bounds)
+        let scrape_scheduler_enabled = env::var("SCRAPE_SCHEDULER_ENABLED")
+            .map(|v| !matches!(v.trim().to_lowercase().as_str(), "0" | "false" | "no"))
+            .unwrap_or(true);
+        let scrape_min_interval_minutes = env::var("SCRAPE_MIN_INTERVAL_MINUTES")
+            .ok()
+            .and_then(|v| v.parse::<i64>().ok())
+            .unwrap_or(5);
+        let scrape_max_interval_minutes = env::var("SCRAPE_MAX_INTERVAL_MINUTES")
+            .ok()
+            .and_then(|v| v.parse::<i64>().ok())
+            .unwrap_or(360);
+
+        if scrape_min_interval_minutes < 1 || scrape_max_interval_minutes > 1440
+            || scrape_min_interval_minutes > scrape_max_interval_minutes
+        {
+            return Err(format!(
+                "SCRAPE_MIN_INTERVAL_MINUTES/SCRAPE_MAX_INTERVAL_MINUTES must satisfy 1 <= min <= max <= 1440, got: {}/{}",
+                scrape_min_interval_minutes, scrape_max_interval_minutes
+            ));
+        }
+
+        // CF friend sync: page size for incremental syncs, optional nightly full pass
+        let cf_friend_sync_depth = env::var("CF_FRIEND_SYNC_DEPTH")
+            .ok()
+            .and_then(|v| v.parse::<u32>().ok())
+            .unwrap_or(100);
+        if !(1..=10_000).contains(&cf_friend_sync_depth) {
+            return Err(format!(
+                "CF_FRIEND_SYNC_DEPTH must be between 1 and 10000, got: {}", cf_friend_sync_depth
+            ));
+        }
+        let cf_friend_nightly_full_sync = env::var("CF_FRIEND_NIGHTLY_FULL_SYNC")
+            .map(|v| matches!(v.trim().to_lowercase().as_str(), "1" | "true" | "yes"))
+            .unwrap_or(false);
+
+        // Local date zone (optional, default: follow the OS)
+        let timezone = match env::var("POS_TIMEZONE") {
+            Ok(v) if !v.trim().is_empty() => Some(super::timezone::parse_zone(&v)?),
+            _ => None,
+        };
+
+        // HTTP bridge (optional): only served when a port is given
+        let http_bridge_port = match env::var("HTTP_BRIDGE_PORT") {
+            Ok(v) if !v.trim().is_empty() => match v.trim().parse::<u16>() {
+                Ok(port) if port > 0 => Some(port),
+                _ => return Err(format!("HTTP_BRIDGE_PORT must be a port between 1 and 65535, got: {}", v)),
+            },
+            _ => None,
+        };
+
+        // Journal export (optional): where day summaries are written as <date>.md
+        let journal_export_dir = env::var("JOURNAL_EXPORT_DIR").ok()
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty());
+
         Ok(Self {
             database_url,
             leetcode_username,
             codeforces_handle,
+            atcoder_username,
             github_username,
             github_token,
             shadow_activity_minutes,
             db_connection_timeout_secs,
             db_max_connections,
+            split_activities_at_midnight,
+            shadow_collision_policy,
+            shadow_merge_window_minutes,
+            activity_overlap_policy,
+            whisper_model_path,
+            whisper_bin,
+            scrape_scheduler_enabled,
+            scrape_min_interval_minutes,
+            scrape_max_interval_minutes,
+            cf_friend_sync_depth,
+            cf_friend_nightly_full_sync,
+            timezone,
+            http_bridge_port,
+            journal_export_dir,
         })
     }
 
@@ -134,6 +327,13 @@ impl PosConfig {
             .ok_or_else(|| "CODEFORCES_HANDLE not configured".to_string())
     }
 
+    /// Get AtCoder user ID or return error
+    pub fn require_atcoder_username(&self) -> Result<&str, String> {
+        self.atcoder_username
+            .as_deref()
+            .ok_or_else(|| "ATCODER_USERNAME not configured".to_string())
+    }
+
     /// Get GitHub username or return error
     pub fn require_github_username(&self) -> Result<&str, String> {
         self.github_username
@@ -169,6 +369,22 @@ mod tests {
         env::set_var("SHADOW_ACTIVITY_MINUTES", "30");
         assert!(PosConfig::from_env().is_ok());
     }
+
+    #[test]
+    fn test_shadow_collision_policy_parse() {
+        assert_eq!(ShadowCollisionPolicy::parse("Merge"), Ok(ShadowCollisionPolicy::Merge));
+        assert_eq!(ShadowCollisionPolicy::parse(" shrink "), Ok(ShadowCollisionPolicy::Shrink));
+        assert_eq!(ShadowCollisionPolicy::parse("keep"), Ok(ShadowCollisionPolicy::Keep));
+        assert_eq!(ShadowCollisionPolicy::parse("SKIP"), Ok(ShadowCollisionPolicy::Skip));
+        assert!(ShadowCollisionPolicy::parse("drop").is_err());
+    }
+
+    #[test]
+    fn test_activity_overlap_policy_parse() {
+        assert_eq!(ActivityOverlapPolicy::parse(" Split "), Ok(ActivityOverlapPolicy::Split));
+        assert_eq!(ActivityOverlapPolicy::parse("truncate"), Ok(ActivityOverlapPolicy::Truncate));
+        assert!(ActivityOverlapPolicy::parse("merge").is_err());
+    }
 }
 
 // ─── Tauri Commands ─────────────────────────────────────────────────
@@ -181,8 +397,23 @@ use tauri::State;
 pub struct PosConfigResponse {
     pub leetcode_username: Option<String>,
     pub codeforces_handle: Option<String>,
+    pub atcoder_username: Option<String>,
     pub github_username: Option<String>,
     pub has_github_token: bool,
+    pub split_activities_at_midnight: bool,
+    pub shadow_collision_policy: ShadowCollisionPolicy,
+    pub shadow_merge_window_minutes: i64,
+    pub activity_overlap_policy: ActivityOverlapPolicy,
+    pub has_whisper_model: bool,
+    pub scrape_scheduler_enabled: bool,
+    pub scrape_min_interval_minutes: i64,
+    pub scrape_max_interval_minutes: i64,
+    pub cf_friend_sync_depth: u32,
+    pub cf_friend_nightly_full_sync: bool,
+    /// IANA name, or "system" when following the OS
+    pub timezone: String,
+    pub http_bridge_port: Option<u16>,
+    pub journal_export_dir: Option<String>,
 }
 
 /// Get POS configuration (without exposing sensitive tokens)
@@ -191,7 +422,21 @@ pub fn get_pos_config(config: State<'_, crate::PosConfig>) -> PosConfigResponse
     PosConfigResponse {
         leetcode_username: config.0.leetcode_username.clone(),
         codeforces_handle: config.0.codeforces_handle.clone(),
+        atcoder_username: config.0.atcoder_username.clone(),
         github_username: config.0.github_username.clone(),
         has_github_token: config.0.github_token.is_some(),
+        split_activities_at_midnight: config.0.split_activities_at_midnight,
+        shadow_collision_policy: config.0.shadow_collision_policy,
+        shadow_merge_window_minutes: config.0.shadow_merge_window_minutes,
+        activity_overlap_policy: config.0.activity_overlap_policy,
+        has_whisper_model: config.0.whisper_model_path.is_some(),
+        scrape_scheduler_enabled: config.0.scrape_scheduler_enabled,
+        scrape_min_interval_minutes: config.0.scrape_min_interval_minutes,
+        scrape_max_interval_minutes: config.0.scrape_max_interval_minutes,
+        cf_friend_sync_depth: config.0.cf_friend_sync_depth,
+        cf_friend_nightly_full_sync: config.0.cf_friend_nightly_full_sync,
+        timezone: config.0.timezone.map_or_else(|| "system".to_string(), |tz| tz.name().to_string()),
+        http_bridge_port: config.0.http_bridge_port,
+        journal_export_dir: config.0.journal_export_dir.clone(),
     }
 }
@@ -0,0 +1,112 @@
+// Two-step confirmation for destructive commands.
+// First call (no token) returns a single-use token plus an impact report; the second call
+// must present that token with identical parameters before anything is deleted.
+
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+use sqlx::PgPool;
+
+use super::error::{PosError, PosResult, db_context};
+use super::utils::gen_id;
+
+const TOKEN_TTL_MINUTES: i64 = 5;
+
+// ─── Types ──────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImpactItem {
+    pub target: String, // table or entity affected
+    pub rows: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImpactReport {
+    pub summary: String,
+    pub items: Vec<ImpactItem>,
+    pub total_rows: i64,
+}
+
+impl ImpactReport {
+    pub fn new(summary: impl Into<String>, items: Vec<ImpactItem>) -> Self {
+        let total_rows = items.iter().map(|i| i.rows).sum();
+        Self { summary: summary.into(), items, total_rows }
+    }
+}
+
+/// Result of a destructive command: either a pending confirmation or the executed result
+#[derive(Debug, Serialize)]
+#[serde(tag = "status", rename_all = "camelCase")]
+pub enum Confirmable<T: Serialize> {
+    #[serde(rename_all = "camelCase")]
+    ConfirmationRequired {
+        token: String,
+        expires_at: DateTime<Utc>,
+        impact: ImpactReport,
+    },
+    Executed { result: T },
+}
+
+// ─── Token lifecycle ────────────────────────────────────────────────
+
+/// Store a single-use token bound to `action` + `params` and return the confirmation response
+pub async fn request_confirmation<T: Serialize>(
+    pool: &PgPool,
+    action: &str,
+    params: serde_json::Value,
+    impact: ImpactReport,
+) -> PosResult<Confirmable<T>> {
+    let token = gen_id();
+    let expires_at = Utc::now() + Duration::minutes(TOKEN_TTL_MINUTES);
+
+    // Opportunistic cleanup of abandoned tokens
+    sqlx::query("DELETE FROM pending_confirmations WHERE expires_at < NOW()")
+        .execute(pool)
+        .await
+        .map_err(|e| db_context("expire confirmations", e))?;
+
+    sqlx::query(
+        r#"INSERT INTO pending_confirmations (token, action, params, impact, created_at, expires_at)
+           VALUES ($1, $2, $3, $4, NOW(), $5)"#
+    )
+    .bind(&token)
+    .bind(action)
+    .bind(&params)
+    .bind(sqlx::types::Json(&impact))
+    .bind(expires_at)
+    .execute(pool)
+    .await
+    .map_err(|e| db_context("insert pending_confirmation", e))?;
+
+    log::info!("[CONFIRM] {} requested ({} rows affected)", action, impact.total_rows);
+    Ok(Confirmable::ConfirmationRequired { token, expires_at, impact })
+}
+
+/// Consume a token. Fails unless it exists, is unexpired, and was issued for the same
+/// action and parameters. Tokens are deleted on use, whether or not the action succeeds.
+pub async fn consume_confirmation(
+    pool: &PgPool,
+    token: &str,
+    action: &str,
+    params: &serde_json::Value,
+) -> PosResult<()> {
+    let row: Option<(String, serde_json::Value, DateTime<Utc>)> = sqlx::query_as(
+        "DELETE FROM pending_confirmations WHERE token = $1 RETURNING action, params, expires_at"
+    )
+    .bind(token)
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| db_context("consume confirmation", e))?;
+
+    let (stored_action, stored_params, expires_at) = row
+        .ok_or_else(|| PosError::InvalidInput("Unknown or already used confirmation token".into()))?;
+
+    if expires_at < Utc::now() {
+        return Err(PosError::InvalidInput("Confirmation token expired — request a new one".into()));
+    }
+    if stored_action != action || &stored_params != params {
+        return Err(PosError::InvalidInput("Confirmation token was issued for a different action".into()));
+    }
+    Ok(())
+}
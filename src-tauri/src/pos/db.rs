@@ -7,6 +7,9 @@ pub async fn init_pos_tables(pool: &PgPool) -> Result<(), sqlx::Error> {
     for ddl in POS_DDL_STATEMENTS {
         sqlx::query(ddl).execute(pool).await?;
     }
+    crate::pos::units::normalize_existing_units(pool).await?;
+    crate::pos::problem_url::canonicalize_existing_problem_urls(pool).await?;
+    crate::knowledge_problems::link_unlinked_knowledge_problems(pool).await?;
     log::info!("[POS] All PostgreSQL tables initialized");
     Ok(())
 }
@@ -192,6 +195,9 @@ const POS_DDL_STATEMENTS: &[&str] = &[
     "CREATE INDEX IF NOT EXISTS idx_unified_goals_created_at ON unified_goals(created_at DESC)",
     "CREATE INDEX IF NOT EXISTS idx_unified_goals_parent ON unified_goals(parent_goal_id) WHERE parent_goal_id IS NOT NULL",
     "CREATE UNIQUE INDEX IF NOT EXISTS idx_unified_goals_recurring_instance ON unified_goals(recurring_template_id, date) WHERE recurring_template_id IS NOT NULL",
+    "ALTER TABLE unified_goals ADD COLUMN IF NOT EXISTS archived_at TIMESTAMPTZ",
+    "CREATE INDEX IF NOT EXISTS idx_unified_goals_active_date ON unified_goals(date) WHERE archived_at IS NULL",
+    "CREATE INDEX IF NOT EXISTS idx_unified_goals_archived ON unified_goals(archived_at DESC) WHERE archived_at IS NOT NULL",
 
     // ─── GitHub Repositories ────────────────────────────────────────
     "CREATE TABLE IF NOT EXISTS github_repositories (
@@ -563,6 +569,21 @@ const POS_DDL_STATEMENTS: &[&str] = &[
     )",
     "CREATE UNIQUE INDEX IF NOT EXISTS cf_daily_recommendations_date_key ON cf_daily_recommendations(date)",
     "CREATE INDEX IF NOT EXISTS idx_cf_daily_recommendations_date ON cf_daily_recommendations(date DESC)",
+    // One row per persisted pick, so completion can be tracked per problem
+    "CREATE TABLE IF NOT EXISTS cf_recommendation_items (
+        date            DATE NOT NULL REFERENCES cf_daily_recommendations(date) ON DELETE CASCADE,
+        problem_id      TEXT NOT NULL,
+        position        INTEGER NOT NULL,
+        problem_name    TEXT NOT NULL,
+        problem_url     TEXT NOT NULL,
+        online_judge    TEXT NOT NULL,
+        difficulty      INTEGER,
+        reason          TEXT NOT NULL,
+        strategy        TEXT NOT NULL,
+        done_at         TIMESTAMPTZ,
+        PRIMARY KEY (date, problem_id)
+    )",
+    "CREATE INDEX IF NOT EXISTS idx_cf_recommendation_items_problem ON cf_recommendation_items(problem_id)",
 
     // ─── Category Progress Tracking ─────────────────────────────────
     "CREATE TABLE IF NOT EXISTS cf_category_progress (
@@ -583,6 +604,130 @@ const POS_DDL_STATEMENTS: &[&str] = &[
         updated_at      TIMESTAMPTZ NOT NULL DEFAULT NOW()
     )",
 
+    // ─── CF Problem Editorials ──────────────────────────────────────
+    "CREATE TABLE IF NOT EXISTS cf_problem_editorials (
+        problem_id      TEXT PRIMARY KEY,
+        contest_id      INTEGER NOT NULL,
+        editorial_url   TEXT,
+        fetched_at      TIMESTAMPTZ NOT NULL DEFAULT NOW()
+    )",
+
+    // ─── Problem Hints ──────────────────────────────────────────────
+    "CREATE TABLE IF NOT EXISTS problem_hints (
+        id              TEXT PRIMARY KEY,
+        problem_id      TEXT NOT NULL,
+        position        INTEGER NOT NULL,
+        hint_text       TEXT NOT NULL,
+        revealed_at     TIMESTAMPTZ,
+        created_at      TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+        UNIQUE(problem_id, position)
+    )",
+    "CREATE INDEX IF NOT EXISTS idx_problem_hints_problem ON problem_hints(problem_id, position)",
+
+    // ─── Dashboard Snapshots ────────────────────────────────────────
+    "CREATE TABLE IF NOT EXISTS dashboard_snapshots (
+        snapshot_date   TEXT PRIMARY KEY,
+        data            JSONB NOT NULL,
+        refreshed_at    TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+        is_stale        BOOLEAN NOT NULL DEFAULT FALSE
+    )",
+
+    // ─── Custom Recommendation Strategies ───────────────────────────
+    "CREATE TABLE IF NOT EXISTS cf_custom_strategies (
+        name            TEXT PRIMARY KEY,
+        sql_text        TEXT NOT NULL,
+        description     TEXT,
+        created_at      TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+        updated_at      TIMESTAMPTZ NOT NULL DEFAULT NOW()
+    )",
+
+    // ─── Capture Roles ──────────────────────────────────────────────
+    "CREATE TABLE IF NOT EXISTS capture_roles (
+        role            TEXT PRIMARY KEY,
+        trigger         TEXT NOT NULL UNIQUE,
+        destination     JSONB NOT NULL,
+        position        INTEGER NOT NULL DEFAULT 0,
+        updated_at      TIMESTAMPTZ NOT NULL DEFAULT NOW()
+    )",
+
+    // ─── Destructive Action Confirmations ───────────────────────────
+    "CREATE TABLE IF NOT EXISTS pending_confirmations (
+        token           TEXT PRIMARY KEY,
+        action          TEXT NOT NULL,
+        params          JSONB NOT NULL,
+        impact          JSONB NOT NULL,
+        created_at      TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+        expires_at      TIMESTAMPTZ NOT NULL
+    )",
+
+    // ─── LeetCode Contest History ───────────────────────────────────
+    "CREATE TABLE IF NOT EXISTS leetcode_contest_history (
+        contest_title       TEXT PRIMARY KEY,
+        start_time          TIMESTAMPTZ NOT NULL,
+        rating              DOUBLE PRECISION NOT NULL,
+        ranking             INTEGER,
+        problems_solved     INTEGER NOT NULL DEFAULT 0,
+        total_problems      INTEGER NOT NULL DEFAULT 0,
+        finish_time_seconds BIGINT,
+        trend_direction     TEXT,
+        synced_at           TIMESTAMPTZ NOT NULL DEFAULT NOW()
+    )",
+    "CREATE INDEX IF NOT EXISTS idx_leetcode_contest_start ON leetcode_contest_history(start_time)",
+
+    // ─── Tag Taxonomy (cross-platform topic mapping) ──────────────────
+    "CREATE TABLE IF NOT EXISTS tag_taxonomy (
+        platform TEXT NOT NULL,
+        platform_tag TEXT NOT NULL,
+        topic TEXT NOT NULL,
+        PRIMARY KEY (platform, platform_tag)
+    )",
+    "CREATE INDEX IF NOT EXISTS idx_tag_taxonomy_topic ON tag_taxonomy(topic)",
+
+    // ─── Problemset Cache ───────────────────────────────────────────
+    "CREATE TABLE IF NOT EXISTS problemset_cache (
+        problem_id      TEXT NOT NULL,
+        judge           TEXT NOT NULL,
+        name            TEXT NOT NULL,
+        url             TEXT NOT NULL,
+        rating          INTEGER,
+        tags            TEXT[] NOT NULL DEFAULT '{}',
+        fetched_at      TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+        PRIMARY KEY (judge, problem_id)
+    )",
+    "CREATE INDEX IF NOT EXISTS idx_problemset_cache_rating ON problemset_cache(rating)",
+    "CREATE INDEX IF NOT EXISTS idx_problemset_cache_tags ON problemset_cache USING GIN(tags)",
+
+    // ─── Activity Evidence ──────────────────────────────────────────
+    "CREATE TABLE IF NOT EXISTS activity_evidence (
+        id              TEXT PRIMARY KEY,
+        activity_id     TEXT NOT NULL REFERENCES pos_activities(id) ON DELETE CASCADE,
+        kind            TEXT NOT NULL,
+        url             TEXT,
+        file_path       TEXT,
+        caption         TEXT,
+        size_bytes      BIGINT,
+        created_at      TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+        CONSTRAINT activity_evidence_kind_check CHECK (kind IN ('screenshot', 'link'))
+    )",
+    "CREATE INDEX IF NOT EXISTS idx_activity_evidence_activity ON activity_evidence(activity_id)",
+
+    // ─── Idempotency Keys ───────────────────────────────────────────
+    "CREATE TABLE IF NOT EXISTS idempotency_keys (
+        key             TEXT PRIMARY KEY,
+        scope           TEXT NOT NULL,
+        result          JSONB,
+        created_at      TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+        expires_at      TIMESTAMPTZ NOT NULL
+    )",
+    "CREATE INDEX IF NOT EXISTS idx_idempotency_keys_expires ON idempotency_keys(expires_at)",
+
+    // ─── Day Plan Locks ─────────────────────────────────────────────
+    "CREATE TABLE IF NOT EXISTS day_plan_locks (
+        date                TEXT PRIMARY KEY,
+        locked_at           TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+        planned_goal_ids    JSONB NOT NULL DEFAULT '[]'::jsonb
+    )",
+
     // ─── Milestone Daily Progress ────────────────────────────────────
     "CREATE TABLE IF NOT EXISTS milestone_daily_progress (
         id           TEXT PRIMARY KEY,
@@ -596,4 +741,336 @@ const POS_DDL_STATEMENTS: &[&str] = &[
     "CREATE INDEX IF NOT EXISTS idx_mdp_milestone_date ON milestone_daily_progress(milestone_id, date)",
     "CREATE INDEX IF NOT EXISTS idx_mdp_date ON milestone_daily_progress(date)",
 
+    // ─── Known Solved (pre-tracking solves, no submission timestamps) ─
+    "CREATE TABLE IF NOT EXISTS known_solved (
+        problem_id  TEXT PRIMARY KEY,
+        platform    TEXT NOT NULL,
+        source      TEXT NOT NULL DEFAULT 'import',
+        created_at  TIMESTAMPTZ NOT NULL DEFAULT NOW()
+    )",
+    "CREATE INDEX IF NOT EXISTS idx_known_solved_platform ON known_solved(platform)",
+    // solved_problems view: defined after the submission correction columns below
+
+    // ─── Soft delete (restorable until purge_deleted) ───────────────
+    "ALTER TABLE unified_goals ADD COLUMN IF NOT EXISTS deleted_at TIMESTAMPTZ",
+    "ALTER TABLE goal_periods ADD COLUMN IF NOT EXISTS deleted_at TIMESTAMPTZ",
+    "ALTER TABLE pos_activities ADD COLUMN IF NOT EXISTS deleted_at TIMESTAMPTZ",
+    "CREATE INDEX IF NOT EXISTS idx_unified_goals_deleted ON unified_goals(deleted_at) WHERE deleted_at IS NOT NULL",
+    "CREATE INDEX IF NOT EXISTS idx_goal_periods_deleted ON goal_periods(deleted_at) WHERE deleted_at IS NOT NULL",
+    "CREATE INDEX IF NOT EXISTS idx_pos_activities_deleted ON pos_activities(deleted_at) WHERE deleted_at IS NOT NULL",
+
+    // ─── API Tokens (HTTP bridge auth + audit) ──────────────────────
+    "CREATE TABLE IF NOT EXISTS api_tokens (
+        id                      TEXT PRIMARY KEY,
+        name                    TEXT NOT NULL,
+        token_hash              TEXT NOT NULL UNIQUE,
+        token_prefix            TEXT NOT NULL,
+        scopes                  TEXT[] NOT NULL,
+        rate_limit_per_minute   INTEGER NOT NULL DEFAULT 60,
+        created_at              TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+        last_used_at            TIMESTAMPTZ,
+        revoked_at              TIMESTAMPTZ
+    )",
+    "CREATE TABLE IF NOT EXISTS api_request_log (
+        id          BIGSERIAL PRIMARY KEY,
+        token_id    TEXT REFERENCES api_tokens(id) ON DELETE SET NULL,
+        method      TEXT NOT NULL,
+        path        TEXT NOT NULL,
+        scope       TEXT NOT NULL,
+        outcome     TEXT NOT NULL,
+        created_at  TIMESTAMPTZ NOT NULL DEFAULT NOW()
+    )",
+    "CREATE INDEX IF NOT EXISTS idx_api_request_log_token_time ON api_request_log(token_id, created_at DESC)",
+    "CREATE INDEX IF NOT EXISTS idx_api_request_log_time ON api_request_log(created_at DESC)",
+
+    // ─── Platform Cache (pos::cache, TTL per caller) ────────────────
+    "CREATE TABLE IF NOT EXISTS platform_cache (
+        platform    TEXT NOT NULL,
+        resource    TEXT NOT NULL,
+        data        JSONB NOT NULL,
+        fetched_at  TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+        PRIMARY KEY (platform, resource)
+    )",
+    // Carry over profile stats cached before the generic cache existed
+    "INSERT INTO platform_cache (platform, resource, data, fetched_at)
+        SELECT platform, 'user_stats', data, updated_at FROM pos_user_stats
+        ON CONFLICT (platform, resource) DO NOTHING",
+
+    // ─── Optimistic concurrency (updated_at doubles as row version) ─
+    "ALTER TABLE pos_activities ADD COLUMN IF NOT EXISTS updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()",
+
+    // ─── Submission corrections (exclude_submission / edit_submission_metadata) ─
+    "ALTER TABLE pos_submissions ADD COLUMN IF NOT EXISTS excluded_at TIMESTAMPTZ",
+    "ALTER TABLE pos_submissions ADD COLUMN IF NOT EXISTS exclusion_reason TEXT",
+    // Set once metadata is edited by hand; scraper backfills leave such rows alone
+    "ALTER TABLE pos_submissions ADD COLUMN IF NOT EXISTS metadata_edited_at TIMESTAMPTZ",
+    // Everything that counts as solved: accepted, non-excluded submissions plus known_solved
+    "CREATE OR REPLACE VIEW solved_problems AS
+        SELECT platform, problem_id FROM pos_submissions
+        WHERE verdict IN ('OK', 'Accepted', 'AC') AND excluded_at IS NULL
+        UNION
+        SELECT platform, problem_id FROM known_solved",
+
+    // ─── Streaks (cache for streaks::get_streaks) ───────────────────
+    "CREATE TABLE IF NOT EXISTS pos_streaks (
+        kind                TEXT PRIMARY KEY,
+        current_length      INTEGER NOT NULL DEFAULT 0,
+        current_start       DATE,
+        last_day            DATE,
+        longest_length      INTEGER NOT NULL DEFAULT 0,
+        longest_start       DATE,
+        longest_end         DATE,
+        dirty               BOOLEAN NOT NULL DEFAULT FALSE,
+        source_fingerprint  TEXT,
+        updated_at          TIMESTAMPTZ NOT NULL DEFAULT NOW()
+    )",
+
+    // ─── Cohort (study group members and their daily solved counts) ─
+    "CREATE TABLE IF NOT EXISTS cohort_members (
+        id              TEXT PRIMARY KEY,
+        platform        TEXT NOT NULL CHECK (platform IN ('codeforces', 'leetcode')),
+        handle          TEXT NOT NULL,
+        display_name    TEXT NOT NULL,
+        last_synced     TIMESTAMPTZ,
+        last_error      TEXT,
+        created_at      TIMESTAMPTZ NOT NULL DEFAULT NOW()
+    )",
+    "CREATE UNIQUE INDEX IF NOT EXISTS cohort_members_platform_handle_key ON cohort_members(platform, LOWER(handle))",
+    // One row per member per day; solved_total is the count at the day's last sync
+    "CREATE TABLE IF NOT EXISTS cohort_progress (
+        member_id       TEXT NOT NULL REFERENCES cohort_members(id) ON DELETE CASCADE,
+        date            DATE NOT NULL,
+        solved_total    INTEGER NOT NULL,
+        synced_at       TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+        PRIMARY KEY (member_id, date)
+    )",
+
+    // ─── Goal Dependencies (goal_id is blocked until depends_on_id completes) ─
+    "CREATE TABLE IF NOT EXISTS goal_dependencies (
+        goal_id         TEXT NOT NULL REFERENCES unified_goals(id) ON DELETE CASCADE,
+        depends_on_id   TEXT NOT NULL REFERENCES unified_goals(id) ON DELETE CASCADE,
+        created_at      TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+        PRIMARY KEY (goal_id, depends_on_id),
+        CONSTRAINT goal_dependencies_no_self CHECK (goal_id <> depends_on_id)
+    )",
+    "CREATE INDEX IF NOT EXISTS idx_goal_dependencies_depends_on ON goal_dependencies(depends_on_id)",
+
+    // ─── CF friend sync cursors (sync_cf_friends) ───────────────────
+    // Creation time (unix seconds) of the newest submission seen; NULL until the first sync
+    "ALTER TABLE cf_friends ADD COLUMN IF NOT EXISTS sync_cursor BIGINT",
+    "ALTER TABLE cf_friends ADD COLUMN IF NOT EXISTS last_full_sync TIMESTAMPTZ",
+
+    // ─── Category inference review queue (low-confidence guesses) ───
+    "CREATE TABLE IF NOT EXISTS activity_category_reviews (
+        activity_id           TEXT PRIMARY KEY REFERENCES pos_activities(id) ON DELETE CASCADE,
+        suggested_category    TEXT NOT NULL,
+        suggested_productive  BOOLEAN NOT NULL,
+        confidence            DOUBLE PRECISION NOT NULL,
+        source                TEXT NOT NULL,
+        created_at            TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+        resolved_at           TIMESTAMPTZ
+    )",
+    "CREATE INDEX IF NOT EXISTS idx_activity_category_reviews_pending ON activity_category_reviews(created_at) WHERE resolved_at IS NULL",
+
+    // ─── Notifications (mutes / quiet hours per category, sent-reminder log) ─
+    "CREATE TABLE IF NOT EXISTS notification_preferences (
+        category     TEXT PRIMARY KEY,
+        muted        BOOLEAN NOT NULL DEFAULT FALSE,
+        quiet_start  TIME,
+        quiet_end    TIME,
+        updated_at   TIMESTAMPTZ NOT NULL DEFAULT NOW()
+    )",
+    "CREATE TABLE IF NOT EXISTS notification_log (
+        key          TEXT PRIMARY KEY,
+        category     TEXT NOT NULL,
+        sent_at      TIMESTAMPTZ NOT NULL DEFAULT NOW()
+    )",
+
+    // ─── Codeforces contest calendar ────────────────────────────────
+    "CREATE TABLE IF NOT EXISTS cf_contests (
+        id                BIGINT PRIMARY KEY,
+        name              TEXT NOT NULL,
+        kind              TEXT NOT NULL,
+        phase             TEXT NOT NULL,
+        start_time        TIMESTAMPTZ NOT NULL,
+        duration_seconds  BIGINT NOT NULL,
+        goal_id           TEXT REFERENCES unified_goals(id) ON DELETE SET NULL,
+        fetched_at        TIMESTAMPTZ NOT NULL DEFAULT NOW()
+    )",
+    "CREATE INDEX IF NOT EXISTS idx_cf_contests_start ON cf_contests(start_time)",
+
+    // ─── Ladder problems unique per ladder (re-imports are idempotent) ─
+    "DELETE FROM cf_ladder_problems a USING cf_ladder_problems b
+        WHERE a.ladder_id = b.ladder_id AND a.problem_id = b.problem_id
+          AND (a.created_at, a.id) > (b.created_at, b.id)",
+    "CREATE UNIQUE INDEX IF NOT EXISTS uq_ladder_problem ON cf_ladder_problems(ladder_id, problem_id)",
+
+    // ─── CF problem notes (approach/mistakes markdown, manual editorial link) ─
+    "CREATE TABLE IF NOT EXISTS cf_problem_notes (
+        problem_id      TEXT PRIMARY KEY,
+        markdown        TEXT NOT NULL DEFAULT '',
+        editorial_url   TEXT,
+        created_at      TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+        updated_at      TIMESTAMPTZ NOT NULL DEFAULT NOW()
+    )",
+
+    // ─── Saved filters (named goal / knowledge queries) ─────────────
+    "CREATE TABLE IF NOT EXISTS saved_filters (
+        name        TEXT PRIMARY KEY,
+        scope       TEXT NOT NULL CHECK (scope IN ('goals', 'knowledge')),
+        filters     JSONB NOT NULL,
+        created_at  TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+        updated_at  TIMESTAMPTZ NOT NULL DEFAULT NOW()
+    )",
+
+    // ─── Scrape cooldowns (per-platform, survive restarts) ──────────
+    "CREATE TABLE IF NOT EXISTS scrape_cooldowns (
+        platform         TEXT PRIMARY KEY,
+        last_started_at  TIMESTAMPTZ NOT NULL,
+        last_finished_at TIMESTAMPTZ,
+        last_ok          BOOLEAN,
+        next_allowed_at  TIMESTAMPTZ NOT NULL
+    )",
+
+    // ─── Capacity profile (working window + routines per weekday) ───
+    "CREATE TABLE IF NOT EXISTS capacity_profile (
+        weekday         SMALLINT PRIMARY KEY CHECK (weekday BETWEEN 0 AND 6),
+        work_start      TIME NOT NULL,
+        work_end        TIME NOT NULL,
+        routine_blocks  JSONB NOT NULL DEFAULT '[]',
+        updated_at      TIMESTAMPTZ NOT NULL DEFAULT NOW()
+    )",
+
+    // ─── Delta sync (submission watermark, hard-delete tombstones) ──
+    "ALTER TABLE pos_submissions ADD COLUMN IF NOT EXISTS updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()",
+    "CREATE TABLE IF NOT EXISTS sync_tombstones (
+        entity      TEXT NOT NULL,
+        id          TEXT NOT NULL,
+        deleted_at  TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+        PRIMARY KEY (entity, id)
+    )",
+    "CREATE INDEX IF NOT EXISTS idx_sync_tombstones_deleted ON sync_tombstones(deleted_at)",
+    "CREATE INDEX IF NOT EXISTS idx_unified_goals_updated ON unified_goals(updated_at)",
+    "CREATE INDEX IF NOT EXISTS idx_pos_activities_updated ON pos_activities(updated_at)",
+    "CREATE INDEX IF NOT EXISTS idx_kb_items_updated ON knowledge_items(updated_at)",
+    "CREATE INDEX IF NOT EXISTS idx_pos_submissions_updated ON pos_submissions(updated_at)",
+
+    // ─── Captures (note-bound question/answer captures) ─────────────
+    "CREATE TABLE IF NOT EXISTS captures (
+        id                 TEXT PRIMARY KEY,
+        role               TEXT NOT NULL,
+        content            TEXT NOT NULL,
+        answer_id          TEXT UNIQUE REFERENCES captures(id) ON DELETE SET NULL,
+        knowledge_item_id  TEXT REFERENCES knowledge_items(id) ON DELETE SET NULL,
+        created_at         TIMESTAMPTZ NOT NULL DEFAULT NOW()
+    )",
+    "CREATE INDEX IF NOT EXISTS idx_captures_created ON captures(created_at DESC)",
+
+    // ─── Stuck log ("I'm stuck" button) ─────────────────────────────
+    "CREATE TABLE IF NOT EXISTS stuck_logs (
+        id               TEXT PRIMARY KEY,
+        problem_id       TEXT NOT NULL,
+        minutes_spent    INTEGER NOT NULL,
+        blockers         TEXT[] NOT NULL DEFAULT '{}',
+        revisit_goal_id  TEXT,
+        quest_item_id    TEXT,
+        created_at       TIMESTAMPTZ NOT NULL DEFAULT NOW()
+    )",
+    "CREATE INDEX IF NOT EXISTS idx_stuck_logs_problem ON stuck_logs(problem_id)",
+
+    // ─── Milestone goal rollup (metric progress of child goals) ─────
+    "ALTER TABLE goal_periods ADD COLUMN IF NOT EXISTS goal_rollup INTEGER NOT NULL DEFAULT 0",
+
+    // ─── Drafts (unsaved editor state per entity and window) ────────
+    "CREATE TABLE IF NOT EXISTS drafts (
+        entity        TEXT NOT NULL,
+        window_label  TEXT NOT NULL,
+        payload       JSONB NOT NULL,
+        updated_at    TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+        PRIMARY KEY (entity, window_label)
+    )",
+    "CREATE INDEX IF NOT EXISTS idx_drafts_updated ON drafts(updated_at)",
+
+    // ─── Streak freeze tokens ───────────────────────────────────────
+    "CREATE TABLE IF NOT EXISTS streak_freeze_state (
+        kind               TEXT PRIMARY KEY,
+        tokens             INTEGER NOT NULL DEFAULT 0,
+        run_days           INTEGER NOT NULL DEFAULT 0,
+        processed_through  DATE NOT NULL,
+        updated_at         TIMESTAMPTZ NOT NULL DEFAULT NOW()
+    )",
+    "CREATE TABLE IF NOT EXISTS streak_freeze_events (
+        id            TEXT PRIMARY KEY,
+        kind          TEXT NOT NULL,
+        event         TEXT NOT NULL CHECK (event IN ('earned', 'spent')),
+        day           DATE NOT NULL,
+        tokens_after  INTEGER NOT NULL,
+        created_at    TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+        UNIQUE (kind, event, day)
+    )",
+
+    // ─── Timed ladder sessions ──────────────────────────────────────
+    "CREATE TABLE IF NOT EXISTS ladder_sessions (
+        id               TEXT PRIMARY KEY,
+        ladder_id        TEXT NOT NULL REFERENCES cf_ladders(id) ON DELETE CASCADE,
+        planned_minutes  INTEGER NOT NULL,
+        created_at       TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+        ended_at         TIMESTAMPTZ
+    )",
+    "CREATE INDEX IF NOT EXISTS idx_ladder_sessions_ladder ON ladder_sessions(ladder_id, created_at DESC)",
+    "CREATE TABLE IF NOT EXISTS ladder_session_items (
+        id              TEXT PRIMARY KEY,
+        session_id      TEXT NOT NULL REFERENCES ladder_sessions(id) ON DELETE CASCADE,
+        problem_id      TEXT NOT NULL,
+        problem_name    TEXT NOT NULL,
+        problem_url     TEXT NOT NULL,
+        online_judge    TEXT NOT NULL,
+        difficulty      INTEGER,
+        position        INTEGER NOT NULL,
+        budget_minutes  INTEGER NOT NULL,
+        outcome         TEXT CHECK (outcome IN ('solved', 'skipped', 'gave_up')),
+        actual_minutes  INTEGER,
+        recorded_at     TIMESTAMPTZ
+    )",
+    "CREATE INDEX IF NOT EXISTS idx_ladder_session_items_session ON ladder_session_items(session_id, position)",
+
+    // ─── GitHub shadow backfill (one row per backfilled repo-day) ───
+    "CREATE TABLE IF NOT EXISTS github_backfill_days (
+        day           DATE NOT NULL,
+        repo          TEXT NOT NULL,
+        commit_count  INTEGER NOT NULL,
+        activity_id   TEXT REFERENCES pos_activities(id) ON DELETE SET NULL,
+        created_at    TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+        PRIMARY KEY (day, repo)
+    )",
+
+    // ─── Effort ledger (points per source row; rates per source) ────
+    "CREATE TABLE IF NOT EXISTS effort_ledger (
+        id          TEXT PRIMARY KEY,
+        source      TEXT NOT NULL CHECK (source IN ('activity', 'solve', 'review', 'commit')),
+        source_id   TEXT NOT NULL,
+        day         DATE NOT NULL,
+        units       DOUBLE PRECISION NOT NULL,
+        points      DOUBLE PRECISION NOT NULL,
+        detail      TEXT,
+        created_at  TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+        UNIQUE (source, source_id)
+    )",
+    "CREATE INDEX IF NOT EXISTS idx_effort_ledger_day ON effort_ledger(day)",
+    "CREATE TABLE IF NOT EXISTS effort_point_rules (
+        source      TEXT PRIMARY KEY,
+        points      DOUBLE PRECISION NOT NULL,
+        updated_at  TIMESTAMPTZ NOT NULL DEFAULT NOW()
+    )",
+    // Commit count each repo has been credited up to; seeded without points
+    "CREATE TABLE IF NOT EXISTS effort_commit_baselines (
+        repo        TEXT PRIMARY KEY,
+        commits     INTEGER NOT NULL,
+        updated_at  TIMESTAMPTZ NOT NULL DEFAULT NOW()
+    )",
+
+
+    // ─── Midnight-split continuation rows point at their first segment ─
+    "ALTER TABLE pos_activities ADD COLUMN IF NOT EXISTS split_from TEXT",
+    "CREATE INDEX IF NOT EXISTS idx_pos_activities_split_from ON pos_activities(split_from) WHERE split_from IS NOT NULL",
 ];
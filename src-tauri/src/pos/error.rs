@@ -8,6 +8,31 @@ pub enum PosError {
     NotFound(String),
     InvalidInput(String),
     External(String),
+    /// HTTP bridge: missing/revoked token or insufficient scope
+    Unauthorized(String),
+    /// HTTP bridge: per-token request limit reached
+    RateLimited(String),
+    /// Optimistic concurrency: the row changed since the client read it
+    Conflict(Box<ConflictDetail>),
+}
+
+/// Payload of PosError::Conflict, carrying the latest row so the client can merge
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConflictDetail {
+    pub entity: String,
+    pub id: String,
+    pub current: serde_json::Value,
+}
+
+impl PosError {
+    pub fn conflict<T: Serialize>(entity: &str, id: &str, current: &T) -> Self {
+        PosError::Conflict(Box::new(ConflictDetail {
+            entity: entity.to_string(),
+            id: id.to_string(),
+            current: serde_json::to_value(current).unwrap_or(serde_json::Value::Null),
+        }))
+    }
 }
 
 pub type PosResult<T> = std::result::Result<T, PosError>;
@@ -19,6 +44,9 @@ impl std::fmt::Display for PosError {
             PosError::NotFound(msg) => write!(f, "Not found: {}", msg),
             PosError::InvalidInput(msg) => write!(f, "Invalid input: {}", msg),
             PosError::External(msg) => write!(f, "External service error: {}", msg),
+            PosError::Unauthorized(msg) => write!(f, "Unauthorized: {}", msg),
+            PosError::RateLimited(msg) => write!(f, "Rate limited: {}", msg),
+            PosError::Conflict(c) => write!(f, "Conflict: {} {} was modified by another client", c.entity, c.id),
         }
     }
 }
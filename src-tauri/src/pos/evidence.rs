@@ -0,0 +1,182 @@
+// Activity evidence: screenshots and links attached to an activity as proof of what
+// was produced. Screenshots are copied into the app data dir so the original can move.
+
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager, State};
+
+use crate::PosDb;
+use super::error::{PosError, PosResult, db_context};
+use super::utils::gen_id;
+
+const MAX_SCREENSHOT_BYTES: u64 = 20 * 1024 * 1024;
+const IMAGE_EXTENSIONS: &[&str] = &["png", "jpg", "jpeg", "webp", "gif"];
+
+// ─── Types ──────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct ActivityEvidenceRow {
+    pub id: String,
+    pub activity_id: String,
+    pub kind: String,              // screenshot | link
+    pub url: Option<String>,       // link target
+    pub file_path: Option<String>, // stored screenshot copy
+    pub caption: Option<String>,
+    pub size_bytes: Option<i64>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// `screenshot`: `source` is a local image path. `link`: `source` is an http(s) URL.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EvidencePayload {
+    pub source: String,
+    pub caption: Option<String>,
+}
+
+// ─── Helpers ────────────────────────────────────────────────────────
+
+fn evidence_dir(app: &AppHandle) -> PosResult<PathBuf> {
+    let dir = app.path().app_data_dir()
+        .map_err(|e| PosError::External(format!("No app data dir: {}", e)))?
+        .join("evidence");
+    std::fs::create_dir_all(&dir)
+        .map_err(|e| PosError::External(format!("Failed to create evidence dir: {}", e)))?;
+    Ok(dir)
+}
+
+/// Copy the screenshot into the evidence dir, returning (stored path, size)
+fn store_screenshot(app: &AppHandle, id: &str, source: &str) -> PosResult<(String, i64)> {
+    let src = Path::new(source);
+    let ext = src.extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_lowercase())
+        .filter(|e| IMAGE_EXTENSIONS.contains(&e.as_str()))
+        .ok_or_else(|| PosError::InvalidInput(format!(
+            "Screenshot must be one of: {}", IMAGE_EXTENSIONS.join(", ")
+        )))?;
+
+    let meta = std::fs::metadata(src)
+        .map_err(|e| PosError::InvalidInput(format!("Cannot read '{}': {}", source, e)))?;
+    if !meta.is_file() || meta.len() > MAX_SCREENSHOT_BYTES {
+        return Err(PosError::InvalidInput("Screenshot must be a file of at most 20 MB".into()));
+    }
+
+    let dest = evidence_dir(app)?.join(format!("{}.{}", id, ext));
+    std::fs::copy(src, &dest)
+        .map_err(|e| PosError::External(format!("Failed to store screenshot: {}", e)))?;
+    Ok((dest.to_string_lossy().into_owned(), meta.len() as i64))
+}
+
+// ─── Commands ───────────────────────────────────────────────────────
+
+#[tauri::command]
+pub async fn attach_evidence_to_activity(
+    app: AppHandle,
+    db: State<'_, PosDb>,
+    activity_id: String,
+    kind: String,
+    payload: EvidencePayload,
+) -> PosResult<ActivityEvidenceRow> {
+    let pool = &db.0;
+
+    let exists: bool = sqlx::query_scalar("SELECT EXISTS (SELECT 1 FROM pos_activities WHERE id = $1)")
+        .bind(&activity_id)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| db_context("check activity", e))?;
+    if !exists {
+        return Err(PosError::NotFound(format!("Activity {}", activity_id)));
+    }
+
+    let id = gen_id();
+    let source = payload.source.trim();
+    let (url, file_path, size_bytes) = match kind.as_str() {
+        "link" => {
+            if !(source.starts_with("http://") || source.starts_with("https://")) {
+                return Err(PosError::InvalidInput("Link evidence must be an http(s) URL".into()));
+            }
+            (Some(source.to_string()), None, None)
+        }
+        "screenshot" => {
+            let (path, size) = store_screenshot(&app, &id, source)?;
+            (None, Some(path), Some(size))
+        }
+        other => return Err(PosError::InvalidInput(format!(
+            "Unknown evidence kind '{}'. Expected screenshot or link", other
+        ))),
+    };
+
+    let row = sqlx::query_as::<_, ActivityEvidenceRow>(
+        r#"INSERT INTO activity_evidence (id, activity_id, kind, url, file_path, caption, size_bytes, created_at)
+           VALUES ($1, $2, $3, $4, $5, $6, $7, NOW())
+           RETURNING id, activity_id, kind, url, file_path, caption, size_bytes, created_at"#
+    )
+    .bind(&id)
+    .bind(&activity_id)
+    .bind(&kind)
+    .bind(&url)
+    .bind(&file_path)
+    .bind(payload.caption.as_deref().map(str::trim).filter(|c| !c.is_empty()))
+    .bind(size_bytes)
+    .fetch_one(pool)
+    .await;
+
+    match row {
+        Ok(row) => {
+            log::info!("[EVIDENCE] Attached {} to activity {}", kind, activity_id);
+            Ok(row)
+        }
+        Err(e) => {
+            // Don't leave an orphaned copy behind
+            if let Some(path) = &file_path {
+                let _ = std::fs::remove_file(path);
+            }
+            Err(db_context("insert activity_evidence", e))
+        }
+    }
+}
+
+#[tauri::command]
+pub async fn get_activity_evidence(
+    db: State<'_, PosDb>,
+    id: String,
+) -> PosResult<Vec<ActivityEvidenceRow>> {
+    sqlx::query_as::<_, ActivityEvidenceRow>(
+        r#"SELECT id, activity_id, kind, url, file_path, caption, size_bytes, created_at
+           FROM activity_evidence WHERE activity_id = $1 ORDER BY created_at ASC"#
+    )
+    .bind(&id)
+    .fetch_all(&db.0)
+    .await
+    .map_err(|e| db_context("get_activity_evidence", e))
+}
+
+#[tauri::command]
+pub async fn delete_activity_evidence(
+    db: State<'_, PosDb>,
+    evidence_id: String,
+) -> PosResult<()> {
+    let file_path: Option<Option<String>> = sqlx::query_scalar(
+        "DELETE FROM activity_evidence WHERE id = $1 RETURNING file_path"
+    )
+    .bind(&evidence_id)
+    .fetch_optional(&db.0)
+    .await
+    .map_err(|e| db_context("delete_activity_evidence", e))?;
+
+    match file_path {
+        None => Err(PosError::NotFound(format!("Evidence {}", evidence_id))),
+        Some(path) => {
+            if let Some(path) = path {
+                if let Err(e) = std::fs::remove_file(&path) {
+                    log::warn!("[EVIDENCE] Could not remove {}: {}", path, e);
+                }
+            }
+            Ok(())
+        }
+    }
+}
@@ -7,7 +7,11 @@ use serde::{Deserialize, Serialize};
 use tauri::State;
 
 use crate::PosDb;
+use super::cache::{self, CachePolicy};
 use super::error::{PosError, PosResult, db_context};
+use super::scrapers::build_http_client;
+use super::scrapers::github::db::update_additional_user_stats;
+use super::scrapers::github::fetcher::refresh_contribution_stats;
 
 // ─── Types ──────────────────────────────────────────────────────────
 
@@ -60,6 +64,26 @@ pub struct GitHubUserStats {
     pub synced_at: DateTime<Utc>,
 }
 
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LanguageShare {
+    pub language: String,
+    pub bytes: i64,
+    /// Fraction of all bytes counted
+    pub share: f64,
+    pub repos: i64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitHubLanguageStats {
+    pub username: String,
+    pub total_bytes: i64,
+    pub languages: Vec<LanguageShare>,
+    /// Repos synced before language sizes were fetched (or with no detected code)
+    pub repos_without_languages: i64,
+}
+
 // ─── Commands ───────────────────────────────────────────────────────
 
 /// Get GitHub repositories with optional filters
@@ -159,21 +183,15 @@ pub async fn get_github_repositories(
     Ok(repos)
 }
 
-/// Get GitHub user statistics
-#[tauri::command]
-pub async fn get_github_user_stats(
-    db: State<'_, PosDb>,
-    username: String,
-) -> PosResult<GitHubUserStats> {
-    let pool = &db.0;
-    
+/// Stored stats row for `username` (written by scrape_github)
+async fn load_user_stats(pool: &sqlx::PgPool, username: &str) -> PosResult<GitHubUserStats> {
     let row = sqlx::query(
         r#"SELECT username, total_repos, total_commits, total_prs, total_issues, total_reviews,
                   total_stars_received, languages_breakdown, current_streak_days, longest_streak_days,
                   contributions_by_year, top_repos, synced_at
            FROM github_user_stats WHERE username = $1"#
     )
-    .bind(&username)
+    .bind(username)
     .fetch_one(pool)
     .await
     .map_err(|e| db_context("Fetch user stats", e))?;
@@ -196,6 +214,81 @@ pub async fn get_github_user_stats(
     })
 }
 
+/// Get GitHub user statistics. For the configured account the contribution totals are
+/// re-fetched from GitHub once a day (stale-while-revalidate); other usernames read the
+/// row from the last sync.
+#[tauri::command]
+pub async fn get_github_user_stats(
+    db: State<'_, PosDb>,
+    config: State<'_, crate::PosConfig>,
+    username: String,
+    force_refresh: Option<bool>,
+) -> PosResult<GitHubUserStats> {
+    let token = config.0.github_token.clone()
+        .filter(|_| config.0.github_username.as_deref() == Some(username.as_str()));
+    let Some(token) = token else {
+        return load_user_stats(&db.0, &username).await;
+    };
+
+    let pool = db.0.clone();
+    let resource = format!("{}:{}", cache::USER_STATS, username);
+    let policy = CachePolicy::default()
+        .revalidate_in_background()
+        .force(force_refresh.unwrap_or(false));
+    cache::get_or_fetch(&db.0, "github", &resource, policy, move || async move {
+        let client = build_http_client();
+        refresh_contribution_stats(&pool, &client, &token, &username).await?;
+        update_additional_user_stats(&pool, &username).await?;
+        load_user_stats(&pool, &username).await
+    }).await
+}
+
+/// Bytes per language summed across the user's synced repos, largest first.
+/// Forks are skipped unless `include_forks` is set, since their code is mostly upstream's.
+#[tauri::command]
+pub async fn get_github_language_stats(
+    db: State<'_, PosDb>,
+    username: String,
+    include_forks: Option<bool>,
+) -> PosResult<GitHubLanguageStats> {
+    let pool = &db.0;
+    let include_forks = include_forks.unwrap_or(false);
+
+    let (rows, repos_without_languages) = tokio::try_join!(
+        sqlx::query_as::<_, (String, i64, i64)>(
+            r#"SELECT l.key, SUM(l.value::bigint)::bigint, COUNT(*)
+               FROM github_repositories r
+               CROSS JOIN LATERAL jsonb_each_text(r.languages) l
+               WHERE r.username = $1 AND ($2 OR NOT COALESCE(r.is_fork, FALSE))
+                 AND jsonb_typeof(r.languages) = 'object'
+               GROUP BY l.key
+               ORDER BY 2 DESC, l.key"#
+        )
+        .bind(&username)
+        .bind(include_forks)
+        .fetch_all(pool),
+        sqlx::query_scalar::<_, i64>(
+            r#"SELECT COUNT(*) FROM github_repositories
+               WHERE username = $1 AND ($2 OR NOT COALESCE(is_fork, FALSE))
+                 AND (languages IS NULL OR languages = '{}'::jsonb)"#
+        )
+        .bind(&username)
+        .bind(include_forks)
+        .fetch_one(pool),
+    )
+    .map_err(|e| db_context("get_github_language_stats", e))?;
+
+    let total_bytes: i64 = rows.iter().map(|(_, bytes, _)| bytes).sum();
+    let languages = rows.into_iter().map(|(language, bytes, repos)| LanguageShare {
+        language,
+        bytes,
+        share: if total_bytes > 0 { bytes as f64 / total_bytes as f64 } else { 0.0 },
+        repos,
+    }).collect();
+
+    Ok(GitHubLanguageStats { username, total_bytes, languages, repos_without_languages })
+}
+
 // ─── Lightweight repo info fetch (for ProjectLogPage) ───────────────
 
 use serde::Deserialize as DeserializeLocal;
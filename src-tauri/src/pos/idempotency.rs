@@ -0,0 +1,133 @@
+// Idempotency keys for mutating commands.
+// The frontend may retry a write after a network hiccup; when the retry carries the same
+// key, the stored result of the first call is returned instead of writing again.
+
+use std::future::Future;
+
+use serde::{de::DeserializeOwned, Serialize};
+use sqlx::PgPool;
+
+use super::error::{PosError, PosResult, db_context};
+
+const KEY_TTL_HOURS: i32 = 24;
+/// A reservation without a result older than this is treated as abandoned (crashed mid-write)
+const PENDING_TIMEOUT_SECS: i64 = 60;
+
+#[derive(sqlx::FromRow)]
+struct KeyRow {
+    scope: String,
+    result: Option<serde_json::Value>,
+    pending_secs: f64,
+    expired: bool,
+}
+
+/// Try to claim `key` for `scope`. Ok(None) means we own it and should run the write;
+/// Ok(Some(value)) is the stored result of an earlier call.
+async fn claim(pool: &PgPool, scope: &str, key: &str) -> PosResult<Option<serde_json::Value>> {
+    for _ in 0..2 {
+        let inserted = sqlx::query(
+            r#"INSERT INTO idempotency_keys (key, scope, result, created_at, expires_at)
+               VALUES ($1, $2, NULL, NOW(), NOW() + make_interval(hours => $3))
+               ON CONFLICT (key) DO NOTHING"#
+        )
+        .bind(key)
+        .bind(scope)
+        .bind(KEY_TTL_HOURS)
+        .execute(pool)
+        .await
+        .map_err(|e| db_context("claim idempotency key", e))?;
+        if inserted.rows_affected() == 1 {
+            return Ok(None);
+        }
+
+        let Some(row) = sqlx::query_as::<_, KeyRow>(
+            r#"SELECT scope, result,
+                      EXTRACT(EPOCH FROM (NOW() - created_at))::float8 AS pending_secs,
+                      expires_at < NOW() AS expired
+               FROM idempotency_keys WHERE key = $1"#
+        )
+        .bind(key)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| db_context("read idempotency key", e))? else {
+            continue; // deleted between the two statements
+        };
+
+        if row.expired {
+            sqlx::query("DELETE FROM idempotency_keys WHERE key = $1 AND expires_at < NOW()")
+                .bind(key)
+                .execute(pool)
+                .await
+                .map_err(|e| db_context("expire idempotency key", e))?;
+            continue;
+        }
+        if row.scope != scope {
+            return Err(PosError::InvalidInput(format!(
+                "Idempotency key already used for {}", row.scope
+            )));
+        }
+        if let Some(result) = row.result {
+            log::info!("[IDEMPOTENCY] Replaying {} for key {}", scope, key);
+            return Ok(Some(result));
+        }
+        if row.pending_secs < PENDING_TIMEOUT_SECS as f64 {
+            return Err(PosError::InvalidInput("A request with this idempotency key is still in progress".into()));
+        }
+
+        // Abandoned reservation: take it over
+        sqlx::query("UPDATE idempotency_keys SET created_at = NOW() WHERE key = $1")
+            .bind(key)
+            .execute(pool)
+            .await
+            .map_err(|e| db_context("take over idempotency key", e))?;
+        return Ok(None);
+    }
+    Err(PosError::InvalidInput("Could not claim idempotency key, retry".into()))
+}
+
+/// Run `write` at most once per `key`. Without a key it just runs. A failed write
+/// releases the key so the client can retry it.
+pub async fn idempotent<T, F>(pool: &PgPool, scope: &str, key: Option<String>, write: F) -> PosResult<T>
+where
+    T: Serialize + DeserializeOwned,
+    F: Future<Output = PosResult<T>>,
+{
+    let Some(key) = key.filter(|k| !k.trim().is_empty()) else {
+        return write.await;
+    };
+
+    if let Some(stored) = claim(pool, scope, &key).await? {
+        return serde_json::from_value(stored)
+            .map_err(|e| PosError::InvalidInput(format!("Stored result for key is unreadable: {}", e)));
+    }
+
+    match write.await {
+        Ok(result) => {
+            let value = serde_json::to_value(&result)
+                .map_err(|e| PosError::InvalidInput(format!("Failed to serialize result: {}", e)))?;
+            sqlx::query("UPDATE idempotency_keys SET result = $1 WHERE key = $2")
+                .bind(&value)
+                .bind(&key)
+                .execute(pool)
+                .await
+                .map_err(|e| db_context("store idempotent result", e))?;
+            Ok(result)
+        }
+        Err(e) => {
+            let _ = sqlx::query("DELETE FROM idempotency_keys WHERE key = $1")
+                .bind(&key)
+                .execute(pool)
+                .await;
+            Err(e)
+        }
+    }
+}
+
+/// Drop expired keys. Called once at startup after table init.
+pub async fn purge_expired_keys(pool: &PgPool) -> PosResult<u64> {
+    let res = sqlx::query("DELETE FROM idempotency_keys WHERE expires_at < NOW()")
+        .execute(pool)
+        .await
+        .map_err(|e| db_context("purge idempotency keys", e))?;
+    Ok(res.rows_affected())
+}
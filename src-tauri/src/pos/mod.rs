@@ -1,11 +1,20 @@
 pub mod activities;
+pub mod api_tokens;
+pub mod cache;
 pub mod config;
+pub mod confirm;
 pub mod db;
 pub mod error;
+pub mod evidence;
 pub mod github;
+pub mod idempotency;
+pub mod problem_url;
+pub mod purge;
 pub mod retry;
 pub mod scrapers;
 pub mod scraper;
 pub mod shadow;
 pub mod submissions;
+pub mod timezone;
+pub mod units;
 pub mod utils;
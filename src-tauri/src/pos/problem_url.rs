@@ -0,0 +1,174 @@
+// Problem URL canonicalization.
+// Codeforces is reachable through several mirrors and URL shapes, and LeetCode links
+// pick up extra path segments. Ladders, categories and friend submissions are joined on
+// problem_url, so every ingest path stores the one form produced here.
+
+use sqlx::PgPool;
+
+const CODEFORCES_HOSTS: &[&str] = &[
+    "codeforces.com", "www.codeforces.com",
+    "m1.codeforces.com", "m2.codeforces.com", "m3.codeforces.com",
+    "codeforces.ml", "codeforces.net", "codeforc.es",
+];
+const LEETCODE_HOSTS: &[&str] = &["leetcode.com", "www.leetcode.com"];
+
+/// (table, url column) pairs rewritten by the startup migration
+const URL_COLUMNS: &[(&str, &str)] = &[
+    ("cf_ladder_problems", "problem_url"),
+    ("cf_category_problems", "problem_url"),
+    ("cf_friend_submissions", "problem_url"),
+    ("problemset_cache", "url"),
+];
+
+/// Canonical form of a problem URL:
+/// - Codeforces: `https://codeforces.com/problemset/problem/{contest}/{INDEX}`
+///   (contest-style links included), gym links as `https://codeforces.com/gym/{id}/problem/{INDEX}`
+/// - LeetCode: `https://leetcode.com/problems/{slug}/`
+///
+/// Other Codeforces pages only get their host and scheme normalized; URLs from any other
+/// site are returned trimmed but otherwise untouched.
+pub fn canonical_problem_url(url: &str) -> String {
+    let trimmed = url.trim();
+    let rest = trimmed.strip_prefix("https://")
+        .or_else(|| trimmed.strip_prefix("http://"))
+        .unwrap_or(trimmed);
+    let rest = rest.split(['?', '#']).next().unwrap_or("");
+    let (host, path) = rest.split_once('/').unwrap_or((rest, ""));
+    let host = host.to_lowercase();
+    let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+
+    if CODEFORCES_HOSTS.contains(&host.as_str()) {
+        return match segments.as_slice() {
+            ["problemset", "problem", contest, index, ..] | ["contest", contest, "problem", index, ..] => {
+                format!("https://codeforces.com/problemset/problem/{}/{}", contest, index.to_uppercase())
+            }
+            ["gym", contest, "problem", index, ..] => {
+                format!("https://codeforces.com/gym/{}/problem/{}", contest, index.to_uppercase())
+            }
+            _ => format!("https://codeforces.com/{}", segments.join("/")),
+        };
+    }
+
+    if LEETCODE_HOSTS.contains(&host.as_str()) {
+        if let ["problems", slug, ..] = segments.as_slice() {
+            return format!("https://leetcode.com/problems/{}/", slug.to_lowercase());
+        }
+    }
+
+    trimmed.to_string()
+}
+
+/// Normalized problem ID (as stored in `pos_submissions.problem_id`) for a problem URL:
+/// `cf-1520A`, `leetcode-two-sum`, `atcoder-abc300_a`. None for anything that isn't a
+/// recognizable problem page.
+pub fn problem_id_from_url(url: &str) -> Option<String> {
+    let canonical = canonical_problem_url(url);
+    if let Some(rest) = canonical.strip_prefix("https://codeforces.com/") {
+        let segments: Vec<&str> = rest.split('/').collect();
+        return match segments.as_slice() {
+            ["problemset", "problem", contest, index] | ["gym", contest, "problem", index]
+                if contest.chars().all(|c| c.is_ascii_digit()) && !index.is_empty() =>
+            {
+                Some(format!("cf-{}{}", contest, index))
+            }
+            _ => None,
+        };
+    }
+    if let Some(slug) = canonical.strip_prefix("https://leetcode.com/problems/") {
+        let slug = slug.trim_end_matches('/');
+        return (!slug.is_empty()).then(|| format!("leetcode-{}", slug));
+    }
+
+    let rest = canonical.strip_prefix("https://").or_else(|| canonical.strip_prefix("http://"))?;
+    let rest = rest.split(['?', '#']).next().unwrap_or("");
+    let segments: Vec<&str> = rest.split('/').filter(|s| !s.is_empty()).collect();
+    match segments.as_slice() {
+        [host, "contests", _, "tasks", task, ..] if host.eq_ignore_ascii_case("atcoder.jp") => {
+            Some(format!("atcoder-{}", task.to_lowercase()))
+        }
+        _ => None,
+    }
+}
+
+/// Rewrite stored problem URLs to their canonical form.
+/// Runs after DDL on every startup — only touches rows whose URL isn't canonical yet.
+pub async fn canonicalize_existing_problem_urls(pool: &PgPool) -> Result<(), sqlx::Error> {
+    let mut updated = 0u64;
+
+    for (table, column) in URL_COLUMNS {
+        let urls: Vec<String> = sqlx::query_scalar(&format!(
+            "SELECT DISTINCT {col} FROM {table} WHERE {col} ~* '(codeforc|leetcode)'",
+            col = column, table = table
+        ))
+        .fetch_all(pool)
+        .await?;
+
+        let (old, new): (Vec<String>, Vec<String>) = urls.into_iter()
+            .filter_map(|u| {
+                let canonical = canonical_problem_url(&u);
+                (canonical != u).then_some((u, canonical))
+            })
+            .unzip();
+        if old.is_empty() {
+            continue;
+        }
+
+        updated += sqlx::query(&format!(
+            "UPDATE {table} t SET {col} = m.new_url
+             FROM UNNEST($1::text[], $2::text[]) AS m(old_url, new_url)
+             WHERE t.{col} = m.old_url",
+            col = column, table = table
+        ))
+        .bind(&old)
+        .bind(&new)
+        .execute(pool)
+        .await?
+        .rows_affected();
+    }
+
+    if updated > 0 {
+        log::info!("[PROBLEM_URL] Canonicalized {} stored problem URLs", updated);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_codeforces_mirrors_and_shapes() {
+        let canonical = "https://codeforces.com/problemset/problem/1520/A";
+        assert_eq!(canonical_problem_url("https://codeforces.com/problemset/problem/1520/A"), canonical);
+        assert_eq!(canonical_problem_url("http://m1.codeforces.com/problemset/problem/1520/A/"), canonical);
+        assert_eq!(canonical_problem_url("https://codeforces.ml/contest/1520/problem/a"), canonical);
+        assert_eq!(canonical_problem_url(" codeforces.com/contest/1520/problem/A?locale=en "), canonical);
+        assert_eq!(
+            canonical_problem_url("https://codeforc.es/gym/104114/problem/b"),
+            "https://codeforces.com/gym/104114/problem/B"
+        );
+    }
+
+    #[test]
+    fn test_leetcode_and_other_urls() {
+        assert_eq!(
+            canonical_problem_url("https://leetcode.com/problems/two-sum/description/"),
+            "https://leetcode.com/problems/two-sum/"
+        );
+        assert_eq!(
+            canonical_problem_url("http://www.leetcode.com/problems/two-sum"),
+            "https://leetcode.com/problems/two-sum/"
+        );
+        assert_eq!(canonical_problem_url("https://www.spoj.com/problems/TEST/"), "https://www.spoj.com/problems/TEST/");
+    }
+
+    #[test]
+    fn test_problem_id_from_url() {
+        assert_eq!(problem_id_from_url("https://m2.codeforces.com/contest/1354/problem/c1").as_deref(), Some("cf-1354C1"));
+        assert_eq!(problem_id_from_url("https://codeforces.com/gym/104114/problem/B").as_deref(), Some("cf-104114B"));
+        assert_eq!(problem_id_from_url("https://leetcode.com/problems/two-sum/description/").as_deref(), Some("leetcode-two-sum"));
+        assert_eq!(problem_id_from_url("https://atcoder.jp/contests/abc300/tasks/abc300_a").as_deref(), Some("atcoder-abc300_a"));
+        assert_eq!(problem_id_from_url("https://codeforces.com/blog/entry/12345"), None);
+        assert_eq!(problem_id_from_url("https://example.com/problems/x"), None);
+    }
+}
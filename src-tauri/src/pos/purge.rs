@@ -0,0 +1,257 @@
+use serde::Serialize;
+use tauri::State;
+
+use crate::PosDb;
+use super::confirm::{consume_confirmation, request_confirmation, Confirmable, ImpactItem, ImpactReport};
+use super::error::{PosError, PosResult, db_context};
+
+// ─── Response types ─────────────────────────────────────────────────
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PurgedTable {
+    pub table: String,
+    pub deleted: u64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PurgeResult {
+    pub platform: String,
+    pub tables: Vec<PurgedTable>,
+    pub total_deleted: u64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PurgeDeletedResult {
+    pub older_than_days: i64,
+    pub tables: Vec<PurgedTable>,
+    pub total_deleted: u64,
+}
+
+// ─── Per-platform delete plan ───────────────────────────────────────
+
+/// Submission shadow activities of the platform. Matched through their submission (a
+/// shadow ends at its submission's time) rather than by category, since AtCoder shadows
+/// share the codeforces category. Runs before pos_submissions is emptied.
+const SHADOW_DELETE: &str = r#"DELETE FROM pos_activities a
+    WHERE a.is_shadow = TRUE
+      AND EXISTS (SELECT 1 FROM pos_submissions s WHERE s.submitted_time = a.end_time AND s.platform = $1)"#;
+
+/// (table, DELETE statement) pairs. Statements that need the platform name bind it as $1.
+fn purge_plan(platform: &str) -> Option<Vec<(&'static str, &'static str)>> {
+    let plan = match platform {
+        "leetcode" => vec![
+            ("pos_activities", SHADOW_DELETE),
+            ("pos_submissions", "DELETE FROM pos_submissions WHERE platform = $1"),
+            ("pos_user_stats", "DELETE FROM pos_user_stats WHERE platform = $1"),
+            ("platform_cache", "DELETE FROM platform_cache WHERE platform = $1"),
+        ],
+        "codeforces" => vec![
+            // cf_ladder_progress / cf_category_progress are kept: they hold progress marked by
+            // hand, which a re-sync cannot rebuild. So is cf_daily_recommendations (it cascades
+            // to the recommendation history and its done state).
+            ("pos_activities", SHADOW_DELETE),
+            ("pos_submissions", "DELETE FROM pos_submissions WHERE platform = $1"),
+            ("pos_user_stats", "DELETE FROM pos_user_stats WHERE platform = $1"),
+            ("platform_cache", "DELETE FROM platform_cache WHERE platform = $1"),
+        ],
+        "atcoder" => vec![
+            ("pos_activities", SHADOW_DELETE),
+            ("pos_submissions", "DELETE FROM pos_submissions WHERE platform = $1"),
+            ("pos_user_stats", "DELETE FROM pos_user_stats WHERE platform = $1"),
+            ("platform_cache", "DELETE FROM platform_cache WHERE platform = $1"),
+        ],
+        "github" => vec![
+            // Backfilled shadow blocks go first, while github_backfill_days still points at them
+            ("pos_activities", r#"DELETE FROM pos_activities
+                WHERE is_shadow = TRUE
+                  AND id IN (SELECT activity_id FROM github_backfill_days WHERE activity_id IS NOT NULL)"#),
+            ("github_backfill_days", "DELETE FROM github_backfill_days"),
+            ("github_repositories", "DELETE FROM github_repositories"),
+            ("github_user_stats", "DELETE FROM github_user_stats"),
+            ("pos_user_stats", "DELETE FROM pos_user_stats WHERE platform = $1"),
+            ("platform_cache", "DELETE FROM platform_cache WHERE platform = $1"),
+        ],
+        _ => return None,
+    };
+    Some(plan)
+}
+
+/// Derived/cache tables that can be emptied wholesale; everything here is rebuilt by a sync.
+const RESETTABLE_TABLES: &[&str] = &[
+    "dashboard_snapshots",
+    "platform_cache",
+    "pos_streaks",
+    "cf_problem_editorials",
+    "cf_friend_submissions",
+];
+
+/// Tables with a soft-delete `deleted_at` column. Activities first: their metrics and
+/// knowledge links cascade, and milestones only lose the (SET NULL) activity reference.
+const SOFT_DELETE_TABLES: &[&str] = &["pos_activities", "goal_periods", "unified_goals"];
+
+/// Row counts each plan step would delete, for the confirmation impact report
+async fn plan_impact(
+    pool: &sqlx::PgPool,
+    platform: &str,
+    plan: &[(&'static str, &'static str)],
+) -> PosResult<Vec<ImpactItem>> {
+    let mut items = Vec::with_capacity(plan.len());
+    for (table, stmt) in plan {
+        let count_sql = stmt.replacen("DELETE FROM", "SELECT COUNT(*) FROM", 1);
+        let mut q = sqlx::query_scalar::<_, i64>(&count_sql);
+        if stmt.contains("$1") {
+            q = q.bind(platform);
+        }
+        let rows = q.fetch_one(pool).await
+            .map_err(|e| db_context(&format!("count {}", table), e))?;
+        items.push(ImpactItem { target: table.to_string(), rows });
+    }
+    Ok(items)
+}
+
+// ─── Commands ───────────────────────────────────────────────────────
+
+/// Hard-delete everything synced from one platform (submissions, shadow activities,
+/// cached stats, derived progress) in a single transaction. Returns per-table counts.
+/// Two-step: without `confirmation_token` only the impact report and a token are returned.
+#[tauri::command]
+pub async fn purge_platform_data(
+    db: State<'_, PosDb>,
+    platform: String,
+    confirmation_token: Option<String>,
+) -> PosResult<Confirmable<PurgeResult>> {
+    let pool = &db.0;
+    let platform = platform.trim().to_lowercase();
+
+    let plan = purge_plan(&platform).ok_or_else(|| PosError::InvalidInput(format!(
+        "Unknown platform '{}'. Expected leetcode, codeforces, atcoder or github", platform
+    )))?;
+
+    let params = serde_json::json!({ "platform": platform });
+    let Some(token) = confirmation_token else {
+        let items = plan_impact(pool, &platform, &plan).await?;
+        let impact = ImpactReport::new(format!("Permanently delete all {} data", platform), items);
+        return request_confirmation(pool, "purge_platform_data", params, impact).await;
+    };
+    consume_confirmation(pool, &token, "purge_platform_data", &params).await?;
+
+    let mut tx = pool.begin().await.map_err(|e| db_context("purge TX begin", e))?;
+    let mut tables = Vec::with_capacity(plan.len());
+
+    for (table, stmt) in plan {
+        let stmt = crate::delta_sync::with_tombstones(table, stmt);
+        let mut q = sqlx::query(&stmt);
+        if stmt.contains("$1") {
+            q = q.bind(&platform);
+        }
+        let deleted = q.execute(&mut *tx).await
+            .map_err(|e| db_context(&format!("purge {}", table), e))?
+            .rows_affected();
+        tables.push(PurgedTable { table: table.to_string(), deleted });
+    }
+
+    tx.commit().await.map_err(|e| db_context("purge TX commit", e))?;
+
+    let total_deleted = tables.iter().map(|t| t.deleted).sum();
+    log::info!("[PURGE] Removed {} rows of {} data: {:?}", total_deleted, platform, tables);
+    crate::streaks::invalidate(pool, crate::streaks::StreakKind::Solved).await;
+
+    Ok(Confirmable::Executed { result: PurgeResult { platform, tables, total_deleted } })
+}
+
+/// Empty one derived/cache table (see RESETTABLE_TABLES). Two-step like purge_platform_data.
+#[tauri::command]
+pub async fn reset_table(
+    db: State<'_, PosDb>,
+    table: String,
+    confirmation_token: Option<String>,
+) -> PosResult<Confirmable<PurgedTable>> {
+    let pool = &db.0;
+    let table = RESETTABLE_TABLES.iter().copied().find(|t| *t == table.trim())
+        .ok_or_else(|| PosError::InvalidInput(format!(
+            "Table '{}' cannot be reset. Allowed: {}", table, RESETTABLE_TABLES.join(", ")
+        )))?;
+
+    let params = serde_json::json!({ "table": table });
+    let Some(token) = confirmation_token else {
+        let rows: i64 = sqlx::query_scalar(&format!("SELECT COUNT(*) FROM {}", table))
+            .fetch_one(pool)
+            .await
+            .map_err(|e| db_context(&format!("count {}", table), e))?;
+        let impact = ImpactReport::new(
+            format!("Delete every row in {}", table),
+            vec![ImpactItem { target: table.to_string(), rows }],
+        );
+        return request_confirmation(pool, "reset_table", params, impact).await;
+    };
+    consume_confirmation(pool, &token, "reset_table", &params).await?;
+
+    let deleted = sqlx::query(&format!("DELETE FROM {}", table))
+        .execute(pool)
+        .await
+        .map_err(|e| db_context(&format!("reset {}", table), e))?
+        .rows_affected();
+
+    log::info!("[PURGE] Reset {} ({} rows)", table, deleted);
+    Ok(Confirmable::Executed { result: PurgedTable { table: table.to_string(), deleted } })
+}
+
+/// Permanently remove soft-deleted goals, milestones and activities deleted more than
+/// `older_than_days` ago. Two-step like purge_platform_data.
+#[tauri::command]
+pub async fn purge_deleted(
+    db: State<'_, PosDb>,
+    older_than_days: i64,
+    confirmation_token: Option<String>,
+) -> PosResult<Confirmable<PurgeDeletedResult>> {
+    let pool = &db.0;
+    if older_than_days < 0 {
+        return Err(PosError::InvalidInput("older_than_days cannot be negative".into()));
+    }
+    let cutoff = chrono::Utc::now() - chrono::Duration::days(older_than_days);
+
+    let params = serde_json::json!({ "olderThanDays": older_than_days });
+    let Some(token) = confirmation_token else {
+        let mut items = Vec::with_capacity(SOFT_DELETE_TABLES.len());
+        for table in SOFT_DELETE_TABLES {
+            let rows: i64 = sqlx::query_scalar(&format!(
+                "SELECT COUNT(*) FROM {} WHERE deleted_at < $1", table
+            ))
+            .bind(cutoff)
+            .fetch_one(pool)
+            .await
+            .map_err(|e| db_context(&format!("count deleted {}", table), e))?;
+            items.push(ImpactItem { target: table.to_string(), rows });
+        }
+        let impact = ImpactReport::new(
+            format!("Permanently delete items deleted more than {} days ago", older_than_days),
+            items,
+        );
+        return request_confirmation(pool, "purge_deleted", params, impact).await;
+    };
+    consume_confirmation(pool, &token, "purge_deleted", &params).await?;
+
+    let mut tx = pool.begin().await.map_err(|e| db_context("purge TX begin", e))?;
+    let mut tables = Vec::with_capacity(SOFT_DELETE_TABLES.len());
+
+    for table in SOFT_DELETE_TABLES {
+        let stmt = format!("DELETE FROM {} WHERE deleted_at < $1", table);
+        let deleted = sqlx::query(&crate::delta_sync::with_tombstones(table, &stmt))
+            .bind(cutoff)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| db_context(&format!("purge deleted {}", table), e))?
+            .rows_affected();
+        tables.push(PurgedTable { table: table.to_string(), deleted });
+    }
+
+    tx.commit().await.map_err(|e| db_context("purge TX commit", e))?;
+
+    let total_deleted = tables.iter().map(|t| t.deleted).sum();
+    log::info!("[PURGE] Removed {} soft-deleted rows older than {} days: {:?}", total_deleted, older_than_days, tables);
+
+    Ok(Confirmable::Executed { result: PurgeDeletedResult { older_than_days, tables, total_deleted } })
+}
@@ -1,6 +1,18 @@
+use std::collections::HashMap;
 use std::future::Future;
+use std::sync::{Mutex, OnceLock};
 use std::time::Duration;
 
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use super::error::{PosError, PosResult};
+
+/// Consecutive failed calls (after retries) before a host's circuit opens
+const CIRCUIT_FAILURE_THRESHOLD: u32 = 3;
+/// How long an open circuit short-circuits calls to its host
+const CIRCUIT_OPEN_MINUTES: i64 = 10;
+
 /// Retry a database operation with exponential backoff
 /// 
 /// Retries transient errors (connection issues, timeouts) up to max_attempts.
@@ -55,6 +67,160 @@ where
     }
 }
 
+// ─── External calls: backoff + per-host circuit breaker ─────────────
+
+#[derive(Debug, Clone, Copy)]
+pub struct BackoffPolicy {
+    pub max_attempts: u32,
+    pub base_delay_ms: u64,
+    pub max_delay_ms: u64,
+}
+
+impl Default for BackoffPolicy {
+    fn default() -> Self {
+        Self { max_attempts: 3, base_delay_ms: 500, max_delay_ms: 8000 }
+    }
+}
+
+#[derive(Debug, Default, Clone)]
+struct Breaker {
+    consecutive_failures: u32,
+    open_until: Option<DateTime<Utc>>,
+    last_error: Option<String>,
+    last_success_at: Option<DateTime<Utc>>,
+    last_failure_at: Option<DateTime<Utc>>,
+}
+
+fn breakers() -> &'static Mutex<HashMap<String, Breaker>> {
+    static BREAKERS: OnceLock<Mutex<HashMap<String, Breaker>>> = OnceLock::new();
+    BREAKERS.get_or_init(Default::default)
+}
+
+/// Exponential delay for `attempt` (1-based) plus up to 50% jitter, capped at max_delay_ms
+fn backoff_delay(policy: &BackoffPolicy, attempt: u32) -> Duration {
+    let exp = policy.base_delay_ms
+        .saturating_mul(1u64 << (attempt - 1).min(16))
+        .min(policy.max_delay_ms);
+    // Clock nanos are plenty random for spreading retries apart
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.subsec_nanos() as u64)
+        .unwrap_or(0);
+    let jitter = if exp >= 2 { nanos % (exp / 2) } else { 0 };
+    Duration::from_millis((exp + jitter).min(policy.max_delay_ms))
+}
+
+fn check_breaker(host: &str) -> PosResult<()> {
+    let map = breakers().lock().unwrap();
+    if let Some(until) = map.get(host).and_then(|b| b.open_until) {
+        if until > Utc::now() {
+            return Err(PosError::External(format!(
+                "{} is temporarily unavailable (circuit open until {})", host, until.format("%H:%M:%S")
+            )));
+        }
+    }
+    Ok(())
+}
+
+fn record_success(host: &str) {
+    let mut map = breakers().lock().unwrap();
+    let b = map.entry(host.to_string()).or_default();
+    b.consecutive_failures = 0;
+    b.open_until = None;
+    b.last_success_at = Some(Utc::now());
+}
+
+fn record_failure(host: &str, err: &PosError) {
+    let mut map = breakers().lock().unwrap();
+    let b = map.entry(host.to_string()).or_default();
+    let now = Utc::now();
+    b.consecutive_failures += 1;
+    b.last_error = Some(err.to_string());
+    b.last_failure_at = Some(now);
+    // Past the threshold every failure (including a half-open probe) re-opens the circuit
+    if b.consecutive_failures >= CIRCUIT_FAILURE_THRESHOLD {
+        b.open_until = Some(now + chrono::Duration::minutes(CIRCUIT_OPEN_MINUTES));
+        log::error!("[RETRY] Circuit open for {} after {} failed calls", host, b.consecutive_failures);
+    }
+}
+
+/// Run an external call against `host` with exponential backoff and jitter.
+///
+/// Only `PosError::External` (network, HTTP, parse) is retried; other errors mean the
+/// host answered and are returned as-is. A call that still fails after all attempts
+/// counts against the host's circuit breaker; while the circuit is open, calls fail
+/// immediately without touching the network.
+pub async fn with_backoff<T, F, Fut>(host: &str, policy: BackoffPolicy, mut op: F) -> PosResult<T>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = PosResult<T>>,
+{
+    check_breaker(host)?;
+
+    let mut attempt = 0;
+    loop {
+        attempt += 1;
+        match op().await {
+            Ok(value) => {
+                record_success(host);
+                return Ok(value);
+            }
+            Err(e @ PosError::External(_)) => {
+                if attempt >= policy.max_attempts {
+                    log::error!("[RETRY] {} failed after {} attempts: {}", host, attempt, e);
+                    record_failure(host, &e);
+                    return Err(e);
+                }
+                let delay = backoff_delay(&policy, attempt);
+                log::warn!("[RETRY] {} attempt {}/{} failed: {}. Retrying in {}ms",
+                    host, attempt, policy.max_attempts, e, delay.as_millis());
+                tokio::time::sleep(delay).await;
+            }
+            Err(e) => {
+                record_success(host);
+                return Err(e);
+            }
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HostSyncStatus {
+    pub host: String,
+    pub state: String, // closed | open | half_open
+    pub consecutive_failures: u32,
+    pub open_until: Option<DateTime<Utc>>,
+    pub last_error: Option<String>,
+    pub last_success_at: Option<DateTime<Utc>>,
+    pub last_failure_at: Option<DateTime<Utc>>,
+}
+
+/// Circuit breaker state for every external host contacted since startup
+#[tauri::command]
+pub fn get_sync_status() -> Vec<HostSyncStatus> {
+    let now = Utc::now();
+    let map = breakers().lock().unwrap();
+    let mut hosts: Vec<HostSyncStatus> = map.iter().map(|(host, b)| {
+        let state = match b.open_until {
+            Some(until) if until > now => "open",
+            Some(_) => "half_open",
+            None => "closed",
+        };
+        HostSyncStatus {
+            host: host.clone(),
+            state: state.into(),
+            consecutive_failures: b.consecutive_failures,
+            open_until: b.open_until,
+            last_error: b.last_error.clone(),
+            last_success_at: b.last_success_at,
+            last_failure_at: b.last_failure_at,
+        }
+    }).collect();
+    hosts.sort_by(|a, b| a.host.cmp(&b.host));
+    hosts
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -119,4 +285,50 @@ mod tests {
             assert_eq!(call_count, 3);
         });
     }
+
+    #[test]
+    fn test_circuit_opens_after_repeated_failures() {
+        use std::sync::atomic::{AtomicU32, Ordering};
+
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        runtime.block_on(async {
+            let host = "breaker-test.invalid";
+            let policy = BackoffPolicy { max_attempts: 2, base_delay_ms: 1, max_delay_ms: 2 };
+            let calls = AtomicU32::new(0);
+
+            for _ in 0..CIRCUIT_FAILURE_THRESHOLD {
+                let result: PosResult<()> = with_backoff(host, policy, || async {
+                    calls.fetch_add(1, Ordering::SeqCst);
+                    Err(PosError::External("boom".into()))
+                }).await;
+                assert!(result.is_err());
+            }
+            assert_eq!(calls.load(Ordering::SeqCst), CIRCUIT_FAILURE_THRESHOLD * 2);
+
+            // Open circuit: the operation is not invoked at all
+            let result: PosResult<()> = with_backoff(host, policy, || async {
+                calls.fetch_add(1, Ordering::SeqCst);
+                Ok(())
+            }).await;
+            assert!(result.is_err());
+            assert_eq!(calls.load(Ordering::SeqCst), CIRCUIT_FAILURE_THRESHOLD * 2);
+            assert!(get_sync_status().iter().any(|h| h.host == host && h.state == "open"));
+        });
+    }
+
+    #[test]
+    fn test_backoff_does_not_retry_non_external_errors() {
+        use std::sync::atomic::{AtomicU32, Ordering};
+
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        runtime.block_on(async {
+            let calls = AtomicU32::new(0);
+            let result: PosResult<()> = with_backoff("not-found-test.invalid", BackoffPolicy::default(), || async {
+                calls.fetch_add(1, Ordering::SeqCst);
+                Err(PosError::NotFound("user".into()))
+            }).await;
+            assert!(matches!(result, Err(PosError::NotFound(_))));
+            assert_eq!(calls.load(Ordering::SeqCst), 1);
+        });
+    }
 }
@@ -0,0 +1,208 @@
+// ─── AtCoder Scraper ────────────────────────────────────────────────
+// Scrapes AtCoder submissions via kenkoooo's AtCoder Problems API.
+// Strategy: page forward from the newest stored submission (500 per page),
+// resolve titles/difficulty from the problem resources, shadow-log accepted.
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use sqlx::PgPool;
+use tauri::State;
+
+use crate::{PosDb, PosConfig};
+use super::super::error::{PosError, PosResult, db_context};
+use super::super::retry::{with_backoff, BackoffPolicy};
+use super::super::shadow::{self, ShadowInput};
+use crate::streaks::{self, StreakKind};
+use super::super::utils::gen_id;
+use super::{build_http_client, queue, ScraperResponse, ATCODER_HOST};
+
+pub(crate) const API_BASE: &str = "https://kenkoooo.com/atcoder";
+/// The API returns at most this many submissions per request
+const PAGE_SIZE: usize = 500;
+/// kenkoooo asks clients to leave at least a second between requests
+const PAGE_DELAY_MS: u64 = 1000;
+
+// ─── REST API Response Types ────────────────────────────────────────
+
+#[derive(Debug, Deserialize)]
+struct AtCoderSubmission {
+    epoch_second: i64,
+    problem_id: String,
+    language: String,
+    result: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct AtCoderProblem {
+    id: String,
+    #[serde(default)]
+    problem_index: String,
+    #[serde(default)]
+    name: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct AtCoderProblemModel {
+    difficulty: Option<f64>,
+}
+
+// ─── Helpers ────────────────────────────────────────────────────────
+
+pub(crate) async fn fetch_json<T: serde::de::DeserializeOwned>(client: &reqwest::Client, url: &str) -> PosResult<T> {
+    with_backoff(ATCODER_HOST, BackoffPolicy { base_delay_ms: PAGE_DELAY_MS, ..Default::default() }, || async {
+        // Responses are gzip-encoded (kenkoooo asks for it); reqwest decodes them
+        let resp = client.get(url).send().await?;
+        if !resp.status().is_success() {
+            return Err(PosError::External(format!("HTTP error: {}", resp.status())));
+        }
+        Ok(resp.json::<T>().await?)
+    }).await
+}
+
+// ─── Scraper Command ────────────────────────────────────────────────
+
+/// Scrape AtCoder submissions via AtCoder Problems. Stores every verdict;
+/// shadow activities only for accepted ("AC") submissions.
+#[tauri::command]
+pub async fn scrape_atcoder(
+    db: State<'_, PosDb>,
+    config: State<'_, PosConfig>,
+) -> PosResult<ScraperResponse> {
+    queue::run(&db.0, "atcoder", run_atcoder_scrape(&db.0, &config.0)).await
+}
+
+/// Tauri-free body of `scrape_atcoder`, shared with the CLI
+pub async fn run_atcoder_scrape(
+    pool: &PgPool,
+    config: &crate::pos::config::PosConfig,
+) -> PosResult<ScraperResponse> {
+    let user = config.require_atcoder_username()
+        .map_err(PosError::InvalidInput)?;
+
+    log::info!("[ATCODER SCRAPER] Starting sync for {}", user);
+
+    // Resume from the newest stored submission (the API filters by from_second inclusive)
+    let latest: Option<DateTime<Utc>> = sqlx::query_scalar(
+        "SELECT MAX(submitted_time) FROM pos_submissions WHERE platform = 'atcoder'"
+    )
+    .fetch_one(pool)
+    .await
+    .map_err(|e| db_context("Latest AtCoder submission", e))?;
+    let mut from_second = latest.map(|t| t.timestamp() + 1).unwrap_or(0);
+
+    let client = build_http_client();
+    let mut submissions: Vec<AtCoderSubmission> = Vec::new();
+    loop {
+        let url = format!("{}/atcoder-api/v3/user/submissions?user={}&from_second={}", API_BASE, user, from_second);
+        let page: Vec<AtCoderSubmission> = fetch_json(&client, &url).await?;
+        queue::page_fetched();
+        let page_len = page.len();
+        if let Some(last) = page.iter().map(|s| s.epoch_second).max() {
+            from_second = last + 1;
+        }
+        submissions.extend(page);
+        if page_len < PAGE_SIZE {
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(PAGE_DELAY_MS)).await;
+    }
+
+    let total = submissions.len() as i32;
+    log::info!("[ATCODER SCRAPER] API returned {} new submissions", total);
+    if submissions.is_empty() {
+        return Ok(ScraperResponse {
+            platform: "atcoder".into(),
+            new_submissions: 0,
+            total_submissions: 0,
+            shadow_activities: 0,
+        });
+    }
+
+    // Titles and difficulty estimates; a failure here only costs metadata
+    let problems: HashMap<String, AtCoderProblem> = fetch_json::<Vec<AtCoderProblem>>(&client, &format!("{}/resources/problems.json", API_BASE))
+        .await
+        .map(|ps| ps.into_iter().map(|p| (p.id.clone(), p)).collect())
+        .unwrap_or_else(|e| {
+            log::warn!("[ATCODER SCRAPER] Problem titles unavailable: {}", e);
+            HashMap::new()
+        });
+    let models: HashMap<String, AtCoderProblemModel> = fetch_json(&client, &format!("{}/resources/problem-models.json", API_BASE))
+        .await
+        .unwrap_or_else(|e| {
+            log::warn!("[ATCODER SCRAPER] Difficulty models unavailable: {}", e);
+            HashMap::new()
+        });
+
+    let mut new_count = 0i32;
+    let mut shadow_inputs: Vec<ShadowInput> = Vec::new();
+
+    queue::items_total(submissions.len());
+    for sub in &submissions {
+        queue::item_processed();
+        let submitted_time = DateTime::from_timestamp(sub.epoch_second, 0)
+            .ok_or_else(|| PosError::InvalidInput("Invalid Unix timestamp".into()))?;
+        let problem_id = format!("atcoder-{}", sub.problem_id);
+        let title = problems.get(&sub.problem_id)
+            .filter(|p| !p.name.is_empty())
+            .map(|p| format!("{}. {}", p.problem_index, p.name))
+            .unwrap_or_else(|| sub.problem_id.clone());
+        // Difficulty models are on the same scale as AtCoder ratings; can be negative for trivial tasks
+        let rating = models.get(&sub.problem_id)
+            .and_then(|m| m.difficulty)
+            .map(|d| d.max(0.0).round() as i32);
+
+        let inserted = sqlx::query(
+            r#"INSERT INTO pos_submissions
+               (id, platform, problem_id, problem_title, submitted_time, verdict, language, rating, tags)
+               VALUES ($1, 'atcoder', $2, $3, $4, $5, $6, $7, '{}')
+               ON CONFLICT (submitted_time) DO NOTHING"#,
+        )
+        .bind(gen_id())
+        .bind(&problem_id)
+        .bind(&title)
+        .bind(submitted_time)
+        .bind(&sub.result)
+        .bind(&sub.language)
+        .bind(rating)
+        .execute(pool)
+        .await
+        .map_err(|e| db_context("Insert submission", e))?
+        .rows_affected();
+
+        if inserted == 0 {
+            continue;
+        }
+        if sub.result == "AC" {
+            shadow_inputs.push(ShadowInput {
+                submitted_time,
+                problem_id,
+                problem_title: title,
+                platform: "atcoder".into(),
+            });
+        }
+        new_count += 1;
+    }
+
+    let shadow_count = shadow::process_submissions(
+        pool, &shadow_inputs, config.shadow_activity_minutes, config.shadow_collision_policy,
+        config.shadow_merge_window_minutes,
+    ).await?;
+
+    for input in &shadow_inputs {
+        streaks::note_day(pool, StreakKind::Solved, crate::pos::timezone::local_date(input.submitted_time)).await;
+    }
+
+    if new_count > 0 {
+        crate::dashboard::mark_snapshot_stale(pool).await;
+    }
+
+    log::info!("[ATCODER SCRAPER] Sync complete: {} new submissions, {} shadow activities", new_count, shadow_count);
+    Ok(ScraperResponse {
+        platform: "atcoder".into(),
+        new_submissions: new_count,
+        total_submissions: total,
+        shadow_activities: shadow_count,
+    })
+}
@@ -4,13 +4,17 @@
 
 use chrono::DateTime;
 use serde::Deserialize;
+use sqlx::PgPool;
 use tauri::State;
 
 use crate::{PosDb, PosConfig};
+use super::super::cache::{self, CachePolicy};
 use super::super::error::{PosError, PosResult, db_context};
+use super::super::retry::{with_backoff, BackoffPolicy};
 use super::super::shadow::{self, ShadowInput};
+use crate::streaks::{self, StreakKind};
 use super::super::utils::gen_id;
-use super::{build_http_client, ScraperResponse};
+use super::{build_http_client, queue, ScraperResponse, CODEFORCES_HOST};
 
 // ─── REST API Response Types ────────────────────────────────────────
 
@@ -50,8 +54,15 @@ pub async fn scrape_codeforces(
     db: State<'_, PosDb>,
     config: State<'_, PosConfig>,
 ) -> PosResult<ScraperResponse> {
-    let pool = &db.0;
-    let handle = config.0.require_codeforces_handle()
+    queue::run(&db.0, "codeforces", run_codeforces_scrape(&db.0, &config.0)).await
+}
+
+/// Tauri-free body of `scrape_codeforces`, shared with the CLI
+pub async fn run_codeforces_scrape(
+    pool: &PgPool,
+    config: &crate::pos::config::PosConfig,
+) -> PosResult<ScraperResponse> {
+    let handle = config.require_codeforces_handle()
         .map_err(|e| PosError::InvalidInput(e))?;
 
     log::info!("[CODEFORCES SCRAPER] Starting sync for {}", handle);
@@ -60,46 +71,14 @@ pub async fn scrape_codeforces(
     // Fetch up to 10000 submissions (API max per request)
     let url = format!("https://codeforces.com/api/user.status?handle={}&from=1&count=10000", handle);
 
-    // Retry logic with exponential backoff
-    let mut last_error = String::new();
-    let data: CodeforcesApiResponse = {
-        let mut result = None;
-        for i in 0..3 {
-            if i > 0 {
-                tokio::time::sleep(std::time::Duration::from_millis(500 * i)).await;
-            }
-
-            let resp = match client.get(&url).send().await {
-                Ok(r) => r,
-                Err(e) => {
-                    log::warn!("[CODEFORCES SCRAPER] Attempt {} failed: {}", i+1, e);
-                    last_error = e.to_string();
-                    continue;
-                }
-            };
-
-            if !resp.status().is_success() {
-                let status = resp.status();
-                log::warn!("[CODEFORCES SCRAPER] Attempt {} failed with HTTP {}", i+1, status);
-                last_error = format!("HTTP error: {}", status);
-                continue;
-            }
-
-            match resp.json::<CodeforcesApiResponse>().await {
-                Ok(d) => {
-                    result = Some(d);
-                    break;
-                }
-                Err(e) => {
-                    log::warn!("[CODEFORCES SCRAPER] Attempt {} failed to parse JSON: {}", i+1, e);
-                    last_error = format!("JSON parse error: {}", e);
-                    continue;
-                }
-            }
+    let data: CodeforcesApiResponse = with_backoff(CODEFORCES_HOST, BackoffPolicy::default(), || async {
+        let resp = client.get(&url).send().await?;
+        if !resp.status().is_success() {
+            return Err(PosError::External(format!("HTTP error: {}", resp.status())));
         }
-        
-        result.ok_or_else(|| PosError::External(format!("Failed after 3 attempts: {}", last_error)))?
-    };
+        Ok(resp.json::<CodeforcesApiResponse>().await?)
+    }).await?;
+    queue::page_fetched();
 
     if data.status != "OK" {
         return Err(PosError::External("Codeforces API returned non-OK status".into()));
@@ -108,12 +87,14 @@ pub async fn scrape_codeforces(
     let submissions = data.result.ok_or_else(|| PosError::External("Invalid response from Codeforces API".into()))?;
     let total = submissions.len() as i32;
     log::info!("[CODEFORCES SCRAPER] API returned {} submissions", total);
+    queue::items_total(submissions.len());
     
     let mut new_count = 0i32;
     let mut skipped_count = 0i32;
     let mut shadow_inputs: Vec<ShadowInput> = Vec::new();
 
     for sub in &submissions {
+        queue::item_processed();
         let submitted_time = DateTime::from_timestamp(sub.creation_time_seconds, 0)
             .ok_or_else(|| PosError::InvalidInput("Invalid Unix timestamp".into()))?;
         let contest_id = sub.problem.contest_id.unwrap_or(0);
@@ -136,7 +117,7 @@ pub async fn scrape_codeforces(
             let needs_verdict = old_verdict != verdict;
             
             if needs_rating || needs_tags || needs_verdict {
-                sqlx::query("UPDATE pos_submissions SET rating = $1, tags = $2, verdict = $3 WHERE id = $4")
+                sqlx::query("UPDATE pos_submissions SET rating = $1, tags = $2, verdict = $3, updated_at = NOW() WHERE id = $4 AND metadata_edited_at IS NULL")
                     .bind(sub.problem.rating)
                     .bind(&sub.problem.tags)
                     .bind(verdict)
@@ -144,6 +125,9 @@ pub async fn scrape_codeforces(
                     .execute(pool)
                     .await
                     .map_err(|e| db_context("Backfill", e))?;
+                if needs_verdict {
+                    streaks::invalidate(pool, StreakKind::Solved).await;
+                }
                 
                 let mut updates = Vec::new();
                 if needs_rating { updates.push("rating".to_string()); }
@@ -189,14 +173,25 @@ pub async fn scrape_codeforces(
     }
 
     // Shadow-log new submissions
-    let shadow_count = shadow::process_submissions(pool, &shadow_inputs, config.0.shadow_activity_minutes).await?;
+    let shadow_count = shadow::process_submissions(
+        pool, &shadow_inputs, config.shadow_activity_minutes, config.shadow_collision_policy,
+        config.shadow_merge_window_minutes,
+    ).await?;
+
+    for input in &shadow_inputs {
+        streaks::note_day(pool, StreakKind::Solved, crate::pos::timezone::local_date(input.submitted_time)).await;
+    }
 
     // Auto-sync ladder progress
-    let sync_msg = crate::cf_ladder_system::sync_ladder_progress_from_submissions(db.clone()).await.unwrap_or_else(|e| {
+    let sync_msg = crate::cf_ladder_system::sync_ladder_progress(pool).await.unwrap_or_else(|e| {
         log::error!("[CF SYNC] Failed to sync ladder progress: {}", e);
         "Sync failed".to_string()
     });
 
+    if new_count > 0 {
+        crate::dashboard::mark_snapshot_stale(pool).await;
+    }
+
     log::info!("[CODEFORCES SCRAPER] Sync complete: {} new submissions. {} skipped (already exist)", new_count, skipped_count);
     Ok(ScraperResponse {
         platform: "codeforces".into(),
@@ -288,149 +283,46 @@ pub async fn get_codeforces_user_stats(
 
     log::info!("[CODEFORCES STATS] Local counts: solved={}, total={}", total_solved, total_submissions);
 
-    // If not forcing refresh, try to serve from cache if fresh (< 24 hrs)
-    if !force_refresh {
-         let cached: Option<(serde_json::Value, chrono::DateTime<chrono::Utc>)> = sqlx::query_as(
-            "SELECT data, updated_at FROM pos_user_stats WHERE platform = 'codeforces'"
-        )
-        .fetch_optional(pool)
-        .await
-        .unwrap_or(None);
-
-        if let Some((data, updated_at)) = cached {
-            let now = chrono::Utc::now();
-            let duration = now.signed_duration_since(updated_at);
-            if duration.num_hours() < 24 {
-                if let Ok(mut stats) = serde_json::from_value::<CodeforcesUserStats>(data) {
-                    // Update dynamic counts
-                    log::info!("[CODEFORCES STATS] Updating cached stats with fresh counts: solved={}, total={}", total_solved, total_submissions);
-                    stats.total_solved = total_solved;
-                    stats.total_submissions = total_submissions;
-                    log::info!("[CODEFORCES] Serving stats from cache (age: {} hrs)", duration.num_hours());
-                    return Ok(stats);
-                }
-            }
-        }
-    }
-
-    log::info!("[CODEFORCES] Fetching fresh stats from API...");
-
-    let client = build_http_client();
-    let url = format!("https://codeforces.com/api/user.info?handles={}", handle);
+    // Profile data is cached for 24 hrs; solved/submission counts always come from the DB
+    let policy = CachePolicy::default().force(force_refresh);
+    let mut stats: CodeforcesUserStats = cache::get_or_fetch(pool, "codeforces", cache::USER_STATS, policy, move || async move {
+        log::info!("[CODEFORCES] Fetching fresh stats from API...");
+        let client = build_http_client();
+        let url = format!("https://codeforces.com/api/user.info?handles={}", handle);
 
-    let api_result = async {
-        let mut last_error = String::new();
-        for i in 0..3 {
-            if i > 0 {
-                tokio::time::sleep(std::time::Duration::from_millis(500 * i)).await;
-            }
-            
-            let resp = match client.get(&url).send().await {
-                Ok(r) => r,
-                Err(e) => {
-                    last_error = e.to_string();
-                    continue;
-                }
-            };
-
-            // Check HTTP status before parsing
+        with_backoff(CODEFORCES_HOST, BackoffPolicy::default(), || async {
+            let resp = client.get(&url).send().await?;
             if !resp.status().is_success() {
-                let status = resp.status();
-                log::warn!("[CODEFORCES] Attempt {} failed with HTTP {}", i+1, status);
-                last_error = format!("HTTP error: {}", status);
-                continue;
+                return Err(PosError::External(format!("HTTP error: {}", resp.status())));
             }
-
-            let text = match resp.text().await {
-                Ok(t) => t,
-                Err(e) => {
-                    last_error = e.to_string();
-                    continue;
-                }
-            };
-            
-            let data: CFUserInfoResponse = match serde_json::from_str(&text) {
-                Ok(d) => d,
-                Err(e) => {
-                    log::warn!("[CODEFORCES] Attempt {} failed to parse response: {}", i+1, text);
-                    last_error = format!("Failed to parse Codeforces response: {}", e);
-                    continue;
-                }
-            };
-
+            let text = resp.text().await?;
+            let data: CFUserInfoResponse = serde_json::from_str(&text).map_err(|e| {
+                log::warn!("[CODEFORCES] Failed to parse response: {}", text);
+                PosError::External(format!("Failed to parse Codeforces response: {}", e))
+            })?;
             if data.status != "OK" {
-                last_error = "Codeforces API returned non-OK status".into();
-                continue;
+                return Err(PosError::External("Codeforces API returned non-OK status".into()));
             }
+            // No point retrying if the user doesn't exist
+            let user = data.result
+                .and_then(|users| users.into_iter().next())
+                .ok_or_else(|| PosError::NotFound("User not found".into()))?;
 
-            let user = match data.result
-                .and_then(|users| users.into_iter().next()) {
-                    Some(u) => u,
-                    None => {
-                        last_error = "User not found".into();
-                        break; // No point retrying if user not found
-                    }
-                };
-
-            return Ok::<CodeforcesUserStats, PosError>(CodeforcesUserStats {
+            Ok(CodeforcesUserStats {
                 handle: user.handle,
                 rating: user.rating,
                 max_rating: user.max_rating,
                 rank: user.rank,
                 max_rank: user.max_rank,
                 avatar: user.title_photo,
-                total_solved, 
+                total_solved,
                 total_submissions,
-            });
-        }
-        
-        log::error!("[CODEFORCES STATS] Failed to fetch from API after 3 attempts: {}", last_error);
-        Err(PosError::External(last_error))
-    }.await;
-
-    match api_result {
-        Ok(stats) => {
-             log::info!("[CODEFORCES STATS] Successfully fetched from API: solved={}, total={}", stats.total_solved, stats.total_submissions);
-             // Save to DB
-            let json_data = serde_json::to_value(&stats).unwrap_or_default();
-            sqlx::query(
-                "INSERT INTO pos_user_stats (platform, username, data, updated_at) 
-                 VALUES ('codeforces', $1, $2, NOW())
-                 ON CONFLICT (platform) DO UPDATE 
-                 SET username = EXCLUDED.username, data = EXCLUDED.data, updated_at = NOW()"
-            )
-            .bind(&stats.handle)
-            .bind(json_data)
-            .execute(pool)
-            .await
-            .map_err(|e| db_context("Save stats", e))?;
-            
-            log::info!("[CODEFORCES] User stats updated and cached");
-            Ok(stats)
-        }
-        Err(e) => {
-            log::warn!("[CODEFORCES] API fetch failed after 3 attempts: {}. Trying cache fallback.", e);
-            // Fallback to strict cache even if old
-             let cached: Option<(serde_json::Value,)> = sqlx::query_as(
-                "SELECT data FROM pos_user_stats WHERE platform = 'codeforces'"
-            )
-            .fetch_optional(pool)
-            .await
-            .map_err(|db_err| db_context("Load cached stats", db_err))?;
-
-            if let Some((data,)) = cached {
-                let mut stats: CodeforcesUserStats = serde_json::from_value(data)
-                    .map_err(|e| PosError::External(format!("Cache parse error: {}", e)))?;
-                // Update counts from DB
-                log::info!("[CODEFORCES STATS] Updating stale cache with fresh counts: solved={}, total={}", total_solved, total_submissions);
-                stats.total_solved = total_solved;
-                stats.total_submissions = total_submissions;
-                log::info!("[CODEFORCES] Serving stale cache due to API unavailability");
-                Ok(stats)
-            } else {
-                log::error!("[CODEFORCES] No cache available and API is down");
-                Err(e)
-            }
-        }
-    }
+            })
+        }).await
+    }).await?;
+
+    log::info!("[CODEFORCES STATS] Applying fresh counts: solved={}, total={}", total_solved, total_submissions);
+    stats.total_solved = total_solved;
+    stats.total_submissions = total_submissions;
+    Ok(stats)
 }
@@ -0,0 +1,337 @@
+// GitHub Shadow Backfill
+// Turns historical per-day, per-repo commit counts into shadow activities for the days
+// before activity tracking started (the first real activity), so past heatmaps aren't
+// empty. Commit timestamps aren't available per day, so each repo-day becomes a block of
+// COMMIT_MINUTES per commit, stacked back from BLOCK_END_HOUR local time. Every repo-day
+// is recorded in github_backfill_days, so reruns (and deleted blocks) are never recreated.
+
+use std::collections::{BTreeMap, HashMap};
+
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use tauri::State;
+
+use crate::{PosDb, PosConfig};
+use crate::pos::error::{PosError, PosResult, db_context};
+use crate::pos::retry::{with_backoff, BackoffPolicy};
+use crate::pos::timezone;
+use crate::pos::utils::gen_id;
+use super::super::{build_http_client, GITHUB_HOST};
+
+const COMMIT_MINUTES: i64 = 20;
+const MAX_REPO_DAY_MINUTES: i64 = 4 * 60;
+/// Stacked blocks for a day end at this local hour
+const BLOCK_END_HOUR: u32 = 21;
+/// Quarter-sized windows keep each repo under the 100-node page of daily contributions
+const WINDOW_MONTHS: u32 = 3;
+
+// ─── Types ──────────────────────────────────────────────────────────
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GithubBackfillReport {
+    pub from: String,
+    /// Last backfilled day: the day before the first real activity, or today
+    pub until: String,
+    pub days: usize,
+    pub activities_created: usize,
+    /// Repo-days already backfilled by an earlier run
+    pub skipped: usize,
+    pub commits: i64,
+}
+
+#[derive(Deserialize)]
+struct CalendarResponse {
+    data: Option<CalendarData>,
+    errors: Option<Vec<serde_json::Value>>,
+}
+
+#[derive(Deserialize)]
+struct CalendarData {
+    viewer: CalendarViewer,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CalendarViewer {
+    contributions_collection: CalendarCollection,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CalendarCollection {
+    commit_contributions_by_repository: Vec<RepoDays>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RepoDays {
+    repository: RepoInfo,
+    contributions: DayNodes,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RepoInfo {
+    name_with_owner: String,
+    primary_language: Option<Language>,
+}
+
+#[derive(Deserialize)]
+struct Language {
+    name: String,
+}
+
+#[derive(Deserialize)]
+struct DayNodes {
+    nodes: Vec<DayNode>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DayNode {
+    occurred_at: DateTime<Utc>,
+    commit_count: i32,
+}
+
+/// One repo's commits on one day
+struct RepoDay {
+    repo: String,
+    category: String,
+    commits: i32,
+}
+
+// ─── Helpers ────────────────────────────────────────────────────────
+
+fn category_for(repo: &str, language: Option<&str>, overrides: &HashMap<String, String>) -> String {
+    if let Some(category) = overrides.get(repo) {
+        return category.clone();
+    }
+    match language {
+        Some("C++") => "cpp".into(),
+        _ => "development".into(),
+    }
+}
+
+/// `[start, end)` windows of WINDOW_MONTHS covering `[from, until]`
+fn windows(from: NaiveDate, until: NaiveDate) -> Vec<(NaiveDate, NaiveDate)> {
+    let mut out = Vec::new();
+    let mut start = from;
+    while start <= until {
+        let end = start.checked_add_months(chrono::Months::new(WINDOW_MONTHS))
+            .unwrap_or(until)
+            .min(until + Duration::days(1));
+        out.push((start, end));
+        start = end;
+    }
+    out
+}
+
+/// (start, end) minute offsets back from the day's end for each repo block, in order
+fn stack_blocks(commits: &[i32]) -> Vec<(i64, i64)> {
+    let mut end = 0;
+    commits.iter().map(|&c| {
+        let minutes = (c as i64 * COMMIT_MINUTES).min(MAX_REPO_DAY_MINUTES);
+        let block = (end + minutes, end);
+        end += minutes;
+        block
+    }).collect()
+}
+
+async fn fetch_window(
+    client: &reqwest::Client,
+    token: &str,
+    start: NaiveDate,
+    end: NaiveDate,
+    overrides: &HashMap<String, String>,
+    days: &mut BTreeMap<NaiveDate, Vec<RepoDay>>,
+) -> PosResult<()> {
+    let body = serde_json::json!({
+        "query": r#"query($from: DateTime!, $to: DateTime!) {
+            viewer {
+                contributionsCollection(from: $from, to: $to) {
+                    commitContributionsByRepository(maxRepositories: 100) {
+                        repository { nameWithOwner primaryLanguage { name } }
+                        contributions(first: 100) { nodes { occurredAt commitCount } }
+                    }
+                }
+            }
+        }"#,
+        "variables": {
+            "from": format!("{}T00:00:00Z", start),
+            "to": format!("{}T00:00:00Z", end),
+        }
+    });
+    let resp: CalendarResponse = with_backoff(GITHUB_HOST, BackoffPolicy::default(), || async {
+        let resp = client
+            .post("https://api.github.com/graphql")
+            .header("Authorization", format!("Bearer {}", token))
+            .header("User-Agent", "coppermind-pos")
+            .json(&body)
+            .send()
+            .await?;
+        if !resp.status().is_success() {
+            return Err(PosError::External(format!("GitHub GraphQL error: {}", resp.status())));
+        }
+        Ok(resp.json::<CalendarResponse>().await?)
+    }).await?;
+    if let Some(errors) = resp.errors {
+        return Err(PosError::External(format!("GitHub GraphQL errors for {}..{}: {:?}", start, end, errors)));
+    }
+
+    let repos = resp.data.map(|d| d.viewer.contributions_collection.commit_contributions_by_repository);
+    for repo in repos.unwrap_or_default() {
+        let name = repo.repository.name_with_owner;
+        let language = repo.repository.primary_language.map(|l| l.name);
+        for node in repo.contributions.nodes.into_iter().filter(|n| n.commit_count > 0) {
+            days.entry(timezone::local_date(node.occurred_at)).or_default().push(RepoDay {
+                repo: name.clone(),
+                category: category_for(&name, language.as_deref(), overrides),
+                commits: node.commit_count,
+            });
+        }
+    }
+    Ok(())
+}
+
+// ─── Command ────────────────────────────────────────────────────────
+
+/// Backfill shadow activities from GitHub commits between `since` (YYYY-MM-DD) and the
+/// start of activity tracking. `category_overrides` maps "owner/repo" to a category;
+/// otherwise C++ repos go to "cpp" and everything else to "development".
+#[tauri::command]
+pub async fn backfill_github_shadow_activities(
+    db: State<'_, PosDb>,
+    config: State<'_, PosConfig>,
+    since: String,
+    category_overrides: Option<HashMap<String, String>>,
+) -> PosResult<GithubBackfillReport> {
+    let pool = &db.0;
+    let token = config.0.require_github_token().map_err(PosError::InvalidInput)?;
+    let from = NaiveDate::parse_from_str(&since, "%Y-%m-%d")
+        .map_err(|e| PosError::InvalidInput(format!("Invalid since date: {}", e)))?;
+    let overrides = category_overrides.unwrap_or_default();
+
+    let first_tracked: Option<String> = sqlx::query_scalar(
+        "SELECT MIN(date) FROM pos_activities WHERE is_shadow = FALSE AND deleted_at IS NULL"
+    )
+    .fetch_one(pool)
+    .await
+    .map_err(|e| db_context("first tracked activity", e))?;
+    let until = first_tracked
+        .and_then(|d| NaiveDate::parse_from_str(&d, "%Y-%m-%d").ok())
+        .map(|d| d - Duration::days(1))
+        .unwrap_or_else(timezone::today);
+
+    let mut report = GithubBackfillReport {
+        from: since.clone(),
+        until: until.format("%Y-%m-%d").to_string(),
+        days: 0,
+        activities_created: 0,
+        skipped: 0,
+        commits: 0,
+    };
+    if from > until {
+        return Ok(report);
+    }
+
+    let client = build_http_client();
+    let mut days: BTreeMap<NaiveDate, Vec<RepoDay>> = BTreeMap::new();
+    for (start, end) in windows(from, until) {
+        log::info!("[GITHUB] Backfill: fetching commits {}..{}", start, end);
+        fetch_window(&client, token, start, end, &overrides, &mut days).await?;
+    }
+    days.retain(|day, _| *day >= from && *day <= until);
+
+    for (day, mut repos) in days {
+        repos.sort_by(|a, b| a.repo.cmp(&b.repo));
+        let created = backfill_day(pool, day, &repos).await?;
+        report.days += 1;
+        report.activities_created += created;
+        report.skipped += repos.len() - created;
+        report.commits += repos.iter().map(|r| r.commits as i64).sum::<i64>();
+    }
+    if report.activities_created > 0 {
+        crate::dashboard::mark_snapshot_stale(pool).await;
+    }
+
+    log::info!(
+        "[GITHUB] Backfill {}..{}: {} shadow activities over {} days ({} already done)",
+        report.from, report.until, report.activities_created, report.days, report.skipped
+    );
+    Ok(report)
+}
+
+/// Insert one day's repo blocks; returns how many were new
+async fn backfill_day(pool: &PgPool, day: NaiveDate, repos: &[RepoDay]) -> PosResult<usize> {
+    let day_end = day.and_hms_opt(BLOCK_END_HOUR, 0, 0)
+        .and_then(timezone::from_local)
+        .ok_or_else(|| PosError::InvalidInput(format!("No local {}:00 on {}", BLOCK_END_HOUR, day)))?;
+    let blocks = stack_blocks(&repos.iter().map(|r| r.commits).collect::<Vec<_>>());
+    let date = day.format("%Y-%m-%d").to_string();
+
+    let mut tx = pool.begin().await.map_err(|e| db_context("TX begin", e))?;
+    let mut created = 0;
+    for (repo, (start_back, end_back)) in repos.iter().zip(blocks) {
+        let activity_id = gen_id();
+        let claimed = sqlx::query(
+            r#"INSERT INTO github_backfill_days (day, repo, commit_count, activity_id, created_at)
+               VALUES ($1, $2, $3, $4, NOW())
+               ON CONFLICT (day, repo) DO NOTHING"#
+        )
+        .bind(day)
+        .bind(&repo.repo)
+        .bind(repo.commits)
+        .bind(&activity_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| db_context("claim backfill day", e))?
+        .rows_affected();
+        if claimed == 0 {
+            continue;
+        }
+
+        let noun = if repo.commits == 1 { "commit" } else { "commits" };
+        sqlx::query(
+            r#"INSERT INTO pos_activities
+               (id, date, start_time, end_time, category, title, description, is_productive, is_shadow)
+               VALUES ($1, $2, $3, $4, $5, $6, $7, TRUE, TRUE)"#
+        )
+        .bind(&activity_id)
+        .bind(&date)
+        .bind(day_end - Duration::minutes(start_back))
+        .bind(day_end - Duration::minutes(end_back))
+        .bind(&repo.category)
+        .bind(format!("GITHUB - {}", repo.repo))
+        .bind(format!("{} {} (backfilled from the contribution calendar)", repo.commits, noun))
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| db_context("insert backfill activity", e))?;
+        created += 1;
+    }
+    tx.commit().await.map_err(|e| db_context("TX commit", e))?;
+    Ok(created)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn d(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn test_windows_cover_range() {
+        let w = windows(d("2023-11-15"), d("2024-03-01"));
+        assert_eq!(w, vec![(d("2023-11-15"), d("2024-02-15")), (d("2024-02-15"), d("2024-03-02"))]);
+        assert_eq!(windows(d("2024-01-01"), d("2024-01-01")), vec![(d("2024-01-01"), d("2024-01-02"))]);
+    }
+
+    #[test]
+    fn test_stack_blocks() {
+        // 2 commits = 40 min, 30 commits capped at 4h, stacked back from the day's end
+        assert_eq!(stack_blocks(&[2, 30, 1]), vec![(40, 0), (280, 40), (300, 280)]);
+    }
+}
@@ -8,6 +8,15 @@ use crate::pos::error::{PosError, PosResult, db_context};
 use crate::pos::utils::gen_id;
 use super::types::GraphQLRepository;
 
+/// `{ "Rust": 120345, ... }` bytes per language, stored in github_repositories.languages
+fn languages_json(repo: &GraphQLRepository) -> serde_json::Value {
+    let sizes: serde_json::Map<String, serde_json::Value> = repo.languages.iter()
+        .flat_map(|l| &l.edges)
+        .map(|e| (e.node.name.clone(), serde_json::Value::from(e.size)))
+        .collect();
+    serde_json::Value::Object(sizes)
+}
+
 pub(crate) async fn insert_repository_from_graphql(
     pool: &sqlx::PgPool,
     username: &str,
@@ -42,7 +51,7 @@ pub(crate) async fn insert_repository_from_graphql(
     .bind(&repo.owner.login)
     .bind(&full_name)
     .bind(&repo.description)
-    .bind(serde_json::json!({}))  // languages - TODO: fetch if needed
+    .bind(languages_json(repo))
     .bind(repo.primary_language.as_ref().map(|l| l.name.clone()))
     .bind(commit_count)
     .bind(0)  // total_prs
@@ -93,7 +102,7 @@ pub(crate) async fn update_repository_from_graphql(
            WHERE id = $15"#
     )
     .bind(&repo.description)
-    .bind(serde_json::json!({}))  // languages
+    .bind(languages_json(repo))
     .bind(repo.primary_language.as_ref().map(|l| l.name.clone()))
     .bind(commit_count)
     .bind(0)  // total_prs
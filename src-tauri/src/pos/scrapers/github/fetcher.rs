@@ -2,11 +2,13 @@ use tauri::State;
 use chrono::{DateTime, Utc};
 use std::collections::HashMap;
 use serde::Deserialize;
+use sqlx::PgPool;
 
 use crate::{PosDb, PosConfig};
 use crate::pos::error::{PosError, PosResult, db_context};
-use crate::pos::scrapers::ScraperResponse;
-use super::super::build_http_client;
+use crate::pos::retry::{with_backoff, BackoffPolicy};
+use crate::pos::scrapers::{queue, ScraperResponse};
+use super::super::{build_http_client, GITHUB_HOST};
 use super::db::{insert_repository_from_graphql, update_repository_from_graphql, update_additional_user_stats, fetch_user_contribution_stats_direct};
 use super::types::{GraphQLRepository, GraphQLResponse};
 
@@ -20,10 +22,17 @@ pub async fn scrape_github(
     db: State<'_, PosDb>,
     config: State<'_, PosConfig>,
 ) -> PosResult<ScraperResponse> {
-    let pool = &db.0;
-    let username = config.0.require_github_username()
+    queue::run(&db.0, "github", run_github_scrape(&db.0, &config.0)).await
+}
+
+/// Tauri-free body of `scrape_github`, shared with the CLI
+pub async fn run_github_scrape(
+    pool: &PgPool,
+    config: &crate::pos::config::PosConfig,
+) -> PosResult<ScraperResponse> {
+    let username = config.require_github_username()
         .map_err(|e| PosError::InvalidInput(e))?;
-    let token = config.0.require_github_token()
+    let token = config.require_github_token()
         .map_err(|e| PosError::InvalidInput(e))?;
 
     log::info!("[GITHUB SCRAPER] Starting sync for {}", username);
@@ -42,7 +51,9 @@ pub async fn scrape_github(
     let mut new_count = 0i32;
     let mut updated_count = 0i32;
     
+    queue::items_total(all_repos.len());
     for (repo, user_commit_count) in &all_repos {
+        queue::item_processed();
         let full_name = format!("{}/{}", repo.owner.login, repo.name);
         
         // Skip repos with 0 user commits
@@ -73,8 +84,33 @@ pub async fn scrape_github(
     }
 
     // Step 4: Fetch and store accurate user-level stats directly from GitHub
+    refresh_contribution_stats(pool, &client, token, username).await?;
+
+    // Step 5: Update additional stats from repos (stars, languages, top repos) WITHOUT overwriting commit counts
+    update_additional_user_stats(pool, username).await?;
+    crate::pos::cache::invalidate(pool, "github").await?;
+
+    log::info!("[GITHUB SCRAPER] Sync complete: {} new, {} updated", new_count, updated_count);
+    Ok(ScraperResponse {
+        platform: "github".into(),
+        new_submissions: new_count,
+        total_submissions: (new_count + updated_count),
+        shadow_activities: 0,
+    })
+}
+
+// ─── Helper Functions ───────────────────────────────────────────────
+
+/// Fetch all-time contribution totals from GitHub and upsert them into github_user_stats
+/// (stars, languages and top repos are left to update_additional_user_stats)
+pub(crate) async fn refresh_contribution_stats(
+    pool: &PgPool,
+    client: &reqwest::Client,
+    token: &str,
+    username: &str,
+) -> PosResult<()> {
     log::info!("[GITHUB] Fetching accurate user stats from GitHub API");
-    let user_stats = fetch_user_contribution_stats_direct(&client, token).await?;
+    let user_stats = fetch_user_contribution_stats_direct(client, token).await?;
     
     // Store user stats with accurate GitHub data
     sqlx::query(
@@ -99,21 +135,9 @@ pub async fn scrape_github(
 
     log::info!("[GITHUB] User stats updated: {} commits, {} PRs, {} issues", 
         user_stats.total_commits, user_stats.total_prs, user_stats.total_issues);
-
-    // Step 5: Update additional stats from repos (stars, languages, top repos) WITHOUT overwriting commit counts
-    update_additional_user_stats(pool, username).await?;
-
-    log::info!("[GITHUB SCRAPER] Sync complete: {} new, {} updated", new_count, updated_count);
-    Ok(ScraperResponse {
-        platform: "github".into(),
-        new_submissions: new_count,
-        total_submissions: (new_count + updated_count),
-        shadow_activities: 0,
-    })
+    Ok(())
 }
 
-// ─── Helper Functions ───────────────────────────────────────────────
-
 /// Fetch user's commit contributions per repository (all-time)
 /// Fetches year-by-year since contributionsCollection only allows 1-year ranges
 async fn fetch_user_contributions(
@@ -212,6 +236,7 @@ async fn fetch_user_contributions(
         }
 
         let data: ContribResponse = resp.json().await?;
+        queue::page_fetched();
 
         if let Some(errors) = data.errors {
             log::error!("[GITHUB] GraphQL errors for year {}: {:?}", year, errors);
@@ -278,6 +303,12 @@ async fn fetch_repos_details(
                                 }
                             }
                             primaryLanguage { name }
+                            languages(first: 10, orderBy: { field: SIZE, direction: DESC }) {
+                                edges {
+                                    size
+                                    node { name }
+                                }
+                            }
                             defaultBranchRef {
                                 target {
                                     ... on Commit {
@@ -310,12 +341,8 @@ async fn fetch_repos_details(
 
         log::info!("[GITHUB] Fetching repo details page {} via GraphQL", page);
 
-        // Retry logic for transient errors (502, 503, etc.)
-        let mut attempts = 0;
-        let max_attempts = 3;
-        let resp = loop {
-            attempts += 1;
-            
+        let policy = BackoffPolicy { base_delay_ms: 1000, ..BackoffPolicy::default() };
+        let data: GraphQLResponse = with_backoff(GITHUB_HOST, policy, || async {
             let response = client
                 .post("https://api.github.com/graphql")
                 .header("Authorization", format!("Bearer {}", token))
@@ -324,27 +351,14 @@ async fn fetch_repos_details(
                 .send()
                 .await?;
 
-            if response.status().is_success() {
-                break response;
-            }
-
             let status = response.status();
-            
-            // Retry on 502/503 (server errors)
-            if (status.as_u16() == 502 || status.as_u16() == 503) && attempts < max_attempts {
-                let backoff_ms = 1000 * (2_u64.pow(attempts - 1)); // Exponential backoff
-                log::warn!("[GITHUB] Got {}, retrying in {}ms (attempt {}/{})", status, backoff_ms, attempts, max_attempts);
-                tokio::time::sleep(std::time::Duration::from_millis(backoff_ms)).await;
-                continue;
+            if !status.is_success() {
+                let body_text = response.text().await.unwrap_or_default();
+                log::error!("[GITHUB] GraphQL error {}: {}", status, body_text);
+                return Err(PosError::External(format!("GitHub GraphQL error: {}", status)));
             }
-
-            // Non-retryable error or max attempts reached
-            let body_text = response.text().await.unwrap_or_default();
-            log::error!("[GITHUB] GraphQL error {}: {}", status, body_text);
-            return Err(PosError::External(format!("GitHub GraphQL error: {}", status)));
-        };
-
-        let data: GraphQLResponse = resp.json().await?;
+            Ok(response.json::<GraphQLResponse>().await?)
+        }).await?;
 
         if let Some(errors) = data.errors {
             log::error!("[GITHUB] GraphQL errors: {:?}", errors);
@@ -356,6 +370,7 @@ async fn fetch_repos_details(
             .viewer;
 
         let repos = viewer.repositories;
+        queue::page_fetched();
         log::info!("[GITHUB] Page {} returned {} repos", page, repos.nodes.len());
         
         // Match repos with user commit counts
@@ -2,6 +2,7 @@
 pub mod types;
 pub mod fetcher;
 pub mod db;
+pub mod backfill;
 
 // Re-export main function for backward compatibility
 pub use fetcher::scrape_github;
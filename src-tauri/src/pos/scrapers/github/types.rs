@@ -57,6 +57,9 @@ pub(crate) struct GraphQLRepository {
     pub(crate) homepage_url: Option<String>,
     pub(crate) repository_topics: RepositoryTopics,
     pub(crate) primary_language: Option<Language>,
+    /// Bytes of code per language (largest first, top 10)
+    #[serde(default)]
+    pub(crate) languages: Option<LanguageConnection>,
     pub(crate) default_branch_ref: Option<BranchRef>,
 }
 
@@ -70,6 +73,17 @@ pub(crate) struct Language {
     pub(crate) name: String,
 }
 
+#[derive(Debug, Deserialize)]
+pub(crate) struct LanguageConnection {
+    pub(crate) edges: Vec<LanguageEdge>,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct LanguageEdge {
+    pub(crate) size: i64,
+    pub(crate) node: Language,
+}
+
 #[derive(Debug, Deserialize)]
 pub(crate) struct WatchersConnection {
     #[serde(rename = "totalCount")]
@@ -4,13 +4,17 @@
 
 use chrono::DateTime;
 use serde::Deserialize;
+use sqlx::PgPool;
 use tauri::State;
 
 use crate::{PosDb, PosConfig};
+use super::super::cache::{self, CachePolicy};
 use super::super::error::{PosError, PosResult, db_context};
+use super::super::retry::{with_backoff, BackoffPolicy};
 use super::super::shadow::{self, ShadowInput};
+use crate::streaks::{self, StreakKind};
 use super::super::utils::gen_id;
-use super::{build_http_client, ScraperResponse};
+use super::{build_http_client, queue, ScraperResponse, LEETCODE_HOST};
 
 // ─── GraphQL Response Types ─────────────────────────────────────────
 
@@ -66,8 +70,15 @@ pub async fn scrape_leetcode(
     db: State<'_, PosDb>,
     config: State<'_, PosConfig>,
 ) -> PosResult<ScraperResponse> {
-    let pool = &db.0;
-    let username = config.0.require_leetcode_username()
+    queue::run(&db.0, "leetcode", run_leetcode_scrape(&db.0, &config.0)).await
+}
+
+/// Tauri-free body of `scrape_leetcode`, shared with the CLI
+pub async fn run_leetcode_scrape(
+    pool: &PgPool,
+    config: &crate::pos::config::PosConfig,
+) -> PosResult<ScraperResponse> {
+    let username = config.require_leetcode_username()
         .map_err(|e| PosError::InvalidInput(e))?;
 
     log::info!("[LEETCODE SCRAPER] Starting sync for {}", username);
@@ -92,30 +103,34 @@ pub async fn scrape_leetcode(
         "variables": { "username": username, "limit": 100 }
     });
 
-    let resp = client
-        .post("https://leetcode.com/graphql")
-        .header("Content-Type", "application/json")
-        .header("Referer", "https://leetcode.com")
-        .json(&body)
-        .send()
-        .await?;
-
-    if !resp.status().is_success() {
-        return Err(PosError::External(format!("LeetCode API returned {}", resp.status())));
-    }
-
-    let data: LeetCodeGqlResponse = resp.json().await?;
+    let data: LeetCodeGqlResponse = with_backoff(LEETCODE_HOST, BackoffPolicy::default(), || async {
+        let resp = client
+            .post("https://leetcode.com/graphql")
+            .header("Content-Type", "application/json")
+            .header("Referer", "https://leetcode.com")
+            .json(&body)
+            .send()
+            .await?;
+
+        if !resp.status().is_success() {
+            return Err(PosError::External(format!("LeetCode API returned {}", resp.status())));
+        }
+        Ok(resp.json::<LeetCodeGqlResponse>().await?)
+    }).await?;
+    queue::page_fetched();
 
     let submissions = data.data
         .and_then(|d| d.recent_submission_list)
         .ok_or_else(|| PosError::External("Invalid response from LeetCode API".into()))?;
 
     let total = submissions.len() as i32;
+    queue::items_total(submissions.len());
     let mut new_count = 0i32;
     let mut shadow_inputs: Vec<ShadowInput> = Vec::new();
 
     // 2. Process each accepted submission
     for sub in &submissions {
+        queue::item_processed();
         if sub.status_display != "Accepted" {
             continue;
         }
@@ -150,7 +165,7 @@ pub async fn scrape_leetcode(
 
         if let Some((ref id, _, _)) = existing {
             // Backfill only
-            sqlx::query("UPDATE pos_submissions SET difficulty = $1, tags = $2 WHERE id = $3")
+            sqlx::query("UPDATE pos_submissions SET difficulty = $1, tags = $2, updated_at = NOW() WHERE id = $3 AND metadata_edited_at IS NULL")
                 .bind(&difficulty)
                 .bind(&tags)
                 .bind(id)
@@ -190,7 +205,18 @@ pub async fn scrape_leetcode(
     }
 
     // 3. Shadow-log new submissions
-    let shadow_count = shadow::process_submissions(pool, &shadow_inputs, config.0.shadow_activity_minutes).await?;
+    let shadow_count = shadow::process_submissions(
+        pool, &shadow_inputs, config.shadow_activity_minutes, config.shadow_collision_policy,
+        config.shadow_merge_window_minutes,
+    ).await?;
+
+    for input in &shadow_inputs {
+        streaks::note_day(pool, StreakKind::Solved, crate::pos::timezone::local_date(input.submitted_time)).await;
+    }
+
+    if new_count > 0 {
+        crate::dashboard::mark_snapshot_stale(pool).await;
+    }
 
     log::info!("[LEETCODE SCRAPER] Sync complete: {} new submissions", new_count);
     Ok(ScraperResponse {
@@ -313,103 +339,66 @@ pub async fn get_leetcode_user_stats(
         None => return Err(PosError::InvalidInput("LeetCode username not configured".into())),
     };
 
-    // If not forcing refresh, try to serve from cache if fresh (< 24 hrs)
-    if !force_refresh {
-         let cached: Option<(serde_json::Value, chrono::DateTime<chrono::Utc>)> = sqlx::query_as(
-            "SELECT data, updated_at FROM pos_user_stats WHERE platform = 'leetcode'"
-        )
-        .fetch_optional(pool)
-        .await
-        .map_err(|e| db_context("Load cached stats", e))?; // Added error handling for fetch_optional
-
-        if let Some((data, updated_at)) = cached {
-            let now = chrono::Utc::now();
-            let duration = now.signed_duration_since(updated_at);
-            // If less than 24 hours old, valid cache
-            if duration.num_hours() < 24 {
-                if let Ok(stats) = serde_json::from_value::<LeetCodeUserStats>(data) {
-                    log::info!("[LEETCODE] Serving stats from cache (age: {} hrs)", duration.num_hours());
-                    return Ok(stats);
-                } else {
-                    log::warn!("[LEETCODE] Failed to parse cached stats, fetching fresh.");
+    let policy = CachePolicy::default().force(force_refresh);
+    cache::get_or_fetch(pool, "leetcode", cache::USER_STATS, policy, move || async move {
+        log::info!("[LEETCODE] Fetching fresh stats from API...");
+
+        let client = build_http_client();
+        let query = r#"
+            query getUserProfile($username: String!) {
+                allQuestionsCount { difficulty count }
+                matchedUser(username: $username) {
+                    username
+                    profile { ranking }
+                    submitStats {
+                        acSubmissionNum { difficulty count }
+                    }
                 }
-            } else {
-                log::info!("[LEETCODE] Cached stats too old (age: {} hrs), fetching fresh.", duration.num_hours());
             }
-        }
-    }
-
-    log::info!("[LEETCODE] Fetching fresh stats from API...");
-
-    let client = build_http_client();
-    let query = r#"
-        query getUserProfile($username: String!) {
-            allQuestionsCount { difficulty count }
-            matchedUser(username: $username) {
-                username
-                profile { ranking }
-                submitStats {
-                    acSubmissionNum { difficulty count }
-                }
-            }
-        }
-    "#;
-
-    let vars = serde_json::json!({ "username": username });
-    let body = serde_json::json!({ "query": query, "variables": vars });
-
-    let resp = client.post("https://leetcode.com/graphql")
-        .header("Content-Type", "application/json")
-        .header("Referer", "https://leetcode.com")
-        .json(&body)
-        .send()
-        .await?;
-
-    let data: LeetCodeGraphqlResponse = resp.json().await?;
-
-    let user = data.data.matched_user.ok_or(PosError::External("User not found".into()))?;
-    let all_counts = data.data.all_questions_count;
-
-    let get_count = |list: &[CategoryCount], diff: &str| -> i32 {
-        list.iter().find(|c| c.difficulty == diff).map(|c| c.count).unwrap_or(0)
-    };
-
-    let total_solved = get_count(&user.submit_stats.ac_submission_num, "All");
-    let easy_solved = get_count(&user.submit_stats.ac_submission_num, "Easy");
-    let medium_solved = get_count(&user.submit_stats.ac_submission_num, "Medium");
-    let hard_solved = get_count(&user.submit_stats.ac_submission_num, "Hard");
-
-    let total_questions = get_count(&all_counts, "All");
-    let acceptance_rate = if total_questions > 0 {
-        (total_solved as f64 / total_questions as f64) * 100.0
-    } else {
-        0.0
-    };
-
-    let stats = LeetCodeUserStats {
-        username: user.username,
-        ranking: user.profile.unwrap().ranking,
-        total_solved,
-        easy_solved,
-        medium_solved,
-        hard_solved,
-        acceptance_rate,
-    };
-
-    // Save to DB
-    let json_data = serde_json::to_value(&stats).unwrap_or_default();
-    sqlx::query(
-        "INSERT INTO pos_user_stats (platform, username, data, updated_at) 
-            VALUES ('leetcode', $1, $2, NOW())
-            ON CONFLICT (platform) DO UPDATE 
-            SET username = EXCLUDED.username, data = EXCLUDED.data, updated_at = NOW()"
-    )
-    .bind(&stats.username)
-    .bind(json_data)
-    .execute(pool)
-    .await
-    .map_err(|e| db_context("Save stats", e))?;
-    
-    log::info!("[LEETCODE] User stats updated and cached");
-    Ok(stats)
+        "#;
+
+        let vars = serde_json::json!({ "username": username });
+        let body = serde_json::json!({ "query": query, "variables": vars });
+
+        let data: LeetCodeGraphqlResponse = with_backoff(LEETCODE_HOST, BackoffPolicy::default(), || async {
+            let resp = client.post("https://leetcode.com/graphql")
+                .header("Content-Type", "application/json")
+                .header("Referer", "https://leetcode.com")
+                .json(&body)
+                .send()
+                .await?;
+            Ok(resp.json::<LeetCodeGraphqlResponse>().await?)
+        }).await?;
+
+        let user = data.data.matched_user.ok_or(PosError::External("User not found".into()))?;
+        let all_counts = data.data.all_questions_count;
+
+        let get_count = |list: &[CategoryCount], diff: &str| -> i32 {
+            list.iter().find(|c| c.difficulty == diff).map(|c| c.count).unwrap_or(0)
+        };
+
+        let total_solved = get_count(&user.submit_stats.ac_submission_num, "All");
+        let easy_solved = get_count(&user.submit_stats.ac_submission_num, "Easy");
+        let medium_solved = get_count(&user.submit_stats.ac_submission_num, "Medium");
+        let hard_solved = get_count(&user.submit_stats.ac_submission_num, "Hard");
+
+        let total_questions = get_count(&all_counts, "All");
+        let acceptance_rate = if total_questions > 0 {
+            (total_solved as f64 / total_questions as f64) * 100.0
+        } else {
+            0.0
+        };
+
+        let stats = LeetCodeUserStats {
+            username: user.username,
+            ranking: user.profile.and_then(|p| p.ranking),
+            total_solved,
+            easy_solved,
+            medium_solved,
+            hard_solved,
+            acceptance_rate,
+        };
+
+        Ok(stats)
+    }).await
 }
@@ -0,0 +1,219 @@
+// ─── LeetCode Contest History ───────────────────────────────────────
+// Pulls contest rating history via the GraphQL contest endpoints and caches it
+// in `leetcode_contest_history` for the rating chart (alongside Codeforces). Runs through
+// the scrape queue under the "leetcode" cooldown.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use tauri::State;
+
+use crate::{PosDb, PosConfig};
+use super::super::error::{PosError, PosResult, db_context};
+use super::super::retry::{with_backoff, BackoffPolicy};
+use super::{build_http_client, queue, LEETCODE_HOST};
+
+// ─── GraphQL Response Types ─────────────────────────────────────────
+
+#[derive(Debug, Deserialize)]
+struct ContestGqlResponse {
+    data: Option<ContestGqlData>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ContestGqlData {
+    user_contest_ranking: Option<ContestRanking>,
+    user_contest_ranking_history: Option<Vec<ContestHistoryEntry>>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ContestRanking {
+    attended_contests_count: Option<i32>,
+    rating: Option<f64>,
+    global_ranking: Option<i32>,
+    top_percentage: Option<f64>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ContestHistoryEntry {
+    attended: bool,
+    trend_direction: Option<String>,
+    problems_solved: Option<i32>,
+    total_problems: Option<i32>,
+    finish_time_in_seconds: Option<i64>,
+    rating: Option<f64>,
+    ranking: Option<i32>,
+    contest: ContestInfo,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ContestInfo {
+    title: String,
+    start_time: i64, // Unix seconds
+}
+
+// ─── Public Types ───────────────────────────────────────────────────
+
+#[derive(Debug, Serialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct LeetCodeContestRow {
+    pub contest_title: String,
+    pub start_time: DateTime<Utc>,
+    pub rating: f64,
+    pub ranking: Option<i32>,
+    pub problems_solved: i32,
+    pub total_problems: i32,
+    pub finish_time_seconds: Option<i64>,
+    pub trend_direction: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LeetCodeContestSyncResult {
+    pub contests_synced: i32,
+    pub attended_count: i32,
+    pub current_rating: Option<f64>,
+    pub global_ranking: Option<i32>,
+    pub top_percentage: Option<f64>,
+}
+
+// ─── Commands ───────────────────────────────────────────────────────
+
+/// Sync attended LeetCode contests (rating, ranking, problems solved) into
+/// `leetcode_contest_history`. Re-running upserts, so it is safe to call repeatedly.
+#[tauri::command]
+pub async fn sync_leetcode_contests(
+    db: State<'_, PosDb>,
+    config: State<'_, PosConfig>,
+) -> PosResult<LeetCodeContestSyncResult> {
+    queue::run(&db.0, "leetcode", run_leetcode_contest_sync(&db.0, &config.0)).await
+}
+
+/// Tauri-free body of `sync_leetcode_contests`
+pub async fn run_leetcode_contest_sync(
+    pool: &PgPool,
+    config: &crate::pos::config::PosConfig,
+) -> PosResult<LeetCodeContestSyncResult> {
+    let username = config.require_leetcode_username()
+        .map_err(PosError::InvalidInput)?;
+
+    log::info!("[LEETCODE CONTESTS] Syncing contest history for {}", username);
+
+    let query = r#"
+        query userContestRankingInfo($username: String!) {
+            userContestRanking(username: $username) {
+                attendedContestsCount
+                rating
+                globalRanking
+                topPercentage
+            }
+            userContestRankingHistory(username: $username) {
+                attended
+                trendDirection
+                problemsSolved
+                totalProblems
+                finishTimeInSeconds
+                rating
+                ranking
+                contest { title startTime }
+            }
+        }
+    "#;
+
+    let body = serde_json::json!({
+        "query": query,
+        "variables": { "username": username }
+    });
+
+    let client = build_http_client();
+    let resp: ContestGqlResponse = with_backoff(LEETCODE_HOST, BackoffPolicy::default(), || async {
+        let resp = client
+            .post("https://leetcode.com/graphql")
+            .header("Content-Type", "application/json")
+            .header("Referer", "https://leetcode.com")
+            .json(&body)
+            .send()
+            .await?;
+        if !resp.status().is_success() {
+            return Err(PosError::External(format!("LeetCode API returned {}", resp.status())));
+        }
+        Ok(resp.json::<ContestGqlResponse>().await?)
+    }).await?;
+    queue::page_fetched();
+
+    let data = resp
+        .data
+        .ok_or_else(|| PosError::External("Invalid response from LeetCode contest API".into()))?;
+
+    let history = data.user_contest_ranking_history.unwrap_or_default();
+    queue::items_total(history.iter().filter(|h| h.attended).count());
+    let mut tx = pool.begin().await.map_err(|e| db_context("TX begin", e))?;
+    let mut synced = 0i32;
+
+    for entry in history.iter().filter(|h| h.attended) {
+        let Some(start_time) = DateTime::from_timestamp(entry.contest.start_time, 0) else {
+            continue;
+        };
+        sqlx::query(
+            r#"INSERT INTO leetcode_contest_history
+               (contest_title, start_time, rating, ranking, problems_solved, total_problems,
+                finish_time_seconds, trend_direction, synced_at)
+               VALUES ($1, $2, $3, $4, $5, $6, $7, $8, NOW())
+               ON CONFLICT (contest_title) DO UPDATE SET
+                   rating = EXCLUDED.rating,
+                   ranking = EXCLUDED.ranking,
+                   problems_solved = EXCLUDED.problems_solved,
+                   total_problems = EXCLUDED.total_problems,
+                   finish_time_seconds = EXCLUDED.finish_time_seconds,
+                   trend_direction = EXCLUDED.trend_direction,
+                   synced_at = NOW()"#,
+        )
+        .bind(&entry.contest.title)
+        .bind(start_time)
+        .bind(entry.rating.unwrap_or(0.0))
+        .bind(entry.ranking)
+        .bind(entry.problems_solved.unwrap_or(0))
+        .bind(entry.total_problems.unwrap_or(0))
+        .bind(entry.finish_time_in_seconds)
+        .bind(&entry.trend_direction)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| db_context("Upsert contest", e))?;
+        synced += 1;
+        queue::item_processed();
+    }
+
+    tx.commit().await.map_err(|e| db_context("TX commit", e))?;
+    crate::dashboard::mark_snapshot_stale(pool).await;
+
+    let ranking = data.user_contest_ranking;
+    log::info!("[LEETCODE CONTESTS] Synced {} attended contests", synced);
+
+    Ok(LeetCodeContestSyncResult {
+        contests_synced: synced,
+        attended_count: ranking.as_ref().and_then(|r| r.attended_contests_count).unwrap_or(synced),
+        current_rating: ranking.as_ref().and_then(|r| r.rating),
+        global_ranking: ranking.as_ref().and_then(|r| r.global_ranking),
+        top_percentage: ranking.as_ref().and_then(|r| r.top_percentage),
+    })
+}
+
+/// Stored contest history, oldest first (chart order)
+#[tauri::command]
+pub async fn get_leetcode_contest_history(
+    db: State<'_, PosDb>,
+) -> PosResult<Vec<LeetCodeContestRow>> {
+    sqlx::query_as::<_, LeetCodeContestRow>(
+        r#"SELECT contest_title, start_time, rating, ranking, problems_solved, total_problems,
+                  finish_time_seconds, trend_direction
+           FROM leetcode_contest_history
+           ORDER BY start_time ASC"#,
+    )
+    .fetch_all(&db.0)
+    .await
+    .map_err(|e| db_context("get_leetcode_contest_history", e))
+}
@@ -3,13 +3,23 @@
 // Each platform has its own module for maintainability.
 
 pub mod leetcode;
+pub mod leetcode_contests;
 pub mod codeforces;
+pub mod atcoder;
 pub mod github;
+pub mod queue;
 
 use serde::Serialize;
 
 // ─── Common HTTP client setup ───────────────────────────────────────
 
+/// Circuit breaker keys for `pos::retry::with_backoff`
+pub(crate) const CODEFORCES_HOST: &str = "codeforces.com";
+pub(crate) const LEETCODE_HOST: &str = "leetcode.com";
+pub(crate) const ATCODER_HOST: &str = "kenkoooo.com";
+pub(crate) const GITHUB_HOST: &str = "api.github.com";
+pub(crate) const OPEN_LIBRARY_HOST: &str = "openlibrary.org";
+
 pub(crate) fn build_http_client() -> reqwest::Client {
     use reqwest::header;
     let mut headers = header::HeaderMap::new();
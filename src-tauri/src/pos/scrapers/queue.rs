@@ -0,0 +1,240 @@
+// ─── Scrape Queue ───────────────────────────────────────────────────
+// Every scrape (manual command, background scheduler, CLI) runs through `run`, which
+// serializes jobs so only one scraper talks to the network at a time and enforces a
+// per-platform cooldown stored in scrape_cooldowns, so repeated clicks or restarts can't
+// hammer an API. While a job runs, scrapers report pages fetched and items processed;
+// each report is emitted as a `scrape-progress` event for the UI's progress bar.
+// The CLI never calls `init`, so it runs the same queue without events.
+
+use std::future::Future;
+use std::sync::{Mutex, OnceLock};
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::PgPool;
+use tauri::{AppHandle, Emitter, State};
+
+use crate::PosDb;
+use super::super::error::{PosError, PosResult, db_context};
+
+const PROGRESS_EVENT: &str = "scrape-progress";
+/// Item reports between two progress events (the last item always emits)
+const ITEMS_PER_EVENT: i64 = 20;
+/// Wait after a failed run; short so a flaky network can be retried soon
+const FAILURE_COOLDOWN_SECS: i64 = 30;
+
+static JOB_LOCK: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+static CURRENT: Mutex<Option<ScrapeProgress>> = Mutex::new(None);
+static APP: OnceLock<AppHandle> = OnceLock::new();
+
+/// Minimum seconds between two successful runs of a platform's scraper
+fn cooldown_secs(platform: &str) -> i64 {
+    match platform {
+        // Codeforces asks for at most one API call every 2s; user.status is a heavy one
+        "codeforces" => 120,
+        "leetcode" => 120,
+        // AtCoder Problems is a volunteer-run API
+        "atcoder" => 300,
+        "github" => 300,
+        // Friend syncs page through user.status once per friend
+        "cf-friends" => 120,
+        _ => 60,
+    }
+}
+
+// ─── Types ──────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScrapeProgress {
+    pub platform: String,
+    /// "queued", "running", "done" or "failed"
+    pub phase: String,
+    pub pages_fetched: i64,
+    pub items_processed: i64,
+    /// Known once the scraper has fetched its item list
+    pub items_total: Option<i64>,
+}
+
+#[derive(Debug, Serialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct ScrapeCooldown {
+    pub platform: String,
+    pub last_started_at: DateTime<Utc>,
+    pub last_finished_at: Option<DateTime<Utc>>,
+    pub last_ok: Option<bool>,
+    pub next_allowed_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScrapeQueueStatus {
+    /// The job holding the queue, if any
+    pub running: Option<ScrapeProgress>,
+    pub cooldowns: Vec<ScrapeCooldown>,
+}
+
+// ─── Progress ───────────────────────────────────────────────────────
+
+/// Enable progress events (called once from app setup)
+pub fn init(app: AppHandle) {
+    let _ = APP.set(app);
+}
+
+fn emit(progress: &ScrapeProgress) {
+    if let Some(app) = APP.get() {
+        let _ = app.emit(PROGRESS_EVENT, progress);
+    }
+}
+
+/// Apply `update` to the running job; emits when it returns true
+fn update_current(update: impl FnOnce(&mut ScrapeProgress) -> bool) {
+    let snapshot = match CURRENT.lock() {
+        Ok(mut current) => current.as_mut().and_then(|p| update(p).then(|| p.clone())),
+        Err(_) => None,
+    };
+    if let Some(progress) = snapshot {
+        emit(&progress);
+    }
+}
+
+fn new_progress(platform: &str, phase: &str) -> ScrapeProgress {
+    ScrapeProgress {
+        platform: platform.to_string(),
+        phase: phase.to_string(),
+        pages_fetched: 0,
+        items_processed: 0,
+        items_total: None,
+    }
+}
+
+/// A page (API response) was fetched by the running scraper
+pub fn page_fetched() {
+    update_current(|p| {
+        p.pages_fetched += 1;
+        true
+    });
+}
+
+/// The running scraper knows how many items it will process
+pub fn items_total(total: usize) {
+    update_current(|p| {
+        p.items_total = Some(total as i64);
+        true
+    });
+}
+
+/// One more item processed by the running scraper
+pub fn item_processed() {
+    update_current(|p| {
+        p.items_processed += 1;
+        p.items_processed % ITEMS_PER_EVENT == 0 || Some(p.items_processed) == p.items_total
+    });
+}
+
+// ─── Queue ──────────────────────────────────────────────────────────
+
+async fn check_cooldown(pool: &PgPool, platform: &str) -> PosResult<()> {
+    let next_allowed: Option<DateTime<Utc>> = sqlx::query_scalar(
+        "SELECT next_allowed_at FROM scrape_cooldowns WHERE platform = $1"
+    )
+    .bind(platform)
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| db_context("check scrape cooldown", e))?;
+
+    match next_allowed {
+        Some(at) if at > Utc::now() => Err(PosError::RateLimited(format!(
+            "{} was scraped recently; try again in {}s", platform, (at - Utc::now()).num_seconds().max(1)
+        ))),
+        _ => Ok(()),
+    }
+}
+
+async fn record_start(pool: &PgPool, platform: &str) -> PosResult<()> {
+    // Until the run finishes, a crash mid-scrape still leaves a full cooldown behind
+    sqlx::query(
+        r#"INSERT INTO scrape_cooldowns (platform, last_started_at, next_allowed_at)
+           VALUES ($1, NOW(), NOW() + make_interval(secs => $2))
+           ON CONFLICT (platform) DO UPDATE
+           SET last_started_at = NOW(), next_allowed_at = EXCLUDED.next_allowed_at"#
+    )
+    .bind(platform)
+    .bind(cooldown_secs(platform) as f64)
+    .execute(pool)
+    .await
+    .map_err(|e| db_context("record scrape start", e))?;
+    Ok(())
+}
+
+async fn record_finish(pool: &PgPool, platform: &str, ok: bool) {
+    let wait = if ok { cooldown_secs(platform) } else { FAILURE_COOLDOWN_SECS };
+    let result = sqlx::query(
+        r#"UPDATE scrape_cooldowns
+           SET last_finished_at = NOW(), last_ok = $2, next_allowed_at = NOW() + make_interval(secs => $3)
+           WHERE platform = $1"#
+    )
+    .bind(platform)
+    .bind(ok)
+    .bind(wait as f64)
+    .execute(pool)
+    .await;
+    if let Err(e) = result {
+        log::warn!("[SCRAPE QUEUE] Failed to record {} cooldown: {}", platform, e);
+    }
+}
+
+/// Run a scrape job for `platform` once the queue is free and its cooldown has passed.
+/// Fails with RateLimited during the cooldown instead of waiting it out.
+pub async fn run<T>(
+    pool: &PgPool,
+    platform: &str,
+    job: impl Future<Output = PosResult<T>>,
+) -> PosResult<T> {
+    let _guard = match JOB_LOCK.try_lock() {
+        Ok(guard) => guard,
+        Err(_) => {
+            log::info!("[SCRAPE QUEUE] {} queued behind a running scrape", platform);
+            emit(&new_progress(platform, "queued"));
+            JOB_LOCK.lock().await
+        }
+    };
+
+    check_cooldown(pool, platform).await?;
+    record_start(pool, platform).await?;
+
+    let started = new_progress(platform, "running");
+    emit(&started);
+    if let Ok(mut current) = CURRENT.lock() {
+        *current = Some(started);
+    }
+
+    let result = job.await;
+
+    record_finish(pool, platform, result.is_ok()).await;
+    let finished = CURRENT.lock().ok().and_then(|mut c| c.take());
+    let mut progress = finished.unwrap_or_else(|| new_progress(platform, "running"));
+    progress.phase = if result.is_ok() { "done" } else { "failed" }.to_string();
+    emit(&progress);
+
+    result
+}
+
+// ─── Commands ───────────────────────────────────────────────────────
+
+/// The running scrape (if any) and every platform's cooldown
+#[tauri::command]
+pub async fn get_scrape_queue_status(db: State<'_, PosDb>) -> PosResult<ScrapeQueueStatus> {
+    let cooldowns = sqlx::query_as::<_, ScrapeCooldown>(
+        r#"SELECT platform, last_started_at, last_finished_at, last_ok, next_allowed_at
+           FROM scrape_cooldowns ORDER BY platform"#
+    )
+    .fetch_all(&db.0)
+    .await
+    .map_err(|e| db_context("get_scrape_queue_status", e))?;
+
+    Ok(ScrapeQueueStatus {
+        running: CURRENT.lock().ok().and_then(|c| c.clone()),
+        cooldowns,
+    })
+}
@@ -1,10 +1,17 @@
 use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
 use sqlx::PgPool;
+use tauri::State;
 
+use crate::PosDb;
+use super::config::ShadowCollisionPolicy;
 use super::error::{PosError, PosResult, db_context};
 use super::utils::gen_id;
 
+type RealBlock = (String, DateTime<Utc>, DateTime<Utc>);
+
 /// Submission data needed by the shadow logger.
+#[derive(sqlx::FromRow)]
 pub struct ShadowInput {
     pub submitted_time: DateTime<Utc>,
     pub problem_id: String,
@@ -12,20 +19,126 @@ pub struct ShadowInput {
     pub platform: String,
 }
 
+/// Public problem page for a submission's problem_id ("cf-1520A", "leetcode-two-sum",
+/// "atcoder-abc300_a")
+fn problem_url(problem_id: &str) -> Option<String> {
+    if let Some(rest) = problem_id.strip_prefix("cf-") {
+        let split = rest.find(|c: char| !c.is_ascii_digit())?;
+        let (contest, index) = rest.split_at(split);
+        return Some(format!("https://codeforces.com/problemset/problem/{}/{}", contest, index));
+    }
+    if let Some(task) = problem_id.strip_prefix("atcoder-") {
+        // Task IDs are `{contest}_{index}` for regular contests
+        let (contest, _) = task.rsplit_once('_')?;
+        return Some(format!("https://atcoder.jp/contests/{}/tasks/{}", contest, task));
+    }
+    problem_id.strip_prefix("leetcode-")
+        .map(|slug| format!("https://leetcode.com/problems/{}/", slug))
+}
+
+/// How a shadow block is resolved against the real activities near it
+#[derive(Debug, PartialEq)]
+enum Collision {
+    /// Nothing to resolve: log/keep the block as is
+    Clear,
+    /// Attach the submission to this real activity instead
+    Merge(String),
+    /// Drop the block; the real activity already accounts for the time
+    Skip,
+    /// Start the block here, after the real activities that overlap it
+    Shrink(DateTime<Utc>),
+}
+
+/// Resolve the block `[start, end)` (`end` = submission time) against `overlapping` real
+/// activities, most overlap first. Real blocks are padded by `window` on both sides, so a
+/// session logged just before or after the submission counts as the same work.
+fn resolve_collision(
+    policy: ShadowCollisionPolicy,
+    window: Duration,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    overlapping: &[RealBlock],
+) -> Collision {
+    let Some((best_id, _, _)) = overlapping.first() else {
+        return Collision::Clear;
+    };
+    // Submitted during (or within the window of) a real activity
+    let covering = overlapping.iter().find(|(_, s, e)| *s - window < end && *e + window >= end);
+    match (policy, covering) {
+        (ShadowCollisionPolicy::Keep, _) => Collision::Clear,
+        (ShadowCollisionPolicy::Skip, _) => Collision::Skip,
+        (ShadowCollisionPolicy::Merge, _) => Collision::Merge(best_id.clone()),
+        (ShadowCollisionPolicy::Shrink, Some((covering_id, _, _))) => Collision::Merge(covering_id.clone()),
+        (ShadowCollisionPolicy::Shrink, None) => {
+            let latest_end = overlapping.iter().map(|(_, _, e)| *e).max().unwrap_or(start);
+            if latest_end > start { Collision::Shrink(latest_end) } else { Collision::Clear }
+        }
+    }
+}
+
+/// Live real activities within `window` of `[start, end)`, most overlap first
+async fn overlapping_real(
+    pool: &PgPool,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    window: Duration,
+) -> PosResult<Vec<RealBlock>> {
+    sqlx::query_as(
+        r#"SELECT id, start_time, end_time FROM pos_activities
+           WHERE is_shadow = FALSE AND deleted_at IS NULL AND start_time < $4 AND end_time > $3
+           ORDER BY LEAST(end_time, $2) - GREATEST(start_time, $1) DESC"#
+    )
+    .bind(start)
+    .bind(end)
+    .bind(start - window)
+    .bind(end + window)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| db_context("shadow collision check", e))
+}
+
+/// Record the submission as link evidence on a real activity instead of logging a shadow
+/// block. Safe to repeat: the same problem link is only attached once per activity.
+async fn merge_into_activity(pool: &PgPool, activity_id: &str, sub: &ShadowInput) -> PosResult<()> {
+    let Some(url) = problem_url(&sub.problem_id) else {
+        log::info!("[SHADOW] Skipping {} (covered by activity {})", sub.problem_id, activity_id);
+        return Ok(());
+    };
+    sqlx::query(
+        r#"INSERT INTO activity_evidence (id, activity_id, kind, url, caption, created_at)
+           SELECT $1, $2, 'link', $3, $4, NOW()
+           WHERE NOT EXISTS (SELECT 1 FROM activity_evidence WHERE activity_id = $2 AND url = $3)"#
+    )
+    .bind(gen_id())
+    .bind(activity_id)
+    .bind(&url)
+    .bind(format!("Accepted: {}", sub.problem_title))
+    .execute(pool)
+    .await
+    .map_err(|e| db_context("shadow merge evidence", e))?;
+
+    log::info!("[SHADOW] Merged {} into real activity {}", sub.problem_id, activity_id);
+    Ok(())
+}
+
 /// Process a single submission → shadow activity.
 /// Creates an activity spanning [submitted_time - DURATION, submitted_time]
 /// with is_shadow = TRUE, then links to any matching unverified goal (same date + problem_id).
+/// Overlaps with real activities (within `merge_window_minutes`) are resolved per `policy`
+/// so daily totals don't double count.
 ///
-/// Returns the created activity ID, or None if a shadow activity already exists.
+/// Returns the created activity ID, or None if a shadow activity already exists
+/// or the submission was merged into / skipped for a real activity.
 pub async fn process_shadow_log(
     pool: &PgPool,
     sub: &ShadowInput,
     duration_minutes: i64,
+    policy: ShadowCollisionPolicy,
+    merge_window_minutes: i64,
 ) -> PosResult<Option<String>> {
     let dur = Duration::minutes(duration_minutes);
-    let start_time = sub.submitted_time - dur;
+    let mut start_time = sub.submitted_time - dur;
     let end_time = sub.submitted_time;
-    let date = start_time.format("%Y-%m-%d").to_string();
 
     // Idempotency: check if shadow activity already exists for this end_time
     let existing: Option<(String,)> = sqlx::query_as(
@@ -41,10 +154,46 @@ pub async fn process_shadow_log(
         return Ok(None);
     }
 
-    // Determine category from platform — only leetcode and codeforces feed shadow logging
+    let excluded: bool = sqlx::query_scalar(
+        "SELECT EXISTS (SELECT 1 FROM pos_submissions WHERE submitted_time = $1 AND excluded_at IS NOT NULL)",
+    )
+    .bind(end_time)
+    .fetch_one(pool)
+    .await
+    .map_err(|e| db_context("shadow exclusion check", e))?;
+
+    if excluded {
+        log::info!("[SHADOW] Skipping excluded {} submission at {}", sub.platform, end_time);
+        return Ok(None);
+    }
+
+    if policy != ShadowCollisionPolicy::Keep {
+        let window = Duration::minutes(merge_window_minutes);
+        let overlapping = overlapping_real(pool, start_time, end_time, window).await?;
+        match resolve_collision(policy, window, start_time, end_time, &overlapping) {
+            Collision::Clear => {}
+            Collision::Merge(activity_id) => {
+                merge_into_activity(pool, &activity_id, sub).await?;
+                return Ok(None);
+            }
+            Collision::Skip => {
+                log::info!("[SHADOW] Skipping {} (overlaps a real activity)", sub.problem_id);
+                return Ok(None);
+            }
+            Collision::Shrink(new_start) => {
+                log::info!("[SHADOW] Shrinking {} block to start at {}", sub.problem_id, new_start);
+                start_time = new_start;
+            }
+        }
+    }
+
+    let date = super::timezone::local_date_string(start_time);
+
+    // Determine category from platform — only leetcode, codeforces and atcoder feed shadow logging
     let category = match sub.platform.as_str() {
         "leetcode" => "leetcode",
-        "codeforces" => "codeforces",
+        // No AtCoder category on the grid; contest practice shares the Codeforces one
+        "codeforces" | "atcoder" => "codeforces",
         _ => "misc", // defensive fallback, should never be reached
     };
 
@@ -156,10 +305,12 @@ pub async fn process_submissions(
     pool: &PgPool,
     submissions: &[ShadowInput],
     duration_minutes: i64,
+    policy: ShadowCollisionPolicy,
+    merge_window_minutes: i64,
 ) -> PosResult<i32> {
     let mut count = 0;
     for sub in submissions {
-        if let Some(_) = process_shadow_log(pool, sub, duration_minutes).await? {
+        if let Some(_) = process_shadow_log(pool, sub, duration_minutes, policy, merge_window_minutes).await? {
             count += 1;
         }
     }
@@ -212,3 +363,168 @@ async fn match_goal_by_keyword(
     Ok(result)
 }
 
+// ─── Reconciliation ─────────────────────────────────────────────────
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShadowReconcileReport {
+    pub date: String,
+    pub policy: ShadowCollisionPolicy,
+    /// Submission shadow activities examined
+    pub checked: usize,
+    /// Shadows removed after attaching their problem to a real activity
+    pub merged: Vec<String>,
+    /// Shadows removed without evidence (skip policy)
+    pub skipped: Vec<String>,
+    /// Shadows whose start moved past the real activities they overlapped
+    pub shrunk: Vec<String>,
+    /// Extra shadows for the same submission (only the first is kept)
+    pub duplicates: Vec<String>,
+    pub dry_run: bool,
+}
+
+#[derive(sqlx::FromRow)]
+struct ShadowRow {
+    id: String,
+    start_time: DateTime<Utc>,
+    #[sqlx(flatten)]
+    sub: ShadowInput,
+}
+
+async fn delete_shadow(pool: &PgPool, id: &str) -> PosResult<()> {
+    let mut tx = pool.begin().await.map_err(|e| db_context("shadow TX begin", e))?;
+    super::activities::set_activity_deleted_tx(&mut tx, id, true).await?;
+    tx.commit().await.map_err(|e| db_context("shadow TX commit", e))
+}
+
+/// Re-apply the collision policy (default: SHADOW_COLLISION_POLICY) to a day's existing
+/// submission shadows, cleaning up blocks logged before the policy or a real activity
+/// existed. Removed shadows are soft-deleted, so restore_activity can undo a merge.
+#[tauri::command]
+pub async fn reconcile_shadow_activities(
+    db: State<'_, PosDb>,
+    config: State<'_, crate::PosConfig>,
+    date: String,
+    policy: Option<ShadowCollisionPolicy>,
+    dry_run: Option<bool>,
+) -> PosResult<ShadowReconcileReport> {
+    let pool = &db.0;
+    let policy = policy.unwrap_or(config.0.shadow_collision_policy);
+    let window = Duration::minutes(config.0.shadow_merge_window_minutes);
+    let dry_run = dry_run.unwrap_or(false);
+    chrono::NaiveDate::parse_from_str(&date, "%Y-%m-%d")
+        .map_err(|e| PosError::InvalidInput(format!("Invalid date '{}': {}", date, e)))?;
+
+    // A shadow's end_time is its submission's time (see process_shadow_log)
+    let shadows = sqlx::query_as::<_, ShadowRow>(
+        r#"SELECT a.id, a.start_time, s.submitted_time, s.problem_id, s.problem_title, s.platform
+           FROM pos_activities a
+           JOIN LATERAL (
+               SELECT submitted_time, problem_id, problem_title, platform FROM pos_submissions
+               WHERE submitted_time = a.end_time
+               ORDER BY id LIMIT 1
+           ) s ON TRUE
+           WHERE a.date = $1 AND a.is_shadow = TRUE AND a.deleted_at IS NULL
+           ORDER BY a.end_time, a.created_at"#
+    )
+    .bind(&date)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| db_context("reconcile load shadows", e))?;
+
+    let mut report = ShadowReconcileReport {
+        date: date.clone(),
+        policy,
+        checked: shadows.len(),
+        merged: Vec::new(),
+        skipped: Vec::new(),
+        shrunk: Vec::new(),
+        duplicates: Vec::new(),
+        dry_run,
+    };
+    let mut last_end: Option<DateTime<Utc>> = None;
+    for shadow in &shadows {
+        let end = shadow.sub.submitted_time;
+        if last_end == Some(end) {
+            if !dry_run {
+                delete_shadow(pool, &shadow.id).await?;
+            }
+            report.duplicates.push(shadow.id.clone());
+            continue;
+        }
+        last_end = Some(end);
+
+        let overlapping = overlapping_real(pool, shadow.start_time, end, window).await?;
+        match resolve_collision(policy, window, shadow.start_time, end, &overlapping) {
+            Collision::Clear => {}
+            Collision::Merge(activity_id) => {
+                if !dry_run {
+                    merge_into_activity(pool, &activity_id, &shadow.sub).await?;
+                    delete_shadow(pool, &shadow.id).await?;
+                }
+                report.merged.push(shadow.id.clone());
+            }
+            Collision::Skip => {
+                if !dry_run {
+                    delete_shadow(pool, &shadow.id).await?;
+                }
+                report.skipped.push(shadow.id.clone());
+            }
+            Collision::Shrink(new_start) => {
+                if !dry_run {
+                    sqlx::query("UPDATE pos_activities SET start_time = $1, date = $2, updated_at = NOW() WHERE id = $3")
+                        .bind(new_start)
+                        .bind(super::timezone::local_date_string(new_start))
+                        .bind(&shadow.id)
+                        .execute(pool)
+                        .await
+                        .map_err(|e| db_context("reconcile shrink shadow", e))?;
+                }
+                report.shrunk.push(shadow.id.clone());
+            }
+        }
+    }
+
+    let changed = report.merged.len() + report.skipped.len() + report.shrunk.len() + report.duplicates.len();
+    if !dry_run && changed > 0 {
+        crate::dashboard::mark_snapshot_stale(pool).await;
+    }
+    log::info!(
+        "[SHADOW] Reconciled {} ({:?}): {} checked, {} merged, {} skipped, {} shrunk, {} duplicates{}",
+        date, policy, report.checked, report.merged.len(), report.skipped.len(), report.shrunk.len(),
+        report.duplicates.len(), if dry_run { " (dry run)" } else { "" }
+    );
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn t(h: u32, m: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 3, 2, h, m, 0).unwrap()
+    }
+
+    #[test]
+    fn test_resolve_collision() {
+        let none = Duration::zero();
+        let (start, end) = (t(10, 0), t(10, 30));
+        let before = vec![("a".to_string(), t(9, 0), t(10, 10))];
+        assert_eq!(resolve_collision(ShadowCollisionPolicy::Shrink, none, start, end, &before), Collision::Shrink(t(10, 10)));
+        assert_eq!(resolve_collision(ShadowCollisionPolicy::Skip, none, start, end, &before), Collision::Skip);
+        assert_eq!(resolve_collision(ShadowCollisionPolicy::Merge, none, start, end, &before), Collision::Merge("a".into()));
+        assert_eq!(resolve_collision(ShadowCollisionPolicy::Keep, none, start, end, &before), Collision::Clear);
+        // Submission inside a real block merges even under shrink
+        let covering = vec![("b".to_string(), t(10, 15), t(11, 0))];
+        assert_eq!(resolve_collision(ShadowCollisionPolicy::Shrink, none, start, end, &covering), Collision::Merge("b".into()));
+        // A block ending 5 minutes before the submission is the same session within a 10 minute window
+        let near = vec![("c".to_string(), t(9, 0), t(10, 25))];
+        assert_eq!(resolve_collision(ShadowCollisionPolicy::Shrink, none, start, end, &near), Collision::Shrink(t(10, 25)));
+        assert_eq!(
+            resolve_collision(ShadowCollisionPolicy::Shrink, Duration::minutes(10), start, end, &near),
+            Collision::Merge("c".into())
+        );
+        assert_eq!(resolve_collision(ShadowCollisionPolicy::Shrink, none, start, end, &[]), Collision::Clear);
+    }
+}
@@ -1,8 +1,10 @@
 use chrono::{DateTime, Utc};
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+use sqlx::{Postgres, QueryBuilder};
 use tauri::State;
 
 use crate::PosDb;
+use crate::streaks::{self, StreakKind};
 use super::error::{PosError, PosResult, db_context};
 
 // ─── Row type ───────────────────────────────────────────────────────
@@ -21,27 +23,318 @@ pub struct SubmissionRow {
     pub difficulty: Option<String>,
     pub tags: Vec<String>,
     pub created_at: DateTime<Utc>,
+    /// Set by exclude_submission; excluded rows are ignored by stats, ladder sync and shadows
+    pub excluded_at: Option<DateTime<Utc>>,
+    pub exclusion_reason: Option<String>,
+    pub metadata_edited_at: Option<DateTime<Utc>>,
 }
 
+pub(crate) const SUBMISSION_COLS: &str = "id, platform, problem_id, problem_title, submitted_time, verdict, language, \
+    rating, difficulty, tags, created_at, excluded_at, exclusion_reason, metadata_edited_at";
+
+/// Hand corrections for a scraped submission; omitted fields are left unchanged
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubmissionMetadataUpdate {
+    pub problem_title: Option<String>,
+    pub verdict: Option<String>,
+    pub language: Option<String>,
+    pub rating: Option<i32>,
+    pub difficulty: Option<String>,
+    pub tags: Option<Vec<String>>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BestSubmission {
+    pub id: String,
+    pub verdict: String,
+    pub language: String,
+    pub submitted_time: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProblemStatus {
+    pub problem_id: String,
+    /// "solved" | "attempted" | "unsolved"
+    pub status: String,
+    pub attempts: i64,
+    pub last_verdict: Option<String>,
+    pub last_submitted_at: Option<DateTime<Utc>>,
+    /// First accepted submission, or the latest attempt if never accepted
+    pub best_submission: Option<BestSubmission>,
+}
+
+#[derive(sqlx::FromRow)]
+struct ProblemStatusRow {
+    problem_id: String,
+    attempts: i64,
+    solved: bool,
+    last_verdict: Option<String>,
+    last_submitted_at: Option<DateTime<Utc>>,
+    best_id: Option<String>,
+    best_verdict: Option<String>,
+    best_language: Option<String>,
+    best_submitted_time: Option<DateTime<Utc>>,
+}
+
+const MAX_STATUS_LOOKUP: usize = 1000;
+
 // ─── Commands ───────────────────────────────────────────────────────
 
-/// Fetch last 100 submissions ordered by submitted_time DESC.
+/// Fetch last 100 submissions ordered by submitted_time DESC (excluded ones included,
+/// flagged, so they can be re-included).
 #[tauri::command]
 pub async fn get_submissions(
     db: State<'_, PosDb>,
 ) -> PosResult<Vec<SubmissionRow>> {
     let pool = &db.0;
 
-    let rows = sqlx::query_as::<_, SubmissionRow>(
-        r#"SELECT id, platform, problem_id, problem_title, submitted_time,
-                  verdict, language, rating, difficulty, tags, created_at
-           FROM pos_submissions
-           ORDER BY submitted_time DESC
-           LIMIT 100"#,
-    )
+    let rows = sqlx::query_as::<_, SubmissionRow>(&format!(
+        "SELECT {} FROM pos_submissions ORDER BY submitted_time DESC LIMIT 100",
+        SUBMISSION_COLS
+    ))
     .fetch_all(pool)
     .await
     .map_err(|e| db_context("get submissions", e))?;
 
     Ok(rows)
 }
+
+/// Solved/attempted/unsolved for a list of normalized problem IDs (`cf-1520A`,
+/// `leetcode-two-sum`) in one query. Bare Codeforces IDs (`1520A`) also match, as in
+/// the recommendation tables. Results keep the input order, duplicates dropped.
+#[tauri::command]
+pub async fn get_problem_statuses(
+    db: State<'_, PosDb>,
+    problem_ids: Vec<String>,
+) -> PosResult<Vec<ProblemStatus>> {
+    let mut seen = std::collections::HashSet::new();
+    let ids: Vec<String> = problem_ids.into_iter()
+        .map(|id| id.trim().to_string())
+        .filter(|id| !id.is_empty() && seen.insert(id.clone()))
+        .collect();
+    if ids.len() > MAX_STATUS_LOOKUP {
+        return Err(PosError::InvalidInput(format!(
+            "At most {} problem IDs per lookup", MAX_STATUS_LOOKUP
+        )));
+    }
+    if ids.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let rows = sqlx::query_as::<_, ProblemStatusRow>(
+        r#"SELECT r.pid AS problem_id,
+                  COALESCE(agg.attempts, 0) AS attempts,
+                  COALESCE(agg.solved, FALSE) AS solved,
+                  last.verdict AS last_verdict,
+                  last.submitted_time AS last_submitted_at,
+                  best.id AS best_id,
+                  best.verdict AS best_verdict,
+                  best.language AS best_language,
+                  best.submitted_time AS best_submitted_time
+           FROM UNNEST($1::text[]) WITH ORDINALITY AS r(pid, ord)
+           LEFT JOIN LATERAL (
+               SELECT COUNT(*) AS attempts, BOOL_OR(s.verdict IN ('OK', 'Accepted')) AS solved
+               FROM pos_submissions s WHERE s.problem_id IN (r.pid, 'cf-' || r.pid) AND s.excluded_at IS NULL
+           ) agg ON TRUE
+           LEFT JOIN LATERAL (
+               SELECT s.verdict, s.submitted_time
+               FROM pos_submissions s WHERE s.problem_id IN (r.pid, 'cf-' || r.pid) AND s.excluded_at IS NULL
+               ORDER BY s.submitted_time DESC LIMIT 1
+           ) last ON TRUE
+           LEFT JOIN LATERAL (
+               SELECT s.id, s.verdict, s.language, s.submitted_time
+               FROM pos_submissions s WHERE s.problem_id IN (r.pid, 'cf-' || r.pid) AND s.excluded_at IS NULL
+               ORDER BY (s.verdict IN ('OK', 'Accepted')) DESC,
+                        CASE WHEN s.verdict IN ('OK', 'Accepted') THEN s.submitted_time END ASC,
+                        s.submitted_time DESC
+               LIMIT 1
+           ) best ON TRUE
+           ORDER BY r.ord"#,
+    )
+    .bind(&ids)
+    .fetch_all(&db.0)
+    .await
+    .map_err(|e| db_context("get_problem_statuses", e))?;
+
+    Ok(rows.into_iter().map(|r| {
+        let status = if r.solved { "solved" } else if r.attempts > 0 { "attempted" } else { "unsolved" };
+        let best_submission = match (r.best_id, r.best_verdict, r.best_language, r.best_submitted_time) {
+            (Some(id), Some(verdict), Some(language), Some(submitted_time)) => {
+                Some(BestSubmission { id, verdict, language, submitted_time })
+            }
+            _ => None,
+        };
+        ProblemStatus {
+            problem_id: r.problem_id,
+            status: status.to_string(),
+            attempts: r.attempts,
+            last_verdict: r.last_verdict,
+            last_submitted_at: r.last_submitted_at,
+            best_submission,
+        }
+    }).collect())
+}
+
+/// Drop ladder/category progress for a problem that no longer counts as solved
+async fn prune_progress(tx: &mut sqlx::Transaction<'_, Postgres>, problem_id: &str) -> PosResult<()> {
+    for table in ["cf_ladder_progress", "cf_category_progress"] {
+        sqlx::query(&format!(
+            r#"DELETE FROM {} pr WHERE ('cf-' || pr.problem_id) = $1
+               AND NOT EXISTS (SELECT 1 FROM solved_problems s WHERE s.problem_id = $1)"#,
+            table
+        ))
+        .bind(problem_id)
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| db_context(&format!("prune {}", table), e))?;
+    }
+    Ok(())
+}
+
+/// Exclude a wrongly scraped submission (e.g. a practice-mode run) from stats, ladder
+/// progress and shadow time. Its shadow activity is soft-deleted. Reversible with
+/// include_submission.
+#[tauri::command]
+pub async fn exclude_submission(
+    db: State<'_, PosDb>,
+    id: String,
+    reason: String,
+) -> PosResult<SubmissionRow> {
+    let pool = &db.0;
+    let reason = reason.trim();
+    if reason.is_empty() {
+        return Err(PosError::InvalidInput("An exclusion reason is required".into()));
+    }
+
+    let mut tx = pool.begin().await.map_err(|e| db_context("TX begin", e))?;
+    let row = sqlx::query_as::<_, SubmissionRow>(&format!(
+        r#"UPDATE pos_submissions
+           SET excluded_at = COALESCE(excluded_at, NOW()), exclusion_reason = $2, updated_at = NOW()
+           WHERE id = $1 RETURNING {}"#,
+        SUBMISSION_COLS
+    ))
+    .bind(&id)
+    .bind(reason)
+    .fetch_optional(&mut *tx)
+    .await
+    .map_err(|e| db_context("exclude_submission", e))?
+    .ok_or_else(|| PosError::NotFound(format!("Submission not found: {}", id)))?;
+
+    // Shadow activities end at their submission's time (unique per submission)
+    sqlx::query(
+        r#"UPDATE pos_activities SET deleted_at = NOW(), updated_at = NOW()
+           WHERE is_shadow = TRUE AND end_time = $1 AND deleted_at IS NULL"#
+    )
+    .bind(row.submitted_time)
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| db_context("exclude shadow activity", e))?;
+
+    prune_progress(&mut tx, &row.problem_id).await?;
+    tx.commit().await.map_err(|e| db_context("TX commit", e))?;
+
+    crate::dashboard::mark_snapshot_stale(pool).await;
+    streaks::invalidate(pool, StreakKind::Solved).await;
+    log::info!("[SUBMISSIONS] Excluded {} ({}): {}", row.id, row.problem_id, reason);
+    Ok(row)
+}
+
+/// Undo exclude_submission. The shadow activity is restored; ladder progress comes back
+/// with the next ladder sync.
+#[tauri::command]
+pub async fn include_submission(db: State<'_, PosDb>, id: String) -> PosResult<SubmissionRow> {
+    let pool = &db.0;
+    let mut tx = pool.begin().await.map_err(|e| db_context("TX begin", e))?;
+
+    let row = sqlx::query_as::<_, SubmissionRow>(&format!(
+        "UPDATE pos_submissions SET excluded_at = NULL, exclusion_reason = NULL, updated_at = NOW() WHERE id = $1 RETURNING {}",
+        SUBMISSION_COLS
+    ))
+    .bind(&id)
+    .fetch_optional(&mut *tx)
+    .await
+    .map_err(|e| db_context("include_submission", e))?
+    .ok_or_else(|| PosError::NotFound(format!("Submission not found: {}", id)))?;
+
+    sqlx::query(
+        r#"UPDATE pos_activities SET deleted_at = NULL, updated_at = NOW()
+           WHERE is_shadow = TRUE AND end_time = $1 AND deleted_at IS NOT NULL"#
+    )
+    .bind(row.submitted_time)
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| db_context("restore shadow activity", e))?;
+    tx.commit().await.map_err(|e| db_context("TX commit", e))?;
+
+    crate::dashboard::mark_snapshot_stale(pool).await;
+    streaks::invalidate(pool, StreakKind::Solved).await;
+    log::info!("[SUBMISSIONS] Re-included {} ({})", row.id, row.problem_id);
+    Ok(row)
+}
+
+/// Correct scraped metadata. Edited rows are skipped by later scraper backfills.
+#[tauri::command]
+pub async fn edit_submission_metadata(
+    db: State<'_, PosDb>,
+    id: String,
+    fields: SubmissionMetadataUpdate,
+) -> PosResult<SubmissionRow> {
+    let pool = &db.0;
+    let text = |v: Option<String>, name: &str| -> PosResult<Option<String>> {
+        match v.map(|s| s.trim().to_string()) {
+            Some(s) if s.is_empty() => Err(PosError::InvalidInput(format!("{} cannot be empty", name))),
+            other => Ok(other),
+        }
+    };
+    let problem_title = text(fields.problem_title, "problemTitle")?;
+    let verdict = text(fields.verdict, "verdict")?;
+    let language = text(fields.language, "language")?;
+    let verdict_changed = verdict.is_some();
+
+    let mut query = QueryBuilder::<Postgres>::new("UPDATE pos_submissions SET ");
+    let mut set = query.separated(", ");
+    set.push("metadata_edited_at = NOW()");
+    set.push("updated_at = NOW()");
+    if let Some(v) = problem_title {
+        set.push("problem_title = ").push_bind_unseparated(v);
+    }
+    if let Some(v) = verdict {
+        set.push("verdict = ").push_bind_unseparated(v);
+    }
+    if let Some(v) = language {
+        set.push("language = ").push_bind_unseparated(v);
+    }
+    if let Some(v) = fields.rating {
+        set.push("rating = ").push_bind_unseparated(v);
+    }
+    if let Some(v) = fields.difficulty {
+        set.push("difficulty = ").push_bind_unseparated(Some(v).filter(|d| !d.trim().is_empty()));
+    }
+    if let Some(v) = fields.tags {
+        let tags: Vec<String> = v.into_iter().map(|t| t.trim().to_string()).filter(|t| !t.is_empty()).collect();
+        set.push("tags = ").push_bind_unseparated(tags);
+    }
+    query.push(" WHERE id = ").push_bind(id.clone());
+    query.push(format!(" RETURNING {}", SUBMISSION_COLS));
+
+    let mut tx = pool.begin().await.map_err(|e| db_context("TX begin", e))?;
+    let row = query
+        .build_query_as::<SubmissionRow>()
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| db_context("edit_submission_metadata", e))?
+        .ok_or_else(|| PosError::NotFound(format!("Submission not found: {}", id)))?;
+    // A verdict corrected away from accepted may un-solve the problem
+    if verdict_changed {
+        prune_progress(&mut tx, &row.problem_id).await?;
+    }
+    tx.commit().await.map_err(|e| db_context("TX commit", e))?;
+
+    crate::dashboard::mark_snapshot_stale(pool).await;
+    streaks::invalidate(pool, StreakKind::Solved).await;
+    log::info!("[SUBMISSIONS] Edited metadata of {} ({})", row.id, row.problem_id);
+    Ok(row)
+}
@@ -0,0 +1,293 @@
+// Local Timezone
+// The zone every stored local date is derived in: pos_activities.date, default goal dates,
+// "today" for debt marking and midnight splitting, and the default for commands whose
+// timezone offset argument is omitted. PosConfig::timezone (POS_TIMEZONE, an IANA name such
+// as Asia/Kolkata) pins it; otherwise the OS zone is detected at startup and re-checked
+// every SYSTEM_ZONE_POLL_SECS so travelling or changing the system clock zone follows along.
+// rederive_activity_dates fixes rows dated by the old UTC formatting.
+
+use std::sync::RwLock;
+use std::time::Duration;
+
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc};
+use chrono_tz::Tz;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, State};
+
+use crate::PosDb;
+use super::error::{PosResult, db_context};
+
+static ZONE: RwLock<Option<Tz>> = RwLock::new(None);
+static PINNED: RwLock<bool> = RwLock::new(false);
+
+const SYSTEM_ZONE_POLL_SECS: u64 = 60;
+const CHANGED_EVENT: &str = "timezone-changed";
+
+/// Rows listed per migration report; the count covers all of them
+const REPORT_SAMPLE: usize = 50;
+
+// ─── Zone ───────────────────────────────────────────────────────────
+
+/// Parse an IANA zone name (POS_TIMEZONE)
+pub fn parse_zone(name: &str) -> Result<Tz, String> {
+    name.trim().parse::<Tz>().map_err(|_| format!("Unknown IANA timezone: {}", name))
+}
+
+fn set_zone(zone: Option<Tz>) {
+    if let Ok(mut current) = ZONE.write() {
+        *current = zone;
+    }
+}
+
+/// The OS zone as IANA, if the platform reports one chrono-tz knows
+pub fn detect_system_zone() -> Option<Tz> {
+    match iana_time_zone::get_timezone() {
+        Ok(name) => parse_zone(&name).ok(),
+        Err(e) => {
+            log::warn!("[TIMEZONE] Could not read the system timezone: {}", e);
+            None
+        }
+    }
+}
+
+/// Use the configured zone, or the detected OS zone when none is configured
+pub fn init(configured: Option<Tz>) {
+    if let Ok(mut pinned) = PINNED.write() {
+        *pinned = configured.is_some();
+    }
+    set_zone(configured.or_else(detect_system_zone));
+}
+
+fn pinned() -> bool {
+    PINNED.read().map(|p| *p).unwrap_or(false)
+}
+
+fn zone() -> Option<Tz> {
+    ZONE.read().ok().and_then(|z| *z)
+}
+
+/// IANA name of the active zone, or "system" if the OS zone couldn't be identified
+pub fn zone_name() -> String {
+    zone().map_or_else(|| "system".to_string(), |tz| tz.name().to_string())
+}
+
+fn date_in(ts: DateTime<Utc>, zone: Option<Tz>) -> NaiveDate {
+    match zone {
+        Some(tz) => ts.with_timezone(&tz).date_naive(),
+        None => ts.with_timezone(&Local).date_naive(),
+    }
+}
+
+fn to_utc_in(naive: NaiveDateTime, zone: Option<Tz>) -> Option<DateTime<Utc>> {
+    match zone {
+        Some(tz) => tz.from_local_datetime(&naive).earliest().map(|dt| dt.with_timezone(&Utc)),
+        None => Local.from_local_datetime(&naive).earliest().map(|dt| dt.with_timezone(&Utc)),
+    }
+}
+
+// ─── Helpers ────────────────────────────────────────────────────────
+
+/// Local calendar date of a UTC instant
+pub fn local_date(ts: DateTime<Utc>) -> NaiveDate {
+    date_in(ts, zone())
+}
+
+/// Local wall-clock time of a UTC instant
+pub fn local_datetime(ts: DateTime<Utc>) -> NaiveDateTime {
+    match zone() {
+        Some(tz) => ts.with_timezone(&tz).naive_local(),
+        None => ts.with_timezone(&Local).naive_local(),
+    }
+}
+
+/// Local calendar date of a UTC instant as YYYY-MM-DD
+pub fn local_date_string(ts: DateTime<Utc>) -> String {
+    local_date(ts).format("%Y-%m-%d").to_string()
+}
+
+pub fn today() -> NaiveDate {
+    local_date(Utc::now())
+}
+
+/// Today's local date as YYYY-MM-DD
+pub fn today_string() -> String {
+    today().format("%Y-%m-%d").to_string()
+}
+
+/// UTC instant of a local wall-clock time (the earlier one when DST repeats it)
+pub fn from_local(naive: NaiveDateTime) -> Option<DateTime<Utc>> {
+    to_utc_in(naive, zone())
+}
+
+/// The current offset in JS getTimezoneOffset() form (minutes, negative for UTC+ zones),
+/// for commands called without one
+pub fn js_offset_minutes() -> i32 {
+    let now = Utc::now();
+    let local = match zone() {
+        Some(tz) => now.with_timezone(&tz).naive_local(),
+        None => now.with_timezone(&Local).naive_local(),
+    };
+    -((local - now.naive_utc()).num_minutes() as i32)
+}
+
+/// SQL expression for the local calendar date of a timestamptz column, so GROUP BY
+/// buckets agree with local_date(). Falls back to the current offset when the zone
+/// couldn't be identified.
+pub fn sql_local_date(column: &str) -> String {
+    match zone() {
+        Some(tz) => format!("({} AT TIME ZONE '{}')::date", column, tz.name()),
+        None => format!("(({} AT TIME ZONE 'UTC') - make_interval(mins => {}))::date", column, js_offset_minutes()),
+    }
+}
+
+/// UTC instant of local midnight starting `date`
+pub fn day_start(date: NaiveDate) -> Option<DateTime<Utc>> {
+    from_local(date.and_time(NaiveTime::MIN))
+}
+
+/// First local midnight after `ts`
+pub fn next_midnight(ts: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    let zone = zone();
+    let next_day = date_in(ts, zone).succ_opt()?;
+    to_utc_in(next_day.and_time(NaiveTime::MIN), zone)
+}
+
+// ─── System zone watcher ────────────────────────────────────────────
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TimezoneInfo {
+    /// IANA name, or "system" if the OS zone couldn't be identified
+    pub zone: String,
+    /// "config" when POS_TIMEZONE pins it, else "system"
+    pub source: String,
+    /// JS getTimezoneOffset() form: negative for UTC+ zones
+    pub offset_minutes: i32,
+}
+
+fn info() -> TimezoneInfo {
+    TimezoneInfo {
+        zone: zone_name(),
+        source: if pinned() { "config" } else { "system" }.to_string(),
+        offset_minutes: js_offset_minutes(),
+    }
+}
+
+/// Follow OS zone changes unless POS_TIMEZONE pins the zone; emits `timezone-changed`
+pub fn watch_system_zone(app: AppHandle) {
+    if pinned() {
+        return;
+    }
+    tauri::async_runtime::spawn(async move {
+        loop {
+            tokio::time::sleep(Duration::from_secs(SYSTEM_ZONE_POLL_SECS)).await;
+            let detected = detect_system_zone();
+            if detected.is_some() && detected != zone() {
+                let previous = zone_name();
+                set_zone(detected);
+                log::info!("[TIMEZONE] System timezone changed: {} -> {}", previous, zone_name());
+                let _ = app.emit(CHANGED_EVENT, info());
+            }
+        }
+    });
+}
+
+#[tauri::command]
+pub fn get_timezone() -> TimezoneInfo {
+    info()
+}
+
+// ─── Migration ──────────────────────────────────────────────────────
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RederivedDate {
+    pub id: String,
+    pub old_date: String,
+    pub new_date: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RederiveReport {
+    pub zone: String,
+    pub dry_run: bool,
+    pub checked: usize,
+    pub changed_count: usize,
+    /// Up to REPORT_SAMPLE of the changed rows
+    pub changed: Vec<RederivedDate>,
+}
+
+/// Re-date activities whose date is the UTC date of their start but not the local one.
+/// Dates that already differ from the UTC date were chosen deliberately and are left alone;
+/// goal dates are always user-chosen, so goals aren't touched.
+#[tauri::command]
+pub async fn rederive_activity_dates(
+    db: State<'_, PosDb>,
+    dry_run: bool,
+) -> PosResult<RederiveReport> {
+    let pool = &db.0;
+    let rows: Vec<(String, String, DateTime<Utc>)> = sqlx::query_as(
+        r#"SELECT id, date, start_time FROM pos_activities
+           WHERE deleted_at IS NULL AND date = to_char(start_time AT TIME ZONE 'UTC', 'YYYY-MM-DD')
+           ORDER BY start_time"#
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| db_context("fetch activity dates", e))?;
+
+    let checked = rows.len();
+    let changed: Vec<RederivedDate> = rows.into_iter().filter_map(|(id, old_date, start)| {
+        let new_date = local_date_string(start);
+        (new_date != old_date).then_some(RederivedDate { id, old_date, new_date })
+    }).collect();
+
+    if !dry_run && !changed.is_empty() {
+        let ids: Vec<&str> = changed.iter().map(|c| c.id.as_str()).collect();
+        let dates: Vec<&str> = changed.iter().map(|c| c.new_date.as_str()).collect();
+        let mut tx = pool.begin().await.map_err(|e| db_context("begin rederive", e))?;
+        sqlx::query(
+            r#"UPDATE pos_activities a SET date = t.date, updated_at = NOW()
+               FROM UNNEST($1::text[], $2::text[]) AS t(id, date)
+               WHERE a.id = t.id"#
+        )
+        .bind(&ids)
+        .bind(&dates)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| db_context("rederive activity dates", e))?;
+        tx.commit().await.map_err(|e| db_context("commit rederive", e))?;
+
+        crate::dashboard::mark_snapshot_stale(pool).await;
+        crate::streaks::invalidate(pool, crate::streaks::StreakKind::Activity).await;
+        log::info!("[TIMEZONE] Re-dated {} of {} activities to {}", changed.len(), checked, zone_name());
+    }
+
+    let changed_count = changed.len();
+    Ok(RederiveReport {
+        zone: zone_name(),
+        dry_run,
+        checked,
+        changed_count,
+        changed: changed.into_iter().take(REPORT_SAMPLE).collect(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dates_in_configured_zone() {
+        let kolkata = Some(parse_zone("Asia/Kolkata").unwrap());
+        let la = Some(parse_zone("America/Los_Angeles").unwrap());
+        // 20:00 UTC is already tomorrow in IST and still today in PST
+        let ts = DateTime::parse_from_rfc3339("2024-03-10T20:00:00Z").unwrap().with_timezone(&Utc);
+        assert_eq!(date_in(ts, kolkata).to_string(), "2024-03-11");
+        assert_eq!(date_in(ts, la).to_string(), "2024-03-10");
+
+        let midnight = to_utc_in(NaiveDate::from_ymd_opt(2024, 3, 11).unwrap().and_time(NaiveTime::MIN), kolkata);
+        assert_eq!(midnight.unwrap().to_rfc3339(), "2024-03-10T18:30:00+00:00");
+        assert!(parse_zone("Mars/Olympus").is_err());
+    }
+}
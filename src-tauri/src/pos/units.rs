@@ -0,0 +1,168 @@
+use serde::Serialize;
+use sqlx::PgPool;
+
+use crate::pos::error::{PosError, PosResult};
+
+// ─── Registry ───────────────────────────────────────────────────────
+
+/// A known metric unit. `factor` converts one of this unit into the
+/// dimension's base unit (minutes for time, 1.0 for plain counts).
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UnitDef {
+    pub canonical: &'static str,
+    pub aliases: &'static [&'static str],
+    pub dimension: &'static str, // "time" | "count" | "distance"
+    pub factor: f64,
+}
+
+pub const UNIT_REGISTRY: &[UnitDef] = &[
+    // Time (base: minutes)
+    UnitDef { canonical: "minutes",   aliases: &["min", "mins", "minute"],               dimension: "time",     factor: 1.0 },
+    UnitDef { canonical: "hours",     aliases: &["hr", "hrs", "hour", "h"],              dimension: "time",     factor: 60.0 },
+    // Counts (no conversions between them)
+    UnitDef { canonical: "problems",  aliases: &["problem", "prob", "probs", "questions", "question", "qs"], dimension: "count", factor: 1.0 },
+    UnitDef { canonical: "pages",     aliases: &["page", "pg", "pgs", "pp"],             dimension: "count",    factor: 1.0 },
+    UnitDef { canonical: "reps",      aliases: &["rep", "repetitions", "repetition"],    dimension: "count",    factor: 1.0 },
+    UnitDef { canonical: "sessions",  aliases: &["session"],                             dimension: "count",    factor: 1.0 },
+    UnitDef { canonical: "chapters",  aliases: &["chapter", "ch", "chs"],                dimension: "count",    factor: 1.0 },
+    UnitDef { canonical: "videos",    aliases: &["video", "lectures", "lecture"],        dimension: "count",    factor: 1.0 },
+    UnitDef { canonical: "commits",   aliases: &["commit"],                              dimension: "count",    factor: 1.0 },
+    UnitDef { canonical: "words",     aliases: &["word"],                                dimension: "count",    factor: 1.0 },
+    UnitDef { canonical: "items",     aliases: &["item", "count", "times", "x"],         dimension: "count",    factor: 1.0 },
+    // Distance (base: kilometers)
+    UnitDef { canonical: "km",        aliases: &["kms", "kilometer", "kilometers", "kilometre", "kilometres"], dimension: "distance", factor: 1.0 },
+    UnitDef { canonical: "meters",    aliases: &["meter", "metre", "metres"],            dimension: "distance", factor: 0.001 },
+];
+
+/// Look up a unit by canonical name or alias (case/whitespace-insensitive).
+pub fn lookup_unit(raw: &str) -> Option<&'static UnitDef> {
+    let key = raw.trim().to_lowercase();
+    if key.is_empty() {
+        return None;
+    }
+    UNIT_REGISTRY
+        .iter()
+        .find(|u| u.canonical == key || u.aliases.contains(&key.as_str()))
+}
+
+/// Validate a free-text unit and return its canonical name.
+/// Empty strings are allowed and stay empty (unit-less metric).
+pub fn normalize_unit(raw: &str) -> PosResult<String> {
+    if raw.trim().is_empty() {
+        return Ok(String::new());
+    }
+    lookup_unit(raw)
+        .map(|u| u.canonical.to_string())
+        .ok_or_else(|| {
+            let known: Vec<&str> = UNIT_REGISTRY.iter().map(|u| u.canonical).collect();
+            PosError::InvalidInput(format!("Unknown unit '{}'. Known units: {}", raw, known.join(", ")))
+        })
+}
+
+/// Convert a value between two units of the same dimension (e.g. minutes ↔ hours).
+pub fn convert_value(value: f64, from: &str, to: &str) -> PosResult<f64> {
+    let src = lookup_unit(from)
+        .ok_or_else(|| PosError::InvalidInput(format!("Unknown unit '{}'", from)))?;
+    let dst = lookup_unit(to)
+        .ok_or_else(|| PosError::InvalidInput(format!("Unknown unit '{}'", to)))?;
+
+    if src.canonical == dst.canonical {
+        return Ok(value);
+    }
+    if src.dimension != dst.dimension || src.dimension == "count" {
+        return Err(PosError::InvalidInput(format!(
+            "Cannot convert '{}' to '{}'", src.canonical, dst.canonical
+        )));
+    }
+    Ok(value * src.factor / dst.factor)
+}
+
+// ─── Migration ──────────────────────────────────────────────────────
+
+/// Rewrite existing free-text units to their canonical names.
+/// Runs after DDL on every startup — only touches rows that still use an alias.
+pub async fn normalize_existing_units(pool: &PgPool) -> Result<(), sqlx::Error> {
+    let mut milestones_updated = 0u64;
+    let mut goals_updated = 0u64;
+
+    for unit in UNIT_REGISTRY {
+        let aliases: Vec<String> = unit.aliases.iter().map(|a| a.to_string()).collect();
+
+        milestones_updated += sqlx::query(
+            "UPDATE goal_periods SET unit = $1
+             WHERE unit IS NOT NULL AND unit <> $1
+               AND (LOWER(TRIM(unit)) = $1 OR LOWER(TRIM(unit)) = ANY($2))"
+        )
+        .bind(unit.canonical)
+        .bind(&aliases)
+        .execute(pool)
+        .await?
+        .rows_affected();
+
+        goals_updated += sqlx::query(
+            r#"UPDATE unified_goals SET metrics = (
+                   SELECT jsonb_agg(
+                       CASE WHEN LOWER(TRIM(m->>'unit')) = $1 OR LOWER(TRIM(m->>'unit')) = ANY($2)
+                            THEN jsonb_set(m, '{unit}', to_jsonb($1::text))
+                            ELSE m END
+                       ORDER BY ord)
+                   FROM jsonb_array_elements(metrics) WITH ORDINALITY AS e(m, ord)
+               ), updated_at = NOW()
+               WHERE jsonb_typeof(metrics) = 'array'
+                 AND EXISTS (
+                     SELECT 1 FROM jsonb_array_elements(metrics) m
+                     WHERE m->>'unit' <> $1
+                       AND (LOWER(TRIM(m->>'unit')) = $1 OR LOWER(TRIM(m->>'unit')) = ANY($2))
+                 )"#
+        )
+        .bind(unit.canonical)
+        .bind(&aliases)
+        .execute(pool)
+        .await?
+        .rows_affected();
+    }
+
+    if milestones_updated > 0 || goals_updated > 0 {
+        log::info!("[UNITS] Normalized units on {} milestones and {} goals", milestones_updated, goals_updated);
+    }
+    Ok(())
+}
+
+// ─── Tauri Commands ─────────────────────────────────────────────────
+
+/// List all known units so the frontend can offer a picker instead of free text
+#[tauri::command]
+pub async fn get_unit_registry() -> PosResult<Vec<UnitDef>> {
+    Ok(UNIT_REGISTRY.to_vec())
+}
+
+/// Convert a metric value between compatible units
+#[tauri::command]
+pub async fn convert_unit_value(value: f64, from: String, to: String) -> PosResult<f64> {
+    convert_value(value, &from, &to)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_aliases_resolve_to_canonical() {
+        assert_eq!(normalize_unit("mins").unwrap(), "minutes");
+        assert_eq!(normalize_unit(" Hrs ").unwrap(), "hours");
+        assert_eq!(normalize_unit("Problem").unwrap(), "problems");
+        assert_eq!(normalize_unit("").unwrap(), "");
+        assert!(normalize_unit("furlongs").is_err());
+        // "m" could be minutes or meters, so it isn't an alias of either
+        assert!(normalize_unit("m").is_err());
+    }
+
+    #[test]
+    fn test_time_conversion() {
+        assert_eq!(convert_value(90.0, "minutes", "hours").unwrap(), 1.5);
+        assert_eq!(convert_value(2.0, "h", "min").unwrap(), 120.0);
+        assert!(convert_value(5.0, "problems", "pages").is_err());
+        assert!(convert_value(5.0, "hours", "km").is_err());
+    }
+}
@@ -0,0 +1,362 @@
+// Problem Detail
+// Everything known about one problem in a single payload for the problem page: metadata,
+// my submissions and attempt stats, time spent, the ladder note, hints, friends who solved
+// it, ladders/categories listing it, and knowledge items mentioning it.
+// Problem IDs come in two families (see problem_renormalize): submission-style ("cf-1520A")
+// and bare ladder IDs ("1520A"), and CF friend submissions use "cf_1520_A". Any of the
+// first two (or a problem URL) is accepted and the others are derived from it.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use tauri::State;
+
+use crate::PosDb;
+use crate::cf_ladder_system::{fetch_note, ProblemHintRow, ProblemNoteRow};
+use crate::knowledge_base::KnowledgeItemRow;
+use crate::knowledge_problems::normalize_problem_id;
+use crate::pos::error::{PosError, PosResult, db_context};
+use crate::pos::problem_url::problem_id_from_url;
+use crate::pos::submissions::{SubmissionRow, SUBMISSION_COLS};
+
+// ─── Types ──────────────────────────────────────────────────────────
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProblemAttempts {
+    pub submissions: usize,
+    /// Non-accepted submissions before the first AC (all of them while unsolved)
+    pub failed_before_ac: usize,
+    pub first_submitted_at: Option<DateTime<Utc>>,
+    pub first_accepted_at: Option<DateTime<Utc>>,
+    /// Accepted submission or marked known-solved
+    pub solved: bool,
+    /// First submission to first AC
+    pub minutes_to_first_ac: Option<f64>,
+    /// Activities linked to goals for this problem
+    pub activity_minutes: f64,
+    pub stuck_minutes: i64,
+    pub stuck_count: i64,
+    /// Time recorded against it in timed ladder sessions
+    pub session_minutes: i64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProblemHints {
+    pub total: i64,
+    pub revealed: Vec<ProblemHintRow>,
+    /// Hints revealed before the first AC
+    pub revealed_before_solve: usize,
+}
+
+#[derive(Debug, Serialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct FriendSolve {
+    pub handle: String,
+    pub display_name: Option<String>,
+    pub current_rating: Option<i32>,
+    pub solved_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct ProblemListing {
+    /// "ladder" | "category"
+    pub kind: String,
+    pub id: String,
+    pub name: String,
+    pub position: i32,
+    /// Marked solved in that ladder/category's progress
+    pub marked_solved: bool,
+    #[serde(skip)]
+    pub problem_name: String,
+    #[serde(skip)]
+    pub problem_url: String,
+    #[serde(skip)]
+    pub difficulty: Option<i32>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProblemDetail {
+    /// Submission-style id ("cf-1520A")
+    pub problem_id: String,
+    /// Ladder-style id ("1520A")
+    pub ladder_id: String,
+    pub judge: Option<String>,
+    pub title: Option<String>,
+    pub url: Option<String>,
+    pub rating: Option<i32>,
+    pub tags: Vec<String>,
+    /// Oldest first
+    pub submissions: Vec<SubmissionRow>,
+    pub attempts: ProblemAttempts,
+    pub note: Option<ProblemNoteRow>,
+    pub hints: ProblemHints,
+    pub friends_solved: Vec<FriendSolve>,
+    pub listings: Vec<ProblemListing>,
+    pub knowledge_items: Vec<KnowledgeItemRow>,
+    pub editorial_url: Option<String>,
+}
+
+#[derive(sqlx::FromRow)]
+struct CacheMeta {
+    name: String,
+    url: String,
+    rating: Option<i32>,
+    tags: Vec<String>,
+}
+
+/// The ids one problem is stored under
+#[derive(Debug, PartialEq)]
+struct ProblemKeys {
+    submission_id: String,
+    ladder_id: String,
+    judge: Option<&'static str>,
+    /// cf_friend_submissions id ("cf_1520_A"), Codeforces only
+    friend_id: Option<String>,
+}
+
+// ─── Helpers ────────────────────────────────────────────────────────
+
+fn problem_keys(input: &str) -> ProblemKeys {
+    let input = input.trim();
+    let submission_id = if input.contains("://") {
+        problem_id_from_url(input).unwrap_or_else(|| input.to_string())
+    } else {
+        normalize_problem_id(input)
+    };
+    let (judge, ladder_id) = [("cf-", "Codeforces"), ("atcoder-", "AtCoder"), ("leetcode-", "LeetCode")]
+        .iter()
+        .find_map(|(prefix, judge)| submission_id.strip_prefix(prefix).map(|rest| (Some(*judge), rest.to_string())))
+        .unwrap_or((None, submission_id.clone()));
+    let friend_id = (judge == Some("Codeforces")).then(|| {
+        let split = ladder_id.find(|c: char| !c.is_ascii_digit()).unwrap_or(ladder_id.len());
+        let (contest, index) = ladder_id.split_at(split);
+        (!contest.is_empty() && !index.is_empty()).then(|| format!("cf_{}_{}", contest, index))
+    }).flatten();
+    ProblemKeys { submission_id, ladder_id, judge, friend_id }
+}
+
+fn is_accepted(verdict: &str) -> bool {
+    matches!(verdict, "OK" | "Accepted" | "AC")
+}
+
+// ─── Command ────────────────────────────────────────────────────────
+
+/// One payload for the problem page. `problem_id` may be "cf-1520A", "1520A",
+/// "atcoder-abc300_a", "leetcode-two-sum" or a problem URL.
+#[tauri::command]
+pub async fn get_problem_detail(
+    db: State<'_, PosDb>,
+    problem_id: String,
+) -> PosResult<ProblemDetail> {
+    let pool = &db.0;
+    if problem_id.trim().is_empty() {
+        return Err(PosError::InvalidInput("problem_id is required".into()));
+    }
+    let keys = problem_keys(&problem_id);
+    let sub_id = &keys.submission_id;
+    let judge = keys.judge.unwrap_or("");
+
+    let (submissions, known_solved, cache, (activity_minutes, stuck_minutes, stuck_count, session_minutes)) = tokio::try_join!(
+        async {
+            sqlx::query_as::<_, SubmissionRow>(&format!(
+                "SELECT {} FROM pos_submissions WHERE problem_id = $1 AND excluded_at IS NULL ORDER BY submitted_time",
+                SUBMISSION_COLS
+            ))
+            .bind(sub_id)
+            .fetch_all(pool)
+            .await
+            .map_err(|e| db_context("problem detail submissions", e))
+        },
+        async {
+            sqlx::query_scalar::<_, bool>("SELECT EXISTS (SELECT 1 FROM known_solved WHERE problem_id = $1)")
+                .bind(sub_id)
+                .fetch_one(pool)
+                .await
+                .map_err(|e| db_context("problem detail known solved", e))
+        },
+        async {
+            sqlx::query_as::<_, CacheMeta>(
+                "SELECT name, url, rating, tags FROM problemset_cache WHERE problem_id = $1 AND judge = LOWER($2)"
+            )
+            .bind(&keys.ladder_id)
+            .bind(judge)
+            .fetch_optional(pool)
+            .await
+            .map_err(|e| db_context("problem detail problemset", e))
+        },
+        async {
+            sqlx::query_as::<_, (f64, i64, i64, i64)>(
+                r#"SELECT
+                       (SELECT COALESCE(SUM(EXTRACT(EPOCH FROM (a.end_time - a.start_time)) / 60), 0)::float8
+                        FROM pos_activities a
+                        WHERE a.deleted_at IS NULL
+                          AND a.goal_ids && ARRAY(SELECT id FROM unified_goals WHERE problem_id = $1 AND deleted_at IS NULL)),
+                       (SELECT COALESCE(SUM(minutes_spent), 0)::bigint FROM stuck_logs WHERE problem_id = $1),
+                       (SELECT COUNT(*) FROM stuck_logs WHERE problem_id = $1),
+                       (SELECT COALESCE(SUM(actual_minutes), 0)::bigint FROM ladder_session_items
+                        WHERE problem_id = $2 AND LOWER(online_judge) = LOWER($3))"#
+            )
+            .bind(sub_id)
+            .bind(&keys.ladder_id)
+            .bind(judge)
+            .fetch_one(pool)
+            .await
+            .map_err(|e| db_context("problem detail time spent", e))
+        },
+    )?;
+
+    let (note, fetched_editorial, hints, hint_total, friends_solved, listings, knowledge_items) = tokio::try_join!(
+        fetch_note(pool, &keys.ladder_id),
+        async {
+            sqlx::query_scalar::<_, Option<String>>("SELECT editorial_url FROM cf_problem_editorials WHERE problem_id = $1")
+                .bind(&keys.ladder_id)
+                .fetch_optional(pool)
+                .await
+                .map(Option::flatten)
+                .map_err(|e| db_context("problem detail editorial", e))
+        },
+        async {
+            sqlx::query_as::<_, ProblemHintRow>(
+                r#"SELECT id, problem_id, position, hint_text, revealed_at, created_at FROM problem_hints
+                   WHERE problem_id = $1 AND revealed_at IS NOT NULL ORDER BY position"#
+            )
+            .bind(&keys.ladder_id)
+            .fetch_all(pool)
+            .await
+            .map_err(|e| db_context("problem detail hints", e))
+        },
+        async {
+            sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM problem_hints WHERE problem_id = $1")
+                .bind(&keys.ladder_id)
+                .fetch_one(pool)
+                .await
+                .map_err(|e| db_context("problem detail hint count", e))
+        },
+        async {
+            sqlx::query_as::<_, FriendSolve>(
+                r#"SELECT f.cf_handle AS handle, f.display_name, f.current_rating, s.submission_time AS solved_at
+                   FROM cf_friend_submissions s
+                   JOIN cf_friends f ON f.id = s.friend_id
+                   WHERE s.problem_id = $1 AND s.verdict = 'OK'
+                   ORDER BY s.submission_time"#
+            )
+            .bind(keys.friend_id.as_deref().unwrap_or(""))
+            .fetch_all(pool)
+            .await
+            .map_err(|e| db_context("problem detail friends", e))
+        },
+        async {
+            sqlx::query_as::<_, ProblemListing>(
+                r#"SELECT 'ladder' AS kind, l.id, l.name, lp.position,
+                          EXISTS (SELECT 1 FROM cf_ladder_progress pr
+                                  WHERE pr.ladder_id = l.id AND pr.problem_id = lp.problem_id
+                                    AND pr.solved_at IS NOT NULL) AS marked_solved,
+                          lp.problem_name, lp.problem_url, lp.difficulty
+                   FROM cf_ladder_problems lp JOIN cf_ladders l ON l.id = lp.ladder_id
+                   WHERE lp.problem_id = $1 AND LOWER(lp.online_judge) = LOWER($2)
+                   UNION ALL
+                   SELECT 'category', c.id, c.name, cp.position,
+                          EXISTS (SELECT 1 FROM cf_category_progress pr
+                                  WHERE pr.category_id = c.id AND pr.problem_id = cp.problem_id
+                                    AND pr.solved_at IS NOT NULL),
+                          cp.problem_name, cp.problem_url, cp.difficulty
+                   FROM cf_category_problems cp JOIN cf_categories c ON c.id = cp.category_id
+                   WHERE cp.problem_id = $1 AND LOWER(cp.online_judge) = LOWER($2)
+                   ORDER BY 1 DESC, 3"#
+            )
+            .bind(&keys.ladder_id)
+            .bind(judge)
+            .fetch_all(pool)
+            .await
+            .map_err(|e| db_context("problem detail listings", e))
+        },
+        async {
+            sqlx::query_as::<_, KnowledgeItemRow>(
+                r#"SELECT id, tags, source, content, metadata, status, next_review_date,
+                          linked_note_id, linked_journal_date, created_at, updated_at
+                   FROM knowledge_items
+                   WHERE metadata->'problemIds' ? $1
+                   ORDER BY created_at DESC"#
+            )
+            .bind(sub_id)
+            .fetch_all(pool)
+            .await
+            .map_err(|e| db_context("problem detail knowledge", e))
+        },
+    )?;
+
+    // Attempts
+    let first_ac = submissions.iter().find(|s| is_accepted(&s.verdict)).map(|s| s.submitted_time);
+    let first_sub = submissions.first().map(|s| s.submitted_time);
+    let failed_before_ac = submissions.iter()
+        .take_while(|s| !is_accepted(&s.verdict))
+        .count();
+    let attempts = ProblemAttempts {
+        submissions: submissions.len(),
+        failed_before_ac,
+        first_submitted_at: first_sub,
+        first_accepted_at: first_ac,
+        solved: first_ac.is_some() || known_solved,
+        minutes_to_first_ac: first_sub.zip(first_ac).map(|(s, a)| (a - s).num_seconds() as f64 / 60.0),
+        activity_minutes,
+        stuck_minutes,
+        stuck_count,
+        session_minutes,
+    };
+    let revealed_before_solve = hints.iter()
+        .filter(|h| match (h.revealed_at, first_ac) {
+            (Some(at), Some(ac)) => at <= ac,
+            (Some(_), None) => true,
+            _ => false,
+        })
+        .count();
+
+    // Metadata: problemset cache, then my latest submission, then a ladder listing
+    let latest = submissions.last();
+    let listing = listings.first();
+    let tags = latest.map(|s| s.tags.clone()).filter(|t| !t.is_empty())
+        .or_else(|| cache.as_ref().map(|c| c.tags.clone()))
+        .unwrap_or_default();
+    Ok(ProblemDetail {
+        title: cache.as_ref().map(|c| c.name.clone())
+            .or_else(|| latest.map(|s| s.problem_title.clone()))
+            .or_else(|| listing.map(|l| l.problem_name.clone())),
+        url: cache.as_ref().map(|c| c.url.clone())
+            .or_else(|| listing.map(|l| l.problem_url.clone())),
+        rating: cache.as_ref().and_then(|c| c.rating)
+            .or_else(|| submissions.iter().filter_map(|s| s.rating).max())
+            .or_else(|| listing.and_then(|l| l.difficulty)),
+        tags,
+        editorial_url: note.as_ref().and_then(|n| n.editorial_url.clone()).or(fetched_editorial),
+        problem_id: keys.submission_id,
+        ladder_id: keys.ladder_id,
+        judge: keys.judge.map(String::from),
+        submissions,
+        attempts,
+        note,
+        hints: ProblemHints { total: hint_total, revealed: hints, revealed_before_solve },
+        friends_solved,
+        listings,
+        knowledge_items,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_problem_keys() {
+        let cf = problem_keys("1520a");
+        assert_eq!(cf.submission_id, "cf-1520A");
+        assert_eq!(cf.ladder_id, "1520A");
+        assert_eq!(cf.judge, Some("Codeforces"));
+        assert_eq!(cf.friend_id.as_deref(), Some("cf_1520_A"));
+
+        let at = problem_keys("atcoder-abc300_a");
+        assert_eq!((at.ladder_id.as_str(), at.judge, at.friend_id), ("abc300_a", Some("AtCoder"), None));
+    }
+}
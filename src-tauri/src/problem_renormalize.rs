@@ -0,0 +1,346 @@
+// Problem ID Re-normalization
+// Problem IDs are computed once at ingest, so when normalize_problem_id / extract_problem_id
+// learn a new shape, older rows keep the old form and stop joining. renormalize_problem_ids
+// recomputes every stored ID with the current parsers and rewrites the ones that changed.
+// Two families exist: submission-style IDs (`cf-1520A`, `leetcode-two-sum`) used by
+// submissions, goals, milestones, known solves and knowledge items, and bare ladder IDs
+// (`1520A`) recomputed from each ladder/category row's URL and judge. Everything runs in one
+// transaction; a dry run executes the same statements and rolls back, so its counts are exact.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::Serialize;
+use serde_json::{json, Value};
+use sqlx::Postgres;
+use tauri::State;
+
+use crate::PosDb;
+use crate::cf_ladder_system::extract_problem_id;
+use crate::knowledge_problems::normalize_problem_id;
+use crate::known_solved::normalize_ref;
+use crate::pos::error::{PosResult, db_context};
+use crate::pos::problem_url::problem_id_from_url;
+
+const SAMPLE_LIMIT: usize = 5;
+
+type Tx<'a> = sqlx::Transaction<'a, Postgres>;
+
+/// A table holding a problem_id column
+struct Target {
+    table: &'static str,
+    /// Column the old → new mapping is scoped to (ladder IDs are only unique per ladder)
+    scope: Option<&'static str>,
+    /// Columns that, together with problem_id, are unique. A row whose renamed form already
+    /// exists is dropped in favour of the existing one. None when problem_id isn't unique.
+    unique_with: Option<&'static [&'static str]>,
+    /// Bump updated_at so delta sync picks the row up
+    touch: bool,
+}
+
+const SUBMISSION_STYLE_TARGETS: &[Target] = &[
+    Target { table: "pos_submissions", scope: None, unique_with: None, touch: true },
+    Target { table: "unified_goals", scope: None, unique_with: None, touch: true },
+    Target { table: "pos_goals", scope: None, unique_with: None, touch: false },
+    Target { table: "milestones", scope: None, unique_with: None, touch: false },
+    Target { table: "known_solved", scope: None, unique_with: Some(&[]), touch: false },
+];
+
+/// (problems table, progress table, scope column)
+const LADDER_TABLES: [(&str, &str, &str); 2] = [
+    ("cf_ladder_problems", "cf_ladder_progress", "ladder_id"),
+    ("cf_category_problems", "cf_category_progress", "category_id"),
+];
+
+/// Tables keyed by bare ladder IDs across all ladders
+const LADDER_KEYED_TARGETS: &[Target] = &[
+    Target { table: "cf_problem_notes", scope: None, unique_with: Some(&[]), touch: true },
+    Target { table: "cf_problem_editorials", scope: None, unique_with: Some(&[]), touch: false },
+    Target { table: "problem_hints", scope: None, unique_with: Some(&["position"]), touch: false },
+];
+
+// ─── Types ──────────────────────────────────────────────────────────
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IdRename {
+    pub from: String,
+    pub to: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TableRenormalization {
+    pub table: String,
+    pub rows_updated: u64,
+    /// Rows removed because their renamed form already existed
+    pub rows_merged: u64,
+    pub samples: Vec<IdRename>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RenormalizeReport {
+    pub dry_run: bool,
+    pub total_updated: u64,
+    pub total_merged: u64,
+    /// Only tables with at least one change
+    pub tables: Vec<TableRenormalization>,
+}
+
+/// (scope, old id) → new id; scope is empty for unscoped tables
+type RenameMap = BTreeMap<(String, String), String>;
+
+// ─── Normalization ──────────────────────────────────────────────────
+
+/// Current submission-style form of a stored ID: URLs are resolved, bare ladder IDs become
+/// Codeforces IDs and prefixed IDs are re-validated. None when the platform can't be told.
+fn submission_style_id(id: &str) -> Option<String> {
+    let id = normalize_problem_id(id);
+    if id.contains("://") {
+        return problem_id_from_url(&id);
+    }
+    let platform = if id.starts_with("cf-") {
+        "codeforces"
+    } else if id.starts_with("leetcode-") {
+        "leetcode"
+    } else if id.starts_with("atcoder-") {
+        "atcoder"
+    } else {
+        return None;
+    };
+    normalize_ref(platform, &id)
+}
+
+/// Positional fallbacks the ladder parser assigns when a URL isn't recognized
+fn is_placeholder(id: &str) -> bool {
+    id.starts_with("prob_") || id.starts_with("cat_prob_")
+}
+
+fn samples(renames: &RenameMap) -> Vec<IdRename> {
+    renames.iter()
+        .take(SAMPLE_LIMIT)
+        .map(|((_, from), to)| IdRename { from: from.clone(), to: to.clone() })
+        .collect()
+}
+
+// ─── Rewriting ──────────────────────────────────────────────────────
+
+/// Apply one rename map to a table: drop rows that would collide, then rename the rest
+async fn rewrite(tx: &mut Tx<'_>, target: &Target, renames: &RenameMap) -> PosResult<TableRenormalization> {
+    let mut report = TableRenormalization {
+        table: target.table.to_string(),
+        rows_updated: 0,
+        rows_merged: 0,
+        samples: samples(renames),
+    };
+    if renames.is_empty() {
+        return Ok(report);
+    }
+
+    let (mut scopes, mut olds, mut news) = (Vec::new(), Vec::new(), Vec::new());
+    for ((scope, old), new) in renames {
+        scopes.push(scope.clone());
+        olds.push(old.clone());
+        news.push(new.clone());
+    }
+    let table = target.table;
+    let scope_clause = target.scope.map(|c| format!("AND t.{} = m.scope", c)).unwrap_or_default();
+    let mapping = "UNNEST($1::text[], $2::text[], $3::text[]) AS m(scope, old_id, new_id)";
+
+    if let Some(cols) = target.unique_with {
+        let same_key: String = target.scope.iter().chain(cols.iter())
+            .map(|c| format!(" AND k.{c} = t.{c}"))
+            .collect();
+        report.rows_merged = sqlx::query(&format!(
+            r#"DELETE FROM {table} t USING {mapping}
+               WHERE t.problem_id = m.old_id {scope_clause}
+                 AND EXISTS (SELECT 1 FROM {table} k WHERE k.problem_id = m.new_id{same_key})"#
+        ))
+        .bind(&scopes).bind(&olds).bind(&news)
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| db_context(&format!("merge {}", table), e))?
+        .rows_affected();
+    }
+
+    let touch = if target.touch { ", updated_at = NOW()" } else { "" };
+    report.rows_updated = sqlx::query(&format!(
+        r#"UPDATE {table} t SET problem_id = m.new_id{touch}
+           FROM {mapping}
+           WHERE t.problem_id = m.old_id {scope_clause}"#
+    ))
+    .bind(&scopes).bind(&olds).bind(&news)
+    .execute(&mut **tx)
+    .await
+    .map_err(|e| db_context(&format!("rename {}", table), e))?
+    .rows_affected();
+
+    Ok(report)
+}
+
+/// Renames for an unscoped submission-style table
+async fn submission_style_renames(tx: &mut Tx<'_>, table: &str) -> PosResult<RenameMap> {
+    let ids: Vec<String> = sqlx::query_scalar(&format!(
+        "SELECT DISTINCT problem_id FROM {} WHERE problem_id IS NOT NULL", table
+    ))
+    .fetch_all(&mut **tx)
+    .await
+    .map_err(|e| db_context(&format!("read {} ids", table), e))?;
+
+    Ok(ids.into_iter()
+        .filter_map(|old| {
+            let new = submission_style_id(&old)?;
+            (new != old).then(|| ((String::new(), old), new))
+        })
+        .collect())
+}
+
+/// Renames for a ladder/category problems table, recomputed from each row's URL and judge
+async fn ladder_renames(tx: &mut Tx<'_>, table: &str, scope: &str) -> PosResult<RenameMap> {
+    let rows: Vec<(String, String, String, String)> = sqlx::query_as(&format!(
+        "SELECT {scope}, problem_id, problem_url, online_judge FROM {table}"
+    ))
+    .fetch_all(&mut **tx)
+    .await
+    .map_err(|e| db_context(&format!("read {} ids", table), e))?;
+
+    Ok(rows.into_iter()
+        .filter_map(|(scope, old, url, judge)| {
+            let new = extract_problem_id(&url, &judge)?;
+            (new != old).then(|| ((scope, old), new))
+        })
+        .collect())
+}
+
+/// Rewrite `metadata.problemIds` on knowledge items
+async fn rewrite_knowledge_items(tx: &mut Tx<'_>) -> PosResult<TableRenormalization> {
+    let rows: Vec<(String, Value)> = sqlx::query_as(
+        "SELECT id, metadata->'problemIds' FROM knowledge_items WHERE jsonb_typeof(metadata->'problemIds') = 'array'"
+    )
+    .fetch_all(&mut **tx)
+    .await
+    .map_err(|e| db_context("read knowledge problemIds", e))?;
+
+    let mut renames = RenameMap::new();
+    let (mut ids, mut lists) = (Vec::new(), Vec::new());
+    for (id, problem_ids) in rows {
+        let old: BTreeSet<String> = problem_ids.as_array().into_iter().flatten()
+            .filter_map(|v| v.as_str().map(String::from))
+            .collect();
+        let new: BTreeSet<String> = old.iter()
+            .map(|p| submission_style_id(p).unwrap_or_else(|| p.clone()))
+            .collect();
+        if new == old {
+            continue;
+        }
+        for p in &old {
+            if let Some(n) = submission_style_id(p).filter(|n| n != p) {
+                renames.insert((String::new(), p.clone()), n);
+            }
+        }
+        ids.push(id);
+        lists.push(json!(new));
+    }
+
+    let rows_updated = if ids.is_empty() {
+        0
+    } else {
+        sqlx::query(
+            r#"UPDATE knowledge_items k
+               SET metadata = jsonb_set(k.metadata, '{problemIds}', m.problem_ids), updated_at = NOW()
+               FROM UNNEST($1::text[], $2::jsonb[]) AS m(id, problem_ids)
+               WHERE k.id = m.id"#
+        )
+        .bind(&ids)
+        .bind(&lists)
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| db_context("rename knowledge problemIds", e))?
+        .rows_affected()
+    };
+
+    Ok(TableRenormalization {
+        table: "knowledge_items".to_string(),
+        rows_updated,
+        rows_merged: 0,
+        samples: samples(&renames),
+    })
+}
+
+// ─── Commands ───────────────────────────────────────────────────────
+
+/// Recompute stored problem IDs with the current parsers and rewrite the ones that changed.
+/// With `dry_run` the rewrite runs and is rolled back, reporting exactly what would change.
+#[tauri::command]
+pub async fn renormalize_problem_ids(
+    db: State<'_, PosDb>,
+    dry_run: bool,
+) -> PosResult<RenormalizeReport> {
+    let mut tx = db.0.begin().await.map_err(|e| db_context("begin renormalize", e))?;
+    let mut tables = Vec::new();
+
+    for target in SUBMISSION_STYLE_TARGETS {
+        let renames = submission_style_renames(&mut tx, target.table).await?;
+        tables.push(rewrite(&mut tx, target, &renames).await?);
+    }
+    tables.push(rewrite_knowledge_items(&mut tx).await?);
+
+    // Bare IDs shared across ladders; an old ID that maps two ways is left alone
+    let mut shared: BTreeMap<String, Option<String>> = BTreeMap::new();
+    for (problems, progress, scope) in LADDER_TABLES {
+        let renames = ladder_renames(&mut tx, problems, scope).await?;
+        for ((_, old), new) in renames.iter().filter(|((_, old), _)| !is_placeholder(old)) {
+            shared.entry(old.clone())
+                .and_modify(|seen| if seen.as_ref() != Some(new) { *seen = None })
+                .or_insert_with(|| Some(new.clone()));
+        }
+        for table in [problems, progress] {
+            let target = Target { table, scope: Some(scope), unique_with: Some(&[]), touch: false };
+            tables.push(rewrite(&mut tx, &target, &renames).await?);
+        }
+    }
+    let shared: RenameMap = shared.into_iter()
+        .filter_map(|(old, new)| Some(((String::new(), old), new?)))
+        .collect();
+    for target in LADDER_KEYED_TARGETS {
+        tables.push(rewrite(&mut tx, target, &shared).await?);
+    }
+
+    tables.retain(|t| t.rows_updated > 0 || t.rows_merged > 0);
+    let total_updated = tables.iter().map(|t| t.rows_updated).sum();
+    let total_merged = tables.iter().map(|t| t.rows_merged).sum();
+
+    if dry_run {
+        tx.rollback().await.map_err(|e| db_context("rollback renormalize", e))?;
+    } else {
+        tx.commit().await.map_err(|e| db_context("commit renormalize", e))?;
+        if total_updated + total_merged > 0 {
+            crate::dashboard::mark_snapshot_stale(&db.0).await;
+        }
+    }
+
+    log::info!(
+        "[RENORMALIZE] {} {} rows, merged {} across {} tables",
+        if dry_run { "Would rename" } else { "Renamed" },
+        total_updated, total_merged, tables.len()
+    );
+    Ok(RenormalizeReport { dry_run, total_updated, total_merged, tables })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_submission_style_id() {
+        assert_eq!(submission_style_id("1520a").as_deref(), Some("cf-1520A"));
+        assert_eq!(submission_style_id("cf-1520a").as_deref(), Some("cf-1520A"));
+        assert_eq!(submission_style_id("leetcode-Two-Sum").as_deref(), Some("leetcode-two-sum"));
+        assert_eq!(submission_style_id("atcoder-ABC300_A").as_deref(), Some("atcoder-abc300_a"));
+        assert_eq!(
+            submission_style_id("https://codeforces.com/contest/1520/problem/a").as_deref(),
+            Some("cf-1520A")
+        );
+        assert_eq!(submission_style_id("custom-problem"), None);
+    }
+}
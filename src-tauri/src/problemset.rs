@@ -0,0 +1,227 @@
+// Problemset Cache & Random Picker
+// Local copy of the Codeforces problemset (plus non-CF problems known from ladders and
+// categories) so problem queries don't hit the API. `pick_random_problem` draws one
+// matching problem in a single query for "just give me something" moments.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use tauri::State;
+
+use crate::PosDb;
+use crate::pos::error::{PosError, PosResult, db_context};
+use crate::pos::retry::{with_backoff, BackoffPolicy};
+use crate::pos::scrapers::{build_http_client, CODEFORCES_HOST};
+
+// ─── Types ──────────────────────────────────────────────────────────
+
+#[derive(Debug, Serialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct CachedProblem {
+    pub problem_id: String, // ladder format, e.g. "1520A"
+    pub judge: String,
+    pub name: String,
+    pub url: String,
+    pub rating: Option<i32>,
+    pub tags: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProblemsetRefreshResult {
+    pub codeforces_problems: usize,
+    pub other_problems: u64,
+    pub refreshed_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RandomProblemConstraints {
+    pub rating_min: Option<i32>,
+    pub rating_max: Option<i32>,
+    /// Problem must carry every one of these tags
+    #[serde(default)]
+    pub include_tags: Vec<String>,
+    /// Problem must carry none of these tags
+    #[serde(default)]
+    pub exclude_tags: Vec<String>,
+    pub judge: Option<String>,
+    /// Defaults to true
+    pub unsolved_only: Option<bool>,
+    /// Skip problems with any submission in the last N days
+    pub not_attempted_days: Option<i32>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CfProblemsetResponse {
+    status: String,
+    result: Option<CfProblemsetResult>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CfProblemsetResult {
+    problems: Vec<CfProblem>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CfProblem {
+    contest_id: Option<i32>,
+    index: String,
+    name: String,
+    rating: Option<i32>,
+    #[serde(default)]
+    tags: Vec<String>,
+}
+
+// ─── Helpers ────────────────────────────────────────────────────────
+
+/// Download the full CF problemset and upsert it, then fold in non-CF ladder/category
+/// problems (no tags) so judge filters have something to pick from.
+pub(crate) async fn refresh_problemset_cache(pool: &PgPool) -> PosResult<ProblemsetRefreshResult> {
+    let client = build_http_client();
+    let resp: CfProblemsetResponse = with_backoff(CODEFORCES_HOST, BackoffPolicy::default(), || async {
+        let resp = client.get("https://codeforces.com/api/problemset.problems").send().await?;
+        if !resp.status().is_success() {
+            return Err(PosError::External(format!("HTTP error: {}", resp.status())));
+        }
+        Ok(resp.json::<CfProblemsetResponse>().await?)
+    }).await?;
+    if resp.status != "OK" {
+        return Err(PosError::External("Codeforces problemset API returned non-OK status".into()));
+    }
+    let problems: Vec<CfProblem> = resp.result.map(|r| r.problems).unwrap_or_default()
+        .into_iter()
+        .filter(|p| p.contest_id.is_some())
+        .collect();
+
+    let mut ids = Vec::with_capacity(problems.len());
+    let mut names = Vec::with_capacity(problems.len());
+    let mut urls = Vec::with_capacity(problems.len());
+    let mut ratings = Vec::with_capacity(problems.len());
+    let mut tags = Vec::with_capacity(problems.len());
+    for p in &problems {
+        let contest_id = p.contest_id.unwrap_or_default();
+        ids.push(format!("{}{}", contest_id, p.index));
+        names.push(p.name.clone());
+        urls.push(format!("https://codeforces.com/problemset/problem/{}/{}", contest_id, p.index));
+        ratings.push(p.rating);
+        // Postgres has no ragged arrays; pass tags comma-joined and split server-side
+        tags.push(p.tags.join(","));
+    }
+
+    let mut tx = pool.begin().await.map_err(|e| db_context("begin tx", e))?;
+
+    sqlx::query(
+        r#"INSERT INTO problemset_cache (problem_id, judge, name, url, rating, tags, fetched_at)
+           SELECT id, 'codeforces', name, url, rating,
+                  CASE WHEN tag_csv = '' THEN '{}'::text[] ELSE string_to_array(tag_csv, ',') END,
+                  NOW()
+           FROM UNNEST($1::text[], $2::text[], $3::text[], $4::int[], $5::text[])
+                AS t(id, name, url, rating, tag_csv)
+           ON CONFLICT (judge, problem_id) DO UPDATE
+           SET name = EXCLUDED.name, url = EXCLUDED.url, rating = EXCLUDED.rating,
+               tags = EXCLUDED.tags, fetched_at = EXCLUDED.fetched_at"#
+    )
+    .bind(&ids).bind(&names).bind(&urls).bind(&ratings).bind(&tags)
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| db_context("upsert problemset_cache", e))?;
+
+    let other = sqlx::query(
+        r#"INSERT INTO problemset_cache (problem_id, judge, name, url, rating, tags, fetched_at)
+           SELECT DISTINCT ON (LOWER(online_judge), problem_id)
+                  problem_id, LOWER(online_judge), problem_name, problem_url, difficulty, '{}'::text[], NOW()
+           FROM (
+               SELECT problem_id, online_judge, problem_name, problem_url, difficulty FROM cf_ladder_problems
+               UNION ALL
+               SELECT problem_id, online_judge, problem_name, problem_url, difficulty FROM cf_category_problems
+           ) p
+           WHERE LOWER(online_judge) <> 'codeforces'
+           ON CONFLICT (judge, problem_id) DO NOTHING"#
+    )
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| db_context("fold ladder problems into problemset_cache", e))?;
+
+    tx.commit().await.map_err(|e| db_context("commit tx", e))?;
+
+    log::info!("[PROBLEMSET] Cached {} Codeforces problems, {} from other judges", ids.len(), other.rows_affected());
+    Ok(ProblemsetRefreshResult {
+        codeforces_problems: ids.len(),
+        other_problems: other.rows_affected(),
+        refreshed_at: Utc::now(),
+    })
+}
+
+// ─── Commands ───────────────────────────────────────────────────────
+
+#[tauri::command]
+pub async fn refresh_problemset(db: State<'_, PosDb>) -> PosResult<ProblemsetRefreshResult> {
+    refresh_problemset_cache(&db.0).await
+}
+
+/// One random problem matching `constraints`, or None if nothing matches.
+/// Downloads the problemset first if the cache has never been filled.
+#[tauri::command]
+pub async fn pick_random_problem(
+    db: State<'_, PosDb>,
+    constraints: Option<RandomProblemConstraints>,
+) -> PosResult<Option<CachedProblem>> {
+    let pool = &db.0;
+    let c = constraints.unwrap_or_default();
+
+    if let (Some(lo), Some(hi)) = (c.rating_min, c.rating_max) {
+        if lo > hi {
+            return Err(PosError::InvalidInput("rating_min must be <= rating_max".into()));
+        }
+    }
+    if c.not_attempted_days.is_some_and(|d| d < 0) {
+        return Err(PosError::InvalidInput("not_attempted_days must be >= 0".into()));
+    }
+
+    let cached: bool = sqlx::query_scalar("SELECT EXISTS (SELECT 1 FROM problemset_cache)")
+        .fetch_one(pool)
+        .await
+        .map_err(|e| db_context("check problemset_cache", e))?;
+    if !cached {
+        refresh_problemset_cache(pool).await?;
+    }
+
+    let lower = |v: &[String]| v.iter().map(|t| t.trim().to_lowercase()).filter(|t| !t.is_empty()).collect::<Vec<_>>();
+    let include_tags = lower(&c.include_tags);
+    let exclude_tags = lower(&c.exclude_tags);
+
+    // pos_submissions ids are "cf-1520A" for Codeforces; other judges are matched as-is
+    sqlx::query_as::<_, CachedProblem>(
+        r#"SELECT p.problem_id, p.judge, p.name, p.url, p.rating, p.tags
+           FROM problemset_cache p
+           WHERE ($1::int IS NULL OR p.rating >= $1)
+             AND ($2::int IS NULL OR p.rating <= $2)
+             AND (cardinality($3::text[]) = 0 OR p.tags @> $3::text[])
+             AND NOT (p.tags && $4::text[])
+             AND ($5::text IS NULL OR p.judge = LOWER($5))
+             AND (NOT $6 OR NOT EXISTS (
+                 SELECT 1 FROM solved_problems s
+                 WHERE s.problem_id IN (p.problem_id, 'cf-' || p.problem_id)
+             ))
+             AND ($7::int IS NULL OR NOT EXISTS (
+                 SELECT 1 FROM pos_submissions s
+                 WHERE s.problem_id IN (p.problem_id, 'cf-' || p.problem_id)
+                   AND s.excluded_at IS NULL
+                   AND s.submitted_time > NOW() - make_interval(days => $7)
+             ))
+           ORDER BY RANDOM()
+           LIMIT 1"#
+    )
+    .bind(c.rating_min)
+    .bind(c.rating_max)
+    .bind(&include_tags)
+    .bind(&exclude_tags)
+    .bind(c.judge.as_deref())
+    .bind(c.unsolved_only.unwrap_or(true))
+    .bind(c.not_attempted_days)
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| db_context("pick_random_problem", e))
+}
@@ -0,0 +1,185 @@
+// Reading Queue
+// Link items get an estimated reading time when their page is fetched for metadata.
+// `get_reading_queue` packs Planned items into a session that fits the time I have,
+// highest priority and oldest first.
+
+use chrono::{DateTime, Utc};
+use scraper::{Html, Selector};
+use serde::Serialize;
+use serde_json::json;
+use sqlx::PgPool;
+use tauri::State;
+
+use crate::PosDb;
+use crate::pos::error::{PosError, PosResult, db_context};
+use crate::pos::scrapers::build_http_client;
+
+const WORDS_PER_MINUTE: usize = 230;
+const MAX_QUEUE_MINUTES: i32 = 8 * 60;
+
+// ─── Types ──────────────────────────────────────────────────────────
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LinkEnrichment {
+    pub id: String,
+    pub url: String,
+    pub title: Option<String>,
+    pub word_count: usize,
+    pub reading_minutes: i32,
+}
+
+#[derive(Debug, Serialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct ReadingQueueItem {
+    pub id: String,
+    pub title: String,
+    pub url: Option<String>,
+    pub reading_minutes: i32,
+    pub priority: String,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReadingQueue {
+    pub items: Vec<ReadingQueueItem>,
+    pub total_minutes: i32,
+    pub remaining_minutes: i32,
+    /// Planned link items that haven't been enriched yet, so have no estimate
+    pub unestimated_count: i64,
+}
+
+// ─── Helpers ────────────────────────────────────────────────────────
+
+fn first_url(content: &str) -> Option<String> {
+    content.split_whitespace()
+        .find(|w| w.starts_with("http://") || w.starts_with("https://"))
+        .map(|w| w.to_string())
+}
+
+/// Page title and the number of visible words (script/style text excluded)
+fn measure_page(html: &str) -> (Option<String>, usize) {
+    let doc = Html::parse_document(html);
+    let title = Selector::parse("title").ok()
+        .and_then(|sel| doc.select(&sel).next().map(|t| t.text().collect::<String>()))
+        .map(|t| t.split_whitespace().collect::<Vec<_>>().join(" "))
+        .filter(|t| !t.is_empty());
+
+    let words = doc.root_element()
+        .descendants()
+        .filter_map(|node| {
+            let text = node.value().as_text()?;
+            let parent = node.parent()?.value().as_element()?.name().to_string();
+            (!matches!(parent.as_str(), "script" | "style" | "noscript" | "title" | "template"))
+                .then(|| text.split_whitespace().count())
+        })
+        .sum();
+    (title, words)
+}
+
+/// Fetch the item's URL and merge title (if missing), word count and reading time
+/// into its metadata.
+pub(crate) async fn enrich_link_item(pool: &PgPool, id: &str) -> PosResult<LinkEnrichment> {
+    let (content, has_title): (String, bool) = sqlx::query_as(
+        "SELECT content, COALESCE(metadata ? 'title', FALSE) FROM knowledge_items WHERE id = $1"
+    )
+    .bind(id)
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| db_context("fetch knowledge item", e))?
+    .ok_or_else(|| PosError::NotFound(format!("Knowledge item {}", id)))?;
+
+    let url = first_url(&content)
+        .ok_or_else(|| PosError::InvalidInput("Knowledge item has no URL to enrich".into()))?;
+
+    let resp = build_http_client().get(&url).send().await?;
+    if !resp.status().is_success() {
+        return Err(PosError::External(format!("Fetching {} returned {}", url, resp.status())));
+    }
+    let is_html = resp.headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .map_or(true, |ct| ct.contains("html"));
+    if !is_html {
+        return Err(PosError::InvalidInput(format!("{} is not an HTML page", url)));
+    }
+    let body = resp.text().await?;
+    let (title, word_count) = measure_page(&body);
+    let reading_minutes = (word_count.div_ceil(WORDS_PER_MINUTE)).max(1) as i32;
+
+    let mut patch = json!({ "wordCount": word_count, "readingMinutes": reading_minutes });
+    if let (false, Some(t)) = (has_title, &title) {
+        patch["title"] = json!(t);
+    }
+    sqlx::query(
+        "UPDATE knowledge_items SET metadata = COALESCE(metadata, '{}'::jsonb) || $2, updated_at = NOW() WHERE id = $1"
+    )
+    .bind(id)
+    .bind(&patch)
+    .execute(pool)
+    .await
+    .map_err(|e| db_context("store link enrichment", e))?;
+
+    log::info!("[READING] {} ~{} min ({} words)", url, reading_minutes, word_count);
+    Ok(LinkEnrichment { id: id.to_string(), url, title, word_count, reading_minutes })
+}
+
+// ─── Commands ───────────────────────────────────────────────────────
+
+#[tauri::command]
+pub async fn enrich_knowledge_link(db: State<'_, PosDb>, id: String) -> PosResult<LinkEnrichment> {
+    enrich_link_item(&db.0, &id).await
+}
+
+/// Pack Planned items with a reading estimate into `max_minutes`: items are taken by
+/// priority (metadata.priority high > medium > low, default medium), then oldest first,
+/// skipping any that no longer fit.
+#[tauri::command]
+pub async fn get_reading_queue(db: State<'_, PosDb>, max_minutes: i32) -> PosResult<ReadingQueue> {
+    if !(1..=MAX_QUEUE_MINUTES).contains(&max_minutes) {
+        return Err(PosError::InvalidInput(format!(
+            "max_minutes must be between 1 and {}", MAX_QUEUE_MINUTES
+        )));
+    }
+    let pool = &db.0;
+
+    let (candidates, unestimated_count) = tokio::try_join!(
+        sqlx::query_as::<_, ReadingQueueItem>(
+            r#"SELECT id,
+                      COALESCE(NULLIF(metadata->>'title', ''), LEFT(content, 120)) AS title,
+                      substring(content FROM 'https?://\S+') AS url,
+                      (metadata->>'readingMinutes')::int AS reading_minutes,
+                      COALESCE(LOWER(metadata->>'priority'), 'medium') AS priority,
+                      created_at
+               FROM knowledge_items
+               WHERE status = 'Planned' AND metadata ? 'readingMinutes'
+               ORDER BY CASE LOWER(metadata->>'priority') WHEN 'high' THEN 0 WHEN 'low' THEN 2 ELSE 1 END,
+                        created_at ASC"#
+        ).fetch_all(pool),
+
+        sqlx::query_scalar::<_, i64>(
+            r#"SELECT COUNT(*) FROM knowledge_items
+               WHERE status = 'Planned' AND 'link' = ANY(tags)
+                 AND NOT COALESCE(metadata ? 'readingMinutes', FALSE)"#
+        ).fetch_one(pool),
+    ).map_err(|e| db_context("get_reading_queue", e))?;
+
+    let mut remaining = max_minutes;
+    let items: Vec<ReadingQueueItem> = candidates.into_iter()
+        .filter(|item| {
+            let fits = item.reading_minutes <= remaining;
+            if fits {
+                remaining -= item.reading_minutes;
+            }
+            fits
+        })
+        .collect();
+
+    Ok(ReadingQueue {
+        total_minutes: max_minutes - remaining,
+        remaining_minutes: remaining,
+        items,
+        unestimated_count,
+    })
+}
@@ -0,0 +1,217 @@
+// Recurrence Patterns
+// Parser and matcher for unified goal `recurring_pattern` strings, shared by the instance
+// generator in unified_goals and by recurring_reconcile. Supported forms (case-insensitive):
+//   "Daily" / "every day"            "Weekdays" / "weekdays only"
+//   "Mon,Wed,Fri" (any day names)   "every 3 days" / "every other day"
+//   "first Mon of month" … "last Fri of month"
+// New and edited patterns are stored in canonical form (see Display). Stored legacy values
+// that don't parse keep the old rule (match every day name the string contains), so
+// existing templates generate exactly what they did before.
+
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::{Datelike, Duration, NaiveDate, Weekday};
+
+const WEEK: [Weekday; 7] = [
+    Weekday::Mon, Weekday::Tue, Weekday::Wed, Weekday::Thu, Weekday::Fri, Weekday::Sat, Weekday::Sun,
+];
+const ORDINALS: [&str; 5] = ["first", "second", "third", "fourth", "fifth"];
+
+// ─── Types ──────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Recurrence {
+    Daily,
+    /// Monday through Friday
+    Weekdays,
+    /// Explicit day list, kept in week order
+    Days(Vec<Weekday>),
+    /// Every `n` days counted from the template's anchor date
+    EveryNDays(u32),
+    /// The `nth` (1-5) given weekday of each month; `None` is the last one
+    MonthlyWeekday { nth: Option<u8>, weekday: Weekday },
+}
+
+// ─── Parsing ────────────────────────────────────────────────────────
+
+fn parse_weekday(s: &str) -> Option<Weekday> {
+    // chrono accepts "mon" and "monday"
+    Weekday::from_str(s).ok().or(match s {
+        "tues" => Some(Weekday::Tue),
+        "thur" | "thurs" => Some(Weekday::Thu),
+        _ => None,
+    })
+}
+
+fn parse_nth(s: &str) -> Option<Option<u8>> {
+    if s == "last" {
+        return Some(None);
+    }
+    let n = ORDINALS.iter().position(|o| *o == s).map(|i| i as u8 + 1)
+        .or_else(|| ["1st", "2nd", "3rd", "4th", "5th"].iter().position(|o| *o == s).map(|i| i as u8 + 1))?;
+    Some(Some(n))
+}
+
+impl Recurrence {
+    /// Strict parse, used to validate patterns on create/update
+    pub fn parse(input: &str) -> Result<Self, String> {
+        let s = input.trim().to_lowercase();
+        let words: Vec<&str> = s.split(|c: char| c == ',' || c.is_whitespace()).filter(|w| !w.is_empty()).collect();
+        match words.as_slice() {
+            [] => Err("Recurrence pattern is empty".into()),
+            ["daily"] | ["every", "day"] => Ok(Recurrence::Daily),
+            ["weekdays"] | ["weekdays", "only"] | ["every", "weekday"] => Ok(Recurrence::Weekdays),
+            ["every", "other", "day"] => Ok(Recurrence::EveryNDays(2)),
+            ["every", n, "days" | "day"] => match n.parse::<u32>() {
+                Ok(1) => Ok(Recurrence::Daily),
+                Ok(n) if n > 1 => Ok(Recurrence::EveryNDays(n)),
+                _ => Err(format!("Invalid interval '{}' in '{}'", n, input.trim())),
+            },
+            [nth, day, "of", rest @ ..] if matches!(rest, ["month"] | ["the", "month"] | ["every", "month"]) => {
+                let nth = parse_nth(nth).ok_or_else(|| format!("Unknown ordinal '{}' in '{}'", nth, input.trim()))?;
+                let weekday = parse_weekday(day).ok_or_else(|| format!("Unknown day '{}' in '{}'", day, input.trim()))?;
+                Ok(Recurrence::MonthlyWeekday { nth, weekday })
+            }
+            days => {
+                let mut list = Vec::new();
+                for d in days {
+                    let day = parse_weekday(d)
+                        .ok_or_else(|| format!("Unrecognized recurrence pattern '{}'", input.trim()))?;
+                    if !list.contains(&day) {
+                        list.push(day);
+                    }
+                }
+                list.sort_by_key(|d| d.num_days_from_monday());
+                Ok(Recurrence::Days(list))
+            }
+        }
+    }
+
+    /// Lenient parse for stored values: anything unparseable falls back to the legacy
+    /// substring rule ("Mon,Wed" contains "Mon")
+    pub fn from_stored(pattern: &str) -> Self {
+        Self::parse(pattern).unwrap_or_else(|_| {
+            log::warn!("[Recurrence] Unrecognized pattern '{}', using legacy day-name matching", pattern);
+            Recurrence::Days(WEEK.iter().copied().filter(|d| pattern.contains(&d.to_string())).collect())
+        })
+    }
+
+    /// Whether an instance is due on `day`. `anchor` is the template's start date;
+    /// nothing recurs before it for interval patterns.
+    pub fn matches(&self, day: NaiveDate, anchor: NaiveDate) -> bool {
+        match self {
+            Recurrence::Daily => true,
+            Recurrence::Weekdays => day.weekday().num_days_from_monday() < 5,
+            Recurrence::Days(days) => days.contains(&day.weekday()),
+            Recurrence::EveryNDays(n) => {
+                let elapsed = (day - anchor).num_days();
+                elapsed >= 0 && elapsed % i64::from(*n) == 0
+            }
+            Recurrence::MonthlyWeekday { nth, weekday } => {
+                day.weekday() == *weekday
+                    && match nth {
+                        Some(n) => (day.day0() / 7 + 1) as u8 == *n,
+                        None => (day + Duration::days(7)).month() != day.month(),
+                    }
+            }
+        }
+    }
+}
+
+impl fmt::Display for Recurrence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Recurrence::Daily => write!(f, "Daily"),
+            Recurrence::Weekdays => write!(f, "Weekdays"),
+            // Same "Mon,Wed" shape the goal form writes and splits
+            Recurrence::Days(days) => {
+                let names: Vec<String> = days.iter().map(|d| d.to_string()).collect();
+                write!(f, "{}", names.join(","))
+            }
+            Recurrence::EveryNDays(n) => write!(f, "Every {} days", n),
+            Recurrence::MonthlyWeekday { nth, weekday } => {
+                let ordinal = match nth {
+                    Some(n) => ORDINALS[(*n as usize).clamp(1, 5) - 1],
+                    None => "last",
+                };
+                let mut ordinal = ordinal.to_string();
+                ordinal[..1].make_ascii_uppercase();
+                write!(f, "{} {} of month", ordinal, weekday)
+            }
+        }
+    }
+}
+
+/// Validate a user-supplied pattern and return its canonical form
+pub fn normalize(pattern: &str) -> Result<String, String> {
+    Recurrence::parse(pattern).map(|r| r.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn d(y: i32, m: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, day).unwrap()
+    }
+
+    #[test]
+    fn test_parse_forms() {
+        assert_eq!(Recurrence::parse("Daily"), Ok(Recurrence::Daily));
+        assert_eq!(Recurrence::parse("weekdays only"), Ok(Recurrence::Weekdays));
+        assert_eq!(Recurrence::parse("Every 3 days"), Ok(Recurrence::EveryNDays(3)));
+        assert_eq!(Recurrence::parse("every other day"), Ok(Recurrence::EveryNDays(2)));
+        assert_eq!(Recurrence::parse("every 1 day"), Ok(Recurrence::Daily));
+        assert_eq!(
+            Recurrence::parse("first Monday of the month"),
+            Ok(Recurrence::MonthlyWeekday { nth: Some(1), weekday: Weekday::Mon })
+        );
+        assert_eq!(
+            Recurrence::parse("last fri of month"),
+            Ok(Recurrence::MonthlyWeekday { nth: None, weekday: Weekday::Fri })
+        );
+        assert_eq!(Recurrence::parse("Wed, mon,Wed"), Ok(Recurrence::Days(vec![Weekday::Mon, Weekday::Wed])));
+        assert!(Recurrence::parse("every 0 days").is_err());
+        assert!(Recurrence::parse("Monthly").is_err());
+        assert!(Recurrence::parse("   ").is_err());
+    }
+
+    #[test]
+    fn test_canonical_round_trip() {
+        for p in ["Daily", "Weekdays", "Mon,Wed,Fri", "Every 3 days", "Second Tue of month", "Last Sun of month"] {
+            assert_eq!(normalize(p).as_deref(), Ok(p));
+        }
+        assert_eq!(normalize("Mon,Tue,Wed,Thu,Fri,Sat,Sun").as_deref(), Ok("Mon,Tue,Wed,Thu,Fri,Sat,Sun"));
+    }
+
+    #[test]
+    fn test_matches() {
+        let anchor = d(2026, 3, 2); // Monday
+        assert!(Recurrence::Weekdays.matches(d(2026, 3, 6), anchor));
+        assert!(!Recurrence::Weekdays.matches(d(2026, 3, 7), anchor));
+        let every3 = Recurrence::EveryNDays(3);
+        assert!(every3.matches(d(2026, 3, 2), anchor));
+        assert!(every3.matches(d(2026, 3, 5), anchor));
+        assert!(!every3.matches(d(2026, 3, 6), anchor));
+        assert!(!every3.matches(d(2026, 2, 27), anchor));
+        let first_mon = Recurrence::parse("first Monday of month").unwrap();
+        assert!(first_mon.matches(d(2026, 3, 2), anchor));
+        assert!(!first_mon.matches(d(2026, 3, 9), anchor));
+        let last_tue = Recurrence::parse("last Tue of month").unwrap();
+        assert!(last_tue.matches(d(2026, 3, 31), anchor));
+        assert!(!last_tue.matches(d(2026, 3, 24), anchor));
+    }
+
+    #[test]
+    fn test_legacy_patterns_match_as_before() {
+        let anchor = d(2026, 1, 1);
+        let (mon, tue) = (d(2026, 3, 2), d(2026, 3, 3));
+        assert!(Recurrence::from_stored("Daily").matches(tue, anchor));
+        assert!(Recurrence::from_stored("Mon,Wed").matches(mon, anchor));
+        assert!(!Recurrence::from_stored("Mon,Wed").matches(tue, anchor));
+        // Unparseable values keep the old substring rule
+        assert!(Recurrence::from_stored("Mon/Wed").matches(mon, anchor));
+        assert!(!Recurrence::from_stored("Mon/Wed").matches(tue, anchor));
+    }
+}
@@ -0,0 +1,171 @@
+// Recurring Instance Reconciliation
+// Instances are generated lazily from their template and copied at generation time, so
+// editing a template (text, metrics, pattern) leaves already-generated future instances
+// stale. Reconciling a template walks its not-yet-due instances: untouched ones are
+// updated to the template's current fields, or deleted when the template is gone or the
+// pattern no longer covers their day. Instances with logged progress (metric progress,
+// linked activities, verification) are never changed.
+// Stale instances are hard-deleted (tombstoned for delta sync) rather than soft-deleted,
+// so the (template, date) slot is free again if the pattern later covers that day.
+
+use chrono::NaiveDate;
+use serde::Serialize;
+use tauri::State;
+
+use crate::PosDb;
+use crate::pos::error::{PosError, PosResult, db_context};
+use crate::unified_goals::{template_schedule, UnifiedGoalMetric, UnifiedGoalRow, UNIFIED_GOAL_COLS};
+
+// ─── Types ──────────────────────────────────────────────────────────
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReconcileReport {
+    pub template_id: String,
+    /// Instances rewritten to the template's current fields
+    pub updated: Vec<String>,
+    /// Instances removed: template gone, or their day no longer matches the pattern
+    pub deleted: Vec<String>,
+    /// Instances left alone because progress was logged against them
+    pub preserved: Vec<String>,
+    /// Instances already matching the template
+    pub unchanged: usize,
+    pub dry_run: bool,
+}
+
+// ─── Helpers ────────────────────────────────────────────────────────
+
+fn has_progress(goal: &UnifiedGoalRow) -> bool {
+    goal.verified
+        || goal.metrics.as_ref().is_some_and(|m| m.0.iter().any(|x| x.current > 0.0))
+        || goal.linked_activity_ids.as_ref().is_some_and(|l| !l.0.is_empty())
+}
+
+/// Whether an instance still carries the fields it would be generated with today
+fn matches_template(instance: &UnifiedGoalRow, tmpl: &UnifiedGoalRow) -> bool {
+    let json = |v: &Option<sqlx::types::Json<Vec<UnifiedGoalMetric>>>| serde_json::to_value(v.as_ref().map(|j| &j.0)).ok();
+    instance.text == tmpl.text
+        && instance.description == tmpl.description
+        && instance.priority == tmpl.priority
+        && instance.urgent == tmpl.urgent
+        && instance.problem_id == tmpl.problem_id
+        && instance.labels.as_ref().map(|l| &l.0) == tmpl.labels.as_ref().map(|l| &l.0)
+        && json(&instance.metrics) == json(&tmpl.metrics)
+}
+
+// ─── Command ────────────────────────────────────────────────────────
+
+/// Bring a template's future, incomplete instances in line with the template.
+/// With `dry_run` the report is computed without writing anything.
+#[tauri::command]
+pub async fn reconcile_recurring_instances(
+    db: State<'_, PosDb>,
+    template_id: String,
+    dry_run: Option<bool>,
+) -> PosResult<ReconcileReport> {
+    let pool = &db.0;
+    let dry_run = dry_run.unwrap_or(false);
+
+    let template = sqlx::query_as::<_, UnifiedGoalRow>(&format!(
+        "SELECT {} FROM unified_goals WHERE id = $1 AND deleted_at IS NULL", UNIFIED_GOAL_COLS
+    ))
+    .bind(&template_id)
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| db_context("reconcile fetch template", e))?;
+    if let Some(t) = &template {
+        if t.recurring_template_id.is_some() {
+            return Err(PosError::InvalidInput(format!("Goal {} is a recurring instance, not a template", template_id)));
+        }
+    }
+    // A deleted or completed template (or one no longer recurring) generates nothing
+    let live = template.as_ref()
+        .filter(|t| !t.completed)
+        .and_then(|t| template_schedule(t).map(|s| (t, s)));
+
+    let mut tx = pool.begin().await.map_err(|e| db_context("TX begin", e))?;
+    let instances = sqlx::query_as::<_, UnifiedGoalRow>(&format!(
+        r#"SELECT {} FROM unified_goals
+           WHERE recurring_template_id = $1 AND date > $2
+             AND completed = FALSE AND deleted_at IS NULL
+           ORDER BY date
+           FOR UPDATE"#,
+        UNIFIED_GOAL_COLS
+    ))
+    .bind(&template_id)
+    .bind(crate::pos::timezone::today_string())
+    .fetch_all(&mut *tx)
+    .await
+    .map_err(|e| db_context("reconcile fetch instances", e))?;
+
+    let mut report = ReconcileReport {
+        template_id: template_id.clone(),
+        updated: Vec::new(),
+        deleted: Vec::new(),
+        preserved: Vec::new(),
+        unchanged: 0,
+        dry_run,
+    };
+    for inst in instances {
+        if has_progress(&inst) {
+            report.preserved.push(inst.id);
+            continue;
+        }
+        let day = inst.date.as_deref().and_then(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d").ok());
+        let tmpl = match (&live, day) {
+            // Same schedule the generator in get_unified_goals uses
+            (Some((tmpl, (schedule, anchor))), Some(day)) if schedule.matches(day, *anchor) => *tmpl,
+            _ => {
+                report.deleted.push(inst.id);
+                continue;
+            }
+        };
+        if matches_template(&inst, tmpl) {
+            report.unchanged += 1;
+            continue;
+        }
+        if !dry_run {
+            sqlx::query(
+                r#"UPDATE unified_goals
+                   SET text = $2, description = $3, priority = $4, urgent = $5,
+                       metrics = $6, problem_id = $7, labels = $8, updated_at = NOW()
+                   WHERE id = $1"#
+            )
+            .bind(&inst.id)
+            .bind(&tmpl.text)
+            .bind(&tmpl.description)
+            .bind(&tmpl.priority)
+            .bind(tmpl.urgent)
+            .bind(&tmpl.metrics)
+            .bind(&tmpl.problem_id)
+            .bind(&tmpl.labels)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| db_context("reconcile update instance", e))?;
+        }
+        report.updated.push(inst.id);
+    }
+
+    if !dry_run && !report.deleted.is_empty() {
+        let stmt = crate::delta_sync::with_tombstones(
+            "unified_goals",
+            "DELETE FROM unified_goals WHERE id = ANY($1)",
+        );
+        sqlx::query(&stmt)
+            .bind(&report.deleted)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| db_context("reconcile delete instances", e))?;
+    }
+    tx.commit().await.map_err(|e| db_context("TX commit", e))?;
+
+    if !dry_run && (!report.updated.is_empty() || !report.deleted.is_empty()) {
+        crate::dashboard::mark_snapshot_stale(pool).await;
+    }
+    log::info!(
+        "[Unified] Reconciled template {}: {} updated, {} deleted, {} preserved, {} unchanged{}",
+        template_id, report.updated.len(), report.deleted.len(), report.preserved.len(), report.unchanged,
+        if dry_run { " (dry run)" } else { "" }
+    );
+    Ok(report)
+}
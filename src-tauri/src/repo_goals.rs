@@ -0,0 +1,160 @@
+// Repo Goal Suggestions
+// Finds GitHub repos I used to commit to regularly but have gone quiet on, and proposes
+// a "ship one commit this week" unified goal for each. Accepting creates the goals.
+
+use chrono::{Datelike, DateTime, Duration, Utc};
+use serde::Serialize;
+use sqlx::PgPool;
+use tauri::State;
+
+use crate::{PosConfig, PosDb};
+use crate::pos::error::{PosError, PosResult, db_context};
+use crate::unified_goals::{insert_unified_goal, CreateGoalRequest, UnifiedGoalRow};
+
+/// Quiet for at least this long before a repo counts as stalled
+const STALL_DAYS: i64 = 14;
+/// Repos quiet for longer than this are considered finished, not stalled
+const ABANDONED_DAYS: i64 = 180;
+const MIN_TOTAL_COMMITS: i32 = 10;
+/// Average commits per week over the repo's active span to count as "previously active"
+const MIN_WEEKLY_CADENCE: f64 = 0.5;
+const MAX_SUGGESTIONS: usize = 5;
+
+// ─── Types ──────────────────────────────────────────────────────────
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RepoGoalSuggestion {
+    pub repo_id: String,
+    pub full_name: String,
+    pub text: String,
+    /// Sunday of the current week (YYYY-MM-DD)
+    pub due_date: String,
+    pub weekly_cadence: f64,
+    pub days_since_last_commit: i64,
+    pub total_commits: i32,
+}
+
+#[derive(sqlx::FromRow)]
+struct RepoRow {
+    id: String,
+    repo_name: String,
+    full_name: String,
+    total_commits: i32,
+    first_commit_date: Option<DateTime<Utc>>,
+    last_commit_date: DateTime<Utc>,
+}
+
+// ─── Helpers ────────────────────────────────────────────────────────
+
+fn repo_label(full_name: &str) -> String {
+    format!("repo:{}", full_name)
+}
+
+/// Stalled repos ranked by how far they fell behind their old cadence (expected commits
+/// missed since the last one). Repos with an open goal for them are skipped.
+async fn stalled_repos(pool: &PgPool, username: &str) -> PosResult<Vec<RepoGoalSuggestion>> {
+    let rows = sqlx::query_as::<_, RepoRow>(
+        r#"SELECT r.id, r.repo_name, r.full_name, r.total_commits, r.first_commit_date, r.last_commit_date
+           FROM github_repositories r
+           WHERE r.username = $1 AND r.is_fork = FALSE
+             AND r.total_commits >= $2
+             AND r.last_commit_date BETWEEN NOW() - make_interval(days => $4) AND NOW() - make_interval(days => $3)
+             AND NOT EXISTS (
+                 SELECT 1 FROM unified_goals g
+                 WHERE g.completed = FALSE AND g.archived_at IS NULL
+                   AND g.labels ? ('repo:' || r.full_name)
+             )"#
+    )
+    .bind(username)
+    .bind(MIN_TOTAL_COMMITS)
+    .bind(STALL_DAYS as i32)
+    .bind(ABANDONED_DAYS as i32)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| db_context("stalled_repos", e))?;
+
+    let now = Utc::now();
+    let today = crate::pos::timezone::today();
+    let due_date = (today + Duration::days(6 - today.weekday().num_days_from_monday() as i64))
+        .format("%Y-%m-%d").to_string();
+
+    let mut suggestions: Vec<(f64, RepoGoalSuggestion)> = rows.into_iter()
+        .filter_map(|r| {
+            let first = r.first_commit_date.unwrap_or(r.last_commit_date);
+            let active_weeks = ((r.last_commit_date - first).num_days() as f64 / 7.0).max(1.0);
+            let weekly_cadence = r.total_commits as f64 / active_weeks;
+            if weekly_cadence < MIN_WEEKLY_CADENCE {
+                return None;
+            }
+            let days_since = (now - r.last_commit_date).num_days();
+            let missed = weekly_cadence * days_since as f64 / 7.0;
+            Some((missed, RepoGoalSuggestion {
+                text: format!("Ship one commit to {} this week", r.repo_name),
+                repo_id: r.id,
+                full_name: r.full_name,
+                due_date: due_date.clone(),
+                weekly_cadence: (weekly_cadence * 10.0).round() / 10.0,
+                days_since_last_commit: days_since,
+                total_commits: r.total_commits,
+            }))
+        })
+        .collect();
+
+    suggestions.sort_by(|a, b| b.0.total_cmp(&a.0));
+    Ok(suggestions.into_iter().map(|(_, s)| s).collect())
+}
+
+// ─── Commands ───────────────────────────────────────────────────────
+
+/// Up to five stalled repos, worst first, each with a proposed goal due this Sunday.
+#[tauri::command]
+pub async fn suggest_repo_goals(
+    db: State<'_, PosDb>,
+    config: State<'_, PosConfig>,
+) -> PosResult<Vec<RepoGoalSuggestion>> {
+    let username = config.0.require_github_username()
+        .map_err(PosError::InvalidInput)?;
+    let mut suggestions = stalled_repos(&db.0, username).await?;
+    suggestions.truncate(MAX_SUGGESTIONS);
+    Ok(suggestions)
+}
+
+/// Create the suggested goals for `repo_ids`. Repos that are no longer stalled (or
+/// already have an open goal) are skipped, so accepting twice is harmless.
+#[tauri::command]
+pub async fn accept_repo_goal_suggestions(
+    db: State<'_, PosDb>,
+    config: State<'_, PosConfig>,
+    repo_ids: Vec<String>,
+) -> PosResult<Vec<UnifiedGoalRow>> {
+    let pool = &db.0;
+    let username = config.0.require_github_username()
+        .map_err(PosError::InvalidInput)?;
+
+    let mut created = Vec::new();
+    for s in stalled_repos(pool, username).await? {
+        if !repo_ids.contains(&s.repo_id) {
+            continue;
+        }
+        let goal = insert_unified_goal(pool, CreateGoalRequest {
+            text: s.text,
+            description: Some(format!(
+                "Usually ~{} commits/week; last commit {} days ago.",
+                s.weekly_cadence, s.days_since_last_commit
+            )),
+            date: Some(s.due_date),
+            recurring_pattern: None,
+            priority: Some("medium".to_string()),
+            urgent: None,
+            metrics: None,
+            problem_id: None,
+            labels: Some(vec!["github".to_string(), repo_label(&s.full_name)]),
+            parent_goal_id: None,
+        }).await?;
+        created.push(goal);
+    }
+
+    log::info!("[REPO_GOALS] Created {} repo goals ({} requested)", created.len(), repo_ids.len());
+    Ok(created)
+}
@@ -4,7 +4,7 @@
 use crate::PosDb;
 use crate::pos::utils::gen_id;
 use crate::pos::error::{PosError, PosResult};
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveTime, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::postgres::PgQueryResult;
 use tauri::State;
@@ -35,6 +35,42 @@ pub struct RetrospectiveStats {
     pub correlation: f64, // Deep work vs satisfaction
 }
 
+#[derive(Debug, Serialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct PeriodStats {
+    pub logged_minutes: i64,
+    pub productive_minutes: i64,
+    pub problems_solved: i64,
+    pub goals_total: i64,
+    pub goals_completed: i64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RetrospectivePrompt {
+    /// questions_data key the answer is stored under
+    pub key: String,
+    /// SPACE dimension: satisfaction, performance, activity, communication, efficiency
+    pub dimension: String,
+    pub question: String,
+    /// The period's numbers the question refers to
+    pub context: Option<String>,
+    /// Prefilled answer derived from the period's data
+    pub suggested: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RetrospectivePrompts {
+    pub period_type: String,
+    pub period_start: DateTime<Utc>,
+    pub period_end: DateTime<Utc>,
+    pub stats: PeriodStats,
+    pub prompts: Vec<RetrospectivePrompt>,
+    /// Retrospective already saved for this period, if any
+    pub existing_id: Option<String>,
+}
+
 // Table creation query
 pub async fn ensure_retrospectives_table(db: &PosDb) -> PosResult<PgQueryResult> {
     sqlx::query::<sqlx::Postgres>(
@@ -247,6 +283,141 @@ async fn calculate_correlation(
     }
 }
 
+#[tauri::command]
+pub async fn update_retrospective(
+    db: State<'_, PosDb>,
+    retrospective_id: String,
+    questions_data: serde_json::Value,
+) -> PosResult<Retrospective> {
+    sqlx::query_as::<sqlx::Postgres, Retrospective>(
+        r#"
+        UPDATE retrospectives SET questions_data = $2
+        WHERE id = $1
+        RETURNING id, period_type, period_start, period_end, questions_data, created_at
+        "#,
+    )
+    .bind(&retrospective_id)
+    .bind(&questions_data)
+    .fetch_optional(&db.0)
+    .await
+    .map_err(|e| PosError::Database(format!("Failed to update retrospective: {}", e)))?
+    .ok_or_else(|| PosError::NotFound(format!("Retrospective not found: {}", retrospective_id)))
+}
+
+/// First and last local day of the week (Monday start) or month containing `date`
+fn period_days(period_type: &str, date: NaiveDate) -> PosResult<(NaiveDate, NaiveDate)> {
+    match period_type {
+        "weekly" => {
+            let monday = date - Duration::days(date.weekday().num_days_from_monday() as i64);
+            Ok((monday, monday + Duration::days(6)))
+        }
+        "monthly" => {
+            let first = date.with_day(1).unwrap_or(date);
+            let next = first.checked_add_months(chrono::Months::new(1))
+                .ok_or_else(|| PosError::InvalidInput(format!("Date out of range: {}", date)))?;
+            Ok((first, next - Duration::days(1)))
+        }
+        _ => Err(PosError::InvalidInput(
+            "period_type must be 'weekly' or 'monthly'".to_string(),
+        )),
+    }
+}
+
+fn build_prompts(period_type: &str, stats: &PeriodStats) -> Vec<RetrospectivePrompt> {
+    let period = if period_type == "weekly" { "week" } else { "month" };
+    let deep_work_hours = (stats.productive_minutes as f64 / 60.0 * 10.0).round() / 10.0;
+    let prompt = |key: &str, dimension: &str, question: String, context: Option<String>, suggested: Option<serde_json::Value>| {
+        RetrospectivePrompt { key: key.into(), dimension: dimension.into(), question, context, suggested }
+    };
+    vec![
+        prompt("satisfaction", "satisfaction",
+            format!("How satisfied are you with this {}'s work? (1-10)", period), None, None),
+        prompt("energy", "satisfaction",
+            format!("How was your energy over the {}? (1-10)", period), None, None),
+        prompt("performance", "performance",
+            format!("Which outcomes mattered most this {}?", period),
+            Some(format!("{} of {} goals completed, {} problems solved",
+                stats.goals_completed, stats.goals_total, stats.problems_solved)),
+            None),
+        prompt("deep_work_hours", "activity",
+            format!("How many hours of deep work did you do this {}?", period),
+            Some(format!("{:.1} productive hours of {:.1} logged",
+                deep_work_hours, stats.logged_minutes as f64 / 60.0)),
+            Some(serde_json::json!(deep_work_hours))),
+        prompt("communication", "communication",
+            "Who did you learn from or help, and where did you get stuck waiting?".into(), None, None),
+        prompt("efficiency", "efficiency",
+            "What interrupted your flow most, and what will you change?".into(), None, None),
+    ]
+}
+
+/// Survey questions for the week/month containing `date` (YYYY-MM-DD, default today),
+/// with that period's minutes, solved problems and goal completion filled in
+#[tauri::command]
+pub async fn get_retrospective_prompts(
+    db: State<'_, PosDb>,
+    period_type: String,
+    date: Option<String>,
+) -> PosResult<RetrospectivePrompts> {
+    let date = match date {
+        Some(d) => NaiveDate::parse_from_str(&d, "%Y-%m-%d")
+            .map_err(|e| PosError::InvalidInput(format!("Invalid date '{}': {}", d, e)))?,
+        None => crate::pos::timezone::today(),
+    };
+    let (first, last) = period_days(&period_type, date)?;
+    let to_utc = |d: NaiveDate| {
+        crate::pos::timezone::from_local(d.and_time(NaiveTime::MIN))
+            .ok_or_else(|| PosError::InvalidInput(format!("No local midnight on {}", d)))
+    };
+    let period_start = to_utc(first)?;
+    let period_end = to_utc(last + Duration::days(1))? - Duration::seconds(1);
+    let (first_str, last_str) = (first.format("%Y-%m-%d").to_string(), last.format("%Y-%m-%d").to_string());
+
+    let (stats, existing_id) = tokio::try_join!(
+        sqlx::query_as::<_, PeriodStats>(
+            r#"
+            SELECT
+                (SELECT COALESCE(SUM(EXTRACT(EPOCH FROM (end_time - start_time)) / 60), 0)::bigint
+                 FROM pos_activities
+                 WHERE is_shadow = FALSE AND deleted_at IS NULL AND date BETWEEN $1 AND $2) AS logged_minutes,
+                (SELECT COALESCE(SUM(EXTRACT(EPOCH FROM (end_time - start_time)) / 60), 0)::bigint
+                 FROM pos_activities
+                 WHERE is_shadow = FALSE AND deleted_at IS NULL AND is_productive AND date BETWEEN $1 AND $2) AS productive_minutes,
+                (SELECT COUNT(DISTINCT problem_id) FROM pos_submissions
+                 WHERE verdict IN ('OK', 'Accepted', 'AC') AND excluded_at IS NULL
+                   AND submitted_time BETWEEN $3 AND $4) AS problems_solved,
+                (SELECT COUNT(*) FROM unified_goals
+                 WHERE deleted_at IS NULL AND archived_at IS NULL AND date BETWEEN $1 AND $2
+                   AND NOT (recurring_pattern IS NOT NULL AND recurring_template_id IS NULL)) AS goals_total,
+                (SELECT COUNT(*) FROM unified_goals
+                 WHERE deleted_at IS NULL AND archived_at IS NULL AND date BETWEEN $1 AND $2 AND completed
+                   AND NOT (recurring_pattern IS NOT NULL AND recurring_template_id IS NULL)) AS goals_completed
+            "#,
+        )
+        .bind(&first_str)
+        .bind(&last_str)
+        .bind(period_start)
+        .bind(period_end)
+        .fetch_one(&db.0),
+        sqlx::query_scalar::<_, String>(
+            "SELECT id FROM retrospectives WHERE period_type = $1 AND period_start = $2 ORDER BY created_at DESC LIMIT 1",
+        )
+        .bind(&period_type)
+        .bind(period_start)
+        .fetch_optional(&db.0),
+    )
+    .map_err(|e| PosError::Database(format!("Failed to gather retrospective stats: {}", e)))?;
+
+    Ok(RetrospectivePrompts {
+        prompts: build_prompts(&period_type, &stats),
+        period_type,
+        period_start,
+        period_end,
+        stats,
+        existing_id,
+    })
+}
+
 #[tauri::command]
 pub async fn delete_retrospective(
     db: State<'_, PosDb>,
@@ -260,3 +431,16 @@ pub async fn delete_retrospective(
 
     Ok(result.rows_affected() > 0)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_period_days() {
+        let d = |s: &str| NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap();
+        assert_eq!(period_days("weekly", d("2024-03-06")).unwrap(), (d("2024-03-04"), d("2024-03-10")));
+        assert_eq!(period_days("monthly", d("2024-02-15")).unwrap(), (d("2024-02-01"), d("2024-02-29")));
+        assert!(period_days("yearly", d("2024-02-15")).is_err());
+    }
+}
@@ -0,0 +1,211 @@
+// Saved Filters
+// Named GoalFilters / KnowledgeItemFilters stored server-side so every window can reuse
+// them. Goal filters may carry `relativeRange` ("today", "this_week", "next_7_days",
+// "this_month") instead of a fixed dateRange; it is resolved against the caller's local
+// date each time the filter is applied.
+
+use chrono::{DateTime, Datelike, Duration, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+use crate::PosDb;
+use crate::knowledge_base::{get_knowledge_items, KnowledgeItemFilters, KnowledgeItemRow};
+use crate::pos::error::{PosError, PosResult, db_context};
+use crate::unified_goals::{get_unified_goals, GoalFilters, UnifiedGoalRow};
+
+const SCOPES: [&str; 2] = ["goals", "knowledge"];
+const RELATIVE_RANGES: [&str; 4] = ["today", "this_week", "next_7_days", "this_month"];
+
+// ─── Types ──────────────────────────────────────────────────────────
+
+#[derive(Debug, Serialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct SavedFilterRow {
+    pub name: String,
+    /// "goals" or "knowledge"
+    pub scope: String,
+    pub filters: sqlx::types::Json<serde_json::Value>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "scope", content = "rows", rename_all = "camelCase")]
+pub enum SavedFilterResult {
+    Goals(Vec<UnifiedGoalRow>),
+    Knowledge(Vec<KnowledgeItemRow>),
+}
+
+// ─── Helpers ────────────────────────────────────────────────────────
+
+/// Inclusive (start, end) for a relative range around `today`
+fn resolve_relative_range(range: &str, today: NaiveDate) -> Option<(NaiveDate, NaiveDate)> {
+    match range {
+        "today" => Some((today, today)),
+        "this_week" => {
+            let monday = today - Duration::days(today.weekday().num_days_from_monday() as i64);
+            Some((monday, monday + Duration::days(6)))
+        }
+        "next_7_days" => Some((today, today + Duration::days(6))),
+        "this_month" => {
+            let first = today.with_day(1)?;
+            let next = first.checked_add_months(chrono::Months::new(1))?;
+            Some((first, next - Duration::days(1)))
+        }
+        _ => None,
+    }
+}
+
+/// Not a real instant: get_unified_goals formats date_range back to YYYY-MM-DD in UTC, so
+/// UTC midnight round-trips to the same local calendar date the range was computed in
+fn midnight_utc(date: NaiveDate) -> DateTime<Utc> {
+    DateTime::from_naive_utc_and_offset(date.and_time(chrono::NaiveTime::MIN), Utc)
+}
+
+/// Reject filters the target query couldn't deserialize, so bad JSON fails at save time
+fn validate(scope: &str, filters: &serde_json::Value) -> PosResult<()> {
+    let invalid = |e: serde_json::Error| PosError::InvalidInput(format!("Invalid {} filters: {}", scope, e));
+    match scope {
+        "goals" => {
+            serde_json::from_value::<GoalFilters>(filters.clone()).map_err(invalid)?;
+            if let Some(range) = filters.get("relativeRange") {
+                let known = range.as_str().is_some_and(|r| RELATIVE_RANGES.contains(&r));
+                if !known {
+                    return Err(PosError::InvalidInput(format!(
+                        "Unknown relativeRange {}. Use {}", range, RELATIVE_RANGES.join(", ")
+                    )));
+                }
+            }
+        }
+        "knowledge" => {
+            serde_json::from_value::<KnowledgeItemFilters>(filters.clone()).map_err(invalid)?;
+        }
+        _ => return Err(PosError::InvalidInput(format!(
+            "Unknown filter scope '{}'. Use {}", scope, SCOPES.join(" or ")
+        ))),
+    }
+    Ok(())
+}
+
+// ─── Commands ───────────────────────────────────────────────────────
+
+/// Create or overwrite a named filter
+#[tauri::command]
+pub async fn save_filter(
+    db: State<'_, PosDb>,
+    name: String,
+    scope: String,
+    filters_json: serde_json::Value,
+) -> PosResult<SavedFilterRow> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(PosError::InvalidInput("Filter name is required".into()));
+    }
+    validate(&scope, &filters_json)?;
+
+    sqlx::query_as::<_, SavedFilterRow>(
+        r#"INSERT INTO saved_filters (name, scope, filters, created_at, updated_at)
+           VALUES ($1, $2, $3, NOW(), NOW())
+           ON CONFLICT (name) DO UPDATE
+           SET scope = EXCLUDED.scope, filters = EXCLUDED.filters, updated_at = NOW()
+           RETURNING name, scope, filters, created_at, updated_at"#
+    )
+    .bind(name)
+    .bind(&scope)
+    .bind(sqlx::types::Json(&filters_json))
+    .fetch_one(&db.0)
+    .await
+    .map_err(|e| db_context("save_filter", e))
+}
+
+/// Saved filters, optionally only those of one scope
+#[tauri::command]
+pub async fn get_saved_filters(
+    db: State<'_, PosDb>,
+    scope: Option<String>,
+) -> PosResult<Vec<SavedFilterRow>> {
+    sqlx::query_as::<_, SavedFilterRow>(
+        r#"SELECT name, scope, filters, created_at, updated_at FROM saved_filters
+           WHERE $1::text IS NULL OR scope = $1
+           ORDER BY scope, name"#
+    )
+    .bind(scope)
+    .fetch_all(&db.0)
+    .await
+    .map_err(|e| db_context("get_saved_filters", e))
+}
+
+#[tauri::command]
+pub async fn delete_saved_filter(db: State<'_, PosDb>, name: String) -> PosResult<()> {
+    let deleted = sqlx::query("DELETE FROM saved_filters WHERE name = $1")
+        .bind(&name)
+        .execute(&db.0)
+        .await
+        .map_err(|e| db_context("delete_saved_filter", e))?
+        .rows_affected();
+    if deleted == 0 {
+        return Err(PosError::NotFound(format!("Saved filter not found: {}", name)));
+    }
+    Ok(())
+}
+
+/// Run a saved filter through get_unified_goals / get_knowledge_items.
+/// `today_local` (YYYY-MM-DD) anchors relative ranges and debt marking; defaults to today in the configured local zone.
+#[tauri::command]
+pub async fn apply_saved_filter(
+    db: State<'_, PosDb>,
+    name: String,
+    today_local: Option<String>,
+) -> PosResult<SavedFilterResult> {
+    let saved = sqlx::query_as::<_, (String, sqlx::types::Json<serde_json::Value>)>(
+        "SELECT scope, filters FROM saved_filters WHERE name = $1"
+    )
+    .bind(&name)
+    .fetch_optional(&db.0)
+    .await
+    .map_err(|e| db_context("fetch saved filter", e))?
+    .ok_or_else(|| PosError::NotFound(format!("Saved filter not found: {}", name)))?;
+    let (scope, sqlx::types::Json(filters)) = saved;
+    let invalid = |e: serde_json::Error| PosError::InvalidInput(format!("Saved filter '{}' is invalid: {}", name, e));
+
+    match scope.as_str() {
+        "goals" => {
+            let mut goal_filters: GoalFilters = serde_json::from_value(filters.clone()).map_err(invalid)?;
+            let today = match today_local.as_deref() {
+                Some(d) => NaiveDate::parse_from_str(d, "%Y-%m-%d")
+                    .map_err(|e| PosError::InvalidInput(format!("Invalid date '{}': {}", d, e)))?,
+                None => crate::pos::timezone::today(),
+            };
+            if let Some(range) = filters.get("relativeRange").and_then(|r| r.as_str()) {
+                let (start, end) = resolve_relative_range(range, today)
+                    .ok_or_else(|| PosError::InvalidInput(format!("Unknown relativeRange '{}'", range)))?;
+                goal_filters.date_range = Some((midnight_utc(start), midnight_utc(end)));
+            }
+            if goal_filters.today_local.is_none() {
+                goal_filters.today_local = Some(today.format("%Y-%m-%d").to_string());
+            }
+            Ok(SavedFilterResult::Goals(get_unified_goals(db, Some(goal_filters)).await?))
+        }
+        "knowledge" => {
+            let kb_filters: KnowledgeItemFilters = serde_json::from_value(filters).map_err(invalid)?;
+            Ok(SavedFilterResult::Knowledge(get_knowledge_items(db, Some(kb_filters)).await?))
+        }
+        other => Err(PosError::InvalidInput(format!("Saved filter '{}' has unknown scope '{}'", name, other))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_relative_range() {
+        let d = |s: &str| NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap();
+        let thu = d("2024-02-29");
+        assert_eq!(resolve_relative_range("today", thu), Some((thu, thu)));
+        assert_eq!(resolve_relative_range("this_week", thu), Some((d("2024-02-26"), d("2024-03-03"))));
+        assert_eq!(resolve_relative_range("next_7_days", thu), Some((thu, d("2024-03-06"))));
+        assert_eq!(resolve_relative_range("this_month", thu), Some((d("2024-02-01"), thu)));
+        assert_eq!(resolve_relative_range("someday", thu), None);
+    }
+}
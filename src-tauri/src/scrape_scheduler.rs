@@ -0,0 +1,214 @@
+// Adaptive Scrape Scheduler
+// Background scrapes per configured platform. Each platform's interval follows its recent
+// submission cadence: a quarter of the time since the last submission, clamped to the
+// configured bounds, and the minimum while a contest looks to be in progress.
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::PgPool;
+use tauri::State;
+
+use crate::PosDb;
+use crate::coppermind_core::{self, Config, Platform};
+use crate::pos::error::{PosError, PosResult, db_context};
+
+const PLATFORMS: [Platform; 4] = [Platform::Codeforces, Platform::LeetCode, Platform::AtCoder, Platform::GitHub];
+/// Submissions older than this don't count towards contest detection
+const CONTEST_WINDOW_HOURS: i64 = 3;
+/// Contest mode ends once the latest submission is older than this
+const CONTEST_IDLE_MINUTES: i64 = 45;
+/// Distinct problems from one contest needed to assume participation
+const CONTEST_MIN_PROBLEMS: usize = 2;
+
+// ─── Types ──────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScrapePlan {
+    pub platform: String,
+    pub interval_minutes: i64,
+    pub reason: String,
+    pub last_activity_at: Option<DateTime<Utc>>,
+    pub contest_active: bool,
+}
+
+// ─── Helpers ────────────────────────────────────────────────────────
+
+fn platform_name(platform: Platform) -> &'static str {
+    match platform {
+        Platform::Codeforces => "codeforces",
+        Platform::LeetCode => "leetcode",
+        Platform::AtCoder => "atcoder",
+        Platform::GitHub => "github",
+    }
+}
+
+fn is_configured(config: &Config, platform: Platform) -> bool {
+    match platform {
+        Platform::Codeforces => config.codeforces_handle.is_some(),
+        Platform::LeetCode => config.leetcode_username.is_some(),
+        Platform::AtCoder => config.atcoder_username.is_some(),
+        Platform::GitHub => config.has_github_config(),
+    }
+}
+
+/// Contest a submission belongs to: `cf-1520A` → `cf-1520`, `atcoder-abc300_a` → `atcoder-abc300`.
+/// LeetCode slugs carry no contest, so LeetCode relies on cadence alone.
+pub fn contest_key(problem_id: &str) -> Option<String> {
+    if let Some(rest) = problem_id.strip_prefix("cf-") {
+        let digits: String = rest.chars().take_while(|c| c.is_ascii_digit()).collect();
+        return (!digits.is_empty() && digits.len() < rest.len()).then(|| format!("cf-{}", digits));
+    }
+    if let Some(rest) = problem_id.strip_prefix("atcoder-") {
+        return rest.rsplit_once('_').map(|(contest, _)| format!("atcoder-{}", contest));
+    }
+    None
+}
+
+/// True when the recent submissions cover several problems of one contest
+pub fn contest_in_progress(recent_problem_ids: &[String]) -> bool {
+    let mut per_contest: HashMap<String, Vec<&str>> = HashMap::new();
+    for pid in recent_problem_ids {
+        if let Some(key) = contest_key(pid) {
+            let problems = per_contest.entry(key).or_default();
+            if !problems.contains(&pid.as_str()) {
+                problems.push(pid);
+            }
+        }
+    }
+    per_contest.values().any(|p| p.len() >= CONTEST_MIN_PROBLEMS)
+}
+
+/// Interval in minutes and a human-readable reason
+pub fn adaptive_interval(minutes_since_last: Option<i64>, contest_active: bool, min: i64, max: i64) -> (i64, String) {
+    if contest_active {
+        return (min, "contest in progress".into());
+    }
+    match minutes_since_last {
+        None => (max, "no recent activity".into()),
+        Some(gap) => {
+            let interval = (gap.max(0) / 4).clamp(min, max);
+            let ago = if gap < 120 { format!("{}m", gap.max(0)) } else { format!("{}h", gap / 60) };
+            (interval, format!("last activity {} ago", ago))
+        }
+    }
+}
+
+async fn plan_for(pool: &PgPool, config: &Config, platform: Platform) -> PosResult<ScrapePlan> {
+    let name = platform_name(platform);
+    let (last_activity_at, recent): (Option<DateTime<Utc>>, Vec<String>) = if platform == Platform::GitHub {
+        let last: Option<DateTime<Utc>> = sqlx::query_scalar(
+            "SELECT MAX(last_commit_date) FROM github_repositories"
+        )
+        .fetch_one(pool)
+        .await
+        .map_err(|e| db_context("scrape plan github", e))?;
+        (last, Vec::new())
+    } else {
+        let (last, recent) = tokio::try_join!(
+            sqlx::query_scalar::<_, Option<DateTime<Utc>>>(
+                "SELECT MAX(submitted_time) FROM pos_submissions WHERE platform = $1"
+            )
+            .bind(name)
+            .fetch_one(pool),
+            sqlx::query_scalar::<_, String>(
+                r#"SELECT problem_id FROM pos_submissions
+                   WHERE platform = $1 AND submitted_time > NOW() - make_interval(hours => $2)"#
+            )
+            .bind(name)
+            .bind(CONTEST_WINDOW_HOURS as i32)
+            .fetch_all(pool),
+        )
+        .map_err(|e| db_context("scrape plan submissions", e))?;
+        (last, recent)
+    };
+
+    let minutes_since_last = last_activity_at.map(|t| (Utc::now() - t).num_minutes());
+    let contest_active = minutes_since_last.is_some_and(|m| m <= CONTEST_IDLE_MINUTES)
+        && contest_in_progress(&recent);
+    let (interval_minutes, reason) = adaptive_interval(
+        minutes_since_last,
+        contest_active,
+        config.scrape_min_interval_minutes,
+        config.scrape_max_interval_minutes,
+    );
+
+    Ok(ScrapePlan { platform: name.to_string(), interval_minutes, reason, last_activity_at, contest_active })
+}
+
+async fn run_platform_loop(pool: PgPool, config: Config, platform: Platform) {
+    let name = platform_name(platform);
+    loop {
+        let interval = match plan_for(&pool, &config, platform).await {
+            Ok(plan) => {
+                log::info!("[SCHEDULER] {} next scrape in {}m ({})", name, plan.interval_minutes, plan.reason);
+                plan.interval_minutes
+            }
+            Err(e) => {
+                log::warn!("[SCHEDULER] {} plan failed, using max interval: {}", name, e);
+                config.scrape_max_interval_minutes
+            }
+        };
+        tokio::time::sleep(Duration::from_secs(interval as u64 * 60)).await;
+
+        match coppermind_core::scrape(&pool, &config, platform).await {
+            Ok(res) => log::info!("[SCHEDULER] {} scrape done: {} new", name, res.new_submissions),
+            // A manual scrape ran moments ago; the next plan picks up from there
+            Err(PosError::RateLimited(msg)) => log::info!("[SCHEDULER] {} skipped: {}", name, msg),
+            Err(e) => log::warn!("[SCHEDULER] {} scrape failed: {}", name, e),
+        }
+    }
+}
+
+/// Spawn one scrape loop per configured platform (no-op when disabled)
+pub fn start(pool: PgPool, config: Config) {
+    if !config.scrape_scheduler_enabled {
+        log::info!("[SCHEDULER] Background scrapes disabled");
+        return;
+    }
+    for platform in PLATFORMS.into_iter().filter(|p| is_configured(&config, *p)) {
+        tauri::async_runtime::spawn(run_platform_loop(pool.clone(), config.clone(), platform));
+    }
+}
+
+// ─── Commands ───────────────────────────────────────────────────────
+
+/// Current interval per configured platform and why it was chosen
+#[tauri::command]
+pub async fn get_scrape_schedule(
+    db: State<'_, PosDb>,
+    config: State<'_, crate::PosConfig>,
+) -> PosResult<Vec<ScrapePlan>> {
+    let mut plans = Vec::new();
+    for platform in PLATFORMS.into_iter().filter(|p| is_configured(&config.0, *p)) {
+        plans.push(plan_for(&db.0, &config.0, platform).await?);
+    }
+    Ok(plans)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_contest_detection() {
+        assert_eq!(contest_key("cf-1520A").as_deref(), Some("cf-1520"));
+        assert_eq!(contest_key("atcoder-abc300_a").as_deref(), Some("atcoder-abc300"));
+        assert_eq!(contest_key("leetcode-two-sum"), None);
+        let ids = |v: &[&str]| v.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        assert!(contest_in_progress(&ids(&["cf-1520A", "cf-1520B"])));
+        assert!(!contest_in_progress(&ids(&["cf-1520A", "cf-1520A", "cf-1700B"])));
+    }
+
+    #[test]
+    fn test_adaptive_interval_bounds() {
+        assert_eq!(adaptive_interval(Some(600), true, 5, 360).0, 5);
+        assert_eq!(adaptive_interval(None, false, 5, 360).0, 360);
+        assert_eq!(adaptive_interval(Some(10), false, 5, 360).0, 5);
+        assert_eq!(adaptive_interval(Some(240), false, 5, 360).0, 60);
+        assert_eq!(adaptive_interval(Some(7 * 24 * 60), false, 5, 360).0, 360);
+    }
+}
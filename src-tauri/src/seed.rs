@@ -0,0 +1,220 @@
+// Demo / fixture data generator
+// Only does real work when built with `--features demo-data`; otherwise the
+// command is still registered (so the frontend can call it) but refuses.
+
+use serde::Serialize;
+use tauri::State;
+
+use crate::PosDb;
+use crate::pos::error::PosResult;
+#[cfg(not(feature = "demo-data"))]
+use crate::pos::error::PosError;
+
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SeedSummary {
+    pub activities: i32,
+    pub goals: i32,
+    pub submissions: i32,
+    pub ladders: i32,
+    pub ladder_problems: i32,
+}
+
+/// Populate an EMPTY database with ~90 days of realistic synthetic data.
+/// Refuses to run if any activities already exist, so a real database is never touched.
+#[tauri::command]
+pub async fn seed_demo_data(
+    db: State<'_, PosDb>,
+    days: Option<i64>,
+) -> PosResult<SeedSummary> {
+    #[cfg(feature = "demo-data")]
+    {
+        demo::seed_all(&db.0, days.unwrap_or(90).clamp(1, 365)).await
+    }
+    #[cfg(not(feature = "demo-data"))]
+    {
+        let _ = (db, days);
+        Err(PosError::InvalidInput(
+            "Demo data is unavailable: app was built without the `demo-data` feature".into(),
+        ))
+    }
+}
+
+#[cfg(feature = "demo-data")]
+mod demo {
+    use chrono::{Duration, TimeZone, Utc};
+    use sqlx::PgPool;
+
+    use super::SeedSummary;
+    use crate::pos::error::{PosError, PosResult, db_context};
+    use crate::pos::utils::gen_id;
+
+    /// Deterministic LCG so fixtures are reproducible between runs.
+    struct Rng(u64);
+
+    impl Rng {
+        fn next(&mut self) -> u64 {
+            self.0 = self.0.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            self.0 >> 33
+        }
+        fn range(&mut self, lo: i64, hi: i64) -> i64 {
+            lo + (self.next() % ((hi - lo + 1) as u64)) as i64
+        }
+        fn pick<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+            &items[self.next() as usize % items.len()]
+        }
+        fn chance(&mut self, percent: u64) -> bool {
+            self.next() % 100 < percent
+        }
+    }
+
+    const CATEGORIES: &[(&str, &str, bool)] = &[
+        ("codeforces", "Codeforces practice", true),
+        ("leetcode", "LeetCode daily", true),
+        ("development", "Side project work", true),
+        ("reading", "Reading", true),
+        ("exercise", "Workout", true),
+        ("entertainment", "YouTube", false),
+    ];
+
+    const PROBLEMS: &[(&str, &str, i32, &[&str])] = &[
+        ("1520A", "Do Not Be Distracted!", 800, &["brute force", "implementation"]),
+        ("1519B", "The Cake Is a Lie", 800, &["dp", "math"]),
+        ("1512C", "A-B Palindrome", 1200, &["constructive algorithms", "strings"]),
+        ("1490D", "Permutation Transformation", 1200, &["divide and conquer", "trees"]),
+        ("1475C", "Ball in Berland", 1400, &["combinatorics", "graphs"]),
+        ("1462D", "Add to Neighbour and Remove", 1400, &["greedy", "math"]),
+        ("1433E", "Two Round Dances", 1300, &["combinatorics", "math"]),
+        ("1399D", "Binary String To Subsequences", 1500, &["greedy", "data structures"]),
+        ("1354C1", "Simple Polygon Embedding", 1400, &["geometry", "math"]),
+        ("1311C", "Perform the Combo", 1300, &["brute force", "binary search"]),
+        ("1324D", "Pair of Topics", 1400, &["binary search", "sortings", "two pointers"]),
+        ("1300B", "Assigning to Classes", 1000, &["greedy", "sortings"]),
+    ];
+
+    const VERDICTS: &[&str] = &["OK", "OK", "OK", "WRONG_ANSWER", "TIME_LIMIT_EXCEEDED"];
+
+    const GOALS: &[&str] = &[
+        "Solve 3 Codeforces problems",
+        "Read 20 pages",
+        "Review segment tree notes",
+        "Ship settings page",
+        "30 minute workout",
+        "Upsolve last contest",
+    ];
+
+    pub(super) async fn seed_all(pool: &PgPool, days: i64) -> PosResult<SeedSummary> {
+        let existing: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM pos_activities")
+            .fetch_one(pool)
+            .await
+            .map_err(|e| db_context("seed precheck", e))?;
+        if existing > 0 {
+            return Err(PosError::InvalidInput(
+                "Refusing to seed: database already contains activities".into(),
+            ));
+        }
+
+        let mut rng = Rng(0xC0FFEE);
+        let mut summary = SeedSummary::default();
+        let today = Utc::now().date_naive();
+
+        let mut tx = pool.begin().await.map_err(|e| db_context("TX begin", e))?;
+
+        for offset in (0..days).rev() {
+            let day = today - Duration::days(offset);
+            let date = day.format("%Y-%m-%d").to_string();
+
+            // ── Activities: 3-6 blocks spread over the day ──
+            let midnight = Utc.from_utc_datetime(&day.and_hms_opt(0, 0, 0).unwrap());
+            let mut hour = rng.range(7, 10);
+            for _ in 0..rng.range(3, 6) {
+                let (category, title, productive) = *rng.pick(CATEGORIES);
+                let minutes = rng.range(25, 120);
+                let start = midnight + Duration::hours(hour);
+                let end = start + Duration::minutes(minutes);
+                // Blocks never run past midnight, so every row stays on its own date
+                if end > midnight + Duration::days(1) {
+                    break;
+                }
+                sqlx::query(
+                    r#"INSERT INTO pos_activities
+                       (id, date, start_time, end_time, category, title, description, is_productive, is_shadow, created_at)
+                       VALUES ($1, $2, $3, $4, $5, $6, '', $7, FALSE, $3)"#
+                )
+                .bind(gen_id()).bind(&date).bind(start).bind(end)
+                .bind(category).bind(title).bind(productive)
+                .execute(&mut *tx).await
+                .map_err(|e| db_context("seed activity", e))?;
+                summary.activities += 1;
+                hour += (minutes / 60) + rng.range(1, 2);
+            }
+
+            // ── Goals: 1-3 per day, older ones mostly completed ──
+            for _ in 0..rng.range(1, 3) {
+                let completed = offset > 0 && rng.chance(75);
+                let created = Utc.from_utc_datetime(&day.and_hms_opt(8, 0, 0).unwrap());
+                sqlx::query(
+                    r#"INSERT INTO unified_goals
+                       (id, text, completed, completed_at, verified, date, priority, urgent, created_at, updated_at, is_debt)
+                       VALUES ($1, $2, $3, $4, FALSE, $5, $6, FALSE, $7, $7, FALSE)"#
+                )
+                .bind(gen_id()).bind(*rng.pick(GOALS)).bind(completed)
+                .bind(completed.then(|| created + Duration::hours(rng.range(2, 12))))
+                .bind(&date).bind(*rng.pick(&["low", "medium", "high"])).bind(created)
+                .execute(&mut *tx).await
+                .map_err(|e| db_context("seed goal", e))?;
+                summary.goals += 1;
+            }
+
+            // ── Submissions: 0-4 per day, unique timestamps ──
+            for i in 0..rng.range(0, 4) {
+                let (pid, title, rating, tags) = *rng.pick(PROBLEMS);
+                let at = Utc.from_utc_datetime(&day.and_hms_opt(20, (i * 7) as u32, rng.range(0, 59) as u32).unwrap());
+                let tags: Vec<String> = tags.iter().map(|t| t.to_string()).collect();
+                let inserted = sqlx::query(
+                    r#"INSERT INTO pos_submissions
+                       (id, platform, problem_id, problem_title, submitted_time, verdict, language, rating, tags, created_at)
+                       VALUES ($1, 'codeforces', $2, $3, $4, $5, 'C++17', $6, $7, $4)
+                       ON CONFLICT (submitted_time) DO NOTHING"#
+                )
+                .bind(gen_id()).bind(format!("cf-{}", pid)).bind(title).bind(at)
+                .bind(*rng.pick(VERDICTS)).bind(rating).bind(&tags)
+                .execute(&mut *tx).await
+                .map_err(|e| db_context("seed submission", e))?;
+                // ON CONFLICT may have skipped the row
+                summary.submissions += inserted.rows_affected() as i32;
+            }
+        }
+
+        // ── One demo ladder containing every fixture problem ──
+        let ladder_id = gen_id();
+        sqlx::query(
+            r#"INSERT INTO cf_ladders (id, name, description, rating_min, rating_max, difficulty, source, problem_count, created_at)
+               VALUES ($1, 'Demo Ladder', 'Synthetic ladder for demos', 800, 1500, 2, 'Custom', $2, NOW())"#
+        )
+        .bind(&ladder_id).bind(PROBLEMS.len() as i32)
+        .execute(&mut *tx).await
+        .map_err(|e| db_context("seed ladder", e))?;
+        summary.ladders += 1;
+
+        for (pos, (pid, title, rating, _)) in PROBLEMS.iter().enumerate() {
+            let split = pid.find(|c: char| c.is_ascii_alphabetic()).unwrap_or(pid.len());
+            let url = format!("https://codeforces.com/problemset/problem/{}/{}", &pid[..split], &pid[split..]);
+            sqlx::query(
+                r#"INSERT INTO cf_ladder_problems
+                   (id, ladder_id, problem_id, problem_name, problem_url, position, difficulty, online_judge, created_at)
+                   VALUES ($1, $2, $3, $4, $5, $6, $7, 'Codeforces', NOW())"#
+            )
+            .bind(gen_id()).bind(&ladder_id).bind(*pid).bind(*title).bind(&url)
+            .bind(pos as i32 + 1).bind(*rating)
+            .execute(&mut *tx).await
+            .map_err(|e| db_context("seed ladder problem", e))?;
+            summary.ladder_problems += 1;
+        }
+
+        tx.commit().await.map_err(|e| db_context("TX commit", e))?;
+
+        log::info!("[SEED] Seeded {} days: {:?}", days, summary);
+        Ok(summary)
+    }
+}
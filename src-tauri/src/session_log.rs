@@ -0,0 +1,184 @@
+// Session Logging
+// One-call end-of-session flow: the activity (with its metric updates), the captures
+// taken during it and the goals it finished are written in a single transaction, so a
+// failure anywhere leaves nothing half-logged.
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tauri::State;
+
+use crate::PosDb;
+use crate::goal_dependencies::ensure_unblocked;
+use crate::knowledge_base::KnowledgeItemRow;
+use crate::knowledge_problems::with_problem_ids;
+use crate::pos::activities::{fetch_activity, insert_activity_tx, note_activity_streak, ActivityRow, CreateActivityRequest};
+use crate::pos::error::{PosError, PosResult, db_context};
+use crate::pos::idempotency::idempotent;
+use crate::pos::utils::gen_id;
+use crate::unified_goals::{UnifiedGoalRow, UNIFIED_GOAL_COLS};
+
+const KB_COLS: &str = "id, tags, source, content, metadata, status, next_review_date, \
+    linked_note_id, linked_journal_date, created_at, updated_at";
+
+// ─── Types ──────────────────────────────────────────────────────────
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionCapture {
+    pub content: String,
+    pub tags: Option<Vec<String>>,
+    pub metadata: Option<Value>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogSessionRequest {
+    /// Metric updates travel in `activity.updates`, as with create_activity
+    pub activity: CreateActivityRequest,
+    /// New knowledge items created from this session
+    pub captures: Option<Vec<SessionCapture>>,
+    /// Existing knowledge items to link to the new activity
+    pub knowledge_item_ids: Option<Vec<String>>,
+    /// Goals to mark completed (and verified) by this activity
+    pub complete_goal_ids: Option<Vec<String>>,
+    /// Complete goals even if they have open dependencies
+    pub override_dependencies: Option<bool>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogSessionResponse {
+    pub activity: ActivityRow,
+    pub knowledge_items: Vec<KnowledgeItemRow>,
+    pub completed_goals: Vec<UnifiedGoalRow>,
+}
+
+// ─── Commands ───────────────────────────────────────────────────────
+
+/// Log a finished session atomically. A replayed `idempotency_key` returns the first result.
+#[tauri::command]
+pub async fn log_session(
+    db: State<'_, PosDb>,
+    config: State<'_, crate::PosConfig>,
+    payload: LogSessionRequest,
+    idempotency_key: Option<String>,
+) -> PosResult<LogSessionResponse> {
+    let split = config.0.split_activities_at_midnight;
+    idempotent(&db.0, "log_session", idempotency_key, write_session(&db.0, split, payload)).await
+}
+
+async fn write_session(
+    pool: &sqlx::PgPool,
+    split_midnight: bool,
+    payload: LogSessionRequest,
+) -> PosResult<LogSessionResponse> {
+    let captures = payload.captures.unwrap_or_default();
+    if captures.iter().any(|c| c.content.trim().is_empty()) {
+        return Err(PosError::InvalidInput("Capture content cannot be empty".into()));
+    }
+    let link_ids = payload.knowledge_item_ids.unwrap_or_default();
+    let goal_ids = payload.complete_goal_ids.unwrap_or_default();
+    let override_dependencies = payload.override_dependencies.unwrap_or(false);
+    for goal_id in &goal_ids {
+        ensure_unblocked(pool, goal_id, override_dependencies).await?;
+    }
+    let now = Utc::now();
+
+    let mut tx = pool.begin().await.map_err(|e| db_context("TX begin", e))?;
+    let (activity_id, segments) = insert_activity_tx(&mut tx, split_midnight, &payload.activity).await?;
+
+    let mut knowledge_items = Vec::with_capacity(captures.len() + link_ids.len());
+    for capture in captures {
+        let mut metadata = with_problem_ids(&capture.content, capture.metadata)
+            .filter(|m| m.is_object())
+            .unwrap_or_else(|| serde_json::json!({}));
+        metadata["activityId"] = Value::String(activity_id.clone());
+
+        let item = sqlx::query_as::<_, KnowledgeItemRow>(&format!(
+            r#"INSERT INTO knowledge_items (id, tags, source, content, metadata, status, created_at, updated_at)
+               VALUES ($1, $2, 'ActivityLog', $3, $4, 'Inbox', $5, $5)
+               RETURNING {}"#, KB_COLS
+        ))
+        .bind(gen_id())
+        .bind(capture.tags.unwrap_or_default())
+        .bind(&capture.content)
+        .bind(sqlx::types::Json(metadata))
+        .bind(now)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| db_context("log_session capture", e))?;
+        knowledge_items.push(item);
+    }
+
+    if !link_ids.is_empty() {
+        let existing = sqlx::query_as::<_, KnowledgeItemRow>(&format!(
+            "SELECT {} FROM knowledge_items WHERE id = ANY($1)", KB_COLS
+        ))
+        .bind(&link_ids)
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(|e| db_context("log_session fetch knowledge", e))?;
+        if let Some(missing) = link_ids.iter().find(|id| !existing.iter().any(|k| &k.id == *id)) {
+            return Err(PosError::NotFound(format!("Knowledge item {}", missing)));
+        }
+        knowledge_items.extend(existing);
+    }
+
+    let kb_ids: Vec<String> = knowledge_items.iter().map(|k| k.id.clone()).collect();
+    if !kb_ids.is_empty() {
+        let link_row_ids: Vec<String> = kb_ids.iter().map(|_| gen_id()).collect();
+        sqlx::query(
+            r#"INSERT INTO activity_knowledge_links (id, activity_id, kb_item_id, link_type, created_at)
+               SELECT link_id, $1, kb_id, 'session', $4
+               FROM UNNEST($2::text[], $3::text[]) AS t(link_id, kb_id)
+               ON CONFLICT (activity_id, kb_item_id) DO NOTHING"#
+        )
+        .bind(&activity_id)
+        .bind(&link_row_ids)
+        .bind(&kb_ids)
+        .bind(now)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| db_context("log_session link knowledge", e))?;
+    }
+
+    let completed_goals = if goal_ids.is_empty() {
+        Vec::new()
+    } else {
+        let rows = sqlx::query_as::<_, UnifiedGoalRow>(&format!(
+            r#"UPDATE unified_goals
+               SET completed = TRUE,
+                   completed_at = COALESCE(completed_at, $2),
+                   verified = TRUE,
+                   linked_activity_ids = (
+                       SELECT jsonb_agg(DISTINCT a) FROM jsonb_array_elements_text(
+                           CASE WHEN jsonb_typeof(linked_activity_ids) = 'array'
+                                THEN linked_activity_ids ELSE '[]'::jsonb END || to_jsonb($3::text)) AS a),
+                   updated_at = $2
+               WHERE id = ANY($1) AND archived_at IS NULL
+               RETURNING {}"#, UNIFIED_GOAL_COLS
+        ))
+        .bind(&goal_ids)
+        .bind(now)
+        .bind(&activity_id)
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(|e| db_context("log_session complete goals", e))?;
+        if let Some(missing) = goal_ids.iter().find(|id| !rows.iter().any(|g| &g.id == *id)) {
+            return Err(PosError::NotFound(format!("Goal {}", missing)));
+        }
+        rows
+    };
+
+    tx.commit().await.map_err(|e| db_context("TX commit", e))?;
+
+    let activity = fetch_activity(pool, &activity_id).await?;
+    crate::dashboard::mark_snapshot_stale(pool).await;
+    crate::debt_payoff::settle_catch_ups(pool, &goal_ids).await;
+    note_activity_streak(pool, &activity, segments).await;
+    log::info!("[SESSION] Logged activity {} with {} knowledge items, {} goals completed",
+        activity_id, knowledge_items.len(), completed_goals.len());
+
+    Ok(LogSessionResponse { activity, knowledge_items, completed_goals })
+}
@@ -0,0 +1,196 @@
+// Settings Transfer
+// Moves configuration between machines as one JSON bundle: capture hotkey roles, the
+// platform tag → topic taxonomy, custom CF recommendation strategies, and the
+// environment config. Env values are exported for reference only (they live in .env,
+// not the database) and the GitHub token is always redacted.
+
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+use crate::{PosConfig, PosDb};
+use crate::capture_roles::{save_capture_roles, CaptureRole, CaptureRolesState};
+use crate::cf_ladder_system::upsert_custom_strategy;
+use crate::pos::error::{PosError, PosResult, db_context};
+
+const SETTINGS_FORMAT_VERSION: u32 = 1;
+const REDACTED: &str = "<redacted>";
+
+// ─── Types ──────────────────────────────────────────────────────────
+
+#[derive(Debug, Serialize, Deserialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct TagMapping {
+    pub platform: String,
+    pub platform_tag: String,
+    pub topic: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct StrategySetting {
+    pub name: String,
+    pub sql_text: String,
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SettingsBundle {
+    pub format_version: u32,
+    pub exported_at: DateTime<Utc>,
+    #[serde(default)]
+    pub capture_roles: Vec<CaptureRole>,
+    #[serde(default)]
+    pub tag_taxonomy: Vec<TagMapping>,
+    #[serde(default)]
+    pub custom_strategies: Vec<StrategySetting>,
+    /// .env keys → values, secrets redacted
+    #[serde(default)]
+    pub env: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SettingsImportReport {
+    pub capture_roles: usize,
+    pub tag_mappings: u64,
+    pub custom_strategies: usize,
+    /// Strategies rejected by validation: "name: reason"
+    pub errors: Vec<String>,
+    /// `.env` lines to add by hand on this machine (redacted values omitted)
+    pub env_lines: Vec<String>,
+}
+
+// ─── Helpers ────────────────────────────────────────────────────────
+
+fn env_snapshot(config: &crate::pos::config::PosConfig) -> BTreeMap<String, String> {
+    let mut env = BTreeMap::new();
+    let optional = [
+        ("LEETCODE_USERNAME", &config.leetcode_username),
+        ("CODEFORCES_HANDLE", &config.codeforces_handle),
+        ("ATCODER_USERNAME", &config.atcoder_username),
+        ("GITHUB_USERNAME", &config.github_username),
+        ("WHISPER_MODEL_PATH", &config.whisper_model_path),
+        ("JOURNAL_EXPORT_DIR", &config.journal_export_dir),
+    ];
+    for (key, value) in optional {
+        if let Some(v) = value {
+            env.insert(key.to_string(), v.clone());
+        }
+    }
+    if config.github_token.is_some() {
+        env.insert("GITHUB_TOKEN".into(), REDACTED.into());
+    }
+    env.insert("SHADOW_ACTIVITY_MINUTES".into(), config.shadow_activity_minutes.to_string());
+    env.insert("SPLIT_ACTIVITIES_AT_MIDNIGHT".into(), config.split_activities_at_midnight.to_string());
+    env.insert("SHADOW_COLLISION_POLICY".into(), format!("{:?}", config.shadow_collision_policy).to_lowercase());
+    env.insert("SHADOW_MERGE_WINDOW_MINUTES".into(), config.shadow_merge_window_minutes.to_string());
+    env.insert("WHISPER_CPP_BIN".into(), config.whisper_bin.clone());
+    env.insert("SCRAPE_SCHEDULER_ENABLED".into(), config.scrape_scheduler_enabled.to_string());
+    env.insert("SCRAPE_MIN_INTERVAL_MINUTES".into(), config.scrape_min_interval_minutes.to_string());
+    env.insert("SCRAPE_MAX_INTERVAL_MINUTES".into(), config.scrape_max_interval_minutes.to_string());
+    env.insert("CF_FRIEND_SYNC_DEPTH".into(), config.cf_friend_sync_depth.to_string());
+    env.insert("CF_FRIEND_NIGHTLY_FULL_SYNC".into(), config.cf_friend_nightly_full_sync.to_string());
+    if let Some(tz) = config.timezone {
+        env.insert("POS_TIMEZONE".into(), tz.name().to_string());
+    }
+    env
+}
+
+// ─── Commands ───────────────────────────────────────────────────────
+
+#[tauri::command]
+pub async fn export_settings(
+    db: State<'_, PosDb>,
+    roles: State<'_, CaptureRolesState>,
+    config: State<'_, PosConfig>,
+) -> PosResult<SettingsBundle> {
+    let pool = &db.0;
+    let (tag_taxonomy, custom_strategies) = tokio::try_join!(
+        sqlx::query_as::<_, TagMapping>(
+            "SELECT platform, platform_tag, topic FROM tag_taxonomy ORDER BY platform, platform_tag"
+        ).fetch_all(pool),
+        sqlx::query_as::<_, StrategySetting>(
+            "SELECT name, sql_text, description FROM cf_custom_strategies ORDER BY name"
+        ).fetch_all(pool),
+    ).map_err(|e| db_context("export_settings", e))?;
+
+    Ok(SettingsBundle {
+        format_version: SETTINGS_FORMAT_VERSION,
+        exported_at: Utc::now(),
+        capture_roles: roles.0.read().unwrap().clone(),
+        tag_taxonomy,
+        custom_strategies,
+        env: env_snapshot(&config.0),
+    })
+}
+
+/// Apply a bundle from `export_settings`. Capture roles replace the current set (only
+/// if the bundle has any); taxonomy and strategies are upserted by key. Env values are
+/// handed back as `.env` lines since they can't be changed at runtime.
+#[tauri::command]
+pub async fn import_settings(
+    db: State<'_, PosDb>,
+    roles: State<'_, CaptureRolesState>,
+    json: String,
+) -> PosResult<SettingsImportReport> {
+    let pool = &db.0;
+    let bundle: SettingsBundle = serde_json::from_str(&json)
+        .map_err(|e| PosError::InvalidInput(format!("Invalid settings bundle: {}", e)))?;
+    if bundle.format_version > SETTINGS_FORMAT_VERSION {
+        return Err(PosError::InvalidInput(format!(
+            "Bundle format {} is newer than this app supports ({})",
+            bundle.format_version, SETTINGS_FORMAT_VERSION
+        )));
+    }
+
+    let capture_roles = if bundle.capture_roles.is_empty() {
+        0
+    } else {
+        save_capture_roles(pool, &roles, bundle.capture_roles).await?.len()
+    };
+
+    let (platforms, tags, topics): (Vec<String>, Vec<String>, Vec<String>) = bundle.tag_taxonomy.into_iter()
+        .fold((Vec::new(), Vec::new(), Vec::new()), |(mut p, mut t, mut o), m| {
+            p.push(m.platform);
+            t.push(m.platform_tag);
+            o.push(m.topic);
+            (p, t, o)
+        });
+    let tag_mappings = sqlx::query(
+        r#"INSERT INTO tag_taxonomy (platform, platform_tag, topic)
+           SELECT * FROM UNNEST($1::text[], $2::text[], $3::text[])
+           ON CONFLICT (platform, platform_tag) DO UPDATE SET topic = EXCLUDED.topic"#
+    )
+    .bind(&platforms)
+    .bind(&tags)
+    .bind(&topics)
+    .execute(pool)
+    .await
+    .map_err(|e| db_context("import tag_taxonomy", e))?
+    .rows_affected();
+
+    let mut custom_strategies = 0;
+    let mut errors = Vec::new();
+    for s in bundle.custom_strategies {
+        let name = s.name.clone();
+        match upsert_custom_strategy(pool, s.name, s.sql_text, s.description).await {
+            Ok(_) => custom_strategies += 1,
+            Err(e) => errors.push(format!("{}: {}", name, e)),
+        }
+    }
+
+    let env_lines = bundle.env.into_iter()
+        .filter(|(_, v)| v != REDACTED)
+        .map(|(k, v)| format!("{}={}", k, v))
+        .collect();
+
+    log::info!(
+        "[SETTINGS] Imported {} capture roles, {} tag mappings, {} strategies ({} rejected)",
+        capture_roles, tag_mappings, custom_strategies, errors.len()
+    );
+    Ok(SettingsImportReport { capture_roles, tag_mappings, custom_strategies, errors, env_lines })
+}
@@ -0,0 +1,230 @@
+// Code Snippets
+// Captures that look like code are stored as knowledge item snippets: tagged `snippet`
+// plus the detected language, with highlight-ready segments in metadata. Detection is
+// keyword/shape scoring — cheap, offline, and good enough to pick a highlighter.
+
+use serde::Serialize;
+use serde_json::{json, Value};
+use tauri::State;
+
+use crate::PosDb;
+use crate::knowledge_base::KnowledgeItemRow;
+use crate::pos::error::{PosError, PosResult, db_context};
+
+pub const SNIPPET_TAG: &str = "snippet";
+/// Below this score the capture is treated as prose
+const MIN_SCORE: u32 = 3;
+const MAX_SNIPPETS: i64 = 200;
+
+/// (language, markers). Each marker found adds a point.
+const LANGUAGE_MARKERS: &[(&str, &[&str])] = &[
+    ("rust", &["fn ", "let mut ", "impl ", "pub fn", "::", "-> ", "&self", "match ", "Vec<", "Option<", "println!", "#[derive", "use crate"]),
+    ("cpp", &["#include", "std::", "int main", "cout", "cin", "vector<", "using namespace", "long long", "->", "template<", "nullptr"]),
+    ("python", &["def ", "import ", "self.", "elif ", "print(", "None", "__init__", "lambda ", " in range(", "from "]),
+    ("typescript", &["interface ", ": string", ": number", "export ", "=> ", "const ", "import {", "type ", "async ", "<T>"]),
+    ("javascript", &["function ", "const ", "let ", "=> ", "console.log", "require(", "document.", "===", "module.exports"]),
+    ("go", &["func ", "package ", ":= ", "fmt.", "go func", "chan ", "defer ", "err != nil", "import ("]),
+    ("java", &["public class", "public static void", "System.out", "private ", "new ", "@Override", "import java"]),
+    ("sql", &["SELECT ", "FROM ", "WHERE ", "INSERT INTO", "UPDATE ", "JOIN ", "GROUP BY", "CREATE TABLE", "ORDER BY"]),
+    ("bash", &["#!/bin/", "echo ", "sudo ", "fi\n", "done\n", "$(", "export ", "| grep", "&& ", "apt ", "cd "]),
+    ("html", &["<div", "</", "<html", "<span", "class=\"", "<script", "href="]),
+];
+
+// ─── Types ──────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LanguageGuess {
+    pub language: String,
+    /// Share of the total marker score that went to this language (0..1)
+    pub confidence: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SnippetSegment {
+    /// "code" | "text"
+    pub kind: String,
+    pub language: Option<String>,
+    pub text: String,
+    /// 1-based line in the original capture
+    pub start_line: usize,
+}
+
+// ─── Helpers ────────────────────────────────────────────────────────
+
+/// Structural hints that text is code at all, independent of language
+fn code_shape_score(content: &str) -> u32 {
+    let lines: Vec<&str> = content.lines().filter(|l| !l.trim().is_empty()).collect();
+    if lines.is_empty() {
+        return 0;
+    }
+    let punctuated = lines.iter()
+        .filter(|l| matches!(l.trim_end().chars().last(), Some(';' | '{' | '}' | ':' | ')' | ',')))
+        .count();
+    let indented = lines.iter().filter(|l| l.starts_with("    ") || l.starts_with('\t')).count();
+    let mut score = 0;
+    if punctuated * 2 >= lines.len() {
+        score += 2;
+    }
+    if indented * 3 >= lines.len() && lines.len() > 1 {
+        score += 1;
+    }
+    if content.contains("```") {
+        score += 2;
+    }
+    score
+}
+
+/// Best language guess for `content`, or None when it reads as prose
+pub fn detect_language(content: &str) -> Option<LanguageGuess> {
+    // A fence label is an explicit answer
+    if let Some(label) = content.lines()
+        .find_map(|l| l.trim().strip_prefix("```"))
+        .map(|l| l.trim().to_lowercase())
+        .filter(|l| !l.is_empty())
+    {
+        return Some(LanguageGuess { language: label, confidence: 1.0 });
+    }
+
+    let scores: Vec<(&str, u32)> = LANGUAGE_MARKERS.iter()
+        .map(|(lang, markers)| (*lang, markers.iter().filter(|m| content.contains(*m)).count() as u32))
+        .collect();
+    let total: u32 = scores.iter().map(|(_, s)| s).sum();
+    let (language, best) = scores.iter().copied().max_by_key(|(_, s)| *s)?;
+
+    if best + code_shape_score(content) < MIN_SCORE || best == 0 {
+        return None;
+    }
+    Some(LanguageGuess {
+        language: language.to_string(),
+        confidence: ((best as f64 / total as f64) * 100.0).round() / 100.0,
+    })
+}
+
+/// Split a capture into code/text segments on ``` fences. Unfenced captures are a
+/// single code segment.
+pub fn split_segments(content: &str, language: &str) -> Vec<SnippetSegment> {
+    if !content.contains("```") {
+        return vec![SnippetSegment {
+            kind: "code".into(),
+            language: Some(language.to_string()),
+            text: content.to_string(),
+            start_line: 1,
+        }];
+    }
+
+    let mut segments = Vec::new();
+    let mut current: Vec<&str> = Vec::new();
+    let mut in_code = false;
+    let mut fence_lang: Option<String> = None;
+    let mut start_line = 1;
+
+    let mut flush = |lines: &mut Vec<&str>, in_code: bool, lang: &Option<String>, start: usize| {
+        let text = lines.join("\n");
+        if !text.trim().is_empty() {
+            segments.push(SnippetSegment {
+                kind: if in_code { "code" } else { "text" }.into(),
+                language: in_code.then(|| lang.clone().unwrap_or_else(|| language.to_string())),
+                text,
+                start_line: start,
+            });
+        }
+        lines.clear();
+    };
+
+    for (i, line) in content.lines().enumerate() {
+        if let Some(label) = line.trim().strip_prefix("```") {
+            flush(&mut current, in_code, &fence_lang, start_line);
+            in_code = !in_code;
+            fence_lang = Some(label.trim().to_lowercase()).filter(|l| in_code && !l.is_empty());
+            start_line = i + 2;
+            continue;
+        }
+        current.push(line);
+    }
+    flush(&mut current, in_code, &fence_lang, start_line);
+    segments
+}
+
+/// Snippet tags and metadata for a capture, or None if it doesn't look like code
+pub(crate) fn snippet_fields(content: &str) -> Option<(Vec<String>, Value)> {
+    let guess = detect_language(content)?;
+    let segments = split_segments(content, &guess.language);
+    let metadata = json!({
+        "type": SNIPPET_TAG,
+        "language": guess.language,
+        "languageConfidence": guess.confidence,
+        "lineCount": content.lines().count(),
+        "segments": segments,
+    });
+    Some((vec![SNIPPET_TAG.to_string(), guess.language], metadata))
+}
+
+// ─── Commands ───────────────────────────────────────────────────────
+
+/// Snippets, newest first, optionally narrowed to a language and/or a text query
+/// (case-insensitive substring of the content or title).
+#[tauri::command]
+pub async fn get_snippets(
+    db: State<'_, PosDb>,
+    language: Option<String>,
+    query: Option<String>,
+    limit: Option<i64>,
+) -> PosResult<Vec<KnowledgeItemRow>> {
+    let limit = limit.unwrap_or(50);
+    if !(1..=MAX_SNIPPETS).contains(&limit) {
+        return Err(PosError::InvalidInput(format!("limit must be between 1 and {}", MAX_SNIPPETS)));
+    }
+    let language = language.map(|l| l.trim().to_lowercase()).filter(|l| !l.is_empty());
+    let pattern = query.map(|q| q.trim().to_string()).filter(|q| !q.is_empty())
+        .map(|q| format!("%{}%", q.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_")));
+
+    sqlx::query_as::<_, KnowledgeItemRow>(
+        r#"SELECT id, tags, source, content, metadata, status, next_review_date,
+                  linked_note_id, linked_journal_date, created_at, updated_at
+           FROM knowledge_items
+           WHERE $1 = ANY(tags)
+             AND ($2::text IS NULL OR metadata->>'language' = $2)
+             AND ($3::text IS NULL OR content ILIKE $3 OR metadata->>'title' ILIKE $3)
+           ORDER BY created_at DESC
+           LIMIT $4"#
+    )
+    .bind(SNIPPET_TAG)
+    .bind(&language)
+    .bind(&pattern)
+    .bind(limit)
+    .fetch_all(&db.0)
+    .await
+    .map_err(|e| db_context("get_snippets", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_language() {
+        let rust = "fn main() {\n    let mut v: Vec<i32> = Vec::new();\n    println!(\"{:?}\", v);\n}";
+        assert_eq!(detect_language(rust).unwrap().language, "rust");
+        let cpp = "#include <bits/stdc++.h>\nusing namespace std;\nint main() {\n    long long n; cin >> n;\n}";
+        assert_eq!(detect_language(cpp).unwrap().language, "cpp");
+        let py = "def solve(n):\n    for i in range(n):\n        print(i)";
+        assert_eq!(detect_language(py).unwrap().language, "python");
+        assert_eq!(detect_language("```go\nx := 1\n```").unwrap().language, "go");
+        assert!(detect_language("Remember to buy milk and call the bank tomorrow.").is_none());
+    }
+
+    #[test]
+    fn test_split_segments() {
+        let content = "Fast IO trick:\n```cpp\nios::sync_with_stdio(false);\n```\nworks on CF";
+        let segs = split_segments(content, "cpp");
+        assert_eq!(segs.len(), 3);
+        assert_eq!(segs[0].kind, "text");
+        assert_eq!(segs[1].kind, "code");
+        assert_eq!(segs[1].language.as_deref(), Some("cpp"));
+        assert_eq!(segs[1].start_line, 3);
+        assert_eq!(segs[2].text, "works on CF");
+        assert_eq!(split_segments("x = 1", "python").len(), 1);
+    }
+}
@@ -0,0 +1,283 @@
+// Streak Freezes
+// Every FREEZE_EARN_DAYS consecutive solve days earn a freeze token (up to MAX_TOKENS).
+// When a finished day has no accepted submission and the run is still alive, a token is
+// spent on it instead of the run breaking. Spent days are folded into the solved streak's
+// qualifying days, so the cached streak math stays unchanged. Days are settled once,
+// oldest first, from `processed_through` up to yesterday (today is still open); tracking
+// starts the first time this runs, nothing is awarded retroactively.
+
+use std::collections::HashSet;
+
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use serde::Serialize;
+use sqlx::PgPool;
+use tauri::{AppHandle, Emitter, State};
+
+use crate::PosDb;
+use crate::pos::error::{PosError, PosResult, db_context};
+use crate::pos::timezone;
+use crate::pos::utils::gen_id;
+use crate::streaks::{self, StreakKind};
+
+pub const FREEZE_EARN_DAYS: i32 = 7;
+pub const MAX_TOKENS: i32 = 2;
+const EARNED_EVENT: &str = "streak-freeze-earned";
+const SPENT_EVENT: &str = "streak-freeze-spent";
+const DEFAULT_HISTORY_LIMIT: i64 = 50;
+
+// ─── Types ──────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, PartialEq, sqlx::FromRow)]
+struct FreezeState {
+    tokens: i32,
+    /// Consecutive solve days since the last break (frozen days neither add nor reset)
+    run_days: i32,
+    processed_through: NaiveDate,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct FreezeChange {
+    event: &'static str,
+    day: NaiveDate,
+    tokens_after: i32,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FreezeInventory {
+    pub tokens: i32,
+    pub max_tokens: i32,
+    pub earn_every_days: i32,
+    /// Solve days until the next token (0 while the inventory is full)
+    pub days_to_next_token: i32,
+    pub processed_through: NaiveDate,
+}
+
+#[derive(Debug, Serialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct FreezeEventRow {
+    pub id: String,
+    /// earned | spent
+    pub event: String,
+    pub day: NaiveDate,
+    pub tokens_after: i32,
+    pub created_at: DateTime<Utc>,
+}
+
+// ─── Settlement ─────────────────────────────────────────────────────
+
+/// Walk the days after `state.processed_through` through `through`, earning on every
+/// FREEZE_EARN_DAYS-th solve day of a run and spending on missed days of a live run
+fn settle(state: &FreezeState, through: NaiveDate, solved: &HashSet<NaiveDate>) -> (FreezeState, Vec<FreezeChange>) {
+    let mut next = state.clone();
+    let mut changes = Vec::new();
+    let mut day = state.processed_through + Duration::days(1);
+    while day <= through {
+        if solved.contains(&day) {
+            next.run_days += 1;
+            if next.run_days % FREEZE_EARN_DAYS == 0 && next.tokens < MAX_TOKENS {
+                next.tokens += 1;
+                changes.push(FreezeChange { event: "earned", day, tokens_after: next.tokens });
+            }
+        } else if next.run_days > 0 && next.tokens > 0 {
+            next.tokens -= 1;
+            changes.push(FreezeChange { event: "spent", day, tokens_after: next.tokens });
+        } else {
+            next.run_days = 0;
+        }
+        next.processed_through = day;
+        day += Duration::days(1);
+    }
+    (next, changes)
+}
+
+/// Settle freeze tokens up to yesterday, emitting an event per token earned or spent.
+/// Never fails the caller.
+pub async fn settle_freezes(pool: &PgPool, app: Option<&AppHandle>) {
+    let res: PosResult<Vec<FreezeChange>> = async {
+        let today = timezone::today();
+        let yesterday = today - Duration::days(1);
+
+        // First run starts tracking from yesterday. Outside the settlement tx, so the row
+        // persists even when there is nothing to settle yet.
+        sqlx::query(
+            r#"INSERT INTO streak_freeze_state (kind, tokens, run_days, processed_through, updated_at)
+               VALUES ($1, 0, 0, $2, NOW())
+               ON CONFLICT (kind) DO NOTHING"#
+        )
+        .bind(StreakKind::Solved.as_str())
+        .bind(yesterday)
+        .execute(pool)
+        .await
+        .map_err(|e| db_context("init streak freezes", e))?;
+
+        let mut tx = pool.begin().await.map_err(|e| db_context("TX begin", e))?;
+
+        let state = sqlx::query_as::<_, FreezeState>(
+            "SELECT tokens, run_days, processed_through FROM streak_freeze_state WHERE kind = $1 FOR UPDATE"
+        )
+        .bind(StreakKind::Solved.as_str())
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| db_context("load streak freezes", e))?;
+        if state.processed_through >= yesterday {
+            return Ok(Vec::new());
+        }
+
+        // Solve days are local dates: bound by local midnights, bucket in Rust
+        let (from, until) = timezone::day_start(state.processed_through + Duration::days(1))
+            .zip(timezone::day_start(today))
+            .ok_or_else(|| PosError::InvalidInput("No local midnight for streak freeze range".into()))?;
+        let solved: HashSet<NaiveDate> = sqlx::query_scalar::<_, DateTime<Utc>>(
+            r#"SELECT submitted_time FROM pos_submissions
+               WHERE verdict IN ('OK', 'Accepted', 'AC') AND excluded_at IS NULL
+                 AND submitted_time >= $1 AND submitted_time < $2"#
+        )
+        .bind(from)
+        .bind(until)
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(|e| db_context("streak freeze days", e))?
+        .into_iter()
+        .map(timezone::local_date)
+        .collect();
+
+        let (next, changes) = settle(&state, yesterday, &solved);
+        for c in &changes {
+            sqlx::query(
+                r#"INSERT INTO streak_freeze_events (id, kind, event, day, tokens_after, created_at)
+                   VALUES ($1, $2, $3, $4, $5, NOW())
+                   ON CONFLICT (kind, event, day) DO NOTHING"#
+            )
+            .bind(gen_id())
+            .bind(StreakKind::Solved.as_str())
+            .bind(c.event)
+            .bind(c.day)
+            .bind(c.tokens_after)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| db_context("record streak freeze", e))?;
+        }
+        sqlx::query(
+            r#"UPDATE streak_freeze_state SET tokens = $2, run_days = $3, processed_through = $4, updated_at = NOW()
+               WHERE kind = $1"#
+        )
+        .bind(StreakKind::Solved.as_str())
+        .bind(next.tokens)
+        .bind(next.run_days)
+        .bind(next.processed_through)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| db_context("store streak freezes", e))?;
+        tx.commit().await.map_err(|e| db_context("TX commit", e))?;
+        Ok(changes)
+    }.await;
+
+    let changes = match res {
+        Ok(changes) => changes,
+        Err(e) => {
+            log::warn!("[STREAKS] Failed to settle freeze tokens: {}", e);
+            return;
+        }
+    };
+    if changes.iter().any(|c| c.event == "spent") {
+        streaks::invalidate(pool, StreakKind::Solved).await;
+    }
+    for c in &changes {
+        log::info!("[STREAKS] Freeze token {} on {} ({} left)", c.event, c.day, c.tokens_after);
+        if let Some(app) = app {
+            let event = if c.event == "earned" { EARNED_EVENT } else { SPENT_EVENT };
+            let _ = app.emit(event, serde_json::json!({
+                "day": c.day,
+                "tokens": c.tokens_after,
+            }));
+        }
+    }
+}
+
+// ─── Commands ───────────────────────────────────────────────────────
+
+#[tauri::command]
+pub async fn get_streak_freeze_inventory(
+    app: AppHandle,
+    db: State<'_, PosDb>,
+) -> PosResult<FreezeInventory> {
+    let pool = &db.0;
+    settle_freezes(pool, Some(&app)).await;
+    let state = sqlx::query_as::<_, FreezeState>(
+        "SELECT tokens, run_days, processed_through FROM streak_freeze_state WHERE kind = $1"
+    )
+    .bind(StreakKind::Solved.as_str())
+    .fetch_one(pool)
+    .await
+    .map_err(|e| db_context("get_streak_freeze_inventory", e))?;
+
+    Ok(FreezeInventory {
+        tokens: state.tokens,
+        max_tokens: MAX_TOKENS,
+        earn_every_days: FREEZE_EARN_DAYS,
+        days_to_next_token: if state.tokens >= MAX_TOKENS {
+            0
+        } else {
+            FREEZE_EARN_DAYS - state.run_days % FREEZE_EARN_DAYS
+        },
+        processed_through: state.processed_through,
+    })
+}
+
+/// Tokens earned and spent, newest first
+#[tauri::command]
+pub async fn get_streak_freeze_history(
+    db: State<'_, PosDb>,
+    limit: Option<i64>,
+) -> PosResult<Vec<FreezeEventRow>> {
+    sqlx::query_as::<_, FreezeEventRow>(
+        r#"SELECT id, event, day, tokens_after, created_at FROM streak_freeze_events
+           WHERE kind = $1 ORDER BY day DESC, created_at DESC LIMIT $2"#
+    )
+    .bind(StreakKind::Solved.as_str())
+    .bind(limit.unwrap_or(DEFAULT_HISTORY_LIMIT))
+    .fetch_all(&db.0)
+    .await
+    .map_err(|e| db_context("get_streak_freeze_history", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn d(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    fn days(from: &str, n: i64) -> Vec<NaiveDate> {
+        (0..n).map(|i| d(from) + Duration::days(i)).collect()
+    }
+
+    #[test]
+    fn test_settle_earns_then_spends() {
+        let start = FreezeState { tokens: 0, run_days: 0, processed_through: d("2024-05-31") };
+        // 7 solve days, one miss, two more solve days, then two misses
+        let mut solved: HashSet<NaiveDate> = days("2024-06-01", 7).into_iter().collect();
+        solved.extend(days("2024-06-09", 2));
+        let (state, changes) = settle(&start, d("2024-06-12"), &solved);
+
+        assert_eq!(changes, vec![
+            FreezeChange { event: "earned", day: d("2024-06-07"), tokens_after: 1 },
+            FreezeChange { event: "spent", day: d("2024-06-08"), tokens_after: 0 },
+        ]);
+        // Out of tokens on the 11th: the run breaks
+        assert_eq!(state, FreezeState { tokens: 0, run_days: 0, processed_through: d("2024-06-12") });
+    }
+
+    #[test]
+    fn test_settle_caps_inventory() {
+        let start = FreezeState { tokens: MAX_TOKENS, run_days: 6, processed_through: d("2024-06-01") };
+        let solved: HashSet<NaiveDate> = days("2024-06-02", 1).into_iter().collect();
+        let (state, changes) = settle(&start, d("2024-06-02"), &solved);
+        assert!(changes.is_empty());
+        assert_eq!((state.tokens, state.run_days), (MAX_TOKENS, 7));
+        // Already settled: nothing to do
+        assert_eq!(settle(&state, d("2024-06-02"), &solved), (state.clone(), Vec::new()));
+    }
+}
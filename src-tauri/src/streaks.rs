@@ -0,0 +1,332 @@
+// Streaks
+// Current and longest runs of consecutive days for: productive activity, solved problems
+// (any platform) and days whose goals were all completed. Results are cached in
+// `pos_streaks`. New activities and accepted submissions extend the cached run in place;
+// edits, deletions and exclusions mark it dirty for a full recompute on the next read.
+// The all-goals streak can't grow append-only (a new goal un-completes its day), so it is
+// recomputed whenever a fingerprint of unified_goals changes.
+
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use serde::Serialize;
+use sqlx::PgPool;
+use tauri::State;
+
+use crate::PosDb;
+use crate::pos::error::{PosResult, db_context};
+use crate::pos::timezone;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StreakKind {
+    Activity,
+    Solved,
+    AllGoals,
+}
+
+impl StreakKind {
+    const ALL: [StreakKind; 3] = [StreakKind::Activity, StreakKind::Solved, StreakKind::AllGoals];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            StreakKind::Activity => "activity",
+            StreakKind::Solved => "solved",
+            StreakKind::AllGoals => "all_goals",
+        }
+    }
+
+    /// Distinct qualifying days, ascending. Solved is bucketed by local date in Rust
+    /// (see solved_days) and binds nothing here; AllGoals binds today's local date.
+    fn days_sql(self) -> &'static str {
+        match self {
+            StreakKind::Activity => r#"
+                SELECT DISTINCT date::date FROM pos_activities
+                WHERE is_productive = TRUE AND is_shadow = FALSE AND deleted_at IS NULL
+                ORDER BY 1"#,
+            // Days covered by a spent freeze token count as solved (see streak_freezes)
+            StreakKind::Solved => r#"
+                SELECT day FROM streak_freeze_events WHERE kind = 'solved' AND event = 'spent'
+                ORDER BY 1"#,
+            StreakKind::AllGoals => r#"
+                SELECT date::date FROM unified_goals
+                WHERE deleted_at IS NULL AND archived_at IS NULL
+                  AND NOT (recurring_pattern IS NOT NULL AND recurring_template_id IS NULL)
+                  AND date::date <= $1::date
+                GROUP BY date
+                HAVING BOOL_AND(completed)
+                ORDER BY 1"#,
+        }
+    }
+}
+
+// ─── Types ──────────────────────────────────────────────────────────
+
+/// Cached run state for one kind
+#[derive(Debug, Clone, Default, PartialEq, sqlx::FromRow)]
+struct StreakState {
+    current_length: i32,
+    current_start: Option<NaiveDate>,
+    last_day: Option<NaiveDate>,
+    longest_length: i32,
+    longest_start: Option<NaiveDate>,
+    longest_end: Option<NaiveDate>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Streak {
+    /// activity | solved | all_goals
+    pub kind: String,
+    /// Run ending today, or yesterday while today is still open; 0 once a day is missed
+    pub current: i32,
+    pub current_start: Option<NaiveDate>,
+    pub longest: i32,
+    pub longest_start: Option<NaiveDate>,
+    pub longest_end: Option<NaiveDate>,
+    pub last_day: Option<NaiveDate>,
+}
+
+// ─── Computation ────────────────────────────────────────────────────
+
+/// Full state from ascending, de-duplicated days
+fn compute_state(days: &[NaiveDate]) -> StreakState {
+    let mut state = StreakState::default();
+    for &day in days {
+        state = advance(&state, day).unwrap_or(state);
+    }
+    state
+}
+
+/// State after one more qualifying day. None when `day` lies before the cached last day
+/// (it may bridge a gap), which needs a full recompute.
+fn advance(state: &StreakState, day: NaiveDate) -> Option<StreakState> {
+    let mut next = state.clone();
+    match state.last_day {
+        Some(last) if day == last => return Some(next),
+        Some(last) if day < last => return None,
+        Some(last) if day == last + Duration::days(1) => next.current_length += 1,
+        _ => {
+            next.current_length = 1;
+            next.current_start = Some(day);
+        }
+    }
+    next.last_day = Some(day);
+    if next.current_length > next.longest_length {
+        next.longest_length = next.current_length;
+        next.longest_start = next.current_start;
+        next.longest_end = Some(day);
+    }
+    Some(next)
+}
+
+fn to_streak(kind: StreakKind, state: StreakState, today: NaiveDate) -> Streak {
+    let alive = state.last_day.is_some_and(|d| d >= today - Duration::days(1));
+    Streak {
+        kind: kind.as_str().to_string(),
+        current: if alive { state.current_length } else { 0 },
+        current_start: if alive { state.current_start } else { None },
+        longest: state.longest_length,
+        longest_start: state.longest_start,
+        longest_end: state.longest_end,
+        last_day: state.last_day,
+    }
+}
+
+/// (current, longest) for ascending, de-duplicated days; reused by the cohort leaderboard
+pub(crate) fn streak_lengths(days: &[NaiveDate], today: NaiveDate) -> (i32, i32) {
+    let streak = to_streak(StreakKind::Activity, compute_state(days), today);
+    (streak.current, streak.longest)
+}
+
+// ─── Cache ──────────────────────────────────────────────────────────
+
+const STATE_COLS: &str = "current_length, current_start, last_day, longest_length, longest_start, longest_end";
+
+async fn store(pool: &PgPool, kind: StreakKind, state: &StreakState, fingerprint: Option<&str>) -> PosResult<()> {
+    sqlx::query(
+        r#"INSERT INTO pos_streaks
+           (kind, current_length, current_start, last_day, longest_length, longest_start, longest_end,
+            dirty, source_fingerprint, updated_at)
+           VALUES ($1, $2, $3, $4, $5, $6, $7, FALSE, $8, NOW())
+           ON CONFLICT (kind) DO UPDATE SET
+               current_length = EXCLUDED.current_length, current_start = EXCLUDED.current_start,
+               last_day = EXCLUDED.last_day, longest_length = EXCLUDED.longest_length,
+               longest_start = EXCLUDED.longest_start, longest_end = EXCLUDED.longest_end,
+               dirty = FALSE, source_fingerprint = EXCLUDED.source_fingerprint, updated_at = NOW()"#
+    )
+    .bind(kind.as_str())
+    .bind(state.current_length)
+    .bind(state.current_start)
+    .bind(state.last_day)
+    .bind(state.longest_length)
+    .bind(state.longest_start)
+    .bind(state.longest_end)
+    .bind(fingerprint)
+    .execute(pool)
+    .await
+    .map_err(|e| db_context("store streak", e))?;
+    Ok(())
+}
+
+async fn qualifying_days(pool: &PgPool, kind: StreakKind) -> Result<Vec<NaiveDate>, sqlx::Error> {
+    let query = sqlx::query_scalar::<_, NaiveDate>(kind.days_sql());
+    let mut days = match kind {
+        StreakKind::AllGoals => query.bind(timezone::today_string()).fetch_all(pool).await?,
+        _ => query.fetch_all(pool).await?,
+    };
+    if kind == StreakKind::Solved {
+        let solved: Vec<DateTime<Utc>> = sqlx::query_scalar(
+            "SELECT submitted_time FROM pos_submissions WHERE verdict IN ('OK', 'Accepted', 'AC') AND excluded_at IS NULL"
+        )
+        .fetch_all(pool)
+        .await?;
+        days.extend(solved.into_iter().map(timezone::local_date));
+        days.sort_unstable();
+        days.dedup();
+    }
+    Ok(days)
+}
+
+async fn recompute(pool: &PgPool, kind: StreakKind, fingerprint: Option<&str>) -> PosResult<StreakState> {
+    let days = qualifying_days(pool, kind)
+        .await
+        .map_err(|e| db_context(&format!("streak days {}", kind.as_str()), e))?;
+    let state = compute_state(&days);
+    store(pool, kind, &state, fingerprint).await?;
+    log::info!("[STREAKS] Recomputed {} over {} days", kind.as_str(), days.len());
+    Ok(state)
+}
+
+/// Changes whenever a goal is added, removed, edited or (un)completed
+async fn goals_fingerprint(pool: &PgPool) -> PosResult<String> {
+    let (count, completed, latest): (i64, i64, Option<chrono::DateTime<chrono::Utc>>) = sqlx::query_as(
+        r#"SELECT COUNT(*), COUNT(*) FILTER (WHERE completed), MAX(GREATEST(updated_at, deleted_at, archived_at))
+           FROM unified_goals"#
+    )
+    .fetch_one(pool)
+    .await
+    .map_err(|e| db_context("goals fingerprint", e))?;
+    Ok(format!("{}:{}:{}", count, completed, latest.map(|t| t.timestamp_micros()).unwrap_or(0)))
+}
+
+/// Extend the cached streak with a new qualifying day. Never fails the caller.
+pub async fn note_day(pool: &PgPool, kind: StreakKind, day: NaiveDate) {
+    let res: PosResult<()> = async {
+        let mut tx = pool.begin().await.map_err(|e| db_context("TX begin", e))?;
+        let cached = sqlx::query_as::<_, StreakState>(&format!(
+            "SELECT {} FROM pos_streaks WHERE kind = $1 AND dirty = FALSE FOR UPDATE", STATE_COLS
+        ))
+        .bind(kind.as_str())
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| db_context("load streak", e))?;
+
+        // Nothing cached yet: the next read computes everything anyway
+        let Some(state) = cached else { return Ok(()) };
+        match advance(&state, day) {
+            Some(next) if next == state => {}
+            Some(next) => {
+                sqlx::query(
+                    r#"UPDATE pos_streaks SET current_length = $2, current_start = $3, last_day = $4,
+                           longest_length = $5, longest_start = $6, longest_end = $7, updated_at = NOW()
+                       WHERE kind = $1"#
+                )
+                .bind(kind.as_str())
+                .bind(next.current_length)
+                .bind(next.current_start)
+                .bind(next.last_day)
+                .bind(next.longest_length)
+                .bind(next.longest_start)
+                .bind(next.longest_end)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| db_context("extend streak", e))?;
+            }
+            None => {
+                sqlx::query("UPDATE pos_streaks SET dirty = TRUE WHERE kind = $1")
+                    .bind(kind.as_str())
+                    .execute(&mut *tx)
+                    .await
+                    .map_err(|e| db_context("invalidate streak", e))?;
+            }
+        }
+        tx.commit().await.map_err(|e| db_context("TX commit", e))
+    }.await;
+    if let Err(e) = res {
+        log::warn!("[STREAKS] Failed to update {} streak: {}", kind.as_str(), e);
+    }
+}
+
+/// Force a full recompute of `kind` on the next read. Never fails the caller.
+pub async fn invalidate(pool: &PgPool, kind: StreakKind) {
+    if let Err(e) = sqlx::query("UPDATE pos_streaks SET dirty = TRUE WHERE kind = $1")
+        .bind(kind.as_str())
+        .execute(pool)
+        .await
+    {
+        log::warn!("[STREAKS] Failed to invalidate {} streak: {}", kind.as_str(), e);
+    }
+}
+
+// ─── Commands ───────────────────────────────────────────────────────
+
+#[tauri::command]
+pub async fn get_streaks(app: tauri::AppHandle, db: State<'_, PosDb>) -> PosResult<Vec<Streak>> {
+    let pool = &db.0;
+    crate::streak_freezes::settle_freezes(pool, Some(&app)).await;
+    let today = timezone::today();
+    let mut streaks = Vec::with_capacity(StreakKind::ALL.len());
+
+    for kind in StreakKind::ALL {
+        let fingerprint = match kind {
+            StreakKind::AllGoals => Some(goals_fingerprint(pool).await?),
+            _ => None,
+        };
+        let cached = sqlx::query_as::<_, StreakState>(&format!(
+            r#"SELECT {} FROM pos_streaks
+               WHERE kind = $1 AND dirty = FALSE AND source_fingerprint IS NOT DISTINCT FROM $2"#,
+            STATE_COLS
+        ))
+        .bind(kind.as_str())
+        .bind(fingerprint.as_deref())
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| db_context("load streak", e))?;
+
+        let state = match cached {
+            Some(state) => state,
+            None => recompute(pool, kind, fingerprint.as_deref()).await?,
+        };
+        streaks.push(to_streak(kind, state, today));
+    }
+    Ok(streaks)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn d(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn test_compute_state_runs() {
+        let days = [d("2024-01-01"), d("2024-01-02"), d("2024-01-03"), d("2024-01-07"), d("2024-01-08")];
+        let state = compute_state(&days);
+        assert_eq!(state.longest_length, 3);
+        assert_eq!((state.longest_start, state.longest_end), (Some(d("2024-01-01")), Some(d("2024-01-03"))));
+        assert_eq!((state.current_length, state.current_start), (2, Some(d("2024-01-07"))));
+
+        assert_eq!(to_streak(StreakKind::Activity, state.clone(), d("2024-01-09")).current, 2);
+        assert_eq!(to_streak(StreakKind::Activity, state, d("2024-01-10")).current, 0);
+    }
+
+    #[test]
+    fn test_advance_incremental_matches_full() {
+        let days = [d("2024-03-30"), d("2024-03-31"), d("2024-04-01")];
+        let partial = compute_state(&days[..2]);
+        assert_eq!(advance(&partial, days[2]), Some(compute_state(&days)));
+        assert_eq!(advance(&partial, days[1]), Some(partial.clone()));
+        // Earlier day may bridge a gap: needs a recompute
+        assert_eq!(advance(&partial, d("2024-03-01")), None);
+    }
+}
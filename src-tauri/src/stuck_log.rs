@@ -0,0 +1,248 @@
+// Stuck Log
+// Backend for the "I'm stuck" button: log_stuck records the struggle (time spent, what
+// blocked me), pulls up my notes on the problem and solved problems sharing its tags,
+// schedules a revisit goal and, when a technique is named, opens a knowledge Quest for it.
+
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+use serde_json::json;
+use sqlx::PgPool;
+use tauri::State;
+
+use crate::PosDb;
+use crate::knowledge_base::KnowledgeItemRow;
+use crate::knowledge_problems::normalize_problem_id;
+use crate::pos::error::{PosError, PosResult, db_context};
+use crate::pos::utils::gen_id;
+
+const DEFAULT_REVISIT_DAYS: i64 = 3;
+const MAX_REVISIT_DAYS: i64 = 365;
+const SIMILAR_LIMIT: i64 = 5;
+
+// ─── Types ──────────────────────────────────────────────────────────
+
+#[derive(Debug, Serialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct StuckLogRow {
+    pub id: String,
+    pub problem_id: String,
+    pub minutes_spent: i32,
+    pub blockers: Vec<String>,
+    pub revisit_goal_id: Option<String>,
+    pub quest_item_id: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct SimilarSolved {
+    pub problem_id: String,
+    pub title: String,
+    pub rating: Option<i32>,
+    pub shared_tags: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StuckReport {
+    pub log: StuckLogRow,
+    /// Times I've been stuck on this problem before this one
+    pub previous_attempts: i64,
+    pub problem_tags: Vec<String>,
+    /// Knowledge items mentioning the problem, newest first
+    pub related_notes: Vec<KnowledgeItemRow>,
+    /// Approach/mistakes markdown from the ladder problem notes (Codeforces only)
+    pub problem_note: Option<String>,
+    pub similar_solved: Vec<SimilarSolved>,
+    pub revisit_date: String,
+}
+
+// ─── Helpers ────────────────────────────────────────────────────────
+
+/// Tags from my own submissions, falling back to the Codeforces problemset cache
+async fn problem_tags(pool: &PgPool, problem_id: &str) -> PosResult<Vec<String>> {
+    let tags: Option<Vec<String>> = sqlx::query_scalar(
+        r#"SELECT tags FROM (
+               SELECT tags, 0 AS rank FROM pos_submissions
+               WHERE problem_id = $1 AND cardinality(tags) > 0
+               UNION ALL
+               SELECT tags, 1 FROM problemset_cache
+               WHERE judge = 'codeforces' AND 'cf-' || problem_id = $1 AND cardinality(tags) > 0
+           ) t ORDER BY rank LIMIT 1"#
+    )
+    .bind(problem_id)
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| db_context("stuck problem tags", e))?;
+    Ok(tags.unwrap_or_default())
+}
+
+/// Solved problems sharing the most tags with the stuck one
+async fn similar_solved(pool: &PgPool, problem_id: &str, tags: &[String]) -> PosResult<Vec<SimilarSolved>> {
+    if tags.is_empty() {
+        return Ok(Vec::new());
+    }
+    sqlx::query_as::<_, SimilarSolved>(
+        r#"SELECT problem_id, title, rating, shared_tags FROM (
+               SELECT DISTINCT ON (s.problem_id)
+                      s.problem_id, s.problem_title AS title, s.rating,
+                      ARRAY(SELECT UNNEST(s.tags) INTERSECT SELECT UNNEST($2::text[])) AS shared_tags
+               FROM pos_submissions s
+               WHERE s.verdict IN ('OK', 'Accepted', 'AC') AND s.excluded_at IS NULL
+                 AND s.problem_id <> $1 AND s.tags && $2::text[]
+               ORDER BY s.problem_id, s.submitted_time DESC
+           ) t
+           ORDER BY cardinality(shared_tags) DESC, rating NULLS LAST, problem_id
+           LIMIT $3"#
+    )
+    .bind(problem_id)
+    .bind(tags)
+    .bind(SIMILAR_LIMIT)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| db_context("stuck similar solved", e))
+}
+
+// ─── Commands ───────────────────────────────────────────────────────
+
+/// Record being stuck on `problem_id` and gather what might unstick me. Schedules a
+/// revisit goal `revisit_in_days` out (default 3) and, with `quest_technique`, adds a
+/// Planned knowledge item "Learn <technique>" linked to the problem.
+#[tauri::command]
+pub async fn log_stuck(
+    db: State<'_, PosDb>,
+    problem_id: String,
+    minutes_spent: i32,
+    blockers: Vec<String>,
+    quest_technique: Option<String>,
+    revisit_in_days: Option<i64>,
+) -> PosResult<StuckReport> {
+    let pool = &db.0;
+    let problem_id = normalize_problem_id(&problem_id);
+    if problem_id.is_empty() {
+        return Err(PosError::InvalidInput("problem_id is required".into()));
+    }
+    if minutes_spent < 0 {
+        return Err(PosError::InvalidInput("minutes_spent cannot be negative".into()));
+    }
+    let blockers: Vec<String> = blockers.into_iter()
+        .map(|b| b.trim().to_string())
+        .filter(|b| !b.is_empty())
+        .collect();
+    let revisit_days = revisit_in_days.unwrap_or(DEFAULT_REVISIT_DAYS).max(1);
+    if revisit_days > MAX_REVISIT_DAYS {
+        return Err(PosError::InvalidInput(format!("revisit_in_days must be at most {}", MAX_REVISIT_DAYS)));
+    }
+    let revisit_date = (crate::pos::timezone::today() + Duration::days(revisit_days))
+        .format("%Y-%m-%d").to_string();
+    let editorial_key = problem_id.strip_prefix("cf-").unwrap_or("").to_string();
+
+    let (problem_tags, related_notes, problem_note, previous_attempts) = tokio::try_join!(
+        problem_tags(pool, &problem_id),
+        async {
+            sqlx::query_as::<_, KnowledgeItemRow>(
+                r#"SELECT id, tags, source, content, metadata, status, next_review_date,
+                          linked_note_id, linked_journal_date, created_at, updated_at
+                   FROM knowledge_items
+                   WHERE metadata->'problemIds' ? $1
+                   ORDER BY created_at DESC"#
+            )
+            .bind(&problem_id)
+            .fetch_all(pool)
+            .await
+            .map_err(|e| db_context("stuck related notes", e))
+        },
+        async {
+            sqlx::query_scalar::<_, String>(
+                "SELECT markdown FROM cf_problem_notes WHERE problem_id = $1 AND markdown <> ''"
+            )
+            .bind(&editorial_key)
+            .fetch_optional(pool)
+            .await
+            .map_err(|e| db_context("stuck problem note", e))
+        },
+        async {
+            sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM stuck_logs WHERE problem_id = $1")
+                .bind(&problem_id)
+                .fetch_one(pool)
+                .await
+                .map_err(|e| db_context("stuck previous attempts", e))
+        },
+    )?;
+    let similar_solved = similar_solved(pool, &problem_id, &problem_tags).await?;
+
+    let now = Utc::now();
+    let stuck_id = gen_id();
+    let revisit_goal_id = gen_id();
+    let mut tx = pool.begin().await.map_err(|e| db_context("TX begin", e))?;
+
+    sqlx::query(
+        r#"INSERT INTO unified_goals (id, text, description, completed, verified, date, priority, urgent,
+                                      problem_id, labels, created_at, updated_at, is_debt)
+           VALUES ($1, $2, $3, FALSE, FALSE, $4, 'medium', FALSE, $5, $6, $7, $7, FALSE)"#
+    )
+    .bind(&revisit_goal_id)
+    .bind(format!("Revisit {}", problem_id))
+    .bind((!blockers.is_empty()).then(|| format!("Stuck on: {}", blockers.join("; "))))
+    .bind(&revisit_date)
+    .bind(&problem_id)
+    .bind(sqlx::types::Json(vec!["revisit".to_string(), "stuck".to_string()]))
+    .bind(now)
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| db_context("stuck revisit goal", e))?;
+
+    let quest_technique = quest_technique.map(|t| t.trim().to_string()).filter(|t| !t.is_empty());
+    let quest_item_id = match &quest_technique {
+        Some(technique) => {
+            let id = gen_id();
+            sqlx::query(
+                r#"INSERT INTO knowledge_items (id, source, content, metadata, status, tags, created_at, updated_at)
+                   VALUES ($1, 'Manual', $2, $3, 'Planned', $4, $5, $5)"#
+            )
+            .bind(&id)
+            .bind(format!("Learn {}", technique))
+            .bind(json!({ "technique": technique, "problemIds": [problem_id], "stuckLogId": stuck_id }))
+            .bind(vec!["quest".to_string()])
+            .bind(now)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| db_context("stuck quest", e))?;
+            Some(id)
+        }
+        None => None,
+    };
+
+    let log = sqlx::query_as::<_, StuckLogRow>(
+        r#"INSERT INTO stuck_logs (id, problem_id, minutes_spent, blockers, revisit_goal_id, quest_item_id, created_at)
+           VALUES ($1, $2, $3, $4, $5, $6, $7)
+           RETURNING id, problem_id, minutes_spent, blockers, revisit_goal_id, quest_item_id, created_at"#
+    )
+    .bind(&stuck_id)
+    .bind(&problem_id)
+    .bind(minutes_spent)
+    .bind(&blockers)
+    .bind(&revisit_goal_id)
+    .bind(&quest_item_id)
+    .bind(now)
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|e| db_context("insert stuck_log", e))?;
+
+    tx.commit().await.map_err(|e| db_context("TX commit", e))?;
+    crate::dashboard::mark_snapshot_stale(pool).await;
+
+    log::info!(
+        "[STUCK] {} after {} min ({} blockers), revisit on {}",
+        problem_id, minutes_spent, blockers.len(), revisit_date
+    );
+    Ok(StuckReport {
+        log,
+        previous_attempts,
+        problem_tags,
+        related_notes,
+        problem_note,
+        similar_solved,
+        revisit_date,
+    })
+}
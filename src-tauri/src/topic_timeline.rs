@@ -0,0 +1,109 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use tauri::State;
+
+use crate::PosDb;
+use crate::pos::error::{PosError, PosResult, db_context};
+
+// ─── Response types ─────────────────────────────────────────────────
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TimelineEntry {
+    pub entity_type: String,       // "activity" | "submission" | "knowledge" | "goal"
+    pub entity_id: String,
+    pub title: String,
+    pub detail: Option<String>,
+    pub occurred_at: DateTime<Utc>,
+}
+
+// ─── Internal row types ─────────────────────────────────────────────
+
+#[derive(sqlx::FromRow)]
+struct TimelineRow {
+    entity_id: String,
+    title: String,
+    detail: Option<String>,
+    occurred_at: DateTime<Utc>,
+}
+
+const PER_SOURCE_LIMIT: i64 = 200;
+
+// ─── Commands ───────────────────────────────────────────────────────
+
+/// Cross-search activities, submissions (by tag or title), knowledge items and goals
+/// for a topic and return a merged, chronological timeline (oldest first).
+#[tauri::command]
+pub async fn get_topic_timeline(
+    db: State<'_, PosDb>,
+    query: String,
+) -> PosResult<Vec<TimelineEntry>> {
+    let pool = &db.0;
+    let topic = query.trim();
+    if topic.is_empty() {
+        return Err(PosError::InvalidInput("Query must not be empty".into()));
+    }
+    let pattern = format!("%{}%", topic);
+
+    let (activities, submissions, kb_items, goals) = tokio::try_join!(
+        sqlx::query_as::<_, TimelineRow>(
+            r#"SELECT id AS entity_id, title, NULLIF(description, '') AS detail, start_time AS occurred_at
+               FROM pos_activities
+               WHERE (title ILIKE $1 OR description ILIKE $1) AND deleted_at IS NULL
+               ORDER BY start_time DESC LIMIT $2"#
+        ).bind(&pattern).bind(PER_SOURCE_LIMIT).fetch_all(pool),
+
+        sqlx::query_as::<_, TimelineRow>(
+            r#"SELECT id AS entity_id, problem_title AS title,
+                      platform || ' · ' || verdict AS detail, submitted_time AS occurred_at
+               FROM pos_submissions
+               WHERE excluded_at IS NULL
+                 AND (problem_title ILIKE $1 OR EXISTS (SELECT 1 FROM unnest(tags) t WHERE t ILIKE $1))
+               ORDER BY submitted_time DESC LIMIT $2"#
+        ).bind(&pattern).bind(PER_SOURCE_LIMIT).fetch_all(pool),
+
+        sqlx::query_as::<_, TimelineRow>(
+            r#"SELECT id AS entity_id,
+                      COALESCE(metadata->>'title', LEFT(content, 100)) AS title,
+                      status AS detail, created_at AS occurred_at
+               FROM knowledge_items
+               WHERE content ILIKE $1 OR metadata::text ILIKE $1
+                  OR EXISTS (SELECT 1 FROM unnest(tags) t WHERE t ILIKE $1)
+               ORDER BY created_at DESC LIMIT $2"#
+        ).bind(&pattern).bind(PER_SOURCE_LIMIT).fetch_all(pool),
+
+        sqlx::query_as::<_, TimelineRow>(
+            r#"SELECT id AS entity_id, text AS title,
+                      CASE WHEN completed THEN 'completed' ELSE 'open' END AS detail,
+                      COALESCE(completed_at, created_at) AS occurred_at
+               FROM unified_goals
+               WHERE (text ILIKE $1 OR description ILIKE $1 OR labels::text ILIKE $1) AND deleted_at IS NULL
+               ORDER BY created_at DESC LIMIT $2"#
+        ).bind(&pattern).bind(PER_SOURCE_LIMIT).fetch_all(pool),
+    ).map_err(|e| db_context("get_topic_timeline", e))?;
+
+    let sources = [
+        ("activity", activities),
+        ("submission", submissions),
+        ("knowledge", kb_items),
+        ("goal", goals),
+    ];
+
+    let mut timeline: Vec<TimelineEntry> = sources
+        .into_iter()
+        .flat_map(|(entity_type, rows)| {
+            rows.into_iter().map(move |r| TimelineEntry {
+                entity_type: entity_type.to_string(),
+                entity_id: r.entity_id,
+                title: r.title,
+                detail: r.detail,
+                occurred_at: r.occurred_at,
+            })
+        })
+        .collect();
+
+    timeline.sort_by(|a, b| a.occurred_at.cmp(&b.occurred_at));
+
+    log::info!("[TIMELINE] '{}' matched {} entries", topic, timeline.len());
+    Ok(timeline)
+}
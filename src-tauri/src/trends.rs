@@ -0,0 +1,134 @@
+// Trend Series
+// Day-by-day values plus trailing rolling averages for dashboard sparklines.
+// One endpoint for every card: pick a metric, get a gap-free series.
+
+use chrono::{Duration, NaiveDate};
+use serde::Serialize;
+use tauri::State;
+
+use crate::PosDb;
+use crate::pos::error::{PosError, PosResult, db_context};
+use crate::pos::timezone;
+
+const DEFAULT_WINDOW: i64 = 7;
+const DEFAULT_DAYS: i64 = 30;
+
+// ─── Types ──────────────────────────────────────────────────────────
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrendPoint {
+    pub date: String,      // YYYY-MM-DD
+    pub value: f64,
+    pub rolling_avg: f64,  // trailing `window`-day mean ending on `date`
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrendSeries {
+    pub metric: String,
+    pub window: i64,
+    pub points: Vec<TrendPoint>,
+    pub total: f64,
+    pub average: f64,
+}
+
+// ─── Metric definitions ─────────────────────────────────────────────
+
+/// Per-day aggregate for a metric as `(day DATE, value)` rows between $1 and $2 (inclusive).
+/// Timestamps are bucketed by local date (see timezone::sql_local_date).
+pub(crate) fn metric_sql(metric: &str) -> Option<String> {
+    let submitted_day = timezone::sql_local_date("submitted_time");
+    let completed_day = timezone::sql_local_date("completed_at");
+    let sql = match metric {
+        "productive_minutes" => r#"
+            SELECT date::date AS day,
+                   SUM(EXTRACT(EPOCH FROM (end_time - start_time)) / 60) FILTER (WHERE is_productive) AS value
+            FROM pos_activities
+            WHERE is_shadow = FALSE AND deleted_at IS NULL AND date::date BETWEEN $1::date AND $2::date
+            GROUP BY date"#.to_string(),
+        "total_minutes" => r#"
+            SELECT date::date AS day, SUM(EXTRACT(EPOCH FROM (end_time - start_time)) / 60) AS value
+            FROM pos_activities
+            WHERE is_shadow = FALSE AND deleted_at IS NULL AND date::date BETWEEN $1::date AND $2::date
+            GROUP BY date"#.to_string(),
+        "problems_solved" => format!(r#"
+            SELECT {submitted_day} AS day, COUNT(DISTINCT problem_id) AS value
+            FROM pos_submissions
+            WHERE verdict IN ('OK', 'Accepted') AND excluded_at IS NULL AND {submitted_day} BETWEEN $1::date AND $2::date
+            GROUP BY 1"#),
+        "submissions" => format!(r#"
+            SELECT {submitted_day} AS day, COUNT(*) AS value
+            FROM pos_submissions
+            WHERE excluded_at IS NULL AND {submitted_day} BETWEEN $1::date AND $2::date
+            GROUP BY 1"#),
+        "goals_completed" => format!(r#"
+            SELECT {completed_day} AS day, COUNT(*) AS value
+            FROM unified_goals
+            WHERE completed = TRUE AND deleted_at IS NULL AND {completed_day} BETWEEN $1::date AND $2::date
+            GROUP BY 1"#),
+        _ => return None,
+    };
+    Some(sql)
+}
+
+// ─── Commands ───────────────────────────────────────────────────────
+
+/// Daily values for `metric` over the last `days` days ending at `end_date` (default today, local),
+/// each with a trailing `window`-day rolling average (default 7, e.g. 30 for monthly cards).
+#[tauri::command]
+pub async fn get_trend_series(
+    db: State<'_, PosDb>,
+    metric: String,
+    window: Option<i64>,
+    days: Option<i64>,
+    end_date: Option<String>,
+) -> PosResult<TrendSeries> {
+    let inner = metric_sql(&metric).ok_or_else(|| PosError::InvalidInput(format!(
+        "Unknown metric '{}'. Expected productive_minutes, total_minutes, problems_solved, submissions or goals_completed",
+        metric
+    )))?;
+
+    let window = window.unwrap_or(DEFAULT_WINDOW);
+    let days = days.unwrap_or(DEFAULT_DAYS);
+    if !(1..=90).contains(&window) || !(1..=366).contains(&days) {
+        return Err(PosError::InvalidInput("window must be 1-90 and days 1-366".into()));
+    }
+
+    let end = match end_date {
+        Some(d) => NaiveDate::parse_from_str(&d, "%Y-%m-%d")
+            .map_err(|e| PosError::InvalidInput(format!("Invalid end_date: {}", e)))?,
+        None => timezone::today(),
+    };
+    let start = end - Duration::days(days - 1);
+    // Fetch window-1 extra leading days so the first points have full rolling windows
+    let fetch_start = start - Duration::days(window - 1);
+
+    let rows = sqlx::query_as::<_, (String, f64)>(&format!(
+        r#"SELECT d::date::text, COALESCE(v.value, 0)::float8
+           FROM generate_series($1::date, $2::date, INTERVAL '1 day') d
+           LEFT JOIN ({inner}) v ON v.day = d::date
+           ORDER BY d"#
+    ))
+    .bind(fetch_start.format("%Y-%m-%d").to_string())
+    .bind(end.format("%Y-%m-%d").to_string())
+    .fetch_all(&db.0)
+    .await
+    .map_err(|e| db_context("get_trend_series", e))?;
+
+    let lead = (window - 1) as usize;
+    let values: Vec<f64> = rows.iter().map(|(_, v)| *v).collect();
+    let points: Vec<TrendPoint> = rows.iter().enumerate().skip(lead).map(|(i, (date, value))| {
+        let slice = &values[i + 1 - window as usize..=i];
+        TrendPoint {
+            date: date.clone(),
+            value: *value,
+            rolling_avg: slice.iter().sum::<f64>() / window as f64,
+        }
+    }).collect();
+
+    let total: f64 = points.iter().map(|p| p.value).sum();
+    let average = if points.is_empty() { 0.0 } else { total / points.len() as f64 };
+
+    Ok(TrendSeries { metric, window, points, total, average })
+}
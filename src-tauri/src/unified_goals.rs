@@ -1,16 +1,21 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use sqlx::{Postgres, QueryBuilder};
 use tauri::State;
 
 use crate::PosDb;
+use crate::goal_dependencies::ensure_unblocked;
 use crate::pos::error::{PosError, PosResult, db_context};
+use crate::pos::idempotency::idempotent;
+use crate::pos::units::normalize_unit;
 use crate::pos::utils::gen_id;
+use crate::recurrence::Recurrence;
 
 /// Reusable explicit column list for `unified_goals` table.
 /// Kept here (next to UnifiedGoalRow) so schema changes only need one update.
 pub const UNIFIED_GOAL_COLS: &str = "id, text, description, completed, completed_at, verified, \
     date, recurring_pattern, recurring_template_id, priority, urgent, metrics, problem_id, \
-    linked_activity_ids, labels, parent_goal_id, created_at, updated_at, original_date, is_debt";
+    linked_activity_ids, labels, parent_goal_id, created_at, updated_at, original_date, is_debt, archived_at";
 
 #[derive(Debug, Serialize, Deserialize, Clone, sqlx::Type)]
 #[sqlx(type_name = "jsonb")]
@@ -22,7 +27,7 @@ pub struct UnifiedGoalMetric {
     pub unit: String,
 }
 
-#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
 #[serde(rename_all = "camelCase")]
 pub struct UnifiedGoalRow {
     pub id: String,
@@ -45,6 +50,9 @@ pub struct UnifiedGoalRow {
     pub updated_at: DateTime<Utc>,
     pub original_date: Option<String>,
     pub is_debt: bool,
+    /// Set when hidden from the active list (see goal_archive). Not selected by every query.
+    #[sqlx(default)]
+    pub archived_at: Option<DateTime<Utc>>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -89,19 +97,90 @@ pub struct GoalFilters {
     pub search: Option<String>,
     pub date_range: Option<(DateTime<Utc>, DateTime<Utc>)>, // Start, End
     pub today_local: Option<String>, // YYYY-MM-DD in local timezone (for debt marking)
+    pub priority: Option<String>,
+    pub labels: Option<Vec<String>>, // Goals carrying any of these labels
+}
+
+/// ILIKE pattern matching `search` literally anywhere in the text
+fn like_pattern(search: &str) -> String {
+    let escaped = search.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_");
+    format!("%{}%", escaped)
 }
 
+/// Validate metric units against the registry and rewrite them to canonical names.
+/// A metric whose unit matches its `stored` counterpart (same id) is left as it is, so
+/// legacy units that aren't in the registry don't block unrelated edits.
+fn normalize_metric_units(
+    metrics: Option<Vec<UnifiedGoalMetric>>,
+    stored: &[UnifiedGoalMetric],
+) -> PosResult<Option<Vec<UnifiedGoalMetric>>> {
+    metrics
+        .map(|list| {
+            list.into_iter()
+                .map(|mut m| {
+                    if !stored.iter().any(|s| s.id == m.id && s.unit == m.unit) {
+                        m.unit = normalize_unit(&m.unit)?;
+                    }
+                    Ok(m)
+                })
+                .collect::<PosResult<Vec<_>>>()
+        })
+        .transpose()
+}
+
+/// Validate a recurring pattern and store its canonical form (empty clears it)
+fn normalize_pattern(pattern: Option<String>) -> PosResult<Option<String>> {
+    pattern
+        .filter(|p| !p.trim().is_empty())
+        .map(|p| crate::recurrence::normalize(&p).map_err(PosError::InvalidInput))
+        .transpose()
+}
+
+/// A template's parsed schedule and the date interval patterns count from
+/// (its due date, else the day it was created)
+pub(crate) fn template_schedule(tmpl: &UnifiedGoalRow) -> Option<(Recurrence, chrono::NaiveDate)> {
+    let pattern = tmpl.recurring_pattern.as_deref()?;
+    let anchor = tmpl.date.as_deref()
+        .and_then(|d| chrono::NaiveDate::parse_from_str(d, "%Y-%m-%d").ok())
+        .unwrap_or_else(|| crate::pos::timezone::local_date(tmpl.created_at));
+    Some((Recurrence::from_stored(pattern), anchor))
+}
+
+/// A replayed `idempotency_key` returns the originally created goal.
+/// Emits `day-overcommitted` (see day_capacity) if the goal's day is now over capacity.
 #[tauri::command]
 pub async fn create_unified_goal(
+    app: tauri::AppHandle,
     db: State<'_, PosDb>,
     req: CreateGoalRequest,
+    idempotency_key: Option<String>,
+) -> PosResult<UnifiedGoalRow> {
+    let row = idempotent(&db.0, "create_unified_goal", idempotency_key, insert_unified_goal(&db.0, req)).await?;
+    if let Some(date) = row.date.clone() {
+        crate::day_capacity::spawn_overcommit_check(app, db.0.clone(), date);
+    }
+    Ok(row)
+}
+
+pub(crate) async fn insert_unified_goal(pool: &sqlx::PgPool, req: CreateGoalRequest) -> PosResult<UnifiedGoalRow> {
+    let mut tx = pool.begin().await.map_err(|e| db_context("TX begin", e))?;
+    let row = insert_unified_goal_tx(&mut tx, req).await?;
+    tx.commit().await.map_err(|e| db_context("TX commit", e))?;
+    crate::dashboard::mark_snapshot_stale(pool).await;
+    Ok(row)
+}
+
+/// insert_unified_goal inside the caller's transaction. The caller marks the dashboard
+/// snapshot stale after committing.
+pub(crate) async fn insert_unified_goal_tx(
+    tx: &mut sqlx::Transaction<'_, Postgres>,
+    mut req: CreateGoalRequest,
 ) -> PosResult<UnifiedGoalRow> {
-    let pool = &db.0;
     let id = gen_id();
     let now = Utc::now();
 
-    let metrics_json = req.metrics.as_ref().map(|m| sqlx::types::Json(m.clone()));
-    let labels_json = req.labels.as_ref().map(|l| sqlx::types::Json(l.clone()));
+    req.metrics = normalize_metric_units(req.metrics.take(), &[])?;
+    req.recurring_pattern = normalize_pattern(req.recurring_pattern.take())?;
 
     // DATE-ONLY LOGIC (matches activities.rs pattern):
     // Frontend sends YYYY-MM-DD string (no time component)
@@ -110,10 +189,18 @@ pub async fn create_unified_goal(
         // User provided a due date
         date_str.clone()
     } else {
-        // No due date = due today
-        now.format("%Y-%m-%d").to_string()
+        // No due date = due today (configured local zone)
+        crate::pos::timezone::today_string()
     };
 
+    // Goals added to a day after its plan was locked are flagged as unplanned
+    if crate::day_plan::is_day_locked(&mut **tx, &date).await? {
+        req.labels.get_or_insert_with(Vec::new).push(crate::day_plan::UNPLANNED_LABEL.to_string());
+    }
+
+    let metrics_json = req.metrics.as_ref().map(|m| sqlx::types::Json(m.clone()));
+    let labels_json = req.labels.as_ref().map(|l| sqlx::types::Json(l.clone()));
+
     let row = sqlx::query_as::<_, UnifiedGoalRow>(
         r#"INSERT INTO unified_goals (
             id, text, description, completed, completed_at, verified,
@@ -135,7 +222,7 @@ pub async fn create_unified_goal(
     .bind(labels_json)
     .bind(&req.parent_goal_id)
     .bind(now)
-    .fetch_one(pool)
+    .fetch_one(&mut **tx)
     .await
     .map_err(|e| db_context("create_unified_goal", e))?;
 
@@ -149,10 +236,6 @@ pub async fn get_unified_goals(
 ) -> PosResult<Vec<UnifiedGoalRow>> {
     let pool = &db.0;
 
-    // Exclude recurring templates from list view (they're internal generation blueprints)
-    // Only show: regular goals + recurring instances
-    let mut query = "SELECT id, text, description, completed, completed_at, verified, date, recurring_pattern, recurring_template_id, priority, urgent, metrics, problem_id, linked_activity_ids, labels, parent_goal_id, created_at, updated_at, original_date, is_debt FROM unified_goals WHERE 1=1 AND NOT (recurring_pattern IS NOT NULL AND recurring_template_id IS NULL)".to_string();
-
     // ─── LAZY DEBT LOGIC ───
     // Automatically move overdue goals to Debt. 
     // We do this before fetching so the UI always sees the latest state.
@@ -166,7 +249,7 @@ pub async fn get_unified_goals(
     let today_local_from_frontend = filters.as_ref().and_then(|f| f.today_local.clone());
     let today_local = today_local_from_frontend
         .clone()
-        .unwrap_or_else(|| Utc::now().format("%Y-%m-%d").to_string());
+        .unwrap_or_else(crate::pos::timezone::today_string);
 
     log::info!(
         "[UnifiedGoals] Debt marking: frontend_sent={:?}, using={}",
@@ -179,9 +262,11 @@ pub async fn get_unified_goals(
     let debt_result = sqlx::query(
         r#"UPDATE unified_goals 
            SET is_debt = TRUE,
-               original_date = date
+               original_date = date,
+               updated_at = NOW()
            WHERE completed = FALSE 
            AND is_debt = FALSE 
+           AND archived_at IS NULL
            AND date IS NOT NULL 
            AND date < $1"#
     )
@@ -213,11 +298,14 @@ pub async fn get_unified_goals(
 
     // 1. Fetch active templates (goals with recurring_pattern set, and NOT an instance themselves)
     let templates = sqlx::query_as::<_, UnifiedGoalRow>(
-        "SELECT id, text, description, completed, completed_at, verified, date, recurring_pattern, recurring_template_id, priority, urgent, metrics, problem_id, linked_activity_ids, labels, parent_goal_id, created_at, updated_at, original_date, is_debt FROM unified_goals WHERE recurring_pattern IS NOT NULL AND recurring_template_id IS NULL AND completed = FALSE"
+        "SELECT id, text, description, completed, completed_at, verified, date, recurring_pattern, recurring_template_id, priority, urgent, metrics, problem_id, linked_activity_ids, labels, parent_goal_id, created_at, updated_at, original_date, is_debt FROM unified_goals WHERE recurring_pattern IS NOT NULL AND recurring_template_id IS NULL AND completed = FALSE AND deleted_at IS NULL"
     )
     .fetch_all(pool)
     .await
     .map_err(|e| db_context("fetch recurring templates", e))?;
+    let schedules: Vec<_> = templates.iter()
+        .filter_map(|t| template_schedule(t).map(|s| (t, s)))
+        .collect();
 
     // 2. Iterate through each day in the range
     let mut curr: DateTime<Utc> = gen_start;
@@ -229,50 +317,48 @@ pub async fn get_unified_goals(
     while curr <= gen_end && days_processed < max_days {
         // Generate date string for this day
         let date_str = curr.format("%Y-%m-%d").to_string();
-        let day_name = curr.format("%a").to_string(); // Mon, Tue, Wed...
-
-        for tmpl in &templates {
-            if let Some(ref pattern) = tmpl.recurring_pattern {
-                // Check if today matches the pattern (e.g. "Mon,Wed" contains "Mon")
-                if pattern.contains(&day_name) || pattern == "Daily" {
-                    // Create instance for this date
-                    // DATE-ONLY: date = date_str (matches activities.rs pattern)
-                    let new_id = gen_id();
-                    let now = Utc::now();
-
-                    // ON CONFLICT (recurring_template_id, date): unique index enforces
-                    // idempotency at the DB level — safe against concurrent requests.
-                    let insert_result = sqlx::query(
-                        r#"INSERT INTO unified_goals (
-                            id, text, description, completed, completed_at, verified,
-                            date, recurring_pattern, recurring_template_id, priority, urgent,
-                            metrics, problem_id, linked_activity_ids, labels, parent_goal_id,
-                            created_at, updated_at, original_date, is_debt
-                        ) VALUES ($1, $2, $3, false, NULL, false, $4, NULL, $5, $6, $7, $8, $9, NULL, $10, NULL, $11, $11, NULL, false)
-                        ON CONFLICT (recurring_template_id, date) DO NOTHING"#
-                    )
-                    .bind(&new_id)
-                    .bind(&tmpl.text)
-                    .bind(&tmpl.description)
-                    .bind(&date_str)        // date (TEXT, e.g. "2026-03-03")
-                    .bind(&tmpl.id)         // recurring_template_id
-                    .bind(&tmpl.priority)
-                    .bind(tmpl.urgent)
-                    .bind(&tmpl.metrics)
-                    .bind(&tmpl.problem_id)
-                    .bind(&tmpl.labels)
-                    .bind(now)
-                    .execute(pool)
-                    .await;
-
-                    match insert_result {
-                        Ok(result) => {
-                            if result.rows_affected() > 0 {
-                                log::info!("[Unified] Generated recurring instance '{}' for {}", tmpl.text, date_str);
-                            }
-                        },
-                        Err(e) => log::error!("[Unified] Failed to generate instance: {}", e),
-                    }
+        let day = curr.date_naive();
+
+        for (tmpl, (schedule, anchor)) in &schedules {
+            // Check if this day matches the pattern (see recurrence.rs)
+            if schedule.matches(day, *anchor) {
+                // Create instance for this date
+                // DATE-ONLY: date = date_str (matches activities.rs pattern)
+                let new_id = gen_id();
+                let now = Utc::now();
+
+                // ON CONFLICT (recurring_template_id, date): unique index enforces
+                // idempotency at the DB level — safe against concurrent requests.
+                let insert_result = sqlx::query(
+                    r#"INSERT INTO unified_goals (
+                        id, text, description, completed, completed_at, verified,
+                        date, recurring_pattern, recurring_template_id, priority, urgent,
+                        metrics, problem_id, linked_activity_ids, labels, parent_goal_id,
+                        created_at, updated_at, original_date, is_debt
+                    ) VALUES ($1, $2, $3, false, NULL, false, $4, NULL, $5, $6, $7, $8, $9, NULL, $10, NULL, $11, $11, NULL, false)
+                    ON CONFLICT (recurring_template_id, date) DO NOTHING"#
+                )
+                .bind(&new_id)
+                .bind(&tmpl.text)
+                .bind(&tmpl.description)
+                .bind(&date_str)        // date (TEXT, e.g. "2026-03-03")
+                .bind(&tmpl.id)         // recurring_template_id
+                .bind(&tmpl.priority)
+                .bind(tmpl.urgent)
+                .bind(&tmpl.metrics)
+                .bind(&tmpl.problem_id)
+                .bind(&tmpl.labels)
+                .bind(now)
+                .execute(pool)
+                .await;
+
+                match insert_result {
+                    Ok(result) => {
+                        if result.rows_affected() > 0 {
+                            log::info!("[Unified] Generated recurring instance '{}' for {}", tmpl.text, date_str);
+                        }
+                    },
+                    Err(e) => log::error!("[Unified] Failed to generate instance: {}", e),
                 }
             }
         }
@@ -283,42 +369,51 @@ pub async fn get_unified_goals(
     }
     // ─────────────────────────────
 
-    if let Some(f) = &filters {
+    // Exclude recurring templates from list view (they're internal generation blueprints)
+    // Only show: regular goals + recurring instances
+    let mut query = QueryBuilder::<Postgres>::new(format!(
+        "SELECT {} FROM unified_goals WHERE archived_at IS NULL AND NOT (recurring_pattern IS NOT NULL AND recurring_template_id IS NULL)",
+        UNIFIED_GOAL_COLS
+    ));
+
+    if let Some(f) = filters {
         if let Some(completed) = f.completed {
-            query.push_str(&format!(" AND completed = {}", completed));
+            query.push(" AND completed = ").push_bind(completed);
         }
         if let Some(urgent) = f.urgent {
-            query.push_str(&format!(" AND urgent = {}", urgent));
+            query.push(" AND urgent = ").push_bind(urgent);
         }
         if let Some(is_debt) = f.is_debt {
-            query.push_str(&format!(" AND is_debt = {}", is_debt));
+            query.push(" AND is_debt = ").push_bind(is_debt);
         }
         if f.has_recurring == Some(true) {
-            query.push_str(" AND recurring_pattern IS NOT NULL");
+            query.push(" AND recurring_pattern IS NOT NULL");
         } else if f.has_recurring == Some(false) {
-            query.push_str(" AND recurring_pattern IS NULL");
+            query.push(" AND recurring_pattern IS NULL");
         }
-        if let Some(search) = &f.search {
-            if !search.is_empty() {
-                query.push_str(&format!(" AND (text ILIKE '%{}%' OR description ILIKE '%{}%')", search, search));
-            }
+        if let Some(search) = f.search.as_deref().map(str::trim).filter(|s| !s.is_empty()) {
+            let pattern = like_pattern(search);
+            query.push(" AND (text ILIKE ").push_bind(pattern.clone())
+                .push(" OR description ILIKE ").push_bind(pattern).push(")");
+        }
+        if let Some(priority) = f.priority.filter(|p| !p.is_empty()) {
+            query.push(" AND priority = ").push_bind(priority);
+        }
+        if let Some(labels) = f.labels.filter(|l| !l.is_empty()) {
+            // Any of the given labels (labels is a JSONB string array)
+            query.push(" AND labels ?| ").push_bind(labels);
         }
         if let Some((start, end)) = f.date_range {
             // DATE-ONLY COMPARISON (matches activities.rs pattern):
             // Filter by date (TEXT YYYY-MM-DD) falling in the range
-            let start_date = start.format("%Y-%m-%d").to_string();
-            let end_date = end.format("%Y-%m-%d").to_string();
-            query.push_str(&format!(
-                " AND (date >= '{}' AND date <= '{}')",
-                start_date,
-                end_date
-            ));
+            query.push(" AND date >= ").push_bind(start.format("%Y-%m-%d").to_string())
+                .push(" AND date <= ").push_bind(end.format("%Y-%m-%d").to_string());
         }
     }
 
-    query.push_str(" ORDER BY created_at DESC");
+    query.push(" ORDER BY created_at DESC");
 
-    let rows = sqlx::query_as::<_, UnifiedGoalRow>(&query)
+    let rows = query.build_query_as::<UnifiedGoalRow>()
         .fetch_all(pool)
         .await
         .map_err(|e| db_context("get_unified_goals", e))?;
@@ -326,125 +421,138 @@ pub async fn get_unified_goals(
     Ok(rows)
 }
 
+/// Patch a goal. With `expected_updated_at` (the `updatedAt` the client last saw) the write
+/// only applies if nobody changed the goal since; otherwise it fails with a Conflict
+/// carrying the current row. Completing a goal with open dependencies needs
+/// `override_dependencies`.
 #[tauri::command]
 pub async fn update_unified_goal(
     db: State<'_, PosDb>,
     id: String,
-    req: UpdateGoalRequest,
+    mut req: UpdateGoalRequest,
+    expected_updated_at: Option<DateTime<Utc>>,
+    override_dependencies: Option<bool>,
 ) -> PosResult<UnifiedGoalRow> {
     let pool = &db.0;
     let now = Utc::now();
 
+    let completing = req.completed == Some(true);
+    if completing {
+        ensure_unblocked(pool, &id, override_dependencies.unwrap_or(false)).await?;
+    }
+
+    let stored_metrics = match req.metrics {
+        Some(_) => sqlx::query_scalar::<_, sqlx::types::Json<Vec<UnifiedGoalMetric>>>(
+            "SELECT metrics FROM unified_goals WHERE id = $1 AND jsonb_typeof(metrics) = 'array'"
+        )
+        .bind(&id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| db_context("fetch stored goal metrics", e))?
+        .map(|m| m.0)
+        .unwrap_or_default(),
+        None => Vec::new(),
+    };
+    req.metrics = normalize_metric_units(req.metrics.take(), &stored_metrics)?;
+
     // Clone date for later is_debt recalculation (before req is consumed)
     let date_updated = req.date.clone();
+    // Anything that decides which milestones the goal counts toward, or by how much
+    let rollup_changed = req.metrics.is_some() || req.date.is_some() || req.labels.is_some()
+        || req.text.is_some() || req.problem_id.is_some();
+    let rollup_before = match rollup_changed {
+        true => crate::milestones::milestones_for_goal(pool, &id).await?,
+        false => Vec::new(),
+    };
 
-    let mut updates = vec!["updated_at = $1".to_string()];
-    let mut bind_idx = 2;
+    let mut query = QueryBuilder::<Postgres>::new("UPDATE unified_goals SET ");
+    let mut set = query.separated(", ");
+    set.push("updated_at = ").push_bind_unseparated(now);
 
-    if let Some(ref text) = req.text {
-        updates.push(format!("text = ${}", bind_idx));
-        bind_idx += 1;
+    if let Some(text) = req.text {
+        set.push("text = ").push_bind_unseparated(text);
     }
-    if let Some(ref description) = req.description {
-        updates.push(format!("description = ${}", bind_idx));
-        bind_idx += 1;
+    if let Some(description) = req.description {
+        set.push("description = ").push_bind_unseparated(description);
     }
     if let Some(completed) = req.completed {
-        updates.push(format!("completed = ${}", bind_idx));
-        bind_idx += 1;
+        set.push("completed = ").push_bind_unseparated(completed);
         if completed {
-            updates.push(format!("completed_at = ${}", bind_idx));
-            bind_idx += 1;
+            set.push("completed_at = ").push_bind_unseparated(now);
         }
     }
     if let Some(verified) = req.verified {
-        updates.push(format!("verified = ${}", bind_idx));
-        bind_idx += 1;
+        set.push("verified = ").push_bind_unseparated(verified);
     }
-    
+
     // DATE-ONLY LOGIC (matches activities.rs pattern):
     // Frontend sends YYYY-MM-DD string (no time component)
     // Update date field directly
-    if let Some(ref date_str) = req.date {
-        updates.push(format!("date = ${}", bind_idx));
-        bind_idx += 1;
+    if let Some(date_str) = req.date {
+        set.push("date = ").push_bind_unseparated(date_str);
     }
 
-    if let Some(ref p) = req.recurring_pattern {
-        updates.push(format!("recurring_pattern = ${}", bind_idx));
-        bind_idx += 1;
+    if let Some(p) = req.recurring_pattern {
+        // If empty string, bind NULL. Else bind the canonical pattern.
+        set.push("recurring_pattern = ").push_bind_unseparated(normalize_pattern(Some(p))?);
     }
 
-    if let Some(ref priority) = req.priority {
-        updates.push(format!("priority = ${}", bind_idx));
-        bind_idx += 1;
+    if let Some(priority) = req.priority {
+        set.push("priority = ").push_bind_unseparated(priority);
     }
     if let Some(urgent) = req.urgent {
-        updates.push(format!("urgent = ${}", bind_idx));
-        bind_idx += 1;
-    }
-    if let Some(ref metrics) = req.metrics {
-        updates.push(format!("metrics = ${}", bind_idx));
-        bind_idx += 1;
-    }
-    if let Some(ref problem_id) = req.problem_id {
-        updates.push(format!("problem_id = ${}", bind_idx));
-        bind_idx += 1;
+        set.push("urgent = ").push_bind_unseparated(urgent);
     }
-    if let Some(ref labels) = req.labels {
-        updates.push(format!("labels = ${}", bind_idx));
-        bind_idx += 1;
+    if let Some(metrics) = req.metrics {
+        set.push("metrics = ").push_bind_unseparated(sqlx::types::Json(metrics));
     }
-
-    let query_str = format!(
-        "UPDATE unified_goals SET {} WHERE id = ${} RETURNING id, text, description, completed, completed_at, verified, date, recurring_pattern, recurring_template_id, priority, urgent, metrics, problem_id, linked_activity_ids, labels, parent_goal_id, created_at, updated_at, original_date, is_debt",
-        updates.join(", "),
-        bind_idx
-    );
-
-    let mut query = sqlx::query_as::<_, UnifiedGoalRow>(&query_str).bind(now);
-
-    if let Some(text) = req.text { query = query.bind(text); }
-    if let Some(description) = req.description { query = query.bind(description); }
-    if let Some(completed) = req.completed {
-        query = query.bind(completed);
-        if completed { query = query.bind(now); }
+    if let Some(problem_id) = req.problem_id {
+        set.push("problem_id = ").push_bind_unseparated(problem_id);
     }
-    if let Some(verified) = req.verified { query = query.bind(verified); }
-    
-    // DATE-ONLY LOGIC (matches activities.rs pattern):
-    // Frontend sends YYYY-MM-DD string (no time component)
-    // Update date field directly
-    if let Some(date_str) = req.date {
-        query = query.bind(date_str);
+    if let Some(labels) = req.labels {
+        set.push("labels = ").push_bind_unseparated(sqlx::types::Json(labels));
     }
 
-    if let Some(p) = req.recurring_pattern {
-        // If empty string, bind NULL. Else bind the string.
-        if p.is_empty() {
-             query = query.bind(Option::<String>::None);
-        } else {
-             query = query.bind(Some(p));
-        }
+    query.push(" WHERE id = ").push_bind(id.clone());
+    if let Some(expected) = expected_updated_at {
+        query.push(" AND updated_at = ").push_bind(expected);
     }
-
-    if let Some(priority) = req.priority { query = query.bind(priority); }
-    if let Some(urgent) = req.urgent { query = query.bind(urgent); }
-    if let Some(metrics) = req.metrics { query = query.bind(sqlx::types::Json(metrics)); }
-    if let Some(problem_id) = req.problem_id { query = query.bind(problem_id); }
-    if let Some(labels) = req.labels { query = query.bind(sqlx::types::Json(labels)); }
-
-    query = query.bind(&id);
+    query.push(format!(" RETURNING {}", UNIFIED_GOAL_COLS));
 
     let row = query
-        .fetch_one(pool)
+        .build_query_as::<UnifiedGoalRow>()
+        .fetch_optional(pool)
         .await
         .map_err(|e| db_context("update_unified_goal", e))?;
 
+    let Some(row) = row else {
+        let current = sqlx::query_as::<_, UnifiedGoalRow>(&format!(
+            "SELECT {} FROM unified_goals WHERE id = $1", UNIFIED_GOAL_COLS
+        ))
+        .bind(&id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| db_context("fetch conflicting goal", e))?
+        .ok_or_else(|| PosError::NotFound(format!("Goal not found: {}", id)))?;
+        log::info!("[UnifiedGoals] Rejected stale update of {} (expected {:?}, current {})",
+            id, expected_updated_at, current.updated_at);
+        return Err(PosError::conflict("goal", &id, &current));
+    };
+
+    crate::dashboard::mark_snapshot_stale(pool).await;
+    if completing {
+        crate::debt_payoff::settle_catch_ups(pool, std::slice::from_ref(&id)).await;
+    }
+    if rollup_changed {
+        if let Err(e) = crate::milestones::rollup_for_goal(pool, &id, &rollup_before).await {
+            log::warn!("[UnifiedGoals] Milestone rollup after {} failed: {}", id, e);
+        }
+    }
+
     // If date was updated, recalculate is_debt status
     // This ensures goals rescheduled to future dates are no longer marked as debt
     if date_updated.is_some() {
-        let today_local = Utc::now().format("%Y-%m-%d").to_string();
+        let today_local = crate::pos::timezone::today_string();
         
         // Clear debt status if rescheduled to today or future
         // Set debt status if rescheduled to past
@@ -458,7 +566,8 @@ pub async fn update_unified_goal(
                    WHEN date < $1 AND original_date IS NULL THEN date
                    WHEN date >= $1 THEN NULL
                    ELSE original_date
-               END
+               END,
+               updated_at = NOW()
                WHERE id = $2"#
         )
         .bind(&today_local)
@@ -467,6 +576,9 @@ pub async fn update_unified_goal(
         .await
         .map_err(|e| db_context("recalculate is_debt after date update", e))?;
 
+        // Moved onto a day whose plan is already locked
+        crate::day_plan::label_if_unplanned(pool, &id).await?;
+
         // Fetch updated row to return correct state
         let updated_row = sqlx::query_as::<_, UnifiedGoalRow>(
             "SELECT id, text, description, completed, completed_at, verified, date, recurring_pattern, recurring_template_id, priority, urgent, metrics, problem_id, linked_activity_ids, labels, parent_goal_id, created_at, updated_at, original_date, is_debt FROM unified_goals WHERE id = $1"
@@ -482,22 +594,71 @@ pub async fn update_unified_goal(
     Ok(row)
 }
 
+/// Soft delete: the goal is also archived (if it wasn't already) so every archive-aware
+/// reader hides it. Restorable with restore_unified_goal until purge_deleted removes it.
 #[tauri::command]
 pub async fn delete_unified_goal(
     db: State<'_, PosDb>,
     id: String,
 ) -> PosResult<()> {
     let pool = &db.0;
+    let now = Utc::now();
 
-    sqlx::query("DELETE FROM unified_goals WHERE id = $1")
-        .bind(id)
-        .execute(pool)
-        .await
-        .map_err(|e| db_context("delete_unified_goal", e))?;
+    let deleted = sqlx::query(
+        r#"UPDATE unified_goals
+           SET deleted_at = $1, archived_at = COALESCE(archived_at, $1), updated_at = $1
+           WHERE id = $2 AND deleted_at IS NULL"#
+    )
+    .bind(now)
+    .bind(&id)
+    .execute(pool)
+    .await
+    .map_err(|e| db_context("delete_unified_goal", e))?
+    .rows_affected();
 
+    if deleted == 0 {
+        return Err(PosError::NotFound(format!("Goal not found: {}", id)));
+    }
+    crate::dashboard::mark_snapshot_stale(pool).await;
+    // The goal still follows the same milestones; the rollup now skips it as deleted
+    if let Err(e) = crate::milestones::rollup_for_goal(pool, &id, &[]).await {
+        log::warn!("[UnifiedGoals] Milestone rollup after deleting {} failed: {}", id, e);
+    }
+    log::info!("[UnifiedGoals] Deleted goal {}", id);
     Ok(())
 }
 
+/// Undo delete_unified_goal. A goal archived before it was deleted stays archived.
+#[tauri::command]
+pub async fn restore_unified_goal(
+    db: State<'_, PosDb>,
+    id: String,
+) -> PosResult<UnifiedGoalRow> {
+    let pool = &db.0;
+
+    let row = sqlx::query_as::<_, UnifiedGoalRow>(&format!(
+        r#"UPDATE unified_goals
+           SET archived_at = CASE WHEN archived_at = deleted_at THEN NULL ELSE archived_at END,
+               deleted_at = NULL,
+               updated_at = $1
+           WHERE id = $2 AND deleted_at IS NOT NULL
+           RETURNING {}"#, UNIFIED_GOAL_COLS
+    ))
+    .bind(Utc::now())
+    .bind(&id)
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| db_context("restore_unified_goal", e))?
+    .ok_or_else(|| PosError::NotFound(format!("Deleted goal not found: {}", id)))?;
+
+    crate::dashboard::mark_snapshot_stale(pool).await;
+    if let Err(e) = crate::milestones::rollup_for_goal(pool, &id, &[]).await {
+        log::warn!("[UnifiedGoals] Milestone rollup after restoring {} failed: {}", id, e);
+    }
+    log::info!("[UnifiedGoals] Restored deleted goal {}", id);
+    Ok(row)
+}
+
 // REMOVED: toggle_unified_goal_completion
 // Goals can ONLY be completed via link_activity_to_unified_goal
 // This enforces that all completed goals have verified activity proof
@@ -507,6 +668,7 @@ pub async fn link_activity_to_unified_goal(
     db: State<'_, PosDb>,
     goal_id: String,
     activity_id: String,
+    override_dependencies: Option<bool>,
 ) -> PosResult<UnifiedGoalRow> {
     let pool = &db.0;
     let now = Utc::now();
@@ -526,6 +688,9 @@ pub async fn link_activity_to_unified_goal(
         // Binary goal: Linking an activity implies "I did it"
         true
     };
+    if should_complete {
+        ensure_unblocked(pool, &goal_id, override_dependencies.unwrap_or(false)).await?;
+    }
 
     let row = sqlx::query_as::<_, UnifiedGoalRow>(
         r#"UPDATE unified_goals 
@@ -543,6 +708,10 @@ pub async fn link_activity_to_unified_goal(
     .await
     .map_err(|e| db_context("link_activity_to_unified_goal", e))?;
 
+    if should_complete {
+        crate::debt_payoff::settle_catch_ups(pool, std::slice::from_ref(&goal_id)).await;
+    }
+
     log::info!("[UnifiedGoals] Linked activity {} → goal {}", activity_id, goal_id);
 
     Ok(row)
@@ -0,0 +1,160 @@
+// Voice Memos
+// Records a short audio memo with an external recorder (arecord, falling back to ffmpeg
+// over PulseAudio/PipeWire), stores it under the app's attachments dir and files it as
+// an Inbox knowledge item. Transcribed locally with whisper.cpp when a model is configured.
+
+use std::path::{Path, PathBuf};
+
+use chrono::Utc;
+use serde::Serialize;
+use serde_json::json;
+use tauri::{AppHandle, Manager, State};
+use tauri_plugin_shell::ShellExt;
+
+use crate::{PosConfig, PosDb};
+use crate::knowledge_base::KnowledgeItemRow;
+use crate::pos::error::{PosError, PosResult, db_context};
+use crate::pos::utils::gen_id;
+
+const MAX_MEMO_SECONDS: u32 = 600;
+const VOICE_MEMO_TAG: &str = "voice-memo";
+
+// ─── Types ──────────────────────────────────────────────────────────
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VoiceMemoResult {
+    pub item: KnowledgeItemRow,
+    pub audio_path: String,
+    pub recorder: String,
+    pub transcript: Option<String>,
+}
+
+// ─── Helpers ────────────────────────────────────────────────────────
+
+fn attachments_dir(app: &AppHandle) -> PosResult<PathBuf> {
+    let dir = app.path().app_data_dir()
+        .map_err(|e| PosError::External(format!("No app data dir: {}", e)))?
+        .join("attachments")
+        .join("voice");
+    std::fs::create_dir_all(&dir)
+        .map_err(|e| PosError::External(format!("Failed to create attachments dir: {}", e)))?;
+    Ok(dir)
+}
+
+/// Record `seconds` of 16 kHz mono WAV (what whisper.cpp expects) into `dest`.
+/// Tries each recorder in turn; a recorder that isn't installed is skipped.
+async fn record(app: &AppHandle, dest: &Path, seconds: u32) -> PosResult<String> {
+    let dest_s = dest.to_string_lossy().to_string();
+    let secs = seconds.to_string();
+    let recorders: [(&str, Vec<&str>); 2] = [
+        ("arecord", vec!["-q", "-f", "S16_LE", "-r", "16000", "-c", "1", "-d", &secs, &dest_s]),
+        ("ffmpeg", vec!["-loglevel", "error", "-y", "-f", "pulse", "-i", "default",
+                        "-t", &secs, "-ar", "16000", "-ac", "1", &dest_s]),
+    ];
+
+    let mut errors = Vec::new();
+    for (bin, args) in recorders {
+        match app.shell().command(bin).args(args).output().await {
+            Ok(out) if out.status.success() && dest.exists() => return Ok(bin.to_string()),
+            Ok(out) => errors.push(format!("{}: {}", bin, String::from_utf8_lossy(&out.stderr).trim())),
+            Err(e) => errors.push(format!("{}: {}", bin, e)),
+        }
+        let _ = std::fs::remove_file(dest);
+    }
+    Err(PosError::External(format!("No audio recorder succeeded ({})", errors.join("; "))))
+}
+
+/// Best effort: None when no model is configured or whisper.cpp fails
+async fn transcribe(app: &AppHandle, config: &crate::pos::config::PosConfig, audio: &Path) -> Option<String> {
+    let model = config.whisper_model_path.as_deref()?;
+    let audio_s = audio.to_string_lossy().to_string();
+    let out = app.shell()
+        .command(&config.whisper_bin)
+        .args(["-m", model, "-f", &audio_s, "-nt", "-np"])
+        .output()
+        .await;
+
+    match out {
+        Ok(out) if out.status.success() => {
+            let text = String::from_utf8_lossy(&out.stdout)
+                .lines()
+                .map(str::trim)
+                .filter(|l| !l.is_empty())
+                .collect::<Vec<_>>()
+                .join(" ");
+            (!text.is_empty()).then_some(text)
+        }
+        Ok(out) => {
+            log::warn!("[VOICE] whisper.cpp failed: {}", String::from_utf8_lossy(&out.stderr).trim());
+            None
+        }
+        Err(e) => {
+            log::warn!("[VOICE] whisper.cpp unavailable: {}", e);
+            None
+        }
+    }
+}
+
+// ─── Commands ───────────────────────────────────────────────────────
+
+/// Record a memo of up to `max_seconds` and file it as an Inbox knowledge item that
+/// references the audio file. The recording runs for the full duration.
+#[tauri::command]
+pub async fn record_voice_memo(
+    app: AppHandle,
+    db: State<'_, PosDb>,
+    config: State<'_, PosConfig>,
+    max_seconds: u32,
+) -> PosResult<VoiceMemoResult> {
+    if max_seconds == 0 || max_seconds > MAX_MEMO_SECONDS {
+        return Err(PosError::InvalidInput(format!(
+            "max_seconds must be between 1 and {}", MAX_MEMO_SECONDS
+        )));
+    }
+
+    let id = gen_id();
+    let audio = attachments_dir(&app)?.join(format!("{}.wav", id));
+    let recorder = record(&app, &audio, max_seconds).await?;
+    let audio_path = audio.to_string_lossy().into_owned();
+    let transcript = transcribe(&app, &config.0, &audio).await;
+
+    let now = Utc::now();
+    let content = match &transcript {
+        Some(text) => format!("Voice memo ({})\n\n{}", now.format("%Y-%m-%d %H:%M"), text),
+        None => format!("Voice memo ({})\n\nfile://{}", now.format("%Y-%m-%d %H:%M"), audio_path),
+    };
+    let metadata = json!({
+        "title": "Voice memo",
+        "type": "voice_memo",
+        "audioPath": audio_path,
+        "durationSeconds": max_seconds,
+        "transcribed": transcript.is_some(),
+    });
+
+    let item = sqlx::query_as::<_, KnowledgeItemRow>(
+        r#"INSERT INTO knowledge_items (id, tags, source, content, metadata, status, created_at, updated_at)
+           VALUES ($1, $2, 'Manual', $3, $4, 'Inbox', $5, $5)
+           RETURNING id, tags, source, content, metadata, status, next_review_date,
+                     linked_note_id, linked_journal_date, created_at, updated_at"#
+    )
+    .bind(&id)
+    .bind(vec![VOICE_MEMO_TAG.to_string()])
+    .bind(&content)
+    .bind(sqlx::types::Json(metadata))
+    .bind(now)
+    .fetch_one(&db.0)
+    .await;
+
+    let item = match item {
+        Ok(item) => item,
+        Err(e) => {
+            let _ = std::fs::remove_file(&audio);
+            return Err(db_context("insert voice memo", e));
+        }
+    };
+
+    log::info!("[VOICE] Saved {}s memo {} via {} (transcribed: {})",
+        max_seconds, id, recorder, transcript.is_some());
+    Ok(VoiceMemoResult { item, audio_path, recorder, transcript })
+}
@@ -0,0 +1,341 @@
+// Weekly Plan Generator
+// Spreads milestone daily targets, open debt and due knowledge reviews across a week,
+// within a per-day minute budget. Items are ordered into my most productive hours.
+// Proposes by default; `apply = true` writes the plan as unified goals. Planned goals carry
+// a `plan:<kind>:<source id>` label, so applying again skips what already exists.
+
+use std::collections::{HashMap, HashSet};
+
+use chrono::{DateTime, Duration, NaiveDate, Timelike, Utc};
+use serde::Serialize;
+use tauri::State;
+
+use crate::PosDb;
+use crate::milestones::MilestoneRow;
+use crate::pos::error::{PosError, PosResult, db_context};
+use crate::pos::timezone;
+use crate::pos::utils::gen_id;
+
+/// Rough minute estimates per planned item
+const MILESTONE_MINUTES: i64 = 45;
+const DEBT_MINUTES: i64 = 30;
+const REVIEW_MINUTES: i64 = 15;
+/// Budget already taken by each open goal on a day
+const EXISTING_GOAL_MINUTES: i64 = 30;
+const PROFILE_DAYS: i32 = 60;
+/// Used when there's no activity history to build a profile from
+const DEFAULT_HOURS: &[u32] = &[9, 10, 11, 14, 15, 16, 17, 20, 21];
+
+// ─── Types ──────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlannedItem {
+    pub kind: String,            // milestone | debt | review
+    pub source_id: String,       // goal_periods / unified_goals / knowledge_items id
+    pub text: String,
+    pub date: String,            // YYYY-MM-DD
+    pub estimated_minutes: i64,
+    pub suggested_hour: Option<u32>,
+    /// Set once applied: the created (or rescheduled) unified goal
+    pub goal_id: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlanDay {
+    pub date: String,
+    pub capacity_minutes: i64,
+    pub existing_minutes: i64,
+    pub planned_minutes: i64,
+    pub items: Vec<PlannedItem>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WeekPlan {
+    pub week_start: String,
+    pub applied: bool,
+    pub days: Vec<PlanDay>,
+    /// Items that didn't fit anywhere in the week
+    pub unscheduled: Vec<PlannedItem>,
+    pub productive_hours: Vec<u32>,
+}
+
+#[derive(sqlx::FromRow)]
+struct DebtRow {
+    id: String,
+    text: String,
+}
+
+#[derive(sqlx::FromRow)]
+struct ReviewRow {
+    id: String,
+    content: String,
+    next_review_date: DateTime<Utc>,
+}
+
+// ─── Helpers ────────────────────────────────────────────────────────
+
+fn item(kind: &str, source_id: &str, text: String, minutes: i64) -> PlannedItem {
+    PlannedItem {
+        kind: kind.into(),
+        source_id: source_id.into(),
+        text,
+        date: String::new(),
+        estimated_minutes: minutes,
+        suggested_hour: None,
+        goal_id: None,
+    }
+}
+
+/// Place `item` on the first day at or after `from` with room left
+fn place(days: &mut [PlanDay], from: usize, mut item: PlannedItem) -> Result<(), PlannedItem> {
+    for day in days.iter_mut().skip(from) {
+        if day.existing_minutes + day.planned_minutes + item.estimated_minutes <= day.capacity_minutes {
+            day.planned_minutes += item.estimated_minutes;
+            item.date = day.date.clone();
+            day.items.push(item);
+            return Ok(());
+        }
+    }
+    Err(item)
+}
+
+/// Walk the day's items through the productive hours in order, advancing by each item's length
+fn assign_hours(day: &mut PlanDay, hours: &[u32]) {
+    let mut slot = 0usize;
+    let mut used_in_slot = 0i64;
+    for item in &mut day.items {
+        if slot >= hours.len() {
+            break;
+        }
+        item.suggested_hour = Some(hours[slot]);
+        used_in_slot += item.estimated_minutes;
+        while used_in_slot >= 60 && slot < hours.len() {
+            used_in_slot -= 60;
+            slot += 1;
+        }
+    }
+}
+
+/// Label tying a created goal back to the milestone or review it was planned from
+fn source_label(kind: &str, source_id: &str) -> String {
+    format!("plan:{}:{}", kind, source_id)
+}
+
+fn truncate(s: &str, max: usize) -> String {
+    let line = s.lines().next().unwrap_or("").trim();
+    if line.chars().count() <= max {
+        line.to_string()
+    } else {
+        format!("{}…", line.chars().take(max).collect::<String>())
+    }
+}
+
+// ─── Commands ───────────────────────────────────────────────────────
+
+/// Build (and optionally apply) a 7-day plan starting at `week_start` (YYYY-MM-DD).
+/// Milestone targets go on every day of their period; debt (oldest first) and reviews
+/// (from their due day) fill the remaining budget. Applying creates milestone/review
+/// goals and moves debt goals onto their planned day.
+#[tauri::command]
+pub async fn generate_week_plan(
+    db: State<'_, PosDb>,
+    week_start: String,
+    capacity_minutes_per_day: i64,
+    apply: Option<bool>,
+) -> PosResult<WeekPlan> {
+    let pool = &db.0;
+    let start = NaiveDate::parse_from_str(&week_start, "%Y-%m-%d")
+        .map_err(|e| PosError::InvalidInput(format!("Invalid week_start: {}", e)))?;
+    if !(15..=16 * 60).contains(&capacity_minutes_per_day) {
+        return Err(PosError::InvalidInput("capacity_minutes_per_day must be between 15 and 960".into()));
+    }
+    let end = start + Duration::days(6);
+    let (start_s, end_s) = (start.format("%Y-%m-%d").to_string(), end.format("%Y-%m-%d").to_string());
+    let (week_from, week_until) = timezone::day_start(start)
+        .zip(timezone::day_start(end + Duration::days(1)))
+        .ok_or_else(|| PosError::InvalidInput(format!("No local midnight around {}", week_start)))?;
+
+    let (milestones, debts, reviews, existing, hour_profile, already_planned) = tokio::try_join!(
+        sqlx::query_as::<_, MilestoneRow>(
+            r#"SELECT id, target_metric, target_value, daily_amount, period_type, period_start, period_end,
+                      current_value, problem_id, unit, created_at, updated_at
+               FROM goal_periods
+               WHERE period_start < $2 AND period_end >= $1
+                 AND COALESCE(current_value, 0) < target_value AND deleted_at IS NULL"#
+        ).bind(week_from).bind(week_until).fetch_all(pool),
+
+        sqlx::query_as::<_, DebtRow>(
+            r#"SELECT id, text FROM unified_goals
+               WHERE is_debt = TRUE AND completed = FALSE AND archived_at IS NULL
+               ORDER BY COALESCE(original_date, date) ASC, created_at ASC"#
+        ).fetch_all(pool),
+
+        sqlx::query_as::<_, ReviewRow>(
+            r#"SELECT id, content, next_review_date FROM knowledge_items
+               WHERE next_review_date IS NOT NULL AND next_review_date < $1
+                 AND status NOT IN ('Completed', 'Archived')
+               ORDER BY next_review_date ASC"#
+        ).bind(week_until).fetch_all(pool),
+
+        sqlx::query_as::<_, (String, i64)>(
+            r#"SELECT date, COUNT(*)::bigint FROM unified_goals
+               WHERE date BETWEEN $1 AND $2 AND completed = FALSE AND archived_at IS NULL
+               GROUP BY date"#
+        ).bind(&start_s).bind(&end_s).fetch_all(pool),
+
+        sqlx::query_as::<_, (DateTime<Utc>, f64)>(
+            r#"SELECT start_time, EXTRACT(EPOCH FROM (end_time - start_time))::float8 / 60 AS minutes
+               FROM pos_activities
+               WHERE is_productive = TRUE AND is_shadow = FALSE AND deleted_at IS NULL
+                 AND start_time >= NOW() - make_interval(days => $1)"#
+        ).bind(PROFILE_DAYS).fetch_all(pool),
+
+        sqlx::query_as::<_, (String, String)>(
+            r#"SELECT g.date, l.label
+               FROM unified_goals g, jsonb_array_elements_text(g.labels) AS l(label)
+               WHERE g.date BETWEEN $1 AND $2 AND g.deleted_at IS NULL
+                 AND jsonb_typeof(g.labels) = 'array' AND l.label LIKE 'plan:%:%'"#
+        ).bind(&start_s).bind(&end_s).fetch_all(pool),
+    ).map_err(|e| db_context("generate_week_plan", e))?;
+
+    // Productive minutes per local start hour
+    let mut hour_minutes = [0.0f64; 24];
+    for (start_time, minutes) in &hour_profile {
+        hour_minutes[timezone::local_datetime(*start_time).hour() as usize] += minutes;
+    }
+    let mut hour_profile: Vec<(u32, f64)> = (0..24u32).zip(hour_minutes).filter(|(_, m)| *m > 0.0).collect();
+    hour_profile.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+    // Most productive hours first, then chronological so a day reads top to bottom
+    let mut productive_hours: Vec<u32> = hour_profile.iter().take(DEFAULT_HOURS.len()).map(|(h, _)| *h).collect();
+    if productive_hours.is_empty() {
+        productive_hours = DEFAULT_HOURS.to_vec();
+    }
+    productive_hours.sort_unstable();
+
+    let existing: HashMap<String, i64> = existing.into_iter().collect();
+    // Goals from an earlier apply are already in `existing`; don't plan them again
+    let planned_sources: HashSet<&str> = already_planned.iter().map(|(_, l)| l.as_str()).collect();
+    let already_planned: HashSet<(String, String)> = already_planned.iter().cloned().collect();
+    let mut days: Vec<PlanDay> = (0..7).map(|i| {
+        let date = (start + Duration::days(i)).format("%Y-%m-%d").to_string();
+        PlanDay {
+            capacity_minutes: capacity_minutes_per_day,
+            existing_minutes: existing.get(&date).copied().unwrap_or(0) * EXISTING_GOAL_MINUTES,
+            planned_minutes: 0,
+            items: Vec::new(),
+            date,
+        }
+    }).collect();
+    let mut unscheduled = Vec::new();
+
+    // 1. Milestones: one item per day inside the period, sized to what's still required,
+    //    on days with room left
+    let today = timezone::today();
+    for m in &milestones {
+        let period_start = timezone::local_date(m.period_start);
+        let period_end = timezone::local_date(m.period_end);
+        let from = period_start.max(start).max(today);
+        let remaining_days = (period_end - from).num_days() + 1;
+        let remaining = (m.target_value - m.current_value).max(0);
+        let daily = if remaining_days > 0 {
+            ((remaining as f64) / remaining_days as f64).ceil() as i32
+        } else {
+            m.daily_amount
+        };
+        if daily <= 0 {
+            continue;
+        }
+        let unit = m.unit.as_deref().unwrap_or("");
+        for (i, day) in days.iter_mut().enumerate() {
+            let date = start + Duration::days(i as i64);
+            if date < period_start || date > period_end || date < today
+                || already_planned.contains(&(day.date.clone(), source_label("milestone", &m.id)))
+            {
+                continue;
+            }
+            let mut it = item("milestone", &m.id, format!("{}: {} {}", m.target_metric, daily, unit).trim_end().to_string(), MILESTONE_MINUTES);
+            if day.existing_minutes + day.planned_minutes + it.estimated_minutes > day.capacity_minutes {
+                unscheduled.push(it);
+                continue;
+            }
+            it.date = day.date.clone();
+            day.planned_minutes += it.estimated_minutes;
+            day.items.push(it);
+        }
+    }
+
+    // 2. Debt, oldest first, into the earliest day with room
+    let first_open = days.iter().position(|d| d.date >= today.format("%Y-%m-%d").to_string()).unwrap_or(days.len());
+    for d in &debts {
+        if let Err(it) = place(&mut days, first_open, item("debt", &d.id, d.text.clone(), DEBT_MINUTES)) {
+            unscheduled.push(it);
+        }
+    }
+
+    // 3. Knowledge reviews, no earlier than their due day
+    for r in reviews.iter().filter(|r| !planned_sources.contains(source_label("review", &r.id).as_str())) {
+        let due_idx = (timezone::local_date(r.next_review_date) - start).num_days().max(0) as usize;
+        let text = format!("Review: {}", truncate(&r.content, 60));
+        if let Err(it) = place(&mut days, due_idx.max(first_open), item("review", &r.id, text, REVIEW_MINUTES)) {
+            unscheduled.push(it);
+        }
+    }
+
+    for day in &mut days {
+        assign_hours(day, &productive_hours);
+    }
+
+    let applied = apply.unwrap_or(false);
+    if applied {
+        // Milestones each moved debt goal counts toward before the move
+        let mut rollup_before = Vec::new();
+        for it in days.iter().flat_map(|d| &d.items).filter(|it| it.kind == "debt") {
+            let before = crate::milestones::milestones_for_goal(pool, &it.source_id).await?;
+            rollup_before.push((it.source_id.clone(), before));
+        }
+        let mut tx = pool.begin().await.map_err(|e| db_context("begin tx", e))?;
+        let now = Utc::now();
+        for day in &mut days {
+            for it in &mut day.items {
+                let description = it.suggested_hour.map(|h| format!("Planned for ~{:02}:00", h));
+                if it.kind == "debt" {
+                    // Keep original_date so debt history stays intact
+                    sqlx::query("UPDATE unified_goals SET date = $1, is_debt = FALSE, updated_at = NOW() WHERE id = $2")
+                        .bind(&it.date).bind(&it.source_id)
+                        .execute(&mut *tx).await
+                        .map_err(|e| db_context("reschedule debt goal", e))?;
+                    crate::day_plan::label_if_unplanned(&mut *tx, &it.source_id).await?;
+                    it.goal_id = Some(it.source_id.clone());
+                    continue;
+                }
+                let id = gen_id();
+                let labels = sqlx::types::Json(vec![format!("plan:{}", it.kind), source_label(&it.kind, &it.source_id)]);
+                sqlx::query(
+                    r#"INSERT INTO unified_goals (
+                        id, text, description, completed, verified, date, priority, urgent,
+                        labels, created_at, updated_at, is_debt
+                    ) VALUES ($1, $2, $3, false, false, $4, 'medium', false, $5, $6, $6, false)"#
+                )
+                .bind(&id).bind(&it.text).bind(&description).bind(&it.date).bind(labels).bind(now)
+                .execute(&mut *tx).await
+                .map_err(|e| db_context("insert planned goal", e))?;
+                crate::day_plan::label_if_unplanned(&mut *tx, &id).await?;
+                it.goal_id = Some(id);
+            }
+        }
+        tx.commit().await.map_err(|e| db_context("commit tx", e))?;
+        crate::dashboard::mark_snapshot_stale(pool).await;
+        for (goal_id, before) in &rollup_before {
+            if let Err(e) = crate::milestones::rollup_for_goal(pool, goal_id, before).await {
+                log::warn!("[WEEK_PLAN] Milestone rollup after moving {} failed: {}", goal_id, e);
+            }
+        }
+        log::info!("[WEEK_PLAN] Applied plan for week of {}", week_start);
+    }
+
+    Ok(WeekPlan { week_start, applied, days, unscheduled, productive_hours })
+}
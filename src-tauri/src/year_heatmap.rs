@@ -0,0 +1,137 @@
+// Year Heatmap
+// A whole year of per-day values in one query, shaped for a GitHub-style contribution
+// grid: one column per week (Sunday first), one row per weekday, and a 0-4 intensity
+// level per day from the quartiles of the year's non-zero days. Metric definitions are
+// shared with the trend series.
+
+use chrono::{Datelike, NaiveDate};
+use serde::Serialize;
+use tauri::State;
+
+use crate::PosDb;
+use crate::pos::error::{PosError, PosResult, db_context};
+use crate::trends::metric_sql;
+
+const METRICS: [&str; 3] = ["productive_minutes", "problems_solved", "goals_completed"];
+
+// ─── Types ──────────────────────────────────────────────────────────
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HeatmapDay {
+    pub date: String, // YYYY-MM-DD
+    pub value: f64,
+    /// 0 = nothing, 1-4 = quartile of the year's non-zero days
+    pub level: u8,
+    /// Grid column: weeks since the Sunday on or before Jan 1
+    pub week: u32,
+    /// Grid row: 0 = Sunday
+    pub weekday: u32,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct YearHeatmap {
+    pub year: i32,
+    pub metric: String,
+    pub days: Vec<HeatmapDay>,
+    pub weeks: u32,
+    pub total: f64,
+    pub max: f64,
+    pub active_days: usize,
+    /// Upper bounds of levels 1-3 (level 4 is anything above the last)
+    pub thresholds: [f64; 3],
+}
+
+// ─── Helpers ────────────────────────────────────────────────────────
+
+/// Quartile cut points of the non-zero values
+fn thresholds(values: &[f64]) -> [f64; 3] {
+    let mut nonzero: Vec<f64> = values.iter().copied().filter(|v| *v > 0.0).collect();
+    if nonzero.is_empty() {
+        return [0.0; 3];
+    }
+    nonzero.sort_by(|a, b| a.total_cmp(b));
+    let at = |q: f64| nonzero[((nonzero.len() - 1) as f64 * q).round() as usize];
+    [at(0.25), at(0.5), at(0.75)]
+}
+
+fn level(value: f64, cuts: &[f64; 3]) -> u8 {
+    if value <= 0.0 {
+        0
+    } else {
+        1 + cuts.iter().filter(|c| value > **c).count() as u8
+    }
+}
+
+// ─── Commands ───────────────────────────────────────────────────────
+
+/// Every day of `year` for `metric` (productive_minutes, problems_solved or goals_completed)
+#[tauri::command]
+pub async fn get_year_heatmap(
+    db: State<'_, PosDb>,
+    year: i32,
+    metric: String,
+) -> PosResult<YearHeatmap> {
+    let inner = METRICS.contains(&metric.as_str())
+        .then(|| metric_sql(&metric))
+        .flatten()
+        .ok_or_else(|| PosError::InvalidInput(format!(
+            "Unknown metric '{}'. Expected {}", metric, METRICS.join(", ")
+        )))?;
+    let (first, last) = NaiveDate::from_ymd_opt(year, 1, 1)
+        .zip(NaiveDate::from_ymd_opt(year, 12, 31))
+        .ok_or_else(|| PosError::InvalidInput(format!("Invalid year {}", year)))?;
+
+    let rows = sqlx::query_as::<_, (NaiveDate, f64)>(&format!(
+        r#"SELECT d::date, COALESCE(v.value, 0)::float8
+           FROM generate_series($1::date, $2::date, INTERVAL '1 day') d
+           LEFT JOIN ({inner}) v ON v.day = d::date
+           ORDER BY d"#
+    ))
+    .bind(first.format("%Y-%m-%d").to_string())
+    .bind(last.format("%Y-%m-%d").to_string())
+    .fetch_all(&db.0)
+    .await
+    .map_err(|e| db_context("get_year_heatmap", e))?;
+
+    let values: Vec<f64> = rows.iter().map(|(_, v)| *v).collect();
+    let cuts = thresholds(&values);
+    let lead = first.weekday().num_days_from_sunday();
+    let days: Vec<HeatmapDay> = rows.iter().map(|(date, value)| {
+        let offset = date.ordinal0() + lead;
+        HeatmapDay {
+            date: date.format("%Y-%m-%d").to_string(),
+            value: *value,
+            level: level(*value, &cuts),
+            week: offset / 7,
+            weekday: offset % 7,
+        }
+    }).collect();
+
+    Ok(YearHeatmap {
+        year,
+        metric,
+        weeks: days.last().map_or(0, |d| d.week + 1),
+        total: values.iter().sum(),
+        max: values.iter().copied().fold(0.0, f64::max),
+        active_days: values.iter().filter(|v| **v > 0.0).count(),
+        thresholds: cuts,
+        days,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_levels_from_quartiles() {
+        let values = [0.0, 10.0, 20.0, 30.0, 40.0, 50.0, 0.0];
+        let cuts = thresholds(&values);
+        assert_eq!(cuts, [20.0, 30.0, 40.0]);
+        let levels: Vec<u8> = values.iter().map(|v| level(*v, &cuts)).collect();
+        assert_eq!(levels, vec![0, 1, 1, 2, 3, 4, 0]);
+        assert_eq!(thresholds(&[0.0, 0.0]), [0.0; 3]);
+    }
+}